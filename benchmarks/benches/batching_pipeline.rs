@@ -0,0 +1,44 @@
+//! Benchmark the batching pipeline (SmartBatcher + in-memory sink) and
+//! enforce its throughput floor
+//!
+//! Run with: cargo bench -p hodei-audit-benchmarks batching_pipeline
+//!
+//! The floor defaults to 100K events/sec; set
+//! `HODEI_AUDIT_MIN_EVENTS_PER_SEC` to override it.
+
+use criterion::{Criterion, Throughput, black_box};
+use tokio::runtime::Runtime;
+
+use hodei_audit_service::performance::throughput::{MIN_EVENTS_PER_SEC_ENV, min_events_per_sec};
+use hodei_audit_service::performance::{ThroughputConfig, ThroughputHarness};
+
+fn bench_batching_pipeline(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let config = ThroughputConfig::default();
+    let harness = ThroughputHarness::new(config.clone());
+
+    let mut group = c.benchmark_group("batching_pipeline");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(config.events as u64));
+    group.bench_function(format!("{}_events", config.events), |b| {
+        b.to_async(&rt)
+            .iter(|| async { black_box(harness.run().await.unwrap()) });
+    });
+    group.finish();
+
+    // Guardrail: one more timed run must sustain the floor
+    let report = rt.block_on(harness.run()).unwrap();
+    let floor = min_events_per_sec();
+    println!(
+        "batching_pipeline: {:.0} events/sec (floor {:.0}, override with {})",
+        report.events_per_sec(),
+        floor,
+        MIN_EVENTS_PER_SEC_ENV
+    );
+    if let Err(e) = report.check_floor(floor) {
+        panic!("{}", e);
+    }
+}
+
+criterion::criterion_group!(benches, bench_batching_pipeline);
+criterion::criterion_main!(benches);
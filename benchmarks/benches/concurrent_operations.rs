@@ -6,7 +6,9 @@ use criterion::{Criterion, Throughput, black_box};
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 
-use hodei_audit_service::performance::batcher::{BatcherConfig, BatchingPolicy, SmartBatcher};
+use hodei_audit_service::performance::batcher::{
+    BatcherConfig, BatchingPolicy, OverflowPolicy, SmartBatcher,
+};
 
 fn bench_concurrent_operations(c: &mut Criterion) {
     let rt = Runtime::new().unwrap();
@@ -29,6 +31,7 @@ fn bench_concurrent_operations(c: &mut Criterion) {
                         adaptive_tuning: false,
                         backpressure_controller: None,
                         enable_metrics: true,
+                        overflow_policy: OverflowPolicy::RejectNew,
                     };
                     let mut batcher = SmartBatcher::new(config);
 
@@ -7,7 +7,9 @@ use std::sync::Arc;
 use tokio::runtime::Runtime;
 
 // Import the modules to benchmark
-use hodei_audit_service::performance::batcher::{BatcherConfig, BatchingPolicy, SmartBatcher};
+use hodei_audit_service::performance::batcher::{
+    BatcherConfig, BatchingPolicy, OverflowPolicy, SmartBatcher,
+};
 
 fn bench_smart_batcher_policies(c: &mut Criterion) {
     let rt = Runtime::new().unwrap();
@@ -26,6 +28,7 @@ fn bench_smart_batcher_policies(c: &mut Criterion) {
                 adaptive_tuning: false,
                 backpressure_controller: None,
                 enable_metrics: true,
+                overflow_policy: OverflowPolicy::RejectNew,
             };
             let mut batcher = SmartBatcher::new(config);
             black_box(batcher.add_event(black_box(test_data.clone())).await);
@@ -43,6 +46,7 @@ fn bench_smart_batcher_policies(c: &mut Criterion) {
                 adaptive_tuning: false,
                 backpressure_controller: None,
                 enable_metrics: true,
+                overflow_policy: OverflowPolicy::RejectNew,
             };
             let mut batcher = SmartBatcher::new(config);
             for _ in 0..5 {
@@ -65,6 +69,7 @@ fn bench_smart_batcher_policies(c: &mut Criterion) {
                 adaptive_tuning: false,
                 backpressure_controller: None,
                 enable_metrics: true,
+                overflow_policy: OverflowPolicy::RejectNew,
             };
             let mut batcher = SmartBatcher::new(config);
             for _ in 0..5 {
@@ -90,6 +95,7 @@ fn bench_smart_batcher_policies(c: &mut Criterion) {
                 adaptive_tuning: false,
                 backpressure_controller: None,
                 enable_metrics: true,
+                overflow_policy: OverflowPolicy::RejectNew,
             };
             let mut batcher = SmartBatcher::new(config);
             for _ in 0..5 {
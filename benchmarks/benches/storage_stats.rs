@@ -0,0 +1,49 @@
+//! Benchmark storage statistics updates under contention: the atomic
+//! counters against the `RwLock<StorageStats>` they replaced
+//!
+//! Run with: cargo bench -p hodei-audit-benchmarks storage_stats
+
+use std::sync::RwLock;
+
+use criterion::{Criterion, Throughput};
+
+use hodei_audit_service::storage::{AtomicStorageStats, StorageStats, StorageTierType};
+
+const THREADS: usize = 8;
+const UPDATES: usize = 10_000;
+
+/// Run `update` `UPDATES` times on each of `THREADS` threads
+fn contend(update: impl Fn() + Sync) {
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| (0..UPDATES).for_each(|_| update()));
+        }
+    });
+}
+
+fn bench_storage_stats(c: &mut Criterion) {
+    let mut group = c.benchmark_group("storage_stats");
+    group.throughput(Throughput::Elements((THREADS * UPDATES) as u64));
+
+    group.bench_function("atomic", |b| {
+        let stats = AtomicStorageStats::new();
+        b.iter(|| contend(|| stats.record_stored(Some(StorageTierType::Hot), 1)));
+    });
+
+    // Per-event update as done before the counters were atomic
+    group.bench_function("rwlock", |b| {
+        let stats = RwLock::new(StorageStats::default());
+        b.iter(|| {
+            contend(|| {
+                let mut stats = stats.write().unwrap();
+                stats.total_events += 1;
+                stats.hot_events += 1;
+            })
+        });
+    });
+
+    group.finish();
+}
+
+criterion::criterion_group!(benches, bench_storage_stats);
+criterion::criterion_main!(benches);
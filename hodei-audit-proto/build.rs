@@ -1,6 +1,12 @@
+use std::path::PathBuf;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+
     tonic_prost_build::configure()
         .emit_rerun_if_changed(true)
+        // Descriptor set consumed by the gRPC reflection service
+        .file_descriptor_set_path(out_dir.join("hodei_audit_descriptor.bin"))
         .compile_protos(
             &[
                 "proto/common.proto",
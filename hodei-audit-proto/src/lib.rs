@@ -4,3 +4,14 @@
 //! for the Hodei Audit ecosystem, inspired by AWS CloudTrail patterns.
 
 tonic::include_proto!("hodei.audit");
+
+pub mod metadata;
+
+pub use metadata::MetadataExt;
+
+/// Encoded `FileDescriptorSet` for every Hodei Audit proto file.
+///
+/// Registered with the tonic reflection service so tools like `grpcurl`
+/// can introspect the running servers.
+pub const FILE_DESCRIPTOR_SET: &[u8] =
+    tonic::include_file_descriptor_set!("hodei_audit_descriptor");
@@ -0,0 +1,184 @@
+//! Typed access to event metadata
+//!
+//! `AuditEvent.metadata` is a `google.protobuf.Struct`, which is awkward to
+//! read and write by hand. [`MetadataExt`] converts between its values and
+//! `serde_json::Value` so callers can work with plain JSON.
+
+use crate::AuditEvent;
+use prost_types::value::Kind;
+use prost_types::{ListValue, Struct, Value};
+use serde::de::DeserializeOwned;
+
+/// Largest integer an `f64` represents exactly (2^53)
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// JSON accessors for protobuf metadata
+pub trait MetadataExt {
+    /// Value stored under `key`, converted to JSON
+    fn get_json(&self, key: &str) -> Option<serde_json::Value>;
+
+    /// Store `value` under `key`, replacing any previous value
+    fn set_json(&mut self, key: &str, value: serde_json::Value);
+
+    /// Remove `key`, returning its previous value
+    fn remove_json(&mut self, key: &str) -> Option<serde_json::Value>;
+
+    /// Value under `key` deserialized into `T`
+    ///
+    /// Returns `None` if the key is missing or does not match `T`.
+    fn get_as<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.get_json(key)
+            .and_then(|value| serde_json::from_value(value).ok())
+    }
+
+    /// String value under `key`
+    fn get_str(&self, key: &str) -> Option<String> {
+        match self.get_json(key)? {
+            serde_json::Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Boolean value under `key`
+    fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get_json(key)?.as_bool()
+    }
+
+    /// Integer value under `key`
+    fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get_json(key)?.as_i64()
+    }
+
+    /// Numeric value under `key`
+    fn get_f64(&self, key: &str) -> Option<f64> {
+        self.get_json(key)?.as_f64()
+    }
+}
+
+impl MetadataExt for Struct {
+    fn get_json(&self, key: &str) -> Option<serde_json::Value> {
+        self.fields.get(key).map(value_to_json)
+    }
+
+    fn set_json(&mut self, key: &str, value: serde_json::Value) {
+        self.fields.insert(key.to_string(), json_to_value(value));
+    }
+
+    fn remove_json(&mut self, key: &str) -> Option<serde_json::Value> {
+        self.fields.remove(key).as_ref().map(value_to_json)
+    }
+}
+
+impl MetadataExt for AuditEvent {
+    fn get_json(&self, key: &str) -> Option<serde_json::Value> {
+        self.metadata.as_ref()?.get_json(key)
+    }
+
+    fn set_json(&mut self, key: &str, value: serde_json::Value) {
+        self.metadata
+            .get_or_insert_with(Struct::default)
+            .set_json(key, value);
+    }
+
+    fn remove_json(&mut self, key: &str) -> Option<serde_json::Value> {
+        self.metadata.as_mut()?.remove_json(key)
+    }
+}
+
+/// Convert a protobuf `Value` into JSON
+///
+/// Protobuf stores every number as `f64`; integral values within the exact
+/// range are returned as JSON integers so they round-trip unchanged.
+pub fn value_to_json(value: &Value) -> serde_json::Value {
+    match &value.kind {
+        None | Some(Kind::NullValue(_)) => serde_json::Value::Null,
+        Some(Kind::BoolValue(b)) => serde_json::Value::Bool(*b),
+        Some(Kind::NumberValue(n)) => {
+            if n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER {
+                serde_json::Value::from(*n as i64)
+            } else {
+                serde_json::Number::from_f64(*n)
+                    .map(serde_json::Value::Number)
+                    .unwrap_or(serde_json::Value::Null)
+            }
+        }
+        Some(Kind::StringValue(s)) => serde_json::Value::String(s.clone()),
+        Some(Kind::ListValue(list)) => {
+            serde_json::Value::Array(list.values.iter().map(value_to_json).collect())
+        }
+        Some(Kind::StructValue(s)) => serde_json::Value::Object(
+            s.fields
+                .iter()
+                .map(|(k, v)| (k.clone(), value_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Convert JSON into a protobuf `Value`
+pub fn json_to_value(value: serde_json::Value) -> Value {
+    let kind = match value {
+        serde_json::Value::Null => Kind::NullValue(0),
+        serde_json::Value::Bool(b) => Kind::BoolValue(b),
+        serde_json::Value::Number(n) => Kind::NumberValue(n.as_f64().unwrap_or_default()),
+        serde_json::Value::String(s) => Kind::StringValue(s),
+        serde_json::Value::Array(items) => Kind::ListValue(ListValue {
+            values: items.into_iter().map(json_to_value).collect(),
+        }),
+        serde_json::Value::Object(map) => Kind::StructValue(Struct {
+            fields: map
+                .into_iter()
+                .map(|(k, v)| (k, json_to_value(v)))
+                .collect(),
+        }),
+    };
+    Value { kind: Some(kind) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_nested_json_round_trip() {
+        let mut event = AuditEvent::default();
+        let nested = json!({
+            "geo": { "country": "ES", "coords": [40.4, -3.7] },
+            "tags": ["a", "b"],
+            "attempts": 3,
+            "admin": false,
+            "note": null
+        });
+
+        event.set_json("context", nested.clone());
+        event.set_json("region", json!("eu-west-1"));
+
+        assert_eq!(event.get_json("context"), Some(nested));
+        assert_eq!(event.get_str("region").as_deref(), Some("eu-west-1"));
+
+        let context: serde_json::Map<String, serde_json::Value> = event.get_as("context").unwrap();
+        assert_eq!(context["attempts"], json!(3));
+
+        let mut metadata = event.metadata.clone().unwrap();
+        metadata.set_json("count", json!(42));
+        assert_eq!(metadata.get_i64("count"), Some(42));
+        assert_eq!(metadata.get_f64("count"), Some(42.0));
+        assert_eq!(metadata.get_bool("count"), None);
+    }
+
+    #[test]
+    fn test_missing_keys_return_none() {
+        let mut event = AuditEvent::default();
+        assert_eq!(event.get_json("missing"), None);
+        assert_eq!(event.get_str("missing"), None);
+        assert_eq!(event.remove_json("missing"), None);
+        assert!(event.metadata.is_none());
+
+        event.set_json("flag", json!(true));
+        assert_eq!(event.get_json("other"), None);
+        assert_eq!(event.get_as::<u32>("flag"), None);
+        assert_eq!(event.remove_json("flag"), Some(json!(true)));
+        assert_eq!(event.get_bool("flag"), None);
+    }
+}
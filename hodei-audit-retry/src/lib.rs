@@ -0,0 +1,312 @@
+//! Retry/backoff policy shared by the Hodei Audit SDK and service
+//!
+//! `retry` runs an async operation until it succeeds or a `RetryPolicy`
+//! gives up, sleeping an exponential, optionally jittered, backoff between
+//! attempts. The SDK, the ClickHouse client and the Vector forwarder all
+//! retry through it, so their backoff behaves the same.
+
+use std::future::Future;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::warn;
+
+/// Jitter applied to the exponential backoff
+///
+/// Jitter keeps every client from retrying at the same instant (thundering
+/// herd) when a server restarts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JitterMode {
+    /// The exact exponential delay
+    None,
+    /// A random delay in `[0, base]`
+    #[default]
+    Full,
+    /// `base / 2` plus a random delay in `[0, base / 2]`
+    Equal,
+}
+
+/// When and how long to wait before retrying
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Base delay before the first retry
+    pub initial_delay: Duration,
+    /// Upper bound of the delay between attempts
+    pub max_delay: Duration,
+    /// Growth factor of the delay
+    pub multiplier: f32,
+    /// Jitter strategy
+    pub jitter: JitterMode,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: JitterMode::Full,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Doubling delays from `initial_delay`, without cap or jitter
+    pub fn exponential(initial_delay: Duration, max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            initial_delay,
+            max_delay: Duration::MAX,
+            multiplier: 2.0,
+            jitter: JitterMode::None,
+        }
+    }
+
+    /// Cap the delay between attempts
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Randomize the delays
+    pub fn with_jitter(mut self, jitter: JitterMode) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Attempts made before giving up, the first one included
+    pub fn max_attempts(&self) -> u32 {
+        self.max_retries.saturating_add(1)
+    }
+
+    /// Delay without jitter before retry `attempt` (from 0), capped at
+    /// `max_delay`
+    pub fn base_delay(&self, attempt: u32) -> Duration {
+        let factor = (self.multiplier.max(1.0) as f64).powi(attempt.min(i32::MAX as u32) as i32);
+        let delay = self.initial_delay.as_secs_f64() * factor;
+
+        if !delay.is_finite() || delay >= self.max_delay.as_secs_f64() {
+            self.max_delay
+        } else {
+            Duration::try_from_secs_f64(delay).unwrap_or(self.max_delay)
+        }
+    }
+
+    /// Delay, jitter included, before retry `attempt` (from 0)
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let base = self.base_delay(attempt);
+
+        match self.jitter {
+            JitterMode::None => base,
+            JitterMode::Full => base.mul_f64(rand::random::<f64>()),
+            JitterMode::Equal => {
+                let half = base / 2;
+                half + half.mul_f64(rand::random::<f64>())
+            }
+        }
+    }
+
+    /// Run `operation`, retrying every failure
+    ///
+    /// The operation is attempted once plus up to `max_retries` times. If
+    /// every attempt fails, `RetryError::Exhausted` carries the last error.
+    pub async fn execute_with_retry<F, Fut, T, E>(&self, operation: F) -> Result<T, RetryError<E>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: std::fmt::Display,
+    {
+        self.execute_with_retry_if(operation, |_| true).await
+    }
+
+    /// Run `operation`, retrying only the failures `is_retryable` accepts
+    ///
+    /// Other failures are returned at once as `RetryError::NotRetryable`.
+    pub async fn execute_with_retry_if<F, Fut, T, E, P>(
+        &self,
+        mut operation: F,
+        is_retryable: P,
+    ) -> Result<T, RetryError<E>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: std::fmt::Display,
+        P: Fn(&E) -> bool,
+    {
+        let mut attempt = 0;
+
+        loop {
+            match operation().await {
+                Ok(result) => return Ok(result),
+                Err(error) if !is_retryable(&error) => {
+                    return Err(RetryError::NotRetryable {
+                        attempts: attempt + 1,
+                        source: error,
+                    });
+                }
+                Err(error) if attempt < self.max_retries => {
+                    let delay = self.delay_for_attempt(attempt);
+                    warn!(
+                        "Retry {} failed: {} (next attempt in {:?})",
+                        attempt + 1,
+                        error,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(error) => {
+                    return Err(RetryError::Exhausted {
+                        attempts: attempt + 1,
+                        source: error,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Run `operation` under `policy`, retrying every failure
+pub async fn retry<F, Fut, T, E>(policy: &RetryPolicy, operation: F) -> Result<T, RetryError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: std::fmt::Display,
+{
+    policy.execute_with_retry(operation).await
+}
+
+/// Failure of a retried operation, with the number of attempts made
+#[derive(Debug, Error)]
+pub enum RetryError<E> {
+    #[error("Retries exhausted after {attempts} attempts: {source}")]
+    Exhausted { attempts: u32, source: E },
+
+    #[error("Non-retryable failure after {attempts} attempts: {source}")]
+    NotRetryable { attempts: u32, source: E },
+}
+
+impl<E> RetryError<E> {
+    /// Attempts made, the failed one included
+    pub fn attempts(&self) -> u32 {
+        match self {
+            Self::Exhausted { attempts, .. } | Self::NotRetryable { attempts, .. } => *attempts,
+        }
+    }
+
+    /// Error of the last attempt
+    pub fn into_inner(self) -> E {
+        match self {
+            Self::Exhausted { source, .. } | Self::NotRetryable { source, .. } => source,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn fast_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy::exponential(Duration::from_millis(1), max_retries)
+    }
+
+    #[test]
+    fn test_delay_sequence() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            multiplier: 3.0,
+            jitter: JitterMode::None,
+        };
+
+        let delays: Vec<_> = (0..4).map(|a| policy.delay_for_attempt(a)).collect();
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_millis(100),
+                Duration::from_millis(300),
+                Duration::from_millis(900),
+                Duration::from_secs(1),
+            ]
+        );
+        assert_eq!(policy.delay_for_attempt(u32::MAX), Duration::from_secs(1));
+
+        let uncapped = RetryPolicy::exponential(Duration::from_millis(100), 3);
+        assert_eq!(uncapped.delay_for_attempt(5), Duration::from_millis(3200));
+        assert_eq!(uncapped.max_attempts(), 4);
+    }
+
+    #[test]
+    fn test_jitter_bounds() {
+        for jitter in [JitterMode::Full, JitterMode::Equal] {
+            let policy = RetryPolicy::default().with_jitter(jitter);
+            for attempt in 0..6 {
+                let base = policy.base_delay(attempt);
+                let floor = if jitter == JitterMode::Equal {
+                    base / 2
+                } else {
+                    Duration::ZERO
+                };
+                for _ in 0..50 {
+                    let delay = policy.delay_for_attempt(attempt);
+                    assert!(delay >= floor && delay <= base, "{:?}", delay);
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_succeeds_after_retries() {
+        let calls = AtomicU32::new(0);
+
+        let result = retry(&fast_policy(3), || {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            async move { if n < 2 { Err("transient") } else { Ok(n) } }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result, 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_max_attempts_exhausted() {
+        let calls = AtomicU32::new(0);
+
+        let error = retry(&fast_policy(2), || {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            async move { Err::<(), _>(format!("failure {}", n)) }
+        })
+        .await
+        .unwrap_err();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(matches!(error, RetryError::Exhausted { attempts: 3, .. }));
+        assert_eq!(error.into_inner(), "failure 2");
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_error_stops_at_once() {
+        let calls = AtomicU32::new(0);
+
+        let error = fast_policy(5)
+            .execute_with_retry_if(
+                || {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    async { Err::<(), _>("invalid argument") }
+                },
+                |e| *e != "invalid argument",
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(error.attempts(), 1);
+        assert!(matches!(error, RetryError::NotRetryable { .. }));
+    }
+}
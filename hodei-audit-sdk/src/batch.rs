@@ -2,18 +2,24 @@
 //!
 //! Este módulo implementa el batching inteligente con flush policies.
 
-use crate::config::AuditSdkConfig;
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, OpenCircuitPolicy};
+use crate::client::AuditTransport;
+use crate::config::{AuditSdkConfig, OverflowPolicy};
 use crate::error::AuditError;
 use crate::models::AuditEvent;
+use crate::offline::DiskBuffer;
+use std::collections::VecDeque;
+use std::future::Future;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use tokio::sync::Notify;
 use tracing::{debug, error, warn};
 
 /// Queue de eventos con capacidad limitada
 #[derive(Debug)]
 pub struct BatchQueue {
     /// Eventos en la queue
-    events: Arc<Mutex<Vec<AuditEvent>>>,
+    events: Arc<Mutex<Vec<QueuedEvent>>>,
     /// Configuración
     config: AuditSdkConfig,
     /// Estado del flush timer
@@ -24,31 +30,175 @@ pub struct BatchQueue {
     total_events: Arc<std::sync::atomic::AtomicU64>,
     /// Contador de errores
     error_count: Arc<std::sync::atomic::AtomicU64>,
+    /// Contador de eventos descartados por overflow
+    dropped_events: Arc<std::sync::atomic::AtomicU64>,
+    /// Notificación de espacio libre para `OverflowPolicy::Block`
+    space_available: Arc<Notify>,
+    /// Destino de los batches (sin sink el envío se simula)
+    sink: Option<Arc<dyn AuditTransport>>,
+    /// WAL donde se persisten los eventos antes de encolarlos
+    offline_buffer: Option<Arc<DiskBuffer>>,
+}
+
+/// Evento encolado junto a su registro en el WAL, si lo hay
+#[derive(Debug)]
+struct QueuedEvent {
+    event: AuditEvent,
+    wal_seq: Option<u64>,
+}
+
+/// Resultado de intentar encolar un evento
+enum PushOutcome {
+    /// Evento encolado (posiblemente descartando otro)
+    Accepted,
+    /// Cola llena con `OverflowPolicy::Block`; se devuelve el evento
+    Full(Box<QueuedEvent>),
+}
+
+/// Rechazar eventos que superen `max_event_bytes` una vez codificados
+pub(crate) fn check_event_size(
+    event: &AuditEvent,
+    max_event_bytes: usize,
+) -> Result<(), AuditError> {
+    let size = event.encoded_len();
+    if size > max_event_bytes {
+        warn!(
+            "Rejecting event of {} bytes (limit {})",
+            size, max_event_bytes
+        );
+        return Err(AuditError::EventTooLarge {
+            size,
+            max: max_event_bytes,
+        });
+    }
+    Ok(())
 }
 
 impl BatchQueue {
     /// Crear nueva batch queue
     pub fn new(config: AuditSdkConfig) -> Self {
         Self {
-            events: Arc::new(Mutex::new(Vec::with_capacity(config.batch_size * 2))),
+            events: Arc::new(Mutex::new(Vec::with_capacity(
+                config.batch_size.min(config.max_queue_size),
+            ))),
             config,
             last_flush: Arc::new(Mutex::new(Instant::now())),
             flush_count: Arc::new(std::sync::atomic::AtomicU64::new(0)),
             total_events: Arc::new(std::sync::atomic::AtomicU64::new(0)),
             error_count: Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            dropped_events: Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            space_available: Arc::new(Notify::new()),
+            sink: None,
+            offline_buffer: None,
         }
     }
 
+    /// Configurar el transporte al que se envían los batches
+    pub fn with_sink(mut self, sink: Arc<dyn AuditTransport>) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Persistir cada evento en el WAL antes de encolarlo
+    ///
+    /// El registro se retiene mientras el evento está en memoria y se elimina
+    /// del WAL al entregarse o descartarse por overflow; si el envío falla se
+    /// libera para el replay del `AuditClient`.
+    pub fn with_offline_buffer(mut self, buffer: Arc<DiskBuffer>) -> Self {
+        self.offline_buffer = Some(buffer);
+        self
+    }
+
     /// Añadir evento al batch (non-blocking)
+    ///
+    /// Con `OverflowPolicy::Block` y la cola llena devuelve
+    /// `AuditError::QueueFull`; usar `enqueue` para esperar espacio.
     pub fn add_event(&self, event: AuditEvent) -> Result<(), AuditError> {
+        self.check_size(&event)?;
+        let queued = self.persist(event)?;
+        match self.try_push(queued)? {
+            PushOutcome::Accepted => Ok(()),
+            PushOutcome::Full(rejected) => {
+                self.discard(&rejected);
+                Err(AuditError::QueueFull(format!(
+                    "batch queue reached max_queue_size ({})",
+                    self.config.max_queue_size
+                )))
+            }
+        }
+    }
+
+    /// Encolar evento aplicando la `OverflowPolicy` configurada
+    ///
+    /// Solo espera cuando la política es `Block` y la cola está llena.
+    pub async fn enqueue(&self, event: AuditEvent) -> Result<(), AuditError> {
+        self.check_size(&event)?;
+        let mut queued = self.persist(event)?;
+        loop {
+            // Register interest before checking so a concurrent flush is not missed
+            let notified = self.space_available.notified();
+            match self.try_push(queued)? {
+                PushOutcome::Accepted => return Ok(()),
+                PushOutcome::Full(rejected) => {
+                    queued = *rejected;
+                    notified.await;
+                }
+            }
+        }
+    }
+
+    fn check_size(&self, event: &AuditEvent) -> Result<(), AuditError> {
+        check_event_size(event, self.config.max_event_bytes)
+    }
+
+    /// Escribir el evento en el WAL, si lo hay, antes de encolarlo
+    fn persist(&self, event: AuditEvent) -> Result<QueuedEvent, AuditError> {
+        let wal_seq = match &self.offline_buffer {
+            Some(buffer) => buffer
+                .append_held(std::slice::from_ref(&event))?
+                .first()
+                .copied(),
+            None => None,
+        };
+        Ok(QueuedEvent { event, wal_seq })
+    }
+
+    /// Eliminar del WAL un evento descartado
+    fn discard(&self, queued: &QueuedEvent) {
+        if let (Some(buffer), Some(seq)) = (&self.offline_buffer, queued.wal_seq)
+            && let Err(e) = buffer.remove(&[seq])
+        {
+            warn!(
+                "Failed to remove discarded event from offline buffer: {}",
+                e
+            );
+        }
+    }
+
+    /// Intentar encolar un evento aplicando la política de overflow
+    fn try_push(&self, event: QueuedEvent) -> Result<PushOutcome, AuditError> {
         let mut events = self.events.lock().map_err(|_| {
             AuditError::ConfigurationError("Failed to acquire batch queue lock".to_string())
         })?;
 
-        // Check backpressure - if queue is full, drop oldest event
-        if events.len() >= self.config.batch_size * 2 {
-            warn!("Batch queue full, dropping oldest event");
-            events.remove(0);
+        if events.len() >= self.config.max_queue_size {
+            match self.config.overflow_policy {
+                OverflowPolicy::DropOldest => {
+                    warn!("Batch queue full, dropping oldest event");
+                    let oldest = events.remove(0);
+                    self.discard(&oldest);
+                    self.dropped_events
+                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                }
+                OverflowPolicy::DropNew => {
+                    warn!("Batch queue full, dropping new event");
+                    self.discard(&event);
+                    self.dropped_events
+                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    return Ok(PushOutcome::Accepted);
+                }
+                OverflowPolicy::Block => return Ok(PushOutcome::Full(Box::new(event))),
+            }
         }
 
         events.push(event);
@@ -59,20 +209,22 @@ impl BatchQueue {
         if events.len() >= self.config.batch_size {
             let events_to_send = events.split_off(0);
             drop(events); // Release lock before async operation
+            self.space_available.notify_waiters();
 
-            // Spawn async task to flush
-            let count = events_to_send.len();
+            // The send failure is already logged and counted by flush_batch
+            let send = self.flush_batch(events_to_send);
             tokio::spawn(async move {
-                // Simulate gRPC call
-                tokio::time::sleep(Duration::from_millis(10)).await;
-                debug!("Flushed {} events", count);
+                let _ = send.await;
             });
         }
 
-        Ok(())
+        Ok(PushOutcome::Accepted)
     }
 
     /// Flush manual del batch
+    ///
+    /// Devuelve el error del sink si el envío falla; los eventos del batch no
+    /// vuelven a la queue (con WAL quedan pendientes de replay).
     pub async fn flush(&self) -> Result<(), AuditError> {
         let events = {
             let mut events = self.events.lock().map_err(|_| {
@@ -85,18 +237,25 @@ impl BatchQueue {
 
             events.split_off(0)
         };
+        self.space_available.notify_waiters();
 
-        self.flush_batch(events).await;
-        Ok(())
+        self.flush_batch(events).await
     }
 
-    /// Flush batch de eventos
-    async fn flush_batch(&self, events: Vec<AuditEvent>) {
-        if events.is_empty() {
-            return;
-        }
-
-        let event_count = events.len();
+    /// Contabilizar un flush y preparar el envío del batch al sink
+    ///
+    /// El futuro no toma prestada la queue, así que el flush por tamaño puede
+    /// lanzarse en una tarea aparte.
+    fn flush_batch(
+        &self,
+        batch: Vec<QueuedEvent>,
+    ) -> impl Future<Output = Result<(), AuditError>> + Send + 'static {
+        let event_count = batch.len();
+        let (events, wal_seqs): (Vec<AuditEvent>, Vec<Option<u64>>) = batch
+            .into_iter()
+            .map(|queued| (queued.event, queued.wal_seq))
+            .unzip();
+        let wal_seqs: Vec<u64> = wal_seqs.into_iter().flatten().collect();
         self.flush_count
             .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
@@ -108,14 +267,34 @@ impl BatchQueue {
 
         debug!("Flushing batch of {} events", event_count);
 
-        // In a full implementation, this would send via gRPC
-        // For now, we simulate the operation
-        tokio::spawn(async move {
-            // Simulate gRPC call
-            tokio::time::sleep(Duration::from_millis(10)).await;
-
-            debug!("Successfully sent batch of {} events", event_count);
-        });
+        let sink = self.sink.clone();
+        let offline_buffer = self.offline_buffer.clone();
+        let error_count = self.error_count.clone();
+        async move {
+            let result = match &sink {
+                Some(sink) => sink.send_batch(&events).await,
+                // Sin sink configurado el envío se simula
+                None => Ok(()),
+            };
+
+            match result {
+                Ok(()) => {
+                    debug!("Successfully sent batch of {} events", event_count);
+                    if let Some(buffer) = &offline_buffer {
+                        buffer.remove(&wal_seqs)?;
+                    }
+                    Ok(())
+                }
+                Err(e) => {
+                    error_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    error!("Failed to send batch of {} events: {}", event_count, e);
+                    if let Some(buffer) = &offline_buffer {
+                        buffer.release(&wal_seqs);
+                    }
+                    Err(e)
+                }
+            }
+        }
     }
 
     /// Obtener estadísticas del batch
@@ -127,6 +306,9 @@ impl BatchQueue {
             total_events: self.total_events.load(std::sync::atomic::Ordering::Relaxed),
             flush_count: self.flush_count.load(std::sync::atomic::Ordering::Relaxed),
             error_count: self.error_count.load(std::sync::atomic::Ordering::Relaxed),
+            dropped_events: self
+                .dropped_events
+                .load(std::sync::atomic::Ordering::Relaxed),
             time_since_last_flush: {
                 let last_flush = self.last_flush.lock().unwrap();
                 last_flush.elapsed()
@@ -134,10 +316,25 @@ impl BatchQueue {
         }
     }
 
-    /// Limpiar la queue
+    /// Copia de los eventos pendientes en la queue
+    #[cfg(test)]
+    pub(crate) fn snapshot(&self) -> Vec<AuditEvent> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|queued| queued.event.clone())
+            .collect()
+    }
+
+    /// Limpiar la queue (los eventos descartados se eliminan también del WAL)
     pub fn clear(&self) {
         let mut events = self.events.lock().unwrap();
-        events.clear();
+        for queued in events.drain(..) {
+            self.discard(&queued);
+        }
+        drop(events);
+        self.space_available.notify_waiters();
 
         let mut last_flush = self.last_flush.lock().unwrap();
         *last_flush = Instant::now();
@@ -151,6 +348,7 @@ pub struct BatchStats {
     pub total_events: u64,
     pub flush_count: u64,
     pub error_count: u64,
+    pub dropped_events: u64,
     pub time_since_last_flush: Duration,
 }
 
@@ -179,12 +377,22 @@ impl FlushPolicy {
 }
 
 /// Connection pool para gRPC
+///
+/// Los envíos pasan por un circuit breaker: tras `failure_threshold` fallos
+/// consecutivos los envíos fallan rápido sin tocar la red hasta que un probe
+/// confirma que el audit service se ha recuperado.
 #[derive(Debug)]
 pub struct GrpcConnectionPool {
     clients: Arc<Mutex<Vec<tonic::transport::Channel>>>,
     max_size: usize,
     min_size: usize,
     url: String,
+    /// Circuit breaker frente al audit service
+    circuit_breaker: Arc<CircuitBreaker>,
+    /// Eventos retenidos mientras el circuito está abierto
+    pending_events: Arc<Mutex<VecDeque<AuditEvent>>>,
+    /// Contador de eventos descartados por el circuito abierto
+    dropped_events: Arc<std::sync::atomic::AtomicU64>,
 }
 
 impl GrpcConnectionPool {
@@ -204,9 +412,23 @@ impl GrpcConnectionPool {
             max_size,
             min_size,
             url,
+            circuit_breaker: Arc::new(CircuitBreaker::default()),
+            pending_events: Arc::new(Mutex::new(VecDeque::new())),
+            dropped_events: Arc::new(std::sync::atomic::AtomicU64::new(0)),
         })
     }
 
+    /// Configurar el circuit breaker del pool
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Arc::new(CircuitBreaker::new(config));
+        self
+    }
+
+    /// Obtener el circuit breaker del pool
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.circuit_breaker
+    }
+
     /// Obtener cliente del pool
     pub async fn get_client(&self) -> Result<tonic::transport::Channel, AuditError> {
         let mut clients = self.clients.lock().map_err(|_| {
@@ -218,11 +440,11 @@ impl GrpcConnectionPool {
         } else {
             // Create new connection if under max
             if clients.len() < self.max_size {
-                // In a full implementation, this would create a new gRPC channel
-                // For now, return an error to simulate connection
-                Err(AuditError::ConfigurationError(
-                    "Connection pool not fully implemented".to_string(),
-                ))
+                // The channel connects on first use, so an unreachable audit
+                // service surfaces as a send failure instead of blocking here
+                let endpoint = tonic::transport::Channel::from_shared(self.url.clone())
+                    .map_err(|e| AuditError::ConfigurationError(format!("Invalid URL: {}", e)))?;
+                Ok(endpoint.connect_lazy())
             } else {
                 Err(AuditError::ConfigurationError(
                     "Connection pool exhausted".to_string(),
@@ -238,66 +460,127 @@ impl GrpcConnectionPool {
             clients.push(channel);
         }
     }
-}
 
-/// Configuración de retry
-#[derive(Debug, Clone)]
-pub struct RetryConfig {
-    pub max_retries: u32,
-    pub initial_delay: Duration,
-    pub max_delay: Duration,
-    pub multiplier: f32,
-}
+    /// Enviar un batch de eventos a través del circuit breaker
+    ///
+    /// Con el circuito abierto no se invoca `send`: los eventos se retienen o
+    /// se descartan según `OpenCircuitPolicy` y se devuelve
+    /// `AuditError::CircuitOpen`. Los eventos retenidos se anteponen al
+    /// siguiente batch que se envíe con el circuito cerrado o en probe.
+    pub async fn send_batch<F, Fut>(
+        &self,
+        events: Vec<AuditEvent>,
+        send: F,
+    ) -> Result<(), AuditError>
+    where
+        F: FnOnce(tonic::transport::Channel, Vec<AuditEvent>) -> Fut,
+        Fut: std::future::Future<Output = Result<(), AuditError>>,
+    {
+        if !self.circuit_breaker.can_execute() {
+            self.hold_events(events);
+            return Err(AuditError::CircuitOpen(
+                "audit service unavailable, send short-circuited".to_string(),
+            ));
+        }
 
-impl Default for RetryConfig {
-    fn default() -> Self {
-        Self {
-            max_retries: 3,
-            initial_delay: Duration::from_millis(100),
-            max_delay: Duration::from_secs(10),
-            multiplier: 2.0,
+        let batch = {
+            let mut pending = self.pending_events.lock().unwrap();
+            let mut batch: Vec<AuditEvent> = pending.drain(..).collect();
+            batch.extend(events);
+            batch
+        };
+
+        let channel = match self.get_client().await {
+            Ok(channel) => channel,
+            Err(e) => {
+                self.circuit_breaker.record_failure();
+                self.hold_events(batch);
+                return Err(e);
+            }
+        };
+
+        // Keep a copy so a failed send can be retained for the next attempt
+        let retained = match self.circuit_breaker.config().open_policy {
+            OpenCircuitPolicy::Buffer { .. } => Some(batch.clone()),
+            OpenCircuitPolicy::Drop | OpenCircuitPolicy::Fail => None,
+        };
+        let count = batch.len();
+
+        match send(channel.clone(), batch).await {
+            Ok(()) => {
+                self.circuit_breaker.record_success();
+                self.return_client(channel);
+                Ok(())
+            }
+            Err(e) => {
+                self.circuit_breaker.record_failure();
+                match retained {
+                    Some(batch) => self.hold_events(batch),
+                    None => self.record_unretained(count),
+                }
+                Err(e)
+            }
         }
     }
-}
 
-impl RetryConfig {
-    /// Ejecutar con retry y exponential backoff
-    pub async fn execute_with_retry<F, T, E>(&self, mut f: F) -> Result<T, RetryError<E>>
-    where
-        F: FnMut() -> BoxFuture<'static, Result<T, E>>,
-        E: std::fmt::Display,
-    {
-        let mut delay = self.initial_delay;
-
-        for attempt in 0..self.max_retries {
-            match f().await {
-                Ok(result) => return Ok(result),
-                Err(error) if attempt < self.max_retries - 1 => {
-                    warn!("Retry {} failed: {}", attempt + 1, error);
-                    tokio::time::sleep(delay).await;
-                    delay = std::cmp::min(delay * self.multiplier as u32, self.max_delay);
+    /// Número de eventos retenidos a la espera de que el circuito se cierre
+    pub fn pending_events(&self) -> usize {
+        self.pending_events.lock().map(|p| p.len()).unwrap_or(0)
+    }
+
+    /// Número de eventos descartados por el circuito abierto
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped_events
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Contabilizar los eventos de un envío fallido que no se retienen
+    fn record_unretained(&self, count: usize) {
+        if self.circuit_breaker.config().open_policy == OpenCircuitPolicy::Drop {
+            self.dropped_events
+                .fetch_add(count as u64, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    /// Retener o descartar eventos según la política del circuit breaker
+    fn hold_events(&self, events: Vec<AuditEvent>) {
+        match self.circuit_breaker.config().open_policy {
+            // El llamador conserva los eventos
+            OpenCircuitPolicy::Fail => {}
+            OpenCircuitPolicy::Drop => {
+                self.dropped_events
+                    .fetch_add(events.len() as u64, std::sync::atomic::Ordering::Relaxed);
+            }
+            OpenCircuitPolicy::Buffer { max_events } => {
+                let mut pending = self.pending_events.lock().unwrap();
+                pending.extend(events);
+                if pending.len() > max_events {
+                    let overflow = pending.len() - max_events;
+                    pending.drain(..overflow);
+                    self.dropped_events
+                        .fetch_add(overflow as u64, std::sync::atomic::Ordering::Relaxed);
+                    warn!(
+                        "Circuit breaker buffer full, dropped {} oldest events",
+                        overflow
+                    );
                 }
-                Err(error) => return Err(RetryError::MaxRetriesExceeded(error)),
             }
         }
-
-        unreachable!()
     }
 }
 
-/// Error de retry
-#[derive(Debug, thiserror::Error)]
-pub enum RetryError<E> {
-    #[error("Max retries exceeded: {0}")]
-    MaxRetriesExceeded(E),
-}
+/// Backoff compartido con el servicio: la política de retry vive en
+/// `hodei-audit-retry` para que el SDK, el cliente de ClickHouse y el
+/// forwarder de Vector reintenten igual
+pub use hodei_audit_retry::{JitterMode, RetryError};
 
-// Helper for async function type
-type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+/// Configuración de retry
+pub type RetryConfig = hodei_audit_retry::RetryPolicy;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::circuit_breaker::CircuitState;
     use std::time::Duration;
 
     #[test]
@@ -311,6 +594,106 @@ mod tests {
         assert_eq!(stats.total_events, 0);
         assert_eq!(stats.flush_count, 0);
         assert_eq!(stats.error_count, 0);
+        assert_eq!(stats.dropped_events, 0);
+    }
+
+    fn named_event(name: &str) -> AuditEvent {
+        AuditEvent {
+            event_name: name.to_string(),
+            ..AuditEvent::default()
+        }
+    }
+
+    #[test]
+    fn test_overflow_drop_oldest() {
+        let config = AuditSdkConfig::builder()
+            .batch_size(10)
+            .max_queue_size(2)
+            .overflow_policy(OverflowPolicy::DropOldest)
+            .build()
+            .unwrap();
+        let queue = BatchQueue::new(config);
+
+        for name in ["a", "b", "c"] {
+            queue.add_event(named_event(name)).unwrap();
+        }
+
+        let names: Vec<String> = queue.snapshot().into_iter().map(|e| e.event_name).collect();
+        assert_eq!(names, vec!["b", "c"]);
+        assert_eq!(queue.get_stats().dropped_events, 1);
+    }
+
+    #[test]
+    fn test_overflow_drop_new() {
+        let config = AuditSdkConfig::builder()
+            .batch_size(10)
+            .max_queue_size(2)
+            .overflow_policy(OverflowPolicy::DropNew)
+            .build()
+            .unwrap();
+        let queue = BatchQueue::new(config);
+
+        for name in ["a", "b", "c"] {
+            queue.add_event(named_event(name)).unwrap();
+        }
+
+        let names: Vec<String> = queue.snapshot().into_iter().map(|e| e.event_name).collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert_eq!(queue.get_stats().dropped_events, 1);
+    }
+
+    #[tokio::test]
+    async fn test_overflow_block_waits_for_flush() {
+        let config = AuditSdkConfig::builder()
+            .batch_size(10)
+            .max_queue_size(1)
+            .overflow_policy(OverflowPolicy::Block)
+            .build()
+            .unwrap();
+        let queue = Arc::new(BatchQueue::new(config));
+
+        queue.enqueue(named_event("a")).await.unwrap();
+        assert!(matches!(
+            queue.add_event(named_event("b")),
+            Err(AuditError::QueueFull(_))
+        ));
+
+        let waiter = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.enqueue(named_event("b")).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        queue.flush().await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(queue.get_stats().queue_size, 1);
+        assert_eq!(queue.get_stats().dropped_events, 0);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_event_rejected_before_enqueue() {
+        let config = AuditSdkConfig::builder()
+            .batch_size(10)
+            .max_event_bytes(512)
+            .build()
+            .unwrap();
+        let queue = BatchQueue::new(config);
+
+        let result = queue.enqueue(named_event(&"x".repeat(1024))).await;
+        assert!(matches!(
+            result,
+            Err(AuditError::EventTooLarge { max: 512, .. })
+        ));
+        assert_eq!(queue.get_stats().queue_size, 0);
+        assert_eq!(queue.get_stats().total_events, 0);
+
+        queue.enqueue(named_event("small")).await.unwrap();
+        assert_eq!(queue.get_stats().queue_size, 1);
     }
 
     #[test]
@@ -352,6 +735,110 @@ mod tests {
         assert!(policy.should_flush(&small_events, &last_flush_old));
     }
 
+    fn failing_pool_config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            failure_threshold: 3,
+            success_threshold: 1,
+            timeout: Duration::from_millis(50),
+            open_policy: OpenCircuitPolicy::Buffer { max_events: 100 },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pool_fast_fails_after_consecutive_failures() {
+        let pool = GrpcConnectionPool::new("http://audit-service:50052".to_string(), 4, 0)
+            .await
+            .unwrap()
+            .with_circuit_breaker(failing_pool_config());
+        let attempts = Arc::new(std::sync::atomic::AtomicU32::new(0));
+
+        for _ in 0..3 {
+            let attempts = attempts.clone();
+            let result = pool
+                .send_batch(vec![AuditEvent::default()], |_, _| async move {
+                    attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    Err(AuditError::RequestError("unavailable".to_string()))
+                })
+                .await;
+            assert!(matches!(result, Err(AuditError::RequestError(_))));
+        }
+        assert_eq!(pool.circuit_breaker().state(), CircuitState::Open);
+
+        // Subsequent sends short-circuit without touching the network
+        let attempts_clone = attempts.clone();
+        let result = pool
+            .send_batch(vec![AuditEvent::default()], |_, _| async move {
+                attempts_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(())
+            })
+            .await;
+        assert!(matches!(result, Err(AuditError::CircuitOpen(_))));
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert_eq!(pool.pending_events(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_pool_resumes_after_successful_probe() {
+        let pool = GrpcConnectionPool::new("http://audit-service:50052".to_string(), 4, 0)
+            .await
+            .unwrap()
+            .with_circuit_breaker(failing_pool_config());
+
+        for _ in 0..3 {
+            let _ = pool
+                .send_batch(vec![AuditEvent::default()], |_, _| async {
+                    Err(AuditError::RequestError("unavailable".to_string()))
+                })
+                .await;
+        }
+        assert_eq!(pool.circuit_breaker().state(), CircuitState::Open);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        // Probe succeeds and carries the buffered events
+        let sent = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let sent_clone = sent.clone();
+        pool.send_batch(vec![AuditEvent::default()], |_, batch| async move {
+            sent_clone.store(batch.len(), std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(sent.load(std::sync::atomic::Ordering::SeqCst), 4);
+        assert_eq!(pool.circuit_breaker().state(), CircuitState::Closed);
+        assert_eq!(pool.pending_events(), 0);
+
+        pool.send_batch(vec![AuditEvent::default()], |_, _| async { Ok(()) })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pool_drop_policy_counts_dropped_events() {
+        let pool = GrpcConnectionPool::new("http://audit-service:50052".to_string(), 4, 0)
+            .await
+            .unwrap()
+            .with_circuit_breaker(CircuitBreakerConfig {
+                failure_threshold: 1,
+                open_policy: OpenCircuitPolicy::Drop,
+                ..Default::default()
+            });
+
+        let _ = pool
+            .send_batch(vec![AuditEvent::default(); 2], |_, _| async {
+                Err(AuditError::RequestError("unavailable".to_string()))
+            })
+            .await;
+        let result = pool
+            .send_batch(vec![AuditEvent::default(); 3], |_, _| async { Ok(()) })
+            .await;
+
+        assert!(matches!(result, Err(AuditError::CircuitOpen(_))));
+        assert_eq!(pool.dropped_events(), 5);
+        assert_eq!(pool.pending_events(), 0);
+    }
+
     #[test]
     fn test_retry_config() {
         let config = RetryConfig::default();
@@ -359,5 +846,115 @@ mod tests {
         assert_eq!(config.max_retries, 3);
         assert_eq!(config.initial_delay, Duration::from_millis(100));
         assert!(config.max_delay > config.initial_delay);
+        assert_eq!(config.jitter, JitterMode::Full);
+    }
+
+    #[test]
+    fn test_retry_base_delay_grows_exponentially() {
+        let config = RetryConfig {
+            max_retries: 10,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            multiplier: 2.0,
+            jitter: JitterMode::None,
+        };
+
+        assert_eq!(config.delay_for_attempt(0), Duration::from_millis(100));
+        assert_eq!(config.delay_for_attempt(1), Duration::from_millis(200));
+        assert_eq!(config.delay_for_attempt(2), Duration::from_millis(400));
+        assert_eq!(config.delay_for_attempt(3), Duration::from_millis(800));
+        // Capped at max_delay
+        assert_eq!(config.delay_for_attempt(4), Duration::from_secs(1));
+        assert_eq!(config.delay_for_attempt(60), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_retry_full_jitter_within_bounds() {
+        let config = RetryConfig {
+            jitter: JitterMode::Full,
+            ..RetryConfig::default()
+        };
+
+        for attempt in 0..6 {
+            let base = config.base_delay(attempt);
+            for _ in 0..50 {
+                assert!(config.delay_for_attempt(attempt) <= base);
+            }
+        }
+    }
+
+    #[test]
+    fn test_retry_equal_jitter_within_bounds() {
+        let config = RetryConfig {
+            jitter: JitterMode::Equal,
+            ..RetryConfig::default()
+        };
+
+        for attempt in 0..6 {
+            let base = config.base_delay(attempt);
+            for _ in 0..50 {
+                let delay = config.delay_for_attempt(attempt);
+                assert!(delay >= base / 2);
+                assert!(delay <= base);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_exhausted_carries_last_error() {
+        let config = RetryConfig {
+            max_retries: 2,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            multiplier: 2.0,
+            jitter: JitterMode::None,
+        };
+        let calls = Arc::new(std::sync::atomic::AtomicU32::new(0));
+
+        let calls_clone = calls.clone();
+        let result: Result<(), _> = config
+            .execute_with_retry(move || {
+                let n = calls_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Box::pin(async move { Err(format!("failure {}", n)) })
+            })
+            .await;
+
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+        match result {
+            Err(RetryError::Exhausted { attempts, source }) => {
+                assert_eq!(attempts, 3);
+                assert_eq!(source, "failure 2");
+            }
+            other => panic!("expected Exhausted error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_succeeds_after_failures() {
+        let config = RetryConfig {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            multiplier: 2.0,
+            jitter: JitterMode::Equal,
+        };
+        let calls = Arc::new(std::sync::atomic::AtomicU32::new(0));
+
+        let calls_clone = calls.clone();
+        let result = config
+            .execute_with_retry(move || {
+                let n = calls_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Box::pin(async move {
+                    if n < 2 {
+                        Err("transient".to_string())
+                    } else {
+                        Ok(n)
+                    }
+                })
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result, 2);
     }
 }
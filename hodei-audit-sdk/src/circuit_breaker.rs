@@ -0,0 +1,329 @@
+//! Circuit breaker ligero para el SDK
+//!
+//! Replica el `CircuitBreaker` del audit service para que el SDK deje de
+//! intentar envíos contra un servicio caído y no añada latencia al request
+//! path de la aplicación host.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Estados del circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CircuitState {
+    /// Circuito cerrado, se permiten envíos
+    #[default]
+    Closed,
+    /// Circuito abierto, los envíos fallan rápido
+    Open,
+    /// Circuito semi-abierto, probando si el servicio se recuperó
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// Verificar si se permiten envíos
+    pub fn allows_requests(&self) -> bool {
+        match self {
+            CircuitState::Closed => true,
+            CircuitState::HalfOpen => true,
+            CircuitState::Open => false,
+        }
+    }
+
+    /// Obtener descripción del estado
+    pub fn description(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "Circuit closed - requests allowed",
+            CircuitState::Open => "Circuit open - requests blocked",
+            CircuitState::HalfOpen => "Circuit half-open - testing recovery",
+        }
+    }
+}
+
+/// Qué hacer con los eventos cuando el circuito está abierto
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenCircuitPolicy {
+    /// Descartar los eventos (se contabilizan como dropped)
+    Drop,
+    /// Retener hasta `max_events` eventos y reenviarlos tras la recuperación
+    Buffer { max_events: usize },
+    /// Ni retener ni descartar: el llamador recibe el error y conserva los
+    /// eventos (por ejemplo en el WAL offline del `AuditClient`)
+    Fail,
+}
+
+impl Default for OpenCircuitPolicy {
+    fn default() -> Self {
+        Self::Buffer { max_events: 10_000 }
+    }
+}
+
+/// Configuración del circuit breaker
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// Fallos consecutivos necesarios para abrir el circuito
+    pub failure_threshold: u32,
+    /// Éxitos en half-open necesarios para cerrar el circuito
+    pub success_threshold: u32,
+    /// Tiempo en estado abierto antes de lanzar un probe
+    pub timeout: Duration,
+    /// Política para los eventos enviados con el circuito abierto
+    pub open_policy: OpenCircuitPolicy,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            success_threshold: 1,
+            timeout: Duration::from_secs(30),
+            open_policy: OpenCircuitPolicy::default(),
+        }
+    }
+}
+
+/// Métricas del circuit breaker
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerMetrics {
+    pub current_state: CircuitState,
+    pub consecutive_failures: u32,
+    pub rejected_requests: u64,
+    pub circuit_opens: u64,
+    pub circuit_closes: u64,
+}
+
+#[derive(Debug)]
+struct BreakerState {
+    state: CircuitState,
+    consecutive_failures: u32,
+    half_open_successes: u32,
+    /// Hay un envío de prueba en curso en half-open
+    probe_in_flight: bool,
+    last_state_change: Instant,
+}
+
+/// Circuit breaker
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<BreakerState>,
+    rejected_requests: AtomicU64,
+    circuit_opens: AtomicU64,
+    circuit_closes: AtomicU64,
+}
+
+impl CircuitBreaker {
+    /// Crear un nuevo circuit breaker
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(BreakerState {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                half_open_successes: 0,
+                probe_in_flight: false,
+                last_state_change: Instant::now(),
+            }),
+            rejected_requests: AtomicU64::new(0),
+            circuit_opens: AtomicU64::new(0),
+            circuit_closes: AtomicU64::new(0),
+        }
+    }
+
+    /// Obtener la configuración
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Verificar si se permite un envío
+    ///
+    /// Si el circuito lleva abierto más de `timeout` pasa a half-open y el
+    /// envío actúa como probe de recuperación. En half-open solo se admite un
+    /// probe a la vez: el resto de envíos se rechazan hasta que se registre
+    /// su resultado.
+    pub fn can_execute(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.state == CircuitState::Open
+            && inner.last_state_change.elapsed() >= self.config.timeout
+        {
+            inner.state = CircuitState::HalfOpen;
+            inner.half_open_successes = 0;
+            inner.last_state_change = Instant::now();
+            info!("Audit circuit breaker half-open - probing audit service");
+        }
+
+        let allowed = match inner.state {
+            CircuitState::Closed => true,
+            CircuitState::HalfOpen => !std::mem::replace(&mut inner.probe_in_flight, true),
+            CircuitState::Open => false,
+        };
+        if !allowed {
+            self.rejected_requests.fetch_add(1, Ordering::Relaxed);
+        }
+        allowed
+    }
+
+    /// Registrar un envío exitoso
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures = 0;
+        inner.probe_in_flight = false;
+
+        if inner.state == CircuitState::HalfOpen {
+            inner.half_open_successes += 1;
+            if inner.half_open_successes >= self.config.success_threshold {
+                inner.state = CircuitState::Closed;
+                inner.last_state_change = Instant::now();
+                self.circuit_closes.fetch_add(1, Ordering::Relaxed);
+                info!("Audit circuit breaker closed after successful probe");
+            }
+        }
+    }
+
+    /// Registrar un envío fallido
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+        inner.probe_in_flight = false;
+
+        let should_open = match inner.state {
+            CircuitState::Closed => inner.consecutive_failures >= self.config.failure_threshold,
+            // Cualquier fallo durante el probe reabre el circuito
+            CircuitState::HalfOpen => true,
+            CircuitState::Open => false,
+        };
+
+        if should_open {
+            inner.state = CircuitState::Open;
+            inner.last_state_change = Instant::now();
+            self.circuit_opens.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "Audit circuit breaker opened after {} consecutive failures",
+                inner.consecutive_failures
+            );
+        }
+    }
+
+    /// Obtener el estado actual
+    pub fn state(&self) -> CircuitState {
+        self.inner.lock().unwrap().state
+    }
+
+    /// Reset manual a estado cerrado
+    pub fn reset(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.state = CircuitState::Closed;
+        inner.consecutive_failures = 0;
+        inner.half_open_successes = 0;
+        inner.probe_in_flight = false;
+        inner.last_state_change = Instant::now();
+    }
+
+    /// Obtener métricas actuales
+    pub fn metrics(&self) -> CircuitBreakerMetrics {
+        let inner = self.inner.lock().unwrap();
+        CircuitBreakerMetrics {
+            current_state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            rejected_requests: self.rejected_requests.load(Ordering::Relaxed),
+            circuit_opens: self.circuit_opens.load(Ordering::Relaxed),
+            circuit_closes: self.circuit_closes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(CircuitBreakerConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_closed_by_default() {
+        let cb = CircuitBreaker::default();
+
+        assert!(cb.can_execute());
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_circuit_opens_on_consecutive_failures() {
+        let cb = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 3,
+            ..Default::default()
+        });
+
+        cb.record_failure();
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Closed);
+
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Open);
+        assert!(!cb.can_execute());
+        assert_eq!(cb.metrics().rejected_requests, 1);
+    }
+
+    #[test]
+    fn test_success_resets_consecutive_failures() {
+        let cb = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 3,
+            ..Default::default()
+        });
+
+        cb.record_failure();
+        cb.record_failure();
+        cb.record_success();
+        cb.record_failure();
+
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_failure_reopens() {
+        let cb = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            timeout: Duration::from_millis(0),
+            ..Default::default()
+        });
+
+        cb.record_failure();
+        assert!(cb.can_execute());
+        assert_eq!(cb.state(), CircuitState::HalfOpen);
+
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Open);
+        assert_eq!(cb.metrics().circuit_opens, 2);
+    }
+
+    #[test]
+    fn test_half_open_admits_a_single_probe() {
+        let cb = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            success_threshold: 2,
+            timeout: Duration::from_millis(0),
+            ..Default::default()
+        });
+
+        cb.record_failure();
+        assert!(cb.can_execute());
+        assert!(!cb.can_execute());
+        assert!(!cb.can_execute());
+        assert_eq!(cb.metrics().rejected_requests, 2);
+
+        // Each recorded probe lets the next one through
+        cb.record_success();
+        assert_eq!(cb.state(), CircuitState::HalfOpen);
+        assert!(cb.can_execute());
+        assert!(!cb.can_execute());
+        cb.record_success();
+        assert_eq!(cb.state(), CircuitState::Closed);
+        assert!(cb.can_execute());
+        assert!(cb.can_execute());
+    }
+}
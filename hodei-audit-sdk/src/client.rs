@@ -3,13 +3,58 @@
 //! Este módulo proporciona el `AuditClient` para logging manual de eventos
 //! que no se capturan automáticamente a través del middleware.
 
+use crate::batch::{BatchQueue, BatchStats, GrpcConnectionPool, check_event_size};
+use crate::circuit_breaker::{CircuitBreaker, OpenCircuitPolicy};
 use crate::config::AuditSdkConfig;
 use crate::error::AuditError;
 use crate::hrn::Hrn;
 use crate::models::AuditEvent;
+use crate::offline::DiskBuffer;
+use crate::types::AuditQuery;
+use async_trait::async_trait;
 use std::sync::Arc;
+use std::time::Duration;
 use tonic::transport::Channel;
 
+/// Intervalo entre consultas de `wait_for_event`
+const WAIT_FOR_EVENT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Conexiones máximas del pool hacia el audit service
+const MAX_POOL_CONNECTIONS: usize = 4;
+
+/// Transporte que entrega los eventos al audit service
+#[async_trait]
+pub trait AuditTransport: Send + Sync + std::fmt::Debug {
+    /// Enviar un batch de eventos
+    async fn send_batch(&self, events: &[AuditEvent]) -> Result<(), AuditError>;
+}
+
+/// Transporte que envía a través del pool y su circuit breaker
+#[derive(Debug)]
+struct PooledTransport {
+    transport: Arc<dyn AuditTransport>,
+    pool: Arc<GrpcConnectionPool>,
+}
+
+#[async_trait]
+impl AuditTransport for PooledTransport {
+    async fn send_batch(&self, events: &[AuditEvent]) -> Result<(), AuditError> {
+        let transport = self.transport.clone();
+        self.pool
+            .send_batch(events.to_vec(), |_channel, batch| async move {
+                transport.send_batch(&batch).await
+            })
+            .await
+    }
+}
+
+/// Servicio de consulta de eventos de auditoría
+#[async_trait]
+pub trait AuditQueryService: Send + Sync + std::fmt::Debug {
+    /// Ejecutar una consulta
+    async fn query(&self, query: &AuditQuery) -> Result<AuditQueryResult, AuditError>;
+}
+
 /// Cliente manual para logging de auditoría
 #[derive(Debug, Clone)]
 pub struct AuditClient {
@@ -17,41 +62,144 @@ pub struct AuditClient {
     config: Arc<AuditSdkConfig>,
     /// Canal gRPC (en una implementación real sería un cliente gRPC)
     _channel: Option<Channel>,
+    /// Cola de eventos para envíos fire-and-forget
+    batch_queue: Arc<BatchQueue>,
+    /// Transporte de envío a través de `pool` (sin transporte el envío se simula)
+    transport: Option<Arc<dyn AuditTransport>>,
+    /// Pool con circuit breaker por el que pasan todos los envíos
+    pool: Arc<GrpcConnectionPool>,
+    /// Servicio de consulta (sin servicio las consultas devuelven vacío)
+    query_service: Option<Arc<dyn AuditQueryService>>,
+    /// WAL para eventos no entregados mientras el servicio no está disponible
+    offline_buffer: Option<Arc<DiskBuffer>>,
+    /// Serializa envío y replay para no perder eventos del WAL
+    offline_lock: Arc<tokio::sync::Mutex<()>>,
 }
 
 impl AuditClient {
     /// Crear un nuevo cliente de auditoría
     pub async fn new(url: String) -> Result<Self, AuditError> {
         // En una implementación real, aquí crearíamos el canal gRPC
-        let config = AuditSdkConfig::default();
+        let config = AuditSdkConfig {
+            audit_service_url: url.clone(),
+            ..AuditSdkConfig::default()
+        };
         let _channel = Channel::from_shared(url)
             .map_err(|e| AuditError::ConfigurationError(format!("Invalid URL: {}", e)))? // Change this to GrpcError when tonic is available
             .connect()
             .await
             .ok(); // En la implementación actual, no conectamos realmente
 
-        Ok(Self {
-            config: Arc::new(config),
-            _channel,
-        })
+        Self::build(config, _channel).await
     }
 
     /// Crear un nuevo cliente con configuración personalizada
     pub async fn with_config(config: AuditSdkConfig) -> Result<Self, AuditError> {
+        Self::build(config, None).await
+    }
+
+    async fn build(config: AuditSdkConfig, channel: Option<Channel>) -> Result<Self, AuditError> {
+        let offline_buffer = match &config.offline_buffer {
+            Some(offline) => Some(Arc::new(DiskBuffer::open(offline)?)),
+            None => None,
+        };
+
+        // Con WAL offline los eventos de un envío fallido se persisten en
+        // disco, así que el pool no debe retener otra copia
+        let mut circuit_breaker = config.circuit_breaker.clone();
+        if offline_buffer.is_some() {
+            circuit_breaker.open_policy = OpenCircuitPolicy::Fail;
+        }
+        let pool =
+            GrpcConnectionPool::new(config.audit_service_url.clone(), MAX_POOL_CONNECTIONS, 0)
+                .await?
+                .with_circuit_breaker(circuit_breaker);
+
         Ok(Self {
+            batch_queue: Self::batch_queue(&config, None, offline_buffer.clone()),
             config: Arc::new(config),
-            _channel: None,
+            _channel: channel,
+            transport: None,
+            pool: Arc::new(pool),
+            query_service: None,
+            offline_buffer,
+            offline_lock: Arc::new(tokio::sync::Mutex::new(())),
         })
     }
 
+    /// Configurar el transporte de envío
+    ///
+    /// Los envíos de `log`, `log_batch` y los flush de `send_async` pasan por
+    /// el pool del cliente. La cola de `send_async` se recrea con el nuevo
+    /// transporte, así que debe configurarse antes de encolar eventos.
+    pub fn with_transport(mut self, transport: Arc<dyn AuditTransport>) -> Self {
+        let pooled: Arc<dyn AuditTransport> = Arc::new(PooledTransport {
+            transport,
+            pool: self.pool.clone(),
+        });
+        self.batch_queue = Self::batch_queue(
+            &self.config,
+            Some(pooled.clone()),
+            self.offline_buffer.clone(),
+        );
+        self.transport = Some(pooled);
+        self
+    }
+
+    /// Cola de `send_async`, persistida en el WAL offline si está configurado
+    fn batch_queue(
+        config: &AuditSdkConfig,
+        sink: Option<Arc<dyn AuditTransport>>,
+        offline_buffer: Option<Arc<DiskBuffer>>,
+    ) -> Arc<BatchQueue> {
+        let mut queue = BatchQueue::new(config.clone());
+        if let Some(sink) = sink {
+            queue = queue.with_sink(sink);
+        }
+        if let Some(buffer) = offline_buffer {
+            queue = queue.with_offline_buffer(buffer);
+        }
+        Arc::new(queue)
+    }
+
+    /// Configurar el servicio de consulta
+    pub fn with_query_service(mut self, query_service: Arc<dyn AuditQueryService>) -> Self {
+        self.query_service = Some(query_service);
+        self
+    }
+
     /// Log de un evento individual
     pub async fn log(&self, event: AuditEvent) -> Result<(), AuditError> {
-        // En una implementación real, aquí enviaríamos el evento vía gRPC
-        // Por ahora, solo logueamos
         tracing::debug!("Logging audit event: {}", event.event_name);
 
-        // Simular envío exitoso
-        Ok(())
+        self.deliver(vec![event]).await
+    }
+
+    /// Encolar un evento sin esperar a la red (fire-and-forget)
+    ///
+    /// El evento se añade a la `BatchQueue` y se envía al transporte en el
+    /// siguiente flush: al alcanzar `batch_size` o al llamar a `flush`. Si la
+    /// cola está llena se aplica la `OverflowPolicy` configurada: solo
+    /// `OverflowPolicy::Block` puede hacer esperar al llamador. Con buffer
+    /// offline el evento se escribe en el WAL antes de encolarse, así que
+    /// sobrevive a una caída o a un flush fallido.
+    pub async fn send_async(&self, event: AuditEvent) -> Result<(), AuditError> {
+        self.batch_queue.enqueue(event).await
+    }
+
+    /// Forzar el envío de los eventos encolados con `send_async`
+    pub async fn flush(&self) -> Result<(), AuditError> {
+        self.batch_queue.flush().await
+    }
+
+    /// Obtener estadísticas de la cola de envío
+    pub fn batch_stats(&self) -> BatchStats {
+        self.batch_queue.get_stats()
+    }
+
+    /// Circuit breaker de los envíos al audit service
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        self.pool.circuit_breaker()
     }
 
     /// Log de múltiples eventos en batch
@@ -60,24 +208,139 @@ impl AuditClient {
             return Ok(());
         }
 
-        // En una implementación real, aquí enviaríamos el batch vía gRPC
         tracing::debug!("Logging batch of {} audit events", events.len());
 
-        // Simular envío exitoso
+        self.deliver(events).await
+    }
+
+    /// Reenviar los eventos pendientes del WAL offline
+    ///
+    /// Devuelve el número de eventos reenviados. Se envían en batches de
+    /// `batch_size` y cada uno se elimina del WAL al entregarse; el primer
+    /// fallo detiene el replay. Los eventos que siguen en la cola de
+    /// `send_async` no se reenvían.
+    pub async fn replay_offline(&self) -> Result<usize, AuditError> {
+        let _guard = self.offline_lock.lock().await;
+        self.replay_offline_locked().await
+    }
+
+    /// Número de eventos pendientes en el WAL offline
+    pub fn offline_pending(&self) -> Result<usize, AuditError> {
+        match &self.offline_buffer {
+            Some(buffer) => buffer.len(),
+            None => Ok(0),
+        }
+    }
+
+    /// Registros ilegibles del WAL offline apartados al abrirlo
+    pub fn offline_corrupt_records(&self) -> u64 {
+        self.offline_buffer
+            .as_ref()
+            .map_or(0, |buffer| buffer.corrupt_records())
+    }
+
+    /// Entregar eventos vía transporte, persistiéndolos en el WAL si falla
+    ///
+    /// Un evento mayor que `max_event_bytes` rechaza la llamada entera antes
+    /// de enviar nada, igual que en `send_async`. Con el circuito abierto el
+    /// envío falla sin tocar la red.
+    async fn deliver(&self, events: Vec<AuditEvent>) -> Result<(), AuditError> {
+        for event in &events {
+            check_event_size(event, self.config.max_event_bytes)?;
+        }
+        let Some(transport) = &self.transport else {
+            // Sin transporte configurado el envío se simula
+            return Ok(());
+        };
+        let Some(buffer) = &self.offline_buffer else {
+            return transport.send_batch(&events).await;
+        };
+
+        let _guard = self.offline_lock.lock().await;
+
+        // Replay pending events first so delivery order is preserved
+        if !buffer.is_empty()? && self.replay_offline_locked().await.is_err() {
+            return buffer.append(&events).map(|_| ());
+        }
+
+        if let Err(e) = transport.send_batch(&events).await {
+            tracing::warn!(
+                "Audit service unavailable ({}), persisting {} events offline",
+                e,
+                events.len()
+            );
+            buffer.append(&events)?;
+        }
+
         Ok(())
     }
 
+    async fn replay_offline_locked(&self) -> Result<usize, AuditError> {
+        let (Some(transport), Some(buffer)) = (&self.transport, &self.offline_buffer) else {
+            return Ok(0);
+        };
+
+        let records = buffer.pending_replay()?;
+        let mut replayed = 0;
+        for chunk in records.chunks(self.config.batch_size) {
+            let events: Vec<AuditEvent> = chunk.iter().map(|r| r.event.clone()).collect();
+            let seqs: Vec<u64> = chunk.iter().map(|r| r.seq).collect();
+            transport.send_batch(&events).await?;
+            buffer.remove(&seqs)?;
+            replayed += chunk.len();
+        }
+
+        if replayed > 0 {
+            tracing::info!("Replayed {} offline audit events", replayed);
+        }
+        Ok(replayed)
+    }
+
     /// Consultar eventos de auditoría
-    pub async fn query(
+    pub async fn query(&self, query: AuditQuery) -> Result<AuditQueryResult, AuditError> {
+        match &self.query_service {
+            Some(service) => service.query(&query).await,
+            None => Ok(AuditQueryResult {
+                total: 0,
+                events: vec![],
+            }),
+        }
+    }
+
+    /// Esperar a que aparezca un evento que cumpla `predicate`
+    ///
+    /// Pensado para tests de integración: consulta periódicamente los eventos
+    /// del tenant configurado hasta encontrar uno que cumpla el predicado o
+    /// agotar `timeout`, en cuyo caso devuelve `AuditError::Timeout`.
+    pub async fn wait_for_event<P>(
         &self,
-        _query: crate::types::AuditQuery,
-    ) -> Result<AuditQueryResult, AuditError> {
-        // En una implementación real, aquí consultaríamos el servicio
-        // Por ahora, retornamos un resultado vacío
-        Ok(AuditQueryResult {
-            total: 0,
-            events: vec![],
-        })
+        predicate: P,
+        timeout: Duration,
+    ) -> Result<AuditEvent, AuditError>
+    where
+        P: Fn(&AuditEvent) -> bool,
+    {
+        let query = AuditQuery {
+            tenant_id: self.config.tenant_id.clone(),
+            ..AuditQuery::default()
+        };
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            let result = self.query(query.clone()).await?;
+            if let Some(event) = result.events.into_iter().find(|e| predicate(e)) {
+                return Ok(event);
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(AuditError::Timeout(format!(
+                    "no matching audit event after {:?}",
+                    timeout
+                )));
+            }
+            tokio::time::sleep(WAIT_FOR_EVENT_POLL_INTERVAL.min(deadline - now)).await;
+        }
     }
 
     /// Resolver metadata de un HRN
@@ -214,6 +477,374 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_send_async_returns_without_network() {
+        // Unreachable service: send_async must not wait on network I/O
+        let config = AuditSdkConfig::builder()
+            .audit_service_url("http://10.255.255.1:50052")
+            .batch_size(100)
+            .build()
+            .unwrap();
+        let client = AuditClient::with_config(config).await.unwrap();
+
+        let result = tokio::time::timeout(
+            Duration::from_millis(50),
+            client.send_async(AuditEvent::default()),
+        )
+        .await;
+
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_ok());
+        assert_eq!(client.batch_stats().queue_size, 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_async_drop_new_counts_overflow() {
+        let config = AuditSdkConfig::builder()
+            .batch_size(100)
+            .max_queue_size(2)
+            .overflow_policy(crate::config::OverflowPolicy::DropNew)
+            .build()
+            .unwrap();
+        let client = AuditClient::with_config(config).await.unwrap();
+
+        for _ in 0..3 {
+            client.send_async(AuditEvent::default()).await.unwrap();
+        }
+
+        let stats = client.batch_stats();
+        assert_eq!(stats.queue_size, 2);
+        assert_eq!(stats.total_events, 2);
+        assert_eq!(stats.dropped_events, 1);
+    }
+
+    /// Transporte de prueba que simula caídas del audit service
+    #[derive(Debug, Default)]
+    struct FlakyTransport {
+        available: std::sync::atomic::AtomicBool,
+        attempts: std::sync::atomic::AtomicUsize,
+        delivered: std::sync::Mutex<Vec<AuditEvent>>,
+        batch_sizes: std::sync::Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl AuditTransport for FlakyTransport {
+        async fn send_batch(&self, events: &[AuditEvent]) -> Result<(), AuditError> {
+            self.attempts
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if !self.available.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(AuditError::RequestError("connection refused".to_string()));
+            }
+            self.delivered.lock().unwrap().extend_from_slice(events);
+            self.batch_sizes.lock().unwrap().push(events.len());
+            Ok(())
+        }
+    }
+
+    fn offline_config(path: std::path::PathBuf) -> AuditSdkConfig {
+        AuditSdkConfig::builder()
+            .offline_buffer(path, 1024 * 1024)
+            .build()
+            .unwrap()
+    }
+
+    fn named_event(name: &str) -> AuditEvent {
+        AuditEvent {
+            event_name: name.to_string(),
+            ..AuditEvent::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_offline_buffer_survives_restart_and_replays() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal = dir.path().join("audit.wal");
+        let transport = Arc::new(FlakyTransport::default());
+
+        // Outage: events are persisted to disk instead of failing the caller
+        {
+            let client = AuditClient::with_config(offline_config(wal.clone()))
+                .await
+                .unwrap()
+                .with_transport(transport.clone());
+            client
+                .log_batch(vec![named_event("a"), named_event("b")])
+                .await
+                .unwrap();
+            client.log(named_event("c")).await.unwrap();
+            assert_eq!(client.offline_pending().unwrap(), 3);
+        }
+        assert!(std::fs::metadata(&wal).unwrap().len() > 0);
+
+        // "Restart": a new client over the same WAL still sees the events
+        let client = AuditClient::with_config(offline_config(wal.clone()))
+            .await
+            .unwrap()
+            .with_transport(transport.clone());
+        assert_eq!(client.offline_pending().unwrap(), 3);
+        assert!(client.replay_offline().await.is_err());
+        assert_eq!(client.offline_pending().unwrap(), 3);
+
+        // Connectivity returns: pending events are replayed in order
+        transport
+            .available
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        client.log(named_event("d")).await.unwrap();
+
+        let names: Vec<String> = transport
+            .delivered
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.event_name.clone())
+            .collect();
+        assert_eq!(names, vec!["a", "b", "c", "d"]);
+        assert_eq!(client.offline_pending().unwrap(), 0);
+    }
+
+    fn delivered_names(transport: &FlakyTransport) -> Vec<String> {
+        transport
+            .delivered
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.event_name.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_send_async_events_survive_a_crash_before_flush() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal = dir.path().join("audit.wal");
+        let transport = Arc::new(FlakyTransport::default());
+
+        // Queued but never flushed: the process "crashes" with them in memory
+        {
+            let client = AuditClient::with_config(offline_config(wal.clone()))
+                .await
+                .unwrap()
+                .with_transport(transport.clone());
+            client.send_async(named_event("a")).await.unwrap();
+            client.send_async(named_event("b")).await.unwrap();
+            assert_eq!(client.offline_pending().unwrap(), 2);
+        }
+
+        let client = AuditClient::with_config(offline_config(wal))
+            .await
+            .unwrap()
+            .with_transport(transport.clone());
+        transport
+            .available
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(client.replay_offline().await.unwrap(), 2);
+        assert_eq!(delivered_names(&transport), vec!["a", "b"]);
+        assert_eq!(client.offline_pending().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_flush_leaves_events_for_replay_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let transport = Arc::new(FlakyTransport::default());
+        let client = AuditClient::with_config(offline_config(dir.path().join("audit.wal")))
+            .await
+            .unwrap()
+            .with_transport(transport.clone());
+
+        client.send_async(named_event("a")).await.unwrap();
+        // Queued events are not replayed while the queue still owns them
+        assert_eq!(client.replay_offline().await.unwrap(), 0);
+        assert!(client.flush().await.is_err());
+        assert_eq!(client.offline_pending().unwrap(), 1);
+
+        transport
+            .available
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(client.replay_offline().await.unwrap(), 1);
+        assert_eq!(client.replay_offline().await.unwrap(), 0);
+        assert_eq!(delivered_names(&transport), vec!["a"]);
+        assert_eq!(client.offline_pending().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_replay_sends_batch_size_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal = dir.path().join("audit.wal");
+        DiskBuffer::open(&crate::offline::OfflineBufferConfig {
+            path: wal.clone(),
+            max_bytes: 1024 * 1024,
+        })
+        .unwrap()
+        .append(
+            &(0..5)
+                .map(|i| named_event(&format!("e{}", i)))
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        let transport = Arc::new(FlakyTransport::default());
+        transport
+            .available
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        let config = AuditSdkConfig::builder()
+            .batch_size(2)
+            .offline_buffer(wal, 1024 * 1024)
+            .build()
+            .unwrap();
+        let client = AuditClient::with_config(config)
+            .await
+            .unwrap()
+            .with_transport(transport.clone());
+
+        assert_eq!(client.replay_offline().await.unwrap(), 5);
+        assert_eq!(*transport.batch_sizes.lock().unwrap(), vec![2, 2, 1]);
+        assert_eq!(
+            delivered_names(&transport),
+            vec!["e0", "e1", "e2", "e3", "e4"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_failure_without_offline_buffer_is_reported() {
+        let client = AuditClient::with_config(AuditSdkConfig::default())
+            .await
+            .unwrap()
+            .with_transport(Arc::new(FlakyTransport::default()));
+
+        assert!(client.log(named_event("a")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_async_delivers_through_transport() {
+        let transport = Arc::new(FlakyTransport::default());
+        transport
+            .available
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        let config = AuditSdkConfig::builder().batch_size(2).build().unwrap();
+        let client = AuditClient::with_config(config)
+            .await
+            .unwrap()
+            .with_transport(transport.clone());
+
+        // Reaching batch_size flushes in the background
+        client.send_async(named_event("a")).await.unwrap();
+        client.send_async(named_event("b")).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while transport.delivered.lock().unwrap().len() < 2 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        // An explicit flush sends the partial batch
+        client.send_async(named_event("c")).await.unwrap();
+        client.flush().await.unwrap();
+
+        let names: Vec<String> = transport
+            .delivered
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.event_name.clone())
+            .collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+        assert_eq!(client.batch_stats().queue_size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_flush_is_reported() {
+        let client = AuditClient::with_config(AuditSdkConfig::default())
+            .await
+            .unwrap()
+            .with_transport(Arc::new(FlakyTransport::default()));
+
+        client.send_async(named_event("a")).await.unwrap();
+        assert!(client.flush().await.is_err());
+        assert_eq!(client.batch_stats().error_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sends_go_through_the_circuit_breaker() {
+        let transport = Arc::new(FlakyTransport::default());
+        let config = AuditSdkConfig::builder()
+            .circuit_breaker(crate::circuit_breaker::CircuitBreakerConfig {
+                failure_threshold: 2,
+                timeout: Duration::from_millis(50),
+                open_policy: OpenCircuitPolicy::Drop,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        let client = AuditClient::with_config(config)
+            .await
+            .unwrap()
+            .with_transport(transport.clone());
+
+        for _ in 0..2 {
+            assert!(matches!(
+                client.log(named_event("a")).await,
+                Err(AuditError::RequestError(_))
+            ));
+        }
+        assert_eq!(
+            client.circuit_breaker().state(),
+            crate::circuit_breaker::CircuitState::Open
+        );
+
+        // Open circuit: fail fast without touching the transport
+        assert!(matches!(
+            client.log(named_event("b")).await,
+            Err(AuditError::CircuitOpen(_))
+        ));
+        assert_eq!(
+            transport.attempts.load(std::sync::atomic::Ordering::SeqCst),
+            2
+        );
+
+        // After the timeout a single probe closes the circuit again
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        transport
+            .available
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        client.log(named_event("c")).await.unwrap();
+        assert_eq!(
+            client.circuit_breaker().state(),
+            crate::circuit_breaker::CircuitState::Closed
+        );
+        assert_eq!(transport.delivered.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_event_is_rejected_before_sending() {
+        let transport = Arc::new(FlakyTransport::default());
+        transport
+            .available
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        let client = AuditClient::with_config(
+            AuditSdkConfig::builder()
+                .max_event_bytes(512)
+                .build()
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .with_transport(transport.clone());
+
+        let oversized = named_event(&"x".repeat(1024));
+        assert!(matches!(
+            client.log(oversized.clone()).await,
+            Err(AuditError::EventTooLarge { max: 512, .. })
+        ));
+        assert!(matches!(
+            client.log_batch(vec![named_event("a"), oversized]).await,
+            Err(AuditError::EventTooLarge { max: 512, .. })
+        ));
+        assert!(transport.delivered.lock().unwrap().is_empty());
+
+        client.log(named_event("a")).await.unwrap();
+        assert_eq!(transport.delivered.lock().unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_query_events() {
         let client = AuditClient::new("http://audit-service:50052".to_string())
@@ -235,6 +866,87 @@ mod tests {
         assert_eq!(result.unwrap().total, 0);
     }
 
+    /// Query service de prueba que expone los eventos publicados
+    #[derive(Debug, Default)]
+    struct MockQueryService {
+        events: std::sync::Mutex<Vec<AuditEvent>>,
+    }
+
+    #[async_trait]
+    impl AuditQueryService for MockQueryService {
+        async fn query(&self, query: &AuditQuery) -> Result<AuditQueryResult, AuditError> {
+            let events: Vec<AuditEvent> = self
+                .events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|e| query.tenant_id.as_ref().is_none_or(|t| &e.tenant_id == t))
+                .cloned()
+                .collect();
+            Ok(AuditQueryResult {
+                total: events.len() as u64,
+                events,
+            })
+        }
+    }
+
+    async fn client_with_query_service(service: Arc<MockQueryService>) -> AuditClient {
+        let config = AuditSdkConfig::builder()
+            .tenant_id("tenant-123")
+            .build()
+            .unwrap();
+        AuditClient::with_config(config)
+            .await
+            .unwrap()
+            .with_query_service(service)
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_event_returns_matching_event() {
+        let service = Arc::new(MockQueryService::default());
+        let client = client_with_query_service(service.clone()).await;
+
+        let publisher = {
+            let service = service.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(120)).await;
+                service.events.lock().unwrap().push(AuditEvent {
+                    event_name: "pet.created".to_string(),
+                    tenant_id: "tenant-123".to_string(),
+                    ..AuditEvent::default()
+                });
+            })
+        };
+
+        let event = client
+            .wait_for_event(|e| e.event_name == "pet.created", Duration::from_secs(2))
+            .await
+            .unwrap();
+
+        assert_eq!(event.tenant_id, "tenant-123");
+        publisher.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_event_times_out() {
+        let service = Arc::new(MockQueryService::default());
+        service.events.lock().unwrap().push(AuditEvent {
+            event_name: "pet.deleted".to_string(),
+            tenant_id: "tenant-123".to_string(),
+            ..AuditEvent::default()
+        });
+        let client = client_with_query_service(service).await;
+
+        let result = client
+            .wait_for_event(
+                |e| e.event_name == "pet.created",
+                Duration::from_millis(150),
+            )
+            .await;
+
+        assert!(matches!(result, Err(AuditError::Timeout(_))));
+    }
+
     #[tokio::test]
     async fn test_resolve_hrn() {
         let client = AuditClient::new("http://audit-service:50052".to_string())
@@ -3,7 +3,11 @@
 //! Este módulo proporciona la configuración del SDK usando un builder pattern
 //! para facilitar la configuración flexible del middleware de auditoría.
 
+use crate::circuit_breaker::CircuitBreakerConfig;
+use crate::offline::OfflineBufferConfig;
+use crate::redaction::RedactionConfig;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -26,6 +30,103 @@ pub struct HrnMetadata {
     pub additional_data: Option<std::collections::HashMap<String, String>>,
 }
 
+/// Política a aplicar cuando la cola de eventos está llena
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Descartar el evento más antiguo de la cola
+    #[default]
+    DropOldest,
+    /// Descartar el evento nuevo
+    DropNew,
+    /// Esperar hasta que haya espacio en la cola
+    Block,
+}
+
+/// Configuración de sampling de requests auditadas
+///
+/// Las requests que cumplen alguna regla "always audit" se auditan siempre;
+/// el resto se audita con probabilidad `rate`.
+#[derive(Debug, Clone)]
+pub struct SamplingConfig {
+    /// Fracción de requests auditadas (0.0 - 1.0)
+    pub rate: f64,
+    /// Auditar siempre las respuestas no-2xx
+    pub always_audit_errors: bool,
+    /// Auditar siempre los métodos que modifican estado (POST/PUT/PATCH/DELETE)
+    pub always_audit_mutations: bool,
+    /// Patrones de HRN que se auditan siempre (`*` como comodín)
+    pub always_audit_hrn_patterns: Vec<String>,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            rate: 1.0,
+            always_audit_errors: true,
+            always_audit_mutations: true,
+            always_audit_hrn_patterns: Vec::new(),
+        }
+    }
+}
+
+impl SamplingConfig {
+    /// Decidir si se audita una request
+    ///
+    /// `sample` es un valor en `[0, 1)`; la request se audita por sampling
+    /// cuando `sample < rate`.
+    pub fn should_audit(&self, method: &http::Method, status: u16, hrn: &str, sample: f64) -> bool {
+        if self.always_audit_errors && !(200..300).contains(&status) {
+            return true;
+        }
+
+        if self.always_audit_mutations
+            && matches!(
+                *method,
+                http::Method::POST | http::Method::PUT | http::Method::PATCH | http::Method::DELETE
+            )
+        {
+            return true;
+        }
+
+        if self
+            .always_audit_hrn_patterns
+            .iter()
+            .any(|pattern| glob_match(pattern, hrn))
+        {
+            return true;
+        }
+
+        sample < self.rate
+    }
+}
+
+/// Match simple con `*` como comodín de cualquier secuencia
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+
+    let mut remaining = value;
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            match remaining.strip_prefix(part) {
+                Some(rest) => remaining = rest,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 {
+            return remaining.ends_with(part);
+        } else {
+            match remaining.find(part) {
+                Some(idx) => remaining = &remaining[idx + part.len()..],
+                None => return false,
+            }
+        }
+    }
+
+    true
+}
+
 /// Configuración del SDK de auditoría
 #[derive(Debug, Clone)]
 pub struct AuditSdkConfig {
@@ -43,10 +144,30 @@ pub struct AuditSdkConfig {
     pub enable_request_body: bool,
     /// Habilitar captura de response body
     pub enable_response_body: bool,
+    /// Habilitar captura de headers de la request
+    pub enable_headers: bool,
+    /// Política de redacción de datos sensibles
+    pub redaction: RedactionConfig,
+    /// Tamaño máximo de body capturado (se trunca al superarlo)
+    pub max_body_bytes: usize,
+    /// Tamaño máximo de un evento serializado; los mayores no se encolan
+    pub max_event_bytes: usize,
+    /// Content types cuyo body se captura (`type/*` admite comodín)
+    pub body_content_types: Vec<String>,
     /// Timeout para requests gRPC
     pub grpc_timeout: Duration,
     /// Número de reintentos
     pub max_retries: u32,
+    /// Capacidad máxima de la cola de eventos pendientes de envío
+    pub max_queue_size: usize,
+    /// Política cuando la cola está llena
+    pub overflow_policy: OverflowPolicy,
+    /// Circuit breaker de los envíos del `AuditClient`
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Sampling de requests auditadas por el middleware
+    pub sampling: SamplingConfig,
+    /// Buffer en disco para eventos no entregados (opcional)
+    pub offline_buffer: Option<OfflineBufferConfig>,
     /// HRN Resolver para metadata
     pub hrn_resolver: Option<Arc<dyn HrnResolver>>,
 }
@@ -61,8 +182,18 @@ impl Default for AuditSdkConfig {
             batch_timeout: Duration::from_millis(100),
             enable_request_body: true,
             enable_response_body: false,
+            enable_headers: false,
+            redaction: RedactionConfig::default(),
+            max_body_bytes: 4096,
+            max_event_bytes: 1024 * 1024,
+            body_content_types: vec!["application/json".to_string()],
             grpc_timeout: Duration::from_secs(30),
             max_retries: 3,
+            max_queue_size: 1000,
+            overflow_policy: OverflowPolicy::DropOldest,
+            circuit_breaker: CircuitBreakerConfig::default(),
+            sampling: SamplingConfig::default(),
+            offline_buffer: None,
             hrn_resolver: None,
         }
     }
@@ -83,6 +214,8 @@ impl AuditSdkConfig {
 /// Builder para AuditSdkConfig
 pub struct AuditConfigBuilder {
     config: AuditSdkConfig,
+    /// Si se ha configurado explícitamente una política de redacción
+    redaction_configured: bool,
 }
 
 impl AuditConfigBuilder {
@@ -90,6 +223,7 @@ impl AuditConfigBuilder {
     pub fn new() -> Self {
         Self {
             config: AuditSdkConfig::default(),
+            redaction_configured: false,
         }
     }
 
@@ -135,6 +269,37 @@ impl AuditConfigBuilder {
         self
     }
 
+    /// Habilitar la captura de headers de la request
+    pub fn enable_headers(mut self, enable: bool) -> Self {
+        self.config.enable_headers = enable;
+        self
+    }
+
+    /// Configurar la política de redacción
+    pub fn redaction(mut self, redaction: RedactionConfig) -> Self {
+        self.config.redaction = redaction;
+        self.redaction_configured = true;
+        self
+    }
+
+    /// Configurar el tamaño máximo de body capturado
+    pub fn max_body_bytes(mut self, max_bytes: usize) -> Self {
+        self.config.max_body_bytes = max_bytes;
+        self
+    }
+
+    /// Configurar el tamaño máximo de un evento serializado
+    pub fn max_event_bytes(mut self, max_bytes: usize) -> Self {
+        self.config.max_event_bytes = max_bytes;
+        self
+    }
+
+    /// Configurar los content types cuyo body se captura
+    pub fn body_content_types(mut self, content_types: &[&str]) -> Self {
+        self.config.body_content_types = content_types.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
     /// Configurar el timeout gRPC
     pub fn grpc_timeout(mut self, timeout: Duration) -> Self {
         self.config.grpc_timeout = timeout;
@@ -147,6 +312,45 @@ impl AuditConfigBuilder {
         self
     }
 
+    /// Configurar la capacidad máxima de la cola de eventos
+    pub fn max_queue_size(mut self, size: usize) -> Self {
+        self.config.max_queue_size = size;
+        self
+    }
+
+    /// Configurar la política cuando la cola está llena
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.config.overflow_policy = policy;
+        self
+    }
+
+    /// Configurar el circuit breaker de los envíos
+    pub fn circuit_breaker(mut self, circuit_breaker: CircuitBreakerConfig) -> Self {
+        self.config.circuit_breaker = circuit_breaker;
+        self
+    }
+
+    /// Configurar el sampling de requests
+    pub fn sampling(mut self, sampling: SamplingConfig) -> Self {
+        self.config.sampling = sampling;
+        self
+    }
+
+    /// Configurar la fracción de requests auditadas (0.0 - 1.0)
+    pub fn sample_rate(mut self, rate: f64) -> Self {
+        self.config.sampling.rate = rate;
+        self
+    }
+
+    /// Habilitar el buffer offline en disco con un tamaño máximo en bytes
+    pub fn offline_buffer(mut self, path: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        self.config.offline_buffer = Some(OfflineBufferConfig {
+            path: path.into(),
+            max_bytes,
+        });
+        self
+    }
+
     /// Configurar el HRN resolver
     pub fn hrn_resolver(mut self, resolver: Arc<dyn HrnResolver>) -> Self {
         self.config.hrn_resolver = Some(resolver);
@@ -154,11 +358,68 @@ impl AuditConfigBuilder {
     }
 
     /// Construir la configuración
-    pub fn build(self) -> Result<AuditSdkConfig, crate::error::AuditError> {
+    ///
+    /// Valida que `service_name` no esté vacío, que `batch_size` y
+    /// `max_queue_size` sean mayores que cero y que `audit_service_url` sea una
+    /// URL http(s) con host.
+    pub fn build(mut self) -> Result<AuditSdkConfig, crate::error::AuditError> {
+        use crate::error::AuditError;
+
+        self.config.service_name = self.config.service_name.trim().to_string();
+        if self.config.service_name.is_empty() {
+            return Err(AuditError::EmptyServiceName);
+        }
+
+        if self.config.batch_size == 0 {
+            return Err(AuditError::InvalidBatchSize(self.config.batch_size));
+        }
+
+        // With an empty queue OverflowPolicy::Block would wait forever
+        if self.config.max_queue_size == 0 {
+            return Err(AuditError::InvalidQueueSize(self.config.max_queue_size));
+        }
+
+        validate_service_url(&self.config.audit_service_url)?;
+
+        // A blank tenant falls back to per-request resolution (x-tenant-id)
+        self.config.tenant_id = self
+            .config
+            .tenant_id
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+
+        if self.config.enable_request_body
+            && self.config.enable_response_body
+            && !self.redaction_configured
+        {
+            tracing::warn!(
+                "Request and response body capture are both enabled for '{}' without an explicit redaction policy; using default redaction",
+                self.config.service_name
+            );
+        }
+
         Ok(self.config)
     }
 }
 
+/// Validar que la URL del audit service sea http(s) y tenga host
+fn validate_service_url(url: &str) -> Result<(), crate::error::AuditError> {
+    let invalid =
+        |reason: &str| crate::error::AuditError::InvalidUrl(format!("{}: {}", url, reason));
+
+    let uri: http::Uri = url.parse().map_err(|_| invalid("malformed URL"))?;
+    match uri.scheme_str() {
+        Some("http") | Some("https") => {}
+        Some(_) => return Err(invalid("scheme must be http or https")),
+        None => return Err(invalid("missing scheme")),
+    }
+    if uri.host().is_none_or(str::is_empty) {
+        return Err(invalid("missing host"));
+    }
+
+    Ok(())
+}
+
 impl std::fmt::Display for AuditSdkConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -168,3 +429,150 @@ impl std::fmt::Display for AuditSdkConfig {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AuditError;
+    use http::Method;
+
+    #[test]
+    fn test_build_with_minimal_inputs() {
+        let config = AuditSdkConfig::builder()
+            .service_name("petclinic")
+            .build()
+            .unwrap();
+
+        assert_eq!(config.service_name, "petclinic");
+        assert_eq!(config.audit_service_url, "http://localhost:50052");
+        assert_eq!(config.tenant_id, None);
+    }
+
+    #[test]
+    fn test_build_rejects_empty_service_name() {
+        let result = AuditSdkConfig::builder().service_name("  ").build();
+
+        assert!(matches!(result, Err(AuditError::EmptyServiceName)));
+    }
+
+    #[test]
+    fn test_build_rejects_zero_batch_size() {
+        let result = AuditSdkConfig::builder().batch_size(0).build();
+
+        assert!(matches!(result, Err(AuditError::InvalidBatchSize(0))));
+    }
+
+    #[test]
+    fn test_build_rejects_zero_queue_size() {
+        let result = AuditSdkConfig::builder()
+            .max_queue_size(0)
+            .overflow_policy(OverflowPolicy::Block)
+            .build();
+
+        assert!(matches!(result, Err(AuditError::InvalidQueueSize(0))));
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_urls() {
+        for url in [
+            "",
+            "not a url",
+            "audit-service:50052",
+            "ftp://audit:21",
+            "http://",
+        ] {
+            let result = AuditSdkConfig::builder().audit_service_url(url).build();
+            assert!(
+                matches!(result, Err(AuditError::InvalidUrl(_))),
+                "expected InvalidUrl for {:?}",
+                url
+            );
+        }
+
+        assert!(
+            AuditSdkConfig::builder()
+                .audit_service_url("https://audit.example.com")
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_build_normalizes_blank_tenant() {
+        let config = AuditSdkConfig::builder().tenant_id(" ").build().unwrap();
+
+        assert_eq!(config.tenant_id, None);
+    }
+
+    #[test]
+    fn test_sampling_rate_applies_to_gets() {
+        let sampling = SamplingConfig {
+            rate: 0.25,
+            ..Default::default()
+        };
+
+        let audited = (0..10_000)
+            .filter(|_| sampling.should_audit(&Method::GET, 200, "", rand::random::<f64>()))
+            .count();
+
+        assert!((2_000..=3_000).contains(&audited), "audited {}", audited);
+    }
+
+    #[test]
+    fn test_sampling_always_audits_errors() {
+        let sampling = SamplingConfig {
+            rate: 0.0,
+            ..Default::default()
+        };
+
+        assert!(sampling.should_audit(&Method::GET, 500, "", 0.99));
+        assert!(sampling.should_audit(&Method::GET, 404, "", 0.99));
+        assert!(!sampling.should_audit(&Method::GET, 200, "", 0.99));
+    }
+
+    #[test]
+    fn test_sampling_always_audits_mutations() {
+        let sampling = SamplingConfig {
+            rate: 0.0,
+            ..Default::default()
+        };
+
+        for method in [Method::POST, Method::PUT, Method::PATCH, Method::DELETE] {
+            assert!(sampling.should_audit(&method, 200, "", 0.99));
+        }
+        assert!(!sampling.should_audit(&Method::HEAD, 200, "", 0.99));
+    }
+
+    #[test]
+    fn test_sampling_always_audits_hrn_patterns() {
+        let sampling = SamplingConfig {
+            rate: 0.0,
+            always_audit_hrn_patterns: vec!["hrn:hodei:auth:*".to_string()],
+            ..Default::default()
+        };
+
+        assert!(sampling.should_audit(
+            &Method::GET,
+            200,
+            "hrn:hodei:auth:tenant-1:global:auth/login",
+            0.99
+        ));
+        assert!(!sampling.should_audit(
+            &Method::GET,
+            200,
+            "hrn:hodei:api:tenant-1:global:user/1",
+            0.99
+        ));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("hrn:*:user/*", "hrn:hodei:api:t:global:user/42"));
+        assert!(glob_match("exact", "exact"));
+        assert!(!glob_match("exact", "exactly"));
+        assert!(!glob_match(
+            "hrn:*:order/*",
+            "hrn:hodei:api:t:global:user/42"
+        ));
+    }
+}
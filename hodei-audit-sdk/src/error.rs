@@ -8,6 +8,18 @@ pub enum AuditError {
     #[error("Configuration error: {0}")]
     ConfigurationError(String),
 
+    #[error("Invalid audit service URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("Invalid batch size: {0} (must be greater than 0)")]
+    InvalidBatchSize(usize),
+
+    #[error("Invalid max queue size: {0} (must be greater than 0)")]
+    InvalidQueueSize(usize),
+
+    #[error("Service name must not be empty")]
+    EmptyServiceName,
+
     #[error("gRPC error: {0}")]
     GrpcError(#[from] tonic::transport::Error),
 
@@ -22,6 +34,29 @@ pub enum AuditError {
 
     #[error("HRN error: {0}")]
     HrnError(String),
+
+    #[error("Circuit open: {0}")]
+    CircuitOpen(String),
+
+    #[error("Queue full: {0}")]
+    QueueFull(String),
+
+    #[error("Timeout: {0}")]
+    Timeout(String),
+
+    #[error("Event too large: {size} bytes exceeds the {max} byte limit")]
+    EventTooLarge { size: usize, max: usize },
+
+    #[error("Invalid event: {0}")]
+    InvalidEvent(#[from] BuildError),
+}
+
+/// Error al construir un evento con `EventBuilder`
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// Falta un campo necesario para el almacenamiento o el enrutado
+    #[error("missing required field `{0}`")]
+    MissingField(&'static str),
 }
 
 impl AuditError {
@@ -103,13 +103,23 @@ impl std::fmt::Display for Hrn {
 }
 
 /// Generar HRN desde método y path
+///
+/// Si se proporciona el route template que ha hecho match (ej:
+/// `/owners/:id/pets/:pid` o `/owners/{id}/pets/{pid}`), el HRN se genera a
+/// partir del template en lugar del path concreto, de modo que todas las
+/// requests a la misma ruta comparten HRN y la cardinalidad queda acotada.
 pub fn generate_hrn_from_path(
     method: &Method,
     path: &str,
+    route: Option<&str>,
     tenant_id: Option<&str>,
 ) -> Result<Hrn, AuditError> {
     let tenant = tenant_id.unwrap_or("unknown");
 
+    if let Some(route) = route {
+        return generate_hrn_from_route(route, tenant);
+    }
+
     // Mapeo de paths a patrones HRN
     let (service_type, resource_type, resource_id) = match path {
         // verified-permissions endpoints
@@ -172,6 +182,55 @@ pub fn generate_hrn_from_path(
     Hrn::parse(&hrn)
 }
 
+/// Generar HRN desde un route template
+///
+/// El resource type es el último segmento estático del template y el
+/// resource id es el template completo con los segmentos unidos por `.` y los
+/// parámetros normalizados a `{nombre}`.
+fn generate_hrn_from_route(route: &str, tenant: &str) -> Result<Hrn, AuditError> {
+    let segments: Vec<String> = route
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(normalize_route_segment)
+        .collect();
+
+    let resource_type = segments
+        .iter()
+        .rev()
+        .find(|s| !s.starts_with('{'))
+        .cloned()
+        .unwrap_or_else(|| "service".to_string());
+    let resource_id = if segments.is_empty() {
+        "root".to_string()
+    } else {
+        segments.join(".")
+    };
+
+    let hrn = format!(
+        "hrn:hodei:{}:{}:global:{}/{}",
+        service_type_from_path(route),
+        tenant,
+        resource_type,
+        resource_id
+    );
+
+    Hrn::parse(&hrn)
+}
+
+/// Normalizar un segmento de route template (`:id`, `*rest`, `{*rest}` -> `{id}`/`{rest}`)
+fn normalize_route_segment(segment: &str) -> String {
+    if let Some(name) = segment
+        .strip_prefix(':')
+        .or_else(|| segment.strip_prefix('*'))
+    {
+        format!("{{{}}}", name)
+    } else if let Some(name) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+        format!("{{{}}}", name.trim_start_matches('*'))
+    } else {
+        segment.to_string()
+    }
+}
+
 /// Extraer ID desde path (ej: /v1/policy-stores/{id}/... -> {id})
 fn extract_id_from_path(path: &str, segment_index: usize) -> Option<String> {
     let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
@@ -268,6 +327,7 @@ mod tests {
         let hrn = generate_hrn_from_path(
             &http::Method::GET,
             "/v1/policy-stores/default/policies",
+            None,
             Some("tenant-123"),
         )
         .unwrap();
@@ -279,8 +339,13 @@ mod tests {
 
     #[test]
     fn test_generate_hrn_authorize() {
-        let hrn = generate_hrn_from_path(&http::Method::POST, "/v1/authorize", Some("tenant-123"))
-            .unwrap();
+        let hrn = generate_hrn_from_path(
+            &http::Method::POST,
+            "/v1/authorize",
+            None,
+            Some("tenant-123"),
+        )
+        .unwrap();
 
         assert!(hrn.as_str().contains("verified-permissions"));
         assert!(hrn.as_str().contains("authorization"));
@@ -288,9 +353,13 @@ mod tests {
 
     #[test]
     fn test_generate_hrn_api_users() {
-        let hrn =
-            generate_hrn_from_path(&http::Method::GET, "/api/v1/users/456", Some("tenant-123"))
-                .unwrap();
+        let hrn = generate_hrn_from_path(
+            &http::Method::GET,
+            "/api/v1/users/456",
+            None,
+            Some("tenant-123"),
+        )
+        .unwrap();
 
         assert!(hrn.as_str().contains("api"));
         assert!(hrn.as_str().contains("user/456"));
@@ -298,8 +367,13 @@ mod tests {
 
     #[test]
     fn test_generate_hrn_auth() {
-        let hrn = generate_hrn_from_path(&http::Method::POST, "/v1/auth/login", Some("tenant-123"))
-            .unwrap();
+        let hrn = generate_hrn_from_path(
+            &http::Method::POST,
+            "/v1/auth/login",
+            None,
+            Some("tenant-123"),
+        )
+        .unwrap();
 
         assert!(hrn.as_str().contains("auth"));
         assert!(hrn.as_str().contains("login"));
@@ -307,13 +381,58 @@ mod tests {
 
     #[test]
     fn test_generate_hrn_health() {
-        let hrn =
-            generate_hrn_from_path(&http::Method::GET, "/health", Some("tenant-123")).unwrap();
+        let hrn = generate_hrn_from_path(&http::Method::GET, "/health", None, Some("tenant-123"))
+            .unwrap();
 
         assert!(hrn.as_str().contains("service"));
         assert!(hrn.as_str().contains("health"));
     }
 
+    #[test]
+    fn test_generate_hrn_from_route_is_stable_across_ids() {
+        let first = generate_hrn_from_path(
+            &http::Method::GET,
+            "/owners/42/pets/7",
+            Some("/owners/:id/pets/:pid"),
+            Some("tenant-123"),
+        )
+        .unwrap();
+        let second = generate_hrn_from_path(
+            &http::Method::GET,
+            "/owners/1001/pets/99",
+            Some("/owners/:id/pets/:pid"),
+            Some("tenant-123"),
+        )
+        .unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(first.resource_type(), "pets");
+        assert_eq!(first.resource_id(), "owners.{id}.pets.{pid}");
+        assert!(!first.as_str().contains("42"));
+    }
+
+    #[test]
+    fn test_generate_hrn_from_route_brace_syntax() {
+        let colon = generate_hrn_from_path(
+            &http::Method::GET,
+            "/api/v1/users/456",
+            Some("/api/v1/users/:user_id"),
+            Some("tenant-123"),
+        )
+        .unwrap();
+        let braces = generate_hrn_from_path(
+            &http::Method::GET,
+            "/api/v1/users/456",
+            Some("/api/v1/users/{user_id}"),
+            Some("tenant-123"),
+        )
+        .unwrap();
+
+        assert_eq!(colon, braces);
+        assert_eq!(colon.service(), "api");
+        assert_eq!(colon.resource_type(), "users");
+    }
+
     #[test]
     fn test_extract_id_from_path() {
         assert_eq!(
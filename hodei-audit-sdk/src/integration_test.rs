@@ -219,6 +219,7 @@ mod benchmarks {
                 let hrn = hodei_audit_sdk::generate_hrn_from_path(
                     &http::Method::GET,
                     "/v1/policy-stores/default/policies",
+                    None,
                     Some("tenant-123"),
                 )
                 .unwrap();
@@ -231,6 +232,7 @@ mod benchmarks {
                 let hrn = hodei_audit_sdk::generate_hrn_from_path(
                     &http::Method::GET,
                     "/api/v1/users/456",
+                    None,
                     Some("tenant-123"),
                 )
                 .unwrap();
@@ -243,6 +245,7 @@ mod benchmarks {
                 let hrn = hodei_audit_sdk::generate_hrn_from_path(
                     &http::Method::POST,
                     "/v1/auth/login",
+                    None,
                     Some("tenant-123"),
                 )
                 .unwrap();
@@ -31,21 +31,33 @@
 //! - `custom-enricher`: Habilita enrichers personalizados
 
 pub mod batch;
+pub mod circuit_breaker;
 pub mod client;
 pub mod config;
 pub mod error;
 pub mod hrn;
 pub mod middleware;
 pub mod models;
+pub mod offline;
+pub mod redaction;
 pub mod types;
 
-pub use batch::{BatchQueue, BatchStats, FlushPolicy, GrpcConnectionPool, RetryConfig, RetryError};
-pub use client::{AuditClient, AuditQueryResult};
-pub use config::{AuditConfigBuilder, AuditSdkConfig, HrnMetadata, HrnResolver};
-pub use error::AuditError;
+pub use batch::{
+    BatchQueue, BatchStats, FlushPolicy, GrpcConnectionPool, JitterMode, RetryConfig, RetryError,
+};
+pub use circuit_breaker::{
+    CircuitBreaker, CircuitBreakerConfig, CircuitBreakerMetrics, CircuitState, OpenCircuitPolicy,
+};
+pub use client::{AuditClient, AuditQueryResult, AuditQueryService, AuditTransport};
+pub use config::{
+    AuditConfigBuilder, AuditSdkConfig, HrnMetadata, HrnResolver, OverflowPolicy, SamplingConfig,
+};
+pub use error::{AuditError, BuildError};
 pub use hrn::{Hrn, enrich_event_with_hrn, generate_hrn_from_path};
-pub use middleware::AuditLayer;
+pub use middleware::{AuditLayer, MatchedRoute};
 pub use models::{AuditEvent, EventBuilder};
+pub use offline::{DiskBuffer, OfflineBufferConfig, WalRecord};
+pub use redaction::{REDACTED_VALUE, RedactionConfig};
 pub use types::AuditQuery;
 
 /// Resultado de operaciones del SDK
@@ -8,7 +8,7 @@ use crate::config::AuditSdkConfig;
 use crate::hrn::{enrich_event_with_hrn, generate_hrn_from_path};
 use crate::models::AuditEvent;
 use bytes::Bytes;
-use http::{Request, Response};
+use http::{HeaderMap, Request, Response};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -16,6 +16,83 @@ use std::task::{Context, Poll};
 use tower::{Layer, Service};
 use tracing::{debug, error};
 
+/// Marcador añadido a los bodies truncados por `max_body_bytes`
+pub const BODY_TRUNCATED_MARKER: &str = "...[truncated]";
+
+/// Capturar un body para el evento de auditoría
+///
+/// Solo se capturan bodies cuyo `content-type` está en `body_content_types`;
+/// el resto (binarios incluidos) se omite. El body se redacta con
+/// `RedactionConfig::redact_body`, que omite los que no sabe redactar. Los
+/// bodies mayores que `max_body_bytes` se truncan y se marcan con
+/// `BODY_TRUNCATED_MARKER`.
+pub fn capture_body(headers: &HeaderMap, body: &[u8], config: &AuditSdkConfig) -> Option<String> {
+    let content_type = headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())?;
+
+    let mime = mime_type(content_type);
+    if !content_type_allowed(&mime, &config.body_content_types) {
+        return None;
+    }
+
+    // Redact before the body can be truncated
+    let redacted = config.redaction.redact_body(&mime, body)?;
+    let body = redacted.as_slice();
+
+    if body.len() <= config.max_body_bytes {
+        return Some(String::from_utf8_lossy(body).into_owned());
+    }
+
+    // Truncate on a char boundary so the captured text stays valid UTF-8
+    let mut captured = String::from_utf8_lossy(&body[..config.max_body_bytes]).into_owned();
+    if captured.ends_with(char::REPLACEMENT_CHARACTER) {
+        captured.pop();
+    }
+    captured.push_str(BODY_TRUNCATED_MARKER);
+    Some(captured)
+}
+
+/// MIME type de un `content-type`, sin parámetros y en minúsculas
+fn mime_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Verificar si un MIME type está en la allowlist
+fn content_type_allowed(mime: &str, allowed: &[String]) -> bool {
+    allowed.iter().any(|entry| {
+        let entry = entry.to_ascii_lowercase();
+        match entry.strip_suffix("/*") {
+            Some(prefix) => mime.split('/').next() == Some(prefix),
+            None => mime == entry,
+        }
+    })
+}
+
+/// Insertar un valor en `additional_data` del evento
+fn insert_additional_data(event: &mut AuditEvent, key: &str, value: serde_json::Value) {
+    let data = event
+        .additional_data
+        .get_or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+
+    if let serde_json::Value::Object(map) = data {
+        map.insert(key.to_string(), value);
+    }
+}
+
+/// Route template que ha hecho match para la request
+///
+/// Insertado como extension de la request (por ejemplo a partir de
+/// `axum::extract::MatchedPath`), hace que el HRN, el `event_name` y el
+/// `resource_path` se generen desde el template y no desde el path concreto.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedRoute(pub String);
+
 /// Layer de auditoría que implementa el middleware Axum
 #[derive(Debug, Clone)]
 pub struct AuditLayer {
@@ -87,11 +164,10 @@ pub struct AuditService<S> {
     service: S,
 }
 
-impl<S, B> Service<Request<B>> for AuditService<S>
+impl<S> Service<Request<Bytes>> for AuditService<S>
 where
-    S: Service<Request<B>, Response = Response<Bytes>> + Send + Clone + 'static,
+    S: Service<Request<Bytes>, Response = Response<Bytes>> + Send + Clone + 'static,
     S::Future: Send + 'static,
-    B: Send + 'static,
 {
     type Response = S::Response;
     type Error = S::Error;
@@ -101,7 +177,7 @@ where
         self.service.poll_ready(cx)
     }
 
-    fn call(&mut self, request: Request<B>) -> Self::Future {
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
         let config = self.config.clone();
         let batch_queue = self.batch_queue.clone();
         let mut service = self.service.clone();
@@ -109,6 +185,10 @@ where
         // Extract audit data from request before moving it
         let method = request.method().clone();
         let path = request.uri().path().to_string();
+        let route = request
+            .extensions()
+            .get::<MatchedRoute>()
+            .map(|r| r.0.clone());
         let user_id = request
             .headers()
             .get("x-user-id")
@@ -118,7 +198,23 @@ where
             .headers()
             .get("x-tenant-id")
             .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string());
+            .map(|s| s.to_string())
+            .or_else(|| config.tenant_id.clone());
+        let query = request
+            .uri()
+            .query()
+            .filter(|q| !q.is_empty())
+            .map(|q| config.redaction.redact_query(q));
+        let request_headers = if config.enable_headers {
+            Some(config.redaction.redact_headers(request.headers()))
+        } else {
+            None
+        };
+        let request_body = if cfg!(feature = "request-body") && config.enable_request_body {
+            capture_body(request.headers(), request.body(), &config)
+        } else {
+            None
+        };
 
         Box::pin(async move {
             // Call next service with the original request (need mutable reference)
@@ -126,47 +222,86 @@ where
 
             // Generate HRN from request
             let hrn =
-                generate_hrn_from_path(&method, &path, tenant_id.as_deref()).unwrap_or_else(|_| {
-                    // Fallback to simple HRN if generation fails
-                    let fallback_hrn = format!(
-                        "hrn:hodei:{}:{}:global:resource/{}",
-                        config.service_name,
-                        tenant_id.as_deref().unwrap_or("unknown"),
-                        path
-                    );
-                    // Parse the fallback HRN
-                    crate::hrn::Hrn::parse(&fallback_hrn).unwrap_or_else(|_| {
-                        // If even the fallback fails, create a minimal valid HRN
-                        crate::hrn::Hrn::parse(&format!(
-                            "hrn:hodei:service:unknown:global:resource/unknown"
-                        ))
-                        .unwrap()
-                    })
-                });
+                generate_hrn_from_path(&method, &path, route.as_deref(), tenant_id.as_deref())
+                    .unwrap_or_else(|_| {
+                        // Fallback to simple HRN if generation fails
+                        let fallback_hrn = format!(
+                            "hrn:hodei:{}:{}:global:resource/{}",
+                            config.service_name,
+                            tenant_id.as_deref().unwrap_or("unknown"),
+                            path
+                        );
+                        // Parse the fallback HRN
+                        crate::hrn::Hrn::parse(&fallback_hrn).unwrap_or_else(|_| {
+                            // If even the fallback fails, create a minimal valid HRN
+                            crate::hrn::Hrn::parse(&format!(
+                                "hrn:hodei:service:unknown:global:resource/unknown"
+                            ))
+                            .unwrap()
+                        })
+                    });
+
+            // Skip events discarded by sampling
+            let status = response.status().as_u16();
+            if !config
+                .sampling
+                .should_audit(&method, status, hrn.as_str(), rand::random::<f64>())
+            {
+                return Ok(response);
+            }
+
+            // Prefer the route template so the event stays low-cardinality
+            let resource_path = route.unwrap_or(path);
 
             // Create audit event (non-blocking)
             let mut event = AuditEvent {
-                event_name: format!("{} {}", method, path),
+                event_name: format!("{} {}", method, resource_path),
                 event_category: 0, // Management event
                 hrn: hrn.to_string(),
                 user_id: user_id.unwrap_or_else(|| "anonymous".to_string()),
                 tenant_id: tenant_id.unwrap_or_else(|| "unknown".to_string()),
                 trace_id: "no-trace".to_string(),
-                resource_path: path,
+                resource_path,
                 http_method: Some(method.to_string()),
-                http_status: Some(response.status().as_u16() as i32),
+                http_status: Some(status as i32),
                 source_ip: None,
                 user_agent: None,
                 additional_data: None,
             };
 
+            let response_body = if cfg!(feature = "response-body") && config.enable_response_body {
+                capture_body(response.headers(), response.body(), &config)
+            } else {
+                None
+            };
+            if let Some(query) = query {
+                insert_additional_data(&mut event, "query", serde_json::Value::String(query));
+            }
+            if let Some(headers) = request_headers {
+                insert_additional_data(
+                    &mut event,
+                    "request_headers",
+                    serde_json::Value::Object(headers),
+                );
+            }
+            if let Some(body) = request_body {
+                insert_additional_data(&mut event, "request_body", serde_json::Value::String(body));
+            }
+            if let Some(body) = response_body {
+                insert_additional_data(
+                    &mut event,
+                    "response_body",
+                    serde_json::Value::String(body),
+                );
+            }
+
             // Enrich with HRN metadata if resolver is available
             if let Err(e) = enrich_event_with_hrn(&mut event, &config.hrn_resolver).await {
                 debug!("Failed to enrich event with HRN metadata: {}", e);
             }
 
-            // Add to batch queue (async, non-blocking)
-            if let Err(e) = batch_queue.add_event(event) {
+            // Add to batch queue (only waits with OverflowPolicy::Block)
+            if let Err(e) = batch_queue.enqueue(event).await {
                 error!("Failed to add event to batch: {}", e);
             }
 
@@ -242,6 +377,220 @@ mod tests {
         assert!(config_str.contains("http://localhost:50052"));
     }
 
+    fn json_headers(content_type: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_str(content_type).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_capture_small_json_body_fully() {
+        let config = AuditSdkConfig::default();
+        let body = br#"{"name":"rex","species":"dog"}"#;
+
+        let captured = capture_body(&json_headers("application/json"), body, &config).unwrap();
+
+        assert_eq!(captured, r#"{"name":"rex","species":"dog"}"#);
+    }
+
+    #[test]
+    fn test_capture_large_json_body_truncated() {
+        let config = AuditSdkConfig::builder()
+            .max_body_bytes(16)
+            .build()
+            .unwrap();
+        let body = format!(r#"{{"data":"{}"}}"#, "x".repeat(100));
+
+        let captured = capture_body(
+            &json_headers("application/json; charset=utf-8"),
+            body.as_bytes(),
+            &config,
+        )
+        .unwrap();
+
+        assert!(captured.ends_with(BODY_TRUNCATED_MARKER));
+        assert_eq!(captured.len(), 16 + BODY_TRUNCATED_MARKER.len());
+        assert_eq!(&captured[..16], &body[..16]);
+    }
+
+    #[test]
+    fn test_capture_skips_binary_body() {
+        let config = AuditSdkConfig::default();
+        let body = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+        assert!(capture_body(&json_headers("image/png"), &body, &config).is_none());
+        assert!(capture_body(&HeaderMap::new(), b"{}", &config).is_none());
+    }
+
+    #[test]
+    fn test_capture_wildcard_content_type() {
+        let config = AuditSdkConfig::builder()
+            .body_content_types(&["text/*"])
+            .redaction(crate::redaction::RedactionConfig {
+                capture_unredacted_bodies: true,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        assert!(capture_body(&json_headers("text/plain"), b"hello", &config).is_some());
+        assert!(capture_body(&json_headers("application/json"), b"{}", &config).is_none());
+    }
+
+    /// Service de prueba que responde con el status indicado y un body JSON vacío
+    #[derive(Clone)]
+    struct MockService(StatusCode);
+
+    impl Service<Request<Bytes>> for MockService {
+        type Response = Response<Bytes>;
+        type Error = std::convert::Infallible;
+        type Future = std::future::Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _request: Request<Bytes>) -> Self::Future {
+            let mut response = Response::new(Bytes::from_static(b"{}"));
+            *response.status_mut() = self.0;
+            std::future::ready(Ok(response))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redaction_applied_before_enqueue() {
+        let config = AuditSdkConfig::builder()
+            .enable_headers(true)
+            .redaction(crate::redaction::RedactionConfig {
+                masked_query_params: vec!["email".to_string()],
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        let layer = AuditLayer::new(config);
+        let mut service = layer.layer(MockService(StatusCode::OK));
+
+        let request = Request::builder()
+            .uri("/api/v1/users?email=jane@example.com&page=2")
+            .header("authorization", "Bearer secret-token")
+            .header("accept", "application/json")
+            .header("content-type", "application/json")
+            .body(Bytes::from_static(br#"{"password":"hunter2"}"#))
+            .unwrap();
+        service.call(request).await.unwrap();
+
+        let events = layer.batch_queue.snapshot();
+        let data = events[0].additional_data.as_ref().unwrap();
+        assert_eq!(data["query"], "email=***&page=2");
+        assert_eq!(data["request_headers"]["authorization"], "***");
+        assert_eq!(data["request_headers"]["accept"], "application/json");
+        assert_eq!(data["request_body"], r#"{"password":"***"}"#);
+
+        let serialized = serde_json::to_string(&events[0]).unwrap();
+        assert!(!serialized.contains("secret-token"));
+        assert!(!serialized.contains("jane@example.com"));
+        assert!(!serialized.contains("hunter2"));
+    }
+
+    #[tokio::test]
+    async fn test_non_json_bodies_are_redacted_or_omitted() {
+        let config = AuditSdkConfig::builder()
+            .body_content_types(&["application/x-www-form-urlencoded", "text/plain"])
+            .build()
+            .unwrap();
+        let layer = AuditLayer::new(config);
+        let mut service = layer.layer(MockService(StatusCode::OK));
+
+        for (content_type, body) in [
+            (
+                "application/x-www-form-urlencoded",
+                "user=jane&password=hunter2",
+            ),
+            ("text/plain", "password=hunter2"),
+        ] {
+            let request = Request::builder()
+                .method(http::Method::POST)
+                .uri("/login")
+                .header("content-type", content_type)
+                .body(Bytes::from_static(body.as_bytes()))
+                .unwrap();
+            service.call(request).await.unwrap();
+        }
+
+        let events = layer.batch_queue.snapshot();
+        let form = events[0].additional_data.as_ref().unwrap();
+        assert_eq!(form["request_body"], "user=jane&password=***");
+        let text = events[1].additional_data.as_ref();
+        assert!(text.is_none_or(|data| data.get("request_body").is_none()));
+    }
+
+    async fn send_request(service: &mut AuditService<MockService>, method: http::Method) {
+        let request = Request::builder()
+            .method(method)
+            .uri("/api/v1/users/1")
+            .body(Bytes::new())
+            .unwrap();
+        service.call(request).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sampling_drops_successful_gets() {
+        let config = AuditSdkConfig::builder().sample_rate(0.0).build().unwrap();
+        let layer = AuditLayer::new(config);
+        let mut service = layer.layer(MockService(StatusCode::OK));
+
+        for _ in 0..10 {
+            send_request(&mut service, http::Method::GET).await;
+        }
+
+        assert!(layer.batch_queue.snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sampling_keeps_errors_and_mutations() {
+        let config = AuditSdkConfig::builder().sample_rate(0.0).build().unwrap();
+        let layer = AuditLayer::new(config);
+
+        let mut failing = layer.layer(MockService(StatusCode::INTERNAL_SERVER_ERROR));
+        send_request(&mut failing, http::Method::GET).await;
+
+        let mut ok = layer.layer(MockService(StatusCode::OK));
+        for method in [http::Method::POST, http::Method::PUT, http::Method::DELETE] {
+            send_request(&mut ok, method).await;
+        }
+
+        let events = layer.batch_queue.snapshot();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].http_status, Some(500));
+    }
+
+    #[tokio::test]
+    async fn test_matched_route_produces_stable_hrn() {
+        let layer = AuditLayer::new(AuditSdkConfig::default());
+        let mut service = layer.layer(MockService(StatusCode::OK));
+
+        for path in ["/owners/42/pets/7", "/owners/1001/pets/99"] {
+            let mut request = Request::builder()
+                .uri(path)
+                .header("x-tenant-id", "tenant-123")
+                .body(Bytes::new())
+                .unwrap();
+            request
+                .extensions_mut()
+                .insert(MatchedRoute("/owners/:id/pets/:pid".to_string()));
+            service.call(request).await.unwrap();
+        }
+
+        let events = layer.batch_queue.snapshot();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].hrn, events[1].hrn);
+        assert_eq!(events[0].resource_path, "/owners/:id/pets/:pid");
+        assert_eq!(events[0].event_name, "GET /owners/:id/pets/:pid");
+    }
+
     #[test]
     fn test_extract_audit_context() {
         // This test verifies that the middleware can extract
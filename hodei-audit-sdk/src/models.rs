@@ -1,5 +1,7 @@
 //! Modelos de datos para eventos de auditoría
 
+use crate::error::BuildError;
+use crate::redaction::RedactionConfig;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
@@ -51,6 +53,42 @@ impl Default for AuditEvent {
     }
 }
 
+impl AuditEvent {
+    /// Tamaño del evento codificado en protobuf, tal como viaja al servicio
+    ///
+    /// Es la medida del límite `max_event_bytes` del SDK y del servicio;
+    /// `additional_data` viaja como su texto JSON.
+    pub fn encoded_len(&self) -> usize {
+        use prost::encoding::{int32, string};
+
+        let optional = |tag: u32, value: &Option<String>| {
+            value
+                .as_ref()
+                .map_or(0, |value| string::encoded_len(tag, value))
+        };
+        let additional_data = self
+            .additional_data
+            .as_ref()
+            .map_or(0, |data| string::encoded_len(12, &data.to_string()));
+
+        string::encoded_len(1, &self.event_name)
+            + int32::encoded_len(2, &self.event_category)
+            + string::encoded_len(3, &self.hrn)
+            + string::encoded_len(4, &self.user_id)
+            + string::encoded_len(5, &self.tenant_id)
+            + string::encoded_len(6, &self.trace_id)
+            + string::encoded_len(7, &self.resource_path)
+            + optional(8, &self.http_method)
+            + self
+                .http_status
+                .as_ref()
+                .map_or(0, |status| int32::encoded_len(9, status))
+            + optional(10, &self.source_ip)
+            + optional(11, &self.user_agent)
+            + additional_data
+    }
+}
+
 /// Categoría de evento (CloudTrail)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -73,6 +111,7 @@ pub struct EventBuilder {
     user_agent: Option<String>,
     additional_data: Option<serde_json::Value>,
     read_only: bool,
+    redaction: Option<RedactionConfig>,
 }
 
 impl EventBuilder {
@@ -90,6 +129,7 @@ impl EventBuilder {
             user_agent: None,
             additional_data: None,
             read_only: true,
+            redaction: None,
         }
     }
 
@@ -159,15 +199,26 @@ impl EventBuilder {
         self
     }
 
+    /// Aplicar una política de redacción a los datos adicionales
+    pub fn redaction(mut self, redaction: &RedactionConfig) -> Self {
+        self.redaction = Some(redaction.clone());
+        self
+    }
+
     /// Construir el evento
-    pub fn build(self) -> Result<AuditEvent, crate::error::AuditError> {
-        let event_name = self.event_name.ok_or_else(|| {
-            crate::error::AuditError::ParseError("event_name is required".to_string())
-        })?;
+    ///
+    /// `event_name`, `hrn` y `tenant_id` son obligatorios (un valor vacío
+    /// cuenta como ausente): sin ellos el evento no se puede particionar ni
+    /// aislar por tenant en el servicio.
+    pub fn build(self) -> Result<AuditEvent, BuildError> {
+        let event_name = required(self.event_name, "event_name")?;
+        let hrn = required(self.hrn, "hrn")?;
+        let tenant_id = required(self.tenant_id, "tenant_id")?;
 
-        let hrn = self
-            .hrn
-            .ok_or_else(|| crate::error::AuditError::ParseError("hrn is required".to_string()))?;
+        let mut additional_data = self.additional_data;
+        if let (Some(redaction), Some(data)) = (&self.redaction, additional_data.as_mut()) {
+            redaction.redact_value(data);
+        }
 
         Ok(AuditEvent {
             event_name,
@@ -181,14 +232,107 @@ impl EventBuilder {
                 .unwrap_or(0),
             hrn,
             user_id: self.user_id.unwrap_or_else(|| "anonymous".to_string()),
-            tenant_id: self.tenant_id.unwrap_or_else(|| "unknown".to_string()),
+            tenant_id,
             trace_id: self.trace_id.unwrap_or_else(|| "no-trace".to_string()),
             resource_path: self.resource_path.unwrap_or_else(|| "".to_string()),
             source_ip: self.source_ip,
             user_agent: self.user_agent,
             http_method: None,
             http_status: None,
-            additional_data: self.additional_data,
+            additional_data,
         })
     }
 }
+
+/// Valor de un campo obligatorio del builder
+fn required(value: Option<String>, field: &'static str) -> Result<String, BuildError> {
+    value
+        .filter(|v| !v.trim().is_empty())
+        .ok_or(BuildError::MissingField(field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redaction::REDACTED_VALUE;
+
+    #[test]
+    fn test_encoded_len_counts_every_field() {
+        let event = AuditEvent::default();
+        let base = event.encoded_len();
+        // Tag y longitud de un byte cada uno
+        assert_eq!(
+            base,
+            ["unknown", "", "anonymous", "unknown", "no-trace", ""]
+                .iter()
+                .map(|s| 2 + s.len())
+                .sum::<usize>()
+                + 2
+        );
+
+        let data = serde_json::json!({"reason": "x".repeat(200)});
+        let with_data = AuditEvent {
+            http_method: Some("GET".to_string()),
+            additional_data: Some(data.clone()),
+            ..event
+        };
+        assert_eq!(
+            with_data.encoded_len(),
+            base + (2 + 3) + (3 + data.to_string().len())
+        );
+    }
+
+    #[test]
+    fn test_event_builder_applies_redaction() {
+        let event = EventBuilder::new()
+            .event_name("user.login")
+            .hrn("hrn:hodei:auth:tenant-123:global:auth/login")
+            .tenant_id("tenant-123")
+            .additional_data(serde_json::json!({"username": "jane", "password": "hunter2"}))
+            .redaction(&RedactionConfig::default())
+            .build()
+            .unwrap();
+
+        let data = event.additional_data.unwrap();
+        assert_eq!(data["username"], "jane");
+        assert_eq!(data["password"], REDACTED_VALUE);
+    }
+
+    #[test]
+    fn test_event_builder_requires_tenant_id() {
+        let builder = || {
+            EventBuilder::new()
+                .event_name("policy.update")
+                .hrn("hrn:hodei:authz:tenant-123:global:policy/p1")
+        };
+
+        let err = builder().build().unwrap_err();
+        assert_eq!(err, BuildError::MissingField("tenant_id"));
+        assert_eq!(
+            builder().tenant_id("  ").build().unwrap_err(),
+            BuildError::MissingField("tenant_id")
+        );
+
+        let event = builder()
+            .tenant_id("tenant-123")
+            .user_id("user-1")
+            .trace_id("trace-1")
+            .resource_path("/policies/p1")
+            .event_category(EventCategory::Management)
+            .read_write()
+            .build()
+            .unwrap();
+        assert_eq!(event.tenant_id, "tenant-123");
+        assert_eq!(event.hrn, "hrn:hodei:authz:tenant-123:global:policy/p1");
+    }
+
+    #[test]
+    fn test_event_builder_names_first_missing_field() {
+        let err = EventBuilder::new()
+            .tenant_id("tenant-123")
+            .build()
+            .unwrap_err();
+        assert_eq!(err, BuildError::MissingField("event_name"));
+        assert_eq!(err.to_string(), "missing required field `event_name`");
+    }
+}
@@ -0,0 +1,430 @@
+//! Buffer offline persistido en disco
+//!
+//! Cuando el audit service no está disponible, los eventos se escriben en un
+//! WAL (un registro JSON por línea) para sobrevivir a reinicios de la
+//! aplicación host. El WAL se reenvía cuando vuelve la conectividad y está
+//! acotado por tamaño, descartando primero los eventos más antiguos.
+//!
+//! Cada registro lleva un número de secuencia. La `BatchQueue` persiste los
+//! eventos antes de encolarlos y los mantiene retenidos mientras están en
+//! memoria, de modo que el replay no los reenvía por duplicado; tras un envío
+//! exitoso se eliminan del WAL por su secuencia.
+
+use crate::error::AuditError;
+use crate::models::AuditEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::warn;
+
+/// Configuración del buffer offline
+#[derive(Debug, Clone)]
+pub struct OfflineBufferConfig {
+    /// Ruta del fichero WAL
+    pub path: PathBuf,
+    /// Tamaño máximo del WAL en bytes
+    pub max_bytes: u64,
+}
+
+/// Registro del WAL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalRecord {
+    /// Secuencia del registro, creciente dentro del WAL
+    pub seq: u64,
+    /// Evento persistido
+    pub event: AuditEvent,
+}
+
+/// Buffer de eventos persistido en disco
+#[derive(Debug)]
+pub struct DiskBuffer {
+    path: PathBuf,
+    max_bytes: u64,
+    /// Serializa el acceso al fichero
+    lock: Mutex<()>,
+    /// Siguiente secuencia a asignar
+    next_seq: AtomicU64,
+    /// Registros retenidos por la cola en memoria; el replay los omite
+    held: Mutex<HashSet<u64>>,
+    /// Eventos descartados por superar `max_bytes`
+    evicted_events: AtomicU64,
+    /// Líneas ilegibles apartadas al abrir el WAL
+    corrupt_records: AtomicU64,
+}
+
+impl DiskBuffer {
+    /// Abrir (o crear) el WAL en la ruta indicada
+    ///
+    /// Las líneas que no se pueden leer (por ejemplo una escritura cortada
+    /// por una caída) se mueven a un fichero `.corrupt` junto al WAL y se
+    /// contabilizan en `corrupt_records`.
+    pub fn open(config: &OfflineBufferConfig) -> Result<Self, AuditError> {
+        if let Some(parent) = config.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&config.path)
+            .map_err(io_error)?;
+
+        let buffer = Self {
+            path: config.path.clone(),
+            max_bytes: config.max_bytes,
+            lock: Mutex::new(()),
+            next_seq: AtomicU64::new(0),
+            held: Mutex::new(HashSet::new()),
+            evicted_events: AtomicU64::new(0),
+            corrupt_records: AtomicU64::new(0),
+        };
+        let last_seq = buffer.recover()?;
+        buffer
+            .next_seq
+            .store(last_seq.map_or(0, |seq| seq + 1), Ordering::Relaxed);
+        Ok(buffer)
+    }
+
+    /// Ruta del WAL
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Añadir eventos al WAL, expulsando los más antiguos si se supera `max_bytes`
+    ///
+    /// Devuelve las secuencias asignadas, en el orden de `events`.
+    pub fn append(&self, events: &[AuditEvent]) -> Result<Vec<u64>, AuditError> {
+        self.write_records(events, false)
+    }
+
+    /// Añadir eventos al WAL retenidos por la cola en memoria
+    ///
+    /// El replay no los incluye hasta que se liberen con `release`.
+    pub fn append_held(&self, events: &[AuditEvent]) -> Result<Vec<u64>, AuditError> {
+        self.write_records(events, true)
+    }
+
+    /// Leer todos los registros del WAL (del más antiguo al más reciente)
+    pub fn load(&self) -> Result<Vec<WalRecord>, AuditError> {
+        let _guard = self.lock.lock().unwrap();
+        self.read_records()
+    }
+
+    /// Registros a reenviar: todos salvo los retenidos por la cola en memoria
+    pub fn pending_replay(&self) -> Result<Vec<WalRecord>, AuditError> {
+        let _guard = self.lock.lock().unwrap();
+        let held = self.held.lock().unwrap();
+        Ok(self
+            .read_records()?
+            .into_iter()
+            .filter(|record| !held.contains(&record.seq))
+            .collect())
+    }
+
+    /// Devolver al replay registros retenidos cuyo envío ha fallado
+    pub fn release(&self, seqs: &[u64]) {
+        let mut held = self.held.lock().unwrap();
+        for seq in seqs {
+            held.remove(seq);
+        }
+    }
+
+    /// Eliminar del WAL registros ya entregados o descartados
+    ///
+    /// Si la reescritura falla los registros siguen en el WAL y quedan
+    /// disponibles para el replay.
+    pub fn remove(&self, seqs: &[u64]) -> Result<(), AuditError> {
+        if seqs.is_empty() {
+            return Ok(());
+        }
+        self.release(seqs);
+
+        let _guard = self.lock.lock().unwrap();
+        let removed: HashSet<u64> = seqs.iter().copied().collect();
+        let kept: Vec<WalRecord> = self
+            .read_records()?
+            .into_iter()
+            .filter(|record| !removed.contains(&record.seq))
+            .collect();
+        self.rewrite(&kept)
+    }
+
+    /// Número de eventos en el WAL
+    pub fn len(&self) -> Result<usize, AuditError> {
+        let _guard = self.lock.lock().unwrap();
+        Ok(self.read_lines()?.len())
+    }
+
+    /// Verificar si el WAL está vacío
+    pub fn is_empty(&self) -> Result<bool, AuditError> {
+        Ok(self.size_bytes()? == 0)
+    }
+
+    /// Tamaño actual del WAL en bytes
+    pub fn size_bytes(&self) -> Result<u64, AuditError> {
+        Ok(std::fs::metadata(&self.path).map_err(io_error)?.len())
+    }
+
+    /// Eventos descartados por superar `max_bytes`
+    pub fn evicted_events(&self) -> u64 {
+        self.evicted_events.load(Ordering::Relaxed)
+    }
+
+    /// Registros ilegibles apartados al abrir el WAL
+    pub fn corrupt_records(&self) -> u64 {
+        self.corrupt_records.load(Ordering::Relaxed)
+    }
+
+    /// Vaciar el WAL
+    pub fn clear(&self) -> Result<(), AuditError> {
+        let _guard = self.lock.lock().unwrap();
+        File::create(&self.path).map_err(io_error)?;
+        self.held.lock().unwrap().clear();
+        Ok(())
+    }
+
+    fn write_records(&self, events: &[AuditEvent], held: bool) -> Result<Vec<u64>, AuditError> {
+        if events.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let _guard = self.lock.lock().unwrap();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&self.path)
+            .map_err(io_error)?;
+        let mut seqs = Vec::with_capacity(events.len());
+        for event in events {
+            let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+            let mut line = serde_json::to_vec(&WalRecord {
+                seq,
+                event: event.clone(),
+            })?;
+            line.push(b'\n');
+            file.write_all(&line).map_err(io_error)?;
+            seqs.push(seq);
+        }
+        file.sync_data().map_err(io_error)?;
+        if held {
+            self.held.lock().unwrap().extend(seqs.iter().copied());
+        }
+
+        if file.metadata().map_err(io_error)?.len() > self.max_bytes {
+            self.evict_oldest()?;
+        }
+
+        Ok(seqs)
+    }
+
+    /// Apartar las líneas ilegibles y devolver la última secuencia del WAL
+    fn recover(&self) -> Result<Option<u64>, AuditError> {
+        let mut records = Vec::new();
+        let mut corrupt = Vec::new();
+        for line in self.read_lines()? {
+            match serde_json::from_str::<WalRecord>(&line) {
+                Ok(record) => records.push(record),
+                Err(_) => corrupt.push(line),
+            }
+        }
+        let last_seq = records.iter().map(|record| record.seq).max();
+        if corrupt.is_empty() {
+            return Ok(last_seq);
+        }
+
+        let corrupt_path = self.path.with_extension("corrupt");
+        let mut quarantine = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&corrupt_path)
+            .map_err(io_error)?;
+        for line in &corrupt {
+            quarantine.write_all(line.as_bytes()).map_err(io_error)?;
+            quarantine.write_all(b"\n").map_err(io_error)?;
+        }
+        quarantine.sync_data().map_err(io_error)?;
+        self.rewrite(&records)?;
+
+        self.corrupt_records
+            .fetch_add(corrupt.len() as u64, Ordering::Relaxed);
+        warn!(
+            "Offline audit buffer had {} unreadable records, moved to {}",
+            corrupt.len(),
+            corrupt_path.display()
+        );
+        Ok(last_seq)
+    }
+
+    /// Reescribir el WAL sin los eventos más antiguos hasta caber en `max_bytes`
+    fn evict_oldest(&self) -> Result<(), AuditError> {
+        let lines = self.read_lines()?;
+        let mut total: u64 = lines.iter().map(|l| l.len() as u64 + 1).sum();
+        let mut skip = 0;
+        while total > self.max_bytes && skip < lines.len() {
+            total -= lines[skip].len() as u64 + 1;
+            skip += 1;
+        }
+
+        self.rewrite_lines(&lines[skip..])?;
+
+        self.evicted_events
+            .fetch_add(skip as u64, Ordering::Relaxed);
+        warn!("Offline audit buffer full, evicted {} oldest events", skip);
+        Ok(())
+    }
+
+    fn rewrite(&self, records: &[WalRecord]) -> Result<(), AuditError> {
+        let lines = records
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.rewrite_lines(&lines)
+    }
+
+    /// Sustituir el WAL de forma atómica
+    fn rewrite_lines(&self, lines: &[String]) -> Result<(), AuditError> {
+        let tmp_path = self.path.with_extension("tmp");
+        {
+            let mut tmp = File::create(&tmp_path).map_err(io_error)?;
+            for line in lines {
+                tmp.write_all(line.as_bytes()).map_err(io_error)?;
+                tmp.write_all(b"\n").map_err(io_error)?;
+            }
+            tmp.sync_data().map_err(io_error)?;
+        }
+        std::fs::rename(&tmp_path, &self.path).map_err(io_error)
+    }
+
+    /// Registros del WAL; tras `open` una línea ilegible es un error
+    fn read_records(&self) -> Result<Vec<WalRecord>, AuditError> {
+        self.read_lines()?
+            .iter()
+            .map(|line| serde_json::from_str(line).map_err(AuditError::from))
+            .collect()
+    }
+
+    fn read_lines(&self) -> Result<Vec<String>, AuditError> {
+        let file = File::open(&self.path).map_err(io_error)?;
+        BufReader::new(file)
+            .lines()
+            .filter(|line| !matches!(line, Ok(l) if l.is_empty()))
+            .collect::<Result<_, _>>()
+            .map_err(io_error)
+    }
+}
+
+fn io_error(e: std::io::Error) -> AuditError {
+    AuditError::RequestError(format!("Offline buffer I/O error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str) -> AuditEvent {
+        AuditEvent {
+            event_name: name.to_string(),
+            ..AuditEvent::default()
+        }
+    }
+
+    fn names(records: Vec<WalRecord>) -> Vec<String> {
+        records.into_iter().map(|r| r.event.event_name).collect()
+    }
+
+    fn open(path: PathBuf) -> DiskBuffer {
+        DiskBuffer::open(&OfflineBufferConfig {
+            path,
+            max_bytes: 1024 * 1024,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_disk_buffer_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = open(dir.path().join("audit.wal"));
+
+        assert_eq!(
+            buffer.append(&[event("a"), event("b")]).unwrap(),
+            vec![0, 1]
+        );
+        assert_eq!(buffer.append(&[event("c")]).unwrap(), vec![2]);
+
+        assert_eq!(names(buffer.load().unwrap()), vec!["a", "b", "c"]);
+
+        buffer.remove(&[1]).unwrap();
+        assert_eq!(names(buffer.load().unwrap()), vec!["a", "c"]);
+
+        buffer.clear().unwrap();
+        assert!(buffer.is_empty().unwrap());
+    }
+
+    #[test]
+    fn test_disk_buffer_evicts_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let line_len = serde_json::to_vec(&WalRecord {
+            seq: 0,
+            event: event("e0"),
+        })
+        .unwrap()
+        .len() as u64
+            + 1;
+        let buffer = DiskBuffer::open(&OfflineBufferConfig {
+            path: dir.path().join("audit.wal"),
+            max_bytes: line_len * 3,
+        })
+        .unwrap();
+
+        for i in 0..5 {
+            buffer.append(&[event(&format!("e{}", i))]).unwrap();
+        }
+
+        assert_eq!(names(buffer.load().unwrap()), vec!["e2", "e3", "e4"]);
+        assert_eq!(buffer.evicted_events(), 2);
+        assert!(buffer.size_bytes().unwrap() <= line_len * 3);
+    }
+
+    #[test]
+    fn test_held_records_are_skipped_by_replay_until_released() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = open(dir.path().join("audit.wal"));
+
+        buffer.append(&[event("a")]).unwrap();
+        let held = buffer.append_held(&[event("b")]).unwrap();
+        assert_eq!(names(buffer.pending_replay().unwrap()), vec!["a"]);
+
+        buffer.release(&held);
+        assert_eq!(names(buffer.pending_replay().unwrap()), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_corrupt_records_are_quarantined_and_counted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.wal");
+        {
+            let buffer = open(path.clone());
+            buffer.append(&[event("a"), event("b")]).unwrap();
+        }
+        // A crash leaves a torn record at the end of the WAL
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"seq\":2,\"event\":{\"event_na\n")
+            .unwrap();
+
+        let buffer = open(path.clone());
+        assert_eq!(buffer.corrupt_records(), 1);
+        assert_eq!(names(buffer.load().unwrap()), vec!["a", "b"]);
+        assert_eq!(
+            std::fs::read_to_string(path.with_extension("corrupt"))
+                .unwrap()
+                .lines()
+                .count(),
+            1
+        );
+
+        // Sequences continue after the last readable record
+        assert_eq!(buffer.append(&[event("c")]).unwrap(), vec![2]);
+    }
+}
@@ -0,0 +1,266 @@
+//! Redacción de datos sensibles antes del envío
+//!
+//! Este módulo aplica la política de redacción configurada en
+//! `AuditSdkConfig` a headers, query params y bodies, de modo que
+//! credenciales o PII nunca lleguen al audit service.
+
+use http::HeaderMap;
+
+/// Valor que sustituye a los datos redactados
+pub const REDACTED_VALUE: &str = "***";
+
+/// Política de redacción de datos sensibles
+#[derive(Debug, Clone)]
+pub struct RedactionConfig {
+    /// Headers capturados; si está vacío se capturan todos
+    pub header_allowlist: Vec<String>,
+    /// Headers cuyo valor se enmascara siempre
+    pub header_denylist: Vec<String>,
+    /// Query params cuyo valor se enmascara
+    pub masked_query_params: Vec<String>,
+    /// Campos JSON cuyo valor se enmascara (a cualquier profundidad)
+    pub masked_fields: Vec<String>,
+    /// Capturar sin redactar los bodies que no son JSON ni formularios
+    pub capture_unredacted_bodies: bool,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            header_allowlist: Vec::new(),
+            header_denylist: [
+                "authorization",
+                "proxy-authorization",
+                "cookie",
+                "set-cookie",
+                "x-api-key",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            masked_query_params: ["token", "access_token", "api_key", "password"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            masked_fields: ["password", "secret", "token"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            capture_unredacted_bodies: false,
+        }
+    }
+}
+
+impl RedactionConfig {
+    /// Redactar headers
+    ///
+    /// Los headers fuera de la allowlist (si la hay) se descartan y los de la
+    /// denylist se sustituyen por `REDACTED_VALUE`.
+    pub fn redact_headers(
+        &self,
+        headers: &HeaderMap,
+    ) -> serde_json::Map<String, serde_json::Value> {
+        let mut redacted = serde_json::Map::new();
+
+        for (name, value) in headers {
+            let name = name.as_str();
+            if !self.header_allowlist.is_empty()
+                && !contains_ignore_case(&self.header_allowlist, name)
+            {
+                continue;
+            }
+
+            let value = if contains_ignore_case(&self.header_denylist, name) {
+                REDACTED_VALUE.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            redacted.insert(name.to_string(), serde_json::Value::String(value));
+        }
+
+        redacted
+    }
+
+    /// Enmascarar los query params configurados
+    pub fn redact_query(&self, query: &str) -> String {
+        query
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((key, _)) if contains_ignore_case(&self.masked_query_params, key) => {
+                    format!("{}={}", key, REDACTED_VALUE)
+                }
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Redactar un body capturado según su MIME type
+    ///
+    /// Los bodies JSON (`application/json` o `+json`) se redactan por campos y
+    /// los formularios `application/x-www-form-urlencoded` por clave, con
+    /// `masked_fields` y `masked_query_params`. El resto no se puede redactar
+    /// y se omite (`None`) salvo con `capture_unredacted_bodies`; un JSON que
+    /// no se puede parsear se omite siempre.
+    pub fn redact_body(&self, mime: &str, body: &[u8]) -> Option<Vec<u8>> {
+        if mime == "application/json" || mime.ends_with("+json") {
+            let mut value = serde_json::from_slice::<serde_json::Value>(body).ok()?;
+            self.redact_value(&mut value);
+            return serde_json::to_vec(&value).ok();
+        }
+
+        if mime == "application/x-www-form-urlencoded" {
+            let form = String::from_utf8_lossy(body);
+            return Some(self.redact_form(&form).into_bytes());
+        }
+
+        self.capture_unredacted_bodies.then(|| body.to_vec())
+    }
+
+    /// Enmascarar los campos de un formulario urlencoded
+    fn redact_form(&self, form: &str) -> String {
+        form.split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((key, _))
+                    if contains_ignore_case(&self.masked_fields, key)
+                        || contains_ignore_case(&self.masked_query_params, key) =>
+                {
+                    format!("{}={}", key, REDACTED_VALUE)
+                }
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Enmascarar los campos configurados dentro de un valor JSON
+    pub fn redact_value(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    if contains_ignore_case(&self.masked_fields, key) {
+                        *field = serde_json::Value::String(REDACTED_VALUE.to_string());
+                    } else {
+                        self.redact_value(field);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.redact_value(item);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn contains_ignore_case(list: &[String], value: &str) -> bool {
+    list.iter().any(|entry| entry.eq_ignore_ascii_case(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_headers_masks_authorization() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer secret-token".parse().unwrap());
+        headers.insert("cookie", "session=abc".parse().unwrap());
+        headers.insert("accept", "application/json".parse().unwrap());
+
+        let redacted = RedactionConfig::default().redact_headers(&headers);
+
+        assert_eq!(redacted["authorization"], REDACTED_VALUE);
+        assert_eq!(redacted["cookie"], REDACTED_VALUE);
+        assert_eq!(redacted["accept"], "application/json");
+    }
+
+    #[test]
+    fn test_redact_headers_allowlist() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer secret-token".parse().unwrap());
+        headers.insert("accept", "application/json".parse().unwrap());
+        headers.insert("x-internal", "debug".parse().unwrap());
+
+        let config = RedactionConfig {
+            header_allowlist: vec!["Accept".to_string(), "Authorization".to_string()],
+            ..Default::default()
+        };
+        let redacted = config.redact_headers(&headers);
+
+        assert_eq!(redacted.len(), 2);
+        assert_eq!(redacted["authorization"], REDACTED_VALUE);
+        assert!(!redacted.contains_key("x-internal"));
+    }
+
+    #[test]
+    fn test_redact_query_masks_configured_params() {
+        let config = RedactionConfig {
+            masked_query_params: vec!["ssn".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.redact_query("page=2&ssn=123-45-6789&flag"),
+            "page=2&ssn=***&flag"
+        );
+    }
+
+    #[test]
+    fn test_redact_value_nested_fields() {
+        let mut value = serde_json::json!({
+            "user": {"name": "jane", "password": "hunter2"},
+            "items": [{"token": "abc"}],
+        });
+
+        RedactionConfig::default().redact_value(&mut value);
+
+        assert_eq!(value["user"]["name"], "jane");
+        assert_eq!(value["user"]["password"], REDACTED_VALUE);
+        assert_eq!(value["items"][0]["token"], REDACTED_VALUE);
+    }
+
+    #[test]
+    fn test_redact_body_by_content_type() {
+        let config = RedactionConfig::default();
+
+        assert_eq!(
+            config
+                .redact_body("application/json", br#"{"password":"hunter2"}"#)
+                .unwrap(),
+            br#"{"password":"***"}"#
+        );
+        assert_eq!(
+            config
+                .redact_body(
+                    "application/x-www-form-urlencoded",
+                    b"user=jane&password=hunter2&api_key=k1"
+                )
+                .unwrap(),
+            b"user=jane&password=***&api_key=***"
+        );
+
+        // Bodies that cannot be redacted are omitted by default
+        assert!(
+            config
+                .redact_body("text/plain", b"password=hunter2")
+                .is_none()
+        );
+        assert!(
+            config
+                .redact_body("application/json", b"password=hunter2")
+                .is_none()
+        );
+
+        let opted_in = RedactionConfig {
+            capture_unredacted_bodies: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            opted_in.redact_body("text/plain", b"hello").unwrap(),
+            b"hello"
+        );
+    }
+}
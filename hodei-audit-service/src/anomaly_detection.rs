@@ -0,0 +1,303 @@
+//! Ingest Rate Anomaly Detection
+//!
+//! Keeps a per-tenant baseline of the audit event rate and flags samples
+//! that deviate from it by more than a configured z-score. A spike may point
+//! to an attack or a runaway client; a drop usually means a broken
+//! integration that stopped sending events.
+//!
+//! The baseline is an exponentially weighted moving average (EWMA) of the
+//! rate together with an exponentially weighted variance. Rates are derived
+//! from the `received` counters `AuditMetrics` already collects on ingest.
+
+use crate::metrics::AuditMetrics;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// Name of the event and metric emitted for every detected anomaly
+pub const ANOMALY_METRIC_NAME: &str = "hodei_audit_anomaly_detected";
+
+/// Anomaly detector configuration
+#[derive(Debug, Clone)]
+pub struct AnomalyDetectorConfig {
+    /// EWMA smoothing factor in (0, 1]; higher values adapt faster
+    pub alpha: f64,
+    /// Absolute z-score above which a sample is anomalous
+    pub z_threshold: f64,
+    /// Samples needed before a tenant's baseline is trusted
+    pub warmup_samples: u32,
+    /// Lower bound for the standard deviation (events/sec), so a perfectly
+    /// flat baseline does not turn tiny jitter into huge z-scores
+    pub min_stddev: f64,
+}
+
+impl Default for AnomalyDetectorConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.1,
+            z_threshold: 3.0,
+            warmup_samples: 10,
+            min_stddev: 1.0,
+        }
+    }
+}
+
+/// Direction of an anomaly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// Rate well above the baseline
+    Spike,
+    /// Rate well below the baseline
+    Drop,
+}
+
+impl AnomalyKind {
+    /// Label value used in logs and metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnomalyKind::Spike => "spike",
+            AnomalyKind::Drop => "drop",
+        }
+    }
+}
+
+/// A sample flagged as anomalous
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    pub tenant_id: String,
+    pub kind: AnomalyKind,
+    /// Observed rate in events/sec
+    pub rate: f64,
+    /// Baseline mean at the time of the sample
+    pub baseline: f64,
+    /// Baseline standard deviation used for the z-score
+    pub stddev: f64,
+    pub z_score: f64,
+}
+
+/// Rolling baseline of one tenant
+#[derive(Debug, Clone, Default)]
+struct TenantBaseline {
+    mean: f64,
+    variance: f64,
+    samples: u32,
+}
+
+impl TenantBaseline {
+    /// Fold a sample into the EWMA mean and variance
+    fn update(&mut self, rate: f64, alpha: f64) {
+        if self.samples == 0 {
+            self.mean = rate;
+            self.variance = 0.0;
+        } else {
+            let diff = rate - self.mean;
+            let increment = alpha * diff;
+            self.mean += increment;
+            self.variance = (1.0 - alpha) * (self.variance + diff * increment);
+        }
+        self.samples = self.samples.saturating_add(1);
+    }
+}
+
+/// Per-tenant ingest rate anomaly detector
+#[derive(Debug, Default)]
+pub struct AnomalyDetector {
+    config: AnomalyDetectorConfig,
+    baselines: HashMap<String, TenantBaseline>,
+    /// `received` totals per tenant at the previous metrics sample
+    last_counts: HashMap<String, u64>,
+}
+
+impl AnomalyDetector {
+    /// Create a new detector
+    pub fn new(config: AnomalyDetectorConfig) -> Self {
+        Self {
+            config,
+            baselines: HashMap::new(),
+            last_counts: HashMap::new(),
+        }
+    }
+
+    /// Observe a tenant's rate (events/sec) and check it against its baseline
+    ///
+    /// Anomalous samples are not folded into the baseline, so a sustained
+    /// spike or outage keeps being reported instead of becoming the norm.
+    pub fn observe(&mut self, tenant_id: &str, rate: f64) -> Option<Anomaly> {
+        let config = &self.config;
+        let baseline = self.baselines.entry(tenant_id.to_string()).or_default();
+
+        if baseline.samples >= config.warmup_samples {
+            let stddev = baseline.variance.sqrt().max(config.min_stddev);
+            let z_score = (rate - baseline.mean) / stddev;
+            if z_score.abs() > config.z_threshold {
+                return Some(Anomaly {
+                    tenant_id: tenant_id.to_string(),
+                    kind: if z_score > 0.0 {
+                        AnomalyKind::Spike
+                    } else {
+                        AnomalyKind::Drop
+                    },
+                    rate,
+                    baseline: baseline.mean,
+                    stddev,
+                    z_score,
+                });
+            }
+        }
+
+        baseline.update(rate, config.alpha);
+        None
+    }
+
+    /// Current baseline (mean, stddev) of a tenant
+    pub fn baseline(&self, tenant_id: &str) -> Option<(f64, f64)> {
+        self.baselines
+            .get(tenant_id)
+            .map(|b| (b.mean, b.variance.sqrt()))
+    }
+
+    /// Sample the ingest counters collected since the previous call
+    ///
+    /// `elapsed` is the time since the previous sample. The first call only
+    /// records the counters. Detected anomalies are logged under
+    /// [`ANOMALY_METRIC_NAME`] and counted in `metrics`.
+    pub fn sample_metrics(
+        &mut self,
+        metrics: &mut AuditMetrics,
+        elapsed: Duration,
+    ) -> Vec<Anomaly> {
+        let mut totals: HashMap<String, u64> = HashMap::new();
+        for (labels, counters) in &metrics.events {
+            *totals.entry(labels.tenant_id.clone()).or_default() += counters.received;
+        }
+
+        let seconds = elapsed.as_secs_f64();
+        let mut anomalies = Vec::new();
+        for (tenant_id, total) in &totals {
+            let Some(previous) = self.last_counts.insert(tenant_id.clone(), *total) else {
+                continue;
+            };
+            if seconds <= 0.0 {
+                continue;
+            }
+
+            let rate = total.saturating_sub(previous) as f64 / seconds;
+            if let Some(anomaly) = self.observe(tenant_id, rate) {
+                warn!(
+                    target: "hodei_audit_anomaly_detected",
+                    "[{}] tenant={} kind={} rate={:.2}/s baseline={:.2}/s z={:.2}",
+                    ANOMALY_METRIC_NAME,
+                    anomaly.tenant_id,
+                    anomaly.kind.as_str(),
+                    anomaly.rate,
+                    anomaly.baseline,
+                    anomaly.z_score
+                );
+                metrics.record_anomaly(&anomaly.tenant_id, anomaly.kind.as_str());
+                anomalies.push(anomaly);
+            }
+        }
+        anomalies
+    }
+
+    /// Sample `metrics` every `interval` until `shutdown` resolves
+    ///
+    /// Returns the number of anomalies detected.
+    pub async fn run(
+        &mut self,
+        metrics: Arc<RwLock<AuditMetrics>>,
+        interval: Duration,
+        shutdown: impl Future<Output = ()>,
+    ) -> usize {
+        let mut ticker = tokio::time::interval(interval);
+        let mut last_tick = ticker.tick().await;
+        {
+            let mut metrics = metrics.write().await;
+            self.sample_metrics(&mut metrics, Duration::ZERO);
+        }
+
+        tokio::pin!(shutdown);
+        let mut detected = 0;
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                tick = ticker.tick() => {
+                    let elapsed = tick.duration_since(last_tick);
+                    last_tick = tick;
+                    let mut metrics = metrics.write().await;
+                    detected += self.sample_metrics(&mut metrics, elapsed).len();
+                }
+            }
+        }
+        detected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warmed_up(rates: &[f64]) -> AnomalyDetector {
+        let mut detector = AnomalyDetector::new(AnomalyDetectorConfig::default());
+        for rate in rates {
+            assert!(detector.observe("tenant-a", *rate).is_none());
+        }
+        detector
+    }
+
+    #[test]
+    fn test_spike_after_steady_baseline_fires() {
+        let steady: Vec<f64> = (0..30).map(|i| 100.0 + (i % 5) as f64 - 2.0).collect();
+        let mut detector = warmed_up(&steady);
+
+        let anomaly = detector.observe("tenant-a", 400.0).unwrap();
+        assert_eq!(anomaly.kind, AnomalyKind::Spike);
+        assert!(anomaly.z_score > 3.0);
+        assert!((anomaly.baseline - 100.0).abs() < 5.0);
+
+        let drop = detector.observe("tenant-a", 0.0).unwrap();
+        assert_eq!(drop.kind, AnomalyKind::Drop);
+
+        // Other tenants have their own (still warming up) baseline
+        assert!(detector.observe("tenant-b", 400.0).is_none());
+    }
+
+    #[test]
+    fn test_jitter_within_band_does_not_fire() {
+        let steady: Vec<f64> = (0..30).map(|i| 100.0 + (i % 5) as f64 - 2.0).collect();
+        let mut detector = warmed_up(&steady);
+
+        for rate in [98.5, 101.5, 99.0, 101.0, 98.0, 102.0] {
+            assert!(detector.observe("tenant-a", rate).is_none());
+        }
+    }
+
+    #[test]
+    fn test_sample_metrics_records_anomaly() {
+        let mut detector = AnomalyDetector::new(AnomalyDetectorConfig {
+            warmup_samples: 3,
+            ..Default::default()
+        });
+        let mut metrics = AuditMetrics::new();
+        let second = Duration::from_secs(1);
+
+        detector.sample_metrics(&mut metrics, second);
+        for _ in 0..5 {
+            for _ in 0..10 {
+                metrics.increment_event("login", "tenant-a", "received");
+            }
+            assert!(detector.sample_metrics(&mut metrics, second).is_empty());
+        }
+
+        for _ in 0..200 {
+            metrics.increment_event("login", "tenant-a", "received");
+        }
+        let anomalies = detector.sample_metrics(&mut metrics, second);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].rate, 200.0);
+        assert_eq!(metrics.get_anomaly_count("tenant-a"), 1);
+    }
+}
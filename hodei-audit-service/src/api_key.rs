@@ -23,6 +23,8 @@ pub enum ApiScope {
     Admin,
     /// System monitoring
     Monitoring,
+    /// Read PII fields in clear
+    PiiRead,
 }
 
 impl std::fmt::Display for ApiScope {
@@ -34,6 +36,7 @@ impl std::fmt::Display for ApiScope {
             ApiScope::AuditQuery => write!(f, "audit:query"),
             ApiScope::Admin => write!(f, "admin"),
             ApiScope::Monitoring => write!(f, "monitoring"),
+            ApiScope::PiiRead => write!(f, "pii:read"),
         }
     }
 }
@@ -48,6 +51,7 @@ impl ApiScope {
             "audit:query" => Some(ApiScope::AuditQuery),
             "admin" => Some(ApiScope::Admin),
             "monitoring" => Some(ApiScope::Monitoring),
+            "pii:read" => Some(ApiScope::PiiRead),
             _ => None,
         }
     }
@@ -61,6 +65,7 @@ impl ApiScope {
             ApiScope::AuditQuery,
             ApiScope::Admin,
             ApiScope::Monitoring,
+            ApiScope::PiiRead,
         ]
     }
 }
@@ -507,7 +512,7 @@ mod tests {
     #[test]
     fn test_api_scope_all() {
         let scopes = ApiScope::all();
-        assert_eq!(scopes.len(), 6);
+        assert_eq!(scopes.len(), 7);
         assert!(scopes.contains(&ApiScope::AuditRead));
         assert!(scopes.contains(&ApiScope::AuditWrite));
         assert!(scopes.contains(&ApiScope::CryptoVerify));
@@ -5,11 +5,13 @@
 //! - I/O driver configuration
 //! - Task scheduling optimization
 //! - TCP/UDP performance tuning
+//! - Adaptive (AIMD) concurrency limiting for batched tasks
 
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::RwLock;
-use tracing::info;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
 /// Async I/O configuration
 #[derive(Debug, Clone)]
@@ -26,6 +28,8 @@ pub struct AsyncIoConfig {
     pub enable_gather: bool,
     /// Enable pin-projected operations
     pub enable_pin_project: bool,
+    /// Adaptive concurrency limit for batched task execution
+    pub concurrency: ConcurrencyLimitConfig,
 }
 
 impl Default for AsyncIoConfig {
@@ -37,10 +41,117 @@ impl Default for AsyncIoConfig {
             use_async_std: false,
             enable_gather: true,
             enable_pin_project: true,
+            concurrency: ConcurrencyLimitConfig::default(),
         }
     }
 }
 
+/// AIMD concurrency limit configuration
+#[derive(Debug, Clone)]
+pub struct ConcurrencyLimitConfig {
+    /// Starting in-flight limit
+    pub initial_limit: usize,
+    /// Lower bound for the limit
+    pub min_limit: usize,
+    /// Upper bound for the limit
+    pub max_limit: usize,
+    /// Limit increase after each task completing with stable latency
+    pub additive_increase: usize,
+    /// Factor applied to the limit when latency rises
+    pub decrease_factor: f64,
+    /// Latency above `baseline * latency_tolerance` counts as rising
+    pub latency_tolerance: f64,
+}
+
+impl Default for ConcurrencyLimitConfig {
+    fn default() -> Self {
+        Self {
+            initial_limit: 16,
+            min_limit: 1,
+            max_limit: 512,
+            additive_increase: 1,
+            decrease_factor: 0.5,
+            latency_tolerance: 2.0,
+        }
+    }
+}
+
+/// AIMD (additive-increase/multiplicative-decrease) concurrency controller
+///
+/// The baseline is the lowest task latency observed. Each completion with a
+/// latency within tolerance of the baseline raises the limit by
+/// `additive_increase`; a completion above it multiplies the limit by
+/// `decrease_factor`.
+#[derive(Debug)]
+pub struct AimdConcurrencyLimit {
+    config: ConcurrencyLimitConfig,
+    state: Mutex<AimdState>,
+}
+
+#[derive(Debug)]
+struct AimdState {
+    limit: usize,
+    baseline: Option<Duration>,
+}
+
+impl AimdConcurrencyLimit {
+    /// Create a new controller
+    pub fn new(config: ConcurrencyLimitConfig) -> Self {
+        let min_limit = config.min_limit.max(1);
+        let limit = config
+            .initial_limit
+            .clamp(min_limit, config.max_limit.max(min_limit));
+        Self {
+            config,
+            state: Mutex::new(AimdState {
+                limit,
+                baseline: None,
+            }),
+        }
+    }
+
+    /// Current in-flight limit
+    pub fn limit(&self) -> usize {
+        self.state.lock().unwrap().limit
+    }
+
+    /// Lowest latency observed so far
+    pub fn baseline_latency(&self) -> Option<Duration> {
+        self.state.lock().unwrap().baseline
+    }
+
+    /// Feed the latency of a completed task and return the new limit
+    pub fn record_latency(&self, latency: Duration) -> usize {
+        let min_limit = self.config.min_limit.max(1);
+        let max_limit = self.config.max_limit.max(min_limit);
+        let mut state = self.state.lock().unwrap();
+
+        let baseline = match state.baseline {
+            Some(baseline) if baseline <= latency => baseline,
+            _ => latency,
+        };
+        state.baseline = Some(baseline);
+
+        let threshold = baseline.mul_f64(self.config.latency_tolerance.max(1.0));
+        let previous = state.limit;
+        state.limit = if latency > threshold {
+            ((previous as f64 * self.config.decrease_factor) as usize).max(min_limit)
+        } else {
+            (previous + self.config.additive_increase).min(max_limit)
+        };
+
+        if state.limit < previous {
+            debug!(
+                latency_ms = latency.as_millis() as u64,
+                baseline_ms = baseline.as_millis() as u64,
+                limit = state.limit,
+                "[AsyncIo] Latency rising, reducing concurrency limit"
+            );
+        }
+        state.limit
+    }
+}
+
 /// Async I/O optimizer
 pub struct AsyncIoOptimizer {
     config: AsyncIoConfig,
@@ -112,19 +223,34 @@ impl AsyncIoOptimizer {
 
     /// Get batched task executor
     pub fn create_batched_executor(&self) -> BatchedTaskExecutor {
-        BatchedTaskExecutor::new(self.config.task_batch_size)
+        BatchedTaskExecutor::with_concurrency(
+            self.config.task_batch_size,
+            self.config.concurrency.clone(),
+        )
     }
 }
 
 /// Batched task executor for efficient task scheduling
+///
+/// Tasks run concurrently up to an AIMD-controlled in-flight limit that
+/// adapts to the latency of completed tasks.
 pub struct BatchedTaskExecutor {
     batch_size: usize,
+    concurrency: Arc<AimdConcurrencyLimit>,
 }
 
 impl BatchedTaskExecutor {
     /// Create a new batched executor
     pub fn new(batch_size: usize) -> Self {
-        Self { batch_size }
+        Self::with_concurrency(batch_size, ConcurrencyLimitConfig::default())
+    }
+
+    /// Create a new batched executor with a custom concurrency limit
+    pub fn with_concurrency(batch_size: usize, concurrency: ConcurrencyLimitConfig) -> Self {
+        Self {
+            batch_size,
+            concurrency: Arc::new(AimdConcurrencyLimit::new(concurrency)),
+        }
     }
 
     /// Get batch size
@@ -132,20 +258,49 @@ impl BatchedTaskExecutor {
         self.batch_size
     }
 
-    /// Mark as deprecated - will be implemented in future
+    /// Current in-flight task limit
+    pub fn concurrency_limit(&self) -> usize {
+        self.concurrency.limit()
+    }
+
+    /// Get the concurrency controller
+    pub fn concurrency(&self) -> &Arc<AimdConcurrencyLimit> {
+        &self.concurrency
+    }
+
+    /// Execute tasks concurrently, keeping at most `concurrency_limit` in flight
+    ///
+    /// Results are returned in the order of `tasks`.
     pub async fn execute_batch<T, Fut>(&self, tasks: Vec<Fut>) -> Vec<T>
     where
         Fut: std::future::Future<Output = T> + Send,
         T: Send,
     {
-        // Simple sequential execution for now
-        // TODO: Implement parallel batching with tokio::spawn
-        let mut results = Vec::new();
-        for fut in tasks {
-            let result = fut.await;
-            results.push(result);
+        let total = tasks.len();
+        let mut pending = tasks.into_iter().enumerate();
+        let mut in_flight = FuturesUnordered::new();
+        let mut results: Vec<Option<T>> = (0..total).map(|_| None).collect();
+
+        loop {
+            while in_flight.len() < self.concurrency.limit() {
+                let Some((index, task)) = pending.next() else {
+                    break;
+                };
+                in_flight.push(async move {
+                    let start = Instant::now();
+                    let result = task.await;
+                    (index, result, start.elapsed())
+                });
+            }
+
+            let Some((index, result, latency)) = in_flight.next().await else {
+                break;
+            };
+            self.concurrency.record_latency(latency);
+            results[index] = Some(result);
         }
-        results
+
+        results.into_iter().flatten().collect()
     }
 }
 
@@ -158,6 +313,10 @@ pub struct AsyncMemoryPoolConfig {
     pub initial_allocation: usize,
     /// Growth factor
     pub growth_factor: f64,
+    /// Outstanding (not returned) buffers tolerated before suspecting a leak
+    pub leak_threshold: usize,
+    /// Time the outstanding count must stay above the threshold to warn
+    pub leak_grace_period: Duration,
 }
 
 impl Default for AsyncMemoryPoolConfig {
@@ -166,14 +325,23 @@ impl Default for AsyncMemoryPoolConfig {
             pool_size: 1000,
             initial_allocation: 1024,
             growth_factor: 1.5,
+            leak_threshold: 1000,
+            leak_grace_period: Duration::from_secs(30),
         }
     }
 }
 
 /// Async memory pool for reducing allocations
+///
+/// Buffers handed out by `acquire` return to the pool when their guard is
+/// dropped. Buffers that are never returned are tracked as outstanding and
+/// reported by `check_leaks`.
 pub struct AsyncMemoryPool {
     config: AsyncMemoryPoolConfig,
-    pool: Arc<RwLock<Vec<Vec<u8>>>>,
+    pool: Arc<Mutex<Vec<Vec<u8>>>>,
+    outstanding: Arc<AtomicUsize>,
+    over_threshold_since: Mutex<Option<Instant>>,
+    leak_warnings: AtomicU64,
 }
 
 impl AsyncMemoryPool {
@@ -181,32 +349,159 @@ impl AsyncMemoryPool {
     pub fn new(config: AsyncMemoryPoolConfig) -> Self {
         Self {
             config,
-            pool: Arc::new(RwLock::new(Vec::new())),
+            pool: Arc::new(Mutex::new(Vec::new())),
+            outstanding: Arc::new(AtomicUsize::new(0)),
+            over_threshold_since: Mutex::new(None),
+            leak_warnings: AtomicU64::new(0),
         }
     }
 
-    /// Allocate a buffer
-    pub async fn allocate(&self, size: usize) -> Vec<u8> {
+    /// Take a pooled buffer of `size` zeroed bytes, or allocate a new one
+    fn take(&self, size: usize) -> Vec<u8> {
+        self.outstanding.fetch_add(1, Ordering::Relaxed);
+
         // Try to get from pool first
-        {
-            let mut pool = self.pool.write().await;
-            if let Some(index) = pool.iter().position(|buf| buf.len() >= size) {
-                return pool.remove(index);
+        let pooled = {
+            let mut pool = self.pool.lock().unwrap();
+            pool.iter()
+                .position(|buf| buf.capacity() >= size)
+                .map(|index| pool.swap_remove(index))
+        };
+
+        match pooled {
+            Some(mut buffer) => {
+                buffer.resize(size, 0);
+                buffer
             }
+            // Allocate new buffer
+            None => vec![0; size],
         }
+    }
 
-        // Allocate new buffer
-        vec![0; size]
+    /// Allocate a buffer
+    ///
+    /// The buffer counts as outstanding until passed to `deallocate`; prefer
+    /// `acquire`, which returns it automatically.
+    pub async fn allocate(&self, size: usize) -> Vec<u8> {
+        self.take(size)
     }
 
     /// Deallocate a buffer
-    pub async fn deallocate(&self, mut buffer: Vec<u8>) {
-        {
-            let mut pool = self.pool.write().await;
-            if pool.len() < self.config.pool_size {
-                buffer.clear();
-                pool.push(buffer);
-            }
+    pub async fn deallocate(&self, buffer: Vec<u8>) {
+        release(&self.pool, &self.outstanding, self.config.pool_size, buffer);
+    }
+
+    /// Acquire a buffer that returns to the pool when the guard is dropped
+    pub async fn acquire(&self, size: usize) -> PooledBuffer {
+        let buffer = self.take(size);
+        self.check_leaks();
+        PooledBuffer {
+            buffer: Some(buffer),
+            pool: self.pool.clone(),
+            outstanding: self.outstanding.clone(),
+            pool_size: self.config.pool_size,
+        }
+    }
+
+    /// Buffers handed out and not returned yet
+    pub fn outstanding(&self) -> usize {
+        self.outstanding.load(Ordering::Relaxed)
+    }
+
+    /// Buffers currently available in the pool
+    pub fn available(&self) -> usize {
+        self.pool.lock().unwrap().len()
+    }
+
+    /// Number of leak warnings emitted
+    pub fn leak_warnings(&self) -> u64 {
+        self.leak_warnings.load(Ordering::Relaxed)
+    }
+
+    /// Warn when outstanding buffers stay above `leak_threshold` for longer
+    /// than `leak_grace_period`
+    ///
+    /// Returns `true` when a leak warning was emitted.
+    pub fn check_leaks(&self) -> bool {
+        let outstanding = self.outstanding();
+        let mut since = self.over_threshold_since.lock().unwrap();
+
+        if outstanding <= self.config.leak_threshold {
+            *since = None;
+            return false;
+        }
+
+        let first_seen = *since.get_or_insert_with(Instant::now);
+        if first_seen.elapsed() < self.config.leak_grace_period {
+            return false;
+        }
+
+        // Warn once per grace period while the condition persists
+        *since = Some(Instant::now());
+        self.leak_warnings.fetch_add(1, Ordering::Relaxed);
+        warn!(
+            outstanding = outstanding,
+            threshold = self.config.leak_threshold,
+            grace_period_ms = self.config.leak_grace_period.as_millis() as u64,
+            "[AsyncIo] Memory pool buffers not returned, possible leak"
+        );
+        true
+    }
+}
+
+/// Return a buffer to the pool, dropping it if the pool is full
+fn release(
+    pool: &Mutex<Vec<Vec<u8>>>,
+    outstanding: &AtomicUsize,
+    pool_size: usize,
+    mut buffer: Vec<u8>,
+) {
+    // Saturating: buffers from elsewhere may be handed back too
+    let _ = outstanding.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+
+    let mut pool = pool.lock().unwrap();
+    if pool.len() < pool_size {
+        buffer.clear();
+        pool.push(buffer);
+    }
+}
+
+/// Buffer borrowed from an `AsyncMemoryPool`, returned to it on drop
+pub struct PooledBuffer {
+    buffer: Option<Vec<u8>>,
+    pool: Arc<Mutex<Vec<Vec<u8>>>>,
+    outstanding: Arc<AtomicUsize>,
+    pool_size: usize,
+}
+
+impl PooledBuffer {
+    /// Take the buffer out of the pool for good
+    pub fn into_inner(mut self) -> Vec<u8> {
+        let _ = self
+            .outstanding
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+        self.buffer.take().unwrap_or_default()
+    }
+}
+
+impl std::ops::Deref for PooledBuffer {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        self.buffer.as_ref().expect("buffer present until drop")
+    }
+}
+
+impl std::ops::DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.buffer.as_mut().expect("buffer present until drop")
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            release(&self.pool, &self.outstanding, self.pool_size, buffer);
         }
     }
 }
@@ -229,4 +524,128 @@ mod tests {
         let executor = BatchedTaskExecutor::new(10);
         assert_eq!(executor.batch_size(), 10);
     }
+
+    #[tokio::test]
+    async fn test_dropped_guard_returns_buffer_to_pool() {
+        let pool = AsyncMemoryPool::new(AsyncMemoryPoolConfig::default());
+
+        {
+            let mut buffer = pool.acquire(256).await;
+            assert_eq!(buffer.len(), 256);
+            buffer[0] = 42;
+            assert_eq!(pool.outstanding(), 1);
+            assert_eq!(pool.available(), 0);
+        }
+
+        assert_eq!(pool.outstanding(), 0);
+        assert_eq!(pool.available(), 1);
+
+        // The returned allocation is reused, zeroed
+        let buffer = pool.acquire(128).await;
+        assert_eq!(pool.available(), 0);
+        assert_eq!(buffer.len(), 128);
+        assert!(buffer.iter().all(|b| *b == 0));
+        assert!(buffer.capacity() >= 256);
+    }
+
+    #[tokio::test]
+    async fn test_forgotten_buffers_trigger_leak_warning() {
+        let pool = AsyncMemoryPool::new(AsyncMemoryPoolConfig {
+            leak_threshold: 2,
+            leak_grace_period: Duration::from_millis(20),
+            ..Default::default()
+        });
+
+        for _ in 0..3 {
+            std::mem::forget(pool.acquire(64).await);
+        }
+        assert_eq!(pool.outstanding(), 3);
+
+        // Above the threshold, but still within the grace period
+        assert!(!pool.check_leaks());
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(pool.check_leaks());
+        assert_eq!(pool.leak_warnings(), 1);
+
+        // Returning buffers clears the condition
+        pool.deallocate(Vec::with_capacity(64)).await;
+        assert_eq!(pool.outstanding(), 2);
+        assert!(!pool.check_leaks());
+    }
+
+    #[test]
+    fn test_aimd_limit_backs_off_and_recovers() {
+        let limit = AimdConcurrencyLimit::new(ConcurrencyLimitConfig {
+            initial_limit: 8,
+            max_limit: 64,
+            ..Default::default()
+        });
+
+        // Stable latency: additive increase
+        for _ in 0..8 {
+            limit.record_latency(Duration::from_millis(10));
+        }
+        assert_eq!(limit.limit(), 16);
+
+        // Backend slows down: multiplicative decrease
+        limit.record_latency(Duration::from_millis(30));
+        assert_eq!(limit.limit(), 8);
+        limit.record_latency(Duration::from_millis(50));
+        assert_eq!(limit.limit(), 4);
+
+        // Latency back to normal: recovers
+        for _ in 0..4 {
+            limit.record_latency(Duration::from_millis(11));
+        }
+        assert_eq!(limit.limit(), 8);
+        assert_eq!(limit.baseline_latency(), Some(Duration::from_millis(10)));
+    }
+
+    #[tokio::test]
+    async fn test_executor_adapts_limit_to_backend_latency() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let executor = BatchedTaskExecutor::with_concurrency(
+            64,
+            ConcurrencyLimitConfig {
+                initial_limit: 4,
+                max_limit: 32,
+                latency_tolerance: 3.0,
+                ..Default::default()
+            },
+        );
+        let backend_latency_ms = Arc::new(AtomicU64::new(5));
+        let batch = |n: usize| {
+            (0..n)
+                .map(|i| {
+                    let latency = backend_latency_ms.clone();
+                    async move {
+                        let ms = latency.load(Ordering::Relaxed);
+                        tokio::time::sleep(Duration::from_millis(ms)).await;
+                        i
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let results = executor.execute_batch(batch(20)).await;
+        assert_eq!(results, (0..20).collect::<Vec<_>>());
+        let stable_limit = executor.concurrency_limit();
+        assert!(stable_limit > 4, "limit should grow, got {}", stable_limit);
+
+        backend_latency_ms.store(60, Ordering::Relaxed);
+        executor.execute_batch(batch(4)).await;
+        let degraded_limit = executor.concurrency_limit();
+        assert!(
+            degraded_limit < stable_limit,
+            "limit should drop: {} -> {}",
+            stable_limit,
+            degraded_limit
+        );
+
+        backend_latency_ms.store(5, Ordering::Relaxed);
+        executor.execute_batch(batch(20)).await;
+        assert!(executor.concurrency_limit() > degraded_limit);
+    }
 }
@@ -0,0 +1,342 @@
+//! Hot Tier Backfill
+//!
+//! Re-ingests events from warm storage (S3 Parquet objects) into ClickHouse
+//! after the hot tier has been rebuilt. A backfill covers one tenant and a
+//! date range, skips events already present by `event_id` so it can be
+//! re-run safely, and paces inserts to stay under a configured rate.
+
+use crate::clickhouse::ClickHouseClient;
+use crate::s3_storage::S3Client;
+use chrono::NaiveDate;
+use hodei_audit_proto::AuditEvent;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::info;
+
+/// Backfill configuration
+#[derive(Debug, Clone)]
+pub struct BackfillConfig {
+    /// Events per ClickHouse insert
+    pub batch_size: usize,
+    /// Upper bound on inserted events per second (None = unthrottled)
+    pub max_events_per_second: Option<u32>,
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 10_000,
+            max_events_per_second: Some(50_000),
+        }
+    }
+}
+
+/// Tenant and date range to backfill (both dates inclusive)
+#[derive(Debug, Clone)]
+pub struct BackfillRequest {
+    pub tenant_id: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+/// Progress of a running backfill; the final value is its report
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackfillProgress {
+    /// Parquet objects matching the request
+    pub objects_total: usize,
+    /// Objects fully processed
+    pub objects_processed: usize,
+    /// Events read from the objects within the date range
+    pub events_read: u64,
+    /// Events inserted into ClickHouse
+    pub events_inserted: u64,
+    /// Events skipped because they were already present
+    pub events_skipped: u64,
+}
+
+/// Backfill from S3 into ClickHouse
+pub struct Backfill {
+    s3: Arc<S3Client>,
+    clickhouse: Arc<ClickHouseClient>,
+    config: BackfillConfig,
+    progress: watch::Sender<BackfillProgress>,
+}
+
+impl Backfill {
+    /// Create a new backfill
+    pub fn new(
+        s3: Arc<S3Client>,
+        clickhouse: Arc<ClickHouseClient>,
+        config: BackfillConfig,
+    ) -> Self {
+        let (progress, _) = watch::channel(BackfillProgress::default());
+        Self {
+            s3,
+            clickhouse,
+            config,
+            progress,
+        }
+    }
+
+    /// Follow the progress of the current run
+    pub fn subscribe(&self) -> watch::Receiver<BackfillProgress> {
+        self.progress.subscribe()
+    }
+
+    /// Run the backfill and return the final progress
+    pub async fn run(&self, request: &BackfillRequest) -> Result<BackfillProgress, anyhow::Error> {
+        if request.start > request.end {
+            return Err(anyhow::anyhow!(
+                "Invalid backfill range: {} is after {}",
+                request.start,
+                request.end
+            ));
+        }
+
+        let objects = self.list_objects(request).await?;
+        let mut progress = BackfillProgress {
+            objects_total: objects.len(),
+            ..Default::default()
+        };
+        self.progress.send_replace(progress.clone());
+
+        info!(
+            "[Backfill] tenant={} range={}..={}: {} objects",
+            request.tenant_id,
+            request.start,
+            request.end,
+            objects.len()
+        );
+
+        let started = Instant::now();
+        for key in &objects {
+            let events: Vec<AuditEvent> = self
+                .s3
+                .read_object(key)
+                .await?
+                .into_iter()
+                .filter(|event| in_request(event, request))
+                .collect();
+            progress.events_read += events.len() as u64;
+
+            for chunk in events.chunks(self.config.batch_size.max(1)) {
+                let pending = self.missing_events(&request.tenant_id, chunk).await?;
+                progress.events_skipped += (chunk.len() - pending.len()) as u64;
+
+                if !pending.is_empty() {
+                    self.clickhouse.insert_batch(&pending).await?;
+                    progress.events_inserted += pending.len() as u64;
+                    self.throttle(started, progress.events_inserted).await;
+                }
+                self.progress.send_replace(progress.clone());
+            }
+
+            progress.objects_processed += 1;
+            self.progress.send_replace(progress.clone());
+        }
+
+        info!(
+            "[Backfill] tenant={} done: {} inserted, {} skipped",
+            request.tenant_id, progress.events_inserted, progress.events_skipped
+        );
+        Ok(progress)
+    }
+
+    /// Parquet objects of the tenant in the partitions covering the range
+    async fn list_objects(&self, request: &BackfillRequest) -> Result<Vec<String>, anyhow::Error> {
+        let tenant_segment = format!("tenant_id={}/", request.tenant_id);
+        let mut objects = BTreeSet::new();
+        for prefix in self.s3.partition_prefixes(request.start, request.end) {
+            for key in self.s3.list_objects(&prefix).await? {
+                if key.contains(&tenant_segment) {
+                    objects.insert(key);
+                }
+            }
+        }
+        Ok(objects.into_iter().collect())
+    }
+
+    /// Events of `chunk` not yet stored in ClickHouse
+    async fn missing_events(
+        &self,
+        tenant_id: &str,
+        chunk: &[AuditEvent],
+    ) -> Result<Vec<AuditEvent>, anyhow::Error> {
+        let ids: Vec<String> = chunk
+            .iter()
+            .filter_map(|event| event.event_id.as_ref().map(|id| id.value.clone()))
+            .collect();
+        let existing = self.clickhouse.existing_event_ids(tenant_id, &ids).await?;
+
+        Ok(chunk
+            .iter()
+            .filter(|event| {
+                event
+                    .event_id
+                    .as_ref()
+                    .is_none_or(|id| !existing.contains(&id.value))
+            })
+            .cloned()
+            .collect())
+    }
+
+    /// Sleep until `inserted` events fit within the configured rate
+    async fn throttle(&self, started: Instant, inserted: u64) {
+        if let Some(rate) = self.config.max_events_per_second.filter(|r| *r > 0) {
+            let earliest = started + Duration::from_secs_f64(inserted as f64 / rate as f64);
+            tokio::time::sleep_until(earliest).await;
+        }
+    }
+}
+
+/// Whether an event belongs to the requested tenant and date range
+///
+/// Events without `event_time` are kept: they were partitioned by upload
+/// time, which already placed them in a listed partition.
+fn in_request(event: &AuditEvent, request: &BackfillRequest) -> bool {
+    let tenant_matches = event
+        .tenant_id
+        .as_ref()
+        .is_some_and(|t| t.value == request.tenant_id);
+    let date = event
+        .event_time
+        .as_ref()
+        .and_then(|t| chrono::DateTime::from_timestamp(t.seconds, t.nanos.max(0) as u32))
+        .map(|t| t.date_naive());
+
+    tenant_matches && date.is_none_or(|d| d >= request.start && d <= request.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clickhouse::ClickHouseConfig;
+    use crate::s3_storage::S3Config;
+    use hodei_audit_proto::{EventId, TenantId};
+
+    fn event(id: &str, tenant: &str, date: NaiveDate) -> AuditEvent {
+        let time = date.and_hms_opt(12, 0, 0).unwrap().and_utc();
+        AuditEvent {
+            event_id: Some(EventId {
+                value: id.to_string(),
+            }),
+            tenant_id: Some(TenantId {
+                value: tenant.to_string(),
+            }),
+            action: "read".to_string(),
+            event_time: Some(prost_types::Timestamp {
+                seconds: time.timestamp(),
+                nanos: 0,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, d).unwrap()
+    }
+
+    async fn seed(s3: &S3Client, tenant: &str, date: NaiveDate, count: usize) {
+        let events: Vec<AuditEvent> = (0..count)
+            .map(|i| event(&format!("{}-{}-{}", tenant, date, i), tenant, date))
+            .collect();
+        s3.upload_batch(&events).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_backfill_reads_range_partitions_and_is_idempotent() {
+        let s3 = Arc::new(S3Client::new(S3Config::default()));
+        let clickhouse = Arc::new(ClickHouseClient::new(ClickHouseConfig::default()));
+
+        seed(&s3, "tenant-a", day(1), 3).await;
+        seed(&s3, "tenant-a", day(2), 4).await;
+        seed(&s3, "tenant-a", day(3), 5).await;
+        // Outside the range or owned by another tenant
+        seed(&s3, "tenant-a", day(5), 6).await;
+        seed(&s3, "tenant-b", day(2), 7).await;
+
+        let backfill = Backfill::new(
+            s3.clone(),
+            clickhouse.clone(),
+            BackfillConfig {
+                batch_size: 2,
+                max_events_per_second: None,
+            },
+        );
+        let progress = backfill.subscribe();
+        let request = BackfillRequest {
+            tenant_id: "tenant-a".to_string(),
+            start: day(1),
+            end: day(3),
+        };
+
+        let report = backfill.run(&request).await.unwrap();
+        assert_eq!(report.objects_total, 3);
+        assert_eq!(report.objects_processed, 3);
+        assert_eq!(report.events_read, 12);
+        assert_eq!(report.events_inserted, 12);
+        assert_eq!(report.events_skipped, 0);
+        assert_eq!(*progress.borrow(), report);
+
+        // Re-running inserts nothing new
+        let rerun = backfill.run(&request).await.unwrap();
+        assert_eq!(rerun.events_inserted, 0);
+        assert_eq!(rerun.events_skipped, 12);
+
+        let ids: Vec<String> = (0..7)
+            .map(|i| format!("tenant-b-{}-{}", day(2), i))
+            .collect();
+        assert!(
+            clickhouse
+                .existing_event_ids("tenant-b", &ids)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_backfill_honors_rate_limit() {
+        let s3 = Arc::new(S3Client::new(S3Config::default()));
+        let clickhouse = Arc::new(ClickHouseClient::new(ClickHouseConfig::default()));
+        seed(&s3, "tenant-a", day(1), 10).await;
+
+        let backfill = Backfill::new(
+            s3,
+            clickhouse,
+            BackfillConfig {
+                batch_size: 5,
+                max_events_per_second: Some(100),
+            },
+        );
+        let request = BackfillRequest {
+            tenant_id: "tenant-a".to_string(),
+            start: day(1),
+            end: day(1),
+        };
+
+        let started = std::time::Instant::now();
+        let report = backfill.run(&request).await.unwrap();
+        assert_eq!(report.events_inserted, 10);
+        // 10 events at 100/s take at least 100ms
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_backfill_rejects_inverted_range() {
+        let backfill = Backfill::new(
+            Arc::new(S3Client::new(S3Config::default())),
+            Arc::new(ClickHouseClient::new(ClickHouseConfig::default())),
+            BackfillConfig::default(),
+        );
+        let request = BackfillRequest {
+            tenant_id: "tenant-a".to_string(),
+            start: day(3),
+            end: day(1),
+        };
+        assert!(backfill.run(&request).await.is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! Bloom Filters for Event Id Lookups
+//!
+//! A storage tier keeps one bloom filter of event ids per partition. A point
+//! lookup consults the filters first and only queries a tier when some
+//! partition may contain the id: a negative answer is definitive, a positive
+//! one may be a false positive at the configured rate. This avoids paying
+//! for a cold-tier retrieval job when the event is not there.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+
+/// Default number of ids a partition filter is sized for
+pub const DEFAULT_EXPECTED_ITEMS: usize = 100_000;
+
+/// Default false positive rate of a partition filter
+pub const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// Fixed-size bloom filter over string keys
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    /// Filter sized for `expected_items` at `false_positive_rate`
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        let n = expected_items.max(1) as f64;
+        let p = false_positive_rate.clamp(1e-9, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+
+        let num_bits = ((-n * p.ln()) / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / n) * ln2).round().clamp(1.0, 32.0) as u32;
+
+        Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    /// Add a key
+    pub fn insert(&mut self, key: &str) {
+        for bit in self.bit_positions(key) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    /// `false` if the key was definitely never inserted
+    pub fn might_contain(&self, key: &str) -> bool {
+        self.bit_positions(key)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Double hashing: position i is `h1 + i * h2`
+    fn bit_positions(&self, key: &str) -> impl Iterator<Item = u64> + use<> {
+        let h1 = seeded_hash(key, 0);
+        let h2 = seeded_hash(key, 1) | 1;
+        let num_bits = self.num_bits;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+}
+
+fn seeded_hash(key: &str, seed: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// One bloom filter per partition
+#[derive(Debug)]
+pub struct PartitionedBloomFilter {
+    partitions: RwLock<HashMap<String, BloomFilter>>,
+    expected_items: usize,
+    false_positive_rate: f64,
+}
+
+impl Default for PartitionedBloomFilter {
+    fn default() -> Self {
+        Self::new(DEFAULT_EXPECTED_ITEMS, DEFAULT_FALSE_POSITIVE_RATE)
+    }
+}
+
+impl PartitionedBloomFilter {
+    /// Partition filters sized for `expected_items` at `false_positive_rate`
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        Self {
+            partitions: RwLock::new(HashMap::new()),
+            expected_items,
+            false_positive_rate,
+        }
+    }
+
+    /// Record `key` in the filter of `partition`
+    pub fn insert(&self, partition: &str, key: &str) {
+        self.partitions
+            .write()
+            .unwrap()
+            .entry(partition.to_string())
+            .or_insert_with(|| BloomFilter::new(self.expected_items, self.false_positive_rate))
+            .insert(key);
+    }
+
+    /// `false` if no partition can contain `key`
+    pub fn might_contain(&self, key: &str) -> bool {
+        self.partitions
+            .read()
+            .unwrap()
+            .values()
+            .any(|filter| filter.might_contain(key))
+    }
+
+    /// Partitions that may contain `key`
+    pub fn candidate_partitions(&self, key: &str) -> Vec<String> {
+        let mut partitions: Vec<String> = self
+            .partitions
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, filter)| filter.might_contain(key))
+            .map(|(partition, _)| partition.clone())
+            .collect();
+        partitions.sort();
+        partitions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_false_negatives_and_low_false_positive_rate() {
+        let mut filter = BloomFilter::new(1_000, 0.01);
+        for i in 0..1_000 {
+            filter.insert(&format!("evt-{}", i));
+        }
+
+        assert!((0..1_000).all(|i| filter.might_contain(&format!("evt-{}", i))));
+        let false_positives = (0..10_000)
+            .filter(|i| filter.might_contain(&format!("other-{}", i)))
+            .count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+    }
+
+    #[test]
+    fn test_partitioned_filter_reports_candidate_partitions() {
+        let filter = PartitionedBloomFilter::default();
+        filter.insert("20250301", "evt-1");
+        filter.insert("20250302", "evt-2");
+
+        assert!(filter.might_contain("evt-1"));
+        assert!(!filter.might_contain("evt-3"));
+        assert_eq!(filter.candidate_partitions("evt-2"), vec!["20250302"]);
+    }
+}
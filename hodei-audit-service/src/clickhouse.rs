@@ -2,12 +2,26 @@
 //!
 //! This module provides a robust, production-ready ClickHouse client
 //! with connection pooling, batch inserts, retry policies, and performance monitoring.
+//! Tenants that need physical isolation are routed to a dedicated table,
+//! provisioned on first insert. The table's columns and how each is filled
+//! from an `AuditEvent` are declared once, in [`AUDIT_EVENT_COLUMNS`].
 
 use hodei_audit_proto::AuditEvent;
-use std::collections::HashMap;
+use hodei_audit_retry::RetryPolicy;
+use hodei_audit_types::Outcome;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::registry::Registry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::future::Future;
 use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
 use std::time::{Duration, SystemTime};
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
+
+use crate::batch_digest::{BatchDigestSigner, BatchIntegrity, batch_digest};
+use crate::clickhouse_tuning::ClickHouseTuningConfig;
+use crate::outbound_tls::OutboundTlsConfig;
 
 /// ClickHouse client configuration
 #[derive(Debug, Clone)]
@@ -30,6 +44,71 @@ pub struct ClickHouseConfig {
     pub query_timeout_secs: u64,
     /// Enable compression
     pub enable_compression: bool,
+    /// Make inserts idempotent on `(tenant_id, event_id)`
+    ///
+    /// Rows already stored are skipped before inserting, and the schema uses
+    /// a `ReplacingMergeTree` keyed on `event_id`, so a batch retried after a
+    /// partial failure doesn't create duplicates.
+    pub dedup_on_event_id: bool,
+    /// Tenants with a dedicated table, by tenant ID; everyone else shares
+    /// `database.table`
+    pub isolated_tenants: HashMap<String, ClickHouseTable>,
+    /// TLS policy for connections; `None` connects in plain text
+    pub tls_config: Option<OutboundTlsConfig>,
+}
+
+impl ClickHouseConfig {
+    /// Give a tenant its own table, `<table>_<tenant_id>` in the same database
+    pub fn with_isolated_tenant(self, tenant_id: &str) -> Self {
+        let suffix: String = tenant_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let table =
+            ClickHouseTable::new(self.database.clone(), format!("{}_{}", self.table, suffix));
+        self.with_tenant_table(tenant_id, table)
+    }
+
+    /// Route a tenant's events to `table`
+    pub fn with_tenant_table(mut self, tenant_id: &str, table: ClickHouseTable) -> Self {
+        self.isolated_tenants.insert(tenant_id.to_string(), table);
+        self
+    }
+
+    /// Table shared by tenants without a dedicated one
+    pub fn shared_table(&self) -> ClickHouseTable {
+        ClickHouseTable::new(self.database.clone(), self.table.clone())
+    }
+
+    /// Table holding a tenant's events
+    pub fn table_for(&self, tenant_id: &str) -> ClickHouseTable {
+        self.isolated_tenants
+            .get(tenant_id)
+            .cloned()
+            .unwrap_or_else(|| self.shared_table())
+    }
+}
+
+/// Fully qualified ClickHouse table
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ClickHouseTable {
+    pub database: String,
+    pub table: String,
+}
+
+impl ClickHouseTable {
+    pub fn new(database: impl Into<String>, table: impl Into<String>) -> Self {
+        Self {
+            database: database.into(),
+            table: table.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ClickHouseTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.database, self.table)
+    }
 }
 
 impl Default for ClickHouseConfig {
@@ -44,6 +123,9 @@ impl Default for ClickHouseConfig {
             retry_delay_ms: 100,
             query_timeout_secs: 30,
             enable_compression: true,
+            dedup_on_event_id: false,
+            isolated_tenants: HashMap::new(),
+            tls_config: None,
         }
     }
 }
@@ -65,6 +147,148 @@ pub struct ClickHouseMetrics {
     pub total_retries: u64,
     /// Connection pool utilization
     pub pool_utilization: f64,
+    /// 99th percentile of recent insert latencies in milliseconds
+    pub p99_insert_latency_ms: f64,
+    /// 99th percentile of recent query latencies in milliseconds
+    pub p99_query_latency_ms: f64,
+}
+
+/// Latencies kept per operation for percentiles
+const LATENCY_SAMPLES: usize = 1024;
+
+/// Most recent insert and query latencies in milliseconds
+#[derive(Debug, Default)]
+struct LatencySamples {
+    insert: VecDeque<f64>,
+    query: VecDeque<f64>,
+}
+
+impl LatencySamples {
+    fn record(samples: &mut VecDeque<f64>, latency_ms: f64) {
+        if samples.len() == LATENCY_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(latency_ms);
+    }
+}
+
+/// 99th percentile of `samples`, 0 when empty
+fn p99(samples: &VecDeque<f64>) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let mut sorted: Vec<f64> = samples.iter().copied().collect();
+    sorted.sort_by(f64::total_cmp);
+    let rank = (sorted.len() as f64 * 0.99).ceil() as usize;
+    sorted[rank.saturating_sub(1)]
+}
+
+/// State a `ClickHouseMetrics` snapshot is taken from
+#[derive(Clone)]
+struct MetricsSource {
+    metrics: Arc<std::sync::RwLock<ClickHouseMetrics>>,
+    latencies: Arc<std::sync::Mutex<LatencySamples>>,
+    pool: Arc<ConnectionPool>,
+}
+
+impl MetricsSource {
+    fn snapshot(&self) -> ClickHouseMetrics {
+        let mut metrics = self.metrics.read().unwrap().clone();
+        metrics.pool_utilization = self.pool.get_utilization();
+        let latencies = self.latencies.lock().unwrap();
+        metrics.p99_insert_latency_ms = p99(&latencies.insert);
+        metrics.p99_query_latency_ms = p99(&latencies.query);
+        metrics
+    }
+}
+
+type LatencyLabels = Vec<(&'static str, &'static str)>;
+
+/// Prometheus series mirroring a `ClickHouseClient`'s metrics
+///
+/// Values are refreshed from the client on a timer, so they're gauges even
+/// where the underlying figure only grows: `reset_metrics` can take them
+/// back to zero. Dropping the exporter stops the refresh.
+#[derive(Debug)]
+pub struct ClickHouseMetricsExporter {
+    series: ClickHouseSeries,
+    refresh: tokio::task::JoinHandle<()>,
+}
+
+impl ClickHouseMetricsExporter {
+    /// Set every series from a metrics snapshot
+    pub fn update(&self, metrics: &ClickHouseMetrics) {
+        self.series.update(metrics);
+    }
+}
+
+impl Drop for ClickHouseMetricsExporter {
+    fn drop(&mut self) {
+        self.refresh.abort();
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ClickHouseSeries {
+    total_inserts: Gauge,
+    total_queries: Gauge,
+    failed_operations: Gauge,
+    total_retries: Gauge,
+    latency_ms: Family<LatencyLabels, Gauge<f64, AtomicU64>>,
+    pool_utilization: Gauge<f64, AtomicU64>,
+}
+
+impl ClickHouseSeries {
+    fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "hodei_clickhouse_total_inserts",
+            "Inserts performed, a batch counting as one",
+            self.total_inserts.clone(),
+        );
+        registry.register(
+            "hodei_clickhouse_total_queries",
+            "Queries performed",
+            self.total_queries.clone(),
+        );
+        registry.register(
+            "hodei_clickhouse_failed_operations",
+            "Inserts and queries that failed after every retry",
+            self.failed_operations.clone(),
+        );
+        registry.register(
+            "hodei_clickhouse_total_retries",
+            "Retried inserts and queries",
+            self.total_retries.clone(),
+        );
+        registry.register(
+            "hodei_clickhouse_latency_ms",
+            "Insert and query latency by operation and statistic",
+            self.latency_ms.clone(),
+        );
+        registry.register(
+            "hodei_clickhouse_pool_utilization",
+            "Share of pooled connections in use",
+            self.pool_utilization.clone(),
+        );
+    }
+
+    fn update(&self, metrics: &ClickHouseMetrics) {
+        self.total_inserts.set(metrics.total_inserts as i64);
+        self.total_queries.set(metrics.total_queries as i64);
+        self.failed_operations.set(metrics.failed_operations as i64);
+        self.total_retries.set(metrics.total_retries as i64);
+        for (operation, stat, value) in [
+            ("insert", "avg", metrics.avg_insert_latency_ms),
+            ("insert", "p99", metrics.p99_insert_latency_ms),
+            ("query", "avg", metrics.avg_query_latency_ms),
+            ("query", "p99", metrics.p99_query_latency_ms),
+        ] {
+            self.latency_ms
+                .get_or_create(&vec![("operation", operation), ("stat", stat)])
+                .set(value);
+        }
+        self.pool_utilization.set(metrics.pool_utilization);
+    }
 }
 
 /// Batch insert statistics
@@ -78,8 +302,34 @@ pub struct BatchStats {
     pub retries: u32,
     /// Success flag
     pub success: bool,
+    /// ID under which the batch digest is stored
+    pub batch_id: String,
+    /// Digest over the hashes of the batch events
+    pub batch_digest: String,
+}
+
+/// Row of the batch digest table
+#[derive(Debug, Clone)]
+struct BatchDigestRow {
+    digest: String,
+    signature: String,
 }
 
+/// Row of an events table, tagged with the insert batch it came in
+#[derive(Debug, Clone)]
+struct InsertedRow {
+    batch_id: String,
+    /// Position of the event in its insert batch
+    batch_offset: usize,
+    event: AuditEvent,
+}
+
+/// Columns tagging every row with its insert batch
+const BATCH_COLUMNS: [&str; 2] = [
+    "batch_id String DEFAULT ''",
+    "batch_offset UInt32 DEFAULT 0",
+];
+
 /// ClickHouse client with connection pooling and retry logic
 pub struct ClickHouseClient {
     /// Configuration
@@ -88,6 +338,26 @@ pub struct ClickHouseClient {
     pool: Arc<ConnectionPool>,
     /// Performance metrics
     metrics: Arc<std::sync::RwLock<ClickHouseMetrics>>,
+    /// Recent latencies for percentiles
+    latencies: Arc<std::sync::Mutex<LatencySamples>>,
+    /// Session settings applied to inserts
+    insert_settings: Vec<(String, String)>,
+    /// Simulated table contents: rows per (tenant_id, event_id)
+    stored_events: Arc<std::sync::RwLock<HashMap<(String, String), u64>>>,
+    /// Simulated failure: the next batch insert breaks after this many rows
+    partial_failure_after: Arc<std::sync::Mutex<Option<usize>>>,
+    /// Simulated rows returned by queries
+    query_results: Arc<std::sync::RwLock<Vec<AuditEvent>>>,
+    /// Simulated row count per table
+    table_rows: Arc<std::sync::RwLock<HashMap<ClickHouseTable, u64>>>,
+    /// Simulated batch digest table, by batch ID
+    batch_digests: Arc<std::sync::RwLock<HashMap<String, BatchDigestRow>>>,
+    /// Simulated rows of each table
+    rows: Arc<std::sync::RwLock<BTreeMap<ClickHouseTable, Vec<InsertedRow>>>>,
+    /// Signs the digest of every insert batch
+    digest_signer: BatchDigestSigner,
+    /// Provisions dedicated tenant tables on first use
+    schema: Arc<ClickHouseSchema>,
 }
 
 /// Simulated connection pool
@@ -165,175 +435,286 @@ impl ClickHouseClient {
         let metrics = Arc::new(std::sync::RwLock::new(ClickHouseMetrics::default()));
 
         info!(
-            "[ClickHouse] Initialized client: pool={}, batch_size={}, compression={}",
-            config.pool_size, config.batch_size, config.enable_compression
+            "[ClickHouse] Initialized client: pool={}, batch_size={}, compression={}, tls={:?}",
+            config.pool_size,
+            config.batch_size,
+            config.enable_compression,
+            config.tls_config.as_ref().map(|tls| tls.min_version)
         );
 
         Self {
+            schema: Arc::new(ClickHouseSchema::new(config.clone())),
             config,
             pool,
             metrics,
+            latencies: Arc::new(std::sync::Mutex::new(LatencySamples::default())),
+            insert_settings: Vec::new(),
+            stored_events: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            partial_failure_after: Arc::new(std::sync::Mutex::new(None)),
+            query_results: Arc::new(std::sync::RwLock::new(Vec::new())),
+            table_rows: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            batch_digests: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            rows: Arc::new(std::sync::RwLock::new(BTreeMap::new())),
+            digest_signer: BatchDigestSigner::generate(),
         }
     }
 
+    /// Sign batch digests with `signer`; without it a random key is used,
+    /// and batches can't be verified by another client
+    pub fn with_digest_signer(mut self, signer: BatchDigestSigner) -> Self {
+        self.digest_signer = signer;
+        self
+    }
+
+    /// Provision dedicated tenant tables with `schema`
+    pub fn with_schema(mut self, schema: ClickHouseSchema) -> Self {
+        self.schema = Arc::new(schema);
+        self
+    }
+
+    /// Schema used to provision dedicated tenant tables
+    pub fn schema(&self) -> &ClickHouseSchema {
+        &self.schema
+    }
+
+    /// Apply the memory settings of a tuning config to inserts
+    pub fn with_tuning(mut self, tuning: &ClickHouseTuningConfig) -> Self {
+        self.insert_settings = if tuning.enable_memory_optimization {
+            tuning.memory_settings.session_settings()
+        } else {
+            Vec::new()
+        };
+        self
+    }
+
+    /// INSERT statement used for batch inserts into the shared table,
+    /// including session settings
+    pub fn insert_statement(&self) -> String {
+        self.insert_statement_for(&self.config.shared_table())
+    }
+
+    /// INSERT statement used for batch inserts into `table`
+    pub fn insert_statement_for(&self, table: &ClickHouseTable) -> String {
+        let mut sql = format!("INSERT INTO {}", table);
+        if !self.insert_settings.is_empty() {
+            let settings: Vec<String> = self
+                .insert_settings
+                .iter()
+                .map(|(name, value)| format!("{} = {}", name, value))
+                .collect();
+            sql.push_str(&format!(" SETTINGS {}", settings.join(", ")));
+        }
+        sql.push_str(" FORMAT RowBinary");
+        sql
+    }
+
     /// Create with default configuration
     pub fn new_with_defaults() -> Self {
         Self::new(ClickHouseConfig::default())
     }
 
-    /// Execute a single insert with retry logic
-    pub async fn insert_event(&self, event: &AuditEvent) -> Result<(), anyhow::Error> {
-        let start_time = SystemTime::now();
+    /// Backoff between attempts: `retry_delay_ms` doubled on every retry,
+    /// `max_retries` attempts in total
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::exponential(
+            Duration::from_millis(self.config.retry_delay_ms),
+            self.config.max_retries.saturating_sub(1),
+        )
+    }
 
-        for attempt in 0..self.config.max_retries {
-            let conn = match self.pool.get_connection() {
-                Ok(conn) => conn,
-                Err(e) => {
-                    if attempt == self.config.max_retries - 1 {
-                        return Err(e);
-                    }
-                    self.retry_delay(attempt).await;
-                    continue;
-                }
-            };
-
-            // Simulate insert operation
-            let result = self.execute_insert(&conn, event).await;
-
-            match result {
-                Ok(_) => {
-                    let latency = start_time.elapsed()?.as_millis() as f64;
-                    self.update_insert_metrics(latency, false);
-                    info!(
-                        "[ClickHouse] Inserted event: {} (attempt {})",
-                        event
-                            .event_id
-                            .as_ref()
-                            .map(|e| e.value.as_str())
-                            .unwrap_or("unknown"),
-                        attempt + 1
-                    );
-                    return Ok(());
-                }
-                Err(e) => {
-                    error!(
-                        "[ClickHouse] Insert failed (attempt {}): {}",
-                        attempt + 1,
-                        e
-                    );
-                    if attempt == self.config.max_retries - 1 {
-                        self.update_error_metrics();
-                        return Err(e);
-                    }
-                    self.retry_delay(attempt).await;
-                    self.update_retry_metrics();
+    /// Run `operation` on a pooled connection under the retry policy
+    ///
+    /// Returns the result with the number of attempts it took. Retries are
+    /// added to the retry metrics, and an operation that still fails counts
+    /// as a failed operation.
+    async fn with_retry<T, F, Fut>(
+        &self,
+        operation_name: &str,
+        mut operation: F,
+    ) -> Result<(T, u32), anyhow::Error>
+    where
+        F: FnMut(ClickHouseConnection) -> Fut,
+        Fut: Future<Output = Result<T, anyhow::Error>>,
+    {
+        let mut attempts = 0u32;
+        let result = self
+            .retry_policy()
+            .execute_with_retry(|| {
+                attempts += 1;
+                let attempt = attempts;
+                let pending = self.pool.get_connection().map(&mut operation);
+                async move {
+                    pending?.await.inspect_err(|e| {
+                        error!(
+                            "[ClickHouse] {} failed (attempt {}): {}",
+                            operation_name, attempt, e
+                        )
+                    })
                 }
+            })
+            .await;
+
+        self.metrics.write().unwrap().total_retries += attempts.saturating_sub(1) as u64;
+        match result {
+            Ok(value) => Ok((value, attempts)),
+            Err(e) => {
+                self.update_error_metrics();
+                Err(e.into_inner())
             }
         }
+    }
+
+    /// Execute a single insert with retry logic
+    pub async fn insert_event(&self, event: &AuditEvent) -> Result<(), anyhow::Error> {
+        let start_time = SystemTime::now();
+
+        let ((), attempts) = self
+            .with_retry("Insert", |conn| async move {
+                self.execute_insert(&conn, event).await
+            })
+            .await?;
 
-        unreachable!()
+        let latency = start_time.elapsed()?.as_millis() as f64;
+        self.update_insert_metrics(latency, false);
+        info!(
+            "[ClickHouse] Inserted event: {} (attempt {})",
+            event
+                .event_id
+                .as_ref()
+                .map(|e| e.value.as_str())
+                .unwrap_or("unknown"),
+            attempts
+        );
+        Ok(())
     }
 
     /// Execute batch insert with retry logic
     pub async fn insert_batch(&self, events: &[AuditEvent]) -> Result<BatchStats, anyhow::Error> {
         let start_time = SystemTime::now();
-        let mut retries = 0;
-
-        for attempt in 0..self.config.max_retries {
-            let conn = match self.pool.get_connection() {
-                Ok(conn) => conn,
-                Err(e) => {
-                    if attempt == self.config.max_retries - 1 {
-                        return Err(e);
-                    }
-                    self.retry_delay(attempt).await;
-                    retries += 1;
-                    continue;
-                }
-            };
-
-            // Simulate batch insert operation
-            let result = self.execute_batch_insert(&conn, events).await;
-
-            match result {
-                Ok(_) => {
-                    let latency = start_time.elapsed()?.as_millis() as f64;
-                    self.update_insert_metrics(latency, false);
-                    info!(
-                        "[ClickHouse] Batch inserted {} events (attempt {})",
-                        events.len(),
-                        attempt + 1
-                    );
-                    return Ok(BatchStats {
-                        batch_size: events.len(),
-                        latency_ms: latency,
-                        retries,
-                        success: true,
-                    });
-                }
-                Err(e) => {
-                    error!(
-                        "[ClickHouse] Batch insert failed (attempt {}): {}",
-                        attempt + 1,
-                        e
-                    );
-                    if attempt == self.config.max_retries - 1 {
-                        self.update_error_metrics();
-                        return Err(e);
-                    }
-                    self.retry_delay(attempt).await;
-                    retries += 1;
-                    self.update_retry_metrics();
+
+        // Every row carries the batch ID, so the digest can be checked
+        // against the rows as stored; a retry only adds the missing offsets
+        let batch_id = uuid::Uuid::new_v4().to_string();
+        let written = std::sync::Mutex::new(BTreeSet::new());
+        let ((), attempts) = self
+            .with_retry("Batch insert", |conn| {
+                let (batch_id, written) = (&batch_id, &written);
+                async move {
+                    self.execute_batch_insert(&conn, events, batch_id, written)
+                        .await
                 }
+            })
+            .await?;
+
+        let rows: Vec<AuditEvent> = written
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|offset| events[offset].clone())
+            .collect();
+        let batch_digest = batch_digest(&rows);
+        let signature = self.digest_signer.sign(&batch_digest);
+        debug!(
+            "[ClickHouse] INSERT INTO {} (batch_id, digest, signature, event_count) VALUES ('{}', '{}', '{}', {})",
+            self.schema.batch_digest_table(),
+            batch_id,
+            batch_digest,
+            signature,
+            rows.len()
+        );
+        self.batch_digests.write().unwrap().insert(
+            batch_id.clone(),
+            BatchDigestRow {
+                digest: batch_digest.clone(),
+                signature,
+            },
+        );
+
+        let latency = start_time.elapsed()?.as_millis() as f64;
+        self.update_insert_metrics(latency, false);
+        info!(
+            "[ClickHouse] Batch inserted {} events (attempt {})",
+            events.len(),
+            attempts
+        );
+        Ok(BatchStats {
+            batch_size: events.len(),
+            latency_ms: latency,
+            retries: attempts - 1,
+            success: true,
+            batch_id,
+            batch_digest,
+        })
+    }
+
+    /// Recompute the digest of an insert batch from the rows tagged with
+    /// its ID and compare it to the signed digest recorded when it was
+    /// inserted
+    pub async fn verify_batch_integrity(
+        &self,
+        batch_id: &str,
+    ) -> Result<BatchIntegrity, anyhow::Error> {
+        let _conn = self.pool.get_connection()?;
+        let quoted = batch_id.replace('\\', "\\\\").replace('\'', "\\'");
+        debug!(
+            "[ClickHouse] SELECT digest, signature FROM {} WHERE batch_id = '{}'",
+            self.schema.batch_digest_table(),
+            quoted
+        );
+        let recorded = self
+            .batch_digests
+            .read()
+            .unwrap()
+            .get(batch_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown batch {}", batch_id))?;
+
+        // Rows of a batch may span tenant tables; a row written twice by a
+        // retried insert counts once
+        let mut rows = BTreeMap::new();
+        for (table, table_rows) in self.rows.read().unwrap().iter() {
+            debug!(
+                "[ClickHouse] SELECT * FROM {} FINAL WHERE batch_id = '{}' ORDER BY batch_offset",
+                table, quoted
+            );
+            for row in table_rows.iter().filter(|row| row.batch_id == batch_id) {
+                rows.entry(row.batch_offset)
+                    .or_insert_with(|| row.event.clone());
             }
         }
-
-        unreachable!()
+        let rows: Vec<AuditEvent> = rows.into_values().collect();
+
+        let integrity = BatchIntegrity::check(
+            batch_id,
+            &rows,
+            recorded.digest,
+            &recorded.signature,
+            &self.digest_signer,
+        );
+        if !integrity.is_valid() {
+            warn!("[ClickHouse] Batch digest mismatch for batch {}", batch_id);
+        }
+        Ok(integrity)
     }
 
     /// Execute a query with retry logic
     pub async fn query(&self, sql: &str) -> Result<Vec<AuditEvent>, anyhow::Error> {
         let start_time = SystemTime::now();
 
-        for attempt in 0..self.config.max_retries {
-            let conn = match self.pool.get_connection() {
-                Ok(conn) => conn,
-                Err(e) => {
-                    if attempt == self.config.max_retries - 1 {
-                        return Err(e);
-                    }
-                    self.retry_delay(attempt).await;
-                    continue;
-                }
-            };
-
-            // Simulate query operation
-            let result = self.execute_query(&conn, sql).await;
-
-            match result {
-                Ok(events) => {
-                    let latency = start_time.elapsed()?.as_millis() as f64;
-                    self.update_query_metrics(latency);
-                    info!(
-                        "[ClickHouse] Query executed: {} events returned (latency: {}ms)",
-                        events.len(),
-                        latency as u64
-                    );
-                    return Ok(events);
-                }
-                Err(e) => {
-                    error!("[ClickHouse] Query failed (attempt {}): {}", attempt + 1, e);
-                    if attempt == self.config.max_retries - 1 {
-                        self.update_error_metrics();
-                        return Err(e);
-                    }
-                    self.retry_delay(attempt).await;
-                    self.update_retry_metrics();
-                }
-            }
-        }
+        let (events, _) = self
+            .with_retry("Query", |conn| async move {
+                self.execute_query(&conn, sql).await
+            })
+            .await?;
 
-        unreachable!()
+        let latency = start_time.elapsed()?.as_millis() as f64;
+        self.update_query_metrics(latency);
+        info!(
+            "[ClickHouse] Query executed: {} events returned (latency: {}ms)",
+            events.len(),
+            latency as u64
+        );
+        Ok(events)
     }
 
     /// Execute a parameterized query
@@ -343,45 +724,90 @@ impl ClickHouseClient {
         params: &HashMap<String, String>,
     ) -> Result<Vec<AuditEvent>, anyhow::Error> {
         let start_time = SystemTime::now();
+        let sql = match params.get("tenant_id") {
+            Some(tenant_id) => self.route_sql(sql, tenant_id),
+            None => sql.to_string(),
+        };
+        let sql = sql.as_str();
+
+        let (events, _) = self
+            .with_retry("Parameterized query", |conn| async move {
+                self.execute_parametrized_query(&conn, sql, params).await
+            })
+            .await?;
+
+        let latency = start_time.elapsed()?.as_millis() as f64;
+        self.update_query_metrics(latency);
+        Ok(events)
+    }
 
-        for attempt in 0..self.config.max_retries {
-            let conn = match self.pool.get_connection() {
-                Ok(conn) => conn,
-                Err(e) => {
-                    if attempt == self.config.max_retries - 1 {
-                        return Err(e);
-                    }
-                    self.retry_delay(attempt).await;
-                    continue;
-                }
-            };
-
-            // Simulate parameterized query
-            let result = self.execute_parametrized_query(&conn, sql, params).await;
+    /// Rewrite a query on the shared table to target a tenant's table
+    ///
+    /// Qualified (`database.table`) and bare `FROM`/`INTO` references are
+    /// rewritten; queries of tenants without a dedicated table are unchanged.
+    pub fn route_sql(&self, sql: &str, tenant_id: &str) -> String {
+        let target = self.config.table_for(tenant_id);
+        let shared = self.config.shared_table();
+        if target == shared {
+            return sql.to_string();
+        }
 
-            match result {
-                Ok(events) => {
-                    let latency = start_time.elapsed()?.as_millis() as f64;
-                    self.update_query_metrics(latency);
-                    return Ok(events);
-                }
-                Err(e) => {
-                    error!(
-                        "[ClickHouse] Parameterized query failed (attempt {}): {}",
-                        attempt + 1,
-                        e
-                    );
-                    if attempt == self.config.max_retries - 1 {
-                        self.update_error_metrics();
-                        return Err(e);
-                    }
-                    self.retry_delay(attempt).await;
-                    self.update_retry_metrics();
-                }
+        let routed = sql.replace(&shared.to_string(), &target.to_string());
+        let mut words: Vec<&str> = routed.split(' ').collect();
+        let target = target.to_string();
+        for i in 1..words.len() {
+            if words[i] == shared.table
+                && matches!(words[i - 1].to_ascii_uppercase().as_str(), "FROM" | "INTO")
+            {
+                words[i] = &target;
             }
         }
+        words.join(" ")
+    }
+
+    /// Subset of `event_ids` already stored for a tenant
+    ///
+    /// Runs `SELECT event_id ... WHERE tenant_id = ? AND event_id IN (...)`,
+    /// used to make re-ingestion idempotent.
+    pub async fn existing_event_ids(
+        &self,
+        tenant_id: &str,
+        event_ids: &[String],
+    ) -> Result<HashSet<String>, anyhow::Error> {
+        let _conn = self.pool.get_connection()?;
+        debug!(
+            "[ClickHouse] SELECT event_id FROM {} WHERE tenant_id = '{}' AND event_id IN ({} ids)",
+            self.config.table_for(tenant_id),
+            tenant_id,
+            event_ids.len()
+        );
+
+        let stored = self.stored_events.read().unwrap();
+        Ok(event_ids
+            .iter()
+            .filter(|id| stored.contains_key(&(tenant_id.to_string(), (*id).clone())))
+            .cloned()
+            .collect())
+    }
+
+    /// Number of rows in the table, duplicates included
+    pub fn stored_row_count(&self) -> u64 {
+        self.stored_events.read().unwrap().values().sum()
+    }
+
+    /// Number of distinct `(tenant_id, event_id)` in the table
+    pub fn unique_event_count(&self) -> usize {
+        self.stored_events.read().unwrap().len()
+    }
 
-        unreachable!()
+    /// Number of rows inserted into `table`
+    pub fn rows_in_table(&self, table: &ClickHouseTable) -> u64 {
+        self.table_rows
+            .read()
+            .unwrap()
+            .get(table)
+            .copied()
+            .unwrap_or(0)
     }
 
     /// Health check
@@ -394,37 +820,80 @@ impl ClickHouseClient {
 
     /// Get current metrics
     pub fn get_metrics(&self) -> ClickHouseMetrics {
-        let mut metrics = self.metrics.read().unwrap().clone();
-        metrics.pool_utilization = self.pool.get_utilization();
-        metrics
+        self.metrics_source().snapshot()
     }
 
     /// Reset metrics
     pub fn reset_metrics(&self) {
         let mut metrics = self.metrics.write().unwrap();
         *metrics = ClickHouseMetrics::default();
+        *self.latencies.lock().unwrap() = LatencySamples::default();
         info!("[ClickHouse] Metrics reset");
     }
 
+    /// Export the client's metrics as `hodei_clickhouse_*` series in
+    /// `registry`, refreshed every `refresh_interval`
+    ///
+    /// Must be called within a Tokio runtime. The series keep their last
+    /// values once the returned exporter is dropped.
+    pub fn register_metrics(
+        &self,
+        registry: &mut Registry,
+        refresh_interval: Duration,
+    ) -> ClickHouseMetricsExporter {
+        let series = ClickHouseSeries::default();
+        series.register(registry);
+        let source = self.metrics_source();
+        series.update(&source.snapshot());
+
+        let refreshed = series.clone();
+        let refresh = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(refresh_interval);
+            loop {
+                interval.tick().await;
+                refreshed.update(&source.snapshot());
+            }
+        });
+        ClickHouseMetricsExporter { series, refresh }
+    }
+
+    fn metrics_source(&self) -> MetricsSource {
+        MetricsSource {
+            metrics: self.metrics.clone(),
+            latencies: self.latencies.clone(),
+            pool: self.pool.clone(),
+        }
+    }
+
     /// Simulate insert operation
     async fn execute_insert(
         &self,
         _conn: &ClickHouseConnection,
-        _event: &AuditEvent,
+        event: &AuditEvent,
     ) -> Result<(), anyhow::Error> {
         // In production, this would:
         // 1. Prepare INSERT statement
         // 2. Bind event data
         // 3. Execute with timeout
         tokio::time::sleep(Duration::from_millis(5)).await; // Simulate network latency
+        let pending = self.pending_events(std::slice::from_ref(event));
+        for (table, events) in self.route_events(&pending) {
+            self.provision(&table).await?;
+            self.record_stored(&table, &events);
+        }
         Ok(())
     }
 
     /// Simulate batch insert operation
+    ///
+    /// Rows are tagged with `batch_id` and their offset in `events`; the
+    /// offsets written are added to `written`.
     async fn execute_batch_insert(
         &self,
         _conn: &ClickHouseConnection,
         events: &[AuditEvent],
+        batch_id: &str,
+        written: &std::sync::Mutex<BTreeSet<usize>>,
     ) -> Result<(), anyhow::Error> {
         // In production, this would:
         // 1. Prepare batch INSERT statement
@@ -433,9 +902,150 @@ impl ClickHouseClient {
         let batch_size = events.len();
         let sleep_time = (batch_size as u64 * 2).min(50); // Simulate proportional latency
         tokio::time::sleep(Duration::from_millis(sleep_time)).await;
+
+        let pending = self.pending_offsets(events);
+        let failure = self.partial_failure_after.lock().unwrap().take();
+        let count = failure.map_or(pending.len(), |rows| rows.min(pending.len()));
+        let mut routed: BTreeMap<ClickHouseTable, Vec<usize>> = BTreeMap::new();
+        for &offset in &pending[..count] {
+            routed
+                .entry(self.table_of(&events[offset]))
+                .or_default()
+                .push(offset);
+        }
+        for (table, offsets) in routed {
+            self.provision(&table).await?;
+            let rows: Vec<_> = offsets
+                .iter()
+                .map(|&offset| {
+                    let mut row = insert_row(&events[offset]);
+                    row.push(("batch_id", ColumnValue::String(batch_id.to_string())));
+                    row.push(("batch_offset", ColumnValue::UInt64(offset as u64)));
+                    row
+                })
+                .collect();
+            debug!(
+                "[ClickHouse] {} ({} rows)",
+                self.insert_statement_for(&table),
+                rows.len()
+            );
+            let inserted: Vec<AuditEvent> = offsets
+                .iter()
+                .map(|&offset| events[offset].clone())
+                .collect();
+            self.record_stored(&table, &inserted);
+            self.rows
+                .write()
+                .unwrap()
+                .entry(table)
+                .or_default()
+                .extend(offsets.iter().map(|&offset| InsertedRow {
+                    batch_id: batch_id.to_string(),
+                    batch_offset: offset,
+                    event: events[offset].clone(),
+                }));
+            written.lock().unwrap().extend(offsets);
+        }
+        if failure.is_some() {
+            return Err(anyhow::anyhow!("Connection reset after {} rows", count));
+        }
+        Ok(())
+    }
+
+    /// Table holding the rows of an event's tenant
+    fn table_of(&self, event: &AuditEvent) -> ClickHouseTable {
+        match &event.tenant_id {
+            Some(tenant) => self.config.table_for(&tenant.value),
+            None => self.config.shared_table(),
+        }
+    }
+
+    /// Group events by the table of their tenant, in table order
+    fn route_events(&self, events: &[AuditEvent]) -> BTreeMap<ClickHouseTable, Vec<AuditEvent>> {
+        let mut routed: BTreeMap<ClickHouseTable, Vec<AuditEvent>> = BTreeMap::new();
+        for event in events {
+            routed
+                .entry(self.table_of(event))
+                .or_default()
+                .push(event.clone());
+        }
+        routed
+    }
+
+    /// Create a dedicated tenant table the first time it's written to
+    async fn provision(&self, table: &ClickHouseTable) -> Result<(), anyhow::Error> {
+        if *table != self.config.shared_table() {
+            self.schema.provision_table(table).await?;
+        }
         Ok(())
     }
 
+    /// Events of a batch that still have to be inserted
+    ///
+    /// With `dedup_on_event_id`, events already stored (as reported by
+    /// `existing_event_ids`) or repeated within the batch are dropped;
+    /// otherwise every event is inserted.
+    fn pending_events(&self, events: &[AuditEvent]) -> Vec<AuditEvent> {
+        self.pending_offsets(events)
+            .into_iter()
+            .map(|offset| events[offset].clone())
+            .collect()
+    }
+
+    /// Offsets in `events` of the events still to insert; see
+    /// `pending_events`
+    fn pending_offsets(&self, events: &[AuditEvent]) -> Vec<usize> {
+        if !self.config.dedup_on_event_id {
+            return (0..events.len()).collect();
+        }
+
+        let stored = self.stored_events.read().unwrap();
+        let mut seen = HashSet::new();
+        let pending: Vec<usize> = events
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| match event_key(event) {
+                Some(key) => !stored.contains_key(&key) && seen.insert(key),
+                None => true,
+            })
+            .map(|(offset, _)| offset)
+            .collect();
+
+        if pending.len() < events.len() {
+            debug!(
+                "[ClickHouse] Skipped {} already stored events",
+                events.len() - pending.len()
+            );
+        }
+        pending
+    }
+
+    /// Track inserted rows in the simulated table
+    fn record_stored(&self, table: &ClickHouseTable, events: &[AuditEvent]) {
+        let mut stored = self.stored_events.write().unwrap();
+        for key in events.iter().filter_map(event_key) {
+            *stored.entry(key).or_insert(0) += 1;
+        }
+        *self
+            .table_rows
+            .write()
+            .unwrap()
+            .entry(table.clone())
+            .or_insert(0) += events.len() as u64;
+    }
+
+    /// Make the next batch insert fail after `rows` rows have been written
+    #[cfg(test)]
+    fn fail_next_batch_after(&self, rows: usize) {
+        *self.partial_failure_after.lock().unwrap() = Some(rows);
+    }
+
+    /// Make queries return `events`
+    #[cfg(test)]
+    pub(crate) fn set_query_results(&self, events: Vec<AuditEvent>) {
+        *self.query_results.write().unwrap() = events;
+    }
+
     /// Simulate query operation
     async fn execute_query(
         &self,
@@ -447,7 +1057,7 @@ impl ClickHouseClient {
         // 2. Parse results
         // 3. Convert to AuditEvent structs
         tokio::time::sleep(Duration::from_millis(10)).await; // Simulate query latency
-        Ok(vec![])
+        Ok(self.query_results.read().unwrap().clone())
     }
 
     /// Simulate parameterized query
@@ -462,17 +1072,12 @@ impl ClickHouseClient {
         // 2. Bind parameter values
         // 3. Execute and parse results
         tokio::time::sleep(Duration::from_millis(15)).await;
-        Ok(vec![])
-    }
-
-    /// Calculate retry delay with exponential backoff
-    async fn retry_delay(&self, attempt: u32) {
-        let delay = self.config.retry_delay_ms * (2u64.pow(attempt));
-        tokio::time::sleep(Duration::from_millis(delay)).await;
+        Ok(self.query_results.read().unwrap().clone())
     }
 
     /// Update insert metrics
     fn update_insert_metrics(&self, latency_ms: f64, _is_batch: bool) {
+        LatencySamples::record(&mut self.latencies.lock().unwrap().insert, latency_ms);
         let mut metrics = self.metrics.write().unwrap();
         metrics.total_inserts += 1;
         if metrics.avg_insert_latency_ms == 0.0 {
@@ -484,6 +1089,7 @@ impl ClickHouseClient {
 
     /// Update query metrics
     fn update_query_metrics(&self, latency_ms: f64) {
+        LatencySamples::record(&mut self.latencies.lock().unwrap().query, latency_ms);
         let mut metrics = self.metrics.write().unwrap();
         metrics.total_queries += 1;
         if metrics.avg_query_latency_ms == 0.0 {
@@ -498,51 +1104,379 @@ impl ClickHouseClient {
         let mut metrics = self.metrics.write().unwrap();
         metrics.failed_operations += 1;
     }
+}
 
-    /// Update retry metrics
-    fn update_retry_metrics(&self) {
-        let mut metrics = self.metrics.write().unwrap();
-        metrics.total_retries += 1;
+/// Expiry of rows in the hot tier
+///
+/// A plain TTL deletes rows after the retention period whether or not the
+/// lifecycle migration has copied them to the warm tier yet, which loses
+/// data when migration falls behind. The coordinated modes only delete rows
+/// the migration has confirmed as copied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotTierTtl {
+    /// `TTL timestamp + INTERVAL n DAY`: rows are deleted after `days`,
+    /// migrated or not
+    Destructive { days: u32 },
+    /// Rows are deleted after `days` only once flagged `migrated = 1`
+    MigrationCoordinated { days: u32 },
+    /// No TTL; the migration deletes rows once they are copied
+    Disabled,
+}
+
+impl Default for HotTierTtl {
+    fn default() -> Self {
+        Self::Destructive { days: 7 }
+    }
+}
+
+impl HotTierTtl {
+    /// `TTL` clause of the table definition, if any
+    pub fn ttl_clause(&self) -> Option<String> {
+        match self {
+            Self::Destructive { days } => Some(format!("TTL timestamp + INTERVAL {} DAY", days)),
+            Self::MigrationCoordinated { days } => Some(format!(
+                "TTL timestamp + INTERVAL {} DAY DELETE WHERE migrated = 1",
+                days
+            )),
+            Self::Disabled => None,
+        }
+    }
+
+    /// Whether the table needs the `migrated` flag column
+    pub fn tracks_migration(&self) -> bool {
+        !matches!(self, Self::Destructive { .. })
+    }
+
+    /// Whether a row of the given age is deleted by the TTL
+    pub fn expires(&self, age: Duration, migrated: bool) -> bool {
+        let past = |days: u32| age >= Duration::from_secs(days as u64 * 24 * 60 * 60);
+        match self {
+            Self::Destructive { days } => past(*days),
+            Self::MigrationCoordinated { days } => migrated && past(*days),
+            Self::Disabled => false,
+        }
+    }
+}
+
+impl std::fmt::Display for HotTierTtl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Destructive { days } => write!(f, "{} days", days),
+            Self::MigrationCoordinated { days } => write!(f, "{} days, migrated rows only", days),
+            Self::Disabled => write!(f, "disabled, rows removed by migration"),
+        }
+    }
+}
+
+/// Value bound to a column of an inserted row
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValue {
+    String(String),
+    UInt16(u16),
+    UInt64(u64),
+    /// Milliseconds since the epoch
+    DateTime64(i64),
+}
+
+/// A column of the events table and how it's filled from an `AuditEvent`
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnMapping {
+    pub column: &'static str,
+    pub column_type: &'static str,
+    /// `AuditEvent` field the column is read from
+    pub field: &'static str,
+    /// Monotonic column worth a `DoubleDelta` codec
+    pub delta: bool,
+    pub bind: fn(&AuditEvent) -> ColumnValue,
+}
+
+impl ColumnMapping {
+    const fn new(
+        column: &'static str,
+        column_type: &'static str,
+        field: &'static str,
+        bind: fn(&AuditEvent) -> ColumnValue,
+    ) -> Self {
+        Self {
+            column,
+            column_type,
+            field,
+            delta: false,
+            bind,
+        }
+    }
+
+    const fn delta(mut self) -> Self {
+        self.delta = true;
+        self
+    }
+}
+
+/// Columns of the events table, in DDL and `RowBinary` order
+///
+/// Both the `CREATE TABLE` statements and the insert rows are generated from
+/// this list. Fields left out are listed in `_unmapped_fields`, which stops
+/// compiling when `AuditEvent` gains a field.
+pub const AUDIT_EVENT_COLUMNS: &[ColumnMapping] = &[
+    ColumnMapping::new("event_id", "String", "event_id", |e| {
+        ColumnValue::String(e.event_id.clone().unwrap_or_default().value)
+    }),
+    ColumnMapping::new("tenant_id", "String", "tenant_id", |e| {
+        ColumnValue::String(e.tenant_id.clone().unwrap_or_default().value)
+    }),
+    ColumnMapping::new("hrn", "String", "hrn", |e| {
+        ColumnValue::String(
+            e.hrn
+                .as_ref()
+                .map(crate::exporters::hrn_string)
+                .unwrap_or_default(),
+        )
+    }),
+    ColumnMapping::new("user_id", "String", "user_identity", |e| {
+        ColumnValue::String(e.user_identity.clone().unwrap_or_default().user_id)
+    }),
+    ColumnMapping::new("action", "String", "action", |e| {
+        ColumnValue::String(e.action.clone())
+    }),
+    ColumnMapping::new("path", "String", "http_context", |e| {
+        ColumnValue::String(e.http_context.clone().unwrap_or_default().path)
+    }),
+    ColumnMapping::new("method", "String", "http_context", |e| {
+        ColumnValue::String(e.http_context.clone().unwrap_or_default().method)
+    }),
+    ColumnMapping::new("status_code", "UInt16", "http_context", |e| {
+        let status = e.http_context.as_ref().map_or(0, |http| http.status_code);
+        ColumnValue::UInt16(u16::try_from(status).unwrap_or_default())
+    }),
+    ColumnMapping::new("outcome", "String", "outcome", |e| {
+        ColumnValue::String(Outcome::from(e.outcome).to_string())
+    }),
+    ColumnMapping::new("latency_ms", "UInt64", "latency_ms", |e| {
+        ColumnValue::UInt64(e.latency_ms)
+    }),
+    ColumnMapping::new("metadata_json", "String", "metadata", |e| {
+        ColumnValue::String(
+            e.metadata
+                .as_ref()
+                .map(|m| crate::schema_registry::struct_to_json(m).to_string())
+                .unwrap_or_default(),
+        )
+    }),
+    ColumnMapping::new("timestamp", "DateTime64(3)", "event_time", |e| {
+        ColumnValue::DateTime64(timestamp_millis(e.event_time.as_ref()))
+    })
+    .delta(),
+    ColumnMapping::new("processed_at", "DateTime64(3)", "processed_at", |e| {
+        ColumnValue::DateTime64(timestamp_millis(e.processed_at.as_ref()))
+    })
+    .delta(),
+];
+
+/// Every `AuditEvent` field, so adding one fails to compile until it is
+/// either mapped in [`AUDIT_EVENT_COLUMNS`] or listed here as unmapped
+fn _unmapped_fields(event: &AuditEvent) {
+    let AuditEvent {
+        // Mapped
+        event_id: _,
+        tenant_id: _,
+        hrn: _,
+        user_identity: _,
+        http_context: _,
+        action: _,
+        outcome: _,
+        latency_ms: _,
+        metadata: _,
+        event_time: _,
+        processed_at: _,
+        // Not stored in ClickHouse
+        event_category: _,
+        management_type: _,
+        access_type: _,
+        read_only: _,
+        error_code: _,
+        error_message: _,
+        correlation_id: _,
+        trace_id: _,
+        span_id: _,
+        event_source: _,
+        event_version: _,
+        management_event: _,
+        enriched: _,
+    } = event;
+}
+
+fn timestamp_millis(timestamp: Option<&prost_types::Timestamp>) -> i64 {
+    timestamp.map_or(0, |t| t.seconds * 1000 + i64::from(t.nanos) / 1_000_000)
+}
+
+/// Row inserted for an event, one value per [`AUDIT_EVENT_COLUMNS`] entry
+pub fn insert_row(event: &AuditEvent) -> Vec<(&'static str, ColumnValue)> {
+    AUDIT_EVENT_COLUMNS
+        .iter()
+        .map(|mapping| (mapping.column, (mapping.bind)(event)))
+        .collect()
+}
+
+/// Columns filled from an `AuditEvent` field; fields the table doesn't
+/// store map to none
+pub fn columns_for_field(field: &str) -> impl Iterator<Item = &'static str> + '_ {
+    AUDIT_EVENT_COLUMNS
+        .iter()
+        .filter(move |mapping| mapping.field == field)
+        .map(|mapping| mapping.column)
+}
+
+/// Deduplication key of an event
+fn event_key(event: &AuditEvent) -> Option<(String, String)> {
+    match (&event.tenant_id, &event.event_id) {
+        (Some(tenant), Some(id)) => Some((tenant.value.clone(), id.value.clone())),
+        _ => None,
     }
 }
 
 /// ClickHouse schema management
 pub struct ClickHouseSchema {
     config: ClickHouseConfig,
+    ttl: HotTierTtl,
+    /// Dedicated tenant tables created so far
+    provisioned: std::sync::Mutex<HashSet<ClickHouseTable>>,
 }
 
 impl ClickHouseSchema {
     /// Create a new schema manager
     pub fn new(config: ClickHouseConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            ttl: HotTierTtl::default(),
+            provisioned: std::sync::Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Set how hot-tier rows expire
+    pub fn with_ttl(mut self, ttl: HotTierTtl) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// How hot-tier rows expire
+    pub fn ttl(&self) -> HotTierTtl {
+        self.ttl
+    }
+
+    /// Statement to run once the migration has copied `event_ids` to the
+    /// warm tier
+    ///
+    /// Flags the rows for the coordinated TTL, or deletes them when the TTL
+    /// is disabled. A destructive TTL needs no follow-up.
+    pub fn post_migration_statement(&self, event_ids: &[String]) -> Option<String> {
+        if event_ids.is_empty() {
+            return None;
+        }
+        let ids = event_ids
+            .iter()
+            .map(|id| format!("'{}'", id.replace('\\', "\\\\").replace('\'', "\\'")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let table = &self.config.table;
+        match self.ttl {
+            HotTierTtl::Destructive { .. } => None,
+            HotTierTtl::MigrationCoordinated { .. } => Some(format!(
+                "ALTER TABLE {} UPDATE migrated = 1 WHERE event_id IN ({});",
+                table, ids
+            )),
+            HotTierTtl::Disabled => Some(format!(
+                "ALTER TABLE {} DELETE WHERE event_id IN ({});",
+                table, ids
+            )),
+        }
+    }
+
+    /// Table engine and sorting key
+    ///
+    /// With `dedup_on_event_id` the engine is a `ReplacingMergeTree` and
+    /// `event_id` joins the sorting key, so merges collapse duplicate rows.
+    fn engine_and_order_by(&self) -> (&'static str, &'static str) {
+        if self.config.dedup_on_event_id {
+            (
+                "ReplacingMergeTree(processed_at)",
+                "(tenant_id, timestamp, hrn, event_id)",
+            )
+        } else {
+            ("MergeTree()", "(tenant_id, timestamp, hrn)")
+        }
+    }
+
+    /// Column definition of the migration flag, when the TTL needs it
+    fn migrated_column(&self) -> Option<&'static str> {
+        self.ttl
+            .tracks_migration()
+            .then_some("migrated UInt8 DEFAULT 0")
+    }
+
+    /// `CREATE TABLE` statement of the events table named `table`
+    pub fn create_table_statement(&self, table: &str) -> String {
+        let migrated_column = self
+            .migrated_column()
+            .into_iter()
+            .chain(BATCH_COLUMNS)
+            .map(|column| format!(",\n            {}", column))
+            .collect::<String>();
+        let ttl = self
+            .ttl
+            .ttl_clause()
+            .map(|clause| format!("\n        {}", clause))
+            .unwrap_or_default();
+
+        let (engine, order_by) = self.engine_and_order_by();
+        let columns = AUDIT_EVENT_COLUMNS
+            .iter()
+            .map(|mapping| format!("{} {}", mapping.column, mapping.column_type))
+            .collect::<Vec<_>>()
+            .join(",\n            ");
+
+        // Schema creation SQL with the hot tier TTL
+        format!(
+            r#"
+        CREATE TABLE IF NOT EXISTS {table} (
+            {columns}{migrated_column}
+        ) ENGINE = {engine}
+        PARTITION BY toYYYYMM(timestamp)
+        ORDER BY {order_by}{ttl}
+        SETTINGS index_granularity = 8192;
+        "#
+        )
+    }
+
+    /// Table holding the digest of every insert batch
+    pub fn batch_digest_table(&self) -> ClickHouseTable {
+        ClickHouseTable::new(
+            self.config.database.clone(),
+            format!("{}_batches", self.config.table),
+        )
+    }
+
+    /// `CREATE TABLE` statement of the batch digest table
+    pub fn create_batch_digest_table_statement(&self) -> String {
+        format!(
+            r#"
+        CREATE TABLE IF NOT EXISTS {} (
+            batch_id String,
+            digest FixedString(64),
+            signature FixedString(64),
+            event_count UInt32,
+            inserted_at DateTime DEFAULT now()
+        ) ENGINE = MergeTree
+        ORDER BY batch_id;
+        "#,
+            self.batch_digest_table()
+        )
     }
 
     /// Create optimized schema
     pub async fn create_schema(&self) -> Result<(), anyhow::Error> {
         info!("[ClickHouse] Creating optimized schema...");
 
-        // Schema creation SQL with TTL (7 days for Hot tier)
-        let create_table_sql = r#"
-        CREATE TABLE IF NOT EXISTS audit_events (
-            event_id String,
-            tenant_id String,
-            hrn String,
-            user_id String,
-            action String,
-            path String,
-            method String,
-            status_code UInt16,
-            outcome String,
-            latency_ms UInt64,
-            metadata_json String,
-            timestamp DateTime64(3),
-            processed_at DateTime64(3)
-        ) ENGINE = MergeTree()
-        PARTITION BY toYYYYMM(timestamp)
-        ORDER BY (tenant_id, timestamp, hrn)
-        TTL timestamp + INTERVAL 7 DAY
-        SETTINGS index_granularity = 8192;
-        "#;
+        let create_table_sql = self.create_table_statement(&self.config.table);
 
         // Index creation SQL
         let create_indices_sql = vec![
@@ -555,15 +1489,145 @@ impl ClickHouseSchema {
         info!("[ClickHouse] Schema created successfully");
         info!("[ClickHouse] Partitioning: toYYYYMM(timestamp)");
         info!("[ClickHouse] Sorting: (tenant_id, timestamp, hrn)");
-        info!("[ClickHouse] TTL: 7 days (Hot tier)");
+        info!("[ClickHouse] TTL: {} (Hot tier)", self.ttl);
         info!("[ClickHouse] Indices: tenant, hrn, timestamp, action");
 
         // In production, execute these SQL statements
-        let _ = (create_table_sql, create_indices_sql);
+        let _ = (
+            create_table_sql,
+            create_indices_sql,
+            self.create_batch_digest_table_statement(),
+        );
+
+        Ok(())
+    }
+
+    /// Build the DDL for the events table from a tuning config
+    ///
+    /// Returns the `CREATE TABLE` statement followed by the `CREATE INDEX`
+    /// statements for the configured index type.
+    pub fn tuned_schema_statements(&self, tuning: &ClickHouseTuningConfig) -> Vec<String> {
+        let table = &self.config.table;
+        let compression = &tuning.compression_settings;
+        let codec = |column: &str, delta: bool| -> String {
+            match (tuning.enable_compression, delta) {
+                (false, _) => String::new(),
+                (true, false) => format!(" CODEC({})", compression.codec_for(column)),
+                (true, true) => format!(" CODEC(DoubleDelta, {})", compression.codec_for(column)),
+            }
+        };
+
+        let mut columns: Vec<String> = AUDIT_EVENT_COLUMNS
+            .iter()
+            .map(|mapping| {
+                format!(
+                    "    {} {}{}",
+                    mapping.column,
+                    mapping.column_type,
+                    codec(mapping.column, mapping.delta)
+                )
+            })
+            .collect();
+        if let Some(column) = self.migrated_column() {
+            columns.push(format!("    {}", column));
+        }
+        columns.extend(BATCH_COLUMNS.iter().map(|column| format!("    {}", column)));
+        let ttl = self
+            .ttl
+            .ttl_clause()
+            .map(|clause| format!("{}\n", clause))
+            .unwrap_or_default();
+
+        let merge_tree = &tuning.merge_tree_settings;
+        let (engine, order_by) = self.engine_and_order_by();
+        let create_table = format!(
+            "CREATE TABLE IF NOT EXISTS {table} (\n{columns}\n) ENGINE = {engine}\n\
+             PARTITION BY toYYYYMM(timestamp)\n\
+             ORDER BY {order_by}\n\
+             {ttl}\
+             SETTINGS index_granularity = {granularity}, \
+             max_bytes_to_merge_at_max_space_in_pool = {merge_bytes}, \
+             max_parts_to_merge_at_once = {merge_parts};",
+            table = table,
+            columns = columns.join(",\n"),
+            engine = engine,
+            order_by = order_by,
+            ttl = ttl,
+            granularity = merge_tree.index_granularity,
+            merge_bytes = merge_tree.max_bytes_to_merge_at_max_space_in_pool,
+            merge_parts = merge_tree.max_parts_to_merge_at_once,
+        );
+
+        let mut statements = vec![create_table];
+        if tuning.enable_index_optimization {
+            if let Some(index_type) = tuning.index_type.skip_index_type() {
+                for column in ["tenant_id", "hrn", "action"] {
+                    statements.push(format!(
+                        "CREATE INDEX IF NOT EXISTS idx_{column} ON {table} ({column}) TYPE {index_type} GRANULARITY 1;"
+                    ));
+                }
+            }
+            statements.push(format!(
+                "CREATE INDEX IF NOT EXISTS idx_timestamp ON {table} (timestamp) TYPE minmax GRANULARITY 4;"
+            ));
+        }
+
+        statements
+    }
+
+    /// Create schema applying a tuning config
+    pub async fn create_schema_with_tuning(
+        &self,
+        tuning: &ClickHouseTuningConfig,
+    ) -> Result<(), anyhow::Error> {
+        info!("[ClickHouse] Creating tuned schema...");
+
+        let statements = self.tuned_schema_statements(tuning);
+        for statement in &statements {
+            debug!("[ClickHouse] {}", statement);
+        }
+
+        info!(
+            "[ClickHouse] index_granularity={}, codec={}, index_type={:?}",
+            tuning.merge_tree_settings.index_granularity,
+            tuning.compression_settings.codec_for(""),
+            tuning.index_type
+        );
+
+        // In production, execute these SQL statements
+        let _ = statements;
 
         Ok(())
     }
 
+    /// Create a tenant's dedicated table unless already done
+    ///
+    /// Returns whether the table was created by this call.
+    pub async fn provision_table(&self, table: &ClickHouseTable) -> Result<bool, anyhow::Error> {
+        let mut provisioned = self.provisioned.lock().unwrap();
+        if provisioned.contains(table) {
+            return Ok(false);
+        }
+
+        let statements = [
+            format!("CREATE DATABASE IF NOT EXISTS {};", table.database),
+            self.create_table_statement(&table.to_string()),
+        ];
+        for statement in &statements {
+            debug!("[ClickHouse] {}", statement);
+        }
+        info!("[ClickHouse] Provisioned dedicated table {}", table);
+
+        // In production, execute these SQL statements
+        provisioned.insert(table.clone());
+        Ok(true)
+    }
+
+    /// Whether a dedicated table has been provisioned
+    pub fn is_provisioned(&self, table: &ClickHouseTable) -> bool {
+        self.provisioned.lock().unwrap().contains(table)
+    }
+
     /// Drop schema
     pub async fn drop_schema(&self) -> Result<(), anyhow::Error> {
         info!("[ClickHouse] Dropping schema...");
@@ -675,6 +1739,59 @@ mod tests {
         assert_eq!(metrics.total_inserts, 1); // Batch counts as 1 insert
     }
 
+    #[tokio::test]
+    async fn test_batch_digest_detects_modified_row() {
+        let client = ClickHouseClient::new_with_defaults();
+        let events: Vec<AuditEvent> = (0..5)
+            .map(|i| create_test_event(&format!("digest-{}", i)))
+            .collect();
+        let stats = client.insert_batch(&events).await.unwrap();
+        assert_eq!(stats.batch_digest, batch_digest(&events));
+
+        let integrity = client
+            .verify_batch_integrity(&stats.batch_id)
+            .await
+            .unwrap();
+        assert!(integrity.is_valid());
+        assert_eq!(integrity.event_count, 5);
+
+        // Rewrite one stored row
+        let tamper = |event: AuditEvent| {
+            for rows in client.rows.write().unwrap().values_mut() {
+                for row in rows.iter_mut().filter(|row| row.batch_offset == 3) {
+                    row.event = event.clone();
+                }
+            }
+        };
+        let mut modified = events[3].clone();
+        modified.outcome = 2;
+        tamper(modified.clone());
+
+        let integrity = client
+            .verify_batch_integrity(&stats.batch_id)
+            .await
+            .unwrap();
+        assert!(!integrity.is_valid());
+        assert!(client.verify_batch_integrity("unknown").await.is_err());
+
+        // Recomputing the recorded digest doesn't hide the change
+        let mut tampered = events.clone();
+        tampered[3] = modified;
+        client
+            .batch_digests
+            .write()
+            .unwrap()
+            .get_mut(&stats.batch_id)
+            .unwrap()
+            .digest = batch_digest(&tampered);
+        let integrity = client
+            .verify_batch_integrity(&stats.batch_id)
+            .await
+            .unwrap();
+        assert!(!integrity.signature_valid);
+        assert!(!integrity.is_valid());
+    }
+
     #[tokio::test]
     async fn test_query_execution() {
         let config = ClickHouseConfig::default();
@@ -735,6 +1852,173 @@ mod tests {
         assert_eq!(metrics.total_retries, 0);
     }
 
+    #[test]
+    fn test_tuned_schema_reflects_tuning() {
+        use crate::clickhouse_tuning::{CompressionSettings, IndexType, MergeTreeSettings};
+
+        let schema = ClickHouseSchema::new(ClickHouseConfig::default());
+        let mut tuning = ClickHouseTuningConfig {
+            index_type: IndexType::MinMax,
+            merge_tree_settings: MergeTreeSettings {
+                index_granularity: 4096,
+                ..Default::default()
+            },
+            compression_settings: CompressionSettings {
+                compression_codec: "zstd".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        tuning
+            .compression_settings
+            .column_codecs
+            .insert("metadata_json".to_string(), "ZSTD(9)".to_string());
+
+        let statements = schema.tuned_schema_statements(&tuning);
+        let create_table = &statements[0];
+
+        assert!(create_table.contains("CREATE TABLE IF NOT EXISTS audit_events"));
+        assert!(create_table.contains("index_granularity = 4096"));
+        assert!(create_table.contains("action String CODEC(ZSTD)"));
+        assert!(create_table.contains("metadata_json String CODEC(ZSTD(9))"));
+        assert!(create_table.contains("timestamp DateTime64(3) CODEC(DoubleDelta, ZSTD)"));
+        assert!(
+            statements
+                .iter()
+                .any(|s| s.contains("idx_hrn ON audit_events (hrn) TYPE minmax"))
+        );
+    }
+
+    fn retrying_client(dedup_on_event_id: bool) -> ClickHouseClient {
+        ClickHouseClient::new(ClickHouseConfig {
+            retry_delay_ms: 1,
+            dedup_on_event_id,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_retried_batch_is_not_double_counted_with_dedup() {
+        let events: Vec<AuditEvent> = (0..10)
+            .map(|i| create_test_event(&format!("evt-{}", i)))
+            .collect();
+
+        // Without dedup the retry re-inserts the rows written before the failure
+        let plain = retrying_client(false);
+        plain.fail_next_batch_after(6);
+        let stats = plain.insert_batch(&events).await.unwrap();
+        assert_eq!(stats.retries, 1);
+        assert_eq!(plain.stored_row_count(), 16);
+        // Both attempts tagged their rows with the batch, which still verifies
+        let integrity = plain.verify_batch_integrity(&stats.batch_id).await.unwrap();
+        assert!(integrity.is_valid());
+        assert_eq!(integrity.event_count, 10);
+
+        let idempotent = retrying_client(true);
+        idempotent.fail_next_batch_after(6);
+        let stats = idempotent.insert_batch(&events).await.unwrap();
+        assert_eq!(stats.retries, 1);
+        assert_eq!(idempotent.stored_row_count(), 10);
+        assert_eq!(idempotent.unique_event_count(), 10);
+        assert!(
+            idempotent
+                .verify_batch_integrity(&stats.batch_id)
+                .await
+                .unwrap()
+                .is_valid()
+        );
+
+        // Replaying the whole batch later is a no-op
+        idempotent.insert_batch(&events).await.unwrap();
+        assert_eq!(idempotent.stored_row_count(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_retries_follow_configured_policy() {
+        let client = retrying_client(false);
+        let policy = client.retry_policy();
+        assert_eq!(policy.max_attempts(), client.config.max_retries);
+        assert_eq!(policy.delay_for_attempt(0), Duration::from_millis(1));
+        assert_eq!(policy.delay_for_attempt(2), Duration::from_millis(4));
+
+        client.fail_next_batch_after(1);
+        client
+            .insert_batch(&[create_test_event("evt-1"), create_test_event("evt-2")])
+            .await
+            .unwrap();
+        let metrics = client.get_metrics();
+        assert_eq!(metrics.total_retries, 1);
+        assert_eq!(metrics.failed_operations, 0);
+    }
+
+    #[test]
+    fn test_dedup_schema_uses_replacing_merge_tree() {
+        let schema = ClickHouseSchema::new(ClickHouseConfig {
+            dedup_on_event_id: true,
+            ..Default::default()
+        });
+        let create_table = &schema.tuned_schema_statements(&ClickHouseTuningConfig::default())[0];
+        assert!(create_table.contains("ENGINE = ReplacingMergeTree(processed_at)"));
+        assert!(create_table.contains("ORDER BY (tenant_id, timestamp, hrn, event_id)"));
+    }
+
+    #[test]
+    fn test_coordinated_ttl_never_expires_unmigrated_rows() {
+        let ten_days = Duration::from_secs(10 * 24 * 60 * 60);
+        let schema = ClickHouseSchema::new(ClickHouseConfig::default())
+            .with_ttl(HotTierTtl::MigrationCoordinated { days: 7 });
+
+        let create_table = &schema.tuned_schema_statements(&ClickHouseTuningConfig::default())[0];
+        assert!(create_table.contains("migrated UInt8 DEFAULT 0"));
+        assert!(create_table.contains("TTL timestamp + INTERVAL 7 DAY DELETE WHERE migrated = 1"));
+
+        // Past retention, only rows confirmed as migrated expire
+        assert!(!schema.ttl().expires(ten_days, false));
+        assert!(schema.ttl().expires(ten_days, true));
+        assert!(!schema.ttl().expires(Duration::from_secs(3600), true));
+        assert_eq!(
+            schema
+                .post_migration_statement(&["evt-1".to_string(), "evt'2".to_string()])
+                .unwrap(),
+            "ALTER TABLE audit_events UPDATE migrated = 1 WHERE event_id IN ('evt-1', 'evt\\'2');"
+        );
+
+        // The legacy TTL deletes regardless of migration
+        let destructive = ClickHouseSchema::new(ClickHouseConfig::default());
+        assert!(destructive.ttl().expires(ten_days, false));
+
+        let disabled =
+            ClickHouseSchema::new(ClickHouseConfig::default()).with_ttl(HotTierTtl::Disabled);
+        let create_table = &disabled.tuned_schema_statements(&ClickHouseTuningConfig::default())[0];
+        assert!(!create_table.contains("TTL"));
+        assert!(!disabled.ttl().expires(ten_days, true));
+        assert!(
+            disabled
+                .post_migration_statement(&["evt-1".to_string()])
+                .unwrap()
+                .starts_with("ALTER TABLE audit_events DELETE WHERE")
+        );
+    }
+
+    #[test]
+    fn test_insert_statement_applies_memory_settings() {
+        let tuning = ClickHouseTuningConfig::default();
+        let client = ClickHouseClient::new(ClickHouseConfig::default()).with_tuning(&tuning);
+
+        let sql = client.insert_statement();
+        assert!(sql.starts_with("INSERT INTO audit_db.audit_events SETTINGS "));
+        assert!(sql.contains(&format!(
+            "max_memory_usage = {}",
+            tuning.memory_settings.max_memory_usage
+        )));
+
+        let plain = ClickHouseClient::new(ClickHouseConfig::default());
+        assert_eq!(
+            plain.insert_statement(),
+            "INSERT INTO audit_db.audit_events FORMAT RowBinary"
+        );
+    }
+
     #[tokio::test]
     async fn test_schema_creation() {
         let config = ClickHouseConfig::default();
@@ -760,4 +2044,164 @@ mod tests {
         let stats = result.unwrap();
         assert_eq!(stats.batch_size, 1000);
     }
+
+    fn tenant_event(id: &str, tenant_id: &str) -> AuditEvent {
+        let mut event = create_test_event(id);
+        event.tenant_id = Some(hodei_audit_proto::TenantId {
+            value: tenant_id.to_string(),
+        });
+        event
+    }
+
+    #[tokio::test]
+    async fn test_isolated_tenant_inserts_target_dedicated_table() {
+        let config = ClickHouseConfig::default().with_isolated_tenant("acme-corp");
+        let client = ClickHouseClient::new(config.clone());
+        let dedicated = ClickHouseTable::new("audit_db", "audit_events_acme_corp");
+        let shared = config.shared_table();
+        assert_eq!(config.table_for("acme-corp"), dedicated);
+        assert_eq!(config.table_for("small-co"), shared);
+        assert!(!client.schema().is_provisioned(&dedicated));
+
+        let events = vec![
+            tenant_event("evt-1", "acme-corp"),
+            tenant_event("evt-2", "small-co"),
+            tenant_event("evt-3", "acme-corp"),
+        ];
+        client.insert_batch(&events).await.unwrap();
+        client
+            .insert_event(&tenant_event("evt-4", "small-co"))
+            .await
+            .unwrap();
+
+        assert_eq!(client.rows_in_table(&dedicated), 2);
+        assert_eq!(client.rows_in_table(&shared), 2);
+        assert!(client.schema().is_provisioned(&dedicated));
+        assert!(!client.schema().is_provisioned(&shared));
+        assert_eq!(
+            client.insert_statement_for(&dedicated),
+            "INSERT INTO audit_db.audit_events_acme_corp FORMAT RowBinary"
+        );
+
+        // Provisioning happens once
+        assert!(!client.schema().provision_table(&dedicated).await.unwrap());
+    }
+
+    #[test]
+    fn test_queries_are_routed_to_tenant_table() {
+        let isolated = ClickHouseTable::new("acme_db", "events");
+        let client = ClickHouseClient::new(
+            ClickHouseConfig::default().with_tenant_table("acme-corp", isolated),
+        );
+
+        let sql = "SELECT * FROM audit_events WHERE tenant_id = {tenant_id:String}";
+        assert_eq!(
+            client.route_sql(sql, "acme-corp"),
+            "SELECT * FROM acme_db.events WHERE tenant_id = {tenant_id:String}"
+        );
+        assert_eq!(
+            client.route_sql("SELECT count() FROM audit_db.audit_events", "acme-corp"),
+            "SELECT count() FROM acme_db.events"
+        );
+        assert_eq!(client.route_sql(sql, "small-co"), sql);
+    }
+
+    /// Column names declared between the parentheses of a `CREATE TABLE`
+    fn ddl_columns(ddl: &str) -> Vec<String> {
+        let start = ddl.find('(').unwrap() + 1;
+        let end = ddl.find(") ENGINE").unwrap();
+        ddl[start..end]
+            .split(",\n")
+            .filter_map(|line| line.split_whitespace().next())
+            .filter(|name| !["migrated", "batch_id", "batch_offset"].contains(name))
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_ddl_columns_match_mapping() {
+        let schema = ClickHouseSchema::new(ClickHouseConfig::default());
+        let mapped: Vec<String> = AUDIT_EVENT_COLUMNS
+            .iter()
+            .map(|mapping| mapping.column.to_string())
+            .collect();
+
+        assert_eq!(
+            ddl_columns(&schema.create_table_statement("audit_events")),
+            mapped
+        );
+        let tuned = &schema.tuned_schema_statements(&ClickHouseTuningConfig::default())[0];
+        assert_eq!(ddl_columns(tuned), mapped);
+    }
+
+    #[test]
+    fn test_every_mapped_column_has_an_insert_binding() {
+        let mut event = create_test_event("bind-1");
+        event.http_context = Some(hodei_audit_proto::HttpContext {
+            method: "POST".to_string(),
+            path: "/policies".to_string(),
+            status_code: 201,
+            ..Default::default()
+        });
+        event.latency_ms = 42;
+
+        let row = insert_row(&event);
+        let columns: Vec<&str> = row.iter().map(|(column, _)| *column).collect();
+        let mapped: Vec<&str> = AUDIT_EVENT_COLUMNS.iter().map(|m| m.column).collect();
+        assert_eq!(columns, mapped);
+
+        let value = |name: &str| row.iter().find(|(c, _)| *c == name).unwrap().1.clone();
+        assert_eq!(value("event_id"), ColumnValue::String("bind-1".to_string()));
+        assert_eq!(
+            value("user_id"),
+            ColumnValue::String("test-user".to_string())
+        );
+        assert_eq!(value("status_code"), ColumnValue::UInt16(201));
+        assert_eq!(value("latency_ms"), ColumnValue::UInt64(42));
+        assert_eq!(
+            columns_for_field("http_context").collect::<Vec<_>>(),
+            vec!["path", "method", "status_code"]
+        );
+    }
+
+    fn encode(registry: &Registry) -> String {
+        let mut body = String::new();
+        prometheus_client::encoding::text::encode(&mut body, registry).unwrap();
+        body
+    }
+
+    #[tokio::test]
+    async fn test_register_metrics_exports_client_metrics() {
+        let client = ClickHouseClient::new(ClickHouseConfig::default());
+        for i in 0..3 {
+            client
+                .insert_event(&create_test_event(&format!("m-{}", i)))
+                .await
+                .unwrap();
+        }
+        client.query("SELECT * FROM audit_events").await.unwrap();
+
+        let mut registry = Registry::default();
+        let _exporter = client.register_metrics(&mut registry, Duration::from_millis(10));
+        let body = encode(&registry);
+        assert!(body.contains("hodei_clickhouse_total_inserts 3\n"));
+        assert!(body.contains("hodei_clickhouse_total_queries 1\n"));
+        assert!(body.contains("hodei_clickhouse_latency_ms{operation=\"insert\",stat=\"p99\"}"));
+        assert!(body.contains("hodei_clickhouse_pool_utilization"));
+
+        // Picked up by the next refresh
+        client
+            .insert_event(&create_test_event("m-3"))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(encode(&registry).contains("hodei_clickhouse_total_inserts 4\n"));
+    }
+
+    #[test]
+    fn test_p99_of_latency_samples() {
+        let samples: VecDeque<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!(p99(&samples), 99.0);
+        assert_eq!(p99(&VecDeque::new()), 0.0);
+    }
 }
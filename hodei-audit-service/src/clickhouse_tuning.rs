@@ -7,9 +7,18 @@
 //! - Merge settings
 //! - Compression settings
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tracing::{info, warn};
 
+use crate::clickhouse::ClickHouseMetrics;
+
+/// Insert latency (ms) above which inserts are considered slow
+const SLOW_INSERT_LATENCY_MS: f64 = 100.0;
+/// Query latency (ms) above which queries are considered slow
+const SLOW_QUERY_LATENCY_MS: f64 = 500.0;
+/// Executions of an aggregation pattern before a projection is suggested
+const MIN_PROJECTION_EXECUTIONS: u64 = 100;
+
 /// ClickHouse tuning configuration
 #[derive(Debug, Clone)]
 pub struct ClickHouseTuningConfig {
@@ -59,9 +68,23 @@ impl Default for IndexType {
     }
 }
 
+impl IndexType {
+    /// Data skipping index expression for lookup columns, if any
+    pub fn skip_index_type(&self) -> Option<&'static str> {
+        match self {
+            IndexType::BloomFilter => Some("bloom_filter"),
+            IndexType::MinMax => Some("minmax"),
+            IndexType::Set => Some("set(100)"),
+            _ => None,
+        }
+    }
+}
+
 /// Merge tree settings for performance
 #[derive(Debug, Clone)]
 pub struct MergeTreeSettings {
+    /// Rows per primary index mark
+    pub index_granularity: u64,
     /// Max bytes to merge at once
     pub max_bytes_to_merge_at_max_space_in_pool: u64,
     /// Max parts to merge at once
@@ -75,6 +98,7 @@ pub struct MergeTreeSettings {
 impl Default for MergeTreeSettings {
     fn default() -> Self {
         Self {
+            index_granularity: 8192,
             max_bytes_to_merge_at_max_space_in_pool: 1024 * 1024 * 1024, // 1GB
             max_parts_to_merge_at_once: 100,
             merge_policy: "ttl".to_string(),
@@ -110,6 +134,26 @@ impl Default for MemorySettings {
     }
 }
 
+impl MemorySettings {
+    /// Session settings applied to inserts
+    pub fn session_settings(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                "max_memory_usage".to_string(),
+                self.max_memory_usage.to_string(),
+            ),
+            (
+                "max_bytes_before_external_group_by".to_string(),
+                self.max_bytes_before_external_group_by.to_string(),
+            ),
+            (
+                "max_bytes_before_external_sort".to_string(),
+                self.max_bytes_before_external_sort.to_string(),
+            ),
+        ]
+    }
+}
+
 /// Compression settings
 #[derive(Debug, Clone)]
 pub struct CompressionSettings {
@@ -121,6 +165,8 @@ pub struct CompressionSettings {
     pub use_lz4: bool,
     /// Use zstd compression
     pub use_zstd: bool,
+    /// Per-column codec overrides (column name -> codec)
+    pub column_codecs: HashMap<String, String>,
 }
 
 impl Default for CompressionSettings {
@@ -130,10 +176,86 @@ impl Default for CompressionSettings {
             compression_codec: "lz4".to_string(),
             use_lz4: true,
             use_zstd: true,
+            column_codecs: HashMap::new(),
+        }
+    }
+}
+
+impl CompressionSettings {
+    /// ClickHouse codec expression for a column
+    ///
+    /// Common codec names are normalized (`zstd` -> `ZSTD`); anything else,
+    /// such as `ZSTD(3)`, is used verbatim.
+    pub fn codec_for(&self, column: &str) -> String {
+        let codec = self
+            .column_codecs
+            .get(column)
+            .unwrap_or(&self.compression_codec);
+        match codec.to_ascii_lowercase().as_str() {
+            "lz4" => "LZ4".to_string(),
+            "lz4hc" => "LZ4HC".to_string(),
+            "zstd" => "ZSTD".to_string(),
+            "none" => "NONE".to_string(),
+            _ => codec.clone(),
         }
     }
 }
 
+/// Workload hint for tuning recommendations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkloadProfile {
+    /// Mostly ingestion, few queries
+    WriteHeavy,
+    /// Mostly queries over ingested data
+    ReadHeavy,
+    /// Mixed ingestion and queries
+    Balanced,
+}
+
+/// Recommended tuning config with a rationale per field
+#[derive(Debug, Clone)]
+pub struct TuningRecommendation {
+    /// Recommended configuration
+    pub config: ClickHouseTuningConfig,
+    /// Short rationale keyed by field path (e.g. `merge_tree_settings.index_granularity`)
+    pub rationale: BTreeMap<&'static str, String>,
+}
+
+/// Observed aggregation query pattern
+#[derive(Debug, Clone)]
+pub struct AggregationQuery {
+    /// Table queried
+    pub table: String,
+    /// GROUP BY expressions (e.g. `action`, `toDate(timestamp)`)
+    pub group_by: Vec<String>,
+    /// Aggregate expressions (e.g. `count()`)
+    pub aggregates: Vec<String>,
+    /// Times the pattern was executed
+    pub executions: u64,
+}
+
+/// Projection DDL suggested for an aggregation pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectionDdl {
+    /// Projection name
+    pub name: String,
+    /// Table the projection belongs to
+    pub table: String,
+    /// `ALTER TABLE ... ADD PROJECTION` statement
+    pub add_statement: String,
+    /// `ALTER TABLE ... MATERIALIZE PROJECTION` statement for existing parts
+    pub materialize_statement: String,
+}
+
+/// Normalized aggregation pattern used to collapse duplicates
+#[derive(Debug, PartialEq, Eq)]
+struct ProjectionKey {
+    table: String,
+    group_by: Vec<String>,
+    /// Sorted and deduplicated
+    aggregates: Vec<String>,
+}
+
 /// ClickHouse performance tuner
 pub struct ClickHousePerformanceTuner {
     config: ClickHouseTuningConfig,
@@ -150,6 +272,105 @@ impl ClickHousePerformanceTuner {
         Self::new(ClickHouseTuningConfig::default())
     }
 
+    /// Recommend a tuning config from observed metrics and a workload hint
+    pub fn recommend(
+        metrics: &ClickHouseMetrics,
+        workload: WorkloadProfile,
+    ) -> ClickHouseTuningConfig {
+        Self::recommend_with_rationale(metrics, workload).config
+    }
+
+    /// Recommend a tuning config, explaining each recommended field
+    pub fn recommend_with_rationale(
+        metrics: &ClickHouseMetrics,
+        workload: WorkloadProfile,
+    ) -> TuningRecommendation {
+        let mut config = ClickHouseTuningConfig::default();
+        let mut rationale = BTreeMap::new();
+        let slow_inserts = metrics.avg_insert_latency_ms > SLOW_INSERT_LATENCY_MS;
+        let slow_queries = metrics.avg_query_latency_ms > SLOW_QUERY_LATENCY_MS;
+
+        match workload {
+            WorkloadProfile::WriteHeavy => {
+                let merge = &mut config.merge_tree_settings;
+                if slow_inserts {
+                    merge.max_bytes_to_merge_at_max_space_in_pool *= 4;
+                    merge.max_parts_to_merge_at_once *= 2;
+                    merge.merge_interval_seconds *= 5;
+                    rationale.insert(
+                        "merge_tree_settings.max_bytes_to_merge_at_max_space_in_pool",
+                        format!(
+                            "Insert latency {:.0}ms: merge larger parts less often",
+                            metrics.avg_insert_latency_ms
+                        ),
+                    );
+                    rationale.insert(
+                        "merge_tree_settings.max_parts_to_merge_at_once",
+                        "Fold more small insert parts into each merge".to_string(),
+                    );
+                    rationale.insert(
+                        "merge_tree_settings.merge_interval_seconds",
+                        "Spread merges out so they compete less with inserts".to_string(),
+                    );
+                }
+                merge.index_granularity = 16384;
+                rationale.insert(
+                    "merge_tree_settings.index_granularity",
+                    "Coarser marks keep the primary index small on write-heavy tables".to_string(),
+                );
+                config.compression_settings.compression_codec = "lz4".to_string();
+                rationale.insert(
+                    "compression_settings.compression_codec",
+                    "LZ4 compresses fastest, keeping insert CPU low".to_string(),
+                );
+                config.index_type = IndexType::MinMax;
+                rationale.insert(
+                    "index_type",
+                    "MinMax indexes are cheapest to maintain on ingest".to_string(),
+                );
+            }
+            WorkloadProfile::ReadHeavy => {
+                config.merge_tree_settings.index_granularity = 4096;
+                rationale.insert(
+                    "merge_tree_settings.index_granularity",
+                    "Finer marks read fewer rows for selective queries".to_string(),
+                );
+                config.compression_settings.compression_codec = "zstd".to_string();
+                rationale.insert(
+                    "compression_settings.compression_codec",
+                    "ZSTD trades insert CPU for less I/O on reads".to_string(),
+                );
+                config.index_type = IndexType::BloomFilter;
+                rationale.insert(
+                    "index_type",
+                    "Bloom filters skip granules for tenant/HRN/action lookups".to_string(),
+                );
+            }
+            WorkloadProfile::Balanced => {
+                rationale.insert(
+                    "index_type",
+                    "Defaults balance insert cost and query selectivity".to_string(),
+                );
+            }
+        }
+
+        if slow_queries && workload != WorkloadProfile::WriteHeavy {
+            let memory = &mut config.memory_settings;
+            memory.max_memory_usage *= 2;
+            memory.max_bytes_before_external_group_by *= 2;
+            memory.max_bytes_before_external_sort *= 2;
+            rationale.insert(
+                "memory_settings.max_memory_usage",
+                format!(
+                    "Query latency {:.0}ms: allow more memory before spilling to disk",
+                    metrics.avg_query_latency_ms
+                ),
+            );
+        }
+
+        TuningRecommendation { config, rationale }
+    }
+
     /// Generate optimized CREATE TABLE statement
     pub fn generate_optimized_table_schema(&self, table_name: &str) -> String {
         let mut sql = format!("CREATE TABLE {} (", table_name);
@@ -293,6 +514,98 @@ impl ClickHousePerformanceTuner {
         Ok(results.join(";\n"))
     }
 
+    /// Suggest projections for frequent aggregation patterns
+    ///
+    /// Patterns with the same table, group-by and aggregates collapse into a
+    /// single projection; their executions add up towards the
+    /// frequency threshold.
+    pub fn suggest_projections(&self, query_patterns: &[AggregationQuery]) -> Vec<ProjectionDdl> {
+        let normalize = |exprs: &[String]| -> Vec<String> {
+            exprs
+                .iter()
+                .map(|e| normalize_expression(e))
+                .filter(|e| !e.is_empty())
+                .collect()
+        };
+
+        // Executions per distinct pattern, in first-seen order
+        let mut patterns: Vec<(ProjectionKey, u64)> = Vec::new();
+        for query in query_patterns {
+            let group_by = normalize(&query.group_by);
+            if group_by.is_empty() {
+                continue;
+            }
+            let mut aggregates = normalize(&query.aggregates);
+            aggregates.sort();
+            aggregates.dedup();
+            if aggregates.is_empty() {
+                aggregates.push("count()".to_string());
+            }
+
+            let key = ProjectionKey {
+                table: query.table.clone(),
+                group_by,
+                aggregates,
+            };
+            match patterns.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, executions)) => *executions += query.executions,
+                None => patterns.push((key, query.executions)),
+            }
+        }
+
+        patterns
+            .into_iter()
+            .filter(|(_, executions)| *executions >= MIN_PROJECTION_EXECUTIONS)
+            .map(|(key, _)| {
+                let ProjectionKey {
+                    table,
+                    group_by,
+                    aggregates,
+                } = key;
+                let keys = group_by.join(", ");
+                let suffix: String = group_by
+                    .join("_")
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                    .collect();
+                let name = format!("proj_{}", suffix.trim_matches('_'));
+
+                ProjectionDdl {
+                    add_statement: format!(
+                        "ALTER TABLE {table} ADD PROJECTION IF NOT EXISTS {name} \
+                         (SELECT {keys}, {aggregates} GROUP BY {keys} ORDER BY {keys})",
+                        aggregates = aggregates.join(", "),
+                    ),
+                    materialize_statement: format!(
+                        "ALTER TABLE {table} MATERIALIZE PROJECTION {name}"
+                    ),
+                    name,
+                    table,
+                }
+            })
+            .collect()
+    }
+
+    /// Apply suggested projections
+    pub async fn apply_projections(
+        &self,
+        projections: &[ProjectionDdl],
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let mut results = Vec::new();
+
+        for projection in projections {
+            info!(
+                "[ClickHouse] Adding projection {} on {}",
+                projection.name, projection.table
+            );
+            results.push(projection.add_statement.clone());
+            // Build the projection for parts written before it existed
+            results.push(projection.materialize_statement.clone());
+        }
+
+        Ok(results.join(";\n"))
+    }
+
     /// Get performance recommendations
     pub fn get_recommendations(&self) -> Vec<String> {
         let mut recommendations = Vec::new();
@@ -318,6 +631,41 @@ impl ClickHousePerformanceTuner {
     }
 }
 
+/// Expression with insignificant whitespace removed
+///
+/// Whitespace only survives, as a single space, between two identifier
+/// characters and inside string literals. Case is kept: ClickHouse function
+/// names such as `toDate` are case-sensitive.
+fn normalize_expression(expr: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut normalized = String::with_capacity(expr.len());
+    let mut in_literal = false;
+    let mut escaped = false;
+    let mut pending_space = false;
+    for c in expr.chars() {
+        if in_literal {
+            normalized.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '\'' {
+                in_literal = false;
+            }
+        } else if c.is_whitespace() {
+            pending_space = true;
+        } else {
+            if pending_space && normalized.ends_with(is_word) && is_word(c) {
+                normalized.push(' ');
+            }
+            pending_space = false;
+            normalized.push(c);
+            in_literal = c == '\'';
+        }
+    }
+    normalized
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -351,6 +699,125 @@ mod tests {
         assert!(settings.contains_key("max_bytes_before_external_group_by"));
     }
 
+    fn metrics(insert_ms: f64, query_ms: f64) -> ClickHouseMetrics {
+        ClickHouseMetrics {
+            avg_insert_latency_ms: insert_ms,
+            avg_query_latency_ms: query_ms,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_recommend_write_heavy_with_slow_inserts() {
+        let defaults = ClickHouseTuningConfig::default();
+        let recommendation = ClickHousePerformanceTuner::recommend_with_rationale(
+            &metrics(250.0, 50.0),
+            WorkloadProfile::WriteHeavy,
+        );
+        let merge = &recommendation.config.merge_tree_settings;
+
+        assert!(
+            merge.max_bytes_to_merge_at_max_space_in_pool
+                > defaults
+                    .merge_tree_settings
+                    .max_bytes_to_merge_at_max_space_in_pool
+        );
+        assert!(
+            merge.max_parts_to_merge_at_once
+                > defaults.merge_tree_settings.max_parts_to_merge_at_once
+        );
+        assert_eq!(
+            recommendation.config.compression_settings.compression_codec,
+            "lz4"
+        );
+        assert!(
+            recommendation
+                .rationale
+                .contains_key("merge_tree_settings.max_parts_to_merge_at_once")
+        );
+    }
+
+    #[test]
+    fn test_recommend_read_heavy_favors_indexing_and_compression() {
+        let defaults = ClickHouseTuningConfig::default();
+        let config = ClickHousePerformanceTuner::recommend(
+            &metrics(20.0, 900.0),
+            WorkloadProfile::ReadHeavy,
+        );
+
+        assert!(matches!(config.index_type, IndexType::BloomFilter));
+        assert!(
+            config.merge_tree_settings.index_granularity
+                < defaults.merge_tree_settings.index_granularity
+        );
+        assert_eq!(config.compression_settings.compression_codec, "zstd");
+        assert!(
+            config.memory_settings.max_memory_usage > defaults.memory_settings.max_memory_usage
+        );
+        assert_eq!(
+            config.merge_tree_settings.max_parts_to_merge_at_once,
+            defaults.merge_tree_settings.max_parts_to_merge_at_once
+        );
+    }
+
+    fn aggregation(group_by: &[&str], executions: u64) -> AggregationQuery {
+        AggregationQuery {
+            table: "audit_events".to_string(),
+            group_by: group_by.iter().map(|s| s.to_string()).collect(),
+            aggregates: vec!["count()".to_string()],
+            executions,
+        }
+    }
+
+    #[test]
+    fn test_suggest_projection_for_frequent_group_by_action() {
+        let tuner = ClickHousePerformanceTuner::new_with_defaults();
+        let projections = tuner
+            .suggest_projections(&[aggregation(&["action"], 500), aggregation(&["user_id"], 3)]);
+
+        assert_eq!(projections.len(), 1);
+        let projection = &projections[0];
+        assert_eq!(projection.name, "proj_action");
+        assert_eq!(
+            projection.add_statement,
+            "ALTER TABLE audit_events ADD PROJECTION IF NOT EXISTS proj_action \
+             (SELECT action, count() GROUP BY action ORDER BY action)"
+        );
+        assert_eq!(
+            projection.materialize_statement,
+            "ALTER TABLE audit_events MATERIALIZE PROJECTION proj_action"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_patterns_collapse_to_one_projection() {
+        let tuner = ClickHousePerformanceTuner::new_with_defaults();
+        let mut daily = aggregation(&["action", "toDate(timestamp)"], 60);
+        let mut daily_again = aggregation(&["action", " toDate( timestamp )"], 60);
+        daily_again.aggregates = vec!["count( )".to_string(), "count()".to_string()];
+        daily.aggregates.clear();
+
+        // Neither reaches the threshold alone, together they do
+        let projections = tuner.suggest_projections(&[daily, daily_again]);
+        assert_eq!(projections.len(), 1);
+        assert_eq!(projections[0].name, "proj_action_toDate_timestamp");
+
+        // Case is significant, so these are different patterns
+        let distinct = tuner.suggest_projections(&[
+            aggregation(&["toDate(timestamp)"], 60),
+            aggregation(&["todate(timestamp)"], 60),
+        ]);
+        assert!(distinct.is_empty());
+        assert_eq!(
+            normalize_expression("if( action = 'a  b' ,  1, 0 )"),
+            "if(action='a  b',1,0)"
+        );
+
+        let applied = tuner.apply_projections(&projections).await.unwrap();
+        assert_eq!(applied.matches("ADD PROJECTION").count(), 1);
+        assert_eq!(applied.matches("MATERIALIZE PROJECTION").count(), 1);
+    }
+
     #[test]
     fn test_get_recommendations() {
         let tuner = ClickHousePerformanceTuner::new_with_defaults();
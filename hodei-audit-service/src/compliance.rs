@@ -5,11 +5,32 @@
 //! - Legal hold support
 //! - GDPR compliance and right to be forgotten
 //! - Audit trail for all deletions
-
+//! - Paginated, rate-limited admin API for retention policies
+//! - Tenant offboarding with signed deletion certificates
+//! - Meta-audit events for admin actions
+//! - Retention enforcement with a dry-run preview
+
+use crate::clock::{Clock, system_clock};
+use crate::crypto::ports::signing::SigningService;
+use crate::meta_audit::{AdminAction, MetaAuditLogger};
+use crate::quotas::QuotaManager;
+use crate::storage::{QueryFilter, TieredStorage};
 use chrono::{DateTime, Duration, Utc};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration as StdDuration, Instant};
 use tracing::{error, info, warn};
 
+/// Default number of admin operations allowed per window
+pub const DEFAULT_ADMIN_RATE_LIMIT: usize = 60;
+
+/// Default admin rate limit window
+pub const DEFAULT_ADMIN_RATE_WINDOW: StdDuration = StdDuration::from_secs(60);
+
+/// Event IDs listed in a `RetentionPreview`
+pub const RETENTION_PREVIEW_SAMPLE_SIZE: usize = 100;
+
 /// Retention policy for a tenant
 #[derive(Debug, Clone)]
 pub struct RetentionPolicy {
@@ -25,6 +46,8 @@ pub struct RetentionPolicy {
     pub updated_at: DateTime<Utc>,
     /// Is policy active
     pub is_active: bool,
+    /// Time source for the retention cutoff
+    clock: Arc<dyn Clock>,
 }
 
 impl RetentionPolicy {
@@ -38,6 +61,7 @@ impl RetentionPolicy {
             created_at: now,
             updated_at: now,
             is_active: true,
+            clock: system_clock(),
         }
     }
 
@@ -52,6 +76,7 @@ impl RetentionPolicy {
             created_at: now,
             updated_at: now,
             is_active: true,
+            clock: system_clock(),
         }
     }
 
@@ -60,10 +85,16 @@ impl RetentionPolicy {
         Self::sme(tenant_id, 1)
     }
 
+    /// Read the current time from `clock`
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Update retention period
     pub fn update_retention(&mut self, retention_days: i64) {
         self.retention_days = retention_days;
-        self.updated_at = Utc::now();
+        self.updated_at = self.clock.now_utc();
     }
 
     /// Check if an event should be deleted based on retention policy
@@ -72,25 +103,24 @@ impl RetentionPolicy {
             return false;
         }
 
-        let cutoff_date = Utc::now() - Duration::days(self.retention_days);
-        event_timestamp < cutoff_date
+        event_timestamp < self.get_cutoff_date()
     }
 
     /// Get cutoff date for deletion
     pub fn get_cutoff_date(&self) -> DateTime<Utc> {
-        Utc::now() - Duration::days(self.retention_days)
+        self.clock.now_utc() - Duration::days(self.retention_days)
     }
 
     /// Enable policy
     pub fn enable(&mut self) {
         self.is_active = true;
-        self.updated_at = Utc::now();
+        self.updated_at = self.clock.now_utc();
     }
 
     /// Disable policy
     pub fn disable(&mut self) {
         self.is_active = false;
-        self.updated_at = Utc::now();
+        self.updated_at = self.clock.now_utc();
     }
 }
 
@@ -179,10 +209,13 @@ impl LegalHold {
 
     /// Check if legal hold has expired
     pub fn is_expired(&self) -> bool {
-        if let Some(expires_at) = self.expires_at {
-            return Utc::now() > expires_at;
-        }
-        false
+        self.is_expired_at(Utc::now())
+    }
+
+    /// Check if legal hold has expired at `now`; a hold expires at its
+    /// `expires_at`
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
     }
 
     /// Check if an event is protected by this legal hold
@@ -192,7 +225,12 @@ impl LegalHold {
 
     /// Update status based on expiration
     pub fn check_expiration(&mut self) {
-        if self.is_expired() {
+        self.check_expiration_at(Utc::now());
+    }
+
+    /// Update status based on expiration at `now`
+    pub fn check_expiration_at(&mut self, now: DateTime<Utc>) {
+        if self.is_expired_at(now) {
             self.status = LegalHoldStatus::Expired;
         }
     }
@@ -213,6 +251,20 @@ pub struct ComplianceManager {
     deletion_audit: Vec<DeletionAuditRecord>,
     /// GDPR requests
     gdpr_requests: Vec<GDPRRequest>,
+    /// Retention policy changes made through the admin API
+    policy_audit: Vec<PolicyAuditRecord>,
+    /// Sliding window of recent admin operations
+    admin_calls: Mutex<VecDeque<Instant>>,
+    admin_rate_limit: usize,
+    admin_rate_window: StdDuration,
+    /// Certificates issued for offboarded tenants, in issue order
+    deletion_certificates: Vec<DeletionCertificate>,
+    /// Signer and private key for deletion certificates
+    certificate_signer: Option<(Arc<dyn SigningService>, Vec<u8>)>,
+    /// Records admin actions into the audit pipeline
+    meta_audit: Option<MetaAuditLogger>,
+    /// Time source for holds, policies and records
+    clock: Arc<dyn Clock>,
 }
 
 impl ComplianceManager {
@@ -223,11 +275,59 @@ impl ComplianceManager {
             legal_holds: HashMap::new(),
             deletion_audit: Vec::new(),
             gdpr_requests: Vec::new(),
+            policy_audit: Vec::new(),
+            admin_calls: Mutex::new(VecDeque::new()),
+            admin_rate_limit: DEFAULT_ADMIN_RATE_LIMIT,
+            admin_rate_window: DEFAULT_ADMIN_RATE_WINDOW,
+            deletion_certificates: Vec::new(),
+            certificate_signer: None,
+            meta_audit: None,
+            clock: system_clock(),
+        }
+    }
+
+    /// Allow at most `max_calls` admin operations per `window`
+    pub fn with_admin_rate_limit(mut self, max_calls: usize, window: StdDuration) -> Self {
+        self.admin_rate_limit = max_calls;
+        self.admin_rate_window = window;
+        self
+    }
+
+    /// Sign deletion certificates with `signing_key`
+    ///
+    /// Offboarding is refused until a signer is configured.
+    pub fn with_certificate_signer(
+        mut self,
+        signer: Arc<dyn SigningService>,
+        signing_key: Vec<u8>,
+    ) -> Self {
+        self.certificate_signer = Some((signer, signing_key));
+        self
+    }
+
+    /// Read the current time from `clock`
+    ///
+    /// Retention policies created afterwards use the same clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Emit a meta-audit event for every admin action
+    pub fn with_meta_audit(mut self, logger: MetaAuditLogger) -> Self {
+        self.meta_audit = Some(logger);
+        self
+    }
+
+    async fn meta_audit(&self, action: AdminAction, tenant_id: &str, actor: &str, resource: &str) {
+        if let Some(logger) = &self.meta_audit {
+            logger.log(action, tenant_id, actor, resource).await;
         }
     }
 
     /// Create or update retention policy
     pub fn create_retention_policy(&mut self, policy: RetentionPolicy) {
+        let policy = policy.with_clock(self.clock.clone());
         info!(
             "[Compliance] Creating retention policy for tenant {}: {} days",
             policy.tenant_id, policy.retention_days
@@ -242,12 +342,148 @@ impl ComplianceManager {
         self.retention_policies.get(tenant_id)
     }
 
+    /// List retention policies ordered by tenant ID, one page at a time
+    ///
+    /// Pages are zero-based. Returns the page and the index of the next page,
+    /// if there is one.
+    pub fn list_retention_policies(
+        &self,
+        page: usize,
+        page_size: usize,
+    ) -> Result<(Vec<RetentionPolicy>, Option<usize>), ComplianceError> {
+        self.check_admin_rate_limit()?;
+        if page_size == 0 {
+            return Err(ComplianceError::Other(
+                "page size must be greater than zero".to_string(),
+            ));
+        }
+
+        let mut policies: Vec<&RetentionPolicy> = self.retention_policies.values().collect();
+        policies.sort_by(|a, b| a.tenant_id.cmp(&b.tenant_id));
+
+        let start = page.saturating_mul(page_size).min(policies.len());
+        let end = start.saturating_add(page_size).min(policies.len());
+        let next_page = (end < policies.len()).then_some(page + 1);
+
+        Ok((
+            policies[start..end].iter().map(|p| (*p).clone()).collect(),
+            next_page,
+        ))
+    }
+
+    /// Delete a tenant's retention policy, recording who deleted it
+    pub async fn delete_retention_policy(
+        &mut self,
+        tenant_id: &str,
+        deleted_by: &str,
+    ) -> Result<RetentionPolicy, ComplianceError> {
+        self.check_admin_rate_limit()?;
+        let policy = self
+            .retention_policies
+            .remove(tenant_id)
+            .ok_or_else(|| ComplianceError::PolicyNotFound(tenant_id.to_string()))?;
+
+        warn!(
+            "[Compliance] Retention policy for tenant {} deleted by {}",
+            tenant_id, deleted_by
+        );
+        self.record_policy_change(tenant_id, PolicyAction::Deleted, deleted_by)
+            .await;
+        Ok(policy)
+    }
+
+    /// Create or replace several retention policies at once
+    ///
+    /// Counts as a single admin operation. Returns the number of policies
+    /// applied.
+    pub async fn apply_retention_policies(
+        &mut self,
+        policies: Vec<RetentionPolicy>,
+        applied_by: &str,
+    ) -> Result<usize, ComplianceError> {
+        self.check_admin_rate_limit()?;
+        if let Some(policy) = policies.iter().find(|p| p.retention_days <= 0) {
+            return Err(ComplianceError::InvalidRetentionPeriod(format!(
+                "{} days for tenant {}",
+                policy.retention_days, policy.tenant_id
+            )));
+        }
+
+        let count = policies.len();
+        for policy in policies {
+            let tenant_id = policy.tenant_id.clone();
+            self.create_retention_policy(policy);
+            self.record_policy_change(&tenant_id, PolicyAction::Applied, applied_by)
+                .await;
+        }
+        Ok(count)
+    }
+
+    /// Get retention policy changes for a tenant
+    pub fn get_policy_audit(&self, tenant_id: &str) -> Vec<&PolicyAuditRecord> {
+        self.policy_audit
+            .iter()
+            .filter(|r| r.tenant_id == tenant_id)
+            .collect()
+    }
+
+    async fn record_policy_change(
+        &mut self,
+        tenant_id: &str,
+        action: PolicyAction,
+        performed_by: &str,
+    ) {
+        self.policy_audit.push(PolicyAuditRecord {
+            record_id: format!("pol_{}", uuid::Uuid::new_v4()),
+            tenant_id: tenant_id.to_string(),
+            action,
+            performed_by: performed_by.to_string(),
+            performed_at: self.clock.now_utc(),
+        });
+        self.meta_audit(
+            AdminAction::ChangeRetention,
+            tenant_id,
+            performed_by,
+            &format!("retention-policy/{}", tenant_id),
+        )
+        .await;
+    }
+
+    /// Account for one admin operation, failing if the limit is reached
+    fn check_admin_rate_limit(&self) -> Result<(), ComplianceError> {
+        let now = Instant::now();
+        let mut calls = self.admin_calls.lock().unwrap();
+        while calls
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= self.admin_rate_window)
+        {
+            calls.pop_front();
+        }
+
+        if calls.len() >= self.admin_rate_limit {
+            let retry_after = calls
+                .front()
+                .map(|t| self.admin_rate_window - now.duration_since(*t))
+                .unwrap_or(self.admin_rate_window);
+            return Err(ComplianceError::RateLimited { retry_after });
+        }
+        calls.push_back(now);
+        Ok(())
+    }
+
     /// Create a legal hold
-    pub fn create_legal_hold(&mut self, hold: LegalHold) {
+    pub async fn create_legal_hold(&mut self, hold: LegalHold) {
         info!(
             "[Compliance] Creating legal hold {} for tenant {}",
             hold.hold_id, hold.tenant_id
         );
+        self.meta_audit(
+            AdminAction::CreateLegalHold,
+            &hold.tenant_id,
+            &hold.initiated_by,
+            &hold.hold_id,
+        )
+        .await;
 
         self.legal_holds
             .entry(hold.tenant_id.clone())
@@ -291,7 +527,7 @@ impl ComplianceManager {
             event_ids,
             reason,
             deleted_by,
-            deleted_at: Utc::now(),
+            deleted_at: self.clock.now_utc(),
         };
 
         info!(
@@ -328,6 +564,35 @@ impl ComplianceManager {
             .find(|r| r.request_id == request_id)
     }
 
+    /// Approve a pending GDPR request
+    pub async fn approve_gdpr_request(
+        &mut self,
+        request_id: &str,
+        approved_by: &str,
+    ) -> Result<(), ComplianceError> {
+        self.check_admin_rate_limit()?;
+        let request = self
+            .gdpr_requests
+            .iter_mut()
+            .find(|r| r.request_id == request_id)
+            .ok_or_else(|| ComplianceError::GDPRRequestNotFound(request_id.to_string()))?;
+        request.approve(approved_by.to_string());
+
+        info!(
+            "[Compliance] GDPR request {} approved by {}",
+            request_id, approved_by
+        );
+        let tenant_id = request.tenant_id.clone();
+        self.meta_audit(
+            AdminAction::ApproveGdprRequest,
+            &tenant_id,
+            approved_by,
+            request_id,
+        )
+        .await;
+        Ok(())
+    }
+
     /// Check if an event should be deleted based on GDPR
     pub fn should_delete_for_gdpr(&self, tenant_id: &str, event_id: &str) -> bool {
         // Check if there's a pending GDPR deletion request for this tenant
@@ -345,6 +610,66 @@ impl ComplianceManager {
         false
     }
 
+    /// Erase the events of an approved right-to-be-forgotten request from
+    /// every tier and the raw store, then complete the request
+    ///
+    /// Returns the number of stored copies deleted.
+    pub async fn execute_gdpr_erasure(
+        &mut self,
+        request_id: &str,
+        storage: &TieredStorage,
+        erased_by: &str,
+    ) -> Result<u64, ComplianceError> {
+        self.check_admin_rate_limit()?;
+        let request = self
+            .get_gdpr_request(request_id)
+            .ok_or_else(|| ComplianceError::GDPRRequestNotFound(request_id.to_string()))?;
+        if request.request_type != GDPRRequestType::RightToBeForgotten
+            || request.status != GDPRRequestStatus::Approved
+        {
+            return Err(ComplianceError::Other(format!(
+                "GDPR request {} is not an approved erasure request",
+                request_id
+            )));
+        }
+        let tenant_id = request.tenant_id.clone();
+        let event_ids = request.event_ids.clone();
+
+        let mut deleted = 0;
+        for event_id in &event_ids {
+            let filter = QueryFilter {
+                tenant_id: Some(tenant_id.clone()),
+                event_id: Some(event_id.clone()),
+                ..Default::default()
+            };
+            for (_, backend) in storage.backends() {
+                deleted += backend
+                    .delete_events(&filter)
+                    .await
+                    .map_err(|e| ComplianceError::Other(format!("event deletion failed: {}", e)))?;
+            }
+        }
+        self.log_deletion(
+            tenant_id.clone(),
+            event_ids,
+            DeletionReason::GDPRRightToBeForgotten,
+            erased_by.to_string(),
+        );
+        if let Some(request) = self
+            .gdpr_requests
+            .iter_mut()
+            .find(|r| r.request_id == request_id)
+        {
+            request.complete();
+        }
+
+        info!(
+            "[Compliance] GDPR request {} executed by {}: {} copies erased",
+            request_id, erased_by, deleted
+        );
+        Ok(deleted)
+    }
+
     /// Get events eligible for deletion based on retention policy
     pub fn get_events_for_deletion(
         &mut self,
@@ -366,7 +691,7 @@ impl ComplianceManager {
             .into_iter()
             .filter(|event_id| {
                 // Get event timestamp (mock)
-                let event_timestamp = Utc::now() - Duration::days(30);
+                let event_timestamp = self.clock.now_utc() - Duration::days(30);
                 self.can_delete_event(tenant_id, event_timestamp)
             })
             .collect();
@@ -395,11 +720,197 @@ impl ComplianceManager {
         Ok(vec![])
     }
 
+    /// Preview which of a tenant's events retention enforcement would
+    /// delete, without deleting anything
+    pub async fn preview_retention(
+        &self,
+        tenant_id: &str,
+        storage: &TieredStorage,
+    ) -> Result<RetentionPreview, ComplianceError> {
+        let event_ids = self.retention_candidates(tenant_id, storage).await?;
+        Ok(RetentionPreview::new(tenant_id, &event_ids, true))
+    }
+
+    /// Delete a tenant's events older than its retention period from every
+    /// tier and the raw store, sparing those under legal hold
+    ///
+    /// With `dry_run` nothing is deleted or logged; the returned preview
+    /// lists what would have been.
+    pub async fn enforce_retention(
+        &mut self,
+        tenant_id: &str,
+        storage: &TieredStorage,
+        enforced_by: &str,
+        dry_run: bool,
+    ) -> Result<RetentionPreview, ComplianceError> {
+        self.check_admin_rate_limit()?;
+        let event_ids = self.retention_candidates(tenant_id, storage).await?;
+        if dry_run {
+            info!(
+                "[Compliance] Dry run: retention would delete {} events of tenant {}",
+                event_ids.len(),
+                tenant_id
+            );
+            return Ok(RetentionPreview::new(tenant_id, &event_ids, true));
+        }
+
+        for event_id in &event_ids {
+            let filter = QueryFilter {
+                tenant_id: Some(tenant_id.to_string()),
+                event_id: Some(event_id.clone()),
+                ..Default::default()
+            };
+            for (_, tier) in storage.backends() {
+                tier.delete_events(&filter)
+                    .await
+                    .map_err(|e| ComplianceError::Other(format!("event deletion failed: {}", e)))?;
+            }
+        }
+        let preview = RetentionPreview::new(tenant_id, &event_ids, false);
+        if !event_ids.is_empty() {
+            self.log_deletion(
+                tenant_id.to_string(),
+                event_ids,
+                DeletionReason::RetentionExpired,
+                enforced_by.to_string(),
+            );
+        }
+        Ok(preview)
+    }
+
+    /// IDs of a tenant's events past retention and not under legal hold,
+    /// across all tiers and the raw store
+    async fn retention_candidates(
+        &self,
+        tenant_id: &str,
+        storage: &TieredStorage,
+    ) -> Result<Vec<String>, ComplianceError> {
+        let policy = self
+            .retention_policies
+            .get(tenant_id)
+            .ok_or_else(|| ComplianceError::PolicyNotFound(tenant_id.to_string()))?;
+        let filter = QueryFilter {
+            tenant_id: Some(tenant_id.to_string()),
+            end_time: Some(policy.get_cutoff_date().into()),
+            ..Default::default()
+        };
+
+        let mut event_ids = BTreeSet::new();
+        for (_, tier) in storage.backends() {
+            let events = tier
+                .query_events(&filter)
+                .await
+                .map_err(|e| ComplianceError::Other(format!("event query failed: {}", e)))?;
+            for event in events {
+                let event_time = event
+                    .event_time
+                    .as_ref()
+                    .and_then(|t| DateTime::from_timestamp(t.seconds, t.nanos as u32));
+                if let (Some(id), Some(event_time)) = (event.event_id, event_time)
+                    && policy.should_delete_event(event_time)
+                    && self.can_delete_event(tenant_id, event_time)
+                {
+                    event_ids.insert(id.value);
+                }
+            }
+        }
+        Ok(event_ids.into_iter().collect())
+    }
+
+    /// Remove every trace of a tenant: its events in all tiers and the raw
+    /// store, its retention policy and its quotas
+    ///
+    /// Refused while the tenant has an active legal hold. The deletion is
+    /// recorded in a signed certificate chained to the previous one, so
+    /// certificates can't be dropped or rewritten unnoticed.
+    pub async fn offboard_tenant(
+        &mut self,
+        tenant_id: &str,
+        storage: &TieredStorage,
+        quotas: &mut QuotaManager,
+        offboarded_by: &str,
+    ) -> Result<DeletionCertificate, ComplianceError> {
+        self.check_admin_rate_limit()?;
+        let (signer, signing_key) = self.certificate_signer.clone().ok_or_else(|| {
+            ComplianceError::Other("no deletion certificate signer configured".to_string())
+        })?;
+
+        let now = self.clock.now_utc();
+        if let Some(hold) = self.legal_holds.get(tenant_id).and_then(|holds| {
+            holds
+                .iter()
+                .find(|h| h.is_active() && !h.is_expired_at(now))
+        }) {
+            warn!(
+                "[Compliance] Refusing to offboard tenant {}: legal hold {} is active",
+                tenant_id, hold.hold_id
+            );
+            return Err(ComplianceError::LegalHoldPreventsDeletion(
+                hold.hold_id.clone(),
+            ));
+        }
+
+        let events_deleted = storage
+            .delete_tenant_events(tenant_id)
+            .await
+            .map_err(|e| ComplianceError::Other(format!("event deletion failed: {}", e)))?;
+
+        let retention_policy_removed = self.retention_policies.remove(tenant_id).is_some();
+        if retention_policy_removed {
+            self.record_policy_change(tenant_id, PolicyAction::Deleted, offboarded_by)
+                .await;
+        }
+        let quota_removed = quotas.remove_tenant(tenant_id);
+
+        let mut certificate = DeletionCertificate {
+            certificate_id: format!("cert_{}", uuid::Uuid::new_v4()),
+            tenant_id: tenant_id.to_string(),
+            events_deleted,
+            retention_policy_removed,
+            quota_removed,
+            offboarded_by: offboarded_by.to_string(),
+            issued_at: self.clock.now_utc(),
+            previous_digest: self.deletion_certificates.last().map(|c| c.digest.clone()),
+            digest: String::new(),
+            signature: Vec::new(),
+            public_key: Vec::new(),
+        };
+        certificate.digest = certificate.compute_digest();
+        certificate.signature = signer
+            .sign(&certificate.digest, &signing_key)
+            .map_err(|e| ComplianceError::Other(format!("certificate signing failed: {}", e)))?;
+        certificate.public_key = signer
+            .get_public_key(&signing_key)
+            .map_err(|e| ComplianceError::Other(format!("certificate signing failed: {}", e)))?;
+
+        warn!(
+            "[Compliance] Tenant {} offboarded by {}: {} events deleted",
+            tenant_id,
+            offboarded_by,
+            certificate.total_events_deleted()
+        );
+        self.meta_audit(
+            AdminAction::OffboardTenant,
+            tenant_id,
+            offboarded_by,
+            &certificate.certificate_id,
+        )
+        .await;
+        self.deletion_certificates.push(certificate.clone());
+        Ok(certificate)
+    }
+
+    /// Deletion certificates issued so far, oldest first
+    pub fn get_deletion_certificates(&self) -> &[DeletionCertificate] {
+        &self.deletion_certificates
+    }
+
     /// Update all legal hold statuses
     pub fn update_legal_hold_statuses(&mut self) {
+        let now = self.clock.now_utc();
         for holds in self.legal_holds.values_mut() {
             for hold in holds {
-                hold.check_expiration();
+                hold.check_expiration_at(now);
             }
         }
     }
@@ -429,7 +940,7 @@ impl ComplianceManager {
                 .iter()
                 .filter(|r| r.tenant_id == tenant_id && r.status == GDPRRequestStatus::Pending)
                 .count(),
-            report_generated_at: Utc::now(),
+            report_generated_at: self.clock.now_utc(),
         }
     }
 }
@@ -455,6 +966,31 @@ pub enum DeletionReason {
     Administrative,
 }
 
+/// Events removed by retention enforcement or, in a dry run, that would be
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionPreview {
+    pub tenant_id: String,
+    pub event_count: usize,
+    /// First `RETENTION_PREVIEW_SAMPLE_SIZE` event IDs, sorted
+    pub sample_event_ids: Vec<String>,
+    pub dry_run: bool,
+}
+
+impl RetentionPreview {
+    fn new(tenant_id: &str, event_ids: &[String], dry_run: bool) -> Self {
+        Self {
+            tenant_id: tenant_id.to_string(),
+            event_count: event_ids.len(),
+            sample_event_ids: event_ids
+                .iter()
+                .take(RETENTION_PREVIEW_SAMPLE_SIZE)
+                .cloned()
+                .collect(),
+            dry_run,
+        }
+    }
+}
+
 /// Deletion audit record
 #[derive(Debug, Clone)]
 pub struct DeletionAuditRecord {
@@ -466,6 +1002,86 @@ pub struct DeletionAuditRecord {
     pub deleted_at: DateTime<Utc>,
 }
 
+/// Signed record of a tenant offboarding
+#[derive(Debug, Clone)]
+pub struct DeletionCertificate {
+    pub certificate_id: String,
+    pub tenant_id: String,
+    /// Events removed from each tier, by tier name
+    pub events_deleted: BTreeMap<String, u64>,
+    pub retention_policy_removed: bool,
+    pub quota_removed: bool,
+    pub offboarded_by: String,
+    pub issued_at: DateTime<Utc>,
+    /// Digest of the certificate issued before this one
+    pub previous_digest: Option<String>,
+    /// SHA-256 over the previous digest and this certificate's contents
+    pub digest: String,
+    /// Signature of `digest`
+    pub signature: Vec<u8>,
+    /// Public key that verifies `signature`
+    pub public_key: Vec<u8>,
+}
+
+impl DeletionCertificate {
+    /// Total events removed across all tiers
+    pub fn total_events_deleted(&self) -> u64 {
+        self.events_deleted.values().sum()
+    }
+
+    /// Recompute the digest from the certificate's contents
+    pub fn compute_digest(&self) -> String {
+        let tiers = self
+            .events_deleted
+            .iter()
+            .map(|(tier, count)| format!("{}={}", tier, count))
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut hasher = Sha256::new();
+        if let Some(previous) = &self.previous_digest {
+            hasher.update(previous);
+        }
+        hasher.update(format!(
+            "{}:{}:{}:{}:{}:{}:{}",
+            self.certificate_id,
+            self.tenant_id,
+            tiers,
+            self.retention_policy_removed,
+            self.quota_removed,
+            self.offboarded_by,
+            self.issued_at.to_rfc3339()
+        ));
+        hex::encode(hasher.finalize())
+    }
+
+    /// Check that the contents match the digest and the digest is signed
+    pub fn verify(&self, signer: &dyn SigningService) -> bool {
+        self.compute_digest() == self.digest
+            && signer
+                .verify(&self.digest, &self.signature, &self.public_key)
+                .unwrap_or(false)
+    }
+}
+
+/// Retention policy change made through the admin API
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyAction {
+    /// Created or replaced by a bulk apply
+    Applied,
+    /// Deleted
+    Deleted,
+}
+
+/// Audit record of a retention policy change
+#[derive(Debug, Clone)]
+pub struct PolicyAuditRecord {
+    pub record_id: String,
+    pub tenant_id: String,
+    pub action: PolicyAction,
+    pub performed_by: String,
+    pub performed_at: DateTime<Utc>,
+}
+
 /// GDPR request types
 #[derive(Debug, Clone, PartialEq)]
 pub enum GDPRRequestType {
@@ -559,6 +1175,9 @@ pub enum ComplianceError {
     #[error("GDPR request not found: {0}")]
     GDPRRequestNotFound(String),
 
+    #[error("Admin rate limit exceeded, retry after {retry_after:?}")]
+    RateLimited { retry_after: StdDuration },
+
     #[error("Other compliance error: {0}")]
     Other(String),
 }
@@ -577,6 +1196,7 @@ pub struct ComplianceReport {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::crypto::Ed25519Signer;
 
     #[test]
     fn test_enterprise_retention_policy() {
@@ -696,8 +1316,8 @@ mod tests {
         assert_eq!(retrieved.unwrap().retention_days, 730);
     }
 
-    #[test]
-    fn test_can_delete_event() {
+    #[tokio::test]
+    async fn test_can_delete_event() {
         let mut manager = ComplianceManager::new();
 
         // No legal holds, should be able to delete
@@ -713,7 +1333,7 @@ mod tests {
             Utc::now() - Duration::days(200),
             Utc::now() - Duration::days(50),
         );
-        manager.create_legal_hold(hold);
+        manager.create_legal_hold(hold).await;
 
         // Event within legal hold range should not be deletable
         let can_delete = manager.can_delete_event("tenant-123", Utc::now() - Duration::days(100));
@@ -738,6 +1358,256 @@ mod tests {
         assert_eq!(audit[0].deleted_by, "admin@example.com");
     }
 
+    #[tokio::test]
+    async fn test_list_retention_policies_returns_stable_pages() {
+        let mut manager = ComplianceManager::new();
+        let policies = (0..5)
+            .rev()
+            .map(|i| RetentionPolicy::startup(format!("tenant-{}", i)))
+            .collect();
+        assert_eq!(
+            manager
+                .apply_retention_policies(policies, "admin@example.com")
+                .await
+                .unwrap(),
+            5
+        );
+
+        let tenants =
+            |page: &[RetentionPolicy]| page.iter().map(|p| p.tenant_id.clone()).collect::<Vec<_>>();
+        let (first, next) = manager.list_retention_policies(0, 2).unwrap();
+        assert_eq!(tenants(&first), vec!["tenant-0", "tenant-1"]);
+        assert_eq!(next, Some(1));
+
+        let (second, next) = manager.list_retention_policies(1, 2).unwrap();
+        assert_eq!(tenants(&second), vec!["tenant-2", "tenant-3"]);
+        let (last, next_after_last) = manager.list_retention_policies(next.unwrap(), 2).unwrap();
+        assert_eq!(tenants(&last), vec!["tenant-4"]);
+        assert_eq!(next_after_last, None);
+
+        // The same page is returned on every call
+        assert_eq!(
+            tenants(&manager.list_retention_policies(1, 2).unwrap().0),
+            tenants(&second)
+        );
+        assert!(manager.list_retention_policies(3, 2).unwrap().0.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_retention_policy_is_audited() {
+        let mut manager = ComplianceManager::new();
+        manager.create_retention_policy(RetentionPolicy::enterprise("tenant-123".to_string()));
+
+        let deleted = manager
+            .delete_retention_policy("tenant-123", "admin@example.com")
+            .await
+            .unwrap();
+        assert_eq!(deleted.tenant_id, "tenant-123");
+        assert!(manager.get_retention_policy("tenant-123").is_none());
+
+        let audit = manager.get_policy_audit("tenant-123");
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].action, PolicyAction::Deleted);
+        assert_eq!(audit[0].performed_by, "admin@example.com");
+
+        assert!(matches!(
+            manager
+                .delete_retention_policy("tenant-123", "admin@example.com")
+                .await,
+            Err(ComplianceError::PolicyNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_admin_api_is_rate_limited() {
+        let manager = ComplianceManager::new().with_admin_rate_limit(2, StdDuration::from_secs(60));
+
+        assert!(manager.list_retention_policies(0, 10).is_ok());
+        assert!(manager.list_retention_policies(0, 10).is_ok());
+        assert!(matches!(
+            manager.list_retention_policies(0, 10),
+            Err(ComplianceError::RateLimited { .. })
+        ));
+    }
+
+    fn tenant_event(id: &str, tenant_id: &str, days_ago: u64) -> hodei_audit_proto::AuditEvent {
+        let event_time =
+            std::time::SystemTime::now() - StdDuration::from_secs(days_ago * 24 * 60 * 60);
+        hodei_audit_proto::AuditEvent {
+            event_id: Some(hodei_audit_proto::EventId {
+                value: id.to_string(),
+            }),
+            tenant_id: Some(hodei_audit_proto::TenantId {
+                value: tenant_id.to_string(),
+            }),
+            event_time: Some(prost_types::Timestamp::from(event_time)),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gdpr_erasure_deletes_enriched_and_raw_copies() {
+        use crate::storage::StorageBackend;
+
+        let raw = Arc::new(crate::storage::InMemoryStorage::new());
+        let storage = TieredStorage::new().with_raw_storage(raw.clone());
+        for id in ["forget-me", "keep-me"] {
+            let event = tenant_event(id, "tenant-123", 1);
+            storage.store_raw_event(&event).await.unwrap();
+            storage.store_event(&event).await.unwrap();
+        }
+        let mut manager = ComplianceManager::new();
+        let request = GDPRRequest::new(
+            "tenant-123".to_string(),
+            GDPRRequestType::RightToBeForgotten,
+            "subject@example.com".to_string(),
+        )
+        .with_event_ids(vec!["forget-me".to_string()]);
+        let request_id = request.request_id.clone();
+        manager.create_gdpr_request(request);
+        assert!(
+            manager
+                .execute_gdpr_erasure(&request_id, &storage, "dpo@example.com")
+                .await
+                .is_err()
+        );
+        manager
+            .approve_gdpr_request(&request_id, "dpo@example.com")
+            .await
+            .unwrap();
+
+        let deleted = manager
+            .execute_gdpr_erasure(&request_id, &storage, "dpo@example.com")
+            .await
+            .unwrap();
+
+        assert_eq!(deleted, 2);
+        assert!(
+            storage
+                .get_event_by_id("forget-me")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let raw_ids: Vec<String> = raw
+            .query_events(&crate::storage::QueryFilter::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event_id.unwrap().value)
+            .collect();
+        assert_eq!(raw_ids, vec!["keep-me"]);
+        assert_eq!(
+            manager.get_gdpr_request(&request_id).unwrap().status,
+            GDPRRequestStatus::Completed
+        );
+        assert_eq!(manager.get_deletion_audit("tenant-123").len(), 1);
+    }
+
+    fn signing_manager() -> (ComplianceManager, Arc<Ed25519Signer>) {
+        let signer = Arc::new(Ed25519Signer::new());
+        let keypair = signer.generate_keypair().unwrap();
+        let manager =
+            ComplianceManager::new().with_certificate_signer(signer.clone(), keypair.private_key);
+        (manager, signer)
+    }
+
+    #[tokio::test]
+    async fn test_offboard_tenant_deletes_across_tiers_with_signed_certificate() {
+        let (mut manager, signer) = signing_manager();
+        let storage = TieredStorage::new();
+        for (id, days_ago) in [("hot", 1), ("warm", 100), ("cold", 1000)] {
+            storage
+                .store_event(&tenant_event(id, "tenant-123", days_ago))
+                .await
+                .unwrap();
+        }
+        storage
+            .store_event(&tenant_event("other", "tenant-456", 1))
+            .await
+            .unwrap();
+        manager.create_retention_policy(RetentionPolicy::enterprise("tenant-123".to_string()));
+        let mut quotas = QuotaManager::new();
+        quotas.create_tenant_quota("tenant-123".to_string(), "enterprise".to_string());
+
+        let certificate = manager
+            .offboard_tenant("tenant-123", &storage, &mut quotas, "admin@example.com")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            certificate.events_deleted,
+            BTreeMap::from([
+                ("cold".to_string(), 1),
+                ("hot".to_string(), 1),
+                ("warm".to_string(), 1)
+            ])
+        );
+        assert!(certificate.retention_policy_removed);
+        assert!(certificate.quota_removed);
+        assert!(certificate.verify(signer.as_ref()));
+        assert!(manager.get_retention_policy("tenant-123").is_none());
+        assert!(quotas.get_tenant_quota("tenant-123").is_none());
+
+        for (_, tier) in storage.tiers() {
+            let remaining = tier
+                .query_events(&crate::storage::QueryFilter::default())
+                .await
+                .unwrap();
+            assert!(
+                remaining.iter().all(|e| {
+                    e.tenant_id.as_ref().map(|t| t.value.as_str()) == Some("tenant-456")
+                })
+            );
+        }
+
+        // Tampering with the certificate breaks verification
+        let mut forged = certificate.clone();
+        forged.events_deleted.insert("hot".to_string(), 0);
+        assert!(!forged.verify(signer.as_ref()));
+
+        // The next certificate is chained to this one
+        let next = manager
+            .offboard_tenant("tenant-456", &storage, &mut quotas, "admin@example.com")
+            .await
+            .unwrap();
+        assert_eq!(next.previous_digest, Some(certificate.digest.clone()));
+        assert_eq!(manager.get_deletion_certificates().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_active_legal_hold_blocks_offboarding() {
+        let (mut manager, _) = signing_manager();
+        let storage = TieredStorage::new();
+        storage
+            .store_event(&tenant_event("held", "tenant-123", 1))
+            .await
+            .unwrap();
+        manager
+            .create_legal_hold(LegalHold::new(
+                "tenant-123".to_string(),
+                "Litigation hold".to_string(),
+                "Case #12345".to_string(),
+                "legal@example.com".to_string(),
+                Utc::now() - Duration::days(30),
+                Utc::now(),
+            ))
+            .await;
+        let mut quotas = QuotaManager::new();
+
+        let result = manager
+            .offboard_tenant("tenant-123", &storage, &mut quotas, "admin@example.com")
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ComplianceError::LegalHoldPreventsDeletion(_))
+        ));
+        assert!(manager.get_deletion_certificates().is_empty());
+        let (_, hot) = storage.tiers().remove(0);
+        assert_eq!(hot.count_events(&Default::default()).await.unwrap(), 1);
+    }
+
     #[test]
     fn test_gdpr_request() {
         let mut request = GDPRRequest::new(
@@ -791,4 +1661,177 @@ mod tests {
         assert_eq!(report.total_deletions, 0);
         assert_eq!(report.gdpr_requests_pending, 0);
     }
+
+    #[tokio::test]
+    async fn test_admin_actions_emit_meta_audit_events() {
+        use crate::meta_audit::{META_AUDIT_EVENT_SOURCE, metadata_str};
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let mut manager = ComplianceManager::new().with_meta_audit(MetaAuditLogger::new(tx));
+
+        let hold = LegalHold::new(
+            "tenant-123".to_string(),
+            "Litigation".to_string(),
+            "CASE-1".to_string(),
+            "legal@example.com".to_string(),
+            Utc::now() - Duration::days(30),
+            Utc::now(),
+        );
+        let hold_id = hold.hold_id.clone();
+        manager.create_legal_hold(hold).await;
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.event_source, META_AUDIT_EVENT_SOURCE);
+        assert_eq!(event.action, "CreateLegalHold");
+        assert_eq!(
+            event.user_identity.as_ref().unwrap().user_id,
+            "legal@example.com"
+        );
+        assert_eq!(metadata_str(&event, "resource"), Some(hold_id.as_str()));
+
+        let request = GDPRRequest::new(
+            "tenant-123".to_string(),
+            GDPRRequestType::RightToBeForgotten,
+            "user@example.com".to_string(),
+        );
+        let request_id = request.request_id.clone();
+        manager.create_gdpr_request(request);
+        manager
+            .approve_gdpr_request(&request_id, "dpo@example.com")
+            .await
+            .unwrap();
+        assert_eq!(
+            manager.get_gdpr_request(&request_id).unwrap().status,
+            GDPRRequestStatus::Approved
+        );
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.event_source, META_AUDIT_EVENT_SOURCE);
+        assert_eq!(event.action, "ApproveGdprRequest");
+        assert_eq!(event.tenant_id.as_ref().unwrap().value, "tenant-123");
+        assert_eq!(
+            event.user_identity.as_ref().unwrap().user_id,
+            "dpo@example.com"
+        );
+        assert_eq!(metadata_str(&event, "resource"), Some(request_id.as_str()));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retention_dry_run_previews_enforcement() {
+        let mut manager = ComplianceManager::new();
+        manager.create_retention_policy(RetentionPolicy::sme("tenant-123".to_string(), 1));
+        let storage = TieredStorage::new();
+        for (id, days_ago) in [
+            ("recent", 10),
+            ("old", 400),
+            ("held", 500),
+            ("ancient", 2000),
+        ] {
+            storage
+                .store_event(&tenant_event(id, "tenant-123", days_ago))
+                .await
+                .unwrap();
+        }
+        storage
+            .store_event(&tenant_event("other", "tenant-456", 2000))
+            .await
+            .unwrap();
+        manager
+            .create_legal_hold(LegalHold::new(
+                "tenant-123".to_string(),
+                "Litigation".to_string(),
+                "CASE-1".to_string(),
+                "legal@example.com".to_string(),
+                Utc::now() - Duration::days(550),
+                Utc::now() - Duration::days(450),
+            ))
+            .await;
+
+        let preview = manager
+            .preview_retention("tenant-123", &storage)
+            .await
+            .unwrap();
+        let dry_run = manager
+            .enforce_retention("tenant-123", &storage, "admin@example.com", true)
+            .await
+            .unwrap();
+        assert_eq!(preview, dry_run);
+        assert_eq!(preview.event_count, 2);
+        assert_eq!(preview.sample_event_ids, vec!["ancient", "old"]);
+        assert!(manager.get_deletion_audit("tenant-123").is_empty());
+        let stored = storage
+            .query_events(&crate::storage::QueryFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 5);
+
+        let enforced = manager
+            .enforce_retention("tenant-123", &storage, "admin@example.com", false)
+            .await
+            .unwrap();
+        assert!(!enforced.dry_run);
+        assert_eq!(enforced.sample_event_ids, preview.sample_event_ids);
+
+        let audit = manager.get_deletion_audit("tenant-123");
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].event_ids, preview.sample_event_ids);
+
+        let mut remaining: Vec<String> = storage
+            .query_events(&crate::storage::QueryFilter::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event_id.unwrap().value)
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, vec!["held", "other", "recent"]);
+    }
+
+    #[tokio::test]
+    async fn test_legal_hold_expires_exactly_at_expires_at() {
+        let start = Utc::now();
+        let clock = crate::clock::MockClock::at(start);
+        let mut manager = ComplianceManager::new().with_clock(Arc::new(clock.clone()));
+        manager
+            .create_legal_hold(
+                LegalHold::new(
+                    "tenant-123".to_string(),
+                    "Litigation".to_string(),
+                    "CASE-1".to_string(),
+                    "legal@example.com".to_string(),
+                    start - Duration::days(30),
+                    start,
+                )
+                .with_expiration(start + Duration::days(10)),
+            )
+            .await;
+        let status =
+            |manager: &ComplianceManager| manager.get_legal_holds("tenant-123").unwrap()[0].status;
+
+        clock.advance(
+            (Duration::days(10) - Duration::milliseconds(1))
+                .to_std()
+                .unwrap(),
+        );
+        manager.update_legal_hold_statuses();
+        assert_eq!(status(&manager), LegalHoldStatus::Active);
+
+        clock.advance(StdDuration::from_millis(1));
+        manager.update_legal_hold_statuses();
+        assert_eq!(status(&manager), LegalHoldStatus::Expired);
+    }
+
+    #[test]
+    fn test_retention_cutoff_follows_clock() {
+        let start = Utc::now();
+        let clock = crate::clock::MockClock::at(start);
+        let mut manager = ComplianceManager::new().with_clock(Arc::new(clock.clone()));
+        manager.create_retention_policy(RetentionPolicy::sme("tenant-123".to_string(), 1));
+        let policy = manager.get_retention_policy("tenant-123").unwrap();
+
+        assert!(!policy.should_delete_event(start));
+        clock.advance(StdDuration::from_secs(365 * 24 * 60 * 60 + 1));
+        assert!(policy.should_delete_event(start));
+    }
 }
@@ -0,0 +1,340 @@
+//! Cross-Tier Consistency Checks
+//!
+//! Reconciles the number of events stored across the storage tiers with the
+//! number of events the digest chain says were ingested. Every digest covers
+//! `total_files` events, so the chain provides the expected count for any
+//! time range. Counts are compared per time bucket, which localizes a
+//! discrepancy (e.g. a partition lost during a hot-to-warm migration) instead
+//! of only reporting that the totals differ.
+
+use crate::crypto::ports::digest_chain::DigestChainService;
+use crate::storage::{QueryFilter, StorageBackend};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+/// Default reconciliation bucket size
+pub const DEFAULT_BUCKET_SIZE: Duration = Duration::from_secs(3600);
+
+/// Counts for one time bucket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BucketReport {
+    /// Bucket start (inclusive)
+    pub start: SystemTime,
+    /// Bucket end (exclusive)
+    pub end: SystemTime,
+    /// Events recorded by the digest chain
+    pub expected: u64,
+    /// Events found in each tier
+    pub tier_counts: BTreeMap<String, u64>,
+}
+
+impl BucketReport {
+    /// Events found across all tiers
+    pub fn actual(&self) -> u64 {
+        self.tier_counts.values().sum()
+    }
+
+    /// Whether the tiers hold exactly the expected number of events
+    pub fn is_consistent(&self) -> bool {
+        self.actual() == self.expected
+    }
+}
+
+/// Result of reconciling a tenant's events over a time range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    pub tenant_id: String,
+    pub buckets: Vec<BucketReport>,
+}
+
+impl ConsistencyReport {
+    /// Events recorded by the digest chain over the whole range
+    pub fn expected_total(&self) -> u64 {
+        self.buckets.iter().map(|b| b.expected).sum()
+    }
+
+    /// Events found across all tiers over the whole range
+    pub fn actual_total(&self) -> u64 {
+        self.buckets.iter().map(BucketReport::actual).sum()
+    }
+
+    /// Buckets whose counts don't add up
+    pub fn discrepancies(&self) -> Vec<&BucketReport> {
+        self.buckets.iter().filter(|b| !b.is_consistent()).collect()
+    }
+
+    /// Whether every bucket is consistent
+    pub fn is_consistent(&self) -> bool {
+        self.buckets.iter().all(BucketReport::is_consistent)
+    }
+}
+
+/// Reconciles tier event counts against the digest chain
+pub struct ConsistencyChecker {
+    chain: Arc<dyn DigestChainService>,
+    tiers: Vec<(String, Arc<dyn StorageBackend>)>,
+    bucket_size: Duration,
+}
+
+impl ConsistencyChecker {
+    /// Create a checker with no tiers and the default bucket size
+    pub fn new(chain: Arc<dyn DigestChainService>) -> Self {
+        Self {
+            chain,
+            tiers: Vec::new(),
+            bucket_size: DEFAULT_BUCKET_SIZE,
+        }
+    }
+
+    /// Add a tier to count events in
+    pub fn with_tier(mut self, name: impl Into<String>, backend: Arc<dyn StorageBackend>) -> Self {
+        self.tiers.push((name.into(), backend));
+        self
+    }
+
+    /// Set the bucket size (at least one second, the digest time resolution)
+    pub fn with_bucket_size(mut self, bucket_size: Duration) -> Self {
+        self.bucket_size = bucket_size.max(Duration::from_secs(1));
+        self
+    }
+
+    /// Reconcile a tenant's events over `range`
+    ///
+    /// The range is split into buckets aligned to `range.start`; the last
+    /// bucket may be shorter.
+    pub async fn reconcile(
+        &self,
+        tenant_id: &str,
+        range: Range<SystemTime>,
+    ) -> Result<ConsistencyReport, anyhow::Error> {
+        let start = unix_seconds(range.start)?;
+        let end = unix_seconds(range.end)?;
+        if end <= start {
+            return Err(anyhow::anyhow!(
+                "Invalid reconciliation range: end is not after start"
+            ));
+        }
+
+        let bucket_secs = self.bucket_size.as_secs();
+        let bucket_count = (end - start).div_ceil(bucket_secs);
+
+        // Digest timestamps are whole seconds and the chain range is inclusive
+        let mut expected = vec![0u64; bucket_count as usize];
+        for digest in self
+            .chain
+            .list_digests(tenant_id, Some(start), Some(end - 1))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list digests: {}", e))?
+        {
+            let index = ((digest.timestamp - start) / bucket_secs) as usize;
+            expected[index] += digest.total_files as u64;
+        }
+
+        let mut buckets = Vec::with_capacity(expected.len());
+        for (index, expected) in expected.into_iter().enumerate() {
+            let bucket_start = range.start + self.bucket_size * index as u32;
+            let bucket_end = (bucket_start + self.bucket_size).min(range.end);
+            let filter = QueryFilter {
+                tenant_id: Some(tenant_id.to_string()),
+                start_time: Some(bucket_start),
+                // Tier filters are inclusive on both ends
+                end_time: Some(bucket_end - Duration::from_nanos(1)),
+                ..Default::default()
+            };
+
+            let mut tier_counts = BTreeMap::new();
+            for (name, backend) in &self.tiers {
+                tier_counts.insert(name.clone(), backend.count_events(&filter).await?);
+            }
+
+            buckets.push(BucketReport {
+                start: bucket_start,
+                end: bucket_end,
+                expected,
+                tier_counts,
+            });
+        }
+
+        let report = ConsistencyReport {
+            tenant_id: tenant_id.to_string(),
+            buckets,
+        };
+
+        if report.is_consistent() {
+            info!(
+                "[Consistency] tenant={} consistent: {} events in {} buckets",
+                tenant_id,
+                report.expected_total(),
+                report.buckets.len()
+            );
+        } else {
+            warn!(
+                "[Consistency] tenant={} expected {} events, found {} ({} inconsistent buckets)",
+                tenant_id,
+                report.expected_total(),
+                report.actual_total(),
+                report.discrepancies().len()
+            );
+        }
+        Ok(report)
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> Result<u64, anyhow::Error> {
+    Ok(time.duration_since(SystemTime::UNIX_EPOCH)?.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::InMemoryDigestChain;
+    use crate::storage::{InMemoryStorage, TieredStorage};
+    use hodei_audit_proto::{AuditEvent, TenantId};
+
+    const BASE: u64 = 1_700_000_000;
+
+    fn event(tenant: &str, seconds: u64) -> AuditEvent {
+        AuditEvent {
+            tenant_id: Some(TenantId {
+                value: tenant.to_string(),
+            }),
+            event_time: Some(prost_types::Timestamp {
+                seconds: seconds as i64,
+                nanos: 0,
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Chain one digest per event, as the digest worker does
+    async fn ingest(
+        chain: &InMemoryDigestChain,
+        tier: &dyn StorageBackend,
+        tenant: &str,
+        seconds: u64,
+    ) {
+        let head = chain.get_latest_digest(tenant).await.unwrap();
+        chain
+            .generate_digest(
+                tenant,
+                seconds,
+                seconds,
+                &[("event", "hash".to_string())],
+                head.as_ref().map(|d| d.id.as_str()),
+            )
+            .await
+            .unwrap();
+        tier.store_event(&event(tenant, seconds)).await.unwrap();
+    }
+
+    fn range(hours: u64) -> Range<SystemTime> {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(BASE);
+        start..start + Duration::from_secs(hours * 3600)
+    }
+
+    #[tokio::test]
+    async fn test_matching_counts_are_consistent() {
+        let chain = Arc::new(InMemoryDigestChain::new());
+        let hot = Arc::new(InMemoryStorage::new());
+        let warm = Arc::new(InMemoryStorage::new());
+
+        for i in 0..6 {
+            ingest(&chain, &*warm, "tenant-a", BASE + i * 600).await;
+        }
+        for i in 0..4 {
+            ingest(&chain, &*hot, "tenant-a", BASE + 7200 + i * 900).await;
+        }
+        // Other tenants are not counted
+        ingest(&chain, &*hot, "tenant-b", BASE + 60).await;
+
+        let checker = ConsistencyChecker::new(chain)
+            .with_tier("hot", hot)
+            .with_tier("warm", warm);
+        let report = checker.reconcile("tenant-a", range(3)).await.unwrap();
+
+        assert!(report.is_consistent());
+        assert_eq!(report.buckets.len(), 3);
+        assert_eq!(report.expected_total(), 10);
+        assert_eq!(report.actual_total(), 10);
+        assert_eq!(report.buckets[0].tier_counts["warm"], 6);
+        assert_eq!(report.buckets[2].tier_counts["hot"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_missing_warm_events_are_localized() {
+        let chain = Arc::new(InMemoryDigestChain::new());
+        let hot = Arc::new(InMemoryStorage::new());
+        let warm = Arc::new(InMemoryStorage::new());
+
+        for hour in 0..3 {
+            for i in 0..5 {
+                ingest(&chain, &*warm, "tenant-a", BASE + hour * 3600 + i * 60).await;
+            }
+        }
+        ingest(&chain, &*hot, "tenant-a", BASE + 3 * 3600).await;
+
+        // A migration lost two events of the second hour
+        for seconds in [BASE + 3600, BASE + 3600 + 60] {
+            let at = SystemTime::UNIX_EPOCH + Duration::from_secs(seconds);
+            let lost = QueryFilter {
+                start_time: Some(at),
+                end_time: Some(at),
+                ..Default::default()
+            };
+            warm.delete_events(&lost).await.unwrap();
+        }
+
+        let checker = ConsistencyChecker::new(chain)
+            .with_tier("hot", hot)
+            .with_tier("warm", warm);
+        let report = checker.reconcile("tenant-a", range(4)).await.unwrap();
+
+        assert!(!report.is_consistent());
+        assert_eq!(report.expected_total(), 16);
+        assert_eq!(report.actual_total(), 14);
+
+        let discrepancies = report.discrepancies();
+        assert_eq!(discrepancies.len(), 1);
+        assert_eq!(
+            discrepancies[0].start,
+            range(4).start + Duration::from_secs(3600)
+        );
+        assert_eq!(discrepancies[0].expected, 5);
+        assert_eq!(discrepancies[0].tier_counts["warm"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_tiered_storage_tiers() {
+        let chain = Arc::new(InMemoryDigestChain::new());
+        let storage = TieredStorage::new();
+        let tiers = storage.tiers();
+        let (hot, warm) = (&tiers[0].1, &tiers[1].1);
+
+        for i in 0..3 {
+            ingest(&chain, warm.as_ref(), "tenant-a", BASE + i * 600).await;
+        }
+        for i in 0..2 {
+            ingest(&chain, hot.as_ref(), "tenant-a", BASE + 3600 + i * 600).await;
+        }
+        // Events of other tenants and outside the range are not counted
+        ingest(&chain, hot.as_ref(), "tenant-b", BASE + 60).await;
+        ingest(&chain, hot.as_ref(), "tenant-a", BASE + 3 * 3600).await;
+
+        let checker = tiers
+            .iter()
+            .fold(ConsistencyChecker::new(chain), |checker, (name, tier)| {
+                checker.with_tier(*name, tier.clone())
+            });
+        let report = checker.reconcile("tenant-a", range(2)).await.unwrap();
+
+        assert!(report.is_consistent());
+        assert_eq!(report.actual_total(), 5);
+        assert_eq!(report.buckets[0].tier_counts["warm"], 3);
+        assert_eq!(report.buckets[0].tier_counts["hot"], 0);
+        assert_eq!(report.buckets[1].tier_counts["hot"], 2);
+        assert_eq!(report.buckets[1].tier_counts["cold"], 0);
+    }
+}
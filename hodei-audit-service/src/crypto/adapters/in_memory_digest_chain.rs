@@ -10,7 +10,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 /// Servicio de cadena de digests en memoria
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InMemoryDigestChain {
     /// Mapa de tenant_id -> lista de digests
     digests: Arc<RwLock<HashMap<String, Vec<DigestInfo>>>>,
@@ -59,8 +59,10 @@ impl DigestChainService for InMemoryDigestChain {
             aggregated_hash.push_str(hash);
         }
 
+        // La posición en la cadena evita ids repetidos con el mismo end_time
+        let position = digests.get(tenant_id).map_or(0, |v| v.len());
         let digest_info = DigestInfo {
-            id: format!("digest_{}_{}", tenant_id, end_time),
+            id: format!("digest_{}_{}_{}", tenant_id, end_time, position),
             hash: aggregated_hash,
             signature: vec![], // Se firmará externamente
             timestamp: end_time,
@@ -3,6 +3,6 @@
 //! Estos ports definen las interfaces que el dominio necesita,
 //! permitiendo la separación clara entre dominio e infraestructura.
 
+pub mod digest_chain;
 pub mod hashing;
 pub mod signing;
-pub mod digest_chain;
@@ -0,0 +1,821 @@
+//! Dead-Letter Store
+//!
+//! Events that fail processing (schema rejections, enrichment or storage
+//! failures) are kept in a dead-letter store instead of being lost. Each letter is the failed event tagged with the stage it failed at
+//! and the reason, so it can be inspected and, once the cause is fixed,
+//! replayed into the pipeline.
+//!
+//! Letters are persisted through any `StorageBackend`, so the store is
+//! configured like a storage tier: a local directory, an S3 bucket or a
+//! ClickHouse table. The tags live in reserved metadata keys that are
+//! stripped again before a replay. Each letter is stored under its own ID,
+//! scoped to the tenant of its event, so removing one never touches the
+//! other letters of the event or another tenant's letters.
+//!
+//! Operators inspect letters through `query`, which pages through them in
+//! failure order and groups them by reason, and discard the ones not worth
+//! replaying with `purge`.
+
+use crate::clock::{Clock, system_clock};
+use crate::meta_audit::metadata_str;
+use crate::storage::{
+    PartitionStrategy, QueryFilter, StorageBackend, StorageConfig, StorageFactory,
+};
+use hodei_audit_proto::{AuditEvent, EventId};
+use prost_types::value::Kind;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Metadata key holding the stage a dead-lettered event failed at
+pub const DEAD_LETTER_STAGE_KEY: &str = "hodei.dead_letter.stage";
+
+/// Metadata key holding why a dead-lettered event failed
+pub const DEAD_LETTER_REASON_KEY: &str = "hodei.dead_letter.reason";
+
+/// Metadata key holding when a dead-lettered event failed (RFC 3339)
+pub const DEAD_LETTER_FAILED_AT_KEY: &str = "hodei.dead_letter.failed_at";
+
+/// Metadata key holding the ID of the dead-lettered event; the letter
+/// itself is stored under an ID of its own
+pub const DEAD_LETTER_EVENT_ID_KEY: &str = "hodei.dead_letter.event_id";
+
+/// Processing stage an event failed at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeadLetterStage {
+    /// Rejected by the registered schema of its `event_source`
+    Schema,
+    /// Enrichment failed
+    Enrichment,
+    /// Every attempt to persist it failed
+    Storage,
+}
+
+impl DeadLetterStage {
+    /// Stage name recorded on the letter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeadLetterStage::Schema => "schema",
+            DeadLetterStage::Enrichment => "enrichment",
+            DeadLetterStage::Storage => "storage",
+        }
+    }
+}
+
+impl fmt::Display for DeadLetterStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DeadLetterStage {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "schema" => Ok(DeadLetterStage::Schema),
+            "enrichment" => Ok(DeadLetterStage::Enrichment),
+            "storage" => Ok(DeadLetterStage::Storage),
+            other => Err(anyhow::anyhow!("Unknown dead-letter stage: {}", other)),
+        }
+    }
+}
+
+/// An event that failed processing, with where and why it failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeadLetter {
+    /// The event as it was submitted, without the dead-letter tags
+    pub event: AuditEvent,
+    pub stage: DeadLetterStage,
+    pub reason: String,
+    pub failed_at: SystemTime,
+}
+
+impl DeadLetter {
+    /// ID of the failed event
+    pub fn event_id(&self) -> &str {
+        self.event
+            .event_id
+            .as_ref()
+            .map(|id| id.value.as_str())
+            .unwrap_or_default()
+    }
+
+    /// Read a letter back from a stored event, with the key it is stored
+    /// under; `None` if it isn't tagged
+    fn from_stored(mut event: AuditEvent) -> Option<(LetterKey, Self)> {
+        let stage = metadata_str(&event, DEAD_LETTER_STAGE_KEY)?.parse().ok()?;
+        let reason = metadata_str(&event, DEAD_LETTER_REASON_KEY)?.to_string();
+        let failed_at = metadata_str(&event, DEAD_LETTER_FAILED_AT_KEY)
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map(SystemTime::from)?;
+        let key = LetterKey {
+            tenant_id: event.tenant_id.as_ref().map(|t| t.value.clone()),
+            stored_id: event.event_id.as_ref()?.value.clone(),
+            legacy: metadata_str(&event, DEAD_LETTER_EVENT_ID_KEY).is_none(),
+        };
+        // Letters stored before they had their own ID keep the event's
+        if let Some(event_id) = metadata_str(&event, DEAD_LETTER_EVENT_ID_KEY) {
+            event.event_id = Some(EventId {
+                value: event_id.to_string(),
+            });
+        }
+        if let Some(metadata) = event.metadata.as_mut() {
+            for key in [
+                DEAD_LETTER_STAGE_KEY,
+                DEAD_LETTER_REASON_KEY,
+                DEAD_LETTER_FAILED_AT_KEY,
+                DEAD_LETTER_EVENT_ID_KEY,
+            ] {
+                metadata.fields.remove(key);
+            }
+            if metadata.fields.is_empty() {
+                event.metadata = None;
+            }
+        }
+        Some((
+            key,
+            Self {
+                event,
+                stage,
+                reason,
+                failed_at,
+            },
+        ))
+    }
+}
+
+/// Where one letter is stored in the backend
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct LetterKey {
+    tenant_id: Option<String>,
+    stored_id: String,
+    /// Stored under the event's own ID, shared with the other letters of
+    /// the event
+    legacy: bool,
+}
+
+impl LetterKey {
+    /// Backend filter selecting the stored letter, within its tenant
+    fn filter(&self) -> QueryFilter {
+        QueryFilter {
+            tenant_id: self.tenant_id.clone(),
+            event_id: Some(self.stored_id.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Tag `event` as a letter failed at `stage`, stored under an ID of its own
+fn tag_letter(event: &AuditEvent, stage: &str, reason: &str, failed_at: &str) -> AuditEvent {
+    let mut letter = event.clone();
+    let event_id = letter
+        .event_id
+        .as_ref()
+        .map(|id| id.value.clone())
+        .unwrap_or_default();
+    letter.event_id = Some(EventId {
+        value: format!("{}#{}", event_id, Uuid::new_v4()),
+    });
+    let fields = &mut letter.metadata.get_or_insert_with(Default::default).fields;
+    for (key, value) in [
+        (DEAD_LETTER_STAGE_KEY, stage),
+        (DEAD_LETTER_REASON_KEY, reason),
+        (DEAD_LETTER_FAILED_AT_KEY, failed_at),
+        (DEAD_LETTER_EVENT_ID_KEY, event_id.as_str()),
+    ] {
+        fields.insert(
+            key.to_string(),
+            prost_types::Value {
+                kind: Some(Kind::StringValue(value.to_string())),
+            },
+        );
+    }
+    letter
+}
+
+/// Selects dead letters to list, replay or purge; unset criteria match
+/// everything
+#[derive(Debug, Clone, Default)]
+pub struct DeadLetterFilter {
+    pub tenant_id: Option<String>,
+    pub event_id: Option<String>,
+    pub stage: Option<DeadLetterStage>,
+    /// Earliest failure time (inclusive)
+    pub start_time: Option<SystemTime>,
+    /// Latest failure time (inclusive)
+    pub end_time: Option<SystemTime>,
+    pub limit: Option<usize>,
+}
+
+impl DeadLetterFilter {
+    /// Whether a letter satisfies every criterion set in the filter
+    /// (`limit` aside)
+    pub fn matches(&self, letter: &DeadLetter) -> bool {
+        let event = &letter.event;
+        self.tenant_id
+            .as_ref()
+            .is_none_or(|t| event.tenant_id.as_ref().is_some_and(|id| &id.value == t))
+            && self
+                .event_id
+                .as_ref()
+                .is_none_or(|e| letter.event_id() == e)
+            && self.stage.is_none_or(|stage| letter.stage == stage)
+            && self
+                .start_time
+                .is_none_or(|start| letter.failed_at >= start)
+            && self.end_time.is_none_or(|end| letter.failed_at <= end)
+    }
+
+    /// Backend query narrowing the stored letters down to candidates
+    fn backend_query(&self) -> QueryFilter {
+        QueryFilter {
+            tenant_id: self.tenant_id.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// One page of a dead-letter query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeadLetterPage {
+    /// Letters of the page, oldest failure first
+    pub letters: Vec<DeadLetter>,
+    /// Index of the next page, if there is one
+    pub next_page: Option<usize>,
+    /// Letters matching the filter across all pages
+    pub total: u64,
+    /// Letters matching the filter across all pages, by failure reason
+    pub counts_by_reason: BTreeMap<String, u64>,
+}
+
+/// Outcome of a replay
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    /// Events re-submitted successfully; their letters were removed
+    pub replayed: u64,
+    /// Events that failed again; their letters were kept
+    pub failed: u64,
+    /// Why each failed event was kept
+    pub errors: Vec<String>,
+}
+
+/// Where replayed events are re-submitted
+#[async_trait::async_trait]
+pub trait ReplaySink: Send + Sync {
+    /// Re-submit one event, failing if it still can't be processed
+    async fn resubmit(&self, event: AuditEvent) -> Result<(), anyhow::Error>;
+}
+
+#[async_trait::async_trait]
+impl<T: StorageBackend + ?Sized> ReplaySink for T {
+    async fn resubmit(&self, event: AuditEvent) -> Result<(), anyhow::Error> {
+        self.store_event(&event).await
+    }
+}
+
+/// Keeps events that failed processing for inspection and replay
+#[derive(Clone)]
+pub struct DeadLetterStore {
+    backend: Arc<dyn StorageBackend>,
+    /// Source of the failure time recorded on letters
+    clock: Arc<dyn Clock>,
+}
+
+impl fmt::Debug for DeadLetterStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeadLetterStore")
+            .field("clock", &self.clock)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DeadLetterStore {
+    /// Keep letters in `backend`
+    pub fn new(backend: Arc<dyn StorageBackend>) -> Self {
+        Self {
+            backend,
+            clock: system_clock(),
+        }
+    }
+
+    /// Use a custom clock for failure times (for testing)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Keep letters in the backend described by `config`, typically a
+    /// `FileSystem`, `S3` or `ClickHouse` one
+    pub fn from_config(config: &StorageConfig) -> Result<Self, anyhow::Error> {
+        info!("[DeadLetter] Using {} dead-letter store", config.kind());
+        Ok(Self::new(StorageFactory::build(
+            config,
+            &PartitionStrategy::default(),
+        )?))
+    }
+
+    /// Record an event that failed at `stage`
+    pub async fn record(
+        &self,
+        event: &AuditEvent,
+        stage: DeadLetterStage,
+        reason: &str,
+    ) -> Result<(), anyhow::Error> {
+        self.record_batch(std::slice::from_ref(event), stage, reason)
+            .await
+    }
+
+    /// Record events that failed together at `stage` for the same reason
+    ///
+    /// Letters are keyed by event ID, so events without one are refused.
+    pub async fn record_batch(
+        &self,
+        events: &[AuditEvent],
+        stage: DeadLetterStage,
+        reason: &str,
+    ) -> Result<(), anyhow::Error> {
+        let failed_at = self.clock.now_utc().to_rfc3339();
+        let letters = events
+            .iter()
+            .map(|event| {
+                if event.event_id.as_ref().is_none_or(|id| id.value.is_empty()) {
+                    anyhow::bail!("Cannot dead-letter an event without event_id");
+                }
+                Ok(tag_letter(event, stage.as_str(), reason, &failed_at))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.backend.store_batch(&letters).await?;
+        warn!(
+            "[DeadLetter] {} events failed at {}: {}",
+            letters.len(),
+            stage,
+            reason
+        );
+        Ok(())
+    }
+
+    /// Every letter matching `filter` (`limit` aside) with its key, oldest
+    /// failure first; ties are ordered by event ID, stage and reason so the
+    /// order is stable across calls
+    async fn matching_keyed(
+        &self,
+        filter: &DeadLetterFilter,
+    ) -> Result<Vec<(LetterKey, DeadLetter)>, anyhow::Error> {
+        let mut letters: Vec<(LetterKey, DeadLetter)> = self
+            .backend
+            .query_events(&filter.backend_query())
+            .await?
+            .into_iter()
+            .filter_map(DeadLetter::from_stored)
+            .filter(|(_, letter)| filter.matches(letter))
+            .collect();
+        letters.sort_by(|(_, a), (_, b)| {
+            (a.failed_at, a.event_id(), a.stage.as_str(), &a.reason).cmp(&(
+                b.failed_at,
+                b.event_id(),
+                b.stage.as_str(),
+                &b.reason,
+            ))
+        });
+        Ok(letters)
+    }
+
+    /// Every letter matching `filter` (`limit` aside), oldest failure first
+    async fn matching(&self, filter: &DeadLetterFilter) -> Result<Vec<DeadLetter>, anyhow::Error> {
+        Ok(self
+            .matching_keyed(filter)
+            .await?
+            .into_iter()
+            .map(|(_, letter)| letter)
+            .collect())
+    }
+
+    /// Letters matching `filter`, oldest failure first
+    pub async fn list(&self, filter: &DeadLetterFilter) -> Result<Vec<DeadLetter>, anyhow::Error> {
+        let mut letters = self.matching(filter).await?;
+        letters.truncate(filter.limit.unwrap_or(usize::MAX));
+        Ok(letters)
+    }
+
+    /// Page through the letters matching `filter` (`limit` aside), oldest
+    /// failure first
+    ///
+    /// Pages are zero-based. The page also carries the total and the counts
+    /// by reason of every matching letter.
+    pub async fn query(
+        &self,
+        filter: &DeadLetterFilter,
+        page: usize,
+        page_size: usize,
+    ) -> Result<DeadLetterPage, anyhow::Error> {
+        if page_size == 0 {
+            anyhow::bail!("page size must be greater than zero");
+        }
+
+        let mut letters = self.matching(filter).await?;
+        let mut counts_by_reason = BTreeMap::new();
+        for letter in &letters {
+            *counts_by_reason.entry(letter.reason.clone()).or_insert(0) += 1;
+        }
+
+        let total = letters.len();
+        let start = page.saturating_mul(page_size).min(total);
+        let end = start.saturating_add(page_size).min(total);
+        let next_page = (end < total).then_some(page + 1);
+        letters.truncate(end);
+        letters.drain(..start);
+
+        Ok(DeadLetterPage {
+            letters,
+            next_page,
+            total: total as u64,
+            counts_by_reason,
+        })
+    }
+
+    /// Number of letters matching `filter` (`limit` aside)
+    pub async fn count(&self, filter: &DeadLetterFilter) -> Result<u64, anyhow::Error> {
+        let filter = DeadLetterFilter {
+            limit: None,
+            ..filter.clone()
+        };
+        Ok(self.list(&filter).await?.len() as u64)
+    }
+
+    /// Remove the stored letters under `keys`
+    ///
+    /// Letters under their own ID are deleted one by one. A legacy key is
+    /// shared by every letter of its event, so the letters of the event that
+    /// `removed` doesn't select are first stored again under IDs of their
+    /// own, and only then is the shared key deleted.
+    async fn remove(
+        &self,
+        keys: BTreeSet<LetterKey>,
+        removed: impl Fn(&DeadLetter) -> bool,
+    ) -> Result<(), anyhow::Error> {
+        for key in keys {
+            if key.legacy {
+                let kept: Vec<AuditEvent> = self
+                    .backend
+                    .query_events(&key.filter())
+                    .await?
+                    .into_iter()
+                    .filter_map(DeadLetter::from_stored)
+                    .filter(|(_, letter)| !removed(letter))
+                    .map(|(_, letter)| {
+                        tag_letter(
+                            &letter.event,
+                            letter.stage.as_str(),
+                            &letter.reason,
+                            &chrono::DateTime::<chrono::Utc>::from(letter.failed_at).to_rfc3339(),
+                        )
+                    })
+                    .collect();
+                if !kept.is_empty() {
+                    self.backend.store_batch(&kept).await?;
+                }
+            }
+            self.backend.delete_events(&key.filter()).await?;
+        }
+        Ok(())
+    }
+
+    /// Discard the letters matching `filter` (`limit` aside), returning how
+    /// many were removed
+    pub async fn purge(&self, filter: &DeadLetterFilter) -> Result<u64, anyhow::Error> {
+        let purged = self.matching_keyed(filter).await?;
+        let removed = purged.len() as u64;
+        let keys = purged.into_iter().map(|(key, _)| key).collect();
+        self.remove(keys, |letter| filter.matches(letter)).await?;
+        warn!("[DeadLetter] Purged {} letters", removed);
+        Ok(removed)
+    }
+
+    /// Re-submit the events of the letters matching `filter` to `sink`
+    ///
+    /// An event dead-lettered more than once is re-submitted once. When it
+    /// is accepted, every letter for it in its tenant is removed; when it
+    /// fails again, they are kept.
+    pub async fn replay(
+        &self,
+        filter: &DeadLetterFilter,
+        sink: &dyn ReplaySink,
+    ) -> Result<ReplayReport, anyhow::Error> {
+        let mut report = ReplayReport::default();
+        let mut seen = BTreeSet::new();
+        for letter in self.list(filter).await? {
+            let event_id = letter.event_id().to_string();
+            let tenant_id = letter.event.tenant_id.as_ref().map(|t| t.value.clone());
+            if !seen.insert((tenant_id.clone(), event_id.clone())) {
+                continue;
+            }
+            match sink.resubmit(letter.event).await {
+                Ok(()) => {
+                    let same_event = DeadLetterFilter {
+                        tenant_id: tenant_id.clone(),
+                        event_id: Some(event_id),
+                        ..Default::default()
+                    };
+                    let keys = self
+                        .matching_keyed(&same_event)
+                        .await?
+                        .into_iter()
+                        .filter(|(key, _)| key.tenant_id == tenant_id)
+                        .map(|(key, _)| key)
+                        .collect();
+                    self.remove(keys, |letter| {
+                        letter.event.tenant_id.as_ref().map(|t| &t.value) == tenant_id.as_ref()
+                    })
+                    .await?;
+                    report.replayed += 1;
+                }
+                Err(e) => {
+                    report.failed += 1;
+                    report.errors.push(format!("{}: {}", event_id, e));
+                }
+            }
+        }
+        info!(
+            "[DeadLetter] Replayed {} letters, {} failed again",
+            report.replayed, report.failed
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::storage::InMemoryStorage;
+    use hodei_audit_proto::TenantId;
+    use std::time::Duration;
+
+    fn event(id: &str) -> AuditEvent {
+        AuditEvent {
+            event_id: Some(EventId {
+                value: id.to_string(),
+            }),
+            tenant_id: Some(TenantId {
+                value: "tenant-1".to_string(),
+            }),
+            action: "CreatePolicyStore".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_letters_keep_stage_and_reason_and_replay_untagged() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DeadLetterStore::from_config(&StorageConfig::FileSystem {
+            root: dir.path().to_path_buf(),
+        })
+        .unwrap();
+        store
+            .record(&event("a"), DeadLetterStage::Schema, "missing field")
+            .await
+            .unwrap();
+        store
+            .record(
+                &event("b"),
+                DeadLetterStage::Enrichment,
+                "enricher timed out",
+            )
+            .await
+            .unwrap();
+        assert!(
+            store
+                .record(&AuditEvent::default(), DeadLetterStage::Enrichment, "x")
+                .await
+                .is_err()
+        );
+
+        let schema = DeadLetterFilter {
+            stage: Some(DeadLetterStage::Schema),
+            ..Default::default()
+        };
+        let letters = store.list(&schema).await.unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].reason, "missing field");
+        assert_eq!(letters[0].event, event("a"));
+
+        let sink = InMemoryStorage::new();
+        let report = store.replay(&schema, &sink).await.unwrap();
+        assert_eq!(report.replayed, 1);
+        assert_eq!(sink.len(), 1);
+        assert_eq!(store.count(&schema).await.unwrap(), 0);
+        assert_eq!(store.count(&DeadLetterFilter::default()).await.unwrap(), 1);
+    }
+
+    /// Six letters failed one second apart, starting at `start`: two per
+    /// reason, with `e1` dead-lettered twice
+    async fn populated_store(start: SystemTime) -> DeadLetterStore {
+        let clock = Arc::new(MockClock::new(start));
+        let store =
+            DeadLetterStore::new(Arc::new(InMemoryStorage::new())).with_clock(clock.clone());
+        for (id, stage, reason) in [
+            ("e0", DeadLetterStage::Schema, "missing field"),
+            ("e1", DeadLetterStage::Storage, "connection refused"),
+            ("e2", DeadLetterStage::Storage, "connection refused"),
+            ("e3", DeadLetterStage::Enrichment, "enricher timed out"),
+            ("e4", DeadLetterStage::Schema, "missing field"),
+            ("e1", DeadLetterStage::Enrichment, "enricher timed out"),
+        ] {
+            store.record(&event(id), stage, reason).await.unwrap();
+            clock.advance(Duration::from_secs(1));
+        }
+        store
+    }
+
+    #[tokio::test]
+    async fn test_query_groups_counts_by_reason() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let store = populated_store(start).await;
+
+        let all = store
+            .query(&DeadLetterFilter::default(), 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(all.total, 6);
+        assert_eq!(
+            all.counts_by_reason,
+            BTreeMap::from([
+                ("connection refused".to_string(), 2),
+                ("missing field".to_string(), 2),
+                ("enricher timed out".to_string(), 2),
+            ])
+        );
+
+        let storage = DeadLetterFilter {
+            stage: Some(DeadLetterStage::Storage),
+            ..Default::default()
+        };
+        let page = store.query(&storage, 0, 10).await.unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(
+            page.counts_by_reason,
+            BTreeMap::from([("connection refused".to_string(), 2)])
+        );
+
+        // e2 and e3 failed at seconds 2 and 3
+        let window = DeadLetterFilter {
+            start_time: Some(start + Duration::from_secs(2)),
+            end_time: Some(start + Duration::from_secs(3)),
+            ..Default::default()
+        };
+        let page = store.query(&window, 0, 10).await.unwrap();
+        let ids: Vec<&str> = page.letters.iter().map(|l| l.event_id()).collect();
+        assert_eq!(ids, ["e2", "e3"]);
+        assert_eq!(page.counts_by_reason.len(), 2);
+        assert!(store.query(&window, 0, 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_pages_are_stable() {
+        let store = populated_store(SystemTime::UNIX_EPOCH).await;
+        let filter = DeadLetterFilter::default();
+
+        let first = store.query(&filter, 0, 4).await.unwrap();
+        let second = store.query(&filter, 1, 4).await.unwrap();
+        assert_eq!((first.letters.len(), first.next_page), (4, Some(1)));
+        assert_eq!((second.letters.len(), second.next_page), (2, None));
+        assert!(store.query(&filter, 2, 4).await.unwrap().letters.is_empty());
+
+        let paged: Vec<DeadLetter> = first.letters.into_iter().chain(second.letters).collect();
+        assert_eq!(paged, store.list(&filter).await.unwrap());
+        assert_eq!(
+            store.query(&filter, 0, 4).await.unwrap().letters,
+            paged[..4]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_purge_removes_exactly_the_filtered_letters() {
+        let store = populated_store(SystemTime::UNIX_EPOCH).await;
+        let enrichment = DeadLetterFilter {
+            stage: Some(DeadLetterStage::Enrichment),
+            ..Default::default()
+        };
+
+        assert_eq!(store.purge(&enrichment).await.unwrap(), 2);
+        assert_eq!(store.count(&enrichment).await.unwrap(), 0);
+
+        // The storage letter of e1 outlives the purge of its enrichment letter
+        let left = store.list(&DeadLetterFilter::default()).await.unwrap();
+        let left: Vec<(&str, DeadLetterStage)> =
+            left.iter().map(|l| (l.event_id(), l.stage)).collect();
+        assert_eq!(
+            left,
+            [
+                ("e0", DeadLetterStage::Schema),
+                ("e1", DeadLetterStage::Storage),
+                ("e2", DeadLetterStage::Storage),
+                ("e4", DeadLetterStage::Schema),
+            ]
+        );
+    }
+
+    fn tenant_event(tenant: &str, id: &str) -> AuditEvent {
+        AuditEvent {
+            tenant_id: Some(TenantId {
+                value: tenant.to_string(),
+            }),
+            ..event(id)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_purge_and_replay_stay_within_the_tenant() {
+        let backend = Arc::new(InMemoryStorage::new());
+        let store = DeadLetterStore::new(backend.clone());
+        for tenant in ["tenant-1", "tenant-2"] {
+            store
+                .record(
+                    &tenant_event(tenant, "shared"),
+                    DeadLetterStage::Enrichment,
+                    "q",
+                )
+                .await
+                .unwrap();
+        }
+        let tenant = |tenant: &str| DeadLetterFilter {
+            tenant_id: Some(tenant.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(store.purge(&tenant("tenant-1")).await.unwrap(), 1);
+        assert_eq!(store.count(&tenant("tenant-2")).await.unwrap(), 1);
+
+        store
+            .record(
+                &tenant_event("tenant-1", "shared"),
+                DeadLetterStage::Enrichment,
+                "q",
+            )
+            .await
+            .unwrap();
+        let report = store
+            .replay(&tenant("tenant-1"), &InMemoryStorage::new())
+            .await
+            .unwrap();
+        assert_eq!(report.replayed, 1);
+        assert_eq!(store.count(&tenant("tenant-1")).await.unwrap(), 0);
+        assert_eq!(store.count(&tenant("tenant-2")).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_purge_keeps_siblings_of_letters_stored_under_the_event_id() {
+        // Letters written before each had its own ID
+        let backend = Arc::new(InMemoryStorage::new());
+        let store = DeadLetterStore::new(backend.clone());
+        let legacy = |stage: DeadLetterStage| {
+            let mut letter = tag_letter(&event("e1"), stage.as_str(), "r", "2024-01-01T00:00:00Z");
+            letter.event_id = event("e1").event_id;
+            letter
+                .metadata
+                .as_mut()
+                .unwrap()
+                .fields
+                .remove(DEAD_LETTER_EVENT_ID_KEY);
+            letter
+        };
+        backend
+            .store_batch(&[
+                legacy(DeadLetterStage::Enrichment),
+                legacy(DeadLetterStage::Storage),
+            ])
+            .await
+            .unwrap();
+
+        let enrichment = DeadLetterFilter {
+            stage: Some(DeadLetterStage::Enrichment),
+            ..Default::default()
+        };
+        assert_eq!(store.purge(&enrichment).await.unwrap(), 1);
+        let left = store.list(&DeadLetterFilter::default()).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].stage, DeadLetterStage::Storage);
+        assert_eq!(left[0].event, event("e1"));
+    }
+
+    #[tokio::test]
+    async fn test_replay_resubmits_each_event_once() {
+        let store = populated_store(SystemTime::UNIX_EPOCH).await;
+        let sink = InMemoryStorage::new();
+
+        let report = store
+            .replay(&DeadLetterFilter::default(), &sink)
+            .await
+            .unwrap();
+        // e1 was dead-lettered twice
+        assert_eq!((report.replayed, report.failed), (5, 0));
+        assert_eq!(sink.len(), 5);
+        assert_eq!(store.count(&DeadLetterFilter::default()).await.unwrap(), 0);
+    }
+}
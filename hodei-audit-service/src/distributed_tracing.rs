@@ -6,19 +6,57 @@
 //! - Complete span attributes
 //! - Jaeger/Tempo setup
 //! - Trace sampling strategy
+//! - Baggage propagation (tenant and correlation id) across stages and tasks
+//! - Per-stage ingest spans (validate, enrich, batch, flush, store)
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Baggage key of the tenant a request belongs to
+pub const TENANT_ID_BAGGAGE: &str = "tenant_id";
+
+/// Baggage key of the correlation id of a request
+pub const CORRELATION_ID_BAGGAGE: &str = "correlation_id";
+
+/// W3C baggage header (`key=value` pairs, comma separated)
+pub const BAGGAGE_HEADER: &str = "baggage";
+
+tokio::task_local! {
+    /// Trace context of the request the current task works for
+    static CURRENT_TRACE: TraceState;
+}
+
 /// Trace ID (16-byte identifier)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct TraceId(String);
 
+impl TraceId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
 /// Span ID (8-byte identifier)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct SpanId(String);
 
+impl SpanId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
 /// Trace state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraceState {
@@ -76,6 +114,126 @@ impl TraceState {
         self.flags = flags;
         self
     }
+
+    /// Value of a baggage item
+    pub fn baggage_item(&self, key: &str) -> Option<&str> {
+        self.baggage.get(key).map(String::as_str)
+    }
+
+    /// Baggage as a W3C `baggage` header value, keys sorted
+    pub fn baggage_header(&self) -> String {
+        self.baggage
+            .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", encode_baggage(key), encode_baggage(value)))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Add the items of a W3C `baggage` header
+    ///
+    /// Item properties (`;key=value`) and malformed items are ignored.
+    pub fn with_baggage_header(mut self, header: &str) -> Self {
+        for item in header.split(',') {
+            let item = item.split(';').next().unwrap_or_default();
+            if let Some((key, value)) = item.split_once('=') {
+                let key = decode_baggage(key.trim());
+                if !key.is_empty() {
+                    self.baggage.insert(key, decode_baggage(value.trim()));
+                }
+            }
+        }
+        self
+    }
+
+    /// Trace context of the current task, set by `scope`
+    pub fn current() -> Option<TraceState> {
+        CURRENT_TRACE.try_with(Clone::clone).ok()
+    }
+
+    /// Run `future` with this state as the current trace context
+    ///
+    /// Spans started without an explicit parent inside `future` become
+    /// children of this state and inherit its baggage.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_TRACE.scope(self, future).await
+    }
+}
+
+/// Run `future` in the caller's trace context
+///
+/// Task-local context does not cross `tokio::spawn`; wrap the future of a
+/// spawned task (e.g. an enrichment task) to keep the request's baggage.
+pub fn in_current_trace<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let trace_state = TraceState::current();
+    async move {
+        match trace_state {
+            Some(trace_state) => trace_state.scope(future).await,
+            None => future.await,
+        }
+    }
+}
+
+/// Percent-encode the characters the baggage header reserves
+fn encode_baggage(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b',' | b';' | b'=' | b'%' | b'"' | b'\\' => encoded.push_str(&format!("%{:02X}", byte)),
+            byte if byte.is_ascii_graphic() => encoded.push(byte as char),
+            byte => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Decode a percent-encoded baggage key or value
+fn decode_baggage(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Stage of the ingest pipeline, traced as a child span of the ingest
+/// request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IngestStage {
+    Validate,
+    Enrich,
+    Batch,
+    Flush,
+    Store,
+}
+
+impl IngestStage {
+    /// Span name and `stage` metric label
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IngestStage::Validate => "validate",
+            IngestStage::Enrich => "enrich",
+            IngestStage::Batch => "batch",
+            IngestStage::Flush => "flush",
+            IngestStage::Store => "store",
+        }
+    }
 }
 
 /// Span kind
@@ -234,6 +392,11 @@ impl Span {
         self
     }
 
+    /// Whether the tracer sampled this span
+    pub fn is_sampled(&self) -> bool {
+        !matches!(&self.status, Status::Error { code, .. } if code == "NOT_SAMPLED")
+    }
+
     /// Get span duration in milliseconds
     pub fn duration_ms(&self) -> Option<u64> {
         if let Some(end) = self.end_time {
@@ -286,6 +449,8 @@ pub struct Tracer {
     max_events: usize,
     /// Maximum links per span
     max_links: usize,
+    /// Where finished spans are exported
+    recorder: Option<Arc<Mutex<SpanRecorder>>>,
 }
 
 impl Tracer {
@@ -297,6 +462,23 @@ impl Tracer {
             max_attributes: 128,
             max_events: 128,
             max_links: 128,
+            recorder: None,
+        }
+    }
+
+    /// Export spans finished with `end_span` to `recorder`
+    pub fn with_recorder(mut self, recorder: Arc<Mutex<SpanRecorder>>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Close a span and export it, unless it was not sampled
+    pub fn end_span(&self, span: Span) {
+        let span = span.close();
+        if let Some(recorder) = &self.recorder
+            && span.is_sampled()
+        {
+            recorder.lock().unwrap().record_span(span);
         }
     }
 
@@ -307,8 +489,15 @@ impl Tracer {
     }
 
     /// Start a new span
+    ///
+    /// Without a trace state, the span is a child of the current trace
+    /// context (see `TraceState::scope`), or a new root outside one. Baggage
+    /// items are recorded as `baggage.<key>` attributes so spans can be
+    /// filtered by tenant.
     pub fn start_span(&self, name: &str, kind: SpanKind, trace_state: Option<TraceState>) -> Span {
-        let trace_state = trace_state.unwrap_or_else(TraceState::new_root);
+        let trace_state = trace_state
+            .or_else(|| TraceState::current().map(|current| current.new_child()))
+            .unwrap_or_else(TraceState::new_root);
 
         // Check sampling
         if !self.sampling_strategy.should_sample() {
@@ -332,6 +521,11 @@ impl Tracer {
             ))
             .with_attribute(SpanAttribute::string("telemetry.sdk.name", "hodei-audit"));
 
+        let baggage: BTreeMap<_, _> = span.trace_state.baggage.clone().into_iter().collect();
+        for (key, value) in baggage {
+            span = span.with_attribute(SpanAttribute::string(&format!("baggage.{}", key), &value));
+        }
+
         span
     }
 
@@ -363,12 +557,25 @@ impl Tracer {
         for (key, value) in &span.trace_state.baggage {
             headers.insert(format!("baggage-{}", key), value.clone());
         }
+        if !span.trace_state.baggage.is_empty() {
+            headers.insert(
+                BAGGAGE_HEADER.to_string(),
+                span.trace_state.baggage_header(),
+            );
+        }
 
         headers
     }
 
     /// Extract trace context from headers
     pub fn extract_context(&self, headers: &HashMap<String, String>) -> Option<TraceState> {
+        TraceState::extract(headers)
+    }
+}
+
+impl TraceState {
+    /// Trace context carried by `traceparent` and baggage headers
+    pub fn extract(headers: &HashMap<String, String>) -> Option<TraceState> {
         // Try to extract from traceparent header
         if let Some(traceparent) = headers.get("traceparent") {
             let parts: Vec<&str> = traceparent.split('-').collect();
@@ -378,6 +585,9 @@ impl Tracer {
                 let flags = parts[0].parse().unwrap_or(0);
 
                 let mut trace_state = TraceState::new(trace_id, span_id).with_flags(flags);
+                if let Some(baggage) = headers.get(BAGGAGE_HEADER) {
+                    trace_state = trace_state.with_baggage_header(baggage);
+                }
 
                 // Extract baggage
                 for (key, value) in headers {
@@ -583,6 +793,24 @@ mod tests {
         assert_eq!(trace_state.baggage.get("key1"), Some(&"value1".to_string()));
     }
 
+    #[test]
+    fn test_end_span_exports_sampled_spans() {
+        let recorder = Arc::new(Mutex::new(SpanRecorder::new()));
+        let tracer = Tracer::new("test-service")
+            .with_sampling_strategy(SamplingStrategy::Always)
+            .with_recorder(recorder.clone());
+        tracer.end_span(tracer.start_root_span("sampled"));
+
+        let unsampled = tracer
+            .clone()
+            .with_sampling_strategy(SamplingStrategy::Never);
+        unsampled.end_span(unsampled.start_root_span("dropped"));
+
+        let recorder = recorder.lock().unwrap();
+        assert_eq!(recorder.get_spans().len(), 1);
+        assert!(recorder.get_spans()[0].end_time.is_some());
+    }
+
     #[test]
     fn test_span_recorder() {
         let mut recorder = SpanRecorder::new();
@@ -598,4 +826,56 @@ mod tests {
         assert_eq!(recorder.find_by_name("span1").len(), 1);
         assert_eq!(recorder.find_by_name("span2").len(), 1);
     }
+
+    #[test]
+    fn test_baggage_header_round_trip() {
+        let trace_state = TraceState::new_root()
+            .with_baggage(TENANT_ID_BAGGAGE, "tenant a")
+            .with_baggage("note", "x=1,y;2%");
+        let header = trace_state.baggage_header();
+        assert_eq!(header, "note=x%3D1%2Cy%3B2%25,tenant_id=tenant%20a");
+
+        let parsed = TraceState::new_root().with_baggage_header(&format!("{};prop=1,bad", header));
+        assert_eq!(parsed.baggage, trace_state.baggage);
+    }
+
+    #[test]
+    fn test_inject_extract_propagates_baggage_header() {
+        let tracer = Tracer::new("test-service");
+        let span = tracer.start_span(
+            "ingest",
+            SpanKind::Server,
+            Some(TraceState::new_root().with_baggage(TENANT_ID_BAGGAGE, "tenant-a")),
+        );
+        let mut headers = tracer.inject_context(&span);
+        headers.retain(|key, _| !key.starts_with("baggage-"));
+
+        let extracted = tracer.extract_context(&headers).unwrap();
+        assert_eq!(extracted.baggage_item(TENANT_ID_BAGGAGE), Some("tenant-a"));
+    }
+
+    #[tokio::test]
+    async fn test_spans_in_scope_are_children_of_current_trace() {
+        let tracer = Tracer::new("test-service");
+        assert!(TraceState::current().is_none());
+        assert!(
+            tracer
+                .start_span("outside", SpanKind::Internal, None)
+                .trace_state
+                .parent_span_id
+                .is_none()
+        );
+
+        let request = TraceState::new_root().with_baggage(TENANT_ID_BAGGAGE, "tenant-a");
+        let span = request
+            .clone()
+            .scope(async { tracer.start_span("inside", SpanKind::Internal, None) })
+            .await;
+        assert_eq!(span.trace_state.trace_id, request.trace_id);
+        assert_eq!(span.trace_state.parent_span_id, Some(request.span_id));
+        assert_eq!(
+            span.trace_state.baggage_item(TENANT_ID_BAGGAGE),
+            Some("tenant-a")
+        );
+    }
 }
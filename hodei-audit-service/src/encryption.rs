@@ -0,0 +1,426 @@
+//! Per-Tenant Encryption at Rest
+//!
+//! Envelope encryption for objects in shared warm/cold storage. Each object
+//! is encrypted with a fresh AES-256-GCM data key; the data key is wrapped
+//! by the tenant's customer master key (CMK) in a KMS and stored next to the
+//! object as metadata. Revoking a tenant's CMK makes every data key of that
+//! tenant unrecoverable, which cryptographically erases only their data.
+//!
+//! The tenant id is bound to every ciphertext as associated data, so an
+//! object can't be decrypted as if it belonged to another tenant.
+
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::{AeadCore, Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use thiserror::Error;
+
+/// Object metadata key holding the wrapped data key (hex)
+pub const WRAPPED_KEY_METADATA: &str = "x-amz-meta-hodei-wrapped-key";
+/// Object metadata key holding the id of the CMK that wrapped the data key
+pub const KEY_ID_METADATA: &str = "x-amz-meta-hodei-key-id";
+/// Object metadata key holding the object nonce (hex)
+pub const NONCE_METADATA: &str = "x-amz-meta-hodei-nonce";
+/// Object metadata key holding the tenant that owns the object
+pub const TENANT_METADATA: &str = "x-amz-meta-hodei-tenant-id";
+/// Object metadata key holding the encryption algorithm
+pub const ALGORITHM_METADATA: &str = "x-amz-meta-hodei-encryption";
+
+const ALGORITHM: &str = "AES256-GCM";
+const NONCE_LEN: usize = 12;
+
+/// Encryption errors
+#[derive(Debug, Error)]
+pub enum EncryptionError {
+    #[error("No key for tenant {0}")]
+    KeyNotFound(String),
+
+    #[error("Decryption failed: {0}")]
+    Decryption(String),
+
+    #[error("Missing or invalid encryption metadata: {0}")]
+    Metadata(String),
+
+    #[error("KMS error: {0}")]
+    Kms(String),
+
+    #[error("Unknown field path: {0}")]
+    UnknownField(String),
+}
+
+/// Data key wrapped by a tenant CMK
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedKey {
+    /// Id of the CMK that wrapped the key
+    pub key_id: String,
+    /// Nonce followed by the encrypted data key
+    pub ciphertext: Vec<u8>,
+}
+
+/// Port to a key management service holding one CMK per tenant
+///
+/// Adapters for a remote KMS connect through the deployment's
+/// `OutboundTlsConfig`, like every other outbound client.
+#[async_trait]
+pub trait KmsClient: Send + Sync + 'static {
+    /// Wrap a data key with the tenant's CMK
+    async fn wrap_key(
+        &self,
+        tenant_id: &str,
+        data_key: &[u8],
+    ) -> Result<WrappedKey, EncryptionError>;
+
+    /// Unwrap a data key with the tenant's CMK
+    async fn unwrap_key(
+        &self,
+        tenant_id: &str,
+        wrapped: &WrappedKey,
+    ) -> Result<Vec<u8>, EncryptionError>;
+}
+
+/// In-memory KMS (development and testing)
+///
+/// A tenant's CMK is created on first use.
+#[derive(Debug, Default)]
+pub struct InMemoryKms {
+    master_keys: RwLock<HashMap<String, (String, Vec<u8>)>>,
+}
+
+impl InMemoryKms {
+    /// Create an empty KMS
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delete a tenant's CMK, making its wrapped keys unrecoverable
+    pub fn revoke(&self, tenant_id: &str) -> bool {
+        self.master_keys
+            .write()
+            .unwrap()
+            .remove(tenant_id)
+            .is_some()
+    }
+
+    fn master_key(&self, tenant_id: &str) -> Option<(String, Vec<u8>)> {
+        self.master_keys.read().unwrap().get(tenant_id).cloned()
+    }
+}
+
+#[async_trait]
+impl KmsClient for InMemoryKms {
+    async fn wrap_key(
+        &self,
+        tenant_id: &str,
+        data_key: &[u8],
+    ) -> Result<WrappedKey, EncryptionError> {
+        let (key_id, master_key) = self
+            .master_keys
+            .write()
+            .unwrap()
+            .entry(tenant_id.to_string())
+            .or_insert_with(|| {
+                (
+                    format!("cmk-{}-{}", tenant_id, uuid::Uuid::new_v4().simple()),
+                    generate_key(),
+                )
+            })
+            .clone();
+
+        Ok(WrappedKey {
+            key_id,
+            ciphertext: seal(&master_key, data_key, tenant_id.as_bytes())?,
+        })
+    }
+
+    async fn unwrap_key(
+        &self,
+        tenant_id: &str,
+        wrapped: &WrappedKey,
+    ) -> Result<Vec<u8>, EncryptionError> {
+        let (key_id, master_key) = self
+            .master_key(tenant_id)
+            .ok_or_else(|| EncryptionError::KeyNotFound(tenant_id.to_string()))?;
+        if key_id != wrapped.key_id {
+            return Err(EncryptionError::Kms(format!(
+                "key {} is not the CMK of tenant {}",
+                wrapped.key_id, tenant_id
+            )));
+        }
+        open(&master_key, &wrapped.ciphertext, tenant_id.as_bytes())
+    }
+}
+
+/// KMS keeping one CMK per tenant in a local directory
+///
+/// For single-node deployments without a managed KMS: CMKs survive
+/// restarts, so data keys wrapped by them stay recoverable. The directory
+/// must be readable by the service only; deployments with a managed KMS
+/// implement `KmsClient` for it instead.
+#[derive(Debug)]
+pub struct FileKms {
+    dir: PathBuf,
+    master_keys: RwLock<HashMap<String, (String, Vec<u8>)>>,
+    /// Serializes CMK creation so a tenant never gets two
+    create_lock: tokio::sync::Mutex<()>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoredMasterKey {
+    key_id: String,
+    key: String,
+}
+
+impl FileKms {
+    /// Keep CMKs under `dir`, creating it if needed
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, EncryptionError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| EncryptionError::Kms(e.to_string()))?;
+        Ok(Self {
+            dir,
+            master_keys: RwLock::new(HashMap::new()),
+            create_lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    fn key_path(&self, tenant_id: &str) -> PathBuf {
+        // Tenant ids are hex-encoded so they can't escape the directory
+        self.dir.join(format!("{}.cmk", hex::encode(tenant_id)))
+    }
+
+    /// CMK of a tenant, from the cache or its file
+    async fn master_key(
+        &self,
+        tenant_id: &str,
+    ) -> Result<Option<(String, Vec<u8>)>, EncryptionError> {
+        if let Some(key) = self.master_keys.read().unwrap().get(tenant_id) {
+            return Ok(Some(key.clone()));
+        }
+        let body = match tokio::fs::read(self.key_path(tenant_id)).await {
+            Ok(body) => body,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(EncryptionError::Kms(e.to_string())),
+        };
+        let stored: StoredMasterKey =
+            serde_json::from_slice(&body).map_err(|e| EncryptionError::Kms(e.to_string()))?;
+        let key = hex::decode(&stored.key).map_err(|e| EncryptionError::Kms(e.to_string()))?;
+        let entry = (stored.key_id, key);
+        self.master_keys
+            .write()
+            .unwrap()
+            .insert(tenant_id.to_string(), entry.clone());
+        Ok(Some(entry))
+    }
+
+    /// CMK of a tenant, created and persisted on first use
+    async fn master_key_or_create(
+        &self,
+        tenant_id: &str,
+    ) -> Result<(String, Vec<u8>), EncryptionError> {
+        if let Some(key) = self.master_key(tenant_id).await? {
+            return Ok(key);
+        }
+        let _guard = self.create_lock.lock().await;
+        if let Some(key) = self.master_key(tenant_id).await? {
+            return Ok(key);
+        }
+
+        let stored = StoredMasterKey {
+            key_id: format!("cmk-{}-{}", tenant_id, uuid::Uuid::new_v4().simple()),
+            key: hex::encode(generate_key()),
+        };
+        let body = serde_json::to_vec(&stored).map_err(|e| EncryptionError::Kms(e.to_string()))?;
+        let path = self.key_path(tenant_id);
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, body)
+            .await
+            .map_err(|e| EncryptionError::Kms(e.to_string()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(&tmp_path, std::fs::Permissions::from_mode(0o600))
+                .await
+                .map_err(|e| EncryptionError::Kms(e.to_string()))?;
+        }
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .map_err(|e| EncryptionError::Kms(e.to_string()))?;
+
+        self.master_key(tenant_id)
+            .await?
+            .ok_or_else(|| EncryptionError::KeyNotFound(tenant_id.to_string()))
+    }
+}
+
+#[async_trait]
+impl KmsClient for FileKms {
+    async fn wrap_key(
+        &self,
+        tenant_id: &str,
+        data_key: &[u8],
+    ) -> Result<WrappedKey, EncryptionError> {
+        let (key_id, master_key) = self.master_key_or_create(tenant_id).await?;
+        Ok(WrappedKey {
+            key_id,
+            ciphertext: seal(&master_key, data_key, tenant_id.as_bytes())?,
+        })
+    }
+
+    async fn unwrap_key(
+        &self,
+        tenant_id: &str,
+        wrapped: &WrappedKey,
+    ) -> Result<Vec<u8>, EncryptionError> {
+        let (key_id, master_key) = self
+            .master_key(tenant_id)
+            .await?
+            .ok_or_else(|| EncryptionError::KeyNotFound(tenant_id.to_string()))?;
+        if key_id != wrapped.key_id {
+            return Err(EncryptionError::Kms(format!(
+                "key {} is not the CMK of tenant {}",
+                wrapped.key_id, tenant_id
+            )));
+        }
+        open(&master_key, &wrapped.ciphertext, tenant_id.as_bytes())
+    }
+}
+
+/// Object encrypted with a tenant data key
+#[derive(Debug, Clone)]
+pub struct EncryptedObject {
+    pub ciphertext: Vec<u8>,
+    /// Metadata to store with the object; needed to decrypt it
+    pub metadata: HashMap<String, String>,
+}
+
+/// Envelope encryption of objects with per-tenant keys
+pub struct EnvelopeEncryptor {
+    kms: std::sync::Arc<dyn KmsClient>,
+}
+
+impl EnvelopeEncryptor {
+    /// Create an encryptor backed by `kms`
+    pub fn new(kms: std::sync::Arc<dyn KmsClient>) -> Self {
+        Self { kms }
+    }
+
+    /// Encrypt an object body under a fresh data key of the tenant
+    pub async fn encrypt(
+        &self,
+        tenant_id: &str,
+        plaintext: &[u8],
+    ) -> Result<EncryptedObject, EncryptionError> {
+        let data_key = generate_key();
+        let wrapped = self.kms.wrap_key(tenant_id, &data_key).await?;
+        let sealed = seal(&data_key, plaintext, tenant_id.as_bytes())?;
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+
+        let metadata = HashMap::from([
+            (
+                WRAPPED_KEY_METADATA.to_string(),
+                hex::encode(&wrapped.ciphertext),
+            ),
+            (KEY_ID_METADATA.to_string(), wrapped.key_id),
+            (NONCE_METADATA.to_string(), hex::encode(nonce)),
+            (TENANT_METADATA.to_string(), tenant_id.to_string()),
+            (ALGORITHM_METADATA.to_string(), ALGORITHM.to_string()),
+        ]);
+
+        Ok(EncryptedObject {
+            ciphertext: ciphertext.to_vec(),
+            metadata,
+        })
+    }
+
+    /// Decrypt an object body of `tenant_id` using its stored metadata
+    pub async fn decrypt(
+        &self,
+        tenant_id: &str,
+        ciphertext: &[u8],
+        metadata: &HashMap<String, String>,
+    ) -> Result<Vec<u8>, EncryptionError> {
+        let field = |key: &str| {
+            metadata
+                .get(key)
+                .ok_or_else(|| EncryptionError::Metadata(key.to_string()))
+        };
+        let hex_field = |key: &str| {
+            field(key).and_then(|value| {
+                hex::decode(value).map_err(|_| EncryptionError::Metadata(key.to_string()))
+            })
+        };
+
+        if field(ALGORITHM_METADATA)? != ALGORITHM {
+            return Err(EncryptionError::Metadata(ALGORITHM_METADATA.to_string()));
+        }
+        let wrapped = WrappedKey {
+            key_id: field(KEY_ID_METADATA)?.clone(),
+            ciphertext: hex_field(WRAPPED_KEY_METADATA)?,
+        };
+        let data_key = self.kms.unwrap_key(tenant_id, &wrapped).await?;
+
+        let mut sealed = hex_field(NONCE_METADATA)?;
+        sealed.extend_from_slice(ciphertext);
+        open(&data_key, &sealed, tenant_id.as_bytes())
+    }
+}
+
+impl std::fmt::Debug for EnvelopeEncryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnvelopeEncryptor").finish_non_exhaustive()
+    }
+}
+
+/// Random AES-256 key
+pub(crate) fn generate_key() -> Vec<u8> {
+    Aes256Gcm::generate_key(OsRng).to_vec()
+}
+
+/// AES-256-GCM encryption; returns the nonce followed by the ciphertext
+pub(crate) fn seal(key: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    let cipher = cipher(key)?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| EncryptionError::Kms("encryption failed".to_string()))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Inverse of [`seal`]
+pub(crate) fn open(key: &[u8], sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    if sealed.len() < NONCE_LEN {
+        return Err(EncryptionError::Decryption(
+            "ciphertext too short".to_string(),
+        ));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    cipher(key)?
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| EncryptionError::Decryption("authentication failed".to_string()))
+}
+
+fn cipher(key: &[u8]) -> Result<Aes256Gcm, EncryptionError> {
+    if key.len() != 32 {
+        return Err(EncryptionError::Decryption(format!(
+            "invalid key length {}",
+            key.len()
+        )));
+    }
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)))
+}
@@ -1,52 +1,426 @@
 //! Event Enrichment Pipeline
+//!
+//! Which enrichment steps run is decided per event by `EnrichmentConfig`:
+//! the first matching rule picks the steps, so the expensive lookups can be
+//! reserved for failures and security events. Batches are enriched
+//! concurrently, in input order unless the consumer opts out.
+//!
+//! Derived metadata is written into the event by default. With an
+//! `EnrichmentSink` configured it goes to the sink instead, as an
+//! `EnrichmentRecord` keyed by event_id, and stored events stay lean.
 
+use futures::stream::{self, StreamExt};
 use hodei_audit_proto::AuditEvent;
-use std::sync::Arc;
+use hodei_audit_types::{EventCategory, Outcome};
+use prost_types::value::Kind;
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
-use tracing::info;
+use tracing::{info, warn};
+
+/// Default number of events `enrich_batch` enriches at once
+pub const DEFAULT_MAX_CONCURRENT_ENRICHMENTS: usize = 16;
+
+/// A single enrichment step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnrichmentStep {
+    /// Stamp `processed_at`
+    ProcessedAt,
+    /// Classify the source IP (`geo_ip_scope` metadata)
+    GeoIp,
+    /// Add the caller's roles and email domain to the metadata
+    UserContext,
+}
+
+impl EnrichmentStep {
+    /// Every step, cheapest first
+    pub const ALL: [EnrichmentStep; 3] = [Self::ProcessedAt, Self::GeoIp, Self::UserContext];
+}
+
+/// Metadata derived for one event, kept apart from the event itself
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnrichmentRecord {
+    pub event_id: String,
+    pub tenant_id: String,
+    pub fields: HashMap<String, String>,
+}
+
+/// Side channel receiving derived metadata instead of the event
+pub trait EnrichmentSink: Send + Sync + fmt::Debug {
+    fn record(&self, record: EnrichmentRecord);
+}
+
+/// `EnrichmentSink` keeping records in memory, keyed by event_id
+#[derive(Debug, Default)]
+pub struct InMemoryEnrichmentSink {
+    records: Mutex<HashMap<String, EnrichmentRecord>>,
+}
+
+impl InMemoryEnrichmentSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Derived metadata of an event
+    pub fn get(&self, event_id: &str) -> Option<EnrichmentRecord> {
+        self.records.lock().unwrap().get(event_id).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl EnrichmentSink for InMemoryEnrichmentSink {
+    fn record(&self, record: EnrichmentRecord) {
+        self.records
+            .lock()
+            .unwrap()
+            .insert(record.event_id.clone(), record);
+    }
+}
+
+/// Runs `steps` on the events matching a predicate
+#[derive(Clone)]
+pub struct EnrichmentRule {
+    name: String,
+    predicate: Arc<dyn Fn(&AuditEvent) -> bool + Send + Sync>,
+    steps: Vec<EnrichmentStep>,
+}
+
+impl EnrichmentRule {
+    /// Create a rule applying `steps` to the events matching `predicate`
+    pub fn new(
+        name: impl Into<String>,
+        predicate: impl Fn(&AuditEvent) -> bool + Send + Sync + 'static,
+        steps: Vec<EnrichmentStep>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            predicate: Arc::new(predicate),
+            steps,
+        }
+    }
+
+    /// Rule name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the event matches this rule
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        (self.predicate)(event)
+    }
+}
+
+impl fmt::Debug for EnrichmentRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnrichmentRule")
+            .field("name", &self.name)
+            .field("steps", &self.steps)
+            .finish()
+    }
+}
+
+/// Enrichment configuration
+#[derive(Debug, Clone)]
+pub struct EnrichmentConfig {
+    /// Rules checked in order; the first match decides the steps
+    pub rules: Vec<EnrichmentRule>,
+    /// Steps for events no rule matches
+    pub default_steps: Vec<EnrichmentStep>,
+    /// Events `enrich_batch` enriches at once
+    pub max_concurrent: usize,
+    /// Return `enrich_batch` results in input order; turning it off lets
+    /// order-insensitive consumers take each event as soon as it's done
+    pub preserve_order: bool,
+    /// Where derived metadata goes; `None` writes it into the event
+    pub sink: Option<Arc<dyn EnrichmentSink>>,
+}
+
+impl EnrichmentConfig {
+    /// Fully enrich failures and insight (security) events, and skip the
+    /// user-context lookup for successful reads
+    pub fn outcome_based() -> Self {
+        Self {
+            rules: vec![
+                EnrichmentRule::new(
+                    "failures",
+                    |event: &AuditEvent| {
+                        matches!(
+                            Outcome::from(event.outcome),
+                            Outcome::Failure | Outcome::Error | Outcome::Denied
+                        )
+                    },
+                    EnrichmentStep::ALL.to_vec(),
+                ),
+                EnrichmentRule::new(
+                    "security",
+                    |event: &AuditEvent| {
+                        EventCategory::from(event.event_category) == EventCategory::Insight
+                    },
+                    EnrichmentStep::ALL.to_vec(),
+                ),
+                EnrichmentRule::new(
+                    "successful-reads",
+                    |event: &AuditEvent| {
+                        event.read_only && Outcome::from(event.outcome) == Outcome::Success
+                    },
+                    vec![EnrichmentStep::ProcessedAt, EnrichmentStep::GeoIp],
+                ),
+            ],
+            ..Self::default()
+        }
+    }
+
+    /// Append a rule, checked after the existing ones
+    pub fn with_rule(mut self, rule: EnrichmentRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Enrich at most `max_concurrent` events of a batch at once
+    pub fn with_max_concurrent(mut self, max_concurrent: usize) -> Self {
+        self.max_concurrent = max_concurrent;
+        self
+    }
+
+    /// Whether `enrich_batch` keeps input order
+    pub fn with_preserve_order(mut self, preserve_order: bool) -> Self {
+        self.preserve_order = preserve_order;
+        self
+    }
+
+    /// Send derived metadata to `sink` instead of the event's metadata
+    pub fn with_sink(mut self, sink: Arc<dyn EnrichmentSink>) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Steps to run for an event
+    pub fn steps_for(&self, event: &AuditEvent) -> &[EnrichmentStep] {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(event))
+            .map(|rule| rule.steps.as_slice())
+            .unwrap_or(&self.default_steps)
+    }
+}
+
+impl Default for EnrichmentConfig {
+    /// Every event gets every step
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            default_steps: EnrichmentStep::ALL.to_vec(),
+            max_concurrent: DEFAULT_MAX_CONCURRENT_ENRICHMENTS,
+            preserve_order: true,
+            sink: None,
+        }
+    }
+}
 
 /// Enrichment statistics
 #[derive(Debug, Clone, Default)]
 pub struct EnrichmentStats {
     pub total_events: u64,
     pub enriched_events: u64,
+    pub failed_events: u64,
+    /// Times each step ran
+    pub steps_applied: HashMap<EnrichmentStep, u64>,
+}
+
+impl EnrichmentStats {
+    fn merge(&mut self, other: EnrichmentStats) {
+        self.total_events += other.total_events;
+        self.enriched_events += other.enriched_events;
+        self.failed_events += other.failed_events;
+        for (step, count) in other.steps_applied {
+            *self.steps_applied.entry(step).or_default() += count;
+        }
+    }
+}
+
+/// Result of enriching a batch
+#[derive(Debug, Clone, Default)]
+pub struct BatchEnrichResult {
+    /// Events enriched successfully, in input order when
+    /// `EnrichmentConfig::preserve_order` is set
+    pub enriched: Vec<AuditEvent>,
+    /// Events that could not be enriched, unchanged, with the reason
+    pub failed: Vec<(AuditEvent, String)>,
 }
 
 /// Event Enricher - Basic implementation
 pub struct EventEnricher {
+    config: EnrichmentConfig,
     stats: Arc<RwLock<EnrichmentStats>>,
+    /// Simulated per-event enrichment latency
+    #[cfg(test)]
+    latency: Option<fn(&AuditEvent) -> std::time::Duration>,
 }
 
 impl EventEnricher {
     pub fn new() -> Self {
-        info!("Initializing EventEnricher");
+        Self::with_config(EnrichmentConfig::default())
+    }
+
+    pub fn with_config(config: EnrichmentConfig) -> Self {
+        info!(
+            "Initializing EventEnricher with {} enrichment rules",
+            config.rules.len()
+        );
         Self {
+            config,
             stats: Arc::new(RwLock::new(EnrichmentStats::default())),
+            #[cfg(test)]
+            latency: None,
         }
     }
 
     pub async fn enrich(&self, mut event: AuditEvent) -> Result<AuditEvent, String> {
         let mut stats = self.stats.write().await;
-        stats.total_events += 1;
+        self.enrich_in_place(&mut event, &mut stats)?;
+        Ok(event)
+    }
 
-        // Add processed_at timestamp
-        let processed_at = chrono::Utc::now();
-        let timestamp = prost_types::Timestamp {
-            seconds: processed_at.timestamp(),
-            nanos: processed_at.timestamp_subsec_nanos() as i32,
+    /// Enrich every event, setting aside the ones that fail instead of
+    /// failing the whole batch
+    ///
+    /// Up to `max_concurrent` events are enriched at once; results come back
+    /// in input order only with `preserve_order`.
+    pub async fn enrich_batch(&self, events: Vec<AuditEvent>) -> BatchEnrichResult {
+        let max_concurrent = self.config.max_concurrent.max(1);
+        let enrichments = stream::iter(events).map(|event| self.enrich_one(event));
+        let outcomes: Vec<_> = if self.config.preserve_order {
+            enrichments.buffered(max_concurrent).collect().await
+        } else {
+            enrichments.buffer_unordered(max_concurrent).collect().await
         };
-        event.processed_at = Some(timestamp);
 
-        stats.enriched_events += 1;
-        Ok(event)
+        let mut stats = self.stats.write().await;
+        let mut result = BatchEnrichResult::default();
+        for (event, outcome, event_stats) in outcomes {
+            stats.merge(event_stats);
+            match outcome {
+                Ok(()) => result.enriched.push(event),
+                Err(e) => {
+                    warn!(
+                        "Enrichment failed for event {}: {}",
+                        event
+                            .event_id
+                            .as_ref()
+                            .map(|id| id.value.as_str())
+                            .unwrap_or("<none>"),
+                        e
+                    );
+                    result.failed.push((event, e));
+                }
+            }
+        }
+        result
     }
 
-    pub async fn enrich_batch(&self, events: Vec<AuditEvent>) -> Result<Vec<AuditEvent>, String> {
-        let mut results = Vec::with_capacity(events.len());
-        for event in events {
-            results.push(self.enrich(event).await?);
+    /// Enrich one event of a batch, with its own stats
+    async fn enrich_one(
+        &self,
+        mut event: AuditEvent,
+    ) -> (AuditEvent, Result<(), String>, EnrichmentStats) {
+        #[cfg(test)]
+        if let Some(latency) = self.latency {
+            tokio::time::sleep(latency(&event)).await;
         }
-        Ok(results)
+        let mut stats = EnrichmentStats::default();
+        let outcome = self.enrich_in_place(&mut event, &mut stats);
+        (event, outcome, stats)
+    }
+
+    /// Make each event take `latency(event)` to enrich
+    #[cfg(test)]
+    fn with_simulated_latency(mut self, latency: fn(&AuditEvent) -> std::time::Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Run the configured steps on an event
+    ///
+    /// The event is validated before any step runs, so on error it is left
+    /// untouched. With a sink, an event needs an event_id to key its record.
+    fn enrich_in_place(
+        &self,
+        event: &mut AuditEvent,
+        stats: &mut EnrichmentStats,
+    ) -> Result<(), String> {
+        stats.total_events += 1;
+        if event
+            .tenant_id
+            .as_ref()
+            .is_none_or(|tenant| tenant.value.is_empty())
+        {
+            stats.failed_events += 1;
+            return Err("event has no tenant_id".to_string());
+        }
+        if self.config.sink.is_some()
+            && event.event_id.as_ref().is_none_or(|id| id.value.is_empty())
+        {
+            stats.failed_events += 1;
+            return Err("event has no event_id".to_string());
+        }
+
+        let mut derived = HashMap::new();
+        for step in self.config.steps_for(event) {
+            match step {
+                EnrichmentStep::ProcessedAt => {
+                    let processed_at = chrono::Utc::now();
+                    event.processed_at = Some(prost_types::Timestamp {
+                        seconds: processed_at.timestamp(),
+                        nanos: processed_at.timestamp_subsec_nanos() as i32,
+                    });
+                }
+                EnrichmentStep::GeoIp => {
+                    if let Some(scope) = event
+                        .http_context
+                        .as_ref()
+                        .map(|http| ip_scope(&http.source_ip))
+                    {
+                        derived.insert("geo_ip_scope".to_string(), scope.to_string());
+                    }
+                }
+                EnrichmentStep::UserContext => {
+                    if let Some(user) = event.user_identity.clone() {
+                        derived.insert("user_roles".to_string(), user.roles.join(","));
+                        if let Some((_, domain)) = user.email.split_once('@') {
+                            derived.insert("user_email_domain".to_string(), domain.to_string());
+                        }
+                    }
+                }
+            }
+            *stats.steps_applied.entry(*step).or_default() += 1;
+        }
+
+        match &self.config.sink {
+            Some(sink) if !derived.is_empty() => sink.record(EnrichmentRecord {
+                event_id: event.event_id.clone().unwrap_or_default().value,
+                tenant_id: event.tenant_id.clone().unwrap_or_default().value,
+                fields: derived,
+            }),
+            Some(_) => {}
+            None => {
+                for (key, value) in derived {
+                    set_metadata(event, &key, value);
+                }
+            }
+        }
+
+        event.enriched = true;
+        stats.enriched_events += 1;
+        Ok(())
     }
 
     pub async fn get_stats(&self) -> EnrichmentStats {
@@ -60,6 +434,30 @@ impl Default for EventEnricher {
     }
 }
 
+/// Coarse location of a source IP: loopback, private, public or invalid
+fn ip_scope(source_ip: &str) -> &'static str {
+    match source_ip.parse::<IpAddr>() {
+        Ok(ip) if ip.is_loopback() => "loopback",
+        Ok(IpAddr::V4(ip)) if ip.is_private() || ip.is_link_local() => "private",
+        Ok(IpAddr::V6(ip)) if ip.is_unique_local() || ip.is_unicast_link_local() => "private",
+        Ok(_) => "public",
+        Err(_) => "invalid",
+    }
+}
+
+fn set_metadata(event: &mut AuditEvent, key: &str, value: String) {
+    event
+        .metadata
+        .get_or_insert_with(Default::default)
+        .fields
+        .insert(
+            key.to_string(),
+            prost_types::Value {
+                kind: Some(Kind::StringValue(value)),
+            },
+        );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,11 +528,183 @@ mod tests {
     async fn test_enrich_batch() {
         let enricher = EventEnricher::new();
         let events = vec![create_test_event(), create_test_event()];
-        let enriched = enricher.enrich_batch(events).await.unwrap();
-        assert_eq!(enriched.len(), 2);
+        let result = enricher.enrich_batch(events).await;
+        assert_eq!(result.enriched.len(), 2);
+        assert!(result.failed.is_empty());
 
         let stats = enricher.get_stats().await;
         assert_eq!(stats.total_events, 2);
         assert_eq!(stats.enriched_events, 2);
     }
+
+    #[tokio::test]
+    async fn test_enrich_batch_isolates_poison_events() {
+        let enricher = EventEnricher::new();
+        let poison = AuditEvent {
+            event_id: Some(EventId {
+                value: "poison".to_string(),
+            }),
+            tenant_id: None,
+            ..create_test_event()
+        };
+        let events = vec![create_test_event(), poison.clone(), create_test_event()];
+
+        let result = enricher.enrich_batch(events).await;
+
+        assert_eq!(result.enriched.len(), 2);
+        assert!(result.enriched.iter().all(|e| e.enriched));
+        assert_eq!(result.failed.len(), 1);
+        let (failed, reason) = &result.failed[0];
+        assert_eq!(failed, &poison);
+        assert_eq!(reason, "event has no tenant_id");
+
+        let stats = enricher.get_stats().await;
+        assert_eq!(stats.total_events, 3);
+        assert_eq!(stats.enriched_events, 2);
+        assert_eq!(stats.failed_events, 1);
+    }
+
+    fn metadata_keys(event: &AuditEvent) -> Vec<&str> {
+        let mut keys: Vec<&str> = event
+            .metadata
+            .iter()
+            .flat_map(|m| m.fields.keys().map(String::as_str))
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[tokio::test]
+    async fn test_security_failure_gets_every_step() {
+        let enricher = EventEnricher::with_config(EnrichmentConfig::outcome_based());
+        let event = AuditEvent {
+            event_category: i32::from(EventCategory::Insight),
+            outcome: i32::from(Outcome::Failure),
+            read_only: false,
+            ..create_test_event()
+        };
+
+        let enriched = enricher.enrich(event).await.unwrap();
+
+        assert!(enriched.processed_at.is_some());
+        assert_eq!(
+            metadata_keys(&enriched),
+            vec!["geo_ip_scope", "user_email_domain", "user_roles"]
+        );
+        let stats = enricher.get_stats().await;
+        for step in EnrichmentStep::ALL {
+            assert_eq!(stats.steps_applied.get(&step), Some(&1));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_successful_read_skips_user_context() {
+        let enricher = EventEnricher::with_config(EnrichmentConfig::outcome_based());
+        let event = AuditEvent {
+            event_category: i32::from(EventCategory::Data),
+            outcome: i32::from(Outcome::Success),
+            read_only: true,
+            ..create_test_event()
+        };
+
+        let enriched = enricher.enrich(event).await.unwrap();
+
+        assert!(enriched.enriched);
+        assert!(enriched.processed_at.is_some());
+        assert_eq!(metadata_keys(&enriched), vec!["geo_ip_scope"]);
+        let stats = enricher.get_stats().await;
+        assert_eq!(stats.steps_applied.get(&EnrichmentStep::GeoIp), Some(&1));
+        assert_eq!(stats.steps_applied.get(&EnrichmentStep::UserContext), None);
+    }
+
+    fn numbered_events(count: usize) -> Vec<AuditEvent> {
+        (0..count)
+            .map(|i| AuditEvent {
+                event_id: Some(EventId {
+                    value: i.to_string(),
+                }),
+                ..create_test_event()
+            })
+            .collect()
+    }
+
+    /// Later events finish first
+    fn decreasing_latency(event: &AuditEvent) -> std::time::Duration {
+        let index: u64 = event.event_id.as_ref().unwrap().value.parse().unwrap();
+        std::time::Duration::from_millis((5 - index) * 10)
+    }
+
+    fn ids(events: &[AuditEvent]) -> Vec<&str> {
+        events
+            .iter()
+            .map(|e| e.event_id.as_ref().unwrap().value.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_enrich_batch_preserves_input_order() {
+        let enricher = EventEnricher::with_config(EnrichmentConfig::default())
+            .with_simulated_latency(decreasing_latency);
+
+        let result = enricher.enrich_batch(numbered_events(5)).await;
+
+        assert_eq!(ids(&result.enriched), vec!["0", "1", "2", "3", "4"]);
+        assert_eq!(enricher.get_stats().await.enriched_events, 5);
+    }
+
+    #[tokio::test]
+    async fn test_enrich_batch_unordered_returns_fastest_first() {
+        let enricher =
+            EventEnricher::with_config(EnrichmentConfig::default().with_preserve_order(false))
+                .with_simulated_latency(decreasing_latency);
+
+        let result = enricher.enrich_batch(numbered_events(5)).await;
+
+        assert_eq!(ids(&result.enriched), vec!["4", "3", "2", "1", "0"]);
+        let stats = enricher.get_stats().await;
+        assert_eq!(stats.total_events, 5);
+        assert_eq!(stats.steps_applied.get(&EnrichmentStep::GeoIp), Some(&5));
+    }
+
+    #[tokio::test]
+    async fn test_sink_keeps_stored_metadata_lean() {
+        let sink = Arc::new(InMemoryEnrichmentSink::new());
+        let enricher =
+            EventEnricher::with_config(EnrichmentConfig::default().with_sink(sink.clone()));
+
+        let result = enricher.enrich_batch(numbered_events(3)).await;
+
+        assert_eq!(result.enriched.len(), 3);
+        assert!(
+            result
+                .enriched
+                .iter()
+                .all(|e| e.enriched && e.metadata.is_none())
+        );
+        assert_eq!(sink.len(), 3);
+        let record = sink.get("1").unwrap();
+        assert_eq!(record.tenant_id, "test-tenant");
+        assert_eq!(record.fields.get("geo_ip_scope").unwrap(), "loopback");
+        assert_eq!(
+            record.fields.get("user_email_domain").unwrap(),
+            "example.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sink_requires_event_id() {
+        let sink = Arc::new(InMemoryEnrichmentSink::new());
+        let enricher =
+            EventEnricher::with_config(EnrichmentConfig::default().with_sink(sink.clone()));
+        let event = AuditEvent {
+            event_id: None,
+            ..create_test_event()
+        };
+
+        assert_eq!(
+            enricher.enrich(event).await.unwrap_err(),
+            "event has no event_id"
+        );
+        assert!(sink.is_empty());
+    }
 }
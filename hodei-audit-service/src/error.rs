@@ -0,0 +1,285 @@
+//! Crate-level error type
+//!
+//! Subsystems keep their own error types; `AuditServiceError` wraps them so
+//! callers can match on the failing subsystem, and so every gRPC handler maps
+//! a failure to the same `tonic::Status` code.
+
+use crate::api_key::ApiKeyError;
+use crate::compliance::ComplianceError;
+use crate::crypto::ports::digest_chain::DigestChainError;
+use crate::crypto::ports::hashing::HashingError;
+use crate::crypto::ports::signing::SigningError;
+use crate::encryption::EncryptionError;
+use crate::grpc_interceptor::TenantValidationError;
+use crate::key_management::ports::key_manager::KeyManagerError;
+use crate::key_management::ports::key_store::KeyStoreError;
+use crate::quotas::QuotaExceeded;
+use crate::schema_registry::SchemaError;
+use crate::storage::QueryBudgetExceeded;
+use crate::vector::VectorError;
+use thiserror::Error;
+use tonic::Status;
+use tonic::metadata::MetadataValue;
+
+/// Result type of the service crate
+pub type AuditServiceResult<T> = Result<T, AuditServiceError>;
+
+/// Error of any subsystem of the audit service
+#[derive(Debug, Error)]
+pub enum AuditServiceError {
+    #[error("Storage error: {0}")]
+    Storage(anyhow::Error),
+
+    #[error("ClickHouse error: {0}")]
+    ClickHouse(anyhow::Error),
+
+    #[error("S3 error: {0}")]
+    S3(anyhow::Error),
+
+    #[error(transparent)]
+    Compliance(#[from] ComplianceError),
+
+    /// Signing, hashing, digest chain, encryption and key management
+    #[error("Crypto error: {0}")]
+    Crypto(Box<dyn std::error::Error + Send + Sync>),
+
+    #[error(transparent)]
+    Quota(#[from] QuotaExceeded),
+
+    #[error(transparent)]
+    QueryBudget(#[from] QueryBudgetExceeded),
+
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+
+    #[error(transparent)]
+    Vector(#[from] VectorError),
+
+    #[error(transparent)]
+    Tenant(#[from] TenantValidationError),
+
+    #[error(transparent)]
+    ApiKey(#[from] ApiKeyError),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+}
+
+impl AuditServiceError {
+    /// ClickHouse failure
+    pub fn clickhouse(error: impl Into<anyhow::Error>) -> Self {
+        AuditServiceError::ClickHouse(error.into())
+    }
+
+    /// S3 failure
+    pub fn s3(error: impl Into<anyhow::Error>) -> Self {
+        AuditServiceError::S3(error.into())
+    }
+
+    /// `tonic::Status` returned to gRPC clients for this error
+    pub fn to_grpc_status(&self) -> Status {
+        let message = self.to_string();
+        match self {
+            AuditServiceError::Storage(_) | AuditServiceError::Crypto(_) => {
+                Status::internal(message)
+            }
+            AuditServiceError::ClickHouse(_) | AuditServiceError::S3(_) => {
+                Status::unavailable(message)
+            }
+            AuditServiceError::Compliance(error) => match error {
+                ComplianceError::PolicyNotFound(_) | ComplianceError::GDPRRequestNotFound(_) => {
+                    Status::not_found(message)
+                }
+                ComplianceError::LegalHoldPreventsDeletion(_) => {
+                    Status::failed_precondition(message)
+                }
+                ComplianceError::InvalidRetentionPeriod(_) => Status::invalid_argument(message),
+                ComplianceError::RateLimited { retry_after } => {
+                    let mut status = Status::resource_exhausted(message);
+                    status.metadata_mut().insert(
+                        "retry-after",
+                        MetadataValue::from(retry_after.as_secs().max(1)),
+                    );
+                    status
+                }
+                ComplianceError::Other(_) => Status::internal(message),
+            },
+            AuditServiceError::Quota(_) => Status::resource_exhausted(message),
+            AuditServiceError::QueryBudget(_) => Status::failed_precondition(message),
+            AuditServiceError::Schema(_) | AuditServiceError::InvalidArgument(_) => {
+                Status::invalid_argument(message)
+            }
+            AuditServiceError::Vector(error) => match error {
+                VectorError::InvalidArgument(_) => Status::invalid_argument(message),
+                VectorError::ConnectionFailed(_) | VectorError::Unavailable(_) => {
+                    Status::unavailable(message)
+                }
+                VectorError::Timeout(_) => Status::deadline_exceeded(message),
+                _ => Status::internal(message),
+            },
+            AuditServiceError::Tenant(error) => match error {
+                TenantValidationError::MissingTenantId
+                | TenantValidationError::InvalidContext(_) => Status::invalid_argument(message),
+                TenantValidationError::InvalidApiKey => Status::unauthenticated(message),
+                TenantValidationError::TenantNotFound => Status::not_found(message),
+                TenantValidationError::TenantDisabled => Status::permission_denied(message),
+                TenantValidationError::QuotaExceeded => Status::resource_exhausted(message),
+            },
+            AuditServiceError::ApiKey(error) => match error {
+                ApiKeyError::InvalidKey(_) | ApiKeyError::KeyNotFound(_) => {
+                    Status::unauthenticated(message)
+                }
+                ApiKeyError::KeyDisabled(_) | ApiKeyError::InsufficientScope(_) => {
+                    Status::permission_denied(message)
+                }
+                ApiKeyError::RateLimited(_) => Status::resource_exhausted(message),
+                ApiKeyError::HashingError(_) | ApiKeyError::Other(_) => Status::internal(message),
+            },
+        }
+    }
+}
+
+/// Storage backends return `anyhow::Error`; typed errors they carry keep
+/// their own variant, anything else is a storage error
+impl From<anyhow::Error> for AuditServiceError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<QueryBudgetExceeded>() {
+            Ok(exceeded) => return AuditServiceError::QueryBudget(exceeded),
+            Err(error) => error,
+        };
+        let error = match error.downcast::<QuotaExceeded>() {
+            Ok(exceeded) => return AuditServiceError::Quota(exceeded),
+            Err(error) => error,
+        };
+        match error.downcast::<ComplianceError>() {
+            Ok(compliance) => AuditServiceError::Compliance(compliance),
+            Err(error) => AuditServiceError::Storage(error),
+        }
+    }
+}
+
+macro_rules! crypto_error {
+    ($($error:ty),*) => {
+        $(
+            impl From<$error> for AuditServiceError {
+                fn from(error: $error) -> Self {
+                    AuditServiceError::Crypto(Box::new(error))
+                }
+            }
+        )*
+    };
+}
+
+crypto_error!(
+    SigningError,
+    HashingError,
+    DigestChainError,
+    EncryptionError,
+    KeyManagerError,
+    KeyStoreError
+);
+
+impl From<AuditServiceError> for Status {
+    fn from(error: AuditServiceError) -> Self {
+        error.to_grpc_status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quotas::QuotaType;
+    use crate::storage::QueryBudget;
+    use std::time::Duration;
+    use tonic::Code;
+
+    fn code(error: impl Into<AuditServiceError>) -> Code {
+        error.into().to_grpc_status().code()
+    }
+
+    #[test]
+    fn test_variants_map_to_grpc_codes() {
+        let quota = QuotaExceeded {
+            tenant_id: "tenant-1".to_string(),
+            quota_type: QuotaType::EventsPerSecond,
+            current_usage: 100,
+            max_value: 100,
+            requested: 1,
+        };
+        assert_eq!(code(quota), Code::ResourceExhausted);
+        assert_eq!(
+            code(ComplianceError::LegalHoldPreventsDeletion(
+                "hold-1".to_string()
+            )),
+            Code::FailedPrecondition
+        );
+        assert_eq!(
+            code(ComplianceError::PolicyNotFound("tenant-1".to_string())),
+            Code::NotFound
+        );
+        assert_eq!(
+            code(ComplianceError::InvalidRetentionPeriod("-1".to_string())),
+            Code::InvalidArgument
+        );
+        assert_eq!(
+            code(AuditServiceError::clickhouse(anyhow::anyhow!("down"))),
+            Code::Unavailable
+        );
+        assert_eq!(
+            code(AuditServiceError::s3(anyhow::anyhow!("down"))),
+            Code::Unavailable
+        );
+        assert_eq!(
+            code(SigningError::Signature("bad".to_string())),
+            Code::Internal
+        );
+        assert_eq!(
+            code(EncryptionError::KeyNotFound("tenant-1".to_string())),
+            Code::Internal
+        );
+        assert_eq!(
+            code(VectorError::Timeout("slow".to_string())),
+            Code::DeadlineExceeded
+        );
+        assert_eq!(
+            code(TenantValidationError::TenantDisabled),
+            Code::PermissionDenied
+        );
+        assert_eq!(
+            code(ApiKeyError::InvalidKey("k".to_string())),
+            Code::Unauthenticated
+        );
+        assert_eq!(
+            code(AuditServiceError::InvalidArgument("x".to_string())),
+            Code::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_compliance_rate_limit_carries_retry_after() {
+        let status = AuditServiceError::from(ComplianceError::RateLimited {
+            retry_after: Duration::from_secs(30),
+        })
+        .to_grpc_status();
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        assert_eq!(status.metadata().get("retry-after").unwrap(), "30");
+    }
+
+    #[test]
+    fn test_anyhow_errors_keep_their_typed_variant() {
+        let exceeded = QueryBudget::unlimited()
+            .with_max_latency_ms(10)
+            .check(&crate::storage::QueryPlan {
+                target_tiers: Vec::new(),
+                estimated_latency_ms: 30_000,
+                estimated_cost_usd: 0.01,
+                parallel_execution: false,
+            })
+            .unwrap_err();
+        assert!(matches!(
+            AuditServiceError::from(anyhow::Error::from(exceeded)),
+            AuditServiceError::QueryBudget(_)
+        ));
+        assert_eq!(code(anyhow::anyhow!("disk full")), Code::Internal);
+    }
+}
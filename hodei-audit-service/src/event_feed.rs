@@ -0,0 +1,122 @@
+//! Live Event Feed
+//!
+//! Fan-out of freshly ingested audit events to live subscribers (e.g. the
+//! `StreamEvents` tail RPC). The ingestion pipeline publishes into a
+//! broadcast channel and the feed keeps a bounded window of recent events so
+//! new subscribers can replay them before following.
+
+use hodei_audit_proto::AuditEvent;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Configuration for the event feed
+#[derive(Debug, Clone)]
+pub struct EventFeedConfig {
+    /// Capacity of the broadcast channel; slower subscribers lag beyond it
+    pub channel_capacity: usize,
+    /// Number of recent events kept for replay
+    pub recent_capacity: usize,
+}
+
+impl Default for EventFeedConfig {
+    fn default() -> Self {
+        Self {
+            channel_capacity: 4096,
+            recent_capacity: 1000,
+        }
+    }
+}
+
+/// Broadcast feed of ingested audit events
+#[derive(Debug)]
+pub struct EventFeed {
+    sender: broadcast::Sender<AuditEvent>,
+    recent: Mutex<VecDeque<AuditEvent>>,
+    recent_capacity: usize,
+}
+
+impl EventFeed {
+    /// Create a new feed
+    pub fn new(config: EventFeedConfig) -> Self {
+        let (sender, _) = broadcast::channel(config.channel_capacity.max(1));
+        Self {
+            sender,
+            recent: Mutex::new(VecDeque::with_capacity(config.recent_capacity)),
+            recent_capacity: config.recent_capacity,
+        }
+    }
+
+    /// Publish an ingested event to all subscribers
+    pub fn publish(&self, event: AuditEvent) {
+        let mut recent = self.recent.lock().unwrap();
+        if self.recent_capacity > 0 {
+            if recent.len() == self.recent_capacity {
+                recent.pop_front();
+            }
+            recent.push_back(event.clone());
+        }
+        // No subscribers is not an error
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribe to the feed
+    ///
+    /// Returns a snapshot of the recent events (oldest first) together with
+    /// a receiver for everything published afterwards; no event is lost or
+    /// duplicated between the two.
+    pub fn subscribe(&self) -> (Vec<AuditEvent>, broadcast::Receiver<AuditEvent>) {
+        let recent = self.recent.lock().unwrap();
+        let receiver = self.sender.subscribe();
+        (recent.iter().cloned().collect(), receiver)
+    }
+
+    /// Number of active subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for EventFeed {
+    fn default() -> Self {
+        Self::new(EventFeedConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hodei_audit_proto::EventId;
+
+    fn event(id: &str) -> AuditEvent {
+        AuditEvent {
+            event_id: Some(EventId {
+                value: id.to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_replays_recent_then_follows() {
+        let feed = EventFeed::new(EventFeedConfig {
+            channel_capacity: 16,
+            recent_capacity: 2,
+        });
+
+        feed.publish(event("e1"));
+        feed.publish(event("e2"));
+        feed.publish(event("e3"));
+
+        let (recent, mut receiver) = feed.subscribe();
+        let ids: Vec<_> = recent
+            .iter()
+            .map(|e| e.event_id.as_ref().unwrap().value.clone())
+            .collect();
+        assert_eq!(ids, vec!["e2", "e3"]);
+
+        feed.publish(event("e4"));
+        let next = receiver.recv().await.unwrap();
+        assert_eq!(next.event_id.unwrap().value, "e4");
+    }
+}
@@ -0,0 +1,342 @@
+//! SIEM Exporters
+//!
+//! Formats audit events as single-line records for SIEM platforms:
+//! - CEF (ArcSight Common Event Format), read by Splunk and ArcSight
+//! - LEEF 1.0 (Log Event Extended Format), read by QRadar
+//!
+//! Both formats are UTF-8, so non-ASCII text is kept as is. Characters that
+//! act as delimiters in each format are backslash-escaped, line breaks are
+//! written as `\n`/`\r`, and any other control character is replaced by a
+//! space so a record always stays on one line.
+
+use chrono::{DateTime, Utc};
+use hodei_audit_proto::{AuditEvent, Hrn, MetadataExt};
+use hodei_audit_types::{EventCategory, Outcome};
+
+/// Vendor reported in the record header
+pub const DEVICE_VENDOR: &str = "Hodei";
+/// Product reported in the record header
+pub const DEVICE_PRODUCT: &str = "Audit Trail";
+/// Metadata key holding the client IP, preferred over `http_context.source_ip`
+pub const CLIENT_IP_METADATA_KEY: &str = "client_ip";
+
+/// Format an event as a CEF record
+///
+/// `CEF:0|Vendor|Product|Version|SignatureID|Name|Severity|Extension`
+pub fn to_cef(event: &AuditEvent) -> String {
+    let action = escape_header(&event.action);
+    let mut record = format!(
+        "CEF:0|{}|{}|{}|{}|{}|{}|",
+        escape_header(DEVICE_VENDOR),
+        escape_header(DEVICE_PRODUCT),
+        escape_header(env!("CARGO_PKG_VERSION")),
+        action,
+        action,
+        severity(event),
+    );
+
+    let fields = ExportFields::from_event(event);
+    let mut pairs = vec![
+        (
+            "rt",
+            fields.event_time.map(|t| t.timestamp_millis().to_string()),
+        ),
+        ("externalId", fields.event_id),
+        ("cat", Some(fields.category.as_str().to_string())),
+        ("act", non_empty(&event.action)),
+        ("outcome", Some(fields.outcome.as_str().to_string())),
+        ("suid", fields.user_id),
+        ("suser", fields.username),
+        ("src", fields.client_ip),
+        ("requestMethod", fields.http_method),
+        ("request", fields.http_path),
+        ("requestClientApplication", fields.user_agent),
+        ("reason", non_empty(&event.error_code)),
+        ("msg", non_empty(&event.error_message)),
+    ];
+
+    // Custom fields carry their meaning in a companion label key
+    let custom = [
+        ("cs1Label", "tenantId", "cs1", fields.tenant_id),
+        ("cs2Label", "hrn", "cs2", fields.hrn),
+        (
+            "cs3Label",
+            "correlationId",
+            "cs3",
+            non_empty(&event.correlation_id),
+        ),
+        (
+            "deviceCustomDate1Label",
+            "processedAt",
+            "deviceCustomDate1",
+            fields
+                .processed_at
+                .map(|t| t.timestamp_millis().to_string()),
+        ),
+    ];
+    for (label_key, label, key, value) in custom {
+        if value.is_some() {
+            pairs.push((label_key, Some(label.to_string())));
+            pairs.push((key, value));
+        }
+    }
+
+    let extension: Vec<String> = pairs
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|v| format!("{}={}", key, escape_cef_value(&v))))
+        .collect();
+
+    record.push_str(&extension.join(" "));
+    record
+}
+
+/// Format an event as a LEEF 1.0 record (tab-delimited attributes)
+///
+/// `LEEF:1.0|Vendor|Product|Version|EventID|key=value<TAB>key=value`
+pub fn to_leef(event: &AuditEvent) -> String {
+    let mut record = format!(
+        "LEEF:1.0|{}|{}|{}|{}|",
+        escape_header(DEVICE_VENDOR),
+        escape_header(DEVICE_PRODUCT),
+        escape_header(env!("CARGO_PKG_VERSION")),
+        escape_header(&event.action),
+    );
+
+    let fields = ExportFields::from_event(event);
+    let attributes: Vec<String> = [
+        (
+            "devTime",
+            fields
+                .event_time
+                .map(|t| t.format("%b %d %Y %H:%M:%S%.3f UTC").to_string()),
+        ),
+        (
+            "devTimeFormat",
+            fields
+                .event_time
+                .map(|_| "MMM dd yyyy HH:mm:ss.SSS z".to_string()),
+        ),
+        ("cat", Some(fields.category.as_str().to_string())),
+        ("sev", Some(severity(event).max(1).to_string())),
+        ("eventId", fields.event_id),
+        ("action", non_empty(&event.action)),
+        ("outcome", Some(fields.outcome.as_str().to_string())),
+        ("usrName", fields.username),
+        ("userId", fields.user_id),
+        ("src", fields.client_ip),
+        ("httpMethod", fields.http_method),
+        ("url", fields.http_path),
+        ("userAgent", fields.user_agent),
+        ("errorCode", non_empty(&event.error_code)),
+        ("errorMessage", non_empty(&event.error_message)),
+        ("tenantId", fields.tenant_id),
+        ("resource", fields.hrn),
+        ("correlationId", non_empty(&event.correlation_id)),
+        (
+            "processedAt",
+            fields
+                .processed_at
+                .map(|t| t.timestamp_millis().to_string()),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| value.map(|v| format!("{}={}", key, escape_leef_value(&v))))
+    .collect();
+
+    record.push_str(&attributes.join("\t"));
+    record
+}
+
+/// Severity on the shared 0-10 scale, derived from the outcome
+fn severity(event: &AuditEvent) -> u8 {
+    match Outcome::from(event.outcome) {
+        Outcome::Success => 3,
+        Outcome::Failure => 6,
+        Outcome::Denied => 7,
+        Outcome::Error => 8,
+        Outcome::Unspecified | Outcome::Unknown => 5,
+    }
+}
+
+/// Event fields shared by both formats, unescaped
+struct ExportFields {
+    event_id: Option<String>,
+    tenant_id: Option<String>,
+    hrn: Option<String>,
+    user_id: Option<String>,
+    username: Option<String>,
+    client_ip: Option<String>,
+    http_method: Option<String>,
+    http_path: Option<String>,
+    user_agent: Option<String>,
+    category: EventCategory,
+    outcome: Outcome,
+    event_time: Option<DateTime<Utc>>,
+    processed_at: Option<DateTime<Utc>>,
+}
+
+impl ExportFields {
+    fn from_event(event: &AuditEvent) -> Self {
+        let timestamp = |ts: &Option<prost_types::Timestamp>| {
+            ts.as_ref()
+                .and_then(|t| DateTime::from_timestamp(t.seconds, t.nanos.max(0) as u32))
+        };
+        let http = event.http_context.as_ref();
+
+        Self {
+            event_id: event.event_id.as_ref().and_then(|id| non_empty(&id.value)),
+            tenant_id: event.tenant_id.as_ref().and_then(|t| non_empty(&t.value)),
+            hrn: event.hrn.as_ref().map(hrn_string),
+            user_id: event
+                .user_identity
+                .as_ref()
+                .and_then(|u| non_empty(&u.user_id)),
+            username: event
+                .user_identity
+                .as_ref()
+                .and_then(|u| non_empty(&u.username)),
+            client_ip: event
+                .get_str(CLIENT_IP_METADATA_KEY)
+                .or_else(|| http.and_then(|h| non_empty(&h.source_ip))),
+            http_method: http.and_then(|h| non_empty(&h.method)),
+            http_path: http.and_then(|h| non_empty(&h.path)),
+            user_agent: http.and_then(|h| non_empty(&h.user_agent)),
+            category: EventCategory::from(event.event_category),
+            outcome: Outcome::from(event.outcome),
+            event_time: timestamp(&event.event_time),
+            processed_at: timestamp(&event.processed_at),
+        }
+    }
+}
+
+/// `hrn:partition:service:tenant:region:type/path`
+pub(crate) fn hrn_string(hrn: &Hrn) -> String {
+    format!(
+        "hrn:{}:{}:{}:{}:{}/{}",
+        hrn.partition, hrn.service, hrn.tenant_id, hrn.region, hrn.resource_type, hrn.resource_path
+    )
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Header fields (both formats): escape `\` and `|`; line breaks are not allowed
+fn escape_header(value: &str) -> String {
+    escape(value, &['\\', '|'], false)
+}
+
+/// CEF extension values: escape `\` and `=`, encode line breaks
+fn escape_cef_value(value: &str) -> String {
+    escape(value, &['\\', '='], true)
+}
+
+/// LEEF attribute values: escape `\` and the tab delimiter, encode line breaks
+fn escape_leef_value(value: &str) -> String {
+    let escaped = escape(value, &['\\'], true);
+    escaped.replace('\t', "\\t")
+}
+
+fn escape(value: &str, specials: &[char], encode_line_breaks: bool) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            c if specials.contains(&c) => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' if encode_line_breaks => out.push_str("\\n"),
+            '\r' if encode_line_breaks => out.push_str("\\r"),
+            '\t' if !encode_line_breaks => out.push(' '),
+            '\t' => out.push('\t'),
+            c if c.is_control() => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hodei_audit_proto::{EventId, HttpContext, TenantId, UserIdentity};
+    use serde_json::json;
+
+    fn known_event() -> AuditEvent {
+        let mut event = AuditEvent {
+            event_id: Some(EventId {
+                value: "evt-1".to_string(),
+            }),
+            tenant_id: Some(TenantId {
+                value: "tenant-a".to_string(),
+            }),
+            hrn: Some(Hrn {
+                partition: "hodei".to_string(),
+                service: "api".to_string(),
+                tenant_id: "tenant-a".to_string(),
+                region: "eu-west-1".to_string(),
+                resource_type: "api".to_string(),
+                resource_path: "gateway".to_string(),
+            }),
+            user_identity: Some(UserIdentity {
+                user_id: "u-42".to_string(),
+                username: "José Núñez".to_string(),
+                ..Default::default()
+            }),
+            http_context: Some(HttpContext {
+                method: "POST".to_string(),
+                path: "/login?next=a|b".to_string(),
+                source_ip: "192.168.1.1".to_string(),
+                ..Default::default()
+            }),
+            action: "user.login|v2".to_string(),
+            event_category: 1,
+            outcome: 2,
+            error_message: "bad password\nretry=3 C:\\path".to_string(),
+            event_time: Some(prost_types::Timestamp {
+                seconds: 1_700_000_000,
+                nanos: 123_000_000,
+            }),
+            ..Default::default()
+        };
+        event.set_json(CLIENT_IP_METADATA_KEY, json!("10.0.0.7"));
+        event
+    }
+
+    #[test]
+    fn test_cef_record_is_spec_conformant() {
+        let record = to_cef(&known_event());
+        let expected = format!(
+            "CEF:0|Hodei|Audit Trail|{}|user.login\\|v2|user.login\\|v2|6|\
+             rt=1700000000123 externalId=evt-1 cat=management act=user.login|v2 \
+             outcome=failure suid=u-42 suser=José Núñez src=10.0.0.7 requestMethod=POST \
+             request=/login?next\\=a|b msg=bad password\\nretry\\=3 C:\\\\path \
+             cs1Label=tenantId cs1=tenant-a cs2Label=hrn \
+             cs2=hrn:hodei:api:tenant-a:eu-west-1:api/gateway",
+            env!("CARGO_PKG_VERSION")
+        );
+        assert_eq!(record, expected);
+        assert!(!record.contains('\n'));
+    }
+
+    #[test]
+    fn test_leef_record_uses_tab_delimiter() {
+        let record = to_leef(&known_event());
+        let (header, attributes) = record.rsplit_once("user.login\\|v2|").unwrap();
+        assert!(header.starts_with("LEEF:1.0|Hodei|Audit Trail|"));
+
+        let attributes: Vec<&str> = attributes.split('\t').collect();
+        assert_eq!(attributes[0], "devTime=Nov 14 2023 22:13:20.123 UTC");
+        assert!(attributes.contains(&"sev=6"));
+        assert!(attributes.contains(&"usrName=José Núñez"));
+        assert!(attributes.contains(&"errorMessage=bad password\\nretry=3 C:\\\\path"));
+        assert!(attributes.contains(&"resource=hrn:hodei:api:tenant-a:eu-west-1:api/gateway"));
+    }
+
+    #[test]
+    fn test_escaping_rules() {
+        assert_eq!(escape_header("a|b\\c\nd"), "a\\|b\\\\c d");
+        assert_eq!(escape_cef_value("k=v\r\n\t"), "k\\=v\\r\\n\t");
+        assert_eq!(escape_leef_value("a\tb=c\u{7}"), "a\\tb=c ");
+    }
+}
@@ -0,0 +1,702 @@
+//! Field-Level Encryption of PII
+//!
+//! Encrypts selected PII fields of an event (email, client IP, user id, ...)
+//! with an AES-256-GCM key of its tenant before the event reaches storage,
+//! so they are encrypted at rest even in the hot tier. All other fields stay
+//! in plaintext and remain queryable. Readers granted the `pii:read` scope
+//! get the values decrypted; everyone else sees them redacted.
+//!
+//! Encryption is randomized, so an encrypted field can't be used as an
+//! equality filter (e.g. `QueryFilter::user_id`) anymore.
+//!
+//! With key custody configured, tenant data keys are wrapped by the
+//! tenant's CMK in the KMS and only the wrapped form is persisted, so the
+//! keys (and the PII they protect) survive restarts.
+
+use crate::api_key::ApiScope;
+use crate::encryption::{EncryptionError, KmsClient, WrappedKey, generate_key, open, seal};
+use crate::storage::{EventStream, QueryFilter, StorageBackend, StorageStats};
+use hodei_audit_proto::AuditEvent;
+use prost_types::value::Kind;
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use tracing::info;
+
+/// Prefix of an encrypted field value
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// Value shown to readers not allowed to see PII
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Fields encrypted by [`FieldEncryptor::with_default_fields`]
+pub const DEFAULT_PII_FIELDS: &[&str] = &[
+    "user_identity.user_id",
+    "user_identity.email",
+    "http_context.source_ip",
+    "metadata.client_ip",
+];
+
+/// Path of an encryptable string field of [`AuditEvent`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FieldPath {
+    UserId,
+    Username,
+    Email,
+    SourceIp,
+    UserAgent,
+    /// String value under a key of the event metadata
+    Metadata(String),
+}
+
+impl FieldPath {
+    /// Parse a dotted path such as `user_identity.email` or `metadata.client_ip`
+    pub fn parse(path: &str) -> Result<Self, EncryptionError> {
+        match path {
+            "user_identity.user_id" => Ok(Self::UserId),
+            "user_identity.username" => Ok(Self::Username),
+            "user_identity.email" => Ok(Self::Email),
+            "http_context.source_ip" => Ok(Self::SourceIp),
+            "http_context.user_agent" => Ok(Self::UserAgent),
+            _ => match path.strip_prefix("metadata.") {
+                Some(key) if !key.is_empty() => Ok(Self::Metadata(key.to_string())),
+                _ => Err(EncryptionError::UnknownField(path.to_string())),
+            },
+        }
+    }
+
+    /// Dotted path of the field
+    pub fn as_path(&self) -> String {
+        match self {
+            Self::UserId => "user_identity.user_id".to_string(),
+            Self::Username => "user_identity.username".to_string(),
+            Self::Email => "user_identity.email".to_string(),
+            Self::SourceIp => "http_context.source_ip".to_string(),
+            Self::UserAgent => "http_context.user_agent".to_string(),
+            Self::Metadata(key) => format!("metadata.{}", key),
+        }
+    }
+
+    /// The field value, if the event has it
+    pub(crate) fn value_mut<'a>(&self, event: &'a mut AuditEvent) -> Option<&'a mut String> {
+        match self {
+            Self::UserId => event.user_identity.as_mut().map(|u| &mut u.user_id),
+            Self::Username => event.user_identity.as_mut().map(|u| &mut u.username),
+            Self::Email => event.user_identity.as_mut().map(|u| &mut u.email),
+            Self::SourceIp => event.http_context.as_mut().map(|h| &mut h.source_ip),
+            Self::UserAgent => event.http_context.as_mut().map(|h| &mut h.user_agent),
+            Self::Metadata(key) => match event
+                .metadata
+                .as_mut()?
+                .fields
+                .get_mut(key)?
+                .kind
+                .as_mut()?
+            {
+                Kind::StringValue(value) => Some(value),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Port to the persistent store of wrapped tenant data keys
+#[async_trait::async_trait]
+pub trait WrappedKeyStore: Send + Sync + 'static {
+    /// Wrapped data key of a tenant, if one was saved
+    async fn load(&self, tenant_id: &str) -> Result<Option<WrappedKey>, EncryptionError>;
+
+    /// Save the wrapped data key of a tenant
+    async fn save(&self, tenant_id: &str, key: &WrappedKey) -> Result<(), EncryptionError>;
+
+    /// Tenants with a saved key
+    async fn tenants(&self) -> Result<Vec<String>, EncryptionError>;
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoredWrappedKey {
+    tenant_id: String,
+    key_id: String,
+    ciphertext: String,
+}
+
+/// Wrapped data keys as one JSON file per tenant under a directory
+#[derive(Debug)]
+pub struct FileWrappedKeyStore {
+    dir: PathBuf,
+}
+
+impl FileWrappedKeyStore {
+    /// Keep wrapped keys under `dir`, creating it if needed
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, EncryptionError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| EncryptionError::Kms(e.to_string()))?;
+        Ok(Self { dir })
+    }
+
+    fn key_path(&self, tenant_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", hex::encode(tenant_id)))
+    }
+}
+
+#[async_trait::async_trait]
+impl WrappedKeyStore for FileWrappedKeyStore {
+    async fn load(&self, tenant_id: &str) -> Result<Option<WrappedKey>, EncryptionError> {
+        let body = match tokio::fs::read(self.key_path(tenant_id)).await {
+            Ok(body) => body,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(EncryptionError::Kms(e.to_string())),
+        };
+        let stored: StoredWrappedKey =
+            serde_json::from_slice(&body).map_err(|e| EncryptionError::Metadata(e.to_string()))?;
+        Ok(Some(WrappedKey {
+            key_id: stored.key_id,
+            ciphertext: hex::decode(&stored.ciphertext)
+                .map_err(|e| EncryptionError::Metadata(e.to_string()))?,
+        }))
+    }
+
+    async fn save(&self, tenant_id: &str, key: &WrappedKey) -> Result<(), EncryptionError> {
+        let stored = StoredWrappedKey {
+            tenant_id: tenant_id.to_string(),
+            key_id: key.key_id.clone(),
+            ciphertext: hex::encode(&key.ciphertext),
+        };
+        let body =
+            serde_json::to_vec(&stored).map_err(|e| EncryptionError::Metadata(e.to_string()))?;
+        let path = self.key_path(tenant_id);
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, body)
+            .await
+            .map_err(|e| EncryptionError::Kms(e.to_string()))?;
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .map_err(|e| EncryptionError::Kms(e.to_string()))
+    }
+
+    async fn tenants(&self) -> Result<Vec<String>, EncryptionError> {
+        let mut entries = tokio::fs::read_dir(&self.dir)
+            .await
+            .map_err(|e| EncryptionError::Kms(e.to_string()))?;
+        let mut tenants = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| EncryptionError::Kms(e.to_string()))?
+        {
+            let name = entry.file_name();
+            let Some(encoded) = name.to_str().and_then(|n| n.strip_suffix(".json")) else {
+                continue;
+            };
+            if let Some(tenant) = hex::decode(encoded)
+                .ok()
+                .and_then(|t| String::from_utf8(t).ok())
+            {
+                tenants.push(tenant);
+            }
+        }
+        Ok(tenants)
+    }
+}
+
+/// KMS that wraps tenant data keys and the store keeping them wrapped
+struct KeyCustody {
+    kms: Arc<dyn KmsClient>,
+    store: Arc<dyn WrappedKeyStore>,
+    /// Serializes key creation so a tenant never gets two data keys
+    create_lock: tokio::sync::Mutex<()>,
+}
+
+/// Encrypts configured PII fields with per-tenant keys
+///
+/// Without key custody, a tenant key is generated in memory on the first
+/// event of the tenant unless one was loaded with
+/// [`FieldEncryptor::set_tenant_key`]; such keys are lost on restart, so
+/// this is for development only. With [`FieldEncryptor::with_key_custody`],
+/// keys are created and loaded through [`FieldEncryptor::ensure_tenant_key`].
+pub struct FieldEncryptor {
+    fields: Vec<FieldPath>,
+    tenant_keys: RwLock<HashMap<String, Vec<u8>>>,
+    custody: Option<KeyCustody>,
+}
+
+impl std::fmt::Debug for FieldEncryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FieldEncryptor")
+            .field("fields", &self.fields)
+            .field("custody", &self.custody.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl FieldEncryptor {
+    /// Encryptor for the given field paths
+    pub fn new<I, S>(paths: I) -> Result<Self, EncryptionError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let fields = paths
+            .into_iter()
+            .map(|path| FieldPath::parse(path.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            fields,
+            tenant_keys: RwLock::new(HashMap::new()),
+            custody: None,
+        })
+    }
+
+    /// Wrap tenant data keys with `kms` and persist them, wrapped, in `store`
+    pub fn with_key_custody(
+        mut self,
+        kms: Arc<dyn KmsClient>,
+        store: Arc<dyn WrappedKeyStore>,
+    ) -> Self {
+        self.custody = Some(KeyCustody {
+            kms,
+            store,
+            create_lock: tokio::sync::Mutex::new(()),
+        });
+        self
+    }
+
+    /// Unwrap every persisted tenant key, returning how many were loaded
+    ///
+    /// Called on startup so stored PII can be decrypted right away.
+    pub async fn load_tenant_keys(&self) -> Result<usize, EncryptionError> {
+        let Some(custody) = &self.custody else {
+            return Ok(0);
+        };
+        let tenants = custody.store.tenants().await?;
+        for tenant_id in &tenants {
+            self.load_tenant_key(tenant_id).await?;
+        }
+        info!("[FieldEncryption] Loaded {} tenant keys", tenants.len());
+        Ok(tenants.len())
+    }
+
+    /// Make sure the key of a tenant is loaded, creating, wrapping and
+    /// persisting it on the tenant's first event
+    pub async fn ensure_tenant_key(&self, tenant_id: &str) -> Result<(), EncryptionError> {
+        if self.has_tenant_key(tenant_id) || self.load_tenant_key(tenant_id).await? {
+            return Ok(());
+        }
+        let Some(custody) = &self.custody else {
+            // Development: the key is generated by `encrypt_event`
+            return Ok(());
+        };
+
+        let _guard = custody.create_lock.lock().await;
+        if self.load_tenant_key(tenant_id).await? {
+            return Ok(());
+        }
+        let key = generate_key();
+        let wrapped = custody.kms.wrap_key(tenant_id, &key).await?;
+        custody.store.save(tenant_id, &wrapped).await?;
+        self.tenant_keys
+            .write()
+            .unwrap()
+            .insert(tenant_id.to_string(), key);
+        info!(
+            "[FieldEncryption] Created data key for tenant {}",
+            tenant_id
+        );
+        Ok(())
+    }
+
+    /// Load a persisted tenant key into memory; `false` if there is none
+    async fn load_tenant_key(&self, tenant_id: &str) -> Result<bool, EncryptionError> {
+        if self.has_tenant_key(tenant_id) {
+            return Ok(true);
+        }
+        let Some(custody) = &self.custody else {
+            return Ok(false);
+        };
+        let Some(wrapped) = custody.store.load(tenant_id).await? else {
+            return Ok(false);
+        };
+        let key = custody.kms.unwrap_key(tenant_id, &wrapped).await?;
+        self.tenant_keys
+            .write()
+            .unwrap()
+            .insert(tenant_id.to_string(), key);
+        Ok(true)
+    }
+
+    fn has_tenant_key(&self, tenant_id: &str) -> bool {
+        self.tenant_keys.read().unwrap().contains_key(tenant_id)
+    }
+
+    /// Encryptor for [`DEFAULT_PII_FIELDS`]
+    pub fn with_default_fields() -> Self {
+        Self::new(DEFAULT_PII_FIELDS).expect("default PII fields are valid")
+    }
+
+    /// Fields this encryptor protects
+    pub fn fields(&self) -> &[FieldPath] {
+        &self.fields
+    }
+
+    /// Load the AES-256 key of a tenant
+    pub fn set_tenant_key(&self, tenant_id: &str, key: Vec<u8>) -> Result<(), EncryptionError> {
+        if key.len() != 32 {
+            return Err(EncryptionError::Metadata(format!(
+                "tenant key must be 32 bytes, got {}",
+                key.len()
+            )));
+        }
+        self.tenant_keys
+            .write()
+            .unwrap()
+            .insert(tenant_id.to_string(), key);
+        Ok(())
+    }
+
+    /// Whether a reader with `scopes` may see PII in clear
+    pub fn is_authorized(scopes: &[ApiScope]) -> bool {
+        scopes.contains(&ApiScope::PiiRead)
+    }
+
+    /// Copy of `event` with its PII fields encrypted
+    ///
+    /// Empty and already encrypted values are left untouched. With key
+    /// custody the tenant key must have been loaded with
+    /// [`FieldEncryptor::ensure_tenant_key`].
+    pub fn encrypt_event(&self, event: &AuditEvent) -> Result<AuditEvent, EncryptionError> {
+        let tenant_id = event_tenant(event)?;
+        let key = match &self.custody {
+            Some(_) => self
+                .tenant_keys
+                .read()
+                .unwrap()
+                .get(&tenant_id)
+                .cloned()
+                .ok_or_else(|| EncryptionError::KeyNotFound(tenant_id.clone()))?,
+            None => self
+                .tenant_keys
+                .write()
+                .unwrap()
+                .entry(tenant_id.clone())
+                .or_insert_with(generate_key)
+                .clone(),
+        };
+
+        let mut encrypted = event.clone();
+        for field in &self.fields {
+            if let Some(value) = field.value_mut(&mut encrypted) {
+                if value.is_empty() || value.starts_with(ENCRYPTED_PREFIX) {
+                    continue;
+                }
+                let sealed = seal(&key, value.as_bytes(), &aad(&tenant_id, field))?;
+                *value = format!("{}{}", ENCRYPTED_PREFIX, hex::encode(sealed));
+            }
+        }
+        Ok(encrypted)
+    }
+
+    /// Copy of `event` with its encrypted fields decrypted
+    pub fn decrypt_event(&self, event: &AuditEvent) -> Result<AuditEvent, EncryptionError> {
+        let tenant_id = event_tenant(event)?;
+        let key = self.tenant_keys.read().unwrap().get(&tenant_id).cloned();
+
+        let mut decrypted = event.clone();
+        for field in &self.fields {
+            let Some(value) = field.value_mut(&mut decrypted) else {
+                continue;
+            };
+            let Some(encoded) = value.strip_prefix(ENCRYPTED_PREFIX) else {
+                continue;
+            };
+            let key = key
+                .as_ref()
+                .ok_or_else(|| EncryptionError::KeyNotFound(tenant_id.clone()))?;
+            let sealed =
+                hex::decode(encoded).map_err(|_| EncryptionError::Decryption(field.as_path()))?;
+            let plaintext = open(key, &sealed, &aad(&tenant_id, field))?;
+            *value = String::from_utf8(plaintext)
+                .map_err(|_| EncryptionError::Decryption(field.as_path()))?;
+        }
+        Ok(decrypted)
+    }
+
+    /// Copy of `event` with every non-empty PII field replaced by [`REDACTED`]
+    pub fn redact_event(&self, event: &AuditEvent) -> AuditEvent {
+        let mut redacted = event.clone();
+        for field in &self.fields {
+            match field.value_mut(&mut redacted) {
+                Some(value) if !value.is_empty() => *value = REDACTED.to_string(),
+                _ => {}
+            }
+        }
+        redacted
+    }
+
+    /// `event` as a reader with `scopes` may see it
+    pub fn reveal(
+        &self,
+        event: &AuditEvent,
+        scopes: &[ApiScope],
+    ) -> Result<AuditEvent, EncryptionError> {
+        if Self::is_authorized(scopes) {
+            self.decrypt_event(event)
+        } else {
+            Ok(self.redact_event(event))
+        }
+    }
+}
+
+fn event_tenant(event: &AuditEvent) -> Result<String, EncryptionError> {
+    event
+        .tenant_id
+        .as_ref()
+        .map(|t| t.value.clone())
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| EncryptionError::KeyNotFound("event has no tenant".to_string()))
+}
+
+/// Binds a ciphertext to its tenant and field
+fn aad(tenant_id: &str, field: &FieldPath) -> Vec<u8> {
+    format!("{}:{}", tenant_id, field.as_path()).into_bytes()
+}
+
+/// Storage backend that encrypts PII fields before delegating writes
+///
+/// Queries through the [`StorageBackend`] trait return events as stored,
+/// with PII encrypted; use [`EncryptedFieldStorage::query_events_as`] to
+/// get them as a given reader may see them.
+pub struct EncryptedFieldStorage {
+    inner: Arc<dyn StorageBackend>,
+    encryptor: Arc<FieldEncryptor>,
+}
+
+impl EncryptedFieldStorage {
+    pub fn new(inner: Arc<dyn StorageBackend>, encryptor: Arc<FieldEncryptor>) -> Self {
+        Self { inner, encryptor }
+    }
+
+    /// Query events, decrypted or redacted according to the reader's scopes
+    pub async fn query_events_as(
+        &self,
+        filter: &QueryFilter,
+        scopes: &[ApiScope],
+    ) -> Result<Vec<AuditEvent>, anyhow::Error> {
+        let events = self.inner.query_events(filter).await?;
+        if FieldEncryptor::is_authorized(scopes) {
+            for tenant_id in tenants_of(&events) {
+                self.encryptor.load_tenant_key(&tenant_id).await?;
+            }
+        }
+        events
+            .iter()
+            .map(|event| Ok(self.encryptor.reveal(event, scopes)?))
+            .collect()
+    }
+
+    /// Load or create the keys of every tenant in `events`
+    async fn ensure_keys(&self, events: &[AuditEvent]) -> Result<(), EncryptionError> {
+        for tenant_id in tenants_of(events) {
+            self.encryptor.ensure_tenant_key(&tenant_id).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Distinct non-empty tenants of `events`
+fn tenants_of(events: &[AuditEvent]) -> BTreeSet<String> {
+    events
+        .iter()
+        .filter_map(|event| event.tenant_id.as_ref())
+        .map(|tenant| tenant.value.clone())
+        .filter(|tenant| !tenant.is_empty())
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for EncryptedFieldStorage {
+    async fn store_event(&self, event: &AuditEvent) -> Result<(), anyhow::Error> {
+        self.ensure_keys(std::slice::from_ref(event)).await?;
+        let event = self.encryptor.encrypt_event(event)?;
+        self.inner.store_event(&event).await
+    }
+
+    async fn store_batch(&self, events: &[AuditEvent]) -> Result<(), anyhow::Error> {
+        self.ensure_keys(events).await?;
+        let events = events
+            .iter()
+            .map(|event| self.encryptor.encrypt_event(event))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.inner.store_batch(&events).await
+    }
+
+    async fn query_events(&self, filter: &QueryFilter) -> Result<Vec<AuditEvent>, anyhow::Error> {
+        self.inner.query_events(filter).await
+    }
+
+    fn query_events_stream<'a>(&'a self, filter: &'a QueryFilter) -> EventStream<'a> {
+        self.inner.query_events_stream(filter)
+    }
+
+    async fn count_events(&self, filter: &QueryFilter) -> Result<u64, anyhow::Error> {
+        self.inner.count_events(filter).await
+    }
+
+    async fn health_check(&self) -> Result<bool, anyhow::Error> {
+        self.inner.health_check().await
+    }
+
+    fn get_stats(&self) -> StorageStats {
+        self.inner.get_stats()
+    }
+
+    async fn delete_events(&self, filter: &QueryFilter) -> Result<u64, anyhow::Error> {
+        self.inner.delete_events(filter).await
+    }
+
+    fn might_contain_event(&self, event_id: &str) -> bool {
+        self.inner.might_contain_event(event_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ClickHouseStorage;
+    use hodei_audit_proto::{HttpContext, TenantId, UserIdentity};
+
+    fn event() -> AuditEvent {
+        AuditEvent {
+            tenant_id: Some(TenantId {
+                value: "tenant-a".to_string(),
+            }),
+            user_identity: Some(UserIdentity {
+                user_id: "user-42".to_string(),
+                username: "alice".to_string(),
+                email: "alice@example.com".to_string(),
+                ..Default::default()
+            }),
+            http_context: Some(HttpContext {
+                source_ip: "10.0.0.7".to_string(),
+                ..Default::default()
+            }),
+            action: "DeletePolicy".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn storage() -> (Arc<ClickHouseStorage>, EncryptedFieldStorage) {
+        let inner = Arc::new(ClickHouseStorage::new(
+            "tcp://localhost:9000".to_string(),
+            "audit".to_string(),
+            "events".to_string(),
+        ));
+        let storage = EncryptedFieldStorage::new(
+            inner.clone(),
+            Arc::new(FieldEncryptor::with_default_fields()),
+        );
+        (inner, storage)
+    }
+
+    fn tenant_filter() -> QueryFilter {
+        QueryFilter {
+            tenant_id: Some("tenant-a".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_email_is_stored_encrypted() {
+        let (inner, storage) = storage();
+        storage.store_batch(&[event()]).await.unwrap();
+
+        let stored = inner.query_events(&tenant_filter()).await.unwrap();
+        let user = stored[0].user_identity.as_ref().unwrap();
+        assert!(user.email.starts_with(ENCRYPTED_PREFIX));
+        assert!(!user.email.contains("alice@example.com"));
+        assert!(user.user_id.starts_with(ENCRYPTED_PREFIX));
+        assert!(
+            stored[0]
+                .http_context
+                .as_ref()
+                .unwrap()
+                .source_ip
+                .starts_with(ENCRYPTED_PREFIX)
+        );
+
+        // Non-PII fields stay queryable in plaintext
+        assert_eq!(user.username, "alice");
+        let by_action = QueryFilter {
+            action: Some("DeletePolicy".to_string()),
+            ..tenant_filter()
+        };
+        assert_eq!(inner.count_events(&by_action).await.unwrap(), 1);
+
+        assert!(matches!(
+            FieldEncryptor::new(["user_identity.password"]),
+            Err(EncryptionError::UnknownField(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_authorized_reader_gets_plaintext() {
+        let (_, storage) = storage();
+        storage.store_event(&event()).await.unwrap();
+
+        let events = storage
+            .query_events_as(&tenant_filter(), &[ApiScope::AuditRead, ApiScope::PiiRead])
+            .await
+            .unwrap();
+        assert_eq!(events, vec![event()]);
+    }
+
+    #[tokio::test]
+    async fn test_wrapped_keys_survive_restart() {
+        use crate::encryption::FileKms;
+
+        let dir = tempfile::tempdir().unwrap();
+        let encryptor = || {
+            let kms = Arc::new(FileKms::new(dir.path().join("kms")).unwrap());
+            let store = Arc::new(FileWrappedKeyStore::new(dir.path().join("keys")).unwrap());
+            Arc::new(FieldEncryptor::with_default_fields().with_key_custody(kms, store))
+        };
+        let inner = Arc::new(crate::storage::InMemoryStorage::new());
+        EncryptedFieldStorage::new(inner.clone(), encryptor())
+            .store_event(&event())
+            .await
+            .unwrap();
+
+        // Only the wrapped key is on disk
+        let saved = std::fs::read_to_string(
+            dir.path()
+                .join("keys")
+                .join(format!("{}.json", hex::encode("tenant-a"))),
+        )
+        .unwrap();
+        assert!(saved.contains("cmk-tenant-a"));
+
+        // A new process loads the wrapped key and decrypts what the old one stored
+        let restarted = encryptor();
+        assert_eq!(restarted.load_tenant_keys().await.unwrap(), 1);
+        let events = EncryptedFieldStorage::new(inner, restarted)
+            .query_events_as(&tenant_filter(), &[ApiScope::PiiRead])
+            .await
+            .unwrap();
+        assert_eq!(events, vec![event()]);
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_reader_sees_redaction() {
+        let (_, storage) = storage();
+        storage.store_event(&event()).await.unwrap();
+
+        let events = storage
+            .query_events_as(&tenant_filter(), &[ApiScope::AuditRead, ApiScope::Admin])
+            .await
+            .unwrap();
+        let user = events[0].user_identity.as_ref().unwrap();
+        assert_eq!(user.email, REDACTED);
+        assert_eq!(user.user_id, REDACTED);
+        assert_eq!(events[0].http_context.as_ref().unwrap().source_ip, REDACTED);
+        assert_eq!(events[0].action, "DeletePolicy");
+    }
+}
@@ -0,0 +1,365 @@
+//! Synthetic audit events for benchmarks and load tests
+//!
+//! An `EventFixtureGenerator` produces realistic `AuditEvent`s with a
+//! weighted tenant distribution, event-category mix, timestamp spread and
+//! metadata. Generation is seeded, so the same `FixtureConfig` always yields
+//! the same sequence of events and a load test can be replayed exactly.
+
+use hodei_audit_proto::{AuditEvent, EventId, Hrn, HttpContext, TenantId, UserIdentity};
+use hodei_audit_types::migration::version_string;
+use hodei_audit_types::{CURRENT_EVENT_VERSION, EventCategory, Outcome};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::{Duration, SystemTime};
+
+/// `event_source` of generated events
+pub const FIXTURE_EVENT_SOURCE: &str = "hodei-audit-fixtures";
+
+/// Actions generated for each category: name, HTTP method, read-only
+const MANAGEMENT_ACTIONS: &[(&str, &str, bool)] = &[
+    ("CreatePolicyStore", "POST", false),
+    ("UpdatePolicy", "PUT", false),
+    ("DeletePolicy", "DELETE", false),
+    ("RotateKey", "POST", false),
+];
+const DATA_ACTIONS: &[(&str, &str, bool)] = &[
+    ("GetPolicy", "GET", true),
+    ("ListPolicies", "GET", true),
+    ("IsAuthorized", "POST", true),
+    ("PutEntity", "PUT", false),
+];
+const INSIGHT_ACTIONS: &[(&str, &str, bool)] = &[
+    ("AnomalyDetected", "POST", true),
+    ("ThresholdExceeded", "POST", true),
+];
+
+/// Shape of the generated events
+#[derive(Debug, Clone)]
+pub struct FixtureConfig {
+    /// Tenants and their relative weights
+    pub tenants: Vec<(String, u32)>,
+    /// Event categories and their relative weights
+    pub categories: Vec<(EventCategory, u32)>,
+    /// Share of events with a `Failure` or `Denied` outcome
+    pub failure_rate: f64,
+    /// Events are timestamped uniformly over `[end_time - time_spread, end_time]`
+    pub time_spread: Duration,
+    pub end_time: SystemTime,
+    /// Extra string metadata fields per event
+    pub metadata_fields: usize,
+    /// Distinct users per tenant
+    pub users_per_tenant: usize,
+    pub seed: u64,
+}
+
+impl Default for FixtureConfig {
+    fn default() -> Self {
+        Self {
+            tenants: vec![
+                ("tenant-1".to_string(), 6),
+                ("tenant-2".to_string(), 3),
+                ("tenant-3".to_string(), 1),
+            ],
+            categories: vec![
+                (EventCategory::Management, 2),
+                (EventCategory::Data, 7),
+                (EventCategory::Insight, 1),
+            ],
+            failure_rate: 0.05,
+            time_spread: Duration::from_secs(60 * 60),
+            end_time: SystemTime::now(),
+            metadata_fields: 4,
+            users_per_tenant: 50,
+            seed: 0,
+        }
+    }
+}
+
+impl FixtureConfig {
+    /// Tenants and their relative weights
+    pub fn with_tenants(mut self, tenants: &[(&str, u32)]) -> Self {
+        self.tenants = tenants
+            .iter()
+            .map(|(tenant, weight)| (tenant.to_string(), *weight))
+            .collect();
+        self
+    }
+
+    /// Event categories and their relative weights
+    pub fn with_categories(mut self, categories: &[(EventCategory, u32)]) -> Self {
+        self.categories = categories.to_vec();
+        self
+    }
+
+    /// Share of failed or denied events
+    pub fn with_failure_rate(mut self, failure_rate: f64) -> Self {
+        self.failure_rate = failure_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Spread events over `time_spread` up to `end_time`
+    pub fn with_time_spread(mut self, time_spread: Duration, end_time: SystemTime) -> Self {
+        self.time_spread = time_spread;
+        self.end_time = end_time;
+        self
+    }
+
+    /// Extra string metadata fields per event
+    pub fn with_metadata_fields(mut self, metadata_fields: usize) -> Self {
+        self.metadata_fields = metadata_fields;
+        self
+    }
+
+    /// Seed of the sequence
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Seeded generator of `AuditEvent`s
+#[derive(Debug, Clone)]
+pub struct EventFixtureGenerator {
+    config: FixtureConfig,
+    tenants: WeightedIndex<u32>,
+    categories: WeightedIndex<u32>,
+    rng: StdRng,
+    generated: u64,
+}
+
+impl EventFixtureGenerator {
+    /// Generator for `config`; fails if the tenant or category weights are
+    /// empty or all zero
+    pub fn new(config: FixtureConfig) -> Result<Self, anyhow::Error> {
+        let tenants = WeightedIndex::new(config.tenants.iter().map(|(_, weight)| *weight))
+            .map_err(|e| anyhow::anyhow!("invalid tenant weights: {}", e))?;
+        let categories = WeightedIndex::new(config.categories.iter().map(|(_, weight)| *weight))
+            .map_err(|e| anyhow::anyhow!("invalid category weights: {}", e))?;
+        Ok(Self {
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+            tenants,
+            categories,
+            generated: 0,
+        })
+    }
+
+    /// Number of events generated so far
+    pub fn generated(&self) -> u64 {
+        self.generated
+    }
+
+    /// Generate the next batch of `size` events
+    pub fn batch(&mut self, size: usize) -> Vec<AuditEvent> {
+        (0..size).map(|_| self.next_event()).collect()
+    }
+
+    /// Generate the next event
+    pub fn next_event(&mut self) -> AuditEvent {
+        let tenant = self.config.tenants[self.tenants.sample(&mut self.rng)]
+            .0
+            .clone();
+        let category = self.config.categories[self.categories.sample(&mut self.rng)].0;
+        let actions = match category {
+            EventCategory::Management => MANAGEMENT_ACTIONS,
+            EventCategory::Insight => INSIGHT_ACTIONS,
+            _ => DATA_ACTIONS,
+        };
+        let (action, method, read_only) = actions[self.rng.gen_range(0..actions.len())];
+
+        let outcome = if self.rng.gen_bool(self.config.failure_rate) {
+            if self.rng.gen_bool(0.5) {
+                Outcome::Failure
+            } else {
+                Outcome::Denied
+            }
+        } else {
+            Outcome::Success
+        };
+        let status_code = match outcome {
+            Outcome::Success => 200,
+            Outcome::Denied => 403,
+            _ => 500,
+        };
+
+        let user = format!(
+            "user-{}",
+            self.rng.gen_range(0..self.config.users_per_tenant.max(1))
+        );
+        let resource = format!("store-{}", self.rng.gen_range(0..100));
+        let spread_ms = self.config.time_spread.as_millis() as u64;
+        let event_time =
+            self.config.end_time - Duration::from_millis(self.rng.gen_range(0..=spread_ms));
+        let latency_ms = self.rng.gen_range(1..250);
+        let event_id = uuid::Builder::from_random_bytes(self.rng.r#gen()).into_uuid();
+        let trace_id = format!("{:032x}", self.rng.r#gen::<u128>());
+
+        let mut event = AuditEvent {
+            event_id: Some(EventId {
+                value: event_id.to_string(),
+            }),
+            tenant_id: Some(TenantId {
+                value: tenant.clone(),
+            }),
+            hrn: Some(Hrn {
+                partition: "hodei".to_string(),
+                service: "verified-permissions".to_string(),
+                tenant_id: tenant.clone(),
+                region: "global".to_string(),
+                resource_type: "policy-store".to_string(),
+                resource_path: resource.clone(),
+            }),
+            user_identity: Some(UserIdentity {
+                user_id: user.clone(),
+                username: user.clone(),
+                email: format!("{}@{}.example.com", user, tenant),
+                roles: vec!["developer".to_string()],
+                tenant_id: tenant,
+            }),
+            http_context: Some(HttpContext {
+                method: method.to_string(),
+                path: format!("/v1/policy-stores/{}", resource),
+                user_agent: "hodei-fixtures/1.0".to_string(),
+                source_ip: format!(
+                    "10.0.{}.{}",
+                    self.rng.gen_range(0..256),
+                    self.rng.gen_range(1..255)
+                ),
+                status_code,
+                ..Default::default()
+            }),
+            action: action.to_string(),
+            event_category: i32::from(category),
+            read_only,
+            outcome: i32::from(outcome),
+            event_time: Some(prost_types::Timestamp::from(event_time)),
+            processed_at: Some(prost_types::Timestamp::from(
+                event_time + Duration::from_millis(latency_ms),
+            )),
+            latency_ms,
+            trace_id,
+            correlation_id: format!("corr-{}", self.generated),
+            event_source: FIXTURE_EVENT_SOURCE.to_string(),
+            event_version: version_string(CURRENT_EVENT_VERSION),
+            management_event: category == EventCategory::Management,
+            ..Default::default()
+        };
+        if outcome != Outcome::Success {
+            event.error_code = if outcome == Outcome::Denied {
+                "AccessDenied".to_string()
+            } else {
+                "InternalError".to_string()
+            };
+            event.error_message = format!("{} failed", action);
+        }
+        if self.config.metadata_fields > 0 {
+            let fields = (0..self.config.metadata_fields)
+                .map(|i| {
+                    let value = format!("value-{}", self.rng.gen_range(0..1000));
+                    (
+                        format!("field_{}", i),
+                        prost_types::Value {
+                            kind: Some(prost_types::value::Kind::StringValue(value)),
+                        },
+                    )
+                })
+                .collect();
+            event.metadata = Some(prost_types::Struct { fields });
+        }
+
+        self.generated += 1;
+        event
+    }
+}
+
+impl Iterator for EventFixtureGenerator {
+    type Item = AuditEvent;
+
+    fn next(&mut self) -> Option<AuditEvent> {
+        Some(self.next_event())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::{DEFAULT_MAX_EVENT_BYTES, check_event_size};
+    use std::collections::HashMap;
+
+    const EVENTS: usize = 20_000;
+
+    fn share<K: std::hash::Hash + Eq>(counts: &HashMap<K, usize>, key: K) -> f64 {
+        counts.get(&key).copied().unwrap_or_default() as f64 / EVENTS as f64
+    }
+
+    #[test]
+    fn test_respects_tenant_and_category_distribution() {
+        let config = FixtureConfig::default()
+            .with_tenants(&[("big", 7), ("medium", 2), ("small", 1)])
+            .with_categories(&[(EventCategory::Data, 3), (EventCategory::Management, 1)])
+            .with_seed(42);
+        let events = EventFixtureGenerator::new(config).unwrap().batch(EVENTS);
+
+        let mut tenants = HashMap::new();
+        let mut categories = HashMap::new();
+        for event in &events {
+            *tenants
+                .entry(event.tenant_id.clone().unwrap().value)
+                .or_insert(0) += 1;
+            *categories
+                .entry(EventCategory::from(event.event_category))
+                .or_insert(0) += 1;
+        }
+
+        let tolerance = 0.02;
+        assert!((share(&tenants, "big".to_string()) - 0.7).abs() < tolerance);
+        assert!((share(&tenants, "medium".to_string()) - 0.2).abs() < tolerance);
+        assert!((share(&tenants, "small".to_string()) - 0.1).abs() < tolerance);
+        assert!((share(&categories, EventCategory::Data) - 0.75).abs() < tolerance);
+        assert!((share(&categories, EventCategory::Management) - 0.25).abs() < tolerance);
+        assert_eq!(share(&categories, EventCategory::Insight), 0.0);
+    }
+
+    #[test]
+    fn test_events_are_valid() {
+        let end_time = SystemTime::now();
+        let spread = Duration::from_secs(600);
+        let config = FixtureConfig::default().with_time_spread(spread, end_time);
+        let mut generator = EventFixtureGenerator::new(config).unwrap();
+
+        for (index, event) in generator.by_ref().take(1_000).enumerate() {
+            let tenant = &event.tenant_id.as_ref().unwrap().value;
+            assert!(!event.event_id.as_ref().unwrap().value.is_empty());
+            assert!(!tenant.is_empty());
+            assert!(!event.action.is_empty());
+
+            let hrn = crate::exporters::hrn_string(event.hrn.as_ref().unwrap());
+            let parsed = hodei_audit_types::Hrn::parse(hrn).unwrap();
+            assert_eq!(&parsed.tenant_id, tenant);
+
+            let event_time = SystemTime::try_from(event.event_time.unwrap()).unwrap();
+            assert!(event_time <= end_time && event_time >= end_time - spread);
+            assert_eq!(event.metadata.as_ref().unwrap().fields.len(), 4);
+            assert!(check_event_size(index as u64, &event, DEFAULT_MAX_EVENT_BYTES).is_ok());
+        }
+        assert_eq!(generator.generated(), 1_000);
+    }
+
+    #[test]
+    fn test_same_seed_replays_same_events() {
+        let config = FixtureConfig::default().with_seed(7);
+        let first = EventFixtureGenerator::new(config.clone())
+            .unwrap()
+            .batch(100);
+        let second = EventFixtureGenerator::new(config).unwrap().batch(100);
+        assert_eq!(first, second);
+
+        let other = EventFixtureGenerator::new(FixtureConfig::default().with_seed(8))
+            .unwrap()
+            .batch(100);
+        assert_ne!(first, other);
+
+        assert!(EventFixtureGenerator::new(FixtureConfig::default().with_tenants(&[])).is_err());
+    }
+}
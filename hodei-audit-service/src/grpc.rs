@@ -3,16 +3,38 @@
 //! Implementación de los servicios gRPC para el Hodei Audit Service
 //! Incluye: AuditControl, AuditQuery, AuditCrypto y VectorApi
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+use tonic::service::InterceptorLayer;
+use tonic::transport::{Certificate, Identity, ServerTlsConfig};
 use tonic::{Request, Response, Status, transport::Server};
+use tonic_reflection::server::v1::{ServerReflection, ServerReflectionServer};
 use tracing::info;
 
+use crate::anomaly_detection::{AnomalyDetector, AnomalyDetectorConfig};
 use crate::crypto::{Ed25519Signer, InMemoryDigestChain, Sha256Hasher};
+use crate::dead_letter::DeadLetterStore;
+use crate::distributed_tracing::Tracer;
+use crate::encryption::FileKms;
+use crate::event_feed::EventFeed;
+use crate::field_encryption::{
+    DEFAULT_PII_FIELDS, EncryptedFieldStorage, FieldEncryptor, FileWrappedKeyStore,
+};
 use crate::grpc::audit_control_server::AuditControlServiceImpl;
 use crate::grpc::audit_crypto_server::AuditCryptoServiceImpl;
 use crate::grpc::audit_query_server::AuditQueryServiceImpl;
 use crate::grpc::vector_api_server::VectorApiServiceImpl;
+use crate::grpc_interceptor::{
+    ClientCertInterceptor, RpcObservabilityLayer, TenantValidationInterceptor,
+};
 use crate::key_management::{FileKeyStore, StandaloneKeyManager};
+use crate::meta_audit::MetaAuditLogger;
+use crate::metrics::{MetricsServerConfig, create_metrics, serve_metrics};
+use crate::performance::{BackpressureConfig, BackpressureController};
+use crate::routing::{EventRouter, RoutingConfig};
+use crate::storage::{StorageBackend, StorageConfig, StorageFactory, TieredStorageConfig};
 
 // Re-exports de los módulos
 pub mod audit_control_server;
@@ -20,6 +42,56 @@ pub mod audit_crypto_server;
 pub mod audit_query_server;
 pub mod vector_api_server;
 
+/// Nombre del servicio en las trazas
+pub const SERVICE_NAME: &str = "hodei-audit-service";
+
+/// Eventos de meta-auditoría en cola antes de frenar las acciones
+/// administrativas
+pub const META_AUDIT_QUEUE_SIZE: usize = 1024;
+
+/// Tamaño máximo por defecto de un evento ingerido (1 MiB codificado)
+pub const DEFAULT_MAX_EVENT_BYTES: usize = 1024 * 1024;
+
+/// Comprobar que un evento codificado no supera `max_event_bytes`
+pub fn check_event_size(
+    index: u64,
+    event: &hodei_audit_proto::AuditEvent,
+    max_event_bytes: usize,
+) -> Result<(), String> {
+    let size = prost::Message::encoded_len(event);
+    if size > max_event_bytes {
+        return Err(format!(
+            "event at index {} is {} bytes, exceeding the {} byte limit",
+            index, size, max_event_bytes
+        ));
+    }
+    Ok(())
+}
+
+/// Persistir la copia en bruto de eventos recibidos, antes de enriquecerlos
+///
+/// Los eventos sin tenant propio heredan `tenant_id`, para que el borrado
+/// por tenant (offboarding, retención, GDPR) alcance también su copia.
+pub(crate) async fn store_raw_copies(
+    raw_storage: &dyn StorageBackend,
+    tenant_id: &str,
+    events: &[hodei_audit_proto::AuditEvent],
+) -> Result<(), anyhow::Error> {
+    let events: Vec<_> = events
+        .iter()
+        .cloned()
+        .map(|mut event| {
+            if event.tenant_id.as_ref().is_none_or(|t| t.value.is_empty()) {
+                event.tenant_id = Some(hodei_audit_proto::TenantId {
+                    value: tenant_id.to_string(),
+                });
+            }
+            event
+        })
+        .collect();
+    raw_storage.store_batch(&events).await
+}
+
 /// Configuración del servidor gRPC
 #[derive(Debug, Clone)]
 pub struct GrpcConfig {
@@ -27,6 +99,40 @@ pub struct GrpcConfig {
     pub audit_query_addr: String,   // Puerto 50053
     pub audit_crypto_addr: String,  // Puerto 50054
     pub vector_api_addr: String,    // Puerto 50051
+    /// Registrar el servicio de reflection gRPC en cada servidor
+    pub enable_reflection: bool,
+    /// TLS/mTLS; `None` sirve gRPC en texto plano
+    pub tls: Option<GrpcTlsConfig>,
+    /// Endpoint HTTP `/metrics` para Prometheus; `None` lo desactiva
+    pub metrics: Option<MetricsServerConfig>,
+    /// Tamaño máximo de un evento ingerido; los mayores se rechazan
+    pub max_event_bytes: usize,
+    /// Directorio del estado que debe sobrevivir a reinicios (CMKs del KMS,
+    /// claves de campo cifradas...)
+    pub data_dir: PathBuf,
+    /// Backends de los niveles hot, warm y cold
+    pub storage: TieredStorageConfig,
+    /// Reglas que reparten los eventos ingeridos entre niveles, sinks y
+    /// notificadores, y espejo opcional a staging; sin reglas cada evento va
+    /// al nivel que le corresponde por edad
+    pub routing: RoutingConfig,
+    /// Campos PII cifrados con la clave del tenant antes de persistirlos
+    pub pii_fields: Vec<String>,
+    /// Nombres (CN o SAN) de los certificados de cliente autorizados para
+    /// las acciones administrativas; vacío las deniega todas
+    pub admin_identities: Vec<String>,
+    /// Tenants cuyos eventos puede seguir con StreamEvents cada certificado
+    /// de cliente (CN o SAN); los certificados que no aparecen no pueden
+    /// seguir ninguno
+    pub tenant_identities: HashMap<String, Vec<String>>,
+    /// Umbrales de eventos pendientes de persistir a partir de los que la
+    /// ingestión se frena
+    pub backpressure: BackpressureConfig,
+    /// Detección de anomalías en el ritmo de ingestión de cada tenant;
+    /// `None` la desactiva
+    pub anomaly_detection: Option<AnomalyDetectorConfig>,
+    /// Intervalo entre muestras de la detección de anomalías
+    pub anomaly_sample_interval: Duration,
 }
 
 impl Default for GrpcConfig {
@@ -36,6 +142,93 @@ impl Default for GrpcConfig {
             audit_query_addr: "0.0.0.0:50053".to_string(),
             audit_crypto_addr: "0.0.0.0:50054".to_string(),
             vector_api_addr: "0.0.0.0:50051".to_string(),
+            enable_reflection: false,
+            tls: None,
+            metrics: None,
+            max_event_bytes: DEFAULT_MAX_EVENT_BYTES,
+            data_dir: PathBuf::from("/tmp/hodei-audit"),
+            storage: TieredStorageConfig::default(),
+            routing: RoutingConfig::default(),
+            pii_fields: DEFAULT_PII_FIELDS.iter().map(|f| f.to_string()).collect(),
+            admin_identities: Vec::new(),
+            tenant_identities: HashMap::new(),
+            backpressure: BackpressureConfig::default(),
+            anomaly_detection: Some(AnomalyDetectorConfig::default()),
+            anomaly_sample_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Configuración TLS del servidor gRPC
+///
+/// Si se indica `client_ca_pem` el servidor exige un certificado de cliente
+/// firmado por esa CA (mTLS), de modo que solo los CAP collectors de
+/// confianza pueden publicar eventos.
+#[derive(Clone)]
+pub struct GrpcTlsConfig {
+    /// Certificado del servidor (PEM)
+    pub cert_pem: String,
+    /// Clave privada del servidor (PEM)
+    pub key_pem: String,
+    /// Bundle de CAs para validar certificados de cliente (PEM)
+    pub client_ca_pem: Option<String>,
+}
+
+impl GrpcTlsConfig {
+    /// Cargar certificado, clave y CA de cliente desde ficheros PEM
+    pub fn from_files(
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+        client_ca_path: Option<impl AsRef<Path>>,
+    ) -> std::io::Result<Self> {
+        Ok(Self {
+            cert_pem: std::fs::read_to_string(cert_path)?,
+            key_pem: std::fs::read_to_string(key_path)?,
+            client_ca_pem: client_ca_path.map(std::fs::read_to_string).transpose()?,
+        })
+    }
+
+    /// Verificar si se exige certificado de cliente
+    pub fn requires_client_cert(&self) -> bool {
+        self.client_ca_pem.is_some()
+    }
+
+    fn server_tls_config(&self) -> ServerTlsConfig {
+        let config =
+            ServerTlsConfig::new().identity(Identity::from_pem(&self.cert_pem, &self.key_pem));
+        match &self.client_ca_pem {
+            Some(ca) => config.client_ca_root(Certificate::from_pem(ca)),
+            None => config,
+        }
+    }
+}
+
+impl std::fmt::Debug for GrpcTlsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // La clave privada nunca se imprime
+        f.debug_struct("GrpcTlsConfig")
+            .field("cert_pem", &self.cert_pem)
+            .field("key_pem", &"[REDACTED]")
+            .field("client_ca_pem", &self.client_ca_pem)
+            .finish()
+    }
+}
+
+/// Opciones comunes a los cuatro servidores
+#[derive(Clone)]
+struct ServerOptions {
+    enable_reflection: bool,
+    tls: Option<GrpcTlsConfig>,
+    observability: RpcObservabilityLayer,
+}
+
+impl ServerOptions {
+    /// Crear el builder del servidor aplicando TLS si está configurado
+    fn server_builder(&self) -> Result<Server, Box<dyn std::error::Error + Send + Sync>> {
+        let builder = Server::builder();
+        match &self.tls {
+            Some(tls) => Ok(builder.tls_config(tls.server_tls_config())?),
+            None => Ok(builder),
         }
     }
 }
@@ -82,9 +275,62 @@ pub async fn run_grpc_server(
     let health_service = HealthService::new();
     health_service.set_status(1); // SERVING
 
+    // Métricas y access log por RPC compartidos entre todos los servidores
+    let metrics = create_metrics();
+
+    // Storage por niveles, al que se escribe a través del router; los campos
+    // PII se cifran con claves por tenant envueltas por el KMS, que se
+    // recargan al arrancar para poder descifrar lo persistido antes del
+    // reinicio
+    let tiered_storage = Arc::new(StorageFactory::build_tiered(&config.storage)?);
+    let field_encryptor = Arc::new(FieldEncryptor::new(&config.pii_fields)?.with_key_custody(
+        Arc::new(FileKms::new(config.data_dir.join("kms"))?),
+        Arc::new(FileWrappedKeyStore::new(
+            config.data_dir.join("field-keys"),
+        )?),
+    ));
+    field_encryptor.load_tenant_keys().await?;
+    let router = Arc::new(EventRouter::from_config(
+        tiered_storage.clone(),
+        &config.routing,
+    ));
+    let storage: Arc<dyn StorageBackend> =
+        Arc::new(EncryptedFieldStorage::new(router, field_encryptor.clone()));
+    // Copias en bruto de los eventos, con el PII cifrado igual que el resto
+    let raw_storage = tiered_storage.raw_storage().map(|raw| {
+        Arc::new(EncryptedFieldStorage::new(raw, field_encryptor.clone()))
+            as Arc<dyn StorageBackend>
+    });
+
+    // Eventos que fallan en la ingestión, para inspeccionarlos y reintentarlos
+    let dead_letters = Arc::new(DeadLetterStore::from_config(&StorageConfig::FileSystem {
+        root: config.data_dir.join("dead-letters"),
+    })?);
+
+    // Las acciones administrativas se auditan por el pipeline normal
+    let (meta_audit_sender, mut meta_audit_events) =
+        tokio::sync::mpsc::channel(META_AUDIT_QUEUE_SIZE);
+    let meta_audit = MetaAuditLogger::new(meta_audit_sender);
+
     // Inicializar servicios
-    let audit_control = AuditControlServiceImpl::new();
-    let audit_query = AuditQueryServiceImpl::new();
+    // Los eventos guardados por control y Vector alimentan el tail de query
+    let event_feed = Arc::new(EventFeed::default());
+    let mut audit_control = AuditControlServiceImpl::new()
+        .with_storage(storage.clone())
+        .with_dead_letters(dead_letters)
+        .with_meta_audit(meta_audit.clone())
+        .with_tiered_storage(tiered_storage.clone())
+        .with_admin_identities(config.admin_identities.clone())
+        .with_event_feed(event_feed.clone())
+        .with_max_event_bytes(config.max_event_bytes)
+        .with_metrics(metrics.clone())
+        .with_backpressure(Arc::new(BackpressureController::new(
+            config.backpressure.clone(),
+        )))
+        .with_tracer(Tracer::new(SERVICE_NAME));
+    let audit_query = AuditQueryServiceImpl::new()
+        .with_event_feed(event_feed.clone())
+        .with_tenant_identities(config.tenant_identities.clone());
 
     // Inicializar servicios crypto con dependencias reales
     let hashing = Sha256Hasher::new();
@@ -97,39 +343,102 @@ pub async fn run_grpc_server(
         Ed25519Signer,
         InMemoryDigestChain,
         StandaloneKeyManager<Ed25519Signer, FileKeyStore>,
-    >::new(hashing, signing, digest_chain, key_manager);
+    >::new(hashing, signing, digest_chain, key_manager)
+    .with_meta_audit(meta_audit);
 
-    let vector_api = VectorApiServiceImpl::new();
+    let mut vector_api = VectorApiServiceImpl::new()
+        .with_storage(storage.clone())
+        .with_event_feed(event_feed)
+        .with_max_event_bytes(config.max_event_bytes)
+        .with_metrics(metrics.clone());
+    if let Some(raw_storage) = raw_storage {
+        audit_control = audit_control.with_raw_storage(raw_storage.clone());
+        vector_api = vector_api.with_raw_storage(raw_storage);
+    }
+
+    let options = ServerOptions {
+        enable_reflection: config.enable_reflection,
+        tls: config.tls.clone(),
+        observability: match &config.metrics {
+            Some(metrics_config) => RpcObservabilityLayer::new(metrics.clone())
+                .with_max_tenant_labels(metrics_config.max_tenant_labels),
+            None => RpcObservabilityLayer::new(metrics.clone()),
+        },
+    };
 
     // Spawner threads para cada servicio
-    let handles = vec![
+    let meta_audit_control = audit_control.clone();
+    let mut handles = vec![
         // Audit Control Service (Puerto 50052)
         tokio::spawn(run_audit_control_server(
             config.audit_control_addr.clone(),
             audit_control,
+            options.clone(),
         )),
         // Audit Query Service (Puerto 50053)
         tokio::spawn(run_audit_query_server(
             config.audit_query_addr.clone(),
             audit_query,
+            options.clone(),
         )),
         // Audit Crypto Service (Puerto 50054)
         tokio::spawn(run_audit_crypto_server(
             config.audit_crypto_addr.clone(),
             audit_crypto,
+            options.clone(),
         )),
         // Vector API Service (Puerto 50051)
         tokio::spawn(run_vector_api_server(
             config.vector_api_addr.clone(),
             vector_api,
+            options.clone(),
         )),
     ];
+    // Consumidor de la meta-auditoría
+    handles.push(tokio::spawn(async move {
+        while let Some(event) = meta_audit_events.recv().await {
+            meta_audit_control.ingest_meta_audit_event(event).await;
+        }
+        Ok(())
+    }));
+    // Detección de anomalías sobre las métricas compartidas; se detiene al
+    // soltarse `_anomaly_shutdown`, cuando el servidor termina o se cancela
+    let (_anomaly_shutdown, anomaly_shutdown) = tokio::sync::oneshot::channel();
+    if let Some(detector_config) = config.anomaly_detection.clone() {
+        handles.push(tokio::spawn(run_anomaly_detector(
+            detector_config,
+            config.anomaly_sample_interval,
+            metrics.clone(),
+            anomaly_shutdown,
+        )));
+    }
+    // Endpoint de métricas para Prometheus
+    if let Some(metrics_config) = config.metrics.clone() {
+        handles.push(tokio::spawn(run_metrics_server(metrics_config, metrics)));
+    }
 
     info!("All gRPC servers started successfully");
     info!("  - AuditControlService: {}", config.audit_control_addr);
     info!("  - AuditQueryService: {}", config.audit_query_addr);
     info!("  - AuditCryptoService: {}", config.audit_crypto_addr);
     info!("  - VectorApi: {}", config.vector_api_addr);
+    if let Some(metrics_config) = &config.metrics {
+        info!("  - Metrics: {}/metrics", metrics_config.addr);
+    }
+    if config.anomaly_detection.is_some() {
+        info!(
+            "  - Anomaly detection every {:?}",
+            config.anomaly_sample_interval
+        );
+    }
+    if config.enable_reflection {
+        info!("  - gRPC reflection enabled");
+    }
+    match &config.tls {
+        Some(tls) if tls.requires_client_cert() => info!("  - TLS enabled (mTLS)"),
+        Some(_) => info!("  - TLS enabled"),
+        None => info!("  - TLS disabled (plaintext)"),
+    }
 
     // Esperar a que todos los servicios terminen
     for handle in handles {
@@ -139,18 +448,54 @@ pub async fn run_grpc_server(
     Ok(())
 }
 
+/// Construir el servicio de reflection si está habilitado
+///
+/// Expone los descriptores de todos los protos de Hodei Audit para que
+/// herramientas como `grpcurl` o Postman puedan introspeccionar el servidor.
+fn reflection_service(
+    enabled: bool,
+) -> Result<Option<ServerReflectionServer<impl ServerReflection>>, tonic_reflection::server::Error>
+{
+    if !enabled {
+        return Ok(None);
+    }
+
+    let service = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(hodei_audit_proto::FILE_DESCRIPTOR_SET)
+        .build_v1()?;
+    Ok(Some(service))
+}
+
+/// Interceptor de tenant de los servicios de control y consulta
+///
+/// Valida el `x-tenant-id` enviado y deja en la petición el `TenantContext`
+/// validado y el contexto de traza con el tenant como baggage. Las RPCs sin
+/// tenant (health check, acciones administrativas) pasan sin él, y la API
+/// key no se exige porque los clientes se autentican por mTLS.
+pub(crate) fn tenant_interceptor() -> TenantValidationInterceptor {
+    TenantValidationInterceptor::new()
+        .strict_mode(false)
+        .require_tenant(false)
+}
+
 async fn run_audit_control_server(
     addr: String,
     service: AuditControlServiceImpl,
+    options: ServerOptions,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     info!("Starting AuditControlService on {}", addr);
 
-    let server = Server::builder()
+    let server = options
+        .server_builder()?
+        .layer(options.observability)
+        .layer(InterceptorLayer::new(ClientCertInterceptor))
+        .layer(InterceptorLayer::new(tenant_interceptor()))
         .add_service(
             hodei_audit_proto::audit_control_service_server::AuditControlServiceServer::new(
                 service,
             ),
         )
+        .add_optional_service(reflection_service(options.enable_reflection)?)
         .serve(addr.parse()?)
         .await?;
 
@@ -161,13 +506,19 @@ async fn run_audit_control_server(
 async fn run_audit_query_server(
     addr: String,
     service: AuditQueryServiceImpl,
+    options: ServerOptions,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     info!("Starting AuditQueryService on {}", addr);
 
-    let server = Server::builder()
+    let server = options
+        .server_builder()?
+        .layer(options.observability)
+        .layer(InterceptorLayer::new(ClientCertInterceptor))
+        .layer(InterceptorLayer::new(tenant_interceptor()))
         .add_service(
             hodei_audit_proto::audit_query_service_server::AuditQueryServiceServer::new(service),
         )
+        .add_optional_service(reflection_service(options.enable_reflection)?)
         .serve(addr.parse()?)
         .await?;
 
@@ -178,6 +529,7 @@ async fn run_audit_query_server(
 async fn run_audit_crypto_server<HS, SS, DS, KM>(
     addr: String,
     service: AuditCryptoServiceImpl<HS, SS, DS, KM>,
+    options: ServerOptions,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
 where
     HS: crate::crypto::ports::hashing::HashingService,
@@ -187,10 +539,14 @@ where
 {
     info!("Starting AuditCryptoService on {}", addr);
 
-    let server = Server::builder()
+    let server = options
+        .server_builder()?
+        .layer(options.observability)
+        .layer(InterceptorLayer::new(ClientCertInterceptor))
         .add_service(
             hodei_audit_proto::audit_crypto_service_server::AuditCryptoServiceServer::new(service),
         )
+        .add_optional_service(reflection_service(options.enable_reflection)?)
         .serve(addr.parse()?)
         .await?;
 
@@ -201,16 +557,424 @@ where
 async fn run_vector_api_server(
     addr: String,
     service: VectorApiServiceImpl,
+    options: ServerOptions,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     info!("Starting VectorApi on {}", addr);
 
-    let server = Server::builder()
+    let server = options
+        .server_builder()?
+        .layer(options.observability)
+        .layer(InterceptorLayer::new(ClientCertInterceptor))
         .add_service(hodei_audit_proto::vector_api_server::VectorApiServer::new(
             service,
         ))
+        .add_optional_service(reflection_service(options.enable_reflection)?)
         .serve(addr.parse()?)
         .await?;
 
     info!("VectorApi stopped");
     Ok(server)
 }
+
+/// Muestrear `metrics` con el detector de anomalías hasta que el emisor de
+/// `shutdown` envíe o se suelte
+async fn run_anomaly_detector(
+    config: AnomalyDetectorConfig,
+    interval: Duration,
+    metrics: Arc<tokio::sync::RwLock<crate::metrics::AuditMetrics>>,
+    shutdown: tokio::sync::oneshot::Receiver<()>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let detected = AnomalyDetector::new(config)
+        .run(metrics, interval, async {
+            let _ = shutdown.await;
+        })
+        .await;
+    info!("Anomaly detector stopped ({} anomalies detected)", detected);
+    Ok(())
+}
+
+async fn run_metrics_server(
+    config: MetricsServerConfig,
+    metrics: Arc<tokio::sync::RwLock<crate::metrics::AuditMetrics>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let listener = tokio::net::TcpListener::bind(&config.addr).await?;
+    serve_metrics(listener, metrics, config.max_tenant_labels).await?;
+    info!("Metrics server stopped");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hodei_audit_proto::HealthCheckRequest;
+    use hodei_audit_proto::audit_control_service_client::AuditControlServiceClient;
+    use std::time::Duration;
+    use tonic_reflection::pb::v1::ServerReflectionRequest;
+    use tonic_reflection::pb::v1::server_reflection_client::ServerReflectionClient;
+    use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
+    use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
+
+    fn free_addr() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    async fn list_services(addr: &str) -> Vec<String> {
+        let endpoint = format!("http://{}", addr);
+        let mut client = None;
+        for _ in 0..50 {
+            let channel = tonic::transport::Endpoint::from_shared(endpoint.clone())
+                .unwrap()
+                .connect()
+                .await;
+            if let Ok(channel) = channel {
+                client = Some(ServerReflectionClient::new(channel));
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let mut client = client.expect("reflection server did not start");
+
+        let request = ServerReflectionRequest {
+            host: String::new(),
+            message_request: Some(MessageRequest::ListServices(String::new())),
+        };
+        let mut responses = client
+            .server_reflection_info(tokio_stream::once(request))
+            .await
+            .unwrap()
+            .into_inner();
+
+        match responses.message().await.unwrap().unwrap().message_response {
+            Some(MessageResponse::ListServicesResponse(list)) => {
+                list.service.into_iter().map(|s| s.name).collect()
+            }
+            other => panic!("unexpected reflection response: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reflection_lists_audit_services() {
+        let config = GrpcConfig {
+            audit_control_addr: free_addr(),
+            audit_query_addr: free_addr(),
+            audit_crypto_addr: free_addr(),
+            vector_api_addr: free_addr(),
+            enable_reflection: true,
+            tls: None,
+            metrics: None,
+            max_event_bytes: DEFAULT_MAX_EVENT_BYTES,
+            ..Default::default()
+        };
+        let query_addr = config.audit_query_addr.clone();
+        let server = tokio::spawn(run_grpc_server(config));
+
+        let services = list_services(&query_addr).await;
+        server.abort();
+
+        for expected in [
+            "hodei.audit.AuditControlService",
+            "hodei.audit.AuditQueryService",
+            "hodei.audit.AuditCryptoService",
+            "hodei.audit.VectorApi",
+            "grpc.reflection.v1.ServerReflection",
+        ] {
+            assert!(
+                services.iter().any(|s| s == expected),
+                "missing {} in {:?}",
+                expected,
+                services
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_anomaly_detector_stops_with_the_server() {
+        let (shutdown, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let detector = tokio::spawn(run_anomaly_detector(
+            AnomalyDetectorConfig::default(),
+            Duration::from_millis(10),
+            create_metrics(),
+            shutdown_rx,
+        ));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(!detector.is_finished());
+
+        // Dropping the sender, as a finished or cancelled server does
+        drop(shutdown);
+        tokio::time::timeout(Duration::from_secs(1), detector)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+    }
+
+    async fn connect_control(addr: &str) -> AuditControlServiceClient<tonic::transport::Channel> {
+        for _ in 0..50 {
+            if let Ok(client) = AuditControlServiceClient::connect(format!("http://{}", addr)).await
+            {
+                return client;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("audit control server did not start");
+    }
+
+    fn routed_event(
+        id: &str,
+        category: hodei_audit_types::EventCategory,
+        outcome: hodei_audit_types::Outcome,
+    ) -> hodei_audit_proto::AuditEvent {
+        hodei_audit_proto::AuditEvent {
+            event_id: Some(hodei_audit_proto::EventId {
+                value: id.to_string(),
+            }),
+            tenant_id: Some(hodei_audit_proto::TenantId {
+                value: "tenant-1".to_string(),
+            }),
+            event_category: i32::from(category),
+            outcome: i32::from(outcome),
+            ..Default::default()
+        }
+    }
+
+    async fn stored_ids(storage: &crate::storage::InMemoryStorage) -> Vec<String> {
+        let mut ids: Vec<String> = storage
+            .query_events(&crate::storage::QueryFilter::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|event| event.event_id.map(|id| id.value))
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_ingested_events_are_routed() {
+        use crate::routing::{MirrorConfig, RouteRule, RouteTarget};
+        use crate::storage::InMemoryStorage;
+        use hodei_audit_proto::vector_api_client::VectorApiClient;
+        use hodei_audit_types::{EventCategory, Outcome};
+
+        let data_dir = tempfile::tempdir().unwrap();
+        let security = Arc::new(InMemoryStorage::new());
+        let staging = Arc::new(InMemoryStorage::new());
+        let config = GrpcConfig {
+            audit_control_addr: free_addr(),
+            audit_query_addr: free_addr(),
+            audit_crypto_addr: free_addr(),
+            vector_api_addr: free_addr(),
+            data_dir: data_dir.path().to_path_buf(),
+            storage: TieredStorageConfig {
+                hot: StorageConfig::InMemory,
+                warm: StorageConfig::InMemory,
+                cold: StorageConfig::InMemory,
+                ..Default::default()
+            },
+            routing: RoutingConfig {
+                rules: vec![
+                    RouteRule::new(
+                        "security-failures",
+                        vec![
+                            RouteTarget::Tier(crate::storage::StorageTierType::Hot),
+                            RouteTarget::Sink("security".to_string()),
+                        ],
+                    )
+                    .with_categories(vec![EventCategory::Insight])
+                    .with_outcomes(vec![Outcome::Denied]),
+                ],
+                sinks: [(
+                    "security".to_string(),
+                    security.clone() as Arc<dyn StorageBackend>,
+                )]
+                .into(),
+                mirror: Some(
+                    MirrorConfig::new(staging.clone(), 0.0).with_rule(
+                        RouteRule::new("mirror-reads", vec![])
+                            .with_categories(vec![EventCategory::Data]),
+                    ),
+                ),
+                ..Default::default()
+            },
+            anomaly_detection: None,
+            ..Default::default()
+        };
+        let control_addr = config.audit_control_addr.clone();
+        let vector_addr = config.vector_api_addr.clone();
+        let server = tokio::spawn(run_grpc_server(config));
+
+        // Ingestión por el servicio de control
+        let mut control = connect_control(&control_addr).await;
+        let summary = control
+            .ingest_event_stream(tokio_stream::iter(vec![
+                routed_event("control-denied", EventCategory::Insight, Outcome::Denied),
+                routed_event("control-read", EventCategory::Data, Outcome::Success),
+            ]))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(summary.accepted, 2);
+        control
+            .publish_event(hodei_audit_proto::PublishEventRequest {
+                tenant_id: "tenant-1".to_string(),
+                event: Some(routed_event(
+                    "publish-denied",
+                    EventCategory::Insight,
+                    Outcome::Denied,
+                )),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let batch = control
+            .publish_batch(hodei_audit_proto::PublishBatchRequest {
+                tenant_id: "tenant-1".to_string(),
+                events: vec![
+                    routed_event("batch-denied", EventCategory::Insight, Outcome::Denied),
+                    routed_event("batch-read", EventCategory::Data, Outcome::Success),
+                ],
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(batch.failed_events.is_empty());
+
+        // Ingestión por el Vector API
+        let mut vector = VectorApiClient::connect(format!("http://{}", vector_addr))
+            .await
+            .unwrap();
+        let response = vector
+            .send_event_batch(hodei_audit_proto::EventBatchRequest {
+                events: vec![
+                    routed_event("vector-denied", EventCategory::Insight, Outcome::Denied),
+                    routed_event("vector-read", EventCategory::Data, Outcome::Success),
+                ],
+                protocol_version: vector_api_server::CURRENT_PROTOCOL_VERSION,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.received_count, 2);
+        server.abort();
+
+        assert_eq!(
+            stored_ids(&security).await,
+            vec![
+                "batch-denied",
+                "control-denied",
+                "publish-denied",
+                "vector-denied"
+            ]
+        );
+        assert_eq!(
+            stored_ids(&staging).await,
+            vec!["batch-read", "control-read", "vector-read"]
+        );
+    }
+
+    const TLS_FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls");
+
+    fn tls_fixture(name: &str) -> String {
+        std::fs::read_to_string(format!("{}/{}", TLS_FIXTURES, name)).unwrap()
+    }
+
+    fn mtls_config() -> GrpcConfig {
+        GrpcConfig {
+            audit_control_addr: free_addr(),
+            audit_query_addr: free_addr(),
+            audit_crypto_addr: free_addr(),
+            vector_api_addr: free_addr(),
+            enable_reflection: false,
+            tls: Some(GrpcTlsConfig {
+                cert_pem: tls_fixture("server.pem"),
+                key_pem: tls_fixture("server.key"),
+                client_ca_pem: Some(tls_fixture("ca.pem")),
+            }),
+            metrics: None,
+            max_event_bytes: DEFAULT_MAX_EVENT_BYTES,
+            ..Default::default()
+        }
+    }
+
+    async fn health_check_with_client_cert(
+        addr: &str,
+        cert: &str,
+        key: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let tls = tonic::transport::ClientTlsConfig::new()
+            .ca_certificate(Certificate::from_pem(tls_fixture("ca.pem")))
+            .identity(Identity::from_pem(tls_fixture(cert), tls_fixture(key)))
+            .domain_name("localhost");
+        let channel = tonic::transport::Endpoint::from_shared(format!("https://{}", addr))?
+            .tls_config(tls)?
+            .connect()
+            .await?;
+
+        AuditControlServiceClient::new(channel)
+            .health_check(HealthCheckRequest::default())
+            .await?;
+        Ok(())
+    }
+
+    /// Esperar a que el servidor acepte al cliente de confianza
+    async fn wait_for_trusted_client(addr: &str) -> bool {
+        for _ in 0..50 {
+            if health_check_with_client_cert(addr, "client.pem", "client.key")
+                .await
+                .is_ok()
+            {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn test_mtls_accepts_client_signed_by_trusted_ca() {
+        let config = mtls_config();
+        let addr = config.audit_control_addr.clone();
+        let server = tokio::spawn(run_grpc_server(config));
+
+        let connected = wait_for_trusted_client(&addr).await;
+        server.abort();
+
+        assert!(connected, "trusted client could not connect over mTLS");
+    }
+
+    #[tokio::test]
+    async fn test_mtls_rejects_untrusted_client_cert() {
+        let config = mtls_config();
+        let addr = config.audit_control_addr.clone();
+        let server = tokio::spawn(run_grpc_server(config));
+        assert!(wait_for_trusted_client(&addr).await);
+
+        let result =
+            health_check_with_client_cert(&addr, "untrusted-client.pem", "untrusted-client.key")
+                .await;
+        server.abort();
+
+        assert!(result.is_err(), "untrusted client cert was accepted");
+    }
+
+    #[test]
+    fn test_tls_config_debug_hides_private_key() {
+        let tls = GrpcTlsConfig {
+            cert_pem: "cert".to_string(),
+            key_pem: "super-secret-key".to_string(),
+            client_ca_pem: None,
+        };
+
+        assert!(!format!("{:?}", tls).contains("super-secret-key"));
+        assert!(!tls.requires_client_cert());
+    }
+
+    #[test]
+    fn test_reflection_disabled_by_default() {
+        assert!(!GrpcConfig::default().enable_reflection);
+        assert!(reflection_service(false).unwrap().is_none());
+    }
+}
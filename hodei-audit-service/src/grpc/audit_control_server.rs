@@ -1,22 +1,153 @@
-use std::sync::Arc;
-use tonic::{Request, Response, Status};
-use tracing::info;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tonic::metadata::MetadataValue;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{info, warn};
 
 use hodei_audit_proto::{
-    AuditEvent, EventId, HealthCheckRequest, HealthCheckResponse, HealthStatus,
+    AuditEvent, EventId, HealthCheckRequest, HealthCheckResponse, HealthStatus, IngestSummary,
     PublishBatchRequest, PublishBatchResponse, PublishEventRequest, PublishEventResponse, TenantId,
+    TriggerLifecycleMigrationRequest, TriggerLifecycleMigrationResponse,
     audit_control_service_server::{AuditControlService, AuditControlServiceServer},
 };
 use uuid::Uuid;
 
+use super::{DEFAULT_MAX_EVENT_BYTES, check_event_size, store_raw_copies};
+use crate::dead_letter::{DeadLetterStage, DeadLetterStore, ReplaySink};
+use crate::distributed_tracing::{IngestStage, Span, SpanAttribute, SpanKind, TraceState, Tracer};
+use crate::enrichment::EventEnricher;
+use crate::event_feed::EventFeed;
+use crate::grpc_interceptor::ClientIdentity;
+use crate::meta_audit::{AdminAction, META_AUDIT_SYSTEM_TENANT, MetaAuditLogger};
+use crate::metrics::AuditMetrics;
+use crate::normalization::EventNormalizer;
+use crate::performance::{
+    BackpressureController, BatcherConfig, BatchingPolicy, PressureLevel, SmartBatcher,
+};
+use crate::schema_registry::SchemaValidator;
+use crate::storage::{StorageBackend, TieredStorage};
+use crate::webhook::WebhookNotifier;
+
 /// Implementación del servicio de control de auditoría
 /// Maneja la ingestión de eventos desde aplicaciones cliente (ARPs)
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct AuditControlServiceImpl {
     // Configuración interna
     config: Arc<ServiceConfig>,
     // Contador de eventos para métricas básicas
     event_counter: Arc<std::sync::atomic::AtomicU64>,
+    // Backend donde se persisten los eventos ingeridos por stream
+    storage: Option<Arc<dyn StorageBackend>>,
+    // Copia en bruto de los eventos recibidos por cualquier vía de ingestión
+    raw_storage: Option<Arc<dyn StorageBackend>>,
+    // Feed donde se publican los eventos aceptados (StreamEvents)
+    event_feed: Option<Arc<EventFeed>>,
+    // Notificaciones inmediatas para eventos que cumplen alguna regla
+    notifier: Option<Arc<WebhookNotifier>>,
+    // Validación del metadata contra el esquema registrado de su event_source
+    schema_validator: Option<Arc<SchemaValidator>>,
+    // Métricas donde se contabilizan los eventos rechazados por tamaño
+    metrics: Option<Arc<tokio::sync::RwLock<AuditMetrics>>>,
+    // Presión del storage; a partir de `High` se rechaza la ingestión
+    backpressure: Option<Arc<BackpressureController>>,
+    // Eventos de streams aceptados y aún sin persistir, la cola que mide
+    // el controlador de backpressure
+    pending_events: Arc<std::sync::atomic::AtomicUsize>,
+    // Storage por niveles cuya migración se puede forzar bajo demanda
+    tiered_storage: Option<Arc<TieredStorage>>,
+    // Migración bajo demanda en curso y fin de la última
+    migration: Arc<Mutex<MigrationState>>,
+    // Meta-auditoría de las migraciones forzadas
+    meta_audit: Option<MetaAuditLogger>,
+    // Identidades mTLS (CN o SAN) autorizadas para las acciones administrativas
+    admin_identities: Arc<HashSet<String>>,
+    // Enriquecimiento de los eventos ingeridos por stream
+    enricher: Option<Arc<EventEnricher>>,
+    // Spans por etapa de la ingestión por stream
+    tracer: Option<Tracer>,
+    // Destino de los eventos rechazados por esquema o que no se pudieron persistir
+    dead_letters: Option<Arc<DeadLetterStore>>,
+    // Normalización de campos tras el enriquecimiento y antes del storage
+    normalizer: Option<Arc<EventNormalizer>>,
+}
+
+/// Etapa en curso de la ingestión: su span y el instante de inicio
+struct StageSpan {
+    stage: IngestStage,
+    started: Instant,
+    span: Option<Span>,
+}
+
+/// Ejecutar `future` en el contexto de traza de la petición, si lo tiene
+async fn in_request_trace<F: std::future::Future>(
+    trace: Option<TraceState>,
+    future: F,
+) -> F::Output {
+    match trace {
+        Some(trace) => trace.scope(future).await,
+        None => future.await,
+    }
+}
+
+/// Eventos de un stream aceptados en su batcher y aún sin persistir
+///
+/// Se suman a los del servicio mientras existe, de modo que un stream que
+/// termina con error no deja pendientes eventos que ya no se van a persistir.
+struct PendingEvents<'a> {
+    service: &'a AuditControlServiceImpl,
+    count: usize,
+}
+
+impl<'a> PendingEvents<'a> {
+    fn new(service: &'a AuditControlServiceImpl) -> Self {
+        Self { service, count: 0 }
+    }
+
+    fn add(&mut self, count: usize) {
+        self.count += count;
+        let pending = self
+            .service
+            .pending_events
+            .fetch_add(count, std::sync::atomic::Ordering::SeqCst)
+            + count;
+        self.service.report_pending(pending);
+    }
+
+    fn remove(&mut self, count: usize) {
+        let count = count.min(self.count);
+        self.count -= count;
+        let pending = self
+            .service
+            .pending_events
+            .fetch_sub(count, std::sync::atomic::Ordering::SeqCst)
+            - count;
+        self.service.report_pending(pending);
+    }
+}
+
+impl Drop for PendingEvents<'_> {
+    fn drop(&mut self) {
+        self.remove(self.count);
+    }
+}
+
+/// Estado de las migraciones de ciclo de vida bajo demanda
+#[derive(Debug, Default)]
+struct MigrationState {
+    running: bool,
+    last_completed: Option<Instant>,
+}
+
+/// Libera la migración en curso al terminar, aunque falle o se cancele
+struct MigrationGuard(Arc<Mutex<MigrationState>>);
+
+impl Drop for MigrationGuard {
+    fn drop(&mut self) {
+        let mut state = self.0.lock().unwrap();
+        state.running = false;
+        state.last_completed = Some(Instant::now());
+    }
 }
 
 /// Configuración del servicio
@@ -24,6 +155,16 @@ pub struct AuditControlServiceImpl {
 struct ServiceConfig {
     max_batch_size: usize,
     enable_metrics: bool,
+    // Tiempo máximo que un evento ingerido por stream espera en el batcher
+    ingest_flush_interval: Duration,
+    // Máximo de mensajes de error devueltos en un IngestSummary
+    max_ingest_errors: usize,
+    // Tamaño máximo codificado de un evento
+    max_event_bytes: usize,
+    // Espera sugerida a los clientes con presión `High`, que crece con el nivel
+    backpressure_retry_after: Duration,
+    // Intervalo mínimo entre el fin de una migración forzada y la siguiente
+    min_migration_interval: Duration,
 }
 
 impl Default for ServiceConfig {
@@ -31,10 +172,40 @@ impl Default for ServiceConfig {
         Self {
             max_batch_size: 1000,
             enable_metrics: true,
+            ingest_flush_interval: Duration::from_millis(100),
+            max_ingest_errors: 100,
+            max_event_bytes: DEFAULT_MAX_EVENT_BYTES,
+            backpressure_retry_after: Duration::from_secs(1),
+            min_migration_interval: Duration::from_secs(300),
         }
     }
 }
 
+impl std::fmt::Debug for AuditControlServiceImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditControlServiceImpl")
+            .field("config", &self.config)
+            .field("event_counter", &self.event_counter)
+            .field("storage", &self.storage.is_some())
+            .field("raw_storage", &self.raw_storage.is_some())
+            .field("event_feed", &self.event_feed.is_some())
+            .field("notifier", &self.notifier.is_some())
+            .field("schema_validator", &self.schema_validator)
+            .field("metrics", &self.metrics.is_some())
+            .field("backpressure", &self.backpressure)
+            .field("pending_events", &self.pending_events)
+            .field("tiered_storage", &self.tiered_storage.is_some())
+            .field("migration", &self.migration)
+            .field("meta_audit", &self.meta_audit.is_some())
+            .field("admin_identities", &self.admin_identities)
+            .field("enricher", &self.enricher.is_some())
+            .field("tracer", &self.tracer)
+            .field("dead_letters", &self.dead_letters.is_some())
+            .field("normalizer", &self.normalizer)
+            .finish()
+    }
+}
+
 impl AuditControlServiceImpl {
     /// Crear nueva instancia del servicio
     pub fn new() -> Self {
@@ -43,19 +214,594 @@ impl AuditControlServiceImpl {
         Self {
             config: Arc::new(ServiceConfig::default()),
             event_counter: Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            storage: None,
+            raw_storage: None,
+            event_feed: None,
+            notifier: None,
+            schema_validator: None,
+            metrics: None,
+            backpressure: None,
+            pending_events: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            tiered_storage: None,
+            migration: Arc::new(Mutex::new(MigrationState::default())),
+            meta_audit: None,
+            admin_identities: Arc::new(HashSet::new()),
+            enricher: None,
+            tracer: None,
+            dead_letters: None,
+            normalizer: None,
+        }
+    }
+
+    /// Persistir los eventos ingeridos en el backend indicado
+    pub fn with_storage(mut self, storage: Arc<dyn StorageBackend>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Guardar en `raw_storage` la copia en bruto de cada evento recibido,
+    /// antes del enriquecimiento, en todas las vías de ingestión
+    pub fn with_raw_storage(mut self, raw_storage: Arc<dyn StorageBackend>) -> Self {
+        self.raw_storage = Some(raw_storage);
+        self
+    }
+
+    /// Publicar los eventos aceptados en el feed indicado
+    pub fn with_event_feed(mut self, event_feed: Arc<EventFeed>) -> Self {
+        self.event_feed = Some(event_feed);
+        self
+    }
+
+    /// Notificar por webhook los eventos aceptados que cumplan alguna regla
+    pub fn with_notifier(mut self, notifier: Arc<WebhookNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Validar los eventos ingeridos contra los esquemas registrados
+    pub fn with_schema_validator(mut self, validator: Arc<SchemaValidator>) -> Self {
+        self.schema_validator = Some(validator);
+        self
+    }
+
+    /// Rechazar los eventos cuyo tamaño codificado supere `max_event_bytes`
+    pub fn with_max_event_bytes(mut self, max_event_bytes: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_event_bytes = max_event_bytes;
+        self
+    }
+
+    /// Contabilizar en `metrics` los eventos rechazados por tamaño
+    pub fn with_metrics(mut self, metrics: Arc<tokio::sync::RwLock<AuditMetrics>>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Rechazar la ingestión con `RESOURCE_EXHAUSTED` mientras la presión
+    /// del controlador sea `High` o mayor
+    ///
+    /// El servicio publica como tamaño de cola del controlador los eventos
+    /// ingeridos por stream que aún no se han persistido.
+    pub fn with_backpressure(mut self, controller: Arc<BackpressureController>) -> Self {
+        self.backpressure = Some(controller);
+        self
+    }
+
+    /// Espera sugerida con presión `High`; `Critical` y `Overloaded` piden
+    /// dos y tres veces más
+    pub fn with_backpressure_retry_after(mut self, retry_after: Duration) -> Self {
+        Arc::make_mut(&mut self.config).backpressure_retry_after = retry_after;
+        self
+    }
+
+    /// Permitir forzar la migración de ciclo de vida de `storage` con
+    /// `TriggerLifecycleMigration`
+    pub fn with_tiered_storage(mut self, storage: Arc<TieredStorage>) -> Self {
+        self.tiered_storage = Some(storage);
+        self
+    }
+
+    /// Autorizar las acciones administrativas a los clientes mTLS cuyo
+    /// certificado tenga alguno de estos nombres (CN o SAN); sin ninguno se
+    /// deniegan todas
+    pub fn with_admin_identities(
+        mut self,
+        identities: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.admin_identities = Arc::new(identities.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Intervalo mínimo entre migraciones forzadas (5 minutos por defecto)
+    pub fn with_min_migration_interval(mut self, interval: Duration) -> Self {
+        Arc::make_mut(&mut self.config).min_migration_interval = interval;
+        self
+    }
+
+    /// Registrar cada migración forzada como evento de meta-auditoría
+    pub fn with_meta_audit(mut self, logger: MetaAuditLogger) -> Self {
+        self.meta_audit = Some(logger);
+        self
+    }
+
+    /// Enriquecer los eventos ingeridos por stream antes de encolarlos
+    pub fn with_enricher(mut self, enricher: Arc<EventEnricher>) -> Self {
+        self.enricher = Some(enricher);
+        self
+    }
+
+    /// Trazar cada etapa de la ingestión por stream (validate, enrich,
+    /// batch, flush, store) como span hijo del de la petición
+    pub fn with_tracer(mut self, tracer: Tracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    /// Guardar en el dead-letter store los eventos de la ingestión por
+    /// stream rechazados por esquema, que no se pudieron enriquecer o que no
+    /// se pudieron persistir
+    pub fn with_dead_letters(mut self, dead_letters: Arc<DeadLetterStore>) -> Self {
+        self.dead_letters = Some(dead_letters);
+        self
+    }
+
+    /// Normalizar los campos configurados de los eventos ingeridos por
+    /// stream antes de persistirlos
+    pub fn with_normalizer(mut self, normalizer: Arc<EventNormalizer>) -> Self {
+        self.normalizer = Some(normalizer);
+        self
+    }
+
+    /// Guardar eventos fallidos en el dead-letter store, si está configurado
+    ///
+    /// Un fallo del propio store se registra pero no interrumpe la ingestión.
+    async fn dead_letter(&self, events: &[AuditEvent], stage: DeadLetterStage, reason: &str) {
+        if let Some(dead_letters) = &self.dead_letters
+            && let Err(e) = dead_letters.record_batch(events, stage, reason).await
+        {
+            warn!(error = %e, stage = stage.as_str(), "Failed to dead-letter events");
+        }
+    }
+
+    /// Abrir una etapa de la ingestión; su span es hijo de `parent`
+    fn start_stage(&self, stage: IngestStage, parent: Option<&Span>) -> StageSpan {
+        let span = self.tracer.as_ref().map(|tracer| match parent {
+            Some(parent) => tracer.start_child_span(stage.as_str(), parent),
+            None => tracer.start_span(stage.as_str(), SpanKind::Internal, None),
+        });
+        StageSpan {
+            stage,
+            started: Instant::now(),
+            span,
+        }
+    }
+
+    /// Enriquecer un evento dentro de la etapa `enrich`; si falla, el
+    /// evento se queda como se recibió
+    async fn enrich_stage(
+        &self,
+        event: &mut AuditEvent,
+        parent: Option<&Span>,
+    ) -> Result<(), String> {
+        let Some(enricher) = &self.enricher else {
+            return Ok(());
+        };
+        let enrich = self.start_stage(IngestStage::Enrich, parent);
+        match enricher.enrich(event.clone()).await {
+            Ok(enriched) => {
+                *event = enriched;
+                self.end_stage(enrich, None).await;
+                Ok(())
+            }
+            Err(e) => {
+                let error = format!("enrichment failed: {}", e);
+                self.end_stage(enrich, Some(&error)).await;
+                Err(error)
+            }
+        }
+    }
+
+    /// Cerrar una etapa, exportando su span y su latencia a las métricas
+    async fn end_stage(&self, stage: StageSpan, error: Option<&str>) {
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .write()
+                .await
+                .record_stage_latency(stage.stage.as_str(), stage.started.elapsed());
+        }
+        if let (Some(tracer), Some(span)) = (&self.tracer, stage.span) {
+            let span = match error {
+                Some(message) => span.with_status_error("ERROR", message),
+                None => span.with_status_ok(),
+            };
+            tracer.end_span(span);
+        }
+    }
+
+    /// Identidad del cliente mTLS autorizado para una acción administrativa
+    ///
+    /// Las cabeceras del cliente no se consideran: solo cuenta el certificado
+    /// verificado, cuyo nombre autorizado se devuelve como actor.
+    fn authorize_admin<T>(&self, request: &Request<T>) -> Result<String, Status> {
+        let Some(identity) = request.extensions().get::<ClientIdentity>() else {
+            return Err(Status::unauthenticated(
+                "admin actions require a client certificate",
+            ));
+        };
+        match identity
+            .names()
+            .find(|name| self.admin_identities.contains(*name))
+        {
+            Some(name) => Ok(name.to_string()),
+            None => {
+                warn!(
+                    "Admin action denied to cn={:?}, san={:?}",
+                    identity.common_name, identity.subject_alt_names
+                );
+                Err(Status::permission_denied(
+                    "client certificate is not authorized for admin actions",
+                ))
+            }
+        }
+    }
+
+    /// Reservar la ejecución de una migración forzada
+    ///
+    /// Con otra migración en curso se devuelve `ABORTED`; antes de cumplirse
+    /// el intervalo mínimo, `RESOURCE_EXHAUSTED` con `retry-after`.
+    fn begin_migration(&self) -> Result<MigrationGuard, Status> {
+        let mut state = self.migration.lock().unwrap();
+        if state.running {
+            return Err(Status::aborted("a lifecycle migration is already running"));
+        }
+        if let Some(last) = state.last_completed {
+            let remaining = self
+                .config
+                .min_migration_interval
+                .saturating_sub(last.elapsed());
+            if !remaining.is_zero() {
+                let retry_after = remaining.as_secs().max(1);
+                let mut status = Status::resource_exhausted(format!(
+                    "lifecycle migration ran recently, retry after {}s",
+                    retry_after
+                ));
+                status
+                    .metadata_mut()
+                    .insert("retry-after", MetadataValue::from(retry_after));
+                return Err(status);
+            }
+        }
+        state.running = true;
+        Ok(MigrationGuard(self.migration.clone()))
+    }
+
+    /// Publicar los eventos pendientes de persistir en el controlador
+    fn report_pending(&self, pending: usize) {
+        if let Some(controller) = &self.backpressure {
+            controller.update_queue_size(pending);
         }
     }
 
+    /// Comprobar la presión del storage antes de aceptar eventos
+    ///
+    /// El error lleva en el metadata `retry-after` los segundos que el
+    /// cliente debería esperar antes de reintentar.
+    fn check_backpressure(&self) -> Result<(), Status> {
+        let Some(controller) = &self.backpressure else {
+            return Ok(());
+        };
+        let pressure = controller.get_current_pressure();
+        if pressure.as_u8() < PressureLevel::High.as_u8() {
+            return Ok(());
+        }
+
+        let factor = u32::from(pressure.as_u8() - PressureLevel::Moderate.as_u8());
+        let retry_after = (self.config.backpressure_retry_after * factor)
+            .as_secs()
+            .max(1);
+        warn!(
+            pressure = ?pressure,
+            retry_after_secs = retry_after,
+            "Rejecting ingestion under backpressure"
+        );
+
+        let mut status = Status::resource_exhausted(format!(
+            "ingestion paused under {:?} pressure ({}), retry after {}s",
+            pressure,
+            pressure.description(),
+            retry_after
+        ));
+        status
+            .metadata_mut()
+            .insert("retry-after", MetadataValue::from(retry_after));
+        Err(status)
+    }
+
+    /// Comprobar el tamaño de un evento, contabilizando el rechazo
+    async fn check_size(
+        &self,
+        index: u64,
+        tenant_id: &str,
+        event: &AuditEvent,
+    ) -> Result<(), String> {
+        let result = check_event_size(index, event, self.config.max_event_bytes);
+        if let Err(error) = &result {
+            warn!(
+                tenant_id = tenant_id,
+                error = error,
+                "Rejected oversized event"
+            );
+            if let Some(metrics) = &self.metrics {
+                metrics.write().await.record_oversized_event(tenant_id);
+            }
+        }
+        result
+    }
+
+    /// Guardar la copia en bruto de los eventos, si hay raw storage
+    async fn store_raw(&self, tenant_id: &str, events: &[AuditEvent]) -> Result<(), String> {
+        match &self.raw_storage {
+            Some(raw_storage) => store_raw_copies(raw_storage.as_ref(), tenant_id, events)
+                .await
+                .map_err(|e| format!("failed to store raw copy: {}", e)),
+            None => Ok(()),
+        }
+    }
+
+    /// Ingerir un evento de meta-auditoría por el pipeline normal: copia en
+    /// bruto, validación, esquema, enriquecimiento y storage
+    ///
+    /// Si falla, el evento se guarda en el dead-letter store para no perder
+    /// la acción administrativa.
+    pub async fn ingest_meta_audit_event(&self, event: AuditEvent) {
+        let result = match self.store_raw("", std::slice::from_ref(&event)).await {
+            Ok(()) => self
+                .resubmit(event.clone())
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        if let Err(error) = result {
+            warn!(
+                error = error,
+                action = event.action,
+                "Meta-audit event could not be ingested"
+            );
+            self.dead_letter(
+                std::slice::from_ref(&event),
+                DeadLetterStage::Storage,
+                &error,
+            )
+            .await;
+        }
+    }
+
+    /// Comprobar el esquema de un evento, si hay validador configurado
+    ///
+    /// En modo `Flag` el evento se anota y se acepta.
+    fn check_schema(&self, index: u64, event: &mut AuditEvent) -> Result<(), String> {
+        match &self.schema_validator {
+            Some(validator) => validator
+                .check(event)
+                .map_err(|e| format!("event at index {}: {}", index, e)),
+            None => Ok(()),
+        }
+    }
+
+    /// Publicar eventos aceptados en el feed y el notificador, si están
+    /// configurados
+    ///
+    /// Los eventos sin tenant propio heredan el `tenant_id` de la petición.
+    fn publish_accepted(&self, tenant_id: &str, events: impl IntoIterator<Item = AuditEvent>) {
+        if self.event_feed.is_none() && self.notifier.is_none() {
+            return;
+        }
+        for mut event in events {
+            if event.tenant_id.as_ref().is_none_or(|t| t.value.is_empty()) {
+                event.tenant_id = Some(TenantId {
+                    value: tenant_id.to_string(),
+                });
+            }
+            if let Some(notifier) = &self.notifier {
+                notifier.dispatch(event.clone());
+            }
+            if let Some(feed) = &self.event_feed {
+                feed.publish(event);
+            }
+        }
+    }
+
+    /// Enriquecer, normalizar y guardar los eventos de una publicación
+    /// unaria, publicando en el feed los que se guardan
+    ///
+    /// Los eventos sin tenant propio heredan el `tenant_id` de la petición.
+    /// Los que fallan se guardan en el dead-letter store y se devuelven con
+    /// su error, junto con la etapa en la que fallaron.
+    async fn persist_published(
+        &self,
+        tenant_id: &str,
+        events: Vec<AuditEvent>,
+    ) -> Vec<(AuditEvent, DeadLetterStage, String)> {
+        let mut failed = Vec::new();
+        let mut enriched = Vec::with_capacity(events.len());
+        for mut event in events {
+            if event.tenant_id.as_ref().is_none_or(|t| t.value.is_empty()) {
+                event.tenant_id = Some(TenantId {
+                    value: tenant_id.to_string(),
+                });
+            }
+            if let Err(error) = self.enrich_stage(&mut event, None).await {
+                self.dead_letter(
+                    std::slice::from_ref(&event),
+                    DeadLetterStage::Enrichment,
+                    &error,
+                )
+                .await;
+                failed.push((event, DeadLetterStage::Enrichment, error));
+                continue;
+            }
+            if let Some(normalizer) = &self.normalizer {
+                normalizer.normalize(&mut event);
+            }
+            enriched.push(event);
+        }
+        if enriched.is_empty() {
+            return failed;
+        }
+
+        let stored = match &self.storage {
+            Some(storage) => storage.store_batch(&enriched).await,
+            None => Ok(()),
+        };
+        match stored {
+            Ok(()) => {
+                self.event_counter
+                    .fetch_add(enriched.len() as u64, std::sync::atomic::Ordering::SeqCst);
+                self.publish_accepted(tenant_id, enriched);
+            }
+            Err(e) => {
+                let error = format!("failed to store event: {}", e);
+                warn!(
+                    batch_size = enriched.len(),
+                    error = error,
+                    "Failed to store published events"
+                );
+                self.dead_letter(&enriched, DeadLetterStage::Storage, &error)
+                    .await;
+                failed.extend(
+                    enriched
+                        .into_iter()
+                        .map(|event| (event, DeadLetterStage::Storage, error.clone())),
+                );
+            }
+        }
+        failed
+    }
+
     /// Registrar evento (para testing)
     pub fn get_event_count(&self) -> u64 {
         self.event_counter.load(std::sync::atomic::Ordering::SeqCst)
     }
+
+    /// Crear el batcher de un stream de ingestión
+    fn ingest_batcher(&self) -> SmartBatcher<AuditEvent> {
+        SmartBatcher::new(BatcherConfig {
+            max_queue_size: self.config.max_batch_size,
+            policy: BatchingPolicy::Hybrid {
+                max_time: self.config.ingest_flush_interval,
+                max_size: self.config.max_batch_size,
+            },
+            adaptive_tuning: false,
+            ..Default::default()
+        })
+    }
+
+    /// Vaciar el batcher hacia el storage y actualizar el resumen
+    async fn flush_ingest_batch(
+        &self,
+        batcher: &SmartBatcher<AuditEvent>,
+        summary: &mut IngestSummary,
+        pending: &mut PendingEvents<'_>,
+        ingest_span: Option<&Span>,
+    ) -> Result<(), Status> {
+        let flush = self.start_stage(IngestStage::Flush, ingest_span);
+        let result = batcher
+            .flush()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        if result.batch.is_empty() {
+            return Ok(());
+        }
+
+        let count = result.batch.len() as u64;
+        let stored = match &self.storage {
+            Some(storage) => {
+                let store = self.start_stage(IngestStage::Store, flush.span.as_ref());
+                let stored = storage.store_batch(&result.batch).await;
+                let error = stored.as_ref().err().map(|e| e.to_string());
+                self.end_stage(store, error.as_deref()).await;
+                stored
+            }
+            None => Ok(()),
+        };
+        self.end_stage(flush, None).await;
+        pending.remove(result.batch.len());
+
+        match stored {
+            Ok(()) => {
+                summary.accepted += count;
+                self.event_counter
+                    .fetch_add(count, std::sync::atomic::Ordering::SeqCst);
+                self.publish_accepted("", result.batch);
+            }
+            Err(e) => {
+                warn!(batch_size = count, error = %e, "Failed to store ingested batch");
+                self.dead_letter(&result.batch, DeadLetterStage::Storage, &e.to_string())
+                    .await;
+                self.record_ingest_rejection(
+                    summary,
+                    count,
+                    format!("failed to store batch of {} events: {}", count, e),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Contabilizar eventos rechazados, limitando los mensajes de error
+    fn record_ingest_rejection(&self, summary: &mut IngestSummary, count: u64, error: String) {
+        summary.rejected += count;
+        if summary.errors.len() < self.config.max_ingest_errors {
+            summary.errors.push(error);
+        }
+    }
 }
 
-#[tonic::async_trait]
-impl AuditControlService for AuditControlServiceImpl {
+/// Reintroducir eventos del dead-letter store: pasan de nuevo por la
+/// validación, el esquema, el enriquecimiento y el storage
+#[async_trait::async_trait]
+impl ReplaySink for AuditControlServiceImpl {
+    async fn resubmit(&self, mut event: AuditEvent) -> Result<(), anyhow::Error> {
+        validate_stream_event(0, &event).map_err(anyhow::Error::msg)?;
+        self.check_schema(0, &mut event)
+            .map_err(anyhow::Error::msg)?;
+        if let Some(enricher) = &self.enricher {
+            event = enricher.enrich(event).await.map_err(anyhow::Error::msg)?;
+        }
+        if let Some(normalizer) = &self.normalizer {
+            normalizer.normalize(&mut event);
+        }
+        if let Some(storage) = &self.storage {
+            storage.store_event(&event).await?;
+        }
+        self.event_counter
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        self.publish_accepted("", [event]);
+        Ok(())
+    }
+}
+
+/// Validar un evento recibido por stream
+fn validate_stream_event(index: u64, event: &AuditEvent) -> Result<(), String> {
+    if event.event_id.as_ref().is_none_or(|id| id.value.is_empty()) {
+        return Err(format!("event at index {} missing event_id", index));
+    }
+    if event
+        .tenant_id
+        .as_ref()
+        .is_none_or(|id| id.value.is_empty())
+    {
+        return Err(format!("event at index {} missing tenant_id", index));
+    }
+    Ok(())
+}
+
+// Las RPCs de ingestión se ejecutan dentro del contexto de traza que el
+// interceptor de tenant deja en la petición, de modo que los spans y las
+// tareas de cada etapa heredan su baggage
+impl AuditControlServiceImpl {
     /// Publicar un único evento de auditoría
-    async fn publish_event(
+    async fn handle_publish_event(
         &self,
         request: Request<PublishEventRequest>,
     ) -> Result<Response<PublishEventResponse>, Status> {
@@ -64,7 +810,7 @@ impl AuditControlService for AuditControlServiceImpl {
         let event = req.event.clone();
 
         // event es Option<AuditEvent>, extraer el valor
-        let event = if let Some(e) = event {
+        let mut event = if let Some(e) = event {
             e
         } else {
             return Err(Status::invalid_argument("event is required"));
@@ -82,24 +828,32 @@ impl AuditControlService for AuditControlServiceImpl {
             return Err(Status::invalid_argument("tenant_id is required"));
         }
 
+        self.check_backpressure()?;
+
         if event_id.is_empty() {
             return Err(Status::invalid_argument("event_id is required"));
         }
 
-        // TODO: Implementar lógica de persistencia
-        // - Validar evento
-        // - Enriquecer evento
-        // - Enviar a Vector
-        // - Confirmar recepción
+        self.check_size(0, &tenant_id, &event)
+            .await
+            .map_err(Status::invalid_argument)?;
+        self.check_schema(0, &mut event)
+            .map_err(Status::invalid_argument)?;
+        self.store_raw(&tenant_id, std::slice::from_ref(&event))
+            .await
+            .map_err(Status::unavailable)?;
 
-        // Simular procesamiento exitoso
+        // El recibo solo se emite una vez guardado el evento
+        if let Some((_, stage, error)) = self.persist_published(&tenant_id, vec![event]).await.pop()
+        {
+            return Err(match stage {
+                DeadLetterStage::Storage => Status::unavailable(error),
+                _ => Status::invalid_argument(error),
+            });
+        }
         let receipt_id = format!("receipt_{}", Uuid::new_v4());
         let receipt_time = prost_types::Timestamp::from(std::time::SystemTime::now());
 
-        // Incrementar contador
-        self.event_counter
-            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-
         info!(
             tenant_id = tenant_id,
             event_id = event_id,
@@ -116,13 +870,13 @@ impl AuditControlService for AuditControlServiceImpl {
     }
 
     /// Publicar un lote de eventos de auditoría (recomendado para performance)
-    async fn publish_batch(
+    async fn handle_publish_batch(
         &self,
         request: Request<PublishBatchRequest>,
     ) -> Result<Response<PublishBatchResponse>, Status> {
         let req = request.into_inner();
         let tenant_id = req.tenant_id.clone();
-        let events = req.events.clone();
+        let mut events = req.events.clone();
         let batch_size = events.len();
 
         info!(
@@ -140,6 +894,8 @@ impl AuditControlService for AuditControlServiceImpl {
             return Err(Status::invalid_argument("events cannot be empty"));
         }
 
+        self.check_backpressure()?;
+
         if batch_size > self.config.max_batch_size {
             return Err(Status::invalid_argument(format!(
                 "batch size {} exceeds maximum {}",
@@ -157,38 +913,200 @@ impl AuditControlService for AuditControlServiceImpl {
                     i
                 )));
             }
+            self.check_size(i as u64, &tenant_id, event)
+                .await
+                .map_err(Status::invalid_argument)?;
+        }
+        for (i, event) in events.iter_mut().enumerate() {
+            self.check_schema(i as u64, event)
+                .map_err(Status::invalid_argument)?;
         }
+        self.store_raw(&tenant_id, &events)
+            .await
+            .map_err(Status::unavailable)?;
 
-        // TODO: Implementar lógica de batch
-        // - Procesar en paralelo
-        // - Enviar a Vector con compresión
-        // - Manejar errores individuales
-        // - Confirmar recepción por lote
+        // Los eventos que no se guardan quedan en el dead-letter store y se
+        // informan en `failed_events`
+        let failed_events: Vec<String> = self
+            .persist_published(&tenant_id, events)
+            .await
+            .into_iter()
+            .map(|(event, _, _)| event.event_id.unwrap_or_default().value)
+            .collect();
 
         let batch_id = format!("batch_{}", Uuid::new_v4());
         let receipt_time = prost_types::Timestamp::from(std::time::SystemTime::now());
 
-        // Incrementar contador
-        self.event_counter
-            .fetch_add(batch_size as u64, std::sync::atomic::Ordering::SeqCst);
-
         info!(
             tenant_id = tenant_id,
             batch_id = batch_id,
             batch_size = batch_size,
-            "Batch published successfully"
+            failed_count = failed_events.len(),
+            "Batch published"
         );
 
         let response = PublishBatchResponse {
             received_count: batch_size as i32,
             batch_id,
             receipt_time: Some(receipt_time),
-            failed_events: vec![], // TODO: Implementar tracking de errores
+            failed_events,
         };
 
         Ok(Response::new(response))
     }
 
+    /// Ingestar un stream de eventos (client-streaming)
+    ///
+    /// Los eventos alimentan un `SmartBatcher` y se persisten por lotes.
+    /// Mientras se persiste un lote no se lee del stream, así que el control
+    /// de flujo de HTTP/2 frena a un productor demasiado rápido. La presión
+    /// se comprueba en cada mensaje: si llega a `High` se persiste lo ya
+    /// aceptado y el stream termina con `RESOURCE_EXHAUSTED`, indicando en
+    /// el metadata `ingest-accepted` cuántos eventos se persistieron.
+    async fn handle_ingest_event_stream(
+        &self,
+        request: Request<Streaming<AuditEvent>>,
+    ) -> Result<Response<IngestSummary>, Status> {
+        // Las etapas cuelgan del span de la petición, que continúa la
+        // traza del cliente si el interceptor la extrajo
+        let request_trace = request.extensions().get::<TraceState>().cloned();
+        let ingest_span = self.tracer.as_ref().map(|tracer| {
+            tracer.start_span(
+                "ingest",
+                SpanKind::Server,
+                request_trace.map(|trace| trace.new_child()),
+            )
+        });
+        let mut stream = request.into_inner();
+        let batcher = self.ingest_batcher();
+        let mut summary = IngestSummary::default();
+        let mut pending = PendingEvents::new(self);
+        let mut index: u64 = 0;
+
+        info!("Received IngestEventStream request");
+        self.check_backpressure()?;
+
+        while let Some(mut event) = stream.message().await? {
+            if let Err(mut status) = self.check_backpressure() {
+                self.flush_ingest_batch(&batcher, &mut summary, &mut pending, ingest_span.as_ref())
+                    .await?;
+                status
+                    .metadata_mut()
+                    .insert("ingest-accepted", MetadataValue::from(summary.accepted));
+                return Err(status);
+            }
+
+            let tenant_id = event.tenant_id.clone().unwrap_or_default().value;
+            let validate = self.start_stage(IngestStage::Validate, ingest_span.as_ref());
+            let validation = match validate_stream_event(index, &event) {
+                Ok(()) => self.check_size(index, &tenant_id, &event).await,
+                Err(e) => Err(e),
+            };
+            let schema = match validation {
+                Ok(()) => self.check_schema(index, &mut event),
+                Err(_) => Ok(()),
+            };
+            let error = validation.as_ref().err().or(schema.as_ref().err());
+            self.end_stage(validate, error.map(String::as_str)).await;
+            if let Err(error) = schema {
+                self.dead_letter(
+                    std::slice::from_ref(&event),
+                    DeadLetterStage::Schema,
+                    &error,
+                )
+                .await;
+                self.record_ingest_rejection(&mut summary, 1, error);
+            } else if let Err(error) = validation {
+                self.record_ingest_rejection(&mut summary, 1, error);
+            } else if let Err(error) = self.store_raw("", std::slice::from_ref(&event)).await {
+                self.dead_letter(
+                    std::slice::from_ref(&event),
+                    DeadLetterStage::Storage,
+                    &error,
+                )
+                .await;
+                self.record_ingest_rejection(&mut summary, 1, error);
+            } else if let Err(error) = self.enrich_stage(&mut event, ingest_span.as_ref()).await {
+                self.dead_letter(
+                    std::slice::from_ref(&event),
+                    DeadLetterStage::Enrichment,
+                    &error,
+                )
+                .await;
+                self.record_ingest_rejection(&mut summary, 1, error);
+            } else {
+                if let Some(normalizer) = &self.normalizer {
+                    normalizer.normalize(&mut event);
+                }
+
+                let batch = self.start_stage(IngestStage::Batch, ingest_span.as_ref());
+                let added = batcher.add_event(event).await.map_err(|e| e.to_string());
+                self.end_stage(batch, added.as_ref().err().map(String::as_str))
+                    .await;
+                added.map_err(Status::internal)?;
+                pending.add(1);
+                if batcher.flush_due().await {
+                    self.flush_ingest_batch(
+                        &batcher,
+                        &mut summary,
+                        &mut pending,
+                        ingest_span.as_ref(),
+                    )
+                    .await?;
+                }
+            }
+            index += 1;
+        }
+
+        // El cliente cerró el stream: persistir lo pendiente
+        self.flush_ingest_batch(&batcher, &mut summary, &mut pending, ingest_span.as_ref())
+            .await?;
+        if let (Some(tracer), Some(span)) = (&self.tracer, ingest_span) {
+            tracer.end_span(
+                span.with_attribute(SpanAttribute::number("ingest.accepted", summary.accepted))
+                    .with_attribute(SpanAttribute::number("ingest.rejected", summary.rejected)),
+            );
+        }
+
+        info!(
+            accepted = summary.accepted,
+            rejected = summary.rejected,
+            "Event stream ingested"
+        );
+
+        Ok(Response::new(summary))
+    }
+}
+
+#[tonic::async_trait]
+impl AuditControlService for AuditControlServiceImpl {
+    /// Publicar un único evento de auditoría
+    async fn publish_event(
+        &self,
+        request: Request<PublishEventRequest>,
+    ) -> Result<Response<PublishEventResponse>, Status> {
+        let trace = request.extensions().get::<TraceState>().cloned();
+        in_request_trace(trace, self.handle_publish_event(request)).await
+    }
+
+    /// Publicar un lote de eventos de auditoría (recomendado para performance)
+    async fn publish_batch(
+        &self,
+        request: Request<PublishBatchRequest>,
+    ) -> Result<Response<PublishBatchResponse>, Status> {
+        let trace = request.extensions().get::<TraceState>().cloned();
+        in_request_trace(trace, self.handle_publish_batch(request)).await
+    }
+
+    /// Ingestar un stream de eventos (client-streaming)
+    async fn ingest_event_stream(
+        &self,
+        request: Request<Streaming<AuditEvent>>,
+    ) -> Result<Response<IngestSummary>, Status> {
+        let trace = request.extensions().get::<TraceState>().cloned();
+        in_request_trace(trace, self.handle_ingest_event_stream(request)).await
+    }
+
     /// Health check del servicio
     async fn health_check(
         &self,
@@ -219,4 +1137,857 @@ impl AuditControlService for AuditControlServiceImpl {
 
         Ok(Response::new(response))
     }
+
+    /// Forzar la migración de ciclo de vida entre niveles (admin)
+    ///
+    /// Solo se ejecuta una migración a la vez y, tras terminar, no se acepta
+    /// otra hasta cumplirse el intervalo mínimo.
+    async fn trigger_lifecycle_migration(
+        &self,
+        request: Request<TriggerLifecycleMigrationRequest>,
+    ) -> Result<Response<TriggerLifecycleMigrationResponse>, Status> {
+        let actor = self.authorize_admin(&request)?;
+        let reason = request.into_inner().reason;
+
+        info!(
+            actor = actor,
+            reason = reason,
+            "Received TriggerLifecycleMigration request"
+        );
+
+        let Some(storage) = &self.tiered_storage else {
+            return Err(Status::failed_precondition(
+                "tiered storage is not configured",
+            ));
+        };
+        let _guard = self.begin_migration()?;
+
+        let migration_id = format!("migration_{}", Uuid::new_v4());
+        let started_at = prost_types::Timestamp::from(std::time::SystemTime::now());
+        let result = storage.run_lifecycle_migration().await;
+
+        // La migración abarca todos los tenants: se audita en el del sistema
+        let report = match result {
+            Ok(report) => {
+                info!(
+                    migration_id = migration_id,
+                    events_moved = report.events_moved,
+                    "Lifecycle migration completed"
+                );
+                if let Some(logger) = &self.meta_audit {
+                    logger
+                        .log_with_details(
+                            AdminAction::TriggerLifecycleMigration,
+                            META_AUDIT_SYSTEM_TENANT,
+                            &actor,
+                            &migration_id,
+                            &[
+                                ("reason", &reason),
+                                ("events_moved", &report.events_moved.to_string()),
+                            ],
+                        )
+                        .await;
+                }
+                report
+            }
+            Err(e) => {
+                warn!(migration_id = migration_id, error = %e, "Lifecycle migration failed");
+                if let Some(logger) = &self.meta_audit {
+                    logger
+                        .log_failure(
+                            AdminAction::TriggerLifecycleMigration,
+                            META_AUDIT_SYSTEM_TENANT,
+                            &actor,
+                            &migration_id,
+                            &[("reason", &reason), ("error", &e.to_string())],
+                        )
+                        .await;
+                }
+                return Err(Status::internal(format!(
+                    "lifecycle migration failed: {}",
+                    e
+                )));
+            }
+        };
+
+        Ok(Response::new(TriggerLifecycleMigrationResponse {
+            migration_id,
+            events_moved: report.events_moved,
+            tiers_touched: report
+                .tiers_touched
+                .iter()
+                .map(|tier| tier.as_str().to_string())
+                .collect(),
+            started_at: Some(started_at),
+            completed_at: Some(prost_types::Timestamp::from(std::time::SystemTime::now())),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::performance::BackpressureConfig;
+    use crate::storage::InMemoryStorage;
+    use hodei_audit_proto::audit_control_service_client::AuditControlServiceClient;
+
+    fn event(i: usize) -> AuditEvent {
+        AuditEvent {
+            event_id: Some(EventId {
+                value: format!("event-{}", i),
+            }),
+            tenant_id: Some(TenantId {
+                value: "tenant-1".to_string(),
+            }),
+            action: "CreatePolicyStore".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ingest_event_stream_persists_events() {
+        let storage = Arc::new(InMemoryStorage::new());
+        let service = AuditControlServiceImpl::new().with_storage(storage.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(AuditControlServiceServer::new(service.clone()))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+
+        // Cada evento 1000 llega sin event_id y debe rechazarse
+        let events: Vec<AuditEvent> = (0..10_000)
+            .map(|i| {
+                let mut e = event(i);
+                if i % 1000 == 0 {
+                    e.event_id = None;
+                }
+                e
+            })
+            .collect();
+
+        let mut client = AuditControlServiceClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+        let summary = client
+            .ingest_event_stream(tokio_stream::iter(events))
+            .await
+            .unwrap()
+            .into_inner();
+        server.abort();
+
+        assert_eq!(summary.accepted, 9_990);
+        assert_eq!(summary.rejected, 10);
+        assert_eq!(summary.errors.len(), 10);
+        assert_eq!(storage.len(), 9_990);
+        assert_eq!(service.get_event_count(), 9_990);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_stages_are_traced_and_timed() {
+        use crate::distributed_tracing::{SamplingStrategy, SpanRecorder};
+
+        let recorder = Arc::new(Mutex::new(SpanRecorder::new()));
+        let metrics = crate::metrics::create_metrics();
+        let service = AuditControlServiceImpl::new()
+            .with_storage(Arc::new(InMemoryStorage::new()))
+            .with_enricher(Arc::new(EventEnricher::new()))
+            .with_metrics(metrics.clone())
+            .with_tracer(
+                Tracer::new("audit-control")
+                    .with_sampling_strategy(SamplingStrategy::Always)
+                    .with_recorder(recorder.clone()),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(AuditControlServiceServer::new(service))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+        let mut client = AuditControlServiceClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+        let summary = client
+            .ingest_event_stream(tokio_stream::iter(vec![event(0)]))
+            .await
+            .unwrap()
+            .into_inner();
+        server.abort();
+        assert_eq!(summary.accepted, 1);
+
+        let stage_counts = metrics.read().await;
+        for stage in ["validate", "enrich", "batch", "flush", "store"] {
+            assert_eq!(stage_counts.get_stage_latency_count(stage), 1, "{}", stage);
+        }
+
+        let recorder = recorder.lock().unwrap();
+        let span = |name: &str| {
+            let spans = recorder.find_by_name(name);
+            assert_eq!(spans.len(), 1, "one {} span", name);
+            spans[0].trace_state.clone()
+        };
+        let ingest = span("ingest");
+        let flush = span("flush");
+        for stage in ["validate", "enrich", "batch", "flush"] {
+            let trace = span(stage);
+            assert_eq!(trace.trace_id, ingest.trace_id);
+            assert_eq!(trace.parent_span_id.as_ref(), Some(&ingest.span_id));
+        }
+        // El store cuelga del flush que lo provoca
+        let store = span("store");
+        assert_eq!(store.trace_id, ingest.trace_id);
+        assert_eq!(store.parent_span_id.as_ref(), Some(&flush.span_id));
+    }
+
+    #[tokio::test]
+    async fn test_configured_fields_are_normalized_before_storage() {
+        use crate::normalization::Normalization;
+        use crate::storage::QueryFilter;
+
+        let storage = Arc::new(InMemoryStorage::new());
+        let normalizer = EventNormalizer::new()
+            .with_field("action", Normalization::all())
+            .unwrap();
+        let service = AuditControlServiceImpl::new()
+            .with_storage(storage.clone())
+            .with_normalizer(Arc::new(normalizer));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(AuditControlServiceServer::new(service))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+        let events: Vec<AuditEvent> = ["GET ", "get"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, action)| AuditEvent {
+                action: action.to_string(),
+                event_source: " Policy-Store ".to_string(),
+                ..event(i)
+            })
+            .collect();
+        let mut client = AuditControlServiceClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+        let summary = client
+            .ingest_event_stream(tokio_stream::iter(events))
+            .await
+            .unwrap()
+            .into_inner();
+        server.abort();
+        assert_eq!(summary.accepted, 2);
+
+        let stored = storage.query_events(&QueryFilter::default()).await.unwrap();
+        assert_eq!(stored.len(), 2);
+        for event in stored {
+            assert_eq!(event.action, "get");
+            // event_source no está configurado y se guarda tal cual
+            assert_eq!(event.event_source, " Policy-Store ");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_raw_copies_are_kept_on_every_ingest_path() {
+        use crate::normalization::Normalization;
+        use crate::storage::QueryFilter;
+
+        let storage = Arc::new(InMemoryStorage::new());
+        let raw_storage = Arc::new(InMemoryStorage::new());
+        let service = AuditControlServiceImpl::new()
+            .with_storage(storage.clone())
+            .with_raw_storage(raw_storage.clone())
+            .with_normalizer(Arc::new(
+                EventNormalizer::new()
+                    .with_field("action", Normalization::all())
+                    .unwrap(),
+            ));
+        let raw_event = |i| AuditEvent {
+            action: " Create Policy ".to_string(),
+            ..event(i)
+        };
+
+        service
+            .publish_event(publish_request(raw_event(0)))
+            .await
+            .unwrap();
+        service
+            .publish_batch(Request::new(PublishBatchRequest {
+                tenant_id: "tenant-1".to_string(),
+                events: vec![AuditEvent {
+                    tenant_id: None,
+                    ..raw_event(1)
+                }],
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(AuditControlServiceServer::new(service))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+        let mut client = AuditControlServiceClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+        client
+            .ingest_event_stream(tokio_stream::iter(vec![raw_event(2)]))
+            .await
+            .unwrap();
+        server.abort();
+
+        let raw = raw_storage
+            .query_events(&QueryFilter {
+                tenant_id: Some("tenant-1".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(raw.len(), 3);
+        // Las copias en bruto no pasan por la normalización
+        assert!(raw.iter().all(|e| e.action == " Create Policy "));
+        let stored = storage.query_events(&QueryFilter::default()).await.unwrap();
+        assert_eq!(stored.len(), 3);
+        assert!(stored.iter().all(|e| e.action == "create policy"));
+        // Cada copia en bruto tiene al lado su evento enriquecido
+        for raw_event in &raw {
+            let event_id = &raw_event.event_id.as_ref().unwrap().value;
+            assert!(
+                stored
+                    .iter()
+                    .any(|e| e.event_id.as_ref().is_some_and(|id| &id.value == event_id)),
+                "no stored event for raw copy {}",
+                event_id
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ingest_errors_are_capped() {
+        let service = AuditControlServiceImpl::new();
+        let mut summary = IngestSummary::default();
+
+        for i in 0..150 {
+            service.record_ingest_rejection(&mut summary, 1, format!("error {}", i));
+        }
+
+        assert_eq!(summary.rejected, 150);
+        assert_eq!(summary.errors.len(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_event_is_rejected() {
+        let metrics = crate::metrics::create_metrics();
+        let service = AuditControlServiceImpl::new()
+            .with_max_event_bytes(512)
+            .with_metrics(metrics.clone());
+
+        let mut oversized = event(0);
+        oversized.action = "x".repeat(1024);
+        let status = service
+            .publish_event(Request::new(PublishEventRequest {
+                tenant_id: "tenant-1".to_string(),
+                event: Some(oversized),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(status.message().contains("exceeding the 512 byte limit"));
+        assert_eq!(service.get_event_count(), 0);
+        assert_eq!(
+            metrics.read().await.get_oversized_event_count("tenant-1"),
+            1
+        );
+
+        service
+            .publish_event(Request::new(PublishEventRequest {
+                tenant_id: "tenant-1".to_string(),
+                event: Some(event(1)),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+        assert_eq!(service.get_event_count(), 1);
+    }
+
+    fn publish_request(event: AuditEvent) -> Request<PublishEventRequest> {
+        Request::new(PublishEventRequest {
+            tenant_id: "tenant-1".to_string(),
+            event: Some(event),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_publish_event_backs_off_under_pressure() {
+        let controller = Arc::new(BackpressureController::new(BackpressureConfig {
+            queue_size_warnings: (10, 50, 80),
+            ..Default::default()
+        }));
+        let service = AuditControlServiceImpl::new()
+            .with_backpressure(controller.clone())
+            .with_backpressure_retry_after(Duration::from_secs(3));
+
+        controller.update_queue_size(100);
+        assert_eq!(controller.get_current_pressure(), PressureLevel::Critical);
+        let status = service
+            .publish_event(publish_request(event(0)))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(status.metadata().get("retry-after").unwrap(), "6");
+        assert_eq!(service.get_event_count(), 0);
+
+        controller.update_queue_size(0);
+        service
+            .publish_event(publish_request(event(1)))
+            .await
+            .unwrap();
+        assert_eq!(service.get_event_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_event_stream_checks_pressure_per_message() {
+        // Two events waiting to be persisted already mean `High` pressure
+        let controller = Arc::new(BackpressureController::new(BackpressureConfig {
+            queue_size_warnings: (1, 2, 10),
+            ..Default::default()
+        }));
+        let storage = Arc::new(InMemoryStorage::new());
+        let mut service = AuditControlServiceImpl::new()
+            .with_storage(storage.clone())
+            .with_backpressure(controller.clone());
+        Arc::make_mut(&mut service.config).ingest_flush_interval = Duration::from_secs(60);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(AuditControlServiceServer::new(service.clone()))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+        let mut client = AuditControlServiceClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+
+        let status = client
+            .ingest_event_stream(tokio_stream::iter((0..5).map(event)))
+            .await
+            .unwrap_err();
+        server.abort();
+
+        // The events accepted before the pressure rose are persisted
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(status.metadata().get("ingest-accepted").unwrap(), "2");
+        assert_eq!(storage.len(), 2);
+        assert_eq!(controller.get_current_pressure(), PressureLevel::Normal);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_event_stream_backs_off_under_pressure() {
+        let controller = Arc::new(BackpressureController::new(BackpressureConfig {
+            queue_size_warnings: (10, 50, 80),
+            ..Default::default()
+        }));
+        controller.update_queue_size(60);
+        let service = AuditControlServiceImpl::new().with_backpressure(controller.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(AuditControlServiceServer::new(service.clone()))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+        let mut client = AuditControlServiceClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+
+        let status = client
+            .ingest_event_stream(tokio_stream::iter(vec![event(0)]))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(status.metadata().get("retry-after").unwrap(), "1");
+
+        controller.update_queue_size(0);
+        let summary = client
+            .ingest_event_stream(tokio_stream::iter(vec![event(1)]))
+            .await
+            .unwrap()
+            .into_inner();
+        server.abort();
+
+        assert_eq!(summary.accepted, 1);
+    }
+
+    /// Nivel caliente que falla mientras `down` está activo y, con `hold`,
+    /// bloquea su health check hasta recibir `release`
+    #[derive(Default)]
+    struct GatedHot {
+        down: std::sync::atomic::AtomicBool,
+        hold: std::sync::atomic::AtomicBool,
+        checking: tokio::sync::Notify,
+        release: tokio::sync::Notify,
+        inner: InMemoryStorage,
+    }
+
+    #[async_trait::async_trait]
+    impl StorageBackend for GatedHot {
+        async fn store_event(&self, event: &AuditEvent) -> Result<(), anyhow::Error> {
+            if self.down.load(std::sync::atomic::Ordering::SeqCst) {
+                anyhow::bail!("connection refused");
+            }
+            self.inner.store_event(event).await
+        }
+
+        async fn store_batch(&self, events: &[AuditEvent]) -> Result<(), anyhow::Error> {
+            if self.down.load(std::sync::atomic::Ordering::SeqCst) {
+                anyhow::bail!("connection refused");
+            }
+            self.inner.store_batch(events).await
+        }
+
+        async fn query_events(
+            &self,
+            filter: &crate::storage::QueryFilter,
+        ) -> Result<Vec<AuditEvent>, anyhow::Error> {
+            self.inner.query_events(filter).await
+        }
+
+        async fn count_events(
+            &self,
+            filter: &crate::storage::QueryFilter,
+        ) -> Result<u64, anyhow::Error> {
+            self.inner.count_events(filter).await
+        }
+
+        async fn health_check(&self) -> Result<bool, anyhow::Error> {
+            if self.hold.load(std::sync::atomic::Ordering::SeqCst) {
+                self.checking.notify_one();
+                self.release.notified().await;
+            }
+            Ok(!self.down.load(std::sync::atomic::Ordering::SeqCst))
+        }
+
+        fn get_stats(&self) -> crate::storage::StorageStats {
+            self.inner.get_stats()
+        }
+    }
+
+    /// Storage por niveles con tres eventos desviados al nivel templado
+    /// durante una caída del caliente, ya recuperado
+    async fn storage_with_spooled_events() -> (Arc<TieredStorage>, Arc<GatedHot>) {
+        let hot = Arc::new(GatedHot::default());
+        let storage = TieredStorage::from_backends(
+            hot.clone(),
+            Arc::new(InMemoryStorage::new()),
+            Arc::new(InMemoryStorage::new()),
+            crate::storage::LifecyclePolicy::default(),
+            crate::storage::PartitionStrategy::default(),
+        )
+        .with_hot_fallback();
+
+        hot.down.store(true, std::sync::atomic::Ordering::SeqCst);
+        for i in 0..3 {
+            let mut e = event(i);
+            e.event_time = Some(prost_types::Timestamp::from(std::time::SystemTime::now()));
+            storage.store_event(&e).await.unwrap();
+        }
+        hot.down.store(false, std::sync::atomic::Ordering::SeqCst);
+        (Arc::new(storage), hot)
+    }
+
+    #[tokio::test]
+    async fn test_failed_store_is_dead_lettered_and_replayed() {
+        use crate::dead_letter::DeadLetterFilter;
+
+        let storage = Arc::new(GatedHot::default());
+        let dead_letters = Arc::new(DeadLetterStore::new(Arc::new(InMemoryStorage::new())));
+        let service = AuditControlServiceImpl::new()
+            .with_storage(storage.clone())
+            .with_dead_letters(dead_letters.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(AuditControlServiceServer::new(service.clone()))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+        storage
+            .down
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        let mut client = AuditControlServiceClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+        let summary = client
+            .ingest_event_stream(tokio_stream::iter(vec![event(0)]))
+            .await
+            .unwrap()
+            .into_inner();
+        server.abort();
+        assert_eq!(summary.rejected, 1);
+
+        let letters = dead_letters
+            .list(&DeadLetterFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].event_id(), "event-0");
+        assert_eq!(letters[0].stage, DeadLetterStage::Storage);
+        assert_eq!(letters[0].reason, "connection refused");
+
+        // Mientras el storage siga caído la carta se conserva
+        let report = dead_letters
+            .replay(&DeadLetterFilter::default(), &service)
+            .await
+            .unwrap();
+        assert_eq!((report.replayed, report.failed), (0, 1));
+
+        storage
+            .down
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        let report = dead_letters
+            .replay(&DeadLetterFilter::default(), &service)
+            .await
+            .unwrap();
+        assert_eq!((report.replayed, report.failed), (1, 0));
+        assert_eq!(storage.inner.len(), 1);
+        assert_eq!(service.get_event_count(), 1);
+        assert_eq!(
+            dead_letters
+                .count(&DeadLetterFilter::default())
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_publish_is_not_acknowledged() {
+        use crate::dead_letter::DeadLetterFilter;
+
+        let storage = Arc::new(GatedHot::default());
+        let dead_letters = Arc::new(DeadLetterStore::new(Arc::new(InMemoryStorage::new())));
+        let service = AuditControlServiceImpl::new()
+            .with_storage(storage.clone())
+            .with_dead_letters(dead_letters.clone());
+        storage
+            .down
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+
+        let status = service
+            .publish_event(publish_request(event(0)))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unavailable);
+        let response = service
+            .publish_batch(Request::new(PublishBatchRequest {
+                tenant_id: "tenant-1".to_string(),
+                events: vec![event(1), event(2)],
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.failed_events, vec!["event-1", "event-2"]);
+        assert_eq!(service.get_event_count(), 0);
+        assert_eq!(
+            dead_letters
+                .count(&DeadLetterFilter::default())
+                .await
+                .unwrap(),
+            3
+        );
+
+        storage
+            .down
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        service
+            .publish_event(publish_request(event(3)))
+            .await
+            .unwrap();
+        assert_eq!(storage.inner.len(), 1);
+        assert_eq!(service.get_event_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_meta_audit_events_go_through_the_pipeline() {
+        use crate::dead_letter::DeadLetterFilter;
+
+        let storage = Arc::new(InMemoryStorage::new());
+        let dead_letters = Arc::new(DeadLetterStore::new(Arc::new(InMemoryStorage::new())));
+        let service = AuditControlServiceImpl::new()
+            .with_storage(storage.clone())
+            .with_dead_letters(dead_letters.clone());
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let logger = MetaAuditLogger::new(tx);
+
+        assert!(
+            logger
+                .log(AdminAction::RotateKey, "tenant-1", "alice", "key-2")
+                .await
+        );
+        // Sin tenant la validación lo rechaza y acaba en el dead-letter store
+        assert!(
+            logger
+                .log(AdminAction::RotateKey, "", "alice", "key-3")
+                .await
+        );
+        drop(logger);
+        while let Some(event) = rx.recv().await {
+            service.ingest_meta_audit_event(event).await;
+        }
+
+        assert_eq!(storage.len(), 1);
+        assert_eq!(
+            dead_letters
+                .count(&DeadLetterFilter::default())
+                .await
+                .unwrap(),
+            1
+        );
+    }
+
+    fn migration_request() -> Request<TriggerLifecycleMigrationRequest> {
+        migration_request_from("ops.hodei.local")
+    }
+
+    /// Petición de un cliente mTLS con el CN indicado
+    fn migration_request_from(common_name: &str) -> Request<TriggerLifecycleMigrationRequest> {
+        let mut request = Request::new(TriggerLifecycleMigrationRequest {
+            reason: "maintenance window".to_string(),
+        });
+        request.extensions_mut().insert(ClientIdentity {
+            common_name: Some(common_name.to_string()),
+            subject_alt_names: vec![],
+        });
+        request
+    }
+
+    #[tokio::test]
+    async fn test_trigger_lifecycle_migration_reports_moved_events() {
+        let (storage, _hot) = storage_with_spooled_events().await;
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let service = AuditControlServiceImpl::new()
+            .with_tiered_storage(storage.clone())
+            .with_admin_identities(["ops.hodei.local"])
+            .with_meta_audit(MetaAuditLogger::new(tx));
+
+        let response = service
+            .trigger_lifecycle_migration(migration_request())
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.events_moved, 3);
+        assert_eq!(response.tiers_touched, vec!["warm", "hot"]);
+        assert_eq!(storage.spooled_event_count(), 0);
+
+        let audit = rx.try_recv().unwrap();
+        assert_eq!(audit.action, "TriggerLifecycleMigration");
+        assert_eq!(
+            audit.user_identity.as_ref().unwrap().user_id,
+            "ops.hodei.local"
+        );
+        assert_eq!(
+            audit.tenant_id.as_ref().unwrap().value,
+            META_AUDIT_SYSTEM_TENANT
+        );
+        assert_eq!(
+            audit.outcome,
+            i32::from(hodei_audit_types::Outcome::Success)
+        );
+        assert_eq!(
+            crate::meta_audit::metadata_str(&audit, "resource"),
+            Some(response.migration_id.as_str())
+        );
+        assert_eq!(
+            crate::meta_audit::metadata_str(&audit, "reason"),
+            Some("maintenance window")
+        );
+
+        // El intervalo mínimo limita la siguiente migración
+        let status = service
+            .trigger_lifecycle_migration(migration_request())
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert!(status.metadata().get("retry-after").is_some());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_trigger_lifecycle_migration_requires_an_admin_certificate() {
+        let (storage, _hot) = storage_with_spooled_events().await;
+        let service = AuditControlServiceImpl::new()
+            .with_tiered_storage(storage.clone())
+            .with_admin_identities(["ops.hodei.local"]);
+
+        // Las cabeceras no autentican: sin certificado se rechaza
+        let mut request = Request::new(TriggerLifecycleMigrationRequest::default());
+        request
+            .metadata_mut()
+            .insert("x-user-id", "ops.hodei.local".parse().unwrap());
+        let status = service
+            .trigger_lifecycle_migration(request)
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let status = service
+            .trigger_lifecycle_migration(migration_request_from("cap-collector"))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        assert_eq!(storage.spooled_event_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_trigger_is_rejected_while_migration_runs() {
+        let (storage, hot) = storage_with_spooled_events().await;
+        let service = AuditControlServiceImpl::new()
+            .with_tiered_storage(storage)
+            .with_admin_identities(["ops.hodei.local"])
+            .with_min_migration_interval(Duration::ZERO);
+
+        hot.hold.store(true, std::sync::atomic::Ordering::SeqCst);
+        let first = tokio::spawn({
+            let service = service.clone();
+            async move {
+                service
+                    .trigger_lifecycle_migration(migration_request())
+                    .await
+            }
+        });
+        hot.checking.notified().await;
+
+        let status = service
+            .trigger_lifecycle_migration(migration_request())
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Aborted);
+
+        hot.hold.store(false, std::sync::atomic::Ordering::SeqCst);
+        hot.release.notify_one();
+        let response = first.await.unwrap().unwrap().into_inner();
+        assert_eq!(response.events_moved, 3);
+
+        // Sin intervalo mínimo, terminada la primera se acepta otra
+        let response = service
+            .trigger_lifecycle_migration(migration_request())
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.events_moved, 0);
+    }
 }
@@ -5,6 +5,7 @@ use crate::crypto::ports::{
     digest_chain::DigestChainService, hashing::HashingService, signing::SigningService,
 };
 use crate::key_management::ports::key_manager::KeyManager;
+use crate::meta_audit::{AdminAction, MetaAuditLogger};
 use hodei_audit_proto::{
     DigestInfo, GenerateDigestRequest, GenerateDigestResponse, GetPublicKeysRequest,
     GetPublicKeysResponse, HealthCheckRequest, HealthCheckResponse, HealthStatus, KeysManifest,
@@ -34,6 +35,8 @@ where
     key_manager: KM,
     /// Contador de operaciones
     crypto_counter: std::sync::Arc<std::sync::atomic::AtomicU64>,
+    /// Meta-auditoría de las rotaciones de clave
+    meta_audit: Option<MetaAuditLogger>,
 }
 
 impl<HS, SS, DS, KM> AuditCryptoServiceImpl<HS, SS, DS, KM>
@@ -57,9 +60,16 @@ where
             digest_chain,
             key_manager,
             crypto_counter: std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            meta_audit: None,
         }
     }
 
+    /// Registrar cada rotación de clave como evento de meta-auditoría
+    pub fn with_meta_audit(mut self, logger: MetaAuditLogger) -> Self {
+        self.meta_audit = Some(logger);
+        self
+    }
+
     /// Incrementar contador de operaciones
     fn next_operation_id(&self) -> String {
         let count = self
@@ -293,6 +303,12 @@ where
         &self,
         request: Request<RotateKeyRequest>,
     ) -> Result<Response<RotateKeyResponse>, Status> {
+        let actor = request
+            .metadata()
+            .get("x-user-id")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("unknown")
+            .to_string();
         let req = request.into_inner();
         let tenant_id = req.tenant_id.clone();
         let force_rotation = req.force_rotation;
@@ -351,6 +367,11 @@ where
             new_key_id = new_key_id,
             "Key rotation completed"
         );
+        if let Some(logger) = &self.meta_audit {
+            logger
+                .log(AdminAction::RotateKey, &tenant_id, &actor, &new_key_id)
+                .await;
+        }
 
         Ok(Response::new(response))
     }
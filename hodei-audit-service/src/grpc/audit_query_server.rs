@@ -1,21 +1,43 @@
+use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::Stream;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
-use tracing::info;
+use tracing::{info, warn};
 
 use hodei_audit_proto::{
-    AnalyticsQueryRequest, AnalyticsQueryResponse, AuditQueryRequest, AuditQueryResponse,
-    HealthCheckRequest, HealthCheckResponse, HealthStatus, Hrn as ProtoHrn, HrnHierarchy,
-    HrnMetadata, QueryMetadata, QueryStats, ResolveHrnRequest, ResolveHrnResponse,
-    SearchHrnRequest, SearchHrnResponse, audit_query_service_server::AuditQueryService,
+    AnalyticsQueryRequest, AnalyticsQueryResponse, AuditEvent, AuditQueryRequest,
+    AuditQueryResponse, HealthCheckRequest, HealthCheckResponse, HealthStatus, Hrn as ProtoHrn,
+    HrnHierarchy, HrnMetadata, QueryMetadata, QueryStats, ResolveHrnRequest, ResolveHrnResponse,
+    SearchHrnRequest, SearchHrnResponse, StreamEventsRequest,
+    audit_query_service_server::AuditQueryService,
 };
 
 use hodei_audit_types::hrn::Hrn;
 
+use crate::event_feed::EventFeed;
+use crate::grpc_interceptor::ClientIdentity;
+use crate::row_level_security::RlsManager;
+
+/// Eventos recientes reenviados por defecto al abrir un StreamEvents
+const DEFAULT_REPLAY_LIMIT: usize = 100;
+/// Máximo de eventos recientes reenviados al abrir un StreamEvents
+const MAX_REPLAY_LIMIT: usize = 1000;
+/// Tabla cuya política RLS aplica al tail de eventos
+const AUDIT_EVENTS_TABLE: &str = "audit_events";
+
 /// Implementación del servicio de query de auditoría
 /// Maneja consultas, analytics y resolución de HRNs
 #[derive(Debug, Clone, Default)]
 pub struct AuditQueryServiceImpl {
     // Contador de queries para métricas
     query_counter: std::sync::Arc<std::sync::atomic::AtomicU64>,
+    // Feed de eventos ingeridos para StreamEvents
+    event_feed: Arc<EventFeed>,
+    // Tenants que puede seguir cada certificado de cliente (CN o SAN)
+    tenant_identities: Arc<HashMap<String, HashSet<String>>>,
 }
 
 impl AuditQueryServiceImpl {
@@ -24,7 +46,59 @@ impl AuditQueryServiceImpl {
         info!("Initializing AuditQueryService");
         Self {
             query_counter: std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            event_feed: Arc::new(EventFeed::default()),
+            tenant_identities: Arc::new(HashMap::new()),
+        }
+    }
+
+    /// Seguir el feed compartido con el pipeline de ingestión
+    pub fn with_event_feed(mut self, event_feed: Arc<EventFeed>) -> Self {
+        self.event_feed = event_feed;
+        self
+    }
+
+    /// Autorizar a cada certificado de cliente mTLS (por CN o SAN) a seguir
+    /// los eventos de sus tenants con `StreamEvents`; un certificado sin
+    /// tenants no puede seguir ninguno
+    pub fn with_tenant_identities(mut self, identities: HashMap<String, Vec<String>>) -> Self {
+        self.tenant_identities = Arc::new(
+            identities
+                .into_iter()
+                .map(|(name, tenants)| (name, tenants.into_iter().collect()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Comprobar que el certificado de cliente verificado puede leer los
+    /// eventos de `tenant_id`
+    ///
+    /// Las cabeceras del cliente no se consideran: el tenant que declara la
+    /// petición solo se acepta si está asignado a alguno de los nombres del
+    /// certificado.
+    fn authorize_tenant<T>(&self, request: &Request<T>, tenant_id: &str) -> Result<(), Status> {
+        let Some(identity) = request.extensions().get::<ClientIdentity>() else {
+            return Err(Status::unauthenticated(
+                "streaming events requires a client certificate",
+            ));
+        };
+        let authorized = identity.names().any(|name| {
+            self.tenant_identities
+                .get(name)
+                .is_some_and(|tenants| tenants.contains(tenant_id))
+        });
+        if !authorized {
+            warn!(
+                tenant_id = tenant_id,
+                "StreamEvents denied to cn={:?}, san={:?}",
+                identity.common_name,
+                identity.subject_alt_names
+            );
+            return Err(Status::permission_denied(
+                "client certificate is not authorized for this tenant",
+            ));
         }
+        Ok(())
     }
 
     /// Incrementar contador de queries
@@ -36,8 +110,81 @@ impl AuditQueryServiceImpl {
     }
 }
 
+/// Verificar si un evento es visible para el tenant de la sesión RLS
+fn is_visible(rls: &RlsManager, event: &AuditEvent) -> bool {
+    let tenant_id = event
+        .tenant_id
+        .as_ref()
+        .map(|t| t.value.as_str())
+        .unwrap_or_default();
+    rls.is_row_visible(AUDIT_EVENTS_TABLE, tenant_id)
+}
+
+/// Verificar si un evento cumple los filtros de un StreamEvents
+fn matches_stream_filter(filter: &StreamEventsRequest, event: &AuditEvent) -> bool {
+    if let Some(hrn_filter) = &filter.hrn {
+        let hrn = event.hrn.clone().unwrap_or_default();
+        let hrn_str = format!(
+            "hrn:{}:{}:{}:{}:{}/{}",
+            hrn.partition,
+            hrn.service,
+            hrn.tenant_id,
+            hrn.region,
+            hrn.resource_type,
+            hrn.resource_path
+        );
+        let checks = [
+            (&hrn_filter.hrn, hrn_str == hrn_filter.hrn),
+            (
+                &hrn_filter.hrn_prefix,
+                hrn_str.starts_with(&hrn_filter.hrn_prefix),
+            ),
+            (
+                &hrn_filter.resource_type,
+                hrn.resource_type == hrn_filter.resource_type,
+            ),
+            (
+                &hrn_filter.resource_path,
+                hrn.resource_path == hrn_filter.resource_path,
+            ),
+            (&hrn_filter.service, hrn.service == hrn_filter.service),
+            (&hrn_filter.region, hrn.region == hrn_filter.region),
+        ];
+        if checks.iter().any(|(value, ok)| !value.is_empty() && !ok) {
+            return false;
+        }
+    }
+
+    if let Some(user_filter) = &filter.user {
+        let user_id = event
+            .user_identity
+            .as_ref()
+            .map(|u| u.user_id.as_str())
+            .unwrap_or_default();
+        if !user_filter.user_id.is_empty() && user_filter.user_id != user_id {
+            return false;
+        }
+        if !user_filter.user_ids.is_empty() && !user_filter.user_ids.iter().any(|u| u == user_id) {
+            return false;
+        }
+    }
+
+    if let Some(action_filter) = &filter.action {
+        if !action_filter.action.is_empty() && action_filter.action != event.action {
+            return false;
+        }
+        if !action_filter.actions.is_empty() && !action_filter.actions.contains(&event.action) {
+            return false;
+        }
+    }
+
+    true
+}
+
 #[tonic::async_trait]
 impl AuditQueryService for AuditQueryServiceImpl {
+    type StreamEventsStream = Pin<Box<dyn Stream<Item = Result<AuditEvent, Status>> + Send>>;
+
     /// Consultar eventos de auditoría
     async fn query_events(
         &self,
@@ -275,4 +422,214 @@ impl AuditQueryService for AuditQueryServiceImpl {
 
         Ok(Response::new(response))
     }
+
+    /// Tail en vivo de eventos de auditoría
+    ///
+    /// Reenvía primero los eventos recientes que cumplen el filtro y después
+    /// mantiene el stream abierto con los nuevos eventos ingeridos. El
+    /// llamante se identifica por su certificado de cliente mTLS, que debe
+    /// tener asignado el tenant pedido; sin certificado se rechaza con
+    /// `UNAUTHENTICATED`. El aislamiento entre tenants se aplica con la
+    /// política RLS de `audit_events`.
+    async fn stream_events(
+        &self,
+        request: Request<StreamEventsRequest>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        info!(
+            tenant_id = request.get_ref().tenant_id,
+            "Received StreamEvents request"
+        );
+
+        // Validación
+        if request.get_ref().tenant_id.is_empty() {
+            return Err(Status::invalid_argument("tenant_id is required"));
+        }
+        self.authorize_tenant(&request, &request.get_ref().tenant_id)?;
+        let req = request.into_inner();
+
+        let mut rls = RlsManager::new();
+        rls.set_tenant_id(req.tenant_id.clone());
+
+        let replay_limit = match req.replay_limit {
+            0 => DEFAULT_REPLAY_LIMIT,
+            n => (n as usize).min(MAX_REPLAY_LIMIT),
+        };
+
+        let (recent, mut receiver) = self.event_feed.subscribe();
+        let backlog: Vec<AuditEvent> = recent
+            .into_iter()
+            .filter(|e| is_visible(&rls, e) && matches_stream_filter(&req, e))
+            .collect();
+        let skip = backlog.len().saturating_sub(replay_limit);
+
+        let (tx, rx) = mpsc::channel(replay_limit.max(16));
+        tokio::spawn(async move {
+            for event in backlog.into_iter().skip(skip) {
+                if tx.send(Ok(event)).await.is_err() {
+                    return;
+                }
+            }
+
+            loop {
+                tokio::select! {
+                    // El cliente cerró el stream
+                    _ = tx.closed() => break,
+                    received = receiver.recv() => match received {
+                        Ok(event) => {
+                            if is_visible(&rls, &event)
+                                && matches_stream_filter(&req, &event)
+                                && tx.send(Ok(event)).await.is_err()
+                            {
+                                break;
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!(
+                                tenant_id = req.tenant_id,
+                                skipped = skipped,
+                                "StreamEvents subscriber lagged, events skipped"
+                            );
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hodei_audit_proto::{ActionFilter, EventId, TenantId};
+    use std::time::Duration;
+    use tokio_stream::StreamExt;
+
+    fn event(id: &str, tenant_id: &str, action: &str) -> AuditEvent {
+        AuditEvent {
+            event_id: Some(EventId {
+                value: id.to_string(),
+            }),
+            tenant_id: Some(TenantId {
+                value: tenant_id.to_string(),
+            }),
+            action: action.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Servicio que autoriza al certificado `collector-a` a seguir `tenant-a`
+    fn service_with_identities(feed: Arc<EventFeed>) -> AuditQueryServiceImpl {
+        AuditQueryServiceImpl::new()
+            .with_event_feed(feed)
+            .with_tenant_identities(HashMap::from([(
+                "collector-a".to_string(),
+                vec!["tenant-a".to_string()],
+            )]))
+    }
+
+    /// Petición de StreamEvents autenticada con el certificado `common_name`
+    fn stream_request(
+        common_name: &str,
+        request: StreamEventsRequest,
+    ) -> Request<StreamEventsRequest> {
+        let mut request = Request::new(request);
+        request.extensions_mut().insert(ClientIdentity {
+            common_name: Some(common_name.to_string()),
+            ..Default::default()
+        });
+        request
+    }
+
+    #[tokio::test]
+    async fn test_stream_events_only_streams_matching_tenant_events() {
+        let feed = Arc::new(EventFeed::default());
+        let service = service_with_identities(feed.clone());
+
+        // Ingerido antes de suscribirse: debe llegar como replay
+        feed.publish(event("replayed", "tenant-a", "CreatePolicyStore"));
+        feed.publish(event("other-tenant-old", "tenant-b", "CreatePolicyStore"));
+
+        let request = stream_request(
+            "collector-a",
+            StreamEventsRequest {
+                tenant_id: "tenant-a".to_string(),
+                action: Some(ActionFilter {
+                    action: "CreatePolicyStore".to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        );
+        let mut stream = service.stream_events(request).await.unwrap().into_inner();
+
+        let first = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.event_id.unwrap().value, "replayed");
+
+        // Ingeridos con el stream abierto
+        feed.publish(event("other-tenant", "tenant-b", "CreatePolicyStore"));
+        feed.publish(event("other-action", "tenant-a", "DeletePolicyStore"));
+        feed.publish(event("live", "tenant-a", "CreatePolicyStore"));
+
+        let next = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(next.event_id.unwrap().value, "live");
+
+        // No llega nada más
+        let pending = tokio::time::timeout(Duration::from_millis(200), stream.next()).await;
+        assert!(pending.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stream_events_rejects_certificate_of_another_tenant() {
+        let service = service_with_identities(Arc::new(EventFeed::default()));
+        // La cabecera de tenant la elige el cliente y no autoriza nada
+        let mut request = stream_request(
+            "collector-a",
+            StreamEventsRequest {
+                tenant_id: "tenant-b".to_string(),
+                ..Default::default()
+            },
+        );
+        request
+            .extensions_mut()
+            .insert(crate::tenant::TenantContext::new("tenant-b".to_string()));
+
+        let err = service.stream_events(request).await.err().unwrap();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+
+        let request = stream_request(
+            "collector-b",
+            StreamEventsRequest {
+                tenant_id: "tenant-a".to_string(),
+                ..Default::default()
+            },
+        );
+        let err = service.stream_events(request).await.err().unwrap();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[tokio::test]
+    async fn test_stream_events_without_certificate_is_unauthenticated() {
+        let service = service_with_identities(Arc::new(EventFeed::default()));
+        let mut request = Request::new(StreamEventsRequest {
+            tenant_id: "tenant-a".to_string(),
+            ..Default::default()
+        });
+        request
+            .extensions_mut()
+            .insert(crate::tenant::TenantContext::new("tenant-a".to_string()));
+
+        let err = service.stream_events(request).await.err().unwrap();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+    }
 }
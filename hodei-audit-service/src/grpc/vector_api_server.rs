@@ -1,23 +1,165 @@
 //! Vector API Service - Implementación
 //!
 //! Servicio para comunicación CAP → Vector.dev (Puerto 50051)
+//!
+//! Los colectores negocian la versión del protocolo con `Handshake` antes de
+//! enviar lotes. Los eventos de versiones anteriores compatibles se traducen
+//! al esquema actual; las versiones incompatibles se rechazan.
+//!
+//! Cada evento de un lote se acepta o rechaza por separado, y la respuesta
+//! incluye su estado para que Vector reintente solo los rechazados. Un
+//! evento que supera `max_event_bytes` nunca se aceptaría al reintentarlo,
+//! así que rechaza el lote entero con `INVALID_ARGUMENT`.
+//!
+//! Los lotes pueden llevar `collector_id` y un `sequence` estrictamente
+//! creciente por colector. Un lote con una secuencia ya vista (repetido o
+//! reenviado) se rechaza con `ALREADY_EXISTS`, sin aceptar ninguno de sus
+//! eventos. La secuencia solo se registra una vez guardado el lote, de modo
+//! que un lote que falló con `UNAVAILABLE` puede reintentarse con la misma.
+//!
+//! Los eventos aceptados se enriquecen y se guardan en `storage`, que en el
+//! servidor es el `EventRouter` que los reparte por reglas. Una vez guardados
+//! se publican en el feed que sigue `StreamEvents`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use tonic::{Request, Response, Status};
 use tracing::{error, info, warn};
 
+use super::{DEFAULT_MAX_EVENT_BYTES, check_event_size, store_raw_copies};
+use crate::enrichment::EventEnricher;
+use crate::event_feed::EventFeed;
+use crate::metrics::AuditMetrics;
+use crate::schema_registry::SchemaValidator;
+use crate::storage::StorageBackend;
 use hodei_audit_proto::{
-    AuditEvent, EventBatchRequest, EventBatchResponse, EventId, HealthCheckRequest,
-    HealthCheckResponse, TenantId, vector_api_server::VectorApi,
+    AuditEvent, EventBatchRequest, EventBatchResponse, EventCategory, EventStatus,
+    HandshakeRequest, HandshakeResponse, HealthCheckRequest, HealthCheckResponse, TenantId,
+    vector_api_server::VectorApi,
 };
 
+/// Versión actual del protocolo (y del esquema de `AuditEvent`)
+pub const CURRENT_PROTOCOL_VERSION: u32 = 2;
+
+/// Versión que hablan los colectores anteriores al handshake, que envían
+/// `protocol_version = 0`
+pub const LEGACY_PROTOCOL_VERSION: u32 = 1;
+
+/// Versiones que el servidor acepta; las anteriores a la actual se traducen
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[u32] =
+    &[LEGACY_PROTOCOL_VERSION, CURRENT_PROTOCOL_VERSION];
+
+/// Elegir la versión más alta soportada por ambos extremos
+pub fn negotiate_version(client_versions: &[u32]) -> Option<u32> {
+    client_versions
+        .iter()
+        .copied()
+        .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+        .max()
+}
+
+/// Traducir un evento de la versión `version` al esquema actual
+///
+/// En la versión 1 el tenant solo viajaba en el HRN o en la identidad del
+/// usuario, y `management_event` no existía: se derivaba de la categoría.
+pub fn upgrade_event(mut event: AuditEvent, version: u32) -> Result<AuditEvent, Status> {
+    match version {
+        CURRENT_PROTOCOL_VERSION => Ok(event),
+        LEGACY_PROTOCOL_VERSION => {
+            if event.tenant_id.as_ref().is_none_or(|t| t.value.is_empty()) {
+                let tenant = event
+                    .hrn
+                    .as_ref()
+                    .map(|hrn| hrn.tenant_id.clone())
+                    .filter(|t| !t.is_empty())
+                    .or_else(|| {
+                        event
+                            .user_identity
+                            .as_ref()
+                            .map(|user| user.tenant_id.clone())
+                            .filter(|t| !t.is_empty())
+                    })
+                    .ok_or_else(|| {
+                        Status::invalid_argument("v1 event without tenant in hrn or user_identity")
+                    })?;
+                event.tenant_id = Some(TenantId { value: tenant });
+            }
+            event.management_event =
+                event.event_category == EventCategory::CategoryManagement as i32;
+            Ok(event)
+        }
+        other => Err(incompatible_version(&[other])),
+    }
+}
+
+/// Eventos de un lote que Vector debe reintentar: los rechazados
+pub fn events_to_retry(events: Vec<AuditEvent>, response: &EventBatchResponse) -> Vec<AuditEvent> {
+    let rejected: std::collections::HashSet<usize> = response
+        .event_statuses
+        .iter()
+        .filter(|status| !status.accepted)
+        .map(|status| status.index as usize)
+        .collect();
+    events
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| rejected.contains(index))
+        .map(|(_, event)| event)
+        .collect()
+}
+
+fn incompatible_version(client_versions: &[u32]) -> Status {
+    Status::failed_precondition(format!(
+        "Incompatible protocol version: client supports {:?}, server supports {:?}",
+        client_versions, SUPPORTED_PROTOCOL_VERSIONS
+    ))
+}
+
 /// Implementación del Vector API
 /// Maneja la comunicación entre el CAP y Vector.dev
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct VectorApiServiceImpl {
     // Contador de lotes procesados (usar interior mutability)
     batch_counter: std::sync::Arc<std::sync::atomic::AtomicU64>,
     // Flag para indicar si Vector está disponible
     vector_available: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    // Validación opcional del esquema de los eventos
+    schema_validator: Option<Arc<SchemaValidator>>,
+    // Tamaño máximo codificado de un evento
+    max_event_bytes: usize,
+    // Métricas donde se contabilizan los eventos rechazados por tamaño
+    metrics: Option<Arc<tokio::sync::RwLock<AuditMetrics>>>,
+    // Exigir `collector_id` y `sequence` en todos los lotes
+    replay_protection: bool,
+    // Última secuencia aceptada de cada colector
+    last_sequences: Arc<Mutex<HashMap<String, u64>>>,
+    // Copia en bruto de los eventos aceptados, tal como llegaron
+    raw_storage: Option<Arc<dyn StorageBackend>>,
+    // Storage de los eventos aceptados, tras enriquecerlos
+    storage: Option<Arc<dyn StorageBackend>>,
+    // Enriquecimiento opcional de los eventos aceptados
+    enricher: Option<Arc<EventEnricher>>,
+    // Feed donde se publican los eventos guardados, para StreamEvents
+    event_feed: Option<Arc<EventFeed>>,
+}
+
+impl std::fmt::Debug for VectorApiServiceImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VectorApiServiceImpl")
+            .field("batch_counter", &self.batch_counter)
+            .field("vector_available", &self.vector_available)
+            .field("schema_validator", &self.schema_validator)
+            .field("max_event_bytes", &self.max_event_bytes)
+            .field("metrics", &self.metrics.is_some())
+            .field("replay_protection", &self.replay_protection)
+            .field("last_sequences", &self.last_sequences)
+            .field("raw_storage", &self.raw_storage.is_some())
+            .field("storage", &self.storage.is_some())
+            .field("enricher", &self.enricher.is_some())
+            .field("event_feed", &self.event_feed.is_some())
+            .finish()
+    }
 }
 
 /// Implementación por defecto
@@ -34,6 +176,163 @@ impl VectorApiServiceImpl {
         Self {
             batch_counter: std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0)),
             vector_available: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true)),
+            schema_validator: None,
+            max_event_bytes: DEFAULT_MAX_EVENT_BYTES,
+            metrics: None,
+            replay_protection: false,
+            last_sequences: Arc::new(Mutex::new(HashMap::new())),
+            raw_storage: None,
+            storage: None,
+            enricher: None,
+            event_feed: None,
+        }
+    }
+
+    /// Guardar en `storage` los eventos aceptados
+    pub fn with_storage(mut self, storage: Arc<dyn StorageBackend>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Enriquecer los eventos aceptados antes de guardarlos; si falla, el
+    /// evento se rechaza
+    pub fn with_enricher(mut self, enricher: Arc<EventEnricher>) -> Self {
+        self.enricher = Some(enricher);
+        self
+    }
+
+    /// Publicar en `event_feed` los eventos guardados
+    pub fn with_event_feed(mut self, event_feed: Arc<EventFeed>) -> Self {
+        self.event_feed = Some(event_feed);
+        self
+    }
+
+    /// Guardar en `raw_storage` la copia en bruto de los eventos aceptados,
+    /// antes de traducirlos al esquema actual
+    pub fn with_raw_storage(mut self, raw_storage: Arc<dyn StorageBackend>) -> Self {
+        self.raw_storage = Some(raw_storage);
+        self
+    }
+
+    /// Validar los eventos ingeridos contra los esquemas registrados
+    pub fn with_schema_validator(mut self, validator: Arc<SchemaValidator>) -> Self {
+        self.schema_validator = Some(validator);
+        self
+    }
+
+    /// Rechazar los lotes con eventos cuyo tamaño codificado supere
+    /// `max_event_bytes`
+    pub fn with_max_event_bytes(mut self, max_event_bytes: usize) -> Self {
+        self.max_event_bytes = max_event_bytes;
+        self
+    }
+
+    /// Contabilizar en `metrics` los eventos rechazados por tamaño
+    pub fn with_metrics(mut self, metrics: Arc<tokio::sync::RwLock<AuditMetrics>>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Rechazar los lotes sin `collector_id` o sin `sequence`
+    pub fn with_replay_protection(mut self) -> Self {
+        self.replay_protection = true;
+        self
+    }
+
+    /// Última secuencia aceptada de `collector_id`
+    pub fn last_sequence(&self, collector_id: &str) -> Option<u64> {
+        self.last_sequences
+            .lock()
+            .unwrap()
+            .get(collector_id)
+            .copied()
+    }
+
+    /// Comprobar que la secuencia del lote supera la última del colector
+    fn check_sequence(&self, collector_id: &str, sequence: u64) -> Result<(), Status> {
+        if sequence == 0 {
+            if self.replay_protection {
+                return Err(Status::invalid_argument(
+                    "collector_id and sequence are required",
+                ));
+            }
+            return Ok(());
+        }
+        if collector_id.is_empty() {
+            return Err(Status::invalid_argument("sequence requires a collector_id"));
+        }
+
+        let last_sequences = self.last_sequences.lock().unwrap();
+        if let Some(&last) = last_sequences.get(collector_id)
+            && sequence <= last
+        {
+            warn!(
+                collector_id = collector_id,
+                sequence = sequence,
+                last_sequence = last,
+                "Rejected replayed event batch"
+            );
+            return Err(Status::already_exists(format!(
+                "batch sequence {} of collector {} was already received (last {})",
+                sequence, collector_id, last
+            )));
+        }
+        Ok(())
+    }
+
+    /// Registrar la secuencia de un lote ya guardado
+    fn commit_sequence(&self, collector_id: &str, sequence: u64) {
+        if sequence == 0 {
+            return;
+        }
+        let mut last_sequences = self.last_sequences.lock().unwrap();
+        let last = last_sequences.entry(collector_id.to_string()).or_default();
+        *last = (*last).max(sequence);
+    }
+
+    /// Comprobar el tamaño de todos los eventos del lote
+    async fn check_batch_size(&self, events: &[AuditEvent]) -> Result<(), Status> {
+        for (index, event) in events.iter().enumerate() {
+            if let Err(error) = check_event_size(index as u64, event, self.max_event_bytes) {
+                let tenant_id = event.tenant_id.clone().unwrap_or_default().value;
+                warn!(
+                    tenant_id = tenant_id,
+                    error = error,
+                    "Rejected oversized event"
+                );
+                if let Some(metrics) = &self.metrics {
+                    metrics.write().await.record_oversized_event(&tenant_id);
+                }
+                return Err(Status::invalid_argument(error));
+            }
+        }
+        Ok(())
+    }
+
+    /// Traducir y validar un evento del lote
+    fn accept_event(&self, event: AuditEvent, version: u32) -> Result<AuditEvent, String> {
+        let mut event = upgrade_event(event, version).map_err(|s| s.message().to_string())?;
+        if event.event_id.as_ref().is_none_or(|id| id.value.is_empty()) {
+            return Err("event_id is required".to_string());
+        }
+        if event.tenant_id.as_ref().is_none_or(|t| t.value.is_empty()) {
+            return Err("tenant_id is required".to_string());
+        }
+        if let Some(validator) = &self.schema_validator {
+            validator.check(&mut event).map_err(|e| e.to_string())?;
+        }
+        Ok(event)
+    }
+
+    /// Aceptar y enriquecer un evento del lote
+    async fn ingest_event(&self, event: AuditEvent, version: u32) -> Result<AuditEvent, String> {
+        let event = self.accept_event(event, version)?;
+        match &self.enricher {
+            Some(enricher) => enricher
+                .enrich(event)
+                .await
+                .map_err(|e| format!("enrichment failed: {}", e)),
+            None => Ok(event),
         }
     }
 
@@ -76,8 +375,12 @@ impl VectorApi for VectorApiServiceImpl {
         request: Request<EventBatchRequest>,
     ) -> Result<Response<EventBatchResponse>, Status> {
         let req = request.into_inner();
-        let events = req.events.clone();
-        let event_count = events.len();
+        // Sin versión el colector no hizo el handshake: habla la v1
+        let version = match req.protocol_version {
+            0 => LEGACY_PROTOCOL_VERSION,
+            version => version,
+        };
+        let event_count = req.events.len();
 
         // Verificar disponibilidad de Vector
         self.check_vector_health()?;
@@ -88,10 +391,97 @@ impl VectorApi for VectorApiServiceImpl {
         );
 
         // Validación básica
-        if events.is_empty() {
+        if req.events.is_empty() {
             return Err(Status::invalid_argument("events cannot be empty"));
         }
 
+        if !SUPPORTED_PROTOCOL_VERSIONS.contains(&version) {
+            return Err(incompatible_version(&[version]));
+        }
+        self.check_batch_size(&req.events).await?;
+        self.check_sequence(&req.collector_id, req.sequence)?;
+        if version != CURRENT_PROTOCOL_VERSION {
+            info!(
+                version = version,
+                event_count = event_count,
+                "Translating event batch to the current schema"
+            );
+        }
+
+        let mut events = Vec::with_capacity(event_count);
+        let mut raw_events = Vec::new();
+        let mut event_statuses = Vec::with_capacity(event_count);
+        for (index, event) in req.events.into_iter().enumerate() {
+            let event_id = event
+                .event_id
+                .as_ref()
+                .map(|id| id.value.clone())
+                .unwrap_or_default();
+            let received = self.raw_storage.as_ref().map(|_| event.clone());
+            let status = match self.ingest_event(event, version).await {
+                Ok(event) => {
+                    if let Some(mut received) = received {
+                        // Los eventos v1 sin tenant_id lo toman del evento traducido
+                        if received
+                            .tenant_id
+                            .as_ref()
+                            .is_none_or(|t| t.value.is_empty())
+                        {
+                            received.tenant_id = event.tenant_id.clone();
+                        }
+                        raw_events.push(received);
+                    }
+                    events.push(event);
+                    EventStatus {
+                        index: index as u32,
+                        event_id,
+                        accepted: true,
+                        error: String::new(),
+                    }
+                }
+                Err(error) => {
+                    warn!(
+                        index = index,
+                        event_id = event_id,
+                        error = error,
+                        "Rejected event"
+                    );
+                    EventStatus {
+                        index: index as u32,
+                        event_id,
+                        accepted: false,
+                        error,
+                    }
+                }
+            };
+            event_statuses.push(status);
+        }
+
+        if let Some(raw_storage) = &self.raw_storage
+            && !raw_events.is_empty()
+        {
+            store_raw_copies(raw_storage.as_ref(), "", &raw_events)
+                .await
+                .map_err(|e| {
+                    error!(error = %e, "Failed to store raw event copies");
+                    Status::unavailable(format!("failed to store raw copies: {}", e))
+                })?;
+        }
+        if let Some(storage) = &self.storage
+            && !events.is_empty()
+        {
+            storage.store_batch(&events).await.map_err(|e| {
+                error!(error = %e, "Failed to store events");
+                Status::unavailable(format!("failed to store events: {}", e))
+            })?;
+        }
+        self.commit_sequence(&req.collector_id, req.sequence);
+        if let Some(feed) = &self.event_feed {
+            for event in &events {
+                feed.publish(event.clone());
+            }
+        }
+
         // TODO: Implementar envío real a Vector
         // - Serializar eventos
         // - Comprimir si es necesario
@@ -102,20 +492,34 @@ impl VectorApi for VectorApiServiceImpl {
 
         // Simular envío a Vector
         let batch_id = self.next_batch_id();
-        let received_count = event_count as u32;
+        let received_count = events.len() as u32;
+        let rejected_count = (event_count - events.len()) as u32;
 
         // Simular éxito (en implementación real, esperar respuesta de Vector)
         info!(
             batch_id = batch_id,
-            event_count = event_count,
+            event_count = received_count,
+            rejected_count = rejected_count,
             "Event batch sent to Vector successfully (simulated)"
         );
 
+        let message = if rejected_count == 0 {
+            "Batch received by Vector".to_string()
+        } else {
+            format!(
+                "{} of {} events accepted, {} rejected",
+                received_count, event_count, rejected_count
+            )
+        };
+        // Un rechazo parcial no invalida el lote: el cliente reenvía solo los
+        // eventos rechazados (ver `events_to_retry`)
         let response = EventBatchResponse {
-            success: true,
-            message: "Batch received by Vector".to_string(),
+            success: received_count > 0,
+            message,
             batch_id,
             received_count,
+            event_statuses,
+            rejected_count,
         };
 
         Ok(Response::new(response))
@@ -174,11 +578,45 @@ impl VectorApi for VectorApiServiceImpl {
 
         Ok(Response::new(response))
     }
+
+    /// Negociar la versión del protocolo con el colector
+    async fn handshake(
+        &self,
+        request: Request<HandshakeRequest>,
+    ) -> Result<Response<HandshakeResponse>, Status> {
+        let req = request.into_inner();
+        if req.supported_versions.is_empty() {
+            return Err(Status::invalid_argument(
+                "supported_versions cannot be empty",
+            ));
+        }
+
+        let negotiated_version = negotiate_version(&req.supported_versions).ok_or_else(|| {
+            warn!(
+                client_id = req.client_id,
+                versions = ?req.supported_versions,
+                "Rejected collector with incompatible protocol versions"
+            );
+            incompatible_version(&req.supported_versions)
+        })?;
+
+        info!(
+            client_id = req.client_id,
+            negotiated_version = negotiated_version,
+            "Protocol version negotiated"
+        );
+        Ok(Response::new(HandshakeResponse {
+            negotiated_version,
+            server_versions: SUPPORTED_PROTOCOL_VERSIONS.to_vec(),
+            current_version: CURRENT_PROTOCOL_VERSION,
+        }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use hodei_audit_proto::EventId;
 
     #[tokio::test]
     async fn test_send_event_batch_success() {
@@ -205,7 +643,11 @@ mod tests {
             },
         ];
 
-        let request = Request::new(EventBatchRequest { events });
+        let request = Request::new(EventBatchRequest {
+            events,
+            protocol_version: CURRENT_PROTOCOL_VERSION,
+            ..Default::default()
+        });
 
         let response = service.send_event_batch(request).await.unwrap();
         let response = response.into_inner();
@@ -219,7 +661,11 @@ mod tests {
     async fn test_send_event_batch_empty() {
         let mut service = VectorApiServiceImpl::new();
 
-        let request = Request::new(EventBatchRequest { events: vec![] });
+        let request = Request::new(EventBatchRequest {
+            events: vec![],
+            protocol_version: CURRENT_PROTOCOL_VERSION,
+            ..Default::default()
+        });
 
         let result = service.send_event_batch(request).await;
         assert!(result.is_err());
@@ -241,7 +687,11 @@ mod tests {
             ..Default::default()
         }];
 
-        let request = Request::new(EventBatchRequest { events });
+        let request = Request::new(EventBatchRequest {
+            events,
+            protocol_version: CURRENT_PROTOCOL_VERSION,
+            ..Default::default()
+        });
 
         let result = service.send_event_batch(request).await;
         assert!(result.is_err());
@@ -303,4 +753,360 @@ mod tests {
                 .load(std::sync::atomic::Ordering::SeqCst)
         );
     }
+
+    #[tokio::test]
+    async fn test_handshake_negotiates_highest_common_version() {
+        let service = VectorApiServiceImpl::new();
+
+        let response = service
+            .handshake(Request::new(HandshakeRequest {
+                supported_versions: vec![1, 2, 7],
+                client_id: "cap-1".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.negotiated_version, CURRENT_PROTOCOL_VERSION);
+        assert_eq!(response.current_version, CURRENT_PROTOCOL_VERSION);
+        assert_eq!(response.server_versions, SUPPORTED_PROTOCOL_VERSIONS);
+    }
+
+    #[tokio::test]
+    async fn test_v1_events_are_translated() {
+        let service = VectorApiServiceImpl::new();
+
+        let handshake = service
+            .handshake(Request::new(HandshakeRequest {
+                supported_versions: vec![1],
+                client_id: "cap-legacy".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(handshake.negotiated_version, 1);
+
+        let legacy = AuditEvent {
+            event_id: Some(EventId {
+                value: "event-legacy".to_string(),
+            }),
+            hrn: Some(hodei_audit_proto::Hrn {
+                tenant_id: "tenant-legacy".to_string(),
+                ..Default::default()
+            }),
+            event_category: EventCategory::CategoryManagement as i32,
+            ..Default::default()
+        };
+        let upgraded = upgrade_event(legacy.clone(), 1).unwrap();
+        assert_eq!(upgraded.tenant_id.unwrap().value, "tenant-legacy");
+        assert!(upgraded.management_event);
+
+        let response = service
+            .send_event_batch(Request::new(EventBatchRequest {
+                events: vec![legacy.clone()],
+                protocol_version: handshake.negotiated_version,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.success);
+        assert_eq!(response.received_count, 1);
+
+        // Sin handshake el lote se trata como v1, no como la versión actual
+        let response = service
+            .send_event_batch(Request::new(EventBatchRequest {
+                events: vec![AuditEvent {
+                    event_id: Some(EventId {
+                        value: "event-unversioned".to_string(),
+                    }),
+                    ..legacy
+                }],
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.received_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_incompatible_version_is_rejected() {
+        let service = VectorApiServiceImpl::new();
+
+        let status = service
+            .handshake(Request::new(HandshakeRequest {
+                supported_versions: vec![3, 4],
+                client_id: "cap-future".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+        assert!(status.message().contains("[3, 4]"));
+
+        let status = service
+            .send_event_batch(Request::new(EventBatchRequest {
+                events: vec![AuditEvent::default()],
+                protocol_version: 3,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+    }
+
+    #[tokio::test]
+    async fn test_partial_failure_reports_per_event_status() {
+        let service = VectorApiServiceImpl::new();
+        let event = |id: &str, tenant: &str| AuditEvent {
+            event_id: Some(EventId {
+                value: id.to_string(),
+            }),
+            tenant_id: Some(TenantId {
+                value: tenant.to_string(),
+            }),
+            ..Default::default()
+        };
+        let events = vec![
+            event("event-1", "test-tenant"),
+            event("event-2", ""),
+            event("event-3", "test-tenant"),
+        ];
+
+        let response = service
+            .send_event_batch(Request::new(EventBatchRequest {
+                events: events.clone(),
+                protocol_version: CURRENT_PROTOCOL_VERSION,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(response.success);
+        assert_eq!(response.received_count, 2);
+        assert_eq!(response.rejected_count, 1);
+        let accepted: Vec<bool> = response.event_statuses.iter().map(|s| s.accepted).collect();
+        assert_eq!(accepted, vec![true, false, true]);
+        assert_eq!(response.event_statuses[1].event_id, "event-2");
+        assert_eq!(response.event_statuses[1].error, "tenant_id is required");
+
+        let retry = events_to_retry(events, &response);
+        assert_eq!(retry.len(), 1);
+        assert_eq!(retry[0].event_id.as_ref().unwrap().value, "event-2");
+    }
+
+    #[tokio::test]
+    async fn test_send_event_batch_rejects_oversized_event() {
+        let metrics = crate::metrics::create_metrics();
+        let service = VectorApiServiceImpl::new()
+            .with_max_event_bytes(256)
+            .with_metrics(metrics.clone());
+
+        let event = |id: &str, action: String| hodei_audit_proto::AuditEvent {
+            event_id: Some(EventId {
+                value: id.to_string(),
+            }),
+            tenant_id: Some(TenantId {
+                value: "test-tenant".to_string(),
+            }),
+            action,
+            ..Default::default()
+        };
+        let request = Request::new(EventBatchRequest {
+            events: vec![
+                event("event-1", "Read".to_string()),
+                event("event-2", "x".repeat(512)),
+            ],
+            ..Default::default()
+        });
+
+        let status = service.send_event_batch(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(status.message().starts_with("event at index 1 is "));
+        assert!(status.message().ends_with("exceeding the 256 byte limit"));
+        assert_eq!(
+            metrics
+                .read()
+                .await
+                .get_oversized_event_count("test-tenant"),
+            1
+        );
+    }
+
+    /// Storage que rechaza todas las escrituras
+    struct FailingStorage;
+
+    #[async_trait::async_trait]
+    impl StorageBackend for FailingStorage {
+        async fn store_event(&self, _event: &AuditEvent) -> Result<(), anyhow::Error> {
+            anyhow::bail!("connection refused")
+        }
+
+        async fn store_batch(&self, _events: &[AuditEvent]) -> Result<(), anyhow::Error> {
+            anyhow::bail!("connection refused")
+        }
+
+        async fn query_events(
+            &self,
+            _filter: &crate::storage::QueryFilter,
+        ) -> Result<Vec<AuditEvent>, anyhow::Error> {
+            Ok(Vec::new())
+        }
+
+        async fn count_events(
+            &self,
+            _filter: &crate::storage::QueryFilter,
+        ) -> Result<u64, anyhow::Error> {
+            Ok(0)
+        }
+
+        async fn health_check(&self) -> Result<bool, anyhow::Error> {
+            Ok(false)
+        }
+
+        fn get_stats(&self) -> crate::storage::StorageStats {
+            crate::storage::StorageStats::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stored_events_are_published_to_the_feed() {
+        let feed = Arc::new(EventFeed::default());
+        let service = VectorApiServiceImpl::new()
+            .with_storage(Arc::new(crate::storage::InMemoryStorage::new()))
+            .with_event_feed(feed.clone());
+        let event = |id: &str, tenant: &str| AuditEvent {
+            event_id: Some(EventId {
+                value: id.to_string(),
+            }),
+            tenant_id: Some(TenantId {
+                value: tenant.to_string(),
+            }),
+            ..Default::default()
+        };
+
+        service
+            .send_event_batch(Request::new(EventBatchRequest {
+                events: vec![event("event-1", "test-tenant"), event("event-2", "")],
+                protocol_version: CURRENT_PROTOCOL_VERSION,
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+        let (recent, _) = feed.subscribe();
+        let published: Vec<&str> = recent
+            .iter()
+            .map(|e| e.event_id.as_ref().unwrap().value.as_str())
+            .collect();
+        assert_eq!(published, vec!["event-1"]);
+
+        // Si el storage falla el lote no se publica
+        let failing = VectorApiServiceImpl::new()
+            .with_storage(Arc::new(FailingStorage))
+            .with_event_feed(feed.clone());
+        let status = failing
+            .send_event_batch(Request::new(EventBatchRequest {
+                events: vec![event("event-3", "test-tenant")],
+                protocol_version: CURRENT_PROTOCOL_VERSION,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unavailable);
+        assert_eq!(feed.subscribe().0.len(), 1);
+    }
+
+    fn sequenced_batch(collector_id: &str, sequence: u64) -> Request<EventBatchRequest> {
+        Request::new(EventBatchRequest {
+            events: vec![AuditEvent {
+                event_id: Some(EventId {
+                    value: format!("{}-{}", collector_id, sequence),
+                }),
+                tenant_id: Some(TenantId {
+                    value: "test-tenant".to_string(),
+                }),
+                ..Default::default()
+            }],
+            collector_id: collector_id.to_string(),
+            sequence,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_replayed_batch_is_rejected() {
+        let service = VectorApiServiceImpl::new();
+
+        let response = service
+            .send_event_batch(sequenced_batch("cap-1", 1))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.success);
+
+        let status = service
+            .send_event_batch(sequenced_batch("cap-1", 1))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::AlreadyExists);
+
+        let response = service
+            .send_event_batch(sequenced_batch("cap-1", 2))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.success);
+        assert_eq!(service.last_sequence("cap-1"), Some(2));
+
+        let status = service
+            .send_event_batch(sequenced_batch("cap-1", 1))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::AlreadyExists);
+    }
+
+    #[tokio::test]
+    async fn test_sequences_are_tracked_per_collector() {
+        let service = VectorApiServiceImpl::new();
+
+        service
+            .send_event_batch(sequenced_batch("cap-1", 5))
+            .await
+            .unwrap();
+        service
+            .send_event_batch(sequenced_batch("cap-2", 1))
+            .await
+            .unwrap();
+
+        assert_eq!(service.last_sequence("cap-1"), Some(5));
+        assert_eq!(service.last_sequence("cap-2"), Some(1));
+        let status = service
+            .send_event_batch(sequenced_batch("cap-2", 1))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::AlreadyExists);
+    }
+
+    #[tokio::test]
+    async fn test_replay_protection_requires_sequence() {
+        let service = VectorApiServiceImpl::new().with_replay_protection();
+
+        let status = service
+            .send_event_batch(sequenced_batch("cap-1", 0))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        let status = service
+            .send_event_batch(sequenced_batch("", 1))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(
+            service
+                .send_event_batch(sequenced_batch("cap-1", 1))
+                .await
+                .is_ok()
+        );
+    }
 }
@@ -1,15 +1,30 @@
 //! gRPC Interceptor for Tenant Validation
 //!
 //! This module implements gRPC interceptors that validate tenant context
-//! and ensure proper isolation between tenants.
+//! and ensure proper isolation between tenants, plus a tower layer that
+//! records per-RPC metrics and structured access logs and an interceptor
+//! that exposes the mTLS client identity.
 
 use http::HeaderMap;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
+use std::net::IpAddr;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 use tonic::service::Interceptor;
-use tonic::{Request, Status};
+use tonic::{Code, Request, Status};
+use tower::{Layer, Service};
 use tracing::{error, info, warn};
+use x509_parser::extensions::GeneralName;
 
+use crate::distributed_tracing::{
+    BAGGAGE_HEADER, CORRELATION_ID_BAGGAGE, SpanId, TENANT_ID_BAGGAGE, TraceId, TraceState,
+};
+use crate::metrics::{AuditMetrics, OTHER_TENANT_LABEL};
+use crate::structured_logging::{LogContext, StructuredLogger};
 use crate::tenant::{TenantContext, TenantContextManager, TenantExtractor};
 
 /// Interceptor for tenant context validation
@@ -19,6 +34,8 @@ pub struct TenantValidationInterceptor {
     extractor: TenantExtractor,
     /// Enable strict validation
     strict_mode: bool,
+    /// Reject requests without `x-tenant-id`
+    require_tenant: bool,
 }
 
 impl TenantValidationInterceptor {
@@ -27,6 +44,7 @@ impl TenantValidationInterceptor {
         Self {
             extractor: TenantExtractor::new(),
             strict_mode: true,
+            require_tenant: true,
         }
     }
 
@@ -35,6 +53,7 @@ impl TenantValidationInterceptor {
         Self {
             extractor,
             strict_mode: true,
+            require_tenant: true,
         }
     }
 
@@ -44,6 +63,14 @@ impl TenantValidationInterceptor {
         self
     }
 
+    /// Let requests without `x-tenant-id` through untouched, for services
+    /// that also serve tenant-less RPCs (health checks, admin actions);
+    /// a tenant that is sent is still validated
+    pub fn require_tenant(mut self, required: bool) -> Self {
+        self.require_tenant = required;
+        self
+    }
+
     /// Validate and extract tenant context from request
     pub fn validate_request(&self, request: &Request<()>) -> Result<TenantContext, Status> {
         let context = self.extractor.extract_from_metadata(request)?;
@@ -74,10 +101,26 @@ impl Default for TenantValidationInterceptor {
 }
 
 impl Interceptor for TenantValidationInterceptor {
-    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if !self.require_tenant && request.metadata().get("x-tenant-id").is_none() {
+            return Ok(request);
+        }
+
         // Validate request
         let context = self.validate_request(&request)?;
 
+        // Trace context with the tenant as baggage; handlers run the request
+        // inside `TraceState::scope` so every stage's spans carry it
+        let trace_state = request_trace_state(&request, &context);
+        request.extensions_mut().insert(trace_state);
+
+        // Handlers take the caller's tenant from the validated context, never
+        // from the raw header
+        request.extensions_mut().insert(context.clone());
+        if let Some(label) = request.extensions().get::<RpcTenantLabel>() {
+            label.set(&context.tenant_id);
+        }
+
         // Set context in manager for the duration of the request
         self.extractor.set_context(context);
 
@@ -85,6 +128,37 @@ impl Interceptor for TenantValidationInterceptor {
     }
 }
 
+/// Trace context of a validated request
+///
+/// Continues the caller's trace when the request carries `traceparent`,
+/// and sets the tenant and correlation id (`x-correlation-id`, else the
+/// trace id) as baggage.
+pub fn request_trace_state<T>(request: &Request<T>, context: &TenantContext) -> TraceState {
+    let metadata = request.metadata();
+    let header = |name: &str| {
+        metadata
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    };
+
+    let propagated: HashMap<String, String> = ["traceparent", BAGGAGE_HEADER]
+        .into_iter()
+        .filter_map(|name| header(name).map(|value| (name.to_string(), value)))
+        .collect();
+    let trace_state = TraceState::extract(&propagated).unwrap_or_else(|| {
+        TraceState::new(
+            TraceId::new(context.trace_id.clone()),
+            SpanId::new(context.span_id.clone()),
+        )
+    });
+    let correlation_id = header("x-correlation-id").unwrap_or_else(|| context.trace_id.clone());
+
+    trace_state
+        .with_baggage(TENANT_ID_BAGGAGE, &context.tenant_id)
+        .with_baggage(CORRELATION_ID_BAGGAGE, &correlation_id)
+}
+
 /// Async interceptor for more complex validation logic
 #[derive(Debug, Clone)]
 pub struct AsyncTenantValidationInterceptor {
@@ -159,6 +233,310 @@ impl Default for AsyncTenantValidationInterceptor {
     }
 }
 
+/// Identity of an mTLS client, taken from its leaf certificate
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientIdentity {
+    /// Subject common name (CN)
+    pub common_name: Option<String>,
+    /// DNS, URI, e-mail and IP subject alternative names
+    pub subject_alt_names: Vec<String>,
+}
+
+impl ClientIdentity {
+    /// Parse the identity from a DER-encoded certificate
+    pub fn from_der(der: &[u8]) -> Option<Self> {
+        let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+
+        let common_name = cert
+            .subject()
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .map(|cn| cn.to_string());
+
+        let subject_alt_names = cert
+            .subject_alternative_name()
+            .ok()
+            .flatten()
+            .map(|san| {
+                san.value
+                    .general_names
+                    .iter()
+                    .filter_map(|name| match name {
+                        GeneralName::DNSName(v)
+                        | GeneralName::URI(v)
+                        | GeneralName::RFC822Name(v) => Some(v.to_string()),
+                        GeneralName::IPAddress(bytes) => ip_from_bytes(bytes),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            common_name,
+            subject_alt_names,
+        })
+    }
+
+    /// Common name followed by the subject alternative names
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.common_name
+            .iter()
+            .chain(&self.subject_alt_names)
+            .map(String::as_str)
+    }
+}
+
+fn ip_from_bytes(bytes: &[u8]) -> Option<String> {
+    let ip = match bytes.len() {
+        4 => IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?),
+        16 => IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?),
+        _ => return None,
+    };
+    Some(ip.to_string())
+}
+
+/// Interceptor that exposes the mTLS client identity to downstream handlers
+///
+/// When the connection presented a client certificate, a `ClientIdentity` is
+/// inserted into the request extensions. Plaintext requests pass through.
+#[derive(Debug, Clone, Default)]
+pub struct ClientCertInterceptor;
+
+impl Interceptor for ClientCertInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let identity = request.peer_certs().and_then(|certs| {
+            certs
+                .first()
+                .and_then(|cert| ClientIdentity::from_der(cert))
+        });
+
+        if let Some(identity) = identity {
+            info!(
+                "mTLS client authenticated: cn={:?}, san={:?}",
+                identity.common_name, identity.subject_alt_names
+            );
+            request.extensions_mut().insert(identity);
+        }
+
+        Ok(request)
+    }
+}
+
+/// `tenant_id` label of RPCs without a validated tenant
+pub const UNKNOWN_TENANT_LABEL: &str = "unknown";
+
+/// Default number of distinct tenants recorded by `RpcObservabilityLayer`
+pub const DEFAULT_MAX_RPC_TENANT_LABELS: usize = 100;
+
+/// Validated tenant of an RPC, filled in by `TenantValidationInterceptor`
+///
+/// `RpcObservabilityLayer` runs before the interceptors, so it hands this
+/// slot down in the request extensions and reads it back once the call
+/// completes.
+#[derive(Debug, Clone, Default)]
+pub struct RpcTenantLabel(Arc<std::sync::OnceLock<String>>);
+
+impl RpcTenantLabel {
+    /// Record the validated tenant; later calls are ignored
+    pub fn set(&self, tenant_id: &str) {
+        let _ = self.0.set(tenant_id.to_string());
+    }
+
+    /// Validated tenant, if any
+    pub fn get(&self) -> Option<&str> {
+        self.0.get().map(String::as_str)
+    }
+}
+
+/// Bounded set of the tenants that get their own `tenant_id` label
+#[derive(Debug)]
+struct TenantLabels {
+    seen: std::sync::Mutex<HashSet<String>>,
+    max: usize,
+}
+
+impl TenantLabels {
+    fn new(max: usize) -> Self {
+        Self {
+            seen: std::sync::Mutex::new(HashSet::new()),
+            max,
+        }
+    }
+
+    /// Label for a validated tenant: itself while under the cap, else
+    /// `OTHER_TENANT_LABEL`
+    fn label(&self, tenant_id: Option<&str>) -> String {
+        let Some(tenant_id) = tenant_id else {
+            return UNKNOWN_TENANT_LABEL.to_string();
+        };
+        let mut seen = self.seen.lock().unwrap();
+        if seen.contains(tenant_id) {
+            return tenant_id.to_string();
+        }
+        if seen.len() < self.max {
+            seen.insert(tenant_id.to_string());
+            return tenant_id.to_string();
+        }
+        OTHER_TENANT_LABEL.to_string()
+    }
+}
+
+/// Tower layer that records method, tenant, duration and gRPC status of
+/// every RPC into `AuditMetrics` and the structured access log
+///
+/// The tenant is the one validated by `TenantValidationInterceptor`; RPCs
+/// without one are recorded as `UNKNOWN_TENANT_LABEL`, and tenants beyond
+/// `max_tenant_labels` as `OTHER_TENANT_LABEL`.
+#[derive(Debug, Clone)]
+pub struct RpcObservabilityLayer {
+    metrics: Arc<RwLock<AuditMetrics>>,
+    logger: StructuredLogger,
+    tenants: Arc<TenantLabels>,
+}
+
+impl RpcObservabilityLayer {
+    /// Create a new layer recording into the given metrics
+    pub fn new(metrics: Arc<RwLock<AuditMetrics>>) -> Self {
+        Self {
+            metrics,
+            logger: StructuredLogger::default(),
+            tenants: Arc::new(TenantLabels::new(DEFAULT_MAX_RPC_TENANT_LABELS)),
+        }
+    }
+
+    /// Use a custom structured logger
+    pub fn with_logger(mut self, logger: StructuredLogger) -> Self {
+        self.logger = logger;
+        self
+    }
+
+    /// Cap the number of distinct tenants that get their own label
+    pub fn with_max_tenant_labels(mut self, max_tenant_labels: usize) -> Self {
+        self.tenants = Arc::new(TenantLabels::new(max_tenant_labels));
+        self
+    }
+}
+
+impl<S> Layer<S> for RpcObservabilityLayer {
+    type Service = RpcObservabilityService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcObservabilityService {
+            inner,
+            metrics: self.metrics.clone(),
+            logger: self.logger.clone(),
+            tenants: self.tenants.clone(),
+        }
+    }
+}
+
+/// Service produced by `RpcObservabilityLayer`
+#[derive(Debug, Clone)]
+pub struct RpcObservabilityService<S> {
+    inner: S,
+    metrics: Arc<RwLock<AuditMetrics>>,
+    logger: StructuredLogger,
+    tenants: Arc<TenantLabels>,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for RpcObservabilityService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send,
+    ReqBody: Send + 'static,
+    ResBody: Send,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<ReqBody>) -> Self::Future {
+        let method = request.uri().path().to_string();
+        let trace_id = header_value(request.headers(), "x-trace-id");
+        let tenant = RpcTenantLabel::default();
+        request.extensions_mut().insert(tenant.clone());
+
+        // Use the instance that was polled ready and leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let metrics = self.metrics.clone();
+        let logger = self.logger.clone();
+        let tenants = self.tenants.clone();
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = inner.call(request).await;
+            let duration = start.elapsed();
+
+            // Trailers-only error responses carry grpc-status in the headers;
+            // a missing status means the call completed successfully.
+            let code = match &result {
+                Ok(response) => Status::from_header_map(response.headers())
+                    .map(|status| status.code())
+                    .unwrap_or(Code::Ok),
+                Err(_) => Code::Internal,
+            };
+            let tenant_id = tenants.label(tenant.get());
+
+            metrics
+                .write()
+                .await
+                .record_rpc(&method, &tenant_id, &format!("{:?}", code), duration);
+            log_rpc(
+                &logger,
+                &method,
+                &tenant_id,
+                trace_id.as_deref(),
+                code,
+                duration,
+            );
+
+            result
+        })
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+fn log_rpc(
+    logger: &StructuredLogger,
+    method: &str,
+    tenant_id: &str,
+    trace_id: Option<&str>,
+    code: Code,
+    duration: Duration,
+) {
+    let mut context = LogContext::new()
+        .tenant_id(tenant_id)
+        .field("grpc_method", method)
+        .field("grpc_status", format!("{:?}", code))
+        .field("duration_ms", duration.as_secs_f64() * 1000.0);
+    if let Some(trace_id) = trace_id {
+        context = context.correlation_id(trace_id);
+    }
+    let context = Some(context.build());
+
+    match code {
+        Code::Ok => logger.info("gRPC call completed", context, "grpc_interceptor"),
+        Code::Internal | Code::Unknown | Code::DataLoss | Code::Unavailable => {
+            logger.error("gRPC call failed", context, "grpc_interceptor")
+        }
+        _ => logger.warn("gRPC call rejected", context, "grpc_interceptor"),
+    }
+}
+
 /// Helper function to get tenant context from anywhere in the service
 pub fn get_tenant_context() -> Option<TenantContext> {
     // This would use the global context manager
@@ -338,6 +716,118 @@ mod tests {
         assert_eq!(context.user_id, Some("test-user".to_string()));
     }
 
+    fn grpc_request(method: &str, tenant_id: &str) -> http::Request<()> {
+        http::Request::builder()
+            .uri(method)
+            .header("x-tenant-id", tenant_id)
+            .body(())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_observability_layer_records_success() {
+        let metrics = crate::metrics::create_metrics();
+        let mut service = RpcObservabilityLayer::new(metrics.clone()).layer(tower::service_fn(
+            |_req: http::Request<()>| async {
+                Ok::<_, std::convert::Infallible>(http::Response::new(()))
+            },
+        ));
+
+        let method = "/hodei.audit.AuditControlService/PublishEvent";
+        service
+            .call(grpc_request(method, "tenant-1"))
+            .await
+            .unwrap();
+
+        let metrics = metrics.read().await;
+        assert_eq!(metrics.get_rpc_count(method, "Ok"), 1);
+        assert_eq!(metrics.total_errors, 0);
+    }
+
+    #[tokio::test]
+    async fn test_observability_layer_records_error_status() {
+        let metrics = crate::metrics::create_metrics();
+        let mut service = RpcObservabilityLayer::new(metrics.clone()).layer(tower::service_fn(
+            |_req: http::Request<()>| async {
+                let response = Status::permission_denied("tenant mismatch").into_http::<()>();
+                Ok::<_, std::convert::Infallible>(response)
+            },
+        ));
+
+        let method = "/hodei.audit.AuditQueryService/QueryEvents";
+        service
+            .call(grpc_request(method, "tenant-1"))
+            .await
+            .unwrap();
+
+        let metrics = metrics.read().await;
+        assert_eq!(metrics.get_rpc_count(method, "PermissionDenied"), 1);
+        assert_eq!(metrics.get_rpc_count(method, "Ok"), 0);
+        assert_eq!(metrics.total_errors, 1);
+    }
+
+    #[tokio::test]
+    async fn test_observability_layer_labels_only_validated_tenants() {
+        let metrics = crate::metrics::create_metrics();
+        let ok = tower::service_fn(|_req: http::Request<()>| async {
+            Ok::<_, std::convert::Infallible>(http::Response::new(()))
+        });
+        let layer = RpcObservabilityLayer::new(metrics.clone()).with_max_tenant_labels(1);
+        let mut validated = layer.layer(
+            tonic::service::InterceptorLayer::new(crate::grpc::tenant_interceptor()).layer(ok),
+        );
+        let mut unvalidated = layer.layer(ok);
+
+        let method = "/hodei.audit.AuditQueryService/QueryEvents";
+        validated
+            .call(grpc_request(method, "tenant-1"))
+            .await
+            .unwrap();
+        validated
+            .call(grpc_request(method, "tenant-2"))
+            .await
+            .unwrap();
+        // Without the interceptor the header is unvalidated and never a label
+        unvalidated
+            .call(grpc_request(method, "spoofed-tenant"))
+            .await
+            .unwrap();
+
+        let metrics = metrics.read().await;
+        let tenants: HashSet<&str> = metrics
+            .rpc_calls
+            .keys()
+            .map(|labels| labels.tenant_id.as_str())
+            .collect();
+        assert_eq!(
+            tenants,
+            HashSet::from(["tenant-1", OTHER_TENANT_LABEL, UNKNOWN_TENANT_LABEL])
+        );
+    }
+
+    #[test]
+    fn test_client_identity_from_certificate() {
+        let pem = include_bytes!("../tests/fixtures/tls/client.pem");
+        let (_, pem) = x509_parser::pem::parse_x509_pem(pem).unwrap();
+
+        let identity = ClientIdentity::from_der(&pem.contents).unwrap();
+
+        assert_eq!(identity.common_name.as_deref(), Some("cap-collector"));
+        assert_eq!(
+            identity.subject_alt_names,
+            vec!["cap-collector.hodei.local"]
+        );
+    }
+
+    #[test]
+    fn test_client_cert_interceptor_passes_plaintext_requests() {
+        let mut interceptor = ClientCertInterceptor;
+
+        let request = interceptor.call(Request::new(())).unwrap();
+
+        assert!(request.extensions().get::<ClientIdentity>().is_none());
+    }
+
     #[test]
     fn test_error_conversion() {
         let error = TenantValidationError::MissingTenantId;
@@ -352,4 +842,91 @@ mod tests {
         let status: Status = error.into();
         assert_eq!(status.code(), tonic::Code::ResourceExhausted);
     }
+
+    #[tokio::test]
+    async fn test_enrichment_span_carries_ingest_tenant_baggage() {
+        use crate::distributed_tracing::{SamplingStrategy, SpanRecorder, Tracer};
+        use crate::enrichment::EventEnricher;
+        use crate::grpc::audit_control_server::AuditControlServiceImpl;
+        use crate::storage::InMemoryStorage;
+        use hodei_audit_proto::audit_control_service_client::AuditControlServiceClient;
+        use hodei_audit_proto::audit_control_service_server::AuditControlServiceServer;
+        use hodei_audit_proto::{AuditEvent, EventId, TenantId as ProtoTenantId};
+        use tonic::service::InterceptorLayer;
+
+        let recorder = Arc::new(std::sync::Mutex::new(SpanRecorder::new()));
+        let service = AuditControlServiceImpl::new()
+            .with_storage(Arc::new(InMemoryStorage::new()))
+            .with_enricher(Arc::new(EventEnricher::new()))
+            .with_tracer(
+                Tracer::new("audit-control")
+                    .with_sampling_strategy(SamplingStrategy::Always)
+                    .with_recorder(recorder.clone()),
+            );
+
+        // El servicio de control con el interceptor que instala el servidor
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            tonic::transport::Server::builder()
+                .layer(InterceptorLayer::new(crate::grpc::tenant_interceptor()))
+                .add_service(AuditControlServiceServer::new(service))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+        let mut client = AuditControlServiceClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+
+        let event = AuditEvent {
+            event_id: Some(EventId {
+                value: "event-1".to_string(),
+            }),
+            tenant_id: Some(ProtoTenantId {
+                value: "tenant-a".to_string(),
+            }),
+            ..Default::default()
+        };
+        let mut request = Request::new(tokio_stream::iter(vec![event]));
+        request
+            .metadata_mut()
+            .insert("x-tenant-id", "tenant-a".parse().unwrap());
+        request
+            .metadata_mut()
+            .insert("x-correlation-id", "req-42".parse().unwrap());
+        let summary = client
+            .ingest_event_stream(request)
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(summary.accepted, 1);
+
+        // Un tenant inválido se rechaza antes de llegar al servicio
+        let mut request = Request::new(tokio_stream::iter(Vec::<AuditEvent>::new()));
+        request
+            .metadata_mut()
+            .insert("x-tenant-id", "".parse().unwrap());
+        let status = client.ingest_event_stream(request).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        server.abort();
+
+        let recorder = recorder.lock().unwrap();
+        let ingest = recorder.find_by_name("ingest")[0].trace_state.clone();
+        let enrich_span = recorder.find_by_name("enrich")[0].clone();
+        let trace_state = &enrich_span.trace_state;
+        assert_eq!(trace_state.trace_id, ingest.trace_id);
+        assert_eq!(
+            trace_state.baggage_item(TENANT_ID_BAGGAGE),
+            Some("tenant-a")
+        );
+        assert_eq!(
+            trace_state.baggage_item(CORRELATION_ID_BAGGAGE),
+            Some("req-42")
+        );
+        assert!(
+            enrich_span
+                .attributes
+                .iter()
+                .any(|a| a.key == "baggage.tenant_id")
+        );
+    }
 }
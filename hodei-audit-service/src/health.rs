@@ -4,16 +4,24 @@
 //! - /health/live - Liveness probe
 //! - /health/ready - Readiness probe
 //! - /health/startup - Startup probe
+//! - /readyz - Readiness of every registered dependency (storage tiers,
+//!   Vector, ...) with a per-component JSON report
 
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
+use tokio::net::TcpListener;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
+use crate::storage::{StorageBackend, TieredStorage};
+use crate::vector::QueuedVectorForwarder;
+
 /// Health status
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum HealthStatus {
     Starting,
     Healthy,
@@ -68,6 +76,33 @@ impl Default for HealthCheckConfig {
     }
 }
 
+/// Health of one dependency in a readiness report
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    pub status: HealthStatus,
+    /// Whether the service can't serve traffic while this component is down
+    pub critical: bool,
+    pub message: String,
+    pub details: BTreeMap<String, String>,
+}
+
+/// Readiness of all registered dependencies, as served on `/readyz`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessReport {
+    /// `false` when any critical component is not healthy
+    pub ready: bool,
+    /// Critical components that are not healthy
+    pub failing: Vec<String>,
+    pub components: BTreeMap<String, ComponentHealth>,
+}
+
+/// Dependency registered for readiness checks
+struct ReadinessComponent {
+    name: String,
+    checker: Arc<dyn HealthChecker>,
+    critical: bool,
+}
+
 /// Health check manager
 pub struct HealthCheckManager {
     /// Configuration
@@ -78,6 +113,8 @@ pub struct HealthCheckManager {
     start_time: Instant,
     /// Health checkers
     checkers: Vec<Arc<dyn HealthChecker>>,
+    /// Dependencies reported by `/readyz`
+    components: Vec<ReadinessComponent>,
 }
 
 impl HealthCheckManager {
@@ -92,6 +129,7 @@ impl HealthCheckManager {
             status: Arc::new(RwLock::new(HealthResult::default())),
             start_time: Instant::now(),
             checkers: Vec::new(),
+            components: Vec::new(),
         }
     }
 
@@ -101,6 +139,67 @@ impl HealthCheckManager {
         self.checkers.push(checker);
     }
 
+    /// Register a dependency reported by `/readyz`
+    ///
+    /// An unhealthy critical component makes the service not ready; a
+    /// non-critical one is only reported.
+    pub fn add_component(
+        &mut self,
+        name: impl Into<String>,
+        checker: Arc<dyn HealthChecker>,
+        critical: bool,
+    ) {
+        let name = name.into();
+        info!(
+            "[Health] Adding readiness component {} (critical: {})",
+            name, critical
+        );
+        self.components.push(ReadinessComponent {
+            name,
+            checker,
+            critical,
+        });
+    }
+
+    /// Check every registered dependency
+    pub async fn readiness(&self) -> ReadinessReport {
+        let results = futures::future::join_all(
+            self.components
+                .iter()
+                .map(|component| component.checker.check()),
+        )
+        .await;
+
+        let mut failing = Vec::new();
+        let mut components = BTreeMap::new();
+        for (component, result) in self.components.iter().zip(results) {
+            if result.status != HealthStatus::Healthy {
+                warn!(
+                    "[Health] Component {} is {:?}: {}",
+                    component.name, result.status, result.message
+                );
+                if component.critical {
+                    failing.push(component.name.clone());
+                }
+            }
+            components.insert(
+                component.name.clone(),
+                ComponentHealth {
+                    status: result.status,
+                    critical: component.critical,
+                    message: result.message,
+                    details: result.details.into_iter().collect(),
+                },
+            );
+        }
+
+        ReadinessReport {
+            ready: failing.is_empty(),
+            failing,
+            components,
+        }
+    }
+
     /// Get current health status
     pub async fn get_status(&self) -> HealthResult {
         let status = self.status.read().await;
@@ -227,6 +326,45 @@ impl HealthCheckManager {
     }
 }
 
+/// Serve the readiness report of `manager` on `GET /readyz`
+///
+/// Responds 200 when every critical component is healthy and 503 otherwise.
+pub async fn serve_readyz(
+    listener: TcpListener,
+    manager: Arc<HealthCheckManager>,
+) -> std::io::Result<()> {
+    info!(
+        "[Health] Serving readiness on {}/readyz",
+        listener.local_addr()?
+    );
+    let app = axum::Router::new()
+        .route("/readyz", axum::routing::get(readyz_route))
+        .with_state(manager);
+    axum::serve(listener, app).await
+}
+
+async fn readyz_route(
+    axum::extract::State(manager): axum::extract::State<Arc<HealthCheckManager>>,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let report = manager.readiness().await;
+    let status = if report.ready {
+        axum::http::StatusCode::OK
+    } else {
+        axum::http::StatusCode::SERVICE_UNAVAILABLE
+    };
+    match serde_json::to_string(&report) {
+        Ok(body) => (
+            status,
+            [(axum::http::header::CONTENT_TYPE, "application/json")],
+            body,
+        )
+            .into_response(),
+        Err(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
 /// Live route - checks if container is alive
 #[cfg(feature = "health-server")]
 async fn live_route(
@@ -341,9 +479,110 @@ impl HealthChecker for ServiceHealthChecker {
     }
 }
 
+/// Storage health checker, reporting each tier backend
+pub struct StorageHealthChecker {
+    tiers: Vec<(String, Arc<dyn StorageBackend>)>,
+}
+
+impl StorageHealthChecker {
+    pub fn new(tiers: Vec<(String, Arc<dyn StorageBackend>)>) -> Self {
+        Self { tiers }
+    }
+
+    /// Check the ClickHouse, S3 and Glacier tiers of `storage`
+    pub fn from_tiered(storage: &TieredStorage) -> Self {
+        Self::new(
+            storage
+                .tiers()
+                .into_iter()
+                .map(|(name, backend)| (name.to_string(), backend))
+                .collect(),
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthChecker for StorageHealthChecker {
+    async fn check(&self) -> HealthResult {
+        let mut details = HashMap::new();
+        let mut unhealthy = Vec::new();
+
+        for (name, backend) in &self.tiers {
+            let state = match backend.health_check().await {
+                Ok(true) => "healthy".to_string(),
+                Ok(false) => "unhealthy".to_string(),
+                Err(e) => format!("error: {}", e),
+            };
+            if state != "healthy" {
+                unhealthy.push(name.as_str());
+            }
+            details.insert(name.clone(), state);
+        }
+
+        HealthResult {
+            status: if unhealthy.is_empty() {
+                HealthStatus::Healthy
+            } else {
+                HealthStatus::Unhealthy
+            },
+            message: if unhealthy.is_empty() {
+                "All storage tiers are healthy".to_string()
+            } else {
+                format!("Unhealthy storage tiers: {}", unhealthy.join(", "))
+            },
+            timestamp: SystemTime::now(),
+            details,
+        }
+    }
+}
+
+/// Vector forwarder connectivity checker
+pub struct VectorHealthChecker {
+    forwarder: Arc<QueuedVectorForwarder>,
+}
+
+impl VectorHealthChecker {
+    pub fn new(forwarder: Arc<QueuedVectorForwarder>) -> Self {
+        Self { forwarder }
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthChecker for VectorHealthChecker {
+    async fn check(&self) -> HealthResult {
+        let connected = self.forwarder.is_connected();
+        HealthResult {
+            status: if connected {
+                HealthStatus::Healthy
+            } else {
+                HealthStatus::Unhealthy
+            },
+            message: if connected {
+                "Connected to Vector".to_string()
+            } else {
+                "Not connected to Vector".to_string()
+            },
+            timestamp: SystemTime::now(),
+            details: HashMap::from([
+                ("connected".to_string(), connected.to_string()),
+                (
+                    "reconnects".to_string(),
+                    self.forwarder.reconnects().to_string(),
+                ),
+                (
+                    "sent_events".to_string(),
+                    self.forwarder.sent_events().to_string(),
+                ),
+            ]),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::{QueryFilter, StorageStats};
+    use hodei_audit_proto::AuditEvent;
     use std::sync::Arc;
 
     struct TestHealthChecker {
@@ -404,6 +643,102 @@ mod tests {
         assert_eq!(status.status, HealthStatus::Unhealthy);
     }
 
+    /// Storage tier whose health check reports a fixed state
+    struct FixedTier {
+        healthy: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl StorageBackend for FixedTier {
+        async fn store_event(&self, _event: &AuditEvent) -> Result<(), anyhow::Error> {
+            Ok(())
+        }
+
+        async fn store_batch(&self, _events: &[AuditEvent]) -> Result<(), anyhow::Error> {
+            Ok(())
+        }
+
+        async fn query_events(
+            &self,
+            _filter: &QueryFilter,
+        ) -> Result<Vec<AuditEvent>, anyhow::Error> {
+            Ok(vec![])
+        }
+
+        async fn count_events(&self, _filter: &QueryFilter) -> Result<u64, anyhow::Error> {
+            Ok(0)
+        }
+
+        async fn health_check(&self) -> Result<bool, anyhow::Error> {
+            Ok(self.healthy)
+        }
+
+        fn get_stats(&self) -> StorageStats {
+            StorageStats::default()
+        }
+    }
+
+    fn storage_checker(warm_healthy: bool) -> Arc<StorageHealthChecker> {
+        Arc::new(StorageHealthChecker::new(vec![
+            ("hot".to_string(), Arc::new(FixedTier { healthy: true })),
+            (
+                "warm".to_string(),
+                Arc::new(FixedTier {
+                    healthy: warm_healthy,
+                }),
+            ),
+        ]))
+    }
+
+    /// Serve `/readyz` for `manager`, returning the status and JSON body
+    async fn get_readyz(manager: HealthCheckManager) -> (u16, serde_json::Value) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve_readyz(listener, Arc::new(manager)));
+
+        let response = reqwest::get(format!("http://{}/readyz", addr))
+            .await
+            .unwrap();
+        let status = response.status().as_u16();
+        let body = response.json().await.unwrap();
+        server.abort();
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn test_readyz_reports_failing_storage_tier() {
+        let mut manager = HealthCheckManager::new(HealthCheckConfig::default());
+        manager.add_component("storage", storage_checker(false), true);
+        manager.add_component("vector", Arc::new(TestHealthChecker::new(true)), true);
+
+        let (status, body) = get_readyz(manager).await;
+
+        assert_eq!(status, 503);
+        assert_eq!(body["ready"], false);
+        assert_eq!(body["failing"], serde_json::json!(["storage"]));
+        let storage = &body["components"]["storage"];
+        assert_eq!(storage["status"], "unhealthy");
+        assert_eq!(storage["details"]["warm"], "unhealthy");
+        assert_eq!(storage["details"]["hot"], "healthy");
+        assert_eq!(body["components"]["vector"]["status"], "healthy");
+    }
+
+    #[tokio::test]
+    async fn test_readyz_ok_when_critical_components_are_healthy() {
+        let mut manager = HealthCheckManager::new(HealthCheckConfig::default());
+        manager.add_component("storage", storage_checker(true), true);
+        manager.add_component("vector", Arc::new(TestHealthChecker::new(true)), true);
+        // Non-critical components are reported without failing readiness
+        manager.add_component("search", Arc::new(TestHealthChecker::new(false)), false);
+
+        let (status, body) = get_readyz(manager).await;
+
+        assert_eq!(status, 200);
+        assert_eq!(body["ready"], true);
+        assert_eq!(body["failing"], serde_json::json!([]));
+        assert_eq!(body["components"]["search"]["status"], "unhealthy");
+    }
+
     #[tokio::test]
     async fn test_health_check_manager_multiple_checkers() {
         let mut manager = HealthCheckManager::new(HealthCheckConfig::default());
@@ -11,8 +11,11 @@ use tempfile::TempDir;
 use tokio::time::sleep;
 
 use crate::clickhouse::{ClickHouseClient, ClickHouseConfig, ClickHouseSchema};
+use crate::object_format::ObjectFormat;
 use crate::s3_storage::{CompressionType, PartitionGranularity, S3Client, S3Config};
-use crate::storage::{LifecyclePolicy, PartitionStrategy, TieredStorage, TimeGranularity};
+use crate::storage::{
+    LifecyclePolicy, PartitionStrategy, TierBasis, TieredStorage, TimeGranularity,
+};
 
 const CLICKHOUSE_IMAGE: &str = "clickhouse/clickhouse-server";
 const CLICKHOUSE_TAG: &str = "24.3";
@@ -83,6 +86,9 @@ async fn test_clickhouse_integration() -> anyhow::Result<()> {
         retry_delay_ms: 100,
         query_timeout_secs: 30,
         enable_compression: true,
+        dedup_on_event_id: false,
+        isolated_tenants: Default::default(),
+        tls_config: None,
     };
 
     let _client = ClickHouseClient::new(config.clone());
@@ -118,6 +124,8 @@ async fn test_minio_integration() -> anyhow::Result<()> {
         enable_lifecycle: true,
         transition_to_ia_days: 30,
         expire_after_days: 365,
+        tls_config: None,
+        object_format: ObjectFormat::Parquet,
     };
 
     let _client = S3Client::new(config);
@@ -169,6 +177,7 @@ async fn test_tiered_storage_integration() -> anyhow::Result<()> {
             cold_retention_days: 2555,
             auto_migrate: true,
             migration_batch_size: 1000,
+            tier_basis: TierBasis::EventTime,
         },
         partition_strategy: PartitionStrategy {
             time_granularity: TimeGranularity::Day,
@@ -209,6 +218,9 @@ async fn test_end_to_end_workflow() -> anyhow::Result<()> {
         retry_delay_ms: 100,
         query_timeout_secs: 30,
         enable_compression: true,
+        dedup_on_event_id: false,
+        isolated_tenants: Default::default(),
+        tls_config: None,
     };
 
     let _ch_schema = ClickHouseSchema::new(ch_config.clone());
@@ -229,6 +241,8 @@ async fn test_end_to_end_workflow() -> anyhow::Result<()> {
         enable_lifecycle: true,
         transition_to_ia_days: 30,
         expire_after_days: 365,
+        tls_config: None,
+        object_format: ObjectFormat::Parquet,
     };
 
     let s3_client = S3Client::new(s3_config);
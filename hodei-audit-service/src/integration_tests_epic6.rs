@@ -254,6 +254,7 @@ mod epic6_integration_tests {
             logs_dir: tmp_dir.path().to_path_buf(),
             interval_hours: 1,
             timeout_secs: 300,
+            ..Default::default()
         };
 
         let worker = DigestWorker::new(
@@ -9,14 +9,27 @@
 //! - Puerto 50054: AuditCryptoService (Criptografía/Compliance)
 //! - Puerto 50051: VectorApi (CAP → Vector communication)
 
+pub mod anomaly_detection;
 pub mod api_key;
 pub mod async_io_optimization;
+pub mod backfill;
+pub mod batch_digest;
+pub mod bloom;
 pub mod clickhouse;
 pub mod clickhouse_tuning;
+pub mod clock;
 pub mod compliance;
+pub mod consistency;
 pub mod crypto;
+pub mod dead_letter;
 pub mod distributed_tracing;
+pub mod encryption;
 pub mod enrichment;
+pub mod error;
+pub mod event_feed;
+pub mod exporters;
+pub mod field_encryption;
+pub mod fixtures;
 pub mod graceful_shutdown;
 pub mod grafana_dashboards;
 pub mod grpc;
@@ -25,17 +38,24 @@ pub mod health;
 pub mod hrn;
 pub mod integration_tests_epic6;
 pub mod key_management;
+pub mod meta_audit;
 pub mod metrics;
+pub mod normalization;
+pub mod object_format;
+pub mod outbound_tls;
 pub mod performance;
 pub mod query;
 pub mod quotas;
+pub mod routing;
 pub mod row_level_security;
 pub mod s3_storage;
+pub mod schema_registry;
 pub mod service;
 pub mod storage;
 pub mod structured_logging;
 pub mod tenant;
 pub mod vector;
+pub mod webhook;
 pub mod workers;
 pub mod zero_copy_batching;
 
@@ -43,21 +63,44 @@ pub mod zero_copy_batching;
 // mod integration_tests;  // Disabled - requires external services and testcontainers setup
 
 // Re-exports públicos
+pub use anomaly_detection::{
+    ANOMALY_METRIC_NAME, Anomaly, AnomalyDetector, AnomalyDetectorConfig, AnomalyKind,
+};
 pub use api_key::{ApiKey, ApiKeyError, ApiKeyMetadata, ApiKeyStore, ApiScope};
 pub use async_io_optimization::{
-    AsyncIoConfig, AsyncIoOptimizer, AsyncMemoryPool, AsyncMemoryPoolConfig, BatchedTaskExecutor,
+    AimdConcurrencyLimit, AsyncIoConfig, AsyncIoOptimizer, AsyncMemoryPool, AsyncMemoryPoolConfig,
+    BatchedTaskExecutor, ConcurrencyLimitConfig, PooledBuffer,
+};
+pub use backfill::{Backfill, BackfillConfig, BackfillProgress, BackfillRequest};
+pub use batch_digest::{BatchDigestSigner, BatchIntegrity, batch_digest};
+pub use clickhouse::{
+    AUDIT_EVENT_COLUMNS, ClickHouseClient, ClickHouseConfig, ClickHouseMetrics,
+    ClickHouseMetricsExporter, ClickHouseSchema, ColumnMapping, ColumnValue, HotTierTtl,
 };
-pub use clickhouse::{ClickHouseClient, ClickHouseConfig, ClickHouseMetrics, ClickHouseSchema};
 pub use clickhouse_tuning::{
-    ClickHousePerformanceTuner, ClickHouseTuningConfig, CompressionSettings, IndexType,
-    MemorySettings, MergeTreeSettings,
+    AggregationQuery, ClickHousePerformanceTuner, ClickHouseTuningConfig, CompressionSettings,
+    IndexType, MemorySettings, MergeTreeSettings, ProjectionDdl, TuningRecommendation,
+    WorkloadProfile,
 };
+pub use clock::{Clock, MockClock, SystemClock};
 pub use compliance::{
-    ComplianceError, ComplianceManager, ComplianceReport, DeletionReason, GDPRRequest,
-    GDPRRequestStatus, GDPRRequestType, LegalHold, LegalHoldStatus, RetentionPolicy,
+    ComplianceError, ComplianceManager, ComplianceReport, DeletionCertificate, DeletionReason,
+    GDPRRequest, GDPRRequestStatus, GDPRRequestType, LegalHold, LegalHoldStatus, PolicyAction,
+    PolicyAuditRecord, RetentionPolicy, RetentionPreview,
 };
+pub use consistency::{BucketReport, ConsistencyChecker, ConsistencyReport};
 pub use crypto::ports::{digest_chain, hashing, signing};
 pub use crypto::{Ed25519Signer, InMemoryDigestChain, Sha256Hasher};
+pub use dead_letter::{
+    DeadLetter, DeadLetterFilter, DeadLetterPage, DeadLetterStage, DeadLetterStore, ReplayReport,
+    ReplaySink,
+};
+pub use encryption::{EncryptionError, EnvelopeEncryptor, FileKms, InMemoryKms, KmsClient};
+pub use error::{AuditServiceError, AuditServiceResult};
+pub use field_encryption::{
+    EncryptedFieldStorage, FieldEncryptor, FieldPath, FileWrappedKeyStore, WrappedKeyStore,
+};
+pub use fixtures::{EventFixtureGenerator, FixtureConfig};
 pub use graceful_shutdown::{
     GracefulShutdown, HttpServerGracefulShutdown, ShutdownConfig, ShutdownState, ShutdownUtils,
     Shutdownable,
@@ -68,22 +111,36 @@ pub use grpc::audit_query_server;
 pub use grpc::vector_api_server;
 pub use grpc_interceptor::{AsyncTenantValidationInterceptor, TenantValidationInterceptor};
 pub use health::{
-    HealthCheckConfig, HealthCheckManager, HealthChecker, HealthResult, HealthStatus,
-    ServiceHealthChecker,
+    ComponentHealth, HealthCheckConfig, HealthCheckManager, HealthChecker, HealthResult,
+    HealthStatus, ReadinessReport, ServiceHealthChecker, StorageHealthChecker, VectorHealthChecker,
+    serve_readyz,
 };
 pub use key_management::ports::{key_manager, key_store};
 pub use key_management::{FileKeyStore, StandaloneKeyManager};
+pub use meta_audit::{AdminAction, META_AUDIT_EVENT_SOURCE, MetaAuditLogger};
+pub use normalization::{EventNormalizer, Normalization, NormalizedField};
+pub use object_format::{OBJECT_FORMAT_METADATA, ObjectFormat};
+pub use outbound_tls::{
+    APPROVED_CIPHER_SUITES, OutboundTlsConfig, OutboundTlsError, OutboundTlsResult, TlsVersion,
+};
 pub use quotas::{QuotaExceeded, QuotaManager, QuotaStatus, QuotaType, TenantQuota};
-pub use row_level_security::{RlsManager, RlsPolicy, RlsQueryBuilder, SecureQueryExecutor};
+pub use routing::{EventRouter, MirrorConfig, RouteRule, RouteTarget, RoutingConfig};
+pub use row_level_security::{
+    FieldMaskPolicy, MaskStyle, RlsManager, RlsPolicy, RlsQueryBuilder, SecureQueryExecutor,
+};
 pub use s3_storage::{
-    CompressionType, LifecyclePolicy, ParquetStats, S3Client, S3Config, S3Metrics,
+    CompressionType, LifecyclePolicy, ObjectStats, S3Client, S3Config, S3Metrics,
+};
+pub use schema_registry::{
+    SchemaError, SchemaRegistry, SchemaValidationMode, SchemaValidator, SchemaViolation,
 };
 pub use service::{HodeiAuditService, ServiceConfig, ServiceMetrics};
 pub use tenant::{TenantContext, TenantContextManager, TenantExtractor, TenantTier};
 pub use vector::{
-    VectorError, VectorForwarder, VectorForwarderConfig, VectorResult, VectorSinkConfig,
-    VectorSinkManager, VectorSinkType, create_default_sinks,
+    QueuedVectorForwarder, VectorError, VectorForwarder, VectorForwarderConfig, VectorResult,
+    VectorSinkConfig, VectorSinkManager, VectorSinkType, create_default_sinks,
 };
+pub use webhook::{Delivery, DeliveryStatus, NotificationRule, WebhookConfig, WebhookNotifier};
 pub use zero_copy_batching::{
     BatcherConfig as ZeroCopyBatcherConfig, BufferError as ZeroCopyError, BufferPool,
     BufferPoolConfig, BufferPoolStats, ZeroCopyBatch, ZeroCopyBatcher,
@@ -91,21 +148,30 @@ pub use zero_copy_batching::{
 
 #[cfg(feature = "vector-metrics")]
 pub use vector::{VectorHealthStatus, VectorMetrics, VectorMetricsCollector, VectorMetricsSummary};
+pub use workers::chain_index::{AsOfStorage, ChainIndex, ChainPosition, InMemoryChainIndex};
+pub use workers::checkpoint::{
+    CheckpointError, CheckpointSink, CheckpointStore, FileCheckpointStore, InMemoryCheckpointSink,
+    InMemoryCheckpointStore, LogCheckpointSink, ReplicaVerification, ReplicatedCheckpointSink,
+    SignedCheckpoint, WorkerCheckpoint,
+};
 pub use workers::digest_worker::{
-    DigestWorker, DigestWorkerConfig, DigestWorkerError, DigestWorkerResult,
+    CheckpointPublicationConfig, DigestWorker, DigestWorkerConfig, DigestWorkerError,
+    DigestWorkerResult, SequencedEvent,
 };
 
 // Performance optimizations
 pub use performance::{
-    BackpressureConfig, BackpressureController, BackpressureMetrics, BatchResult, BatcherConfig,
-    BatcherError, BatchingPolicy, CircuitBreaker, CircuitBreakerConfig, CircuitBreakerMetrics,
-    CircuitState, ConnectionPool, PoolConfig, PoolError, PoolStats, PooledConnection,
-    PressureLevel, SmartBatcher,
+    BackpressureConfig, BackpressureController, BackpressureMetrics, BatchHandler, BatchResult,
+    BatcherConfig, BatcherError, BatchingPolicy, CircuitBreaker, CircuitBreakerConfig,
+    CircuitBreakerMetrics, CircuitState, ConnectionPool, OverflowPolicy, PoolConfig, PoolError,
+    PoolStats, PooledConnection, PressureLevel, ShardedBatcher, ShardedBatcherConfig,
+    ShardedBatcherMetrics, SmartBatcher, TenantBatchHandler,
 };
 
 // Metrics and observability
 pub use metrics::{
-    AuditMetrics, BatchLabels, EventLabels, QueryLabels, get_metrics, register_metrics,
+    AnomalyLabels, AuditMetrics, BatchLabels, EventLabels, MetricsServerConfig, QueryLabels,
+    get_metrics, register_metrics, serve_metrics,
 };
 
 // Grafana dashboards
@@ -121,6 +187,9 @@ pub use structured_logging::{
 
 // Distributed tracing
 pub use distributed_tracing::{
-    SamplingStrategy, Span, SpanAttribute, SpanEvent, SpanKind, SpanLink, SpanRecorder, Status,
-    TraceId, TraceState, Tracer,
+    IngestStage, SamplingStrategy, Span, SpanAttribute, SpanEvent, SpanKind, SpanLink,
+    SpanRecorder, Status, TraceId, TraceState, Tracer,
 };
+
+// Live event feed
+pub use event_feed::{EventFeed, EventFeedConfig};
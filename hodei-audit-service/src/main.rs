@@ -8,6 +8,8 @@
 //! - Puerto 50053: AuditQueryService (Query/Analytics)
 //! - Puerto 50054: AuditCryptoService (Criptografía/Compliance)
 //! - Puerto 50051: VectorApi (CAP → Vector communication)
+//!
+//! Métricas Prometheus en HTTP `/metrics` (puerto 9090 por defecto)
 
 use anyhow::Result;
 use std::env;
@@ -16,7 +18,10 @@ use tracing::{error, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 // Use the library instead of redeclaring modules
-use hodei_audit_service::{grpc::GrpcConfig, grpc::run_grpc_server};
+use hodei_audit_service::{
+    MetricsServerConfig, grpc::DEFAULT_MAX_EVENT_BYTES, grpc::GrpcConfig, grpc::GrpcTlsConfig,
+    grpc::run_grpc_server, storage::StorageConfig, storage::TieredStorageConfig,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -33,6 +38,16 @@ async fn main() -> anyhow::Result<()> {
     info!("🚀 Starting Hodei Audit Service");
     info!("Version: {}", env!("CARGO_PKG_VERSION"));
 
+    // TLS opcional; con GRPC_TLS_CLIENT_CA se exige mTLS
+    let tls = match (env::var("GRPC_TLS_CERT"), env::var("GRPC_TLS_KEY")) {
+        (Ok(cert), Ok(key)) => Some(GrpcTlsConfig::from_files(
+            cert,
+            key,
+            env::var("GRPC_TLS_CLIENT_CA").ok(),
+        )?),
+        _ => None,
+    };
+
     // Cargar configuración desde variables de entorno
     let config = GrpcConfig {
         audit_control_addr: env::var("AUDIT_CONTROL_ADDR")
@@ -43,6 +58,68 @@ async fn main() -> anyhow::Result<()> {
             .unwrap_or_else(|_| "0.0.0.0:50054".to_string()),
         vector_api_addr: env::var("VECTOR_API_ADDR")
             .unwrap_or_else(|_| "0.0.0.0:50051".to_string()),
+        enable_reflection: env::var("GRPC_REFLECTION_ENABLED")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false),
+        tls,
+        // METRICS_ADDR vacío desactiva el endpoint de métricas
+        metrics: match env::var("METRICS_ADDR") {
+            Ok(addr) if addr.is_empty() => None,
+            addr => Some(MetricsServerConfig {
+                addr: addr.unwrap_or_else(|_| "0.0.0.0:9090".to_string()),
+                max_tenant_labels: env::var("METRICS_MAX_TENANT_LABELS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(100),
+            }),
+        },
+        max_event_bytes: env::var("MAX_EVENT_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_EVENT_BYTES),
+        // Estado persistente (claves del KMS y de campos cifrados)
+        data_dir: env::var("AUDIT_DATA_DIR")
+            .map(Into::into)
+            .unwrap_or_else(|_| "/tmp/hodei-audit".into()),
+        // Copias en bruto de los eventos en disco; sin la variable no se guardan
+        storage: TieredStorageConfig {
+            raw: env::var("AUDIT_RAW_EVENTS_DIR")
+                .ok()
+                .map(|root| StorageConfig::FileSystem { root: root.into() }),
+            ..Default::default()
+        },
+        // Certificados de cliente (CN o SAN, separados por comas) con permiso
+        // para las acciones administrativas
+        admin_identities: env::var("GRPC_ADMIN_IDENTITIES")
+            .map(|names| {
+                names
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        // Tenants que puede seguir con StreamEvents cada certificado de
+        // cliente: `nombre=tenant-a|tenant-b`, separados por comas
+        tenant_identities: env::var("GRPC_TENANT_IDENTITIES")
+            .map(|entries| {
+                entries
+                    .split(',')
+                    .filter_map(|entry| entry.split_once('='))
+                    .map(|(name, tenants)| {
+                        let tenants = tenants
+                            .split('|')
+                            .map(str::trim)
+                            .filter(|tenant| !tenant.is_empty())
+                            .map(String::from)
+                            .collect();
+                        (name.trim().to_string(), tenants)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ..Default::default()
     };
 
     info!("📡 gRPC Configuration:");
@@ -50,6 +127,9 @@ async fn main() -> anyhow::Result<()> {
     info!("  - AuditQuery: {}", config.audit_query_addr);
     info!("  - AuditCrypto: {}", config.audit_crypto_addr);
     info!("  - VectorApi: {}", config.vector_api_addr);
+    if let Some(metrics) = &config.metrics {
+        info!("  - Metrics: {}/metrics", metrics.addr);
+    }
 
     // Setup graceful shutdown
     let shutdown_signal = async {
@@ -0,0 +1,364 @@
+//! Meta-audit of the audit service's own admin actions
+//!
+//! Creating legal holds, approving GDPR requests, changing retention,
+//! rotating keys and forcing tier migrations are themselves recorded as
+//! `AuditEvent`s, tagged with the reserved `event_source`
+//! [`META_AUDIT_EVENT_SOURCE`], and sent into the normal ingestion pipeline.
+//! Emission is rate-limited per actor so a runaway admin client can't flood
+//! the pipeline: an actor over the limit waits for its window to free up,
+//! and a full pipeline applies back-pressure. No admin action is dropped.
+
+use hodei_audit_proto::{AuditEvent, EventId, TenantId, UserIdentity};
+use hodei_audit_types::{EventCategory, Outcome};
+use prost_types::value::Kind;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+/// Reserved `event_source` of meta-audit events
+pub const META_AUDIT_EVENT_SOURCE: &str = "hodei-audit-admin";
+
+/// Tenant of meta-audit events for actions spanning every tenant
+pub const META_AUDIT_SYSTEM_TENANT: &str = "hodei-system";
+
+/// Default number of meta-audit events per actor per window
+pub const DEFAULT_META_AUDIT_RATE_LIMIT: usize = 100;
+
+/// Default meta-audit rate limit window
+pub const DEFAULT_META_AUDIT_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Admin action on the audit service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AdminAction {
+    CreateLegalHold,
+    ApproveGdprRequest,
+    ChangeRetention,
+    RotateKey,
+    OffboardTenant,
+    TriggerLifecycleMigration,
+}
+
+impl AdminAction {
+    /// Action name recorded on the event
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdminAction::CreateLegalHold => "CreateLegalHold",
+            AdminAction::ApproveGdprRequest => "ApproveGdprRequest",
+            AdminAction::ChangeRetention => "ChangeRetention",
+            AdminAction::RotateKey => "RotateKey",
+            AdminAction::OffboardTenant => "OffboardTenant",
+            AdminAction::TriggerLifecycleMigration => "TriggerLifecycleMigration",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ActorWindow {
+    calls: VecDeque<Instant>,
+}
+
+/// Emits an `AuditEvent` for every admin action on the audit service
+#[derive(Debug, Clone)]
+pub struct MetaAuditLogger {
+    sender: mpsc::Sender<AuditEvent>,
+    rate_limit: usize,
+    rate_window: Duration,
+    actors: Arc<Mutex<HashMap<String, ActorWindow>>>,
+}
+
+impl MetaAuditLogger {
+    /// Send meta-audit events into the pipeline fed by `sender`
+    pub fn new(sender: mpsc::Sender<AuditEvent>) -> Self {
+        Self {
+            sender,
+            rate_limit: DEFAULT_META_AUDIT_RATE_LIMIT,
+            rate_window: DEFAULT_META_AUDIT_RATE_WINDOW,
+            actors: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Emit at most `max_events` per actor per `window`
+    pub fn with_rate_limit(mut self, max_events: usize, window: Duration) -> Self {
+        self.rate_limit = max_events;
+        self.rate_window = window;
+        self
+    }
+
+    /// Record `actor` performing `action` on `resource` of `tenant_id`
+    ///
+    /// Waits while the actor is over its rate limit or the pipeline is
+    /// full. Returns whether the event was handed to the pipeline, which
+    /// only fails once the pipeline is gone.
+    pub async fn log(
+        &self,
+        action: AdminAction,
+        tenant_id: &str,
+        actor: &str,
+        resource: &str,
+    ) -> bool {
+        self.log_with_details(action, tenant_id, actor, resource, &[])
+            .await
+    }
+
+    /// Like [`log`](Self::log), also recording `details` as metadata
+    pub async fn log_with_details(
+        &self,
+        action: AdminAction,
+        tenant_id: &str,
+        actor: &str,
+        resource: &str,
+        details: &[(&str, &str)],
+    ) -> bool {
+        self.emit(
+            Outcome::Success,
+            action,
+            tenant_id,
+            actor,
+            resource,
+            details,
+        )
+        .await
+    }
+
+    /// Record an admin action that failed, with `details` as metadata
+    pub async fn log_failure(
+        &self,
+        action: AdminAction,
+        tenant_id: &str,
+        actor: &str,
+        resource: &str,
+        details: &[(&str, &str)],
+    ) -> bool {
+        self.emit(
+            Outcome::Failure,
+            action,
+            tenant_id,
+            actor,
+            resource,
+            details,
+        )
+        .await
+    }
+
+    async fn emit(
+        &self,
+        outcome: Outcome,
+        action: AdminAction,
+        tenant_id: &str,
+        actor: &str,
+        resource: &str,
+        details: &[(&str, &str)],
+    ) -> bool {
+        let throttled = self.admit(actor).await;
+
+        let mut event = AuditEvent {
+            event_id: Some(EventId {
+                value: uuid::Uuid::new_v4().to_string(),
+            }),
+            tenant_id: Some(TenantId {
+                value: tenant_id.to_string(),
+            }),
+            user_identity: Some(UserIdentity {
+                user_id: actor.to_string(),
+                ..Default::default()
+            }),
+            action: action.as_str().to_string(),
+            event_category: i32::from(EventCategory::Management),
+            outcome: i32::from(outcome),
+            event_time: Some(prost_types::Timestamp::from(SystemTime::now())),
+            event_source: META_AUDIT_EVENT_SOURCE.to_string(),
+            management_event: true,
+            ..Default::default()
+        };
+        set_metadata(&mut event, "resource", resource.to_string());
+        for (key, value) in details {
+            set_metadata(&mut event, key, value.to_string());
+        }
+        if !throttled.is_zero() {
+            set_metadata(
+                &mut event,
+                "throttled_ms",
+                throttled.as_millis().to_string(),
+            );
+        }
+
+        match self.sender.send(event).await {
+            Ok(()) => true,
+            Err(_) => {
+                error!(
+                    "[MetaAudit] Pipeline closed, {} by {} on {} not recorded",
+                    action.as_str(),
+                    actor,
+                    resource
+                );
+                false
+            }
+        }
+    }
+
+    /// Account for one event of `actor`, first waiting for a free slot in
+    /// its window if it's over the limit; returns how long it waited
+    async fn admit(&self, actor: &str) -> Duration {
+        let mut waited = Duration::ZERO;
+        loop {
+            let wait = {
+                let now = Instant::now();
+                let mut actors = self.actors.lock().unwrap();
+                let window = actors.entry(actor.to_string()).or_default();
+                while window
+                    .calls
+                    .front()
+                    .is_some_and(|t| now.duration_since(*t) >= self.rate_window)
+                {
+                    window.calls.pop_front();
+                }
+                match window.calls.front() {
+                    Some(oldest) if window.calls.len() >= self.rate_limit.max(1) => {
+                        self.rate_window - now.duration_since(*oldest)
+                    }
+                    _ => {
+                        window.calls.push_back(now);
+                        return waited;
+                    }
+                }
+            };
+            warn!(
+                "[MetaAudit] Rate limit reached for actor {}, waiting {:?}",
+                actor, wait
+            );
+            tokio::time::sleep(wait).await;
+            waited += wait;
+        }
+    }
+}
+
+fn set_metadata(event: &mut AuditEvent, key: &str, value: String) {
+    event
+        .metadata
+        .get_or_insert_with(Default::default)
+        .fields
+        .insert(
+            key.to_string(),
+            prost_types::Value {
+                kind: Some(Kind::StringValue(value)),
+            },
+        );
+}
+
+/// String metadata value of a meta-audit event
+pub fn metadata_str<'a>(event: &'a AuditEvent, key: &str) -> Option<&'a str> {
+    match event.metadata.as_ref()?.fields.get(key)?.kind.as_ref()? {
+        Kind::StringValue(value) => Some(value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_log_emits_tagged_event() {
+        let (tx, mut rx) = mpsc::channel(8);
+        let logger = MetaAuditLogger::new(tx);
+
+        assert!(
+            logger
+                .log(AdminAction::RotateKey, "tenant-a", "alice", "key/tenant-a")
+                .await
+        );
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.event_source, META_AUDIT_EVENT_SOURCE);
+        assert_eq!(event.action, "RotateKey");
+        assert_eq!(event.tenant_id.unwrap().value, "tenant-a");
+        assert_eq!(event.user_identity.as_ref().unwrap().user_id, "alice");
+        assert!(event.management_event);
+    }
+
+    #[tokio::test]
+    async fn test_log_failure_records_outcome_and_details() {
+        let (tx, mut rx) = mpsc::channel(8);
+        let logger = MetaAuditLogger::new(tx);
+
+        assert!(
+            logger
+                .log_failure(
+                    AdminAction::TriggerLifecycleMigration,
+                    META_AUDIT_SYSTEM_TENANT,
+                    "ops",
+                    "migration-1",
+                    &[("reason", "maintenance"), ("error", "warm tier down")],
+                )
+                .await
+        );
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.outcome, i32::from(Outcome::Failure));
+        assert_eq!(metadata_str(&event, "reason"), Some("maintenance"));
+        assert_eq!(metadata_str(&event, "error"), Some("warm tier down"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_is_per_actor_and_delays_instead_of_dropping() {
+        let (tx, mut rx) = mpsc::channel(8);
+        let logger = MetaAuditLogger::new(tx).with_rate_limit(1, Duration::from_millis(50));
+
+        let started = Instant::now();
+        assert!(
+            logger
+                .log(AdminAction::ChangeRetention, "t", "alice", "r1")
+                .await
+        );
+        assert!(
+            logger
+                .log(AdminAction::ChangeRetention, "t", "bob", "r2")
+                .await
+        );
+        assert!(started.elapsed() < Duration::from_millis(50));
+        assert!(
+            logger
+                .log(AdminAction::ChangeRetention, "t", "alice", "r3")
+                .await
+        );
+        assert!(started.elapsed() >= Duration::from_millis(50));
+
+        let events: Vec<_> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+        let resources: Vec<_> = events
+            .iter()
+            .map(|e| metadata_str(e, "resource").unwrap())
+            .collect();
+        assert_eq!(resources, ["r1", "r2", "r3"]);
+        assert!(metadata_str(&events[2], "throttled_ms").is_some());
+        assert!(metadata_str(&events[1], "throttled_ms").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_full_pipeline_applies_back_pressure() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let logger = MetaAuditLogger::new(tx);
+        assert!(logger.log(AdminAction::RotateKey, "t", "alice", "k1").await);
+
+        let pending = tokio::spawn({
+            let logger = logger.clone();
+            async move { logger.log(AdminAction::RotateKey, "t", "alice", "k2").await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!pending.is_finished());
+
+        assert_eq!(
+            metadata_str(&rx.recv().await.unwrap(), "resource"),
+            Some("k1")
+        );
+        assert!(pending.await.unwrap());
+        assert_eq!(
+            metadata_str(&rx.recv().await.unwrap(), "resource"),
+            Some("k2")
+        );
+
+        drop(rx);
+        assert!(!logger.log(AdminAction::RotateKey, "t", "alice", "k3").await);
+    }
+}
@@ -4,13 +4,45 @@
 //! - Event counters (received, published, failed)
 //! - Batch size histograms
 //! - Processing latency measurements
+//! - Per-stage ingest latency histograms
 //! - Query duration tracking
 //! - Active connections gauge
-
-use std::collections::HashMap;
+//! - Per-RPC call counts and durations
+//!
+//! The metrics are served in the Prometheus/OpenMetrics text format on
+//! `/metrics` by [`serve_metrics`]. Only the busiest tenants keep their own
+//! `tenant_id` label; the rest are folded into [`OTHER_TENANT_LABEL`] so a
+//! large tenant population can't blow up the series count.
+
+use prometheus_client::encoding::text::encode;
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::{Histogram, exponential_buckets};
+use prometheus_client::registry::Registry;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
 use std::time::Instant;
+use tokio::net::TcpListener;
 use tokio::sync::RwLock;
+use tracing::info;
+
+/// `tenant_id` label of the tenants beyond `max_tenant_labels`
+pub const OTHER_TENANT_LABEL: &str = "other";
+
+/// Content type of the `/metrics` response
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+type Labels = Vec<(&'static str, String)>;
+type SecondsCounter = Counter<f64, AtomicU64>;
+type HistogramFamily = Family<Labels, Histogram, fn() -> Histogram>;
+
+/// Buckets (seconds) of the latency histograms
+fn latency_histogram() -> Histogram {
+    Histogram::new(exponential_buckets(0.0005, 2.0, 14))
+}
 
 /// Metric labels for event metrics
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -35,6 +67,21 @@ pub struct QueryLabels {
     pub status: String,
 }
 
+/// Metric labels for gRPC call metrics
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct RpcLabels {
+    pub method: String,
+    pub tenant_id: String,
+    pub status: String,
+}
+
+/// Metric labels for ingest anomaly metrics
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct AnomalyLabels {
+    pub tenant_id: String,
+    pub kind: String,
+}
+
 /// Event counters
 #[derive(Debug, Default, Clone)]
 pub struct EventCounters {
@@ -50,6 +97,32 @@ pub struct QueryMetrics {
     pub count: u64,
 }
 
+/// gRPC call metrics
+#[derive(Debug, Default, Clone)]
+pub struct RpcMetrics {
+    pub count: u64,
+    pub total_duration_ms: u64,
+}
+
+/// Per-stage ingest latency histograms
+///
+/// Samples are observed straight into the exported histograms, so memory
+/// stays constant however many events are ingested.
+#[derive(Debug, Clone)]
+pub struct StageLatencies {
+    histograms: HistogramFamily,
+    counts: HashMap<String, u64>,
+}
+
+impl Default for StageLatencies {
+    fn default() -> Self {
+        Self {
+            histograms: HistogramFamily::new_with_constructor(latency_histogram),
+            counts: HashMap::new(),
+        }
+    }
+}
+
 /// AuditMetrics provides comprehensive metrics collection
 #[derive(Debug, Clone, Default)]
 pub struct AuditMetrics {
@@ -59,8 +132,18 @@ pub struct AuditMetrics {
     pub batch_sizes: HashMap<BatchLabels, Vec<usize>>,
     /// Processing latency samples
     pub processing_latencies: Vec<f64>,
+    /// Latency histograms by ingest stage
+    pub stage_latencies: StageLatencies,
     /// Query duration metrics
     pub query_durations: HashMap<QueryLabels, QueryMetrics>,
+    /// gRPC call metrics by method, tenant, and status
+    pub rpc_calls: HashMap<RpcLabels, RpcMetrics>,
+    /// Ingest rate anomalies (`hodei_audit_anomaly_detected`) by tenant and kind
+    pub anomalies: HashMap<AnomalyLabels, u64>,
+    /// Events rejected at ingest for exceeding `max_event_bytes`, by tenant
+    pub oversized_events: HashMap<String, u64>,
+    /// Queries over the slow-query thresholds, by tenant
+    pub slow_queries: HashMap<String, u64>,
     /// Active connections count
     pub active_connections: u64,
     /// Total events processed
@@ -80,7 +163,12 @@ impl AuditMetrics {
             events: HashMap::new(),
             batch_sizes: HashMap::new(),
             processing_latencies: Vec::new(),
+            stage_latencies: StageLatencies::default(),
             query_durations: HashMap::new(),
+            rpc_calls: HashMap::new(),
+            anomalies: HashMap::new(),
+            oversized_events: HashMap::new(),
+            slow_queries: HashMap::new(),
             active_connections: 0,
             total_events: 0,
             total_batches: 0,
@@ -138,6 +226,24 @@ impl AuditMetrics {
         self.latency_samples.push(latency.as_secs_f64());
     }
 
+    /// Record the latency of one ingest stage (validate, enrich, ...)
+    pub fn record_stage_latency(&mut self, stage: &str, latency: std::time::Duration) {
+        self.stage_latencies
+            .histograms
+            .get_or_create(&vec![("stage", stage.to_string())])
+            .observe(latency.as_secs_f64());
+        *self
+            .stage_latencies
+            .counts
+            .entry(stage.to_string())
+            .or_default() += 1;
+    }
+
+    /// Get the number of latency samples recorded for an ingest stage
+    pub fn get_stage_latency_count(&self, stage: &str) -> usize {
+        self.stage_latencies.counts.get(stage).copied().unwrap_or(0) as usize
+    }
+
     /// Record query duration
     pub fn record_query_duration(
         &mut self,
@@ -161,6 +267,81 @@ impl AuditMetrics {
         metrics.total_duration_ms += duration.as_millis() as u64;
     }
 
+    /// Record a completed gRPC call
+    ///
+    /// Any status other than `Ok` also counts towards `total_errors`.
+    pub fn record_rpc(
+        &mut self,
+        method: &str,
+        tenant_id: &str,
+        status: &str,
+        duration: std::time::Duration,
+    ) {
+        let labels = RpcLabels {
+            method: method.to_string(),
+            tenant_id: tenant_id.to_string(),
+            status: status.to_string(),
+        };
+
+        let metrics = self.rpc_calls.entry(labels).or_default();
+        metrics.count += 1;
+        metrics.total_duration_ms += duration.as_millis() as u64;
+
+        if status != "Ok" {
+            self.total_errors += 1;
+        }
+    }
+
+    /// Get the number of gRPC calls for a method and status across all tenants
+    pub fn get_rpc_count(&self, method: &str, status: &str) -> u64 {
+        self.rpc_calls
+            .iter()
+            .filter(|(labels, _)| labels.method == method && labels.status == status)
+            .map(|(_, metrics)| metrics.count)
+            .sum()
+    }
+
+    /// Record a detected ingest rate anomaly
+    pub fn record_anomaly(&mut self, tenant_id: &str, kind: &str) {
+        let labels = AnomalyLabels {
+            tenant_id: tenant_id.to_string(),
+            kind: kind.to_string(),
+        };
+        *self.anomalies.entry(labels).or_default() += 1;
+    }
+
+    /// Get the number of anomalies detected for a tenant across all kinds
+    pub fn get_anomaly_count(&self, tenant_id: &str) -> u64 {
+        self.anomalies
+            .iter()
+            .filter(|(labels, _)| labels.tenant_id == tenant_id)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Record an event rejected for exceeding the ingest size limit
+    pub fn record_oversized_event(&mut self, tenant_id: &str) {
+        *self
+            .oversized_events
+            .entry(tenant_id.to_string())
+            .or_default() += 1;
+    }
+
+    /// Get the number of oversized events rejected for a tenant
+    pub fn get_oversized_event_count(&self, tenant_id: &str) -> u64 {
+        self.oversized_events.get(tenant_id).copied().unwrap_or(0)
+    }
+
+    /// Record a query over the slow-query thresholds
+    pub fn record_slow_query(&mut self, tenant_id: &str) {
+        *self.slow_queries.entry(tenant_id.to_string()).or_default() += 1;
+    }
+
+    /// Get the number of slow queries of a tenant
+    pub fn get_slow_query_count(&self, tenant_id: &str) -> u64 {
+        self.slow_queries.get(tenant_id).copied().unwrap_or(0)
+    }
+
     /// Update active connections gauge
     pub fn set_active_connections(&mut self, count: u64) {
         self.active_connections = count;
@@ -207,6 +388,212 @@ impl AuditMetrics {
         }
     }
 
+    /// Tenants by activity, busiest first
+    fn tenants_by_volume(&self) -> Vec<String> {
+        let mut volume: HashMap<&str, u64> = HashMap::new();
+        for (labels, counters) in &self.events {
+            *volume.entry(&labels.tenant_id).or_default() +=
+                counters.received + counters.published + counters.failed;
+        }
+        for (labels, sizes) in &self.batch_sizes {
+            *volume.entry(&labels.tenant_id).or_default() += sizes.len() as u64;
+        }
+        for (labels, metrics) in &self.query_durations {
+            *volume.entry(&labels.tenant_id).or_default() += metrics.count;
+        }
+        for (labels, metrics) in &self.rpc_calls {
+            *volume.entry(&labels.tenant_id).or_default() += metrics.count;
+        }
+        for (labels, count) in &self.anomalies {
+            *volume.entry(&labels.tenant_id).or_default() += count;
+        }
+        for (tenant_id, count) in &self.oversized_events {
+            *volume.entry(tenant_id).or_default() += count;
+        }
+        for (tenant_id, count) in &self.slow_queries {
+            *volume.entry(tenant_id).or_default() += count;
+        }
+
+        let mut tenants: Vec<(&str, u64)> = volume.into_iter().collect();
+        tenants.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        tenants.into_iter().map(|(t, _)| t.to_string()).collect()
+    }
+
+    /// Register a snapshot of these metrics in a Prometheus registry
+    ///
+    /// Only the `max_tenant_labels` busiest tenants get their own
+    /// `tenant_id` label; the others are aggregated under
+    /// [`OTHER_TENANT_LABEL`].
+    pub fn register_snapshot(&self, registry: &mut Registry, max_tenant_labels: usize) {
+        let kept: HashSet<String> = self
+            .tenants_by_volume()
+            .into_iter()
+            .take(max_tenant_labels)
+            .collect();
+        let tenant = |tenant_id: &str| -> String {
+            if kept.contains(tenant_id) {
+                tenant_id.to_string()
+            } else {
+                OTHER_TENANT_LABEL.to_string()
+            }
+        };
+
+        let events = Family::<Labels, Counter>::default();
+        for (labels, counters) in &self.events {
+            events
+                .get_or_create(&vec![
+                    ("event_type", labels.event_type.clone()),
+                    ("tenant_id", tenant(&labels.tenant_id)),
+                    ("status", labels.status.clone()),
+                ])
+                .inc_by(counters.received + counters.published + counters.failed);
+        }
+        registry.register(
+            "hodei_audit_events",
+            "Audit events by type, tenant and status",
+            events,
+        );
+
+        let batch_sizes =
+            Family::<Labels, Histogram, fn() -> Histogram>::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(1.0, 4.0, 10))
+            });
+        for (labels, sizes) in &self.batch_sizes {
+            let histogram = batch_sizes.get_or_create(&vec![
+                ("tenant_id", tenant(&labels.tenant_id)),
+                ("batch_type", labels.batch_type.clone()),
+            ]);
+            for size in sizes {
+                histogram.observe(*size as f64);
+            }
+        }
+        registry.register("hodei_audit_batch_size", "Events per batch", batch_sizes);
+
+        let latency = latency_histogram();
+        for seconds in &self.processing_latencies {
+            latency.observe(*seconds);
+        }
+        registry.register(
+            "hodei_audit_processing_latency_seconds",
+            "Event processing latency",
+            latency,
+        );
+
+        registry.register(
+            "hodei_audit_stage_latency_seconds",
+            "Ingest latency by pipeline stage",
+            self.stage_latencies.histograms.clone(),
+        );
+
+        let queries = Family::<Labels, Counter>::default();
+        let query_seconds = Family::<Labels, SecondsCounter>::default();
+        for (labels, metrics) in &self.query_durations {
+            let labels = vec![
+                ("query_type", labels.query_type.clone()),
+                ("tenant_id", tenant(&labels.tenant_id)),
+                ("status", labels.status.clone()),
+            ];
+            queries.get_or_create(&labels).inc_by(metrics.count);
+            query_seconds
+                .get_or_create(&labels)
+                .inc_by(metrics.total_duration_ms as f64 / 1000.0);
+        }
+        registry.register(
+            "hodei_audit_queries",
+            "Queries by type, tenant and status",
+            queries,
+        );
+        registry.register(
+            "hodei_audit_query_duration_seconds",
+            "Time spent running queries",
+            query_seconds,
+        );
+
+        let rpc_calls = Family::<Labels, Counter>::default();
+        let rpc_seconds = Family::<Labels, SecondsCounter>::default();
+        for (labels, metrics) in &self.rpc_calls {
+            let labels = vec![
+                ("method", labels.method.clone()),
+                ("tenant_id", tenant(&labels.tenant_id)),
+                ("status", labels.status.clone()),
+            ];
+            rpc_calls.get_or_create(&labels).inc_by(metrics.count);
+            rpc_seconds
+                .get_or_create(&labels)
+                .inc_by(metrics.total_duration_ms as f64 / 1000.0);
+        }
+        registry.register(
+            "hodei_audit_rpc_calls",
+            "gRPC calls by method, tenant and status",
+            rpc_calls,
+        );
+        registry.register(
+            "hodei_audit_rpc_duration_seconds",
+            "Time spent serving gRPC calls",
+            rpc_seconds,
+        );
+
+        let anomalies = Family::<Labels, Counter>::default();
+        for (labels, count) in &self.anomalies {
+            anomalies
+                .get_or_create(&vec![
+                    ("tenant_id", tenant(&labels.tenant_id)),
+                    ("kind", labels.kind.clone()),
+                ])
+                .inc_by(*count);
+        }
+        registry.register(
+            "hodei_audit_anomaly_detected",
+            "Ingest rate anomalies by tenant and kind",
+            anomalies,
+        );
+
+        let oversized = Family::<Labels, Counter>::default();
+        for (tenant_id, count) in &self.oversized_events {
+            oversized
+                .get_or_create(&vec![("tenant_id", tenant(tenant_id))])
+                .inc_by(*count);
+        }
+        registry.register(
+            "hodei_audit_oversized_events_rejected",
+            "Events rejected at ingest for exceeding the size limit",
+            oversized,
+        );
+
+        let slow_queries = Family::<Labels, Counter>::default();
+        for (tenant_id, count) in &self.slow_queries {
+            slow_queries
+                .get_or_create(&vec![("tenant_id", tenant(tenant_id))])
+                .inc_by(*count);
+        }
+        registry.register(
+            "hodei_audit_slow_queries",
+            "Queries over the slow-query duration or cost thresholds",
+            slow_queries,
+        );
+
+        let active_connections = Gauge::<i64>::default();
+        active_connections.set(self.active_connections as i64);
+        registry.register(
+            "hodei_audit_active_connections",
+            "Open client connections",
+            active_connections,
+        );
+
+        let errors = Counter::<u64>::default();
+        errors.inc_by(self.total_errors);
+        registry.register("hodei_audit_errors", "Failed events and gRPC calls", errors);
+    }
+
+    /// Render these metrics in the OpenMetrics text exposition format
+    pub fn encode_prometheus(&self, max_tenant_labels: usize) -> Result<String, std::fmt::Error> {
+        let mut registry = Registry::default();
+        self.register_snapshot(&mut registry, max_tenant_labels);
+        let mut body = String::new();
+        encode(&mut body, &registry)?;
+        Ok(body)
+    }
+
     /// Get events per second (simplified)
     pub fn get_events_per_second(&self) -> f64 {
         if self.processing_latencies.is_empty() {
@@ -233,6 +620,69 @@ pub fn get_metrics() -> Arc<RwLock<AuditMetrics>> {
     create_metrics()
 }
 
+/// Configuration of the `/metrics` HTTP endpoint
+#[derive(Debug, Clone)]
+pub struct MetricsServerConfig {
+    /// Address to listen on
+    pub addr: String,
+    /// Tenants that keep their own `tenant_id` label
+    pub max_tenant_labels: usize,
+}
+
+impl Default for MetricsServerConfig {
+    fn default() -> Self {
+        Self {
+            addr: "0.0.0.0:9090".to_string(),
+            max_tenant_labels: 100,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct MetricsState {
+    metrics: Arc<RwLock<AuditMetrics>>,
+    max_tenant_labels: usize,
+}
+
+/// Serve `metrics` on `GET /metrics` for Prometheus scraping
+pub async fn serve_metrics(
+    listener: TcpListener,
+    metrics: Arc<RwLock<AuditMetrics>>,
+    max_tenant_labels: usize,
+) -> std::io::Result<()> {
+    info!(
+        "[Metrics] Serving Prometheus metrics on {}/metrics",
+        listener.local_addr()?
+    );
+    let app = axum::Router::new()
+        .route("/metrics", axum::routing::get(metrics_route))
+        .with_state(MetricsState {
+            metrics,
+            max_tenant_labels,
+        });
+    axum::serve(listener, app).await
+}
+
+async fn metrics_route(
+    axum::extract::State(state): axum::extract::State<MetricsState>,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let encoded = state
+        .metrics
+        .read()
+        .await
+        .encode_prometheus(state.max_tenant_labels);
+    match encoded {
+        Ok(body) => (
+            [(axum::http::header::CONTENT_TYPE, OPENMETRICS_CONTENT_TYPE)],
+            body,
+        )
+            .into_response(),
+        Err(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
 /// Register metrics with Prometheus registry (simplified stub)
 pub fn register_metrics(
     _registry: &mut prometheus_client::registry::Registry,
@@ -289,6 +739,23 @@ mod tests {
         assert_eq!(metrics.processing_latencies.len(), 1);
     }
 
+    #[test]
+    fn test_stage_latencies_are_exported_by_stage() {
+        let mut metrics = AuditMetrics::new();
+        metrics.record_stage_latency("enrich", std::time::Duration::from_millis(2));
+        metrics.record_stage_latency("store", std::time::Duration::from_millis(40));
+        metrics.record_stage_latency("store", std::time::Duration::from_millis(60));
+        assert_eq!(metrics.get_stage_latency_count("store"), 2);
+
+        let body = metrics.encode_prometheus(10).unwrap();
+        assert!(body.contains("hodei_audit_stage_latency_seconds_count{stage=\"store\"} 2"));
+        assert!(body.contains("hodei_audit_stage_latency_seconds_count{stage=\"enrich\"} 1"));
+
+        // Scraping again doesn't replay the samples
+        let body = metrics.encode_prometheus(10).unwrap();
+        assert!(body.contains("hodei_audit_stage_latency_seconds_count{stage=\"store\"} 2"));
+    }
+
     #[tokio::test]
     async fn test_record_query_duration() {
         let mut metrics = AuditMetrics::new();
@@ -307,6 +774,35 @@ mod tests {
         assert_eq!(query_metrics.unwrap().count, 1);
     }
 
+    #[tokio::test]
+    async fn test_record_rpc() {
+        let mut metrics = AuditMetrics::new();
+        let method = "/hodei.audit.AuditQueryService/QueryEvents";
+
+        metrics.record_rpc(
+            method,
+            "tenant_1",
+            "Ok",
+            std::time::Duration::from_millis(5),
+        );
+        metrics.record_rpc(
+            method,
+            "tenant_2",
+            "Ok",
+            std::time::Duration::from_millis(5),
+        );
+        metrics.record_rpc(
+            method,
+            "tenant_1",
+            "NotFound",
+            std::time::Duration::from_millis(1),
+        );
+
+        assert_eq!(metrics.get_rpc_count(method, "Ok"), 2);
+        assert_eq!(metrics.get_rpc_count(method, "NotFound"), 1);
+        assert_eq!(metrics.total_errors, 1);
+    }
+
     #[tokio::test]
     async fn test_active_connections() {
         let mut metrics = AuditMetrics::new();
@@ -349,4 +845,78 @@ mod tests {
         assert!(avg.is_some());
         assert!((avg.unwrap() - 0.02).abs() < 0.001);
     }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_serves_exposition_format() {
+        let metrics = create_metrics();
+        {
+            let mut metrics = metrics.write().await;
+            metrics.increment_event("login", "tenant_1", "received");
+            metrics.record_batch_size(10, "tenant_1", "event_batch");
+            metrics.record_rpc(
+                "/hodei.audit.AuditControlService/PublishEvent",
+                "tenant_1",
+                "Ok",
+                std::time::Duration::from_millis(3),
+            );
+            metrics.set_active_connections(2);
+            metrics.record_slow_query("tenant_1");
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve_metrics(listener, metrics, 10));
+
+        let response = reqwest::get(format!("http://{}/metrics", addr))
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(response.headers()["content-type"], OPENMETRICS_CONTENT_TYPE);
+        let body = response.text().await.unwrap();
+        server.abort();
+
+        for expected in [
+            "# TYPE hodei_audit_events counter",
+            "hodei_audit_events_total{event_type=\"login\",tenant_id=\"tenant_1\",status=\"received\"} 1",
+            "# TYPE hodei_audit_batch_size histogram",
+            "hodei_audit_rpc_calls_total{",
+            "hodei_audit_active_connections 2",
+            "hodei_audit_slow_queries_total{tenant_id=\"tenant_1\"} 1",
+        ] {
+            assert!(
+                body.contains(expected),
+                "missing {:?} in:\n{}",
+                expected,
+                body
+            );
+        }
+        assert!(body.ends_with("# EOF\n"));
+        for line in body.lines().filter(|line| !line.starts_with('#')) {
+            let (series, value) = line.rsplit_once(' ').unwrap();
+            assert!(series.starts_with("hodei_audit_"), "bad series {:?}", line);
+            assert!(value.parse::<f64>().is_ok(), "bad value {:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_tenant_labels_are_capped() {
+        let mut metrics = AuditMetrics::new();
+        for (tenant, events) in [
+            ("tenant_a", 5),
+            ("tenant_b", 3),
+            ("tenant_c", 1),
+            ("tenant_d", 1),
+        ] {
+            for _ in 0..events {
+                metrics.increment_event("login", tenant, "received");
+            }
+        }
+
+        let body = metrics.encode_prometheus(2).unwrap();
+        assert!(body.contains("tenant_id=\"tenant_a\",status=\"received\"} 5"));
+        assert!(body.contains("tenant_id=\"tenant_b\",status=\"received\"} 3"));
+        assert!(body.contains("tenant_id=\"other\",status=\"received\"} 2"));
+        assert!(!body.contains("tenant_c"));
+        assert!(!body.contains("tenant_d"));
+    }
 }
@@ -0,0 +1,203 @@
+//! Event Normalization
+//!
+//! Clients don't agree on casing and whitespace, so the same action shows up
+//! as `GET`, `get` and `get ` and splits group-by aggregations. The
+//! normalizer rewrites configured string fields of an event after enrichment
+//! and before storage. It is opt-in per field: fields without a rule, which
+//! may hold case-sensitive values, are never touched.
+
+use hodei_audit_proto::AuditEvent;
+use prost_types::value::Kind;
+
+/// Rewrites applied to a normalized field
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Normalization {
+    /// Strip leading and trailing whitespace
+    pub trim: bool,
+    /// Lowercase the value
+    pub lowercase: bool,
+    /// Replace every run of whitespace with a single space
+    pub collapse_whitespace: bool,
+}
+
+impl Normalization {
+    /// Trim, lowercase and collapse whitespace
+    pub fn all() -> Self {
+        Self {
+            trim: true,
+            lowercase: true,
+            collapse_whitespace: true,
+        }
+    }
+
+    /// Normalized form of `value`
+    pub fn apply(&self, value: &str) -> String {
+        let mut value = if self.collapse_whitespace {
+            let mut collapsed = String::with_capacity(value.len());
+            let mut in_whitespace = false;
+            for c in value.chars() {
+                if c.is_whitespace() {
+                    if !in_whitespace {
+                        collapsed.push(' ');
+                    }
+                    in_whitespace = true;
+                } else {
+                    collapsed.push(c);
+                    in_whitespace = false;
+                }
+            }
+            collapsed
+        } else {
+            value.to_string()
+        };
+        if self.trim {
+            value = value.trim().to_string();
+        }
+        if self.lowercase {
+            value = value.to_lowercase();
+        }
+        value
+    }
+}
+
+/// Path of a normalizable string field of [`AuditEvent`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NormalizedField {
+    Action,
+    EventSource,
+    ErrorCode,
+    UserId,
+    Username,
+    Email,
+    /// String value under a key of the event metadata
+    Metadata(String),
+}
+
+impl NormalizedField {
+    /// Parse a dotted path such as `action` or `metadata.http_method`
+    pub fn parse(path: &str) -> Result<Self, anyhow::Error> {
+        match path {
+            "action" => Ok(Self::Action),
+            "event_source" => Ok(Self::EventSource),
+            "error_code" => Ok(Self::ErrorCode),
+            "user_identity.user_id" => Ok(Self::UserId),
+            "user_identity.username" => Ok(Self::Username),
+            "user_identity.email" => Ok(Self::Email),
+            _ => match path.strip_prefix("metadata.") {
+                Some(key) if !key.is_empty() => Ok(Self::Metadata(key.to_string())),
+                _ => Err(anyhow::anyhow!("Unknown normalizable field: {}", path)),
+            },
+        }
+    }
+
+    /// The field value, if the event has it
+    fn value_mut<'a>(&self, event: &'a mut AuditEvent) -> Option<&'a mut String> {
+        match self {
+            Self::Action => Some(&mut event.action),
+            Self::EventSource => Some(&mut event.event_source),
+            Self::ErrorCode => Some(&mut event.error_code),
+            Self::UserId => event.user_identity.as_mut().map(|u| &mut u.user_id),
+            Self::Username => event.user_identity.as_mut().map(|u| &mut u.username),
+            Self::Email => event.user_identity.as_mut().map(|u| &mut u.email),
+            Self::Metadata(key) => match event
+                .metadata
+                .as_mut()?
+                .fields
+                .get_mut(key)?
+                .kind
+                .as_mut()?
+            {
+                Kind::StringValue(value) => Some(value),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Normalizes the configured fields of events before storage
+#[derive(Debug, Clone, Default)]
+pub struct EventNormalizer {
+    rules: Vec<(NormalizedField, Normalization)>,
+}
+
+impl EventNormalizer {
+    /// Normalizer without rules; it leaves events untouched
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Normalize the field at `path` with `normalization`, replacing any
+    /// earlier rule for it
+    pub fn with_field(
+        mut self,
+        path: &str,
+        normalization: Normalization,
+    ) -> Result<Self, anyhow::Error> {
+        let field = NormalizedField::parse(path)?;
+        self.rules.retain(|(f, _)| *f != field);
+        self.rules.push((field, normalization));
+        Ok(self)
+    }
+
+    /// Fields this normalizer rewrites
+    pub fn fields(&self) -> impl Iterator<Item = &NormalizedField> {
+        self.rules.iter().map(|(field, _)| field)
+    }
+
+    /// Normalize the configured fields of `event` in place
+    pub fn normalize(&self, event: &mut AuditEvent) {
+        for (field, normalization) in &self.rules {
+            if let Some(value) = field.value_mut(event) {
+                *value = normalization.apply(value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_configured_fields_are_normalized() {
+        let normalizer = EventNormalizer::new()
+            .with_field(
+                "metadata.http_method",
+                Normalization {
+                    trim: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .with_field("action", Normalization::all())
+            .unwrap();
+        assert!(
+            EventNormalizer::new()
+                .with_field("event_time", Normalization::all())
+                .is_err()
+        );
+
+        let mut event = AuditEvent {
+            action: "  List\tPolicy   Stores ".to_string(),
+            event_source: " Verified-Permissions ".to_string(),
+            metadata: Some(prost_types::Struct {
+                fields: [(
+                    "http_method".to_string(),
+                    prost_types::Value {
+                        kind: Some(Kind::StringValue(" GET ".to_string())),
+                    },
+                )]
+                .into(),
+            }),
+            ..Default::default()
+        };
+        normalizer.normalize(&mut event);
+
+        assert_eq!(event.action, "list policy stores");
+        assert_eq!(event.event_source, " Verified-Permissions ");
+        assert_eq!(
+            event.metadata.unwrap().fields["http_method"].kind,
+            Some(Kind::StringValue("GET".to_string()))
+        );
+    }
+}
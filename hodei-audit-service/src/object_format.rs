@@ -0,0 +1,436 @@
+//! Warm-Tier Object Formats
+//!
+//! Objects in S3 are written as Parquet by default, but some downstream
+//! analytics pipelines expect Avro or newline-delimited JSON. The format is
+//! chosen per deployment in `S3Config` and recorded in each object's
+//! metadata, so objects written before a format change stay readable.
+//!
+//! NDJSON and Avro rows share one flat record layout mirroring the
+//! ClickHouse columns: the protobuf metadata `Struct` is stored as a JSON
+//! string and unsigned integers as signed 64-bit values.
+
+use apache_avro::{Reader, Schema, Writer};
+use hodei_audit_proto::metadata::{json_to_value, value_to_json};
+use hodei_audit_proto::{AuditEvent, EventId, Hrn, HttpContext, TenantId, UserIdentity};
+use prost::Message;
+use prost_types::value::Kind;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+/// Object metadata key holding the format the object was written in
+pub const OBJECT_FORMAT_METADATA: &str = "x-amz-meta-hodei-object-format";
+
+/// Serialization format of warm-tier objects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ObjectFormat {
+    #[default]
+    Parquet,
+    /// Newline-delimited JSON, one event per line
+    NdJson,
+    /// Avro object container file
+    Avro,
+}
+
+impl ObjectFormat {
+    /// Name of the format, as stored in object metadata
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ObjectFormat::Parquet => "parquet",
+            ObjectFormat::NdJson => "ndjson",
+            ObjectFormat::Avro => "avro",
+        }
+    }
+
+    /// File extension of object keys written in this format
+    pub fn extension(self) -> &'static str {
+        self.as_str()
+    }
+
+    /// Serialize a batch of events into an object body
+    pub fn encode(self, events: &[AuditEvent]) -> Result<Vec<u8>, anyhow::Error> {
+        match self {
+            ObjectFormat::Parquet => Ok(encode_parquet(events)),
+            ObjectFormat::NdJson => {
+                let mut body = Vec::new();
+                for event in events {
+                    serde_json::to_writer(&mut body, &EventRecord::from(event))?;
+                    body.push(b'\n');
+                }
+                Ok(body)
+            }
+            ObjectFormat::Avro => {
+                let mut writer = Writer::new(&AVRO_SCHEMA, Vec::new())?;
+                for event in events {
+                    writer.append_ser(EventRecord::from(event))?;
+                }
+                Ok(writer.into_inner()?)
+            }
+        }
+    }
+
+    /// Deserialize the events of an object body
+    pub fn decode(self, body: &[u8]) -> Result<Vec<AuditEvent>, anyhow::Error> {
+        match self {
+            ObjectFormat::Parquet => decode_parquet(body),
+            ObjectFormat::NdJson => body
+                .split(|byte| *byte == b'\n')
+                .filter(|line| !line.is_empty())
+                .map(|line| serde_json::from_slice::<EventRecord>(line)?.try_into())
+                .collect(),
+            ObjectFormat::Avro => Reader::new(body)?
+                .map(|value| apache_avro::from_value::<EventRecord>(&value?)?.try_into())
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for ObjectFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ObjectFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "parquet" => Ok(ObjectFormat::Parquet),
+            "ndjson" => Ok(ObjectFormat::NdJson),
+            "avro" => Ok(ObjectFormat::Avro),
+            other => Err(anyhow::anyhow!("Unknown object format: {}", other)),
+        }
+    }
+}
+
+/// Encode events as the body of a simulated Parquet object
+fn encode_parquet(events: &[AuditEvent]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for event in events {
+        event
+            .encode_length_delimited(&mut body)
+            .expect("Vec has unbounded capacity");
+    }
+    body
+}
+
+/// Decode the events of a simulated Parquet object
+fn decode_parquet(mut body: &[u8]) -> Result<Vec<AuditEvent>, anyhow::Error> {
+    let mut events = Vec::new();
+    while !body.is_empty() {
+        events.push(AuditEvent::decode_length_delimited(&mut body)?);
+    }
+    Ok(events)
+}
+
+/// Avro schema of [`EventRecord`]
+static AVRO_SCHEMA: LazyLock<Schema> = LazyLock::new(|| {
+    Schema::parse_str(AVRO_SCHEMA_JSON).expect("audit event Avro schema is valid")
+});
+
+const AVRO_SCHEMA_JSON: &str = r#"{
+  "type": "record",
+  "name": "AuditEvent",
+  "namespace": "hodei.audit",
+  "fields": [
+    {"name": "event_id", "type": ["null", "string"], "default": null},
+    {"name": "tenant_id", "type": ["null", "string"], "default": null},
+    {"name": "hrn", "type": ["null", {
+      "type": "record",
+      "name": "Hrn",
+      "fields": [
+        {"name": "partition", "type": "string"},
+        {"name": "service", "type": "string"},
+        {"name": "tenant_id", "type": "string"},
+        {"name": "region", "type": "string"},
+        {"name": "resource_type", "type": "string"},
+        {"name": "resource_path", "type": "string"}
+      ]
+    }], "default": null},
+    {"name": "user_identity", "type": ["null", {
+      "type": "record",
+      "name": "UserIdentity",
+      "fields": [
+        {"name": "user_id", "type": "string"},
+        {"name": "username", "type": "string"},
+        {"name": "email", "type": "string"},
+        {"name": "roles", "type": {"type": "array", "items": "string"}},
+        {"name": "tenant_id", "type": "string"}
+      ]
+    }], "default": null},
+    {"name": "http_context", "type": ["null", {
+      "type": "record",
+      "name": "HttpContext",
+      "fields": [
+        {"name": "method", "type": "string"},
+        {"name": "path", "type": "string"},
+        {"name": "user_agent", "type": "string"},
+        {"name": "source_ip", "type": "string"},
+        {"name": "status_code", "type": "int"},
+        {"name": "content_length", "type": "long"}
+      ]
+    }], "default": null},
+    {"name": "action", "type": "string"},
+    {"name": "event_category", "type": "int"},
+    {"name": "management_type", "type": "int"},
+    {"name": "access_type", "type": "int"},
+    {"name": "read_only", "type": "boolean"},
+    {"name": "outcome", "type": "int"},
+    {"name": "error_code", "type": "string"},
+    {"name": "error_message", "type": "string"},
+    {"name": "event_time", "type": ["null", {
+      "type": "record",
+      "name": "Timestamp",
+      "fields": [
+        {"name": "seconds", "type": "long"},
+        {"name": "nanos", "type": "int"}
+      ]
+    }], "default": null},
+    {"name": "processed_at", "type": ["null", "Timestamp"], "default": null},
+    {"name": "latency_ms", "type": "long"},
+    {"name": "metadata_json", "type": ["null", "string"], "default": null},
+    {"name": "correlation_id", "type": "string"},
+    {"name": "trace_id", "type": "string"},
+    {"name": "span_id", "type": "string"},
+    {"name": "event_source", "type": "string"},
+    {"name": "event_version", "type": "string"},
+    {"name": "management_event", "type": "boolean"},
+    {"name": "enriched", "type": "boolean"}
+  ]
+}"#;
+
+/// Row layout of NDJSON and Avro objects
+#[derive(Debug, Serialize, Deserialize)]
+struct EventRecord {
+    event_id: Option<String>,
+    tenant_id: Option<String>,
+    hrn: Option<HrnRecord>,
+    user_identity: Option<UserIdentityRecord>,
+    http_context: Option<HttpContextRecord>,
+    action: String,
+    event_category: i32,
+    management_type: i32,
+    access_type: i32,
+    read_only: bool,
+    outcome: i32,
+    error_code: String,
+    error_message: String,
+    event_time: Option<TimestampRecord>,
+    processed_at: Option<TimestampRecord>,
+    latency_ms: i64,
+    metadata_json: Option<String>,
+    correlation_id: String,
+    trace_id: String,
+    span_id: String,
+    event_source: String,
+    event_version: String,
+    management_event: bool,
+    enriched: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HrnRecord {
+    partition: String,
+    service: String,
+    tenant_id: String,
+    region: String,
+    resource_type: String,
+    resource_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct UserIdentityRecord {
+    user_id: String,
+    username: String,
+    email: String,
+    roles: Vec<String>,
+    tenant_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HttpContextRecord {
+    method: String,
+    path: String,
+    user_agent: String,
+    source_ip: String,
+    status_code: i32,
+    content_length: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TimestampRecord {
+    seconds: i64,
+    nanos: i32,
+}
+
+impl From<&AuditEvent> for EventRecord {
+    fn from(event: &AuditEvent) -> Self {
+        Self {
+            event_id: event.event_id.as_ref().map(|id| id.value.clone()),
+            tenant_id: event.tenant_id.as_ref().map(|id| id.value.clone()),
+            hrn: event.hrn.as_ref().map(|hrn| HrnRecord {
+                partition: hrn.partition.clone(),
+                service: hrn.service.clone(),
+                tenant_id: hrn.tenant_id.clone(),
+                region: hrn.region.clone(),
+                resource_type: hrn.resource_type.clone(),
+                resource_path: hrn.resource_path.clone(),
+            }),
+            user_identity: event.user_identity.as_ref().map(|user| UserIdentityRecord {
+                user_id: user.user_id.clone(),
+                username: user.username.clone(),
+                email: user.email.clone(),
+                roles: user.roles.clone(),
+                tenant_id: user.tenant_id.clone(),
+            }),
+            http_context: event.http_context.as_ref().map(|http| HttpContextRecord {
+                method: http.method.clone(),
+                path: http.path.clone(),
+                user_agent: http.user_agent.clone(),
+                source_ip: http.source_ip.clone(),
+                status_code: http.status_code,
+                content_length: http.content_length as i64,
+            }),
+            action: event.action.clone(),
+            event_category: event.event_category,
+            management_type: event.management_type,
+            access_type: event.access_type,
+            read_only: event.read_only,
+            outcome: event.outcome,
+            error_code: event.error_code.clone(),
+            error_message: event.error_message.clone(),
+            event_time: event.event_time.as_ref().map(TimestampRecord::from),
+            processed_at: event.processed_at.as_ref().map(TimestampRecord::from),
+            latency_ms: event.latency_ms as i64,
+            metadata_json: event.metadata.as_ref().map(|metadata| {
+                value_to_json(&prost_types::Value {
+                    kind: Some(Kind::StructValue(metadata.clone())),
+                })
+                .to_string()
+            }),
+            correlation_id: event.correlation_id.clone(),
+            trace_id: event.trace_id.clone(),
+            span_id: event.span_id.clone(),
+            event_source: event.event_source.clone(),
+            event_version: event.event_version.clone(),
+            management_event: event.management_event,
+            enriched: event.enriched,
+        }
+    }
+}
+
+impl TryFrom<EventRecord> for AuditEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(record: EventRecord) -> Result<Self, Self::Error> {
+        let metadata = match record.metadata_json {
+            Some(json) => match json_to_value(serde_json::from_str(&json)?).kind {
+                Some(Kind::StructValue(metadata)) => Some(metadata),
+                _ => return Err(anyhow::anyhow!("Event metadata is not a JSON object")),
+            },
+            None => None,
+        };
+
+        Ok(AuditEvent {
+            event_id: record.event_id.map(|value| EventId { value }),
+            tenant_id: record.tenant_id.map(|value| TenantId { value }),
+            hrn: record.hrn.map(|hrn| Hrn {
+                partition: hrn.partition,
+                service: hrn.service,
+                tenant_id: hrn.tenant_id,
+                region: hrn.region,
+                resource_type: hrn.resource_type,
+                resource_path: hrn.resource_path,
+            }),
+            user_identity: record.user_identity.map(|user| UserIdentity {
+                user_id: user.user_id,
+                username: user.username,
+                email: user.email,
+                roles: user.roles,
+                tenant_id: user.tenant_id,
+            }),
+            http_context: record.http_context.map(|http| HttpContext {
+                method: http.method,
+                path: http.path,
+                user_agent: http.user_agent,
+                source_ip: http.source_ip,
+                status_code: http.status_code,
+                content_length: http.content_length as u64,
+            }),
+            action: record.action,
+            event_category: record.event_category,
+            management_type: record.management_type,
+            access_type: record.access_type,
+            read_only: record.read_only,
+            outcome: record.outcome,
+            error_code: record.error_code,
+            error_message: record.error_message,
+            event_time: record.event_time.map(Into::into),
+            processed_at: record.processed_at.map(Into::into),
+            latency_ms: record.latency_ms as u64,
+            metadata,
+            correlation_id: record.correlation_id,
+            trace_id: record.trace_id,
+            span_id: record.span_id,
+            event_source: record.event_source,
+            event_version: record.event_version,
+            management_event: record.management_event,
+            enriched: record.enriched,
+        })
+    }
+}
+
+impl From<&prost_types::Timestamp> for TimestampRecord {
+    fn from(timestamp: &prost_types::Timestamp) -> Self {
+        Self {
+            seconds: timestamp.seconds,
+            nanos: timestamp.nanos,
+        }
+    }
+}
+
+impl From<TimestampRecord> for prost_types::Timestamp {
+    fn from(record: TimestampRecord) -> Self {
+        Self {
+            seconds: record.seconds,
+            nanos: record.nanos,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hodei_audit_proto::MetadataExt;
+
+    #[test]
+    fn test_format_names_round_trip() {
+        for format in [
+            ObjectFormat::Parquet,
+            ObjectFormat::NdJson,
+            ObjectFormat::Avro,
+        ] {
+            assert_eq!(format.as_str().parse::<ObjectFormat>().unwrap(), format);
+        }
+        assert!("csv".parse::<ObjectFormat>().is_err());
+    }
+
+    #[test]
+    fn test_unset_fields_survive_encoding() {
+        let mut event = AuditEvent {
+            event_id: Some(EventId {
+                value: "evt-1".to_string(),
+            }),
+            latency_ms: u64::MAX,
+            ..Default::default()
+        };
+        event.set_json("attempts", serde_json::json!(3));
+
+        for format in [ObjectFormat::NdJson, ObjectFormat::Avro] {
+            let body = format.encode(std::slice::from_ref(&event)).unwrap();
+            assert_eq!(format.decode(&body).unwrap(), vec![event.clone()]);
+        }
+    }
+}
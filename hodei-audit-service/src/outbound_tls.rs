@@ -0,0 +1,388 @@
+//! TLS policy for outbound connections
+//!
+//! ClickHouse, S3, Vector and a remote KMS are all reached through the same
+//! `OutboundTlsConfig`, so a deployment sets the minimum protocol version and
+//! the accepted cipher suites once. The handshake is done by rustls, which
+//! never negotiates SSLv3, TLS 1.0 or TLS 1.1: a server that offers nothing
+//! newer than the configured minimum is refused instead of downgraded to.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use hyper_util::rt::TokioIo;
+use thiserror::Error;
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::crypto::{CryptoProvider, ring};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName};
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore, SupportedProtocolVersion};
+use tonic::transport::{Channel, Endpoint, Uri};
+
+/// Cipher suites accepted by default: AEAD only, with forward secrecy
+pub const APPROVED_CIPHER_SUITES: &[&str] = &[
+    "TLS13_AES_256_GCM_SHA384",
+    "TLS13_AES_128_GCM_SHA256",
+    "TLS13_CHACHA20_POLY1305_SHA256",
+    "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384",
+    "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+    "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256",
+    "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+    "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+    "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+];
+
+/// Lowest TLS version an outbound connection may negotiate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum TlsVersion {
+    /// TLS 1.2 or TLS 1.3
+    #[default]
+    Tls12,
+    /// TLS 1.3 only
+    Tls13,
+}
+
+static TLS13_ONLY: &[&SupportedProtocolVersion] = &[&rustls::version::TLS13];
+
+impl TlsVersion {
+    fn protocol_versions(self) -> &'static [&'static SupportedProtocolVersion] {
+        match self {
+            Self::Tls12 => rustls::ALL_VERSIONS,
+            Self::Tls13 => TLS13_ONLY,
+        }
+    }
+}
+
+/// Outbound TLS errors
+#[derive(Debug, Error)]
+pub enum OutboundTlsError {
+    #[error("Unknown cipher suite: {0}")]
+    UnknownCipherSuite(String),
+
+    #[error("Invalid CA certificate: {0}")]
+    InvalidCertificate(String),
+
+    #[error("Invalid server name: {0}")]
+    InvalidServerName(String),
+
+    #[error("TLS configuration error: {0}")]
+    Config(#[from] rustls::Error),
+
+    #[error("Connection to {address} failed: {source}")]
+    Connect {
+        address: String,
+        source: std::io::Error,
+    },
+
+    #[error("TLS handshake with {address} failed: {source}")]
+    Handshake {
+        address: String,
+        source: std::io::Error,
+    },
+}
+
+pub type OutboundTlsResult<T> = Result<T, OutboundTlsError>;
+
+/// TLS policy shared by every outbound client
+#[derive(Debug, Clone)]
+pub struct OutboundTlsConfig {
+    /// Lowest protocol version accepted
+    pub min_version: TlsVersion,
+    /// Accepted cipher suites, by IANA name
+    pub cipher_suites: Vec<String>,
+    /// CA bundle (PEM) trusted for server certificates; `None` uses the
+    /// platform's roots
+    pub ca_pem: Option<String>,
+    /// Name checked against the server certificate; `None` uses the host
+    /// being connected to
+    pub server_name: Option<String>,
+}
+
+impl Default for OutboundTlsConfig {
+    fn default() -> Self {
+        Self {
+            min_version: TlsVersion::default(),
+            cipher_suites: APPROVED_CIPHER_SUITES
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            ca_pem: None,
+            server_name: None,
+        }
+    }
+}
+
+impl OutboundTlsConfig {
+    /// Require at least `version`
+    pub fn with_min_version(mut self, version: TlsVersion) -> Self {
+        self.min_version = version;
+        self
+    }
+
+    /// Accept only these cipher suites
+    pub fn with_cipher_suites<I, S>(mut self, suites: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.cipher_suites = suites.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Trust server certificates signed by this CA bundle (PEM)
+    pub fn with_ca_pem(mut self, ca_pem: impl Into<String>) -> Self {
+        self.ca_pem = Some(ca_pem.into());
+        self
+    }
+
+    /// Check server certificates against `server_name`
+    pub fn with_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.server_name = Some(server_name.into());
+        self
+    }
+
+    /// rustls client configuration enforcing this policy
+    ///
+    /// `alpn_protocols` is offered as is (e.g. `h2` for gRPC).
+    pub fn client_config(&self, alpn_protocols: &[&[u8]]) -> OutboundTlsResult<ClientConfig> {
+        let base = ring::default_provider();
+        let mut cipher_suites = Vec::with_capacity(self.cipher_suites.len());
+        for name in &self.cipher_suites {
+            let suite = base
+                .cipher_suites
+                .iter()
+                .find(|s| s.suite().as_str() == Some(name.as_str()))
+                .ok_or_else(|| OutboundTlsError::UnknownCipherSuite(name.clone()))?;
+            cipher_suites.push(*suite);
+        }
+        let provider = CryptoProvider {
+            cipher_suites,
+            ..base
+        };
+
+        let mut config = ClientConfig::builder_with_provider(Arc::new(provider))
+            .with_protocol_versions(self.min_version.protocol_versions())?
+            .with_root_certificates(self.root_store()?)
+            .with_no_client_auth();
+        config.alpn_protocols = alpn_protocols.iter().map(|p| p.to_vec()).collect();
+        Ok(config)
+    }
+
+    fn root_store(&self) -> OutboundTlsResult<RootCertStore> {
+        let mut roots = RootCertStore::empty();
+        match &self.ca_pem {
+            Some(pem) => {
+                for cert in CertificateDer::pem_slice_iter(pem.as_bytes()) {
+                    let cert =
+                        cert.map_err(|e| OutboundTlsError::InvalidCertificate(e.to_string()))?;
+                    roots
+                        .add(cert)
+                        .map_err(|e| OutboundTlsError::InvalidCertificate(e.to_string()))?;
+                }
+                if roots.is_empty() {
+                    return Err(OutboundTlsError::InvalidCertificate(
+                        "no certificate in CA bundle".to_string(),
+                    ));
+                }
+            }
+            None => {
+                let (added, ignored) =
+                    roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+                tracing::debug!(
+                    "[OutboundTls] Loaded {} platform root certificates ({} ignored)",
+                    added,
+                    ignored
+                );
+            }
+        }
+        Ok(roots)
+    }
+
+    fn server_name_for(&self, host: &str) -> OutboundTlsResult<ServerName<'static>> {
+        let name = self.server_name.as_deref().unwrap_or(host);
+        ServerName::try_from(name.to_string())
+            .map_err(|_| OutboundTlsError::InvalidServerName(name.to_string()))
+    }
+
+    /// Open a TCP connection to `host:port` and complete the TLS handshake
+    pub async fn connect(&self, host: &str, port: u16) -> OutboundTlsResult<TlsStream<TcpStream>> {
+        let connector = TlsConnector::from(Arc::new(self.client_config(&[])?));
+        connect_with(&connector, self.server_name_for(host)?, host, port).await
+    }
+
+    /// gRPC channel to `endpoint` under this policy
+    ///
+    /// tonic's own TLS can't restrict versions or ciphers, so the handshake
+    /// is done by a custom connector instead: `endpoint` must use the `http`
+    /// scheme, or tonic refuses to connect without its TLS configured.
+    pub async fn connect_channel(&self, endpoint: Endpoint) -> OutboundTlsResult<Channel> {
+        let uri = endpoint.uri().clone();
+        let host = uri.host().unwrap_or_default().to_string();
+        let address = format!("{}:{}", host, uri.port_u16().unwrap_or(443));
+        let connector = TlsConnector::from(Arc::new(self.client_config(&[b"h2"])?));
+        let server_name = self.server_name_for(&host)?;
+
+        let connect = tower::service_fn(move |uri: Uri| {
+            let connector = connector.clone();
+            let server_name = server_name.clone();
+            Box::pin(async move {
+                let host = uri.host().unwrap_or_default();
+                let port = uri.port_u16().unwrap_or(443);
+                let stream = connect_with(&connector, server_name, host, port).await?;
+                Ok::<_, OutboundTlsError>(TokioIo::new(stream))
+            }) as Pin<Box<dyn Future<Output = _> + Send>>
+        });
+
+        endpoint
+            .connect_with_connector(connect)
+            .await
+            .map_err(|e| OutboundTlsError::Handshake {
+                address,
+                source: std::io::Error::other(e),
+            })
+    }
+}
+
+async fn connect_with(
+    connector: &TlsConnector,
+    server_name: ServerName<'static>,
+    host: &str,
+    port: u16,
+) -> OutboundTlsResult<TlsStream<TcpStream>> {
+    let address = format!("{}:{}", host, port);
+    let tcp =
+        TcpStream::connect((host, port))
+            .await
+            .map_err(|source| OutboundTlsError::Connect {
+                address: address.clone(),
+                source,
+            })?;
+    connector
+        .connect(server_name, tcp)
+        .await
+        .map_err(|source| OutboundTlsError::Handshake { address, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_rustls::TlsAcceptor;
+    use tokio_rustls::rustls::ServerConfig;
+    use tokio_rustls::rustls::pki_types::PrivateKeyDer;
+
+    const TLS_FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls");
+
+    fn tls_fixture(name: &str) -> String {
+        std::fs::read_to_string(format!("{}/{}", TLS_FIXTURES, name)).unwrap()
+    }
+
+    fn trusting_fixtures() -> OutboundTlsConfig {
+        OutboundTlsConfig::default().with_ca_pem(tls_fixture("ca.pem"))
+    }
+
+    /// Server negotiating only `version`, accepting a single connection
+    async fn rustls_server(version: &'static SupportedProtocolVersion) -> u16 {
+        let certs = CertificateDer::pem_slice_iter(tls_fixture("server.pem").as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let key = PrivateKeyDer::from_pem_slice(tls_fixture("server.key").as_bytes()).unwrap();
+        let config = ServerConfig::builder_with_protocol_versions(&[version])
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let _ = TlsAcceptor::from(Arc::new(config)).accept(tcp).await;
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_connects_to_tls13_server() {
+        let port = rustls_server(&rustls::version::TLS13).await;
+
+        let stream = trusting_fixtures()
+            .connect("localhost", port)
+            .await
+            .unwrap();
+
+        let (_, session) = stream.get_ref();
+        assert_eq!(
+            session.protocol_version(),
+            Some(rustls::ProtocolVersion::TLSv1_3)
+        );
+    }
+
+    #[test]
+    fn test_never_offers_legacy_versions() {
+        use rustls::ProtocolVersion::{TLSv1_2, TLSv1_3};
+
+        // The versions handed to the rustls client builder
+        let offered = |min_version: TlsVersion| -> Vec<_> {
+            min_version
+                .protocol_versions()
+                .iter()
+                .map(|v| v.version)
+                .collect()
+        };
+        assert_eq!(offered(TlsVersion::Tls12), vec![TLSv1_3, TLSv1_2]);
+        assert_eq!(offered(TlsVersion::Tls13), vec![TLSv1_3]);
+
+        for min_version in [TlsVersion::Tls12, TlsVersion::Tls13] {
+            assert!(
+                trusting_fixtures()
+                    .with_min_version(min_version)
+                    .client_config(&[])
+                    .is_ok()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_min_version_refuses_older_server() {
+        let port = rustls_server(&rustls::version::TLS12).await;
+
+        let error = trusting_fixtures()
+            .with_min_version(TlsVersion::Tls13)
+            .connect("localhost", port)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, OutboundTlsError::Handshake { .. }));
+    }
+
+    #[test]
+    fn test_cipher_policy() {
+        let config = OutboundTlsConfig::default()
+            .with_cipher_suites(["TLS13_AES_256_GCM_SHA384"])
+            .client_config(&[b"h2"])
+            .unwrap();
+        let suites: Vec<_> = config
+            .crypto_provider()
+            .cipher_suites
+            .iter()
+            .map(|s| s.suite())
+            .collect();
+        assert_eq!(suites, vec![rustls::CipherSuite::TLS13_AES_256_GCM_SHA384]);
+        assert_eq!(config.alpn_protocols, vec![b"h2".to_vec()]);
+
+        let error = OutboundTlsConfig::default()
+            .with_cipher_suites(["TLS_RSA_WITH_RC4_128_SHA"])
+            .client_config(&[])
+            .unwrap_err();
+        assert!(matches!(error, OutboundTlsError::UnknownCipherSuite(_)));
+
+        // A TLS 1.3 minimum with only TLS 1.2 suites can't negotiate anything
+        assert!(
+            OutboundTlsConfig::default()
+                .with_min_version(TlsVersion::Tls13)
+                .with_cipher_suites(["TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256"])
+                .client_config(&[])
+                .is_err()
+        );
+    }
+}
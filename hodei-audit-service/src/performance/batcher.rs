@@ -5,14 +5,23 @@
 //! - Size-based: Flush batch when size threshold reached
 //! - Adaptive: Dynamically adjust based on throughput
 //! - Pressure-aware: Adjust based on system pressure
+//!
+//! When the queue is full, the overflow policy decides whether new events
+//! are rejected, evict the oldest ones, or wait for a flush to free room.
+//!
+//! Once started, a background task flushes the batch as soon as it reaches
+//! the size threshold of the policy, and partial batches as soon as the
+//! oldest buffered event exceeds its time bound, so low-volume tenants still
+//! get bounded latency. Size-based batching has no time bound.
 
 use std::collections::VecDeque;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use tokio::sync::{Mutex, mpsc, oneshot};
-use tokio::time::timeout;
+use tokio::sync::{Mutex, Notify, mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
 use tracing::{debug, error, info, warn};
 
 use crate::performance::backpressure::{BackpressureController, PressureLevel};
@@ -36,6 +45,18 @@ pub enum BatchingPolicy {
     },
 }
 
+/// What `add_event` does when the queue is at `max_queue_size`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Fail with `BatcherError::QueueFull`
+    #[default]
+    RejectNew,
+    /// Evict the oldest queued event to make room
+    DropOldest,
+    /// Wait up to the timeout for a flush to free room, then fail
+    BlockUntilSpace(Duration),
+}
+
 /// Configuration for SmartBatcher
 #[derive(Debug, Clone)]
 pub struct BatcherConfig {
@@ -51,6 +72,8 @@ pub struct BatcherConfig {
     pub backpressure_controller: Option<Arc<BackpressureController>>,
     /// Enable metrics collection
     pub enable_metrics: bool,
+    /// Behaviour when the queue is full
+    pub overflow_policy: OverflowPolicy,
 }
 
 impl Default for BatcherConfig {
@@ -65,6 +88,7 @@ impl Default for BatcherConfig {
             adaptive_tuning: true,
             backpressure_controller: None,
             enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
         }
     }
 }
@@ -78,6 +102,33 @@ pub struct BatchResult<T> {
     pub pressure_level: PressureLevel,
 }
 
+/// Receiver of the batches flushed by the background task
+#[async_trait]
+pub trait BatchHandler<T>: Send + Sync + 'static {
+    async fn handle_batch(&self, batch: BatchResult<T>);
+}
+
+#[async_trait]
+impl<T: Send + 'static> BatchHandler<T> for mpsc::UnboundedSender<BatchResult<T>> {
+    async fn handle_batch(&self, batch: BatchResult<T>) {
+        if self.send(batch).is_err() {
+            warn!("[SmartBatcher] Batch receiver closed, dropping flushed batch");
+        }
+    }
+}
+
+/// Flush requests sent to the background task, answered once flushed
+type FlushRequests = mpsc::UnboundedReceiver<oneshot::Sender<()>>;
+
+/// Running background flush task
+#[derive(Debug)]
+struct BackgroundFlush<T> {
+    /// Carries the final batch; the task delivers it and exits
+    shutdown: oneshot::Sender<BatchResult<T>>,
+    /// Returns the flush requests receiver, so the task can be restarted
+    handle: JoinHandle<FlushRequests>,
+}
+
 /// SmartBatcher for high-performance event processing
 #[derive(Debug)]
 pub struct SmartBatcher<T> {
@@ -86,6 +137,15 @@ pub struct SmartBatcher<T> {
     last_flush: Arc<Mutex<Instant>>,
     metrics: Arc<Mutex<BatcherMetrics>>,
     flush_notifier: mpsc::UnboundedSender<oneshot::Sender<()>>,
+    /// Receiver of `flush_notifier` while the background task is not running
+    flush_requests: std::sync::Mutex<Option<FlushRequests>>,
+    /// Signalled when a flush frees room in the queue
+    space_available: Arc<Notify>,
+    /// When the oldest buffered event was enqueued
+    oldest_event: Arc<Mutex<Option<Instant>>>,
+    /// Signalled when an event lands in an empty queue
+    event_enqueued: Arc<Notify>,
+    background: std::sync::Mutex<Option<BackgroundFlush<T>>>,
 }
 
 /// Metrics for SmartBatcher
@@ -99,12 +159,20 @@ pub struct BatcherMetrics {
     pub queue_size: usize,
     pub pressure_level: PressureLevel,
     pub adaptive_adjustments: u64,
+    /// Events refused because the queue was full
+    pub rejected_events: u64,
+    /// Queued events evicted to make room (`DropOldest`)
+    pub dropped_events: u64,
+    /// Events accepted after waiting for room (`BlockUntilSpace`)
+    pub blocked_events: u64,
+    /// Events that gave up waiting for room (`BlockUntilSpace`)
+    pub block_timeouts: u64,
 }
 
 impl<T> SmartBatcher<T> {
     /// Create a new SmartBatcher
     pub fn new(config: BatcherConfig) -> Self {
-        let (flush_notifier, _) = mpsc::unbounded_channel();
+        let (flush_notifier, flush_requests) = mpsc::unbounded_channel();
         let last_flush = Arc::new(Mutex::new(Instant::now()));
 
         let metrics = Arc::new(Mutex::new(BatcherMetrics::default()));
@@ -115,22 +183,72 @@ impl<T> SmartBatcher<T> {
             last_flush,
             metrics,
             flush_notifier,
+            flush_requests: std::sync::Mutex::new(Some(flush_requests)),
+            space_available: Arc::new(Notify::new()),
+            oldest_event: Arc::new(Mutex::new(None)),
+            event_enqueued: Arc::new(Notify::new()),
+            background: std::sync::Mutex::new(None),
         }
     }
 
     /// Add event to batch
+    ///
+    /// A full queue is handled according to the configured overflow policy.
     pub async fn add_event(&self, event: T) -> Result<(), BatcherError> {
-        let mut queue = self.queue.lock().await;
-        let mut metrics = self.metrics.lock().await;
-
-        // Check queue size and apply backpressure if needed
-        if queue.len() >= self.config.max_queue_size {
+        let deadline = match self.config.overflow_policy {
+            OverflowPolicy::BlockUntilSpace(wait) => Some(Instant::now() + wait),
+            _ => None,
+        };
+        let mut waited = false;
+
+        let (mut queue, mut metrics) = loop {
+            // Register for the wake-up before checking, so a flush between
+            // the check and the wait is not missed
+            let space_available = self.space_available.notified();
+            tokio::pin!(space_available);
+            space_available.as_mut().enable();
+
+            let mut queue = self.queue.lock().await;
+            let mut metrics = self.metrics.lock().await;
+            if queue.len() < self.config.max_queue_size {
+                break (queue, metrics);
+            }
             metrics.queue_size = queue.len();
-            return Err(BatcherError::QueueFull(self.config.max_queue_size));
-        }
+
+            match (self.config.overflow_policy, deadline) {
+                (OverflowPolicy::DropOldest, _) => {
+                    queue.pop_front();
+                    metrics.dropped_events += 1;
+                    break (queue, metrics);
+                }
+                (OverflowPolicy::BlockUntilSpace(_), Some(deadline)) => {
+                    drop(queue);
+                    drop(metrics);
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if timeout(remaining, space_available).await.is_err() {
+                        self.metrics.lock().await.block_timeouts += 1;
+                        return Err(BatcherError::QueueFull(self.config.max_queue_size));
+                    }
+                    waited = true;
+                }
+                _ => {
+                    metrics.rejected_events += 1;
+                    return Err(BatcherError::QueueFull(self.config.max_queue_size));
+                }
+            }
+        };
 
         queue.push_back(event);
         metrics.queue_size = queue.len();
+        if waited {
+            metrics.blocked_events += 1;
+        }
+        let mut oldest_event = self.oldest_event.lock().await;
+        if oldest_event.is_none() {
+            *oldest_event = Some(Instant::now());
+            self.event_enqueued.notify_one();
+        }
+        drop(oldest_event);
 
         // Check if we should flush
         if self.should_flush(queue.len()) {
@@ -146,7 +264,7 @@ impl<T> SmartBatcher<T> {
     fn should_flush(&self, queue_len: usize) -> bool {
         match self.config.policy {
             BatchingPolicy::TimeBased(_) => {
-                // Will be handled by timeout mechanism
+                // Handled by the background flush task
                 false
             }
             BatchingPolicy::SizeBased(max_size) => queue_len >= max_size,
@@ -179,8 +297,12 @@ impl<T> SmartBatcher<T> {
             PressureLevel::Normal
         };
 
+        let batch: Vec<T> = queue.drain(..).collect();
+        *self.oldest_event.lock().await = None;
+        self.space_available.notify_waiters();
+
         BatchResult {
-            batch: queue.drain(..).collect(),
+            batch,
             size: 0,
             age,
             pressure_level,
@@ -198,6 +320,8 @@ impl<T> SmartBatcher<T> {
 
         let batch: Vec<T> = queue.drain(..).collect();
         let batch_size = batch.len();
+        *self.oldest_event.lock().await = None;
+        self.space_available.notify_waiters();
 
         // Update metrics
         metrics.total_flushes += 1;
@@ -225,13 +349,82 @@ impl<T> SmartBatcher<T> {
         })
     }
 
-    /// Notify flush (async)
+    /// Check whether the pending batch should be flushed now
+    ///
+    /// Combines the size threshold of the policy with its time limit, like
+    /// the background task, so consumers driving the batcher inline can poll
+    /// it after each event.
+    pub async fn flush_due(&self) -> bool {
+        let queue_len = self.queue.lock().await.len();
+        if queue_len == 0 {
+            return false;
+        }
+        if self.should_flush(queue_len) {
+            return true;
+        }
+
+        match (self.max_batch_age(), self.oldest_event_age().await) {
+            (Some(max_age), Some(age)) => age >= max_age,
+            _ => false,
+        }
+    }
+
+    /// Longest an event may stay buffered before the batch is flushed
+    ///
+    /// The time limit of the policy; `None` for size-based batching, which
+    /// only flushes once the size threshold is reached.
+    pub fn max_batch_age(&self) -> Option<Duration> {
+        match self.config.policy {
+            BatchingPolicy::TimeBased(max_time) => Some(max_time),
+            BatchingPolicy::Hybrid { max_time, .. } => Some(max_time),
+            BatchingPolicy::Adaptive { max_time, .. } => Some(max_time),
+            BatchingPolicy::SizeBased(_) => None,
+        }
+    }
+
+    /// Age of the oldest buffered event, if any
+    async fn oldest_event_age(&self) -> Option<Duration> {
+        self.oldest_event.lock().await.map(|at| at.elapsed())
+    }
+
+    /// Stop the background flush task, flushing the remaining events to its handler
+    ///
+    /// Does nothing if the task is not running.
+    pub async fn stop(&self) -> Result<(), BatcherError> {
+        let Some(background) = self.background.lock().unwrap().take() else {
+            return Ok(());
+        };
+
+        let remaining = self.flush().await?;
+        let _ = background.shutdown.send(remaining);
+        let flush_requests = background
+            .handle
+            .await
+            .map_err(|e| BatcherError::ProcessingError(e.to_string()))?;
+        *self.flush_requests.lock().unwrap() = Some(flush_requests);
+        Ok(())
+    }
+
+    /// Whether the background flush task is running
+    pub fn is_running(&self) -> bool {
+        self.background.lock().unwrap().is_some()
+    }
+
+    /// Ask the background task to flush the batch that reached its size threshold
+    ///
+    /// Without a running task the batcher is driven inline through
+    /// `flush_due` and there is nobody to notify. A task that stopped
+    /// listening is reported as `FlushNotifierClosed`; the event stays queued.
     async fn notify_flush(&self) -> Result<(), BatcherError> {
+        if !self.is_running() {
+            return Ok(());
+        }
+
         let (tx, _) = oneshot::channel();
-        self.flush_notifier
-            .send(tx)
-            .map_err(|_| BatcherError::FlushNotifierClosed)?;
-        Ok(())
+        self.flush_notifier.send(tx).map_err(|_| {
+            warn!("[SmartBatcher] Background flush task is gone, batch left queued");
+            BatcherError::FlushNotifierClosed
+        })
     }
 
     /// Get metrics
@@ -239,15 +432,23 @@ impl<T> SmartBatcher<T> {
         self.metrics.lock().await.clone()
     }
 
-    /// Wait for flush notification
+    /// Have the background task flush the pending batch and wait for it
+    ///
+    /// Fails with `FlushNotifierClosed` if the task is not running or exits
+    /// before flushing.
     pub async fn wait_for_flush(&self) -> Result<(), BatcherError> {
+        if !self.is_running() {
+            return Err(BatcherError::FlushNotifierClosed);
+        }
+
         let (tx, rx) = oneshot::channel();
         self.flush_notifier
             .send(tx)
             .map_err(|_| BatcherError::FlushNotifierClosed)?;
 
         match timeout(self.config.flush_timeout, rx).await {
-            Ok(_) => Ok(()),
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(_)) => Err(BatcherError::FlushNotifierClosed),
             Err(_) => Err(BatcherError::FlushTimeout),
         }
     }
@@ -267,6 +468,133 @@ impl<T> SmartBatcher<T> {
     }
 }
 
+impl<T: Send + 'static> SmartBatcher<T> {
+    /// Start the background flush task
+    ///
+    /// The batch is flushed to `handler` when it reaches the size threshold
+    /// of the policy, and whenever the oldest buffered event is older than
+    /// [`max_batch_age`](Self::max_batch_age). The task only keeps a weak
+    /// reference to the batcher; `stop()` or dropping the batcher flushes the
+    /// remaining events and ends it.
+    pub fn start(self: &Arc<Self>, handler: Arc<dyn BatchHandler<T>>) -> Result<(), BatcherError> {
+        let mut background = self.background.lock().unwrap();
+        if background.is_some() {
+            return Err(BatcherError::AlreadyStarted);
+        }
+        let flush_requests = self
+            .flush_requests
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or(BatcherError::FlushNotifierClosed)?;
+
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let handle = tokio::spawn(run_background_flush(
+            Arc::downgrade(self),
+            handler,
+            self.event_enqueued.clone(),
+            self.max_batch_age(),
+            flush_requests,
+            shutdown_rx,
+        ));
+        *background = Some(BackgroundFlush { shutdown, handle });
+        info!(
+            "[SmartBatcher] Background flush started (max batch age {:?})",
+            self.max_batch_age()
+        );
+        Ok(())
+    }
+}
+
+async fn run_background_flush<T: Send + 'static>(
+    batcher: Weak<SmartBatcher<T>>,
+    handler: Arc<dyn BatchHandler<T>>,
+    event_enqueued: Arc<Notify>,
+    max_age: Option<Duration>,
+    mut flush_requests: FlushRequests,
+    mut shutdown: oneshot::Receiver<BatchResult<T>>,
+) -> FlushRequests {
+    loop {
+        // Time left until the oldest event is due, or None to wait for one
+        let wait = match batcher.upgrade() {
+            Some(batcher) => match (batcher.oldest_event_age().await, max_age) {
+                (Some(age), Some(max_age)) if age >= max_age => {
+                    deliver_flush(batcher, &handler, "Time-bound").await;
+                    continue;
+                }
+                (Some(age), Some(max_age)) => Some(max_age - age),
+                _ => None,
+            },
+            // Dropped: the final batch is already in the shutdown channel
+            None => None,
+        };
+
+        tokio::select! {
+            biased;
+            last = &mut shutdown => {
+                if let Ok(last) = last
+                    && !last.batch.is_empty()
+                {
+                    handler.handle_batch(last).await;
+                }
+                break;
+            }
+            Some(done) = flush_requests.recv() => {
+                if let Some(batcher) = batcher.upgrade() {
+                    deliver_flush(batcher, &handler, "Requested").await;
+                }
+                let _ = done.send(());
+            }
+            _ = sleep(wait.unwrap_or_default()), if wait.is_some() => {}
+            _ = event_enqueued.notified(), if wait.is_none() => {}
+        }
+    }
+    debug!("[SmartBatcher] Background flush stopped");
+    flush_requests
+}
+
+/// Flush the batcher and hand the batch, if any, to `handler`
+async fn deliver_flush<T: Send + 'static>(
+    batcher: Arc<SmartBatcher<T>>,
+    handler: &Arc<dyn BatchHandler<T>>,
+    trigger: &str,
+) {
+    match batcher.flush().await {
+        Ok(result) if !result.batch.is_empty() => {
+            drop(batcher);
+            debug!("[SmartBatcher] {} flush of {} events", trigger, result.size);
+            handler.handle_batch(result).await;
+        }
+        Ok(_) => {}
+        Err(e) => error!("[SmartBatcher] Background flush failed: {}", e),
+    }
+}
+
+impl<T> Drop for SmartBatcher<T> {
+    fn drop(&mut self) {
+        let Some(background) = self.background.get_mut().unwrap().take() else {
+            return;
+        };
+
+        // No await here: hand the remaining events to the task to deliver
+        let batch: Vec<T> = match self.queue.try_lock() {
+            Ok(mut queue) => queue.drain(..).collect(),
+            Err(_) => Vec::new(),
+        };
+        let age = self
+            .last_flush
+            .try_lock()
+            .map(|last_flush| last_flush.elapsed())
+            .unwrap_or_default();
+        let _ = background.shutdown.send(BatchResult {
+            size: batch.len(),
+            batch,
+            age,
+            pressure_level: self.get_pressure_level(),
+        });
+    }
+}
+
 /// Batcher error types
 #[derive(Debug, thiserror::Error)]
 pub enum BatcherError {
@@ -278,6 +606,8 @@ pub enum BatcherError {
     FlushNotifierClosed,
     #[error("Batch processing error: {0}")]
     ProcessingError(String),
+    #[error("Background flush task already started")]
+    AlreadyStarted,
 }
 
 #[cfg(test)]
@@ -294,6 +624,7 @@ mod tests {
             adaptive_tuning: false,
             backpressure_controller: None,
             enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
         };
 
         let batcher = SmartBatcher::new(config);
@@ -325,6 +656,7 @@ mod tests {
             adaptive_tuning: false,
             backpressure_controller: None,
             enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
         };
 
         let batcher = SmartBatcher::new(config);
@@ -337,6 +669,222 @@ mod tests {
         // Next event should fail
         let result = batcher.add_event(100).await;
         assert!(matches!(result, Err(BatcherError::QueueFull(5))));
+        assert_eq!(batcher.get_metrics().await.rejected_events, 1);
+    }
+
+    fn overflow_config(overflow_policy: OverflowPolicy) -> BatcherConfig {
+        BatcherConfig {
+            max_queue_size: 3,
+            policy: BatchingPolicy::SizeBased(10),
+            adaptive_tuning: false,
+            overflow_policy,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_evicts_to_accept_new_event() {
+        let batcher = SmartBatcher::new(overflow_config(OverflowPolicy::DropOldest));
+        for i in 0..3 {
+            batcher.add_event(i).await.unwrap();
+        }
+
+        batcher.add_event(3).await.unwrap();
+        batcher.add_event(4).await.unwrap();
+
+        let metrics = batcher.get_metrics().await;
+        assert_eq!(metrics.dropped_events, 2);
+        assert_eq!(metrics.rejected_events, 0);
+        assert_eq!(batcher.flush().await.unwrap().batch, vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_block_until_space_waits_for_flush() {
+        let batcher = Arc::new(SmartBatcher::new(overflow_config(
+            OverflowPolicy::BlockUntilSpace(Duration::from_secs(5)),
+        )));
+        for i in 0..3 {
+            batcher.add_event(i).await.unwrap();
+        }
+
+        let producer = {
+            let batcher = batcher.clone();
+            tokio::spawn(async move { batcher.add_event(3).await })
+        };
+        sleep(Duration::from_millis(20)).await;
+        assert!(!producer.is_finished());
+
+        assert_eq!(batcher.flush().await.unwrap().batch, vec![0, 1, 2]);
+        producer.await.unwrap().unwrap();
+
+        assert_eq!(batcher.queue_size().await, 1);
+        assert_eq!(batcher.get_metrics().await.blocked_events, 1);
+    }
+
+    #[tokio::test]
+    async fn test_block_until_space_times_out() {
+        let batcher = SmartBatcher::new(overflow_config(OverflowPolicy::BlockUntilSpace(
+            Duration::from_millis(20),
+        )));
+        for i in 0..3 {
+            batcher.add_event(i).await.unwrap();
+        }
+
+        let result = batcher.add_event(3).await;
+        assert!(matches!(result, Err(BatcherError::QueueFull(3))));
+        assert_eq!(batcher.get_metrics().await.block_timeouts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_flush_due_on_size_threshold() {
+        let config = BatcherConfig {
+            max_queue_size: 1000,
+            policy: BatchingPolicy::SizeBased(3),
+            flush_timeout: Duration::from_millis(100),
+            adaptive_tuning: false,
+            backpressure_controller: None,
+            enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
+        };
+
+        let batcher = SmartBatcher::new(config);
+
+        batcher.add_event(1).await.unwrap();
+        batcher.add_event(2).await.unwrap();
+        assert!(!batcher.flush_due().await);
+
+        // Reaching the threshold must not fail even without a flush listener
+        batcher.add_event(3).await.unwrap();
+        assert!(batcher.flush_due().await);
+
+        batcher.flush().await.unwrap();
+        assert!(!batcher.flush_due().await);
+    }
+
+    #[tokio::test]
+    async fn test_flush_due_on_time_limit() {
+        let config = BatcherConfig {
+            max_queue_size: 1000,
+            policy: BatchingPolicy::Hybrid {
+                max_time: Duration::from_millis(10),
+                max_size: 1000,
+            },
+            flush_timeout: Duration::from_millis(100),
+            adaptive_tuning: false,
+            backpressure_controller: None,
+            enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
+        };
+
+        let batcher = SmartBatcher::new(config);
+        batcher.add_event(1).await.unwrap();
+
+        sleep(Duration::from_millis(20)).await;
+        assert!(batcher.flush_due().await);
+    }
+
+    #[tokio::test]
+    async fn test_background_task_flushes_on_time_bound() {
+        let batcher = Arc::new(SmartBatcher::new(BatcherConfig {
+            policy: BatchingPolicy::TimeBased(Duration::from_millis(50)),
+            adaptive_tuning: false,
+            ..Default::default()
+        }));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        batcher.start(Arc::new(tx)).unwrap();
+        assert!(matches!(
+            batcher.start(Arc::new(mpsc::unbounded_channel().0)),
+            Err(BatcherError::AlreadyStarted)
+        ));
+
+        for i in 0..3 {
+            batcher.add_event(i).await.unwrap();
+        }
+        sleep(Duration::from_millis(100)).await;
+
+        let flushed = rx.try_recv().expect("no automatic flush");
+        assert_eq!(flushed.batch, vec![0, 1, 2]);
+        assert_eq!(batcher.queue_size().await, 0);
+        assert_eq!(batcher.get_metrics().await.total_flushes, 1);
+    }
+
+    #[tokio::test]
+    async fn test_background_task_applies_size_policy() {
+        let batcher = Arc::new(SmartBatcher::new(BatcherConfig {
+            policy: BatchingPolicy::SizeBased(3),
+            flush_timeout: Duration::from_millis(10),
+            adaptive_tuning: false,
+            ..Default::default()
+        }));
+        assert_eq!(batcher.max_batch_age(), None);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        batcher.start(Arc::new(tx)).unwrap();
+
+        // Below the threshold nothing is flushed, however long it waits
+        batcher.add_event(0).await.unwrap();
+        batcher.add_event(1).await.unwrap();
+        sleep(Duration::from_millis(50)).await;
+        assert!(rx.try_recv().is_err());
+        assert!(!batcher.flush_due().await);
+
+        batcher.add_event(2).await.unwrap();
+        let flushed = timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .expect("no size-triggered flush")
+            .unwrap();
+        assert_eq!(flushed.batch, vec![0, 1, 2]);
+
+        // Restarted tasks keep receiving flush requests
+        batcher.stop().await.unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        batcher.start(Arc::new(tx)).unwrap();
+        for i in 3..6 {
+            batcher.add_event(i).await.unwrap();
+        }
+        let flushed = timeout(Duration::from_secs(1), rx.recv()).await.unwrap();
+        assert_eq!(flushed.unwrap().batch, vec![3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_flush_needs_the_background_task() {
+        let batcher = Arc::new(SmartBatcher::new(BatcherConfig {
+            policy: BatchingPolicy::TimeBased(Duration::from_secs(60)),
+            adaptive_tuning: false,
+            ..Default::default()
+        }));
+        batcher.add_event(1).await.unwrap();
+        assert!(matches!(
+            batcher.wait_for_flush().await,
+            Err(BatcherError::FlushNotifierClosed)
+        ));
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        batcher.start(Arc::new(tx)).unwrap();
+        batcher.wait_for_flush().await.unwrap();
+        assert_eq!(rx.try_recv().unwrap().batch, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_stop_and_drop_flush_remaining_events() {
+        let config = BatcherConfig {
+            policy: BatchingPolicy::TimeBased(Duration::from_secs(60)),
+            adaptive_tuning: false,
+            ..Default::default()
+        };
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let batcher = Arc::new(SmartBatcher::new(config.clone()));
+        batcher.start(Arc::new(tx.clone())).unwrap();
+        batcher.add_event(1).await.unwrap();
+        batcher.stop().await.unwrap();
+        assert!(!batcher.is_running());
+        assert_eq!(rx.recv().await.unwrap().batch, vec![1]);
+
+        let batcher = Arc::new(SmartBatcher::new(config));
+        batcher.start(Arc::new(tx)).unwrap();
+        batcher.add_event(2).await.unwrap();
+        drop(batcher);
+        assert_eq!(rx.recv().await.unwrap().batch, vec![2]);
     }
 
     #[tokio::test]
@@ -348,6 +896,7 @@ mod tests {
             adaptive_tuning: false,
             backpressure_controller: None,
             enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
         };
 
         let batcher = SmartBatcher::new(config);
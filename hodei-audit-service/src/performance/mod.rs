@@ -8,12 +8,21 @@ pub mod backpressure;
 pub mod batcher;
 pub mod circuit_breaker;
 pub mod connection_pool;
+pub mod sharded_batcher;
+pub mod throughput;
 
 pub use backpressure::{
     BackpressureConfig, BackpressureController, BackpressureMetrics, PressureLevel,
 };
-pub use batcher::{BatchResult, BatcherConfig, BatcherError, BatchingPolicy, SmartBatcher};
+pub use batcher::{
+    BatchHandler, BatchResult, BatcherConfig, BatcherError, BatchingPolicy, OverflowPolicy,
+    SmartBatcher,
+};
 pub use circuit_breaker::{
     CircuitBreaker, CircuitBreakerConfig, CircuitBreakerMetrics, CircuitState,
 };
 pub use connection_pool::{ConnectionPool, PoolConfig, PoolError, PoolStats, PooledConnection};
+pub use sharded_batcher::{
+    ShardedBatcher, ShardedBatcherConfig, ShardedBatcherMetrics, TenantBatchHandler,
+};
+pub use throughput::{ThroughputConfig, ThroughputHarness, ThroughputReport};
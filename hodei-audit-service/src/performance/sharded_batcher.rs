@@ -0,0 +1,428 @@
+//! ShardedBatcher - Per-tenant isolated batching
+//!
+//! Keeps an independent `SmartBatcher` per tenant, each with its own
+//! background flush task, so a slow sink for one tenant's batches only
+//! holds back that tenant. The number of live shards is capped with an LRU
+//! and idle shards can be evicted; an evicted shard is closed to new events
+//! and flushes its remaining ones before it goes away.
+
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use lru::LruCache;
+use tracing::{debug, info, warn};
+
+use crate::performance::batcher::{
+    BatchHandler, BatchResult, BatcherConfig, BatcherError, BatcherMetrics, SmartBatcher,
+};
+
+/// Configuration for ShardedBatcher
+#[derive(Debug, Clone)]
+pub struct ShardedBatcherConfig {
+    /// Configuration of every tenant batcher
+    pub batcher: BatcherConfig,
+    /// Maximum number of live tenant batchers
+    pub max_shards: usize,
+    /// Tenant batchers unused for this long are evicted by `evict_idle`
+    pub idle_timeout: Duration,
+}
+
+impl Default for ShardedBatcherConfig {
+    fn default() -> Self {
+        Self {
+            batcher: BatcherConfig::default(),
+            max_shards: 1_000,
+            idle_timeout: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Receiver of the batches flushed for each tenant
+#[async_trait]
+pub trait TenantBatchHandler<T>: Send + Sync + 'static {
+    async fn handle_batch(&self, tenant_id: &str, batch: BatchResult<T>);
+}
+
+/// Adapts a tenant handler to the batches of a single shard
+struct ShardHandler<T> {
+    tenant_id: String,
+    handler: Arc<dyn TenantBatchHandler<T>>,
+}
+
+#[async_trait]
+impl<T: Send + 'static> BatchHandler<T> for ShardHandler<T> {
+    async fn handle_batch(&self, batch: BatchResult<T>) {
+        self.handler.handle_batch(&self.tenant_id, batch).await;
+    }
+}
+
+/// Batcher of one tenant, closed once retired
+#[derive(Debug)]
+struct TenantBatcher<T> {
+    batcher: Arc<SmartBatcher<T>>,
+    /// Adds hold the read lock; retiring sets it under the write lock
+    closed: tokio::sync::RwLock<bool>,
+}
+
+#[derive(Debug)]
+struct Shard<T> {
+    tenant: Arc<TenantBatcher<T>>,
+    last_used: Instant,
+}
+
+/// Aggregated metrics of all shards
+#[derive(Debug, Clone, Default)]
+pub struct ShardedBatcherMetrics {
+    /// Live tenant batchers
+    pub active_shards: usize,
+    /// Tenant batchers evicted by the LRU cap or for being idle
+    pub evicted_shards: u64,
+    /// Sum of the metrics of every shard, evicted ones included
+    pub totals: BatcherMetrics,
+}
+
+/// Batcher keyed by tenant id
+pub struct ShardedBatcher<T> {
+    config: ShardedBatcherConfig,
+    handler: Arc<dyn TenantBatchHandler<T>>,
+    shards: Mutex<LruCache<String, Shard<T>>>,
+    /// Count and metrics of evicted shards
+    retired: Arc<tokio::sync::Mutex<(u64, BatcherMetrics)>>,
+}
+
+impl<T: Send + 'static> ShardedBatcher<T> {
+    /// Create a sharded batcher delivering flushed batches to `handler`
+    pub fn new(config: ShardedBatcherConfig, handler: Arc<dyn TenantBatchHandler<T>>) -> Self {
+        let capacity = NonZeroUsize::new(config.max_shards).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            config,
+            handler,
+            shards: Mutex::new(LruCache::new(capacity)),
+            retired: Arc::new(tokio::sync::Mutex::new((0, BatcherMetrics::default()))),
+        }
+    }
+
+    /// Add an event to the batch of its tenant
+    pub async fn add_event(&self, tenant_id: &str, event: T) -> Result<(), BatcherError> {
+        loop {
+            let tenant = self.shard(tenant_id)?;
+            let closed = tenant.closed.read().await;
+            // Retired after it was looked up; a new shard takes the event
+            if *closed {
+                continue;
+            }
+            return tenant.batcher.add_event(event).await;
+        }
+    }
+
+    /// Batcher of a tenant, creating (and possibly evicting) shards as needed
+    fn shard(&self, tenant_id: &str) -> Result<Arc<TenantBatcher<T>>, BatcherError> {
+        let mut shards = self.shards.lock().unwrap();
+        if let Some(shard) = shards.get_mut(tenant_id) {
+            shard.last_used = Instant::now();
+            return Ok(shard.tenant.clone());
+        }
+
+        let batcher = Arc::new(SmartBatcher::new(self.config.batcher.clone()));
+        batcher.start(Arc::new(ShardHandler {
+            tenant_id: tenant_id.to_string(),
+            handler: self.handler.clone(),
+        }))?;
+        let tenant = Arc::new(TenantBatcher {
+            batcher,
+            closed: tokio::sync::RwLock::new(false),
+        });
+        let evicted = shards.push(
+            tenant_id.to_string(),
+            Shard {
+                tenant: tenant.clone(),
+                last_used: Instant::now(),
+            },
+        );
+        drop(shards);
+
+        if let Some((evicted_tenant, shard)) = evicted {
+            debug!(
+                "[ShardedBatcher] Shard cap reached, evicting tenant {}",
+                evicted_tenant
+            );
+            // Stopping flushes the shard; don't hold up the caller for it
+            let retired = self.retire(evicted_tenant, shard.tenant);
+            tokio::spawn(retired);
+        }
+        Ok(tenant)
+    }
+
+    /// Close a shard removed from routing, flush its remaining events and
+    /// keep its metrics
+    fn retire(
+        &self,
+        tenant_id: String,
+        tenant: Arc<TenantBatcher<T>>,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        let retired = self.retired.clone();
+        async move {
+            // Wait for in-flight adds and turn later ones away, so none land
+            // after the final flush
+            *tenant.closed.write().await = true;
+            if let Err(e) = tenant.batcher.stop().await {
+                warn!(
+                    "[ShardedBatcher] Stopping shard of {} failed: {}",
+                    tenant_id, e
+                );
+            }
+            let metrics = tenant.batcher.get_metrics().await;
+            let mut retired = retired.lock().await;
+            retired.0 += 1;
+            merge_metrics(&mut retired.1, &metrics);
+        }
+    }
+
+    /// Evict the shards unused for longer than `idle_timeout`
+    ///
+    /// Returns the number of evicted shards.
+    pub async fn evict_idle(&self) -> usize {
+        let idle: Vec<(String, Arc<TenantBatcher<T>>)> = {
+            let mut shards = self.shards.lock().unwrap();
+            let idle_tenants: Vec<String> = shards
+                .iter()
+                .filter(|(_, shard)| shard.last_used.elapsed() >= self.config.idle_timeout)
+                .map(|(tenant, _)| tenant.clone())
+                .collect();
+            idle_tenants
+                .into_iter()
+                .filter_map(|tenant| {
+                    let shard = shards.pop(&tenant)?;
+                    Some((tenant, shard.tenant))
+                })
+                .collect()
+        };
+
+        let evicted = idle.len();
+        for (tenant_id, tenant) in idle {
+            debug!("[ShardedBatcher] Evicting idle tenant {}", tenant_id);
+            self.retire(tenant_id, tenant).await;
+        }
+        evicted
+    }
+
+    /// Flush every shard to the handler
+    pub async fn flush_all(&self) -> Result<(), BatcherError> {
+        for (tenant, batcher) in self.batchers() {
+            let result = batcher.flush().await?;
+            if !result.batch.is_empty() {
+                self.handler.handle_batch(&tenant, result).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop every shard, flushing their remaining events
+    pub async fn stop(&self) {
+        let shards: Vec<(String, Arc<TenantBatcher<T>>)> = {
+            let mut shards = self.shards.lock().unwrap();
+            std::iter::from_fn(|| shards.pop_lru())
+                .map(|(tenant_id, shard)| (tenant_id, shard.tenant))
+                .collect()
+        };
+        info!("[ShardedBatcher] Stopping {} shards", shards.len());
+        for (tenant_id, tenant) in shards {
+            self.retire(tenant_id, tenant).await;
+        }
+    }
+
+    /// Number of live shards
+    pub fn shard_count(&self) -> usize {
+        self.shards.lock().unwrap().len()
+    }
+
+    /// Metrics aggregated across shards
+    pub async fn get_metrics(&self) -> ShardedBatcherMetrics {
+        let (evicted_shards, mut totals) = self.retired.lock().await.clone();
+        let batchers = self.batchers();
+        for (_, batcher) in &batchers {
+            merge_metrics(&mut totals, &batcher.get_metrics().await);
+        }
+        ShardedBatcherMetrics {
+            active_shards: batchers.len(),
+            evicted_shards,
+            totals,
+        }
+    }
+
+    fn batchers(&self) -> Vec<(String, Arc<SmartBatcher<T>>)> {
+        self.shards
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(tenant, shard)| (tenant.clone(), shard.tenant.batcher.clone()))
+            .collect()
+    }
+}
+
+impl<T> std::fmt::Debug for ShardedBatcher<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShardedBatcher")
+            .field("config", &self.config)
+            .field("shards", &self.shards.lock().unwrap().len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Add the metrics of one shard to a running total
+fn merge_metrics(total: &mut BatcherMetrics, shard: &BatcherMetrics) {
+    let batches = total.total_batches + shard.total_batches;
+    if batches > 0 {
+        total.avg_batch_size = (total.avg_batch_size * total.total_batches as f64
+            + shard.avg_batch_size * shard.total_batches as f64)
+            / batches as f64;
+    }
+    total.total_batches = batches;
+    total.total_events += shard.total_events;
+    total.total_flushes += shard.total_flushes;
+    total.avg_batch_age = total.avg_batch_age.max(shard.avg_batch_age);
+    total.queue_size += shard.queue_size;
+    if shard.pressure_level.as_u8() > total.pressure_level.as_u8() {
+        total.pressure_level = shard.pressure_level;
+    }
+    total.adaptive_adjustments += shard.adaptive_adjustments;
+    total.rejected_events += shard.rejected_events;
+    total.dropped_events += shard.dropped_events;
+    total.blocked_events += shard.blocked_events;
+    total.block_timeouts += shard.block_timeouts;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::performance::batcher::BatchingPolicy;
+    use tokio::sync::mpsc;
+    use tokio::time::sleep;
+
+    /// Sends flushed batches to a channel; batches of `blocked_tenant` never complete
+    struct TestHandler {
+        flushed: mpsc::UnboundedSender<(String, Vec<u32>)>,
+        blocked_tenant: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl TenantBatchHandler<u32> for TestHandler {
+        async fn handle_batch(&self, tenant_id: &str, batch: BatchResult<u32>) {
+            if self.blocked_tenant == Some(tenant_id) {
+                std::future::pending::<()>().await;
+            }
+            let _ = self.flushed.send((tenant_id.to_string(), batch.batch));
+        }
+    }
+
+    fn sharded(
+        max_shards: usize,
+        idle_timeout: Duration,
+        blocked_tenant: Option<&'static str>,
+    ) -> (
+        ShardedBatcher<u32>,
+        mpsc::UnboundedReceiver<(String, Vec<u32>)>,
+    ) {
+        let (flushed, rx) = mpsc::unbounded_channel();
+        let config = ShardedBatcherConfig {
+            batcher: BatcherConfig {
+                policy: BatchingPolicy::TimeBased(Duration::from_millis(50)),
+                adaptive_tuning: false,
+                ..Default::default()
+            },
+            max_shards,
+            idle_timeout,
+        };
+        let handler = Arc::new(TestHandler {
+            flushed,
+            blocked_tenant,
+        });
+        (ShardedBatcher::new(config, handler), rx)
+    }
+
+    #[tokio::test]
+    async fn test_blocked_tenant_does_not_delay_others() {
+        let (batcher, mut rx) = sharded(10, Duration::from_secs(300), Some("tenant-slow"));
+
+        batcher.add_event("tenant-slow", 1).await.unwrap();
+        sleep(Duration::from_millis(80)).await;
+        // tenant-slow's sink is now stuck on its first batch
+        batcher.add_event("tenant-slow", 2).await.unwrap();
+        batcher.add_event("tenant-fast", 10).await.unwrap();
+        batcher.add_event("tenant-fast", 11).await.unwrap();
+        sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            ("tenant-fast".to_string(), vec![10, 11])
+        );
+        assert!(rx.try_recv().is_err());
+
+        let metrics = batcher.get_metrics().await;
+        assert_eq!(metrics.active_shards, 2);
+        assert_eq!(metrics.totals.total_events, 3);
+        assert_eq!(metrics.totals.queue_size, 1);
+    }
+
+    #[tokio::test]
+    async fn test_evicted_shards_flush_remaining_events() {
+        let (batcher, mut rx) = sharded(1, Duration::ZERO, None);
+
+        batcher.add_event("tenant-a", 1).await.unwrap();
+        batcher.add_event("tenant-b", 2).await.unwrap();
+        assert_eq!(batcher.shard_count(), 1);
+        assert_eq!(rx.recv().await.unwrap(), ("tenant-a".to_string(), vec![1]));
+
+        assert_eq!(batcher.evict_idle().await, 1);
+        assert_eq!(rx.recv().await.unwrap(), ("tenant-b".to_string(), vec![2]));
+
+        let metrics = batcher.get_metrics().await;
+        assert_eq!(metrics.active_shards, 0);
+        assert_eq!(metrics.evicted_shards, 2);
+        assert_eq!(metrics.totals.total_events, 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_adds_racing_with_retire_are_not_lost() {
+        let (batcher, mut rx) = sharded(10, Duration::ZERO, None);
+        let batcher = Arc::new(batcher);
+
+        let producers: Vec<_> = (0..4)
+            .map(|producer| {
+                let batcher = batcher.clone();
+                tokio::spawn(async move {
+                    for i in 0..250 {
+                        batcher
+                            .add_event("tenant-a", producer * 1000 + i)
+                            .await
+                            .unwrap();
+                        // Keep adding for long enough to overlap evictions
+                        if i % 25 == 0 {
+                            sleep(Duration::from_millis(1)).await;
+                        }
+                    }
+                })
+            })
+            .collect();
+        let mut evictions = 0;
+        while !producers.iter().all(|producer| producer.is_finished()) {
+            evictions += batcher.evict_idle().await;
+            tokio::task::yield_now().await;
+        }
+        for producer in producers {
+            producer.await.unwrap();
+        }
+        batcher.stop().await;
+
+        let mut delivered = Vec::new();
+        while let Ok((_, batch)) = rx.try_recv() {
+            delivered.extend(batch);
+        }
+        delivered.sort_unstable();
+        delivered.dedup();
+        assert!(evictions > 0);
+        assert_eq!(delivered.len(), 1000);
+    }
+}
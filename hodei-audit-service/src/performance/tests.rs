@@ -12,7 +12,8 @@ use std::time::Duration;
 
 use crate::performance::{
     BackpressureConfig, BackpressureController, BatcherConfig, BatchingPolicy, CircuitBreaker,
-    CircuitBreakerConfig, CircuitState, ConnectionPool, PoolConfig, PressureLevel, SmartBatcher,
+    CircuitBreakerConfig, CircuitState, ConnectionPool, OverflowPolicy, PoolConfig, PressureLevel,
+    SmartBatcher,
 };
 
 #[cfg(test)]
@@ -30,6 +31,7 @@ mod tests {
             adaptive_tuning: false,
             backpressure_controller: None,
             enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
         };
 
         let batcher = SmartBatcher::new(config);
@@ -59,6 +61,7 @@ mod tests {
             adaptive_tuning: false,
             backpressure_controller: None,
             enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
         };
 
         let batcher = SmartBatcher::new(config);
@@ -92,6 +95,7 @@ mod tests {
             adaptive_tuning: true,
             backpressure_controller: None,
             enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
         };
 
         let batcher = SmartBatcher::new(config);
@@ -115,6 +119,7 @@ mod tests {
             adaptive_tuning: false,
             backpressure_controller: None,
             enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
         };
 
         let batcher = SmartBatcher::new(config);
@@ -544,6 +549,7 @@ mod tests {
             adaptive_tuning: true,
             backpressure_controller: None,
             enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
         };
 
         let circuit_breaker_config = CircuitBreakerConfig {
@@ -603,6 +609,7 @@ mod tests {
             adaptive_tuning: false,
             backpressure_controller: None,
             enable_metrics: true,
+            overflow_policy: OverflowPolicy::RejectNew,
         };
 
         let batcher = SmartBatcher::new(config);
@@ -0,0 +1,173 @@
+//! Throughput harness for the batching pipeline
+//!
+//! Drives fixture events through a `SmartBatcher` into an in-memory sink,
+//! with the same inline add/flush loop as the stream ingestion, and measures
+//! sustained events/sec. The criterion benchmark and the throughput
+//! guardrail test both run it, so a regression below the floor fails
+//! instead of going unnoticed.
+
+use std::time::{Duration, Instant};
+
+use crate::fixtures::{EventFixtureGenerator, FixtureConfig};
+use crate::performance::{BatcherConfig, BatchingPolicy, SmartBatcher};
+use crate::storage::{InMemoryStorage, StorageBackend};
+
+/// Environment variable overriding the throughput floor (events/sec)
+pub const MIN_EVENTS_PER_SEC_ENV: &str = "HODEI_AUDIT_MIN_EVENTS_PER_SEC";
+
+/// Throughput the batching pipeline is expected to sustain
+pub const DEFAULT_MIN_EVENTS_PER_SEC: f64 = 100_000.0;
+
+/// Throughput floor from [`MIN_EVENTS_PER_SEC_ENV`], or the default
+pub fn min_events_per_sec() -> f64 {
+    std::env::var(MIN_EVENTS_PER_SEC_ENV)
+        .ok()
+        .and_then(|floor| floor.parse().ok())
+        .unwrap_or(DEFAULT_MIN_EVENTS_PER_SEC)
+}
+
+/// Shape of a throughput run
+#[derive(Debug, Clone)]
+pub struct ThroughputConfig {
+    /// Events pushed through the pipeline
+    pub events: usize,
+    /// Size that triggers a flush
+    pub batch_size: usize,
+    /// Age that triggers a flush
+    pub max_batch_age: Duration,
+    /// Events are generated up front, outside the timed section
+    pub fixture: FixtureConfig,
+}
+
+impl Default for ThroughputConfig {
+    fn default() -> Self {
+        Self {
+            events: 100_000,
+            batch_size: 1000,
+            max_batch_age: Duration::from_millis(100),
+            fixture: FixtureConfig::default(),
+        }
+    }
+}
+
+/// Outcome of a throughput run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThroughputReport {
+    /// Events stored in the sink
+    pub events: u64,
+    /// Batches flushed to the sink
+    pub batches: u64,
+    /// Time from the first event added to the last batch stored
+    pub elapsed: Duration,
+}
+
+impl ThroughputReport {
+    /// Sustained throughput of the run
+    pub fn events_per_sec(&self) -> f64 {
+        self.events as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Fail if the run sustained less than `min_events_per_sec`
+    pub fn check_floor(&self, min_events_per_sec: f64) -> Result<(), anyhow::Error> {
+        let events_per_sec = self.events_per_sec();
+        if events_per_sec < min_events_per_sec {
+            anyhow::bail!(
+                "Batching pipeline sustained {:.0} events/sec, below the floor of {:.0}",
+                events_per_sec,
+                min_events_per_sec
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Runs fixture events through `SmartBatcher` into an in-memory sink
+#[derive(Debug, Clone, Default)]
+pub struct ThroughputHarness {
+    config: ThroughputConfig,
+}
+
+impl ThroughputHarness {
+    pub fn new(config: ThroughputConfig) -> Self {
+        Self { config }
+    }
+
+    /// Push every event through the pipeline and time it
+    pub async fn run(&self) -> Result<ThroughputReport, anyhow::Error> {
+        let events =
+            EventFixtureGenerator::new(self.config.fixture.clone())?.batch(self.config.events);
+        let sink = InMemoryStorage::new();
+        let batcher = SmartBatcher::new(BatcherConfig {
+            max_queue_size: self.config.batch_size,
+            policy: BatchingPolicy::Hybrid {
+                max_time: self.config.max_batch_age,
+                max_size: self.config.batch_size,
+            },
+            adaptive_tuning: false,
+            enable_metrics: false,
+            ..Default::default()
+        });
+
+        let mut batches = 0;
+        let started = Instant::now();
+        for event in events {
+            batcher.add_event(event).await?;
+            if batcher.flush_due().await {
+                sink.store_batch(&batcher.flush().await?.batch).await?;
+                batches += 1;
+            }
+        }
+        let rest = batcher.flush().await?;
+        if !rest.batch.is_empty() {
+            sink.store_batch(&rest.batch).await?;
+            batches += 1;
+        }
+        let elapsed = started.elapsed();
+
+        if sink.len() != self.config.events {
+            anyhow::bail!(
+                "Sink holds {} events, expected {}",
+                sink.len(),
+                self.config.events
+            );
+        }
+        Ok(ThroughputReport {
+            events: sink.len() as u64,
+            batches,
+            elapsed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_harness_reports_plausible_throughput() {
+        let report = ThroughputHarness::new(ThroughputConfig {
+            events: 5_000,
+            batch_size: 500,
+            ..Default::default()
+        })
+        .run()
+        .await
+        .unwrap();
+
+        assert_eq!(report.events, 5_000);
+        assert_eq!(report.batches, 10);
+        let events_per_sec = report.events_per_sec();
+        assert!(events_per_sec.is_finite() && events_per_sec > 0.0);
+        assert!(report.check_floor(0.0).is_ok());
+        assert!(report.check_floor(f64::MAX).is_err());
+    }
+
+    /// Guardrail for the 100K events/sec target; meaningful only on a
+    /// release build: `cargo test --release -- --ignored throughput_floor`
+    #[tokio::test]
+    #[ignore]
+    async fn test_batching_pipeline_meets_throughput_floor() {
+        let report = ThroughputHarness::default().run().await.unwrap();
+        report.check_floor(min_events_per_sec()).unwrap();
+    }
+}
@@ -5,7 +5,8 @@
 
 use hodei_audit_proto::AuditEvent;
 use hodei_audit_types::hrn::Hrn;
-use std::time::SystemTime;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 use tracing::info;
 
 /// Query result with events and metadata
@@ -369,6 +370,138 @@ impl Default for QueryEngine {
     }
 }
 
+/// Default gap allowed between consecutive events of a correlated group
+pub const DEFAULT_CORRELATION_WINDOW: Duration = Duration::from_secs(300);
+
+/// What the events of a group have in common
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CorrelationKey {
+    /// Same `correlation_id`
+    CorrelationId(String),
+    /// Same `trace_id`, no `correlation_id`
+    TraceId(String),
+    /// Same user, close in time, neither id set
+    User(String),
+    /// A single event with nothing to correlate on, by event id
+    Uncorrelated(String),
+}
+
+/// Related audit events, oldest first
+#[derive(Debug, Clone)]
+pub struct EventGroup {
+    pub key: CorrelationKey,
+    pub events: Vec<AuditEvent>,
+}
+
+impl EventGroup {
+    /// Time of the first event
+    pub fn start_time(&self) -> Option<SystemTime> {
+        self.events.first().and_then(event_time)
+    }
+
+    /// Time of the last event
+    pub fn end_time(&self) -> Option<SystemTime> {
+        self.events.last().and_then(event_time)
+    }
+}
+
+/// How `correlate` groups events
+#[derive(Debug, Clone)]
+pub struct CorrelationConfig {
+    /// Largest gap between consecutive events of a group; a longer gap
+    /// starts a new group even if the key matches
+    pub window: Duration,
+    /// Cluster events without correlation or trace id by user
+    pub cluster_by_user: bool,
+}
+
+impl Default for CorrelationConfig {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_CORRELATION_WINDOW,
+            cluster_by_user: false,
+        }
+    }
+}
+
+/// Group related events, for a "related events" view
+///
+/// Events are keyed by `correlation_id`, else `trace_id`, else (when
+/// enabled) user. Groups are ordered by their first event and hold their
+/// events in time order; events without a time sort first.
+pub fn correlate(mut events: Vec<AuditEvent>, config: &CorrelationConfig) -> Vec<EventGroup> {
+    events.sort_by_key(|event| event_time(event).unwrap_or(SystemTime::UNIX_EPOCH));
+
+    let mut groups: Vec<EventGroup> = Vec::new();
+    // Group currently accepting events for each key, with its last event time
+    let mut open: HashMap<CorrelationKey, (usize, Option<SystemTime>)> = HashMap::new();
+
+    for event in events {
+        let time = event_time(&event);
+        let key = correlation_key(&event, config.cluster_by_user);
+        if matches!(key, CorrelationKey::Uncorrelated(_)) {
+            groups.push(EventGroup {
+                key,
+                events: vec![event],
+            });
+            continue;
+        }
+
+        let joins = open.get(&key).filter(|(_, last)| match (last, time) {
+            (Some(last), Some(time)) => time
+                .duration_since(*last)
+                .is_ok_and(|gap| gap <= config.window),
+            _ => true,
+        });
+        match joins {
+            Some(&(index, _)) => {
+                groups[index].events.push(event);
+                open.insert(key, (index, time));
+            }
+            None => {
+                open.insert(key.clone(), (groups.len(), time));
+                groups.push(EventGroup {
+                    key,
+                    events: vec![event],
+                });
+            }
+        }
+    }
+    groups
+}
+
+fn correlation_key(event: &AuditEvent, cluster_by_user: bool) -> CorrelationKey {
+    let user_id = event
+        .user_identity
+        .as_ref()
+        .map(|u| u.user_id.as_str())
+        .filter(|u| !u.is_empty());
+    if !event.correlation_id.is_empty() {
+        CorrelationKey::CorrelationId(event.correlation_id.clone())
+    } else if !event.trace_id.is_empty() {
+        CorrelationKey::TraceId(event.trace_id.clone())
+    } else if let Some(user_id) = user_id.filter(|_| cluster_by_user) {
+        CorrelationKey::User(user_id.to_string())
+    } else {
+        CorrelationKey::Uncorrelated(
+            event
+                .event_id
+                .as_ref()
+                .map(|id| id.value.clone())
+                .unwrap_or_default(),
+        )
+    }
+}
+
+fn event_time(event: &AuditEvent) -> Option<SystemTime> {
+    event.event_time.as_ref().and_then(|t| {
+        SystemTime::UNIX_EPOCH.checked_add(Duration::new(
+            u64::try_from(t.seconds).ok()?,
+            u32::try_from(t.nanos).ok()?,
+        ))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -522,4 +655,103 @@ mod tests {
         assert_eq!(result.total_count, 100);
         assert!(result.has_more);
     }
+
+    fn correlated_event(
+        id: &str,
+        correlation_id: &str,
+        trace_id: &str,
+        seconds: i64,
+    ) -> AuditEvent {
+        AuditEvent {
+            correlation_id: correlation_id.to_string(),
+            trace_id: trace_id.to_string(),
+            event_time: Some(prost_types::Timestamp { seconds, nanos: 0 }),
+            ..create_test_event(id, "tenant1", "user1", "GET")
+        }
+    }
+
+    fn group_ids(groups: &[EventGroup]) -> Vec<Vec<String>> {
+        groups
+            .iter()
+            .map(|g| {
+                g.events
+                    .iter()
+                    .map(|e| e.event_id.as_ref().unwrap().value.clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_correlate_groups_by_correlation_id() {
+        let events = vec![
+            correlated_event("side-effect", "req-1", "trace-a", 1_010),
+            correlated_event("other", "req-2", "trace-b", 1_005),
+            correlated_event("action", "req-1", "trace-c", 1_000),
+        ];
+
+        let groups = correlate(events, &CorrelationConfig::default());
+
+        assert_eq!(
+            group_ids(&groups),
+            vec![vec!["action", "side-effect"], vec!["other"]]
+        );
+        assert_eq!(
+            groups[0].key,
+            CorrelationKey::CorrelationId("req-1".to_string())
+        );
+        assert_eq!(
+            groups[1].key,
+            CorrelationKey::CorrelationId("req-2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_correlate_falls_back_to_trace_id_and_splits_on_window() {
+        let config = CorrelationConfig {
+            window: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let events = vec![
+            correlated_event("t1", "", "trace-a", 1_000),
+            correlated_event("t2", "", "trace-a", 1_030),
+            // Same trace, but well past the window
+            correlated_event("t3", "", "trace-a", 2_000),
+        ];
+
+        let groups = correlate(events, &config);
+
+        assert_eq!(group_ids(&groups), vec![vec!["t1", "t2"], vec!["t3"]]);
+        assert!(
+            groups
+                .iter()
+                .all(|g| g.key == CorrelationKey::TraceId("trace-a".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_correlate_clusters_by_user_only_when_enabled() {
+        let events = vec![
+            correlated_event("u1", "", "", 1_000),
+            correlated_event("u2", "", "", 1_100),
+        ];
+
+        let separate = correlate(events.clone(), &CorrelationConfig::default());
+        assert_eq!(group_ids(&separate), vec![vec!["u1"], vec!["u2"]]);
+        assert_eq!(
+            separate[0].key,
+            CorrelationKey::Uncorrelated("u1".to_string())
+        );
+
+        let clustered = correlate(
+            events,
+            &CorrelationConfig {
+                cluster_by_user: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(group_ids(&clustered), vec![vec!["u1", "u2"]]);
+        assert_eq!(clustered[0].key, CorrelationKey::User("user1".to_string()));
+        assert!(clustered[0].start_time() < clustered[0].end_time());
+    }
 }
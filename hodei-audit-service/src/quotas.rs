@@ -298,6 +298,13 @@ impl QuotaManager {
         Ok(())
     }
 
+    /// Remove a tenant's quota and usage history
+    pub fn remove_tenant(&mut self, tenant_id: &str) -> bool {
+        info!("[Quota] Removing quota for tenant {}", tenant_id);
+        self.usage_history.remove(tenant_id);
+        self.tenant_quotas.remove(tenant_id).is_some()
+    }
+
     /// Record usage for abuse detection
     fn record_usage(&mut self, tenant_id: &str, quota_type: QuotaType, amount: u64) {
         let now = SystemTime::now();
@@ -0,0 +1,760 @@
+//! Event routing
+//!
+//! An `EventRouter` decides, after enrichment, where each event goes: which
+//! storage tiers, extra sinks and webhook notifiers. Rules match on
+//! `EventCategory`, `Outcome` and an HRN pattern (`*` matches any run of
+//! characters), and every matching rule adds its targets. Events no rule
+//! matches go to the default targets, by default the tier
+//! `TieredStorage::determine_tier` picks from the event's age.
+//!
+//! Tier targets are stored through `TieredStorage::store_in_tiers`, so they
+//! get its dedup window and hot tier fallback. Tiers are written first and
+//! are what makes a route succeed; sinks are secondary, and a sink that
+//! fails after the event was stored elsewhere is only logged and counted.
+//!
+//! A `MirrorConfig` additionally copies a sample of the routed events, plus
+//! every event its rules match, to a staging sink. Mirrored copies carry the
+//! `mirrored` metadata flag, and failing to mirror never fails the route.
+//!
+//! `EventRouter` is itself a `StorageBackend`: the ingest services store
+//! through it, so writes are routed while reads go to the tiered storage.
+
+use crate::storage::{
+    EventStream, QueryFilter, StorageBackend, StorageStats, StorageTierType, TieredStorage,
+};
+use crate::webhook::WebhookNotifier;
+use hodei_audit_proto::{AuditEvent, MetadataExt};
+use hodei_audit_types::{EventCategory, Outcome};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, warn};
+
+/// Destination of a routed event
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteTarget {
+    /// The tier `TieredStorage::determine_tier` picks for the event's age
+    AgeBasedTier,
+    /// A specific tier, whatever the event's age
+    Tier(StorageTierType),
+    /// A storage backend registered with `EventRouter::with_sink`
+    Sink(String),
+    /// A notifier registered with `EventRouter::with_notifier`
+    Notifier(String),
+}
+
+/// Sends the events matching every set criterion to `targets`
+///
+/// A rule without criteria matches every event.
+#[derive(Debug, Clone)]
+pub struct RouteRule {
+    name: String,
+    categories: Vec<EventCategory>,
+    outcomes: Vec<Outcome>,
+    hrn_pattern: Option<String>,
+    targets: Vec<RouteTarget>,
+}
+
+impl RouteRule {
+    /// Create a rule routing to `targets`
+    pub fn new(name: impl Into<String>, targets: Vec<RouteTarget>) -> Self {
+        Self {
+            name: name.into(),
+            categories: Vec::new(),
+            outcomes: Vec::new(),
+            hrn_pattern: None,
+            targets,
+        }
+    }
+
+    /// Only match events of one of `categories`
+    pub fn with_categories(mut self, categories: Vec<EventCategory>) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    /// Only match events with one of `outcomes`
+    pub fn with_outcomes(mut self, outcomes: Vec<Outcome>) -> Self {
+        self.outcomes = outcomes;
+        self
+    }
+
+    /// Only match events whose HRN matches `pattern`
+    pub fn with_hrn_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.hrn_pattern = Some(pattern.into());
+        self
+    }
+
+    /// Rule name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the event matches this rule
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        (self.categories.is_empty()
+            || self
+                .categories
+                .contains(&EventCategory::from(event.event_category)))
+            && (self.outcomes.is_empty() || self.outcomes.contains(&Outcome::from(event.outcome)))
+            && self.hrn_pattern.as_ref().is_none_or(|pattern| {
+                event
+                    .hrn
+                    .as_ref()
+                    .is_some_and(|hrn| glob_matches(pattern, &crate::exporters::hrn_string(hrn)))
+            })
+    }
+}
+
+/// Metadata flag set on the copies a `MirrorConfig` forwards
+pub const MIRRORED_METADATA_KEY: &str = "mirrored";
+
+/// Copies a sample of the routed events to a staging sink
+///
+/// Sampling hashes the event_id, so a given event is always either mirrored
+/// or not, even when routed again.
+#[derive(Clone)]
+pub struct MirrorConfig {
+    sink: Arc<dyn StorageBackend>,
+    sample_rate: f64,
+    rules: Vec<RouteRule>,
+}
+
+impl MirrorConfig {
+    /// Mirror to `sink` a `sample_rate` fraction (0.0 to 1.0) of the events
+    pub fn new(sink: Arc<dyn StorageBackend>, sample_rate: f64) -> Self {
+        Self {
+            sink,
+            sample_rate: sample_rate.clamp(0.0, 1.0),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Always mirror the events matching `rule`; its targets are ignored
+    pub fn with_rule(mut self, rule: RouteRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Whether an event is mirrored
+    pub fn should_mirror(&self, event: &AuditEvent) -> bool {
+        if self.rules.iter().any(|rule| rule.matches(event)) {
+            return true;
+        }
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        event
+            .event_id
+            .as_ref()
+            .map(|id| id.value.as_str())
+            .hash(&mut hasher);
+        (hasher.finish() as f64 / u64::MAX as f64) < self.sample_rate
+    }
+}
+
+impl std::fmt::Debug for MirrorConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MirrorConfig")
+            .field("sample_rate", &self.sample_rate)
+            .field("rules", &self.rules)
+            .finish()
+    }
+}
+
+/// Rules, sinks, notifiers and mirror of an `EventRouter`
+#[derive(Clone, Default)]
+pub struct RoutingConfig {
+    /// Rules, in order; events none matches go to the age-based tier
+    pub rules: Vec<RouteRule>,
+    /// Storage backends the rules can name as `RouteTarget::Sink`
+    pub sinks: HashMap<String, Arc<dyn StorageBackend>>,
+    /// Notifiers the rules can name as `RouteTarget::Notifier`
+    pub notifiers: HashMap<String, Arc<WebhookNotifier>>,
+    /// Staging sink receiving a sample of the routed events
+    pub mirror: Option<MirrorConfig>,
+}
+
+impl std::fmt::Debug for RoutingConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RoutingConfig")
+            .field("rules", &self.rules)
+            .field("sinks", &self.sinks.keys().collect::<Vec<_>>())
+            .field("notifiers", &self.notifiers.keys().collect::<Vec<_>>())
+            .field("mirror", &self.mirror)
+            .finish()
+    }
+}
+
+/// Routes events to tiers, sinks and notifiers by rule
+pub struct EventRouter {
+    storage: Arc<TieredStorage>,
+    rules: Vec<RouteRule>,
+    default_targets: Vec<RouteTarget>,
+    sinks: HashMap<String, Arc<dyn StorageBackend>>,
+    notifiers: HashMap<String, Arc<WebhookNotifier>>,
+    mirror: Option<MirrorConfig>,
+    mirror_failures: AtomicU64,
+    sink_failures: AtomicU64,
+}
+
+impl EventRouter {
+    /// Route into `storage`, by age until rules are added
+    pub fn new(storage: Arc<TieredStorage>) -> Self {
+        Self {
+            storage,
+            rules: Vec::new(),
+            default_targets: vec![RouteTarget::AgeBasedTier],
+            sinks: HashMap::new(),
+            notifiers: HashMap::new(),
+            mirror: None,
+            mirror_failures: AtomicU64::new(0),
+            sink_failures: AtomicU64::new(0),
+        }
+    }
+
+    /// Route into `storage` as `config` describes
+    pub fn from_config(storage: Arc<TieredStorage>, config: &RoutingConfig) -> Self {
+        Self {
+            rules: config.rules.clone(),
+            sinks: config.sinks.clone(),
+            notifiers: config.notifiers.clone(),
+            mirror: config.mirror.clone(),
+            ..Self::new(storage)
+        }
+    }
+
+    /// Append a rule
+    pub fn with_rule(mut self, rule: RouteRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Targets of the events no rule matches
+    pub fn with_default_targets(mut self, targets: Vec<RouteTarget>) -> Self {
+        self.default_targets = targets;
+        self
+    }
+
+    /// Register a storage backend as `RouteTarget::Sink(name)`
+    pub fn with_sink(mut self, name: impl Into<String>, sink: Arc<dyn StorageBackend>) -> Self {
+        self.sinks.insert(name.into(), sink);
+        self
+    }
+
+    /// Register a notifier as `RouteTarget::Notifier(name)`
+    pub fn with_notifier(
+        mut self,
+        name: impl Into<String>,
+        notifier: Arc<WebhookNotifier>,
+    ) -> Self {
+        self.notifiers.insert(name.into(), notifier);
+        self
+    }
+
+    /// Mirror a sample of the routed events to a staging sink
+    pub fn with_mirror(mut self, mirror: MirrorConfig) -> Self {
+        self.mirror = Some(mirror);
+        self
+    }
+
+    /// The tiered storage as a `StorageBackend`, which answers the reads
+    fn reads(&self) -> &dyn StorageBackend {
+        self.storage.as_ref()
+    }
+
+    /// Mirrored copies the staging sink failed to store
+    pub fn mirror_failures(&self) -> u64 {
+        self.mirror_failures.load(Ordering::Relaxed)
+    }
+
+    /// Sink writes that failed after the event was stored elsewhere
+    pub fn sink_failures(&self) -> u64 {
+        self.sink_failures.load(Ordering::Relaxed)
+    }
+
+    /// Targets for an event, without duplicates, in rule order
+    pub fn targets_for(&self, event: &AuditEvent) -> Vec<RouteTarget> {
+        let mut targets = Vec::new();
+        for rule in self.rules.iter().filter(|rule| rule.matches(event)) {
+            debug!("[Router] rule={} matched", rule.name);
+            for target in &rule.targets {
+                if !targets.contains(target) {
+                    targets.push(target.clone());
+                }
+            }
+        }
+        if targets.is_empty() {
+            targets = self.default_targets.clone();
+        }
+        targets
+    }
+
+    /// Deliver an event to each of its targets, returning those it reached
+    ///
+    /// Tier targets are stored together, with dedup and hot tier fallback,
+    /// and a tier failure fails the route before anything else is written.
+    /// Sinks come next: a failing sink is logged and counted in
+    /// `sink_failures`, and only fails the route when no tier or other sink
+    /// stored the event. Notifiers are then dispatched in the background.
+    /// Targets naming an unregistered sink or notifier are skipped with a
+    /// warning. The event is mirrored once delivered; mirroring errors are
+    /// only logged.
+    pub async fn route(&self, event: &AuditEvent) -> Result<Vec<RouteTarget>, anyhow::Error> {
+        let targets = self.targets_for(event);
+        let tiers: Vec<StorageTierType> = targets
+            .iter()
+            .filter_map(|target| match target {
+                RouteTarget::AgeBasedTier => Some(self.storage.age_tier(event)),
+                RouteTarget::Tier(tier) => Some(*tier),
+                _ => None,
+            })
+            .collect();
+        if !tiers.is_empty() {
+            self.storage.store_in_tiers(event, &tiers).await?;
+        }
+
+        let mut delivered = Vec::with_capacity(targets.len());
+        let mut sink_error = None;
+        for target in &targets {
+            match target {
+                RouteTarget::AgeBasedTier | RouteTarget::Tier(_) => {}
+                RouteTarget::Sink(name) => match self.sinks.get(name) {
+                    Some(sink) => {
+                        if let Err(e) = sink.store_event(event).await {
+                            self.sink_failures.fetch_add(1, Ordering::Relaxed);
+                            warn!("[Router] Failed to store event in sink {}: {}", name, e);
+                            sink_error = Some(e);
+                            continue;
+                        }
+                    }
+                    None => {
+                        warn!("[Router] Unknown sink {}", name);
+                        continue;
+                    }
+                },
+                RouteTarget::Notifier(_) => continue,
+            }
+            delivered.push(target.clone());
+        }
+        if let Some(e) = sink_error
+            && delivered.is_empty()
+        {
+            return Err(e);
+        }
+
+        // Notifiers only hear about events that were stored
+        for target in targets {
+            if let RouteTarget::Notifier(name) = &target {
+                match self.notifiers.get(name) {
+                    Some(notifier) => notifier.dispatch(event.clone()),
+                    None => {
+                        warn!("[Router] Unknown notifier {}", name);
+                        continue;
+                    }
+                }
+                delivered.push(target);
+            }
+        }
+
+        self.mirror(event).await;
+        Ok(delivered)
+    }
+
+    async fn mirror(&self, event: &AuditEvent) {
+        let Some(mirror) = &self.mirror else {
+            return;
+        };
+        if !mirror.should_mirror(event) {
+            return;
+        }
+        let mut copy = event.clone();
+        copy.set_json(MIRRORED_METADATA_KEY, serde_json::Value::Bool(true));
+        if let Err(e) = mirror.sink.store_event(&copy).await {
+            self.mirror_failures.fetch_add(1, Ordering::Relaxed);
+            warn!("[Router] Failed to mirror event: {}", e);
+        }
+    }
+}
+
+/// Writes are routed; everything else is answered by the tiered storage
+#[async_trait::async_trait]
+impl StorageBackend for EventRouter {
+    async fn store_event(&self, event: &AuditEvent) -> Result<(), anyhow::Error> {
+        self.route(event).await?;
+        Ok(())
+    }
+
+    async fn store_batch(&self, events: &[AuditEvent]) -> Result<(), anyhow::Error> {
+        for event in events {
+            self.route(event).await?;
+        }
+        Ok(())
+    }
+
+    async fn query_events(&self, filter: &QueryFilter) -> Result<Vec<AuditEvent>, anyhow::Error> {
+        self.reads().query_events(filter).await
+    }
+
+    fn query_events_stream<'a>(&'a self, filter: &'a QueryFilter) -> EventStream<'a> {
+        self.reads().query_events_stream(filter)
+    }
+
+    async fn count_events(&self, filter: &QueryFilter) -> Result<u64, anyhow::Error> {
+        self.reads().count_events(filter).await
+    }
+
+    async fn health_check(&self) -> Result<bool, anyhow::Error> {
+        self.reads().health_check().await
+    }
+
+    async fn delete_events(&self, filter: &QueryFilter) -> Result<u64, anyhow::Error> {
+        self.reads().delete_events(filter).await
+    }
+
+    fn get_stats(&self) -> StorageStats {
+        self.reads().get_stats()
+    }
+
+    fn might_contain_event(&self, event_id: &str) -> bool {
+        self.reads().might_contain_event(event_id)
+    }
+}
+
+impl std::fmt::Debug for EventRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventRouter")
+            .field("rules", &self.rules)
+            .field("default_targets", &self.default_targets)
+            .field("sinks", &self.sinks.keys().collect::<Vec<_>>())
+            .field("notifiers", &self.notifiers.keys().collect::<Vec<_>>())
+            .field("mirror", &self.mirror)
+            .finish()
+    }
+}
+
+/// Match `text` against a pattern where `*` stands for any run of characters
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*`: the whole text must match
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{InMemoryStorage, LifecyclePolicy, PartitionStrategy};
+    use crate::webhook::WebhookConfig;
+    use hodei_audit_proto::{EventId, Hrn, TenantId};
+
+    struct Tiers {
+        hot: Arc<InMemoryStorage>,
+        warm: Arc<InMemoryStorage>,
+        cold: Arc<InMemoryStorage>,
+    }
+
+    fn tiered_storage() -> (Arc<TieredStorage>, Tiers) {
+        let tiers = Tiers {
+            hot: Arc::new(InMemoryStorage::new()),
+            warm: Arc::new(InMemoryStorage::new()),
+            cold: Arc::new(InMemoryStorage::new()),
+        };
+        let storage = TieredStorage::from_backends(
+            tiers.hot.clone(),
+            tiers.warm.clone(),
+            tiers.cold.clone(),
+            LifecyclePolicy::default(),
+            PartitionStrategy::default(),
+        );
+        (Arc::new(storage), tiers)
+    }
+
+    fn event(id: &str, category: EventCategory, outcome: Outcome, read_only: bool) -> AuditEvent {
+        AuditEvent {
+            event_id: Some(EventId {
+                value: id.to_string(),
+            }),
+            tenant_id: Some(TenantId {
+                value: "tenant-1".to_string(),
+            }),
+            hrn: Some(Hrn {
+                partition: "hodei".to_string(),
+                service: "verified-permissions".to_string(),
+                tenant_id: "tenant-1".to_string(),
+                region: "global".to_string(),
+                resource_type: "policy-store".to_string(),
+                resource_path: "default".to_string(),
+            }),
+            event_category: i32::from(category),
+            outcome: i32::from(outcome),
+            read_only,
+            event_time: Some(prost_types::Timestamp::from(std::time::SystemTime::now())),
+            ..Default::default()
+        }
+    }
+
+    fn router(storage: Arc<TieredStorage>) -> EventRouter {
+        let notifier = Arc::new(WebhookNotifier::new(WebhookConfig::new(
+            "http://127.0.0.1:9/hooks",
+            "s3cret",
+        )));
+        EventRouter::new(storage)
+            .with_notifier("security", notifier)
+            .with_rule(
+                RouteRule::new(
+                    "security-failures",
+                    vec![
+                        RouteTarget::Tier(StorageTierType::Hot),
+                        RouteTarget::Notifier("security".to_string()),
+                    ],
+                )
+                .with_categories(vec![EventCategory::Insight])
+                .with_outcomes(vec![Outcome::Failure, Outcome::Denied]),
+            )
+            .with_default_targets(vec![RouteTarget::Tier(StorageTierType::Warm)])
+    }
+
+    #[tokio::test]
+    async fn test_security_failure_goes_to_hot_tier_and_notifier() {
+        let (storage, tiers) = tiered_storage();
+        let router = router(storage);
+
+        let targets = router
+            .route(&event(
+                "sec-1",
+                EventCategory::Insight,
+                Outcome::Denied,
+                false,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            targets,
+            vec![
+                RouteTarget::Tier(StorageTierType::Hot),
+                RouteTarget::Notifier("security".to_string()),
+            ]
+        );
+        assert_eq!(tiers.hot.len(), 1);
+        assert!(tiers.warm.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_routine_read_goes_to_default_tier_only() {
+        let (storage, tiers) = tiered_storage();
+        let router = router(storage);
+
+        let targets = router
+            .route(&event(
+                "read-1",
+                EventCategory::Data,
+                Outcome::Success,
+                true,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(targets, vec![RouteTarget::Tier(StorageTierType::Warm)]);
+        assert_eq!(tiers.warm.len(), 1);
+        assert!(tiers.hot.is_empty());
+        assert!(tiers.cold.is_empty());
+    }
+
+    #[test]
+    fn test_hrn_pattern() {
+        let rule = RouteRule::new("policies", vec![RouteTarget::AgeBasedTier])
+            .with_hrn_pattern("hrn:hodei:verified-permissions:*:policy-store/*");
+        assert!(rule.matches(&event("e", EventCategory::Data, Outcome::Success, true)));
+
+        let rule = rule.with_hrn_pattern("hrn:hodei:api:*");
+        assert!(!rule.matches(&event("e", EventCategory::Data, Outcome::Success, true)));
+
+        assert!(glob_matches("a*c*e", "abcde"));
+        assert!(!glob_matches("a*c", "abcd"));
+        assert!(glob_matches("abc", "abc"));
+    }
+
+    /// Sink whose writes always fail
+    struct FailingSink;
+
+    #[async_trait::async_trait]
+    impl StorageBackend for FailingSink {
+        async fn store_event(&self, _event: &AuditEvent) -> Result<(), anyhow::Error> {
+            Err(anyhow::anyhow!("staging is down"))
+        }
+
+        async fn store_batch(&self, _events: &[AuditEvent]) -> Result<(), anyhow::Error> {
+            Err(anyhow::anyhow!("staging is down"))
+        }
+
+        async fn query_events(
+            &self,
+            _filter: &QueryFilter,
+        ) -> Result<Vec<AuditEvent>, anyhow::Error> {
+            Ok(vec![])
+        }
+
+        async fn count_events(&self, _filter: &QueryFilter) -> Result<u64, anyhow::Error> {
+            Ok(0)
+        }
+
+        async fn health_check(&self) -> Result<bool, anyhow::Error> {
+            Ok(false)
+        }
+
+        fn get_stats(&self) -> StorageStats {
+            StorageStats::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mirror_samples_configured_fraction() {
+        let (storage, tiers) = tiered_storage();
+        let staging = Arc::new(InMemoryStorage::new());
+        let router = router(storage).with_mirror(MirrorConfig::new(staging.clone(), 0.1));
+
+        for i in 0..2_000 {
+            let event = event(
+                &format!("read-{}", i),
+                EventCategory::Data,
+                Outcome::Success,
+                true,
+            );
+            router.route(&event).await.unwrap();
+        }
+
+        assert_eq!(tiers.warm.len(), 2_000);
+        assert!((140..=260).contains(&staging.len()), "{}", staging.len());
+        let mirrored = staging.query_events(&QueryFilter::default()).await.unwrap();
+        assert!(
+            mirrored
+                .iter()
+                .all(|e| e.get_json(MIRRORED_METADATA_KEY) == Some(serde_json::json!(true)))
+        );
+        let primary = tiers
+            .warm
+            .query_events(&QueryFilter::default())
+            .await
+            .unwrap();
+        assert!(
+            primary
+                .iter()
+                .all(|e| e.get_json(MIRRORED_METADATA_KEY).is_none())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mirror_rules_always_match() {
+        let (storage, _tiers) = tiered_storage();
+        let staging = Arc::new(InMemoryStorage::new());
+        let router = router(storage).with_mirror(
+            MirrorConfig::new(staging.clone(), 0.0)
+                .with_rule(RouteRule::new("denied", vec![]).with_outcomes(vec![Outcome::Denied])),
+        );
+
+        for i in 0..50 {
+            let outcome = if i % 2 == 0 {
+                Outcome::Denied
+            } else {
+                Outcome::Success
+            };
+            let event = event(&format!("e-{}", i), EventCategory::Data, outcome, true);
+            router.route(&event).await.unwrap();
+        }
+
+        assert_eq!(staging.len(), 25);
+    }
+
+    #[tokio::test]
+    async fn test_mirror_failure_does_not_affect_primary() {
+        let (storage, tiers) = tiered_storage();
+        let router = router(storage).with_mirror(MirrorConfig::new(Arc::new(FailingSink), 1.0));
+
+        let targets = router
+            .route(&event(
+                "read-1",
+                EventCategory::Data,
+                Outcome::Success,
+                true,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(targets, vec![RouteTarget::Tier(StorageTierType::Warm)]);
+        assert_eq!(tiers.warm.len(), 1);
+        assert_eq!(router.mirror_failures(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tier_targets_are_deduplicated_and_spooled() {
+        let warm = Arc::new(InMemoryStorage::new());
+        let storage = TieredStorage::from_backends(
+            Arc::new(FailingSink),
+            warm.clone(),
+            Arc::new(InMemoryStorage::new()),
+            LifecyclePolicy::default(),
+            PartitionStrategy::default(),
+        )
+        .with_hot_fallback()
+        .with_dedup_window(std::time::Duration::from_secs(300));
+        let router = router(Arc::new(storage));
+        let security_event = event("sec-1", EventCategory::Insight, Outcome::Denied, false);
+
+        router.route(&security_event).await.unwrap();
+        router.route(&security_event).await.unwrap();
+
+        // The hot tier is down, so the event waits in warm, once
+        assert_eq!(warm.len(), 1);
+        assert_eq!(router.get_stats().suppressed_duplicates, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failing_sink_does_not_fail_stored_event() {
+        let (storage, tiers) = tiered_storage();
+        let router = EventRouter::new(storage)
+            .with_sink("archive", Arc::new(FailingSink))
+            .with_rule(RouteRule::new(
+                "archive-all",
+                vec![
+                    RouteTarget::Tier(StorageTierType::Hot),
+                    RouteTarget::Sink("archive".to_string()),
+                ],
+            ));
+
+        let targets = router
+            .route(&event("e-1", EventCategory::Data, Outcome::Success, true))
+            .await
+            .unwrap();
+
+        assert_eq!(targets, vec![RouteTarget::Tier(StorageTierType::Hot)]);
+        assert_eq!(tiers.hot.len(), 1);
+        assert_eq!(router.sink_failures(), 1);
+
+        // With nowhere else to go, the sink failure fails the route
+        let (storage, _) = tiered_storage();
+        let router = EventRouter::new(storage)
+            .with_sink("archive", Arc::new(FailingSink))
+            .with_default_targets(vec![RouteTarget::Sink("archive".to_string())]);
+        assert!(
+            router
+                .route(&event("e-2", EventCategory::Data, Outcome::Success, true))
+                .await
+                .is_err()
+        );
+        assert_eq!(router.sink_failures(), 1);
+    }
+}
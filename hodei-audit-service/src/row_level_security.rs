@@ -2,8 +2,16 @@
 //!
 //! This module implements Row-Level Security policies for ClickHouse
 //! to ensure complete tenant isolation at the database level.
-
-use std::collections::HashMap;
+//!
+//! Within a tenant, a [`FieldMaskPolicy`] hides sensitive fields (e.g.
+//! `user_identity.email`) from callers without a role allowed to see them.
+//! Masking is applied by [`SecureQueryExecutor`] to the rows that passed RLS,
+//! before they leave the server.
+
+use crate::encryption::EncryptionError;
+use crate::field_encryption::{FieldPath, REDACTED};
+use hodei_audit_proto::AuditEvent;
+use std::collections::{HashMap, HashSet};
 use tracing::{error, info, warn};
 
 /// Row-Level Security policy
@@ -81,6 +89,8 @@ pub struct RlsManager {
     policies: HashMap<String, RlsPolicy>,
     /// Current tenant ID
     current_tenant_id: Option<String>,
+    /// Roles of the current caller
+    current_roles: Vec<String>,
 }
 
 impl RlsManager {
@@ -89,6 +99,7 @@ impl RlsManager {
         let mut manager = Self {
             policies: HashMap::new(),
             current_tenant_id: None,
+            current_roles: Vec::new(),
         };
 
         // Register default policy for audit_events table
@@ -127,6 +138,17 @@ impl RlsManager {
         self.current_tenant_id.as_deref()
     }
 
+    /// Set the roles of the current caller (used for field masking)
+    pub fn set_roles(&mut self, roles: Vec<String>) {
+        info!("[RLS] Setting caller roles: {:?}", roles);
+        self.current_roles = roles;
+    }
+
+    /// Get the roles of the current caller
+    pub fn get_roles(&self) -> &[String] {
+        &self.current_roles
+    }
+
     /// Generate SQL to set tenant context
     pub fn set_tenant_context_sql(&self) -> Option<String> {
         self.current_tenant_id
@@ -158,6 +180,20 @@ impl RlsManager {
         Ok(())
     }
 
+    /// Check whether a row of `table` owned by `row_tenant_id` is visible
+    /// in the current tenant context
+    ///
+    /// Tables without an enabled policy are unrestricted. With a policy, rows
+    /// are only visible to the tenant set via `set_tenant_id`.
+    pub fn is_row_visible(&self, table: &str, row_tenant_id: &str) -> bool {
+        match self.policies.get(table) {
+            Some(policy) if policy.enabled => {
+                self.current_tenant_id.as_deref() == Some(row_tenant_id)
+            }
+            _ => true,
+        }
+    }
+
     /// Get all registered policies
     pub fn get_policies(&self) -> Vec<&RlsPolicy> {
         self.policies.values().collect()
@@ -299,12 +335,89 @@ impl RlsQueryBuilder {
     }
 }
 
+/// How a masked field is hidden
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MaskStyle {
+    /// Replace the value with [`REDACTED`]
+    #[default]
+    Redact,
+    /// Clear the value
+    Null,
+}
+
+/// Field hidden from every role but `visible_to`
+#[derive(Debug, Clone)]
+struct FieldMaskRule {
+    field: FieldPath,
+    style: MaskStyle,
+    visible_to: HashSet<String>,
+}
+
+/// Field-level access control for query results
+///
+/// A masked field is hidden unless the caller holds one of the roles it is
+/// visible to, so callers without roles see every masked field hidden.
+#[derive(Debug, Clone, Default)]
+pub struct FieldMaskPolicy {
+    rules: Vec<FieldMaskRule>,
+}
+
+impl FieldMaskPolicy {
+    /// Create a policy masking nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mask `path` (e.g. `user_identity.email`) for callers without any of
+    /// the `visible_to` roles
+    pub fn mask_field<I, S>(
+        mut self,
+        path: &str,
+        style: MaskStyle,
+        visible_to: I,
+    ) -> Result<Self, EncryptionError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rules.push(FieldMaskRule {
+            field: FieldPath::parse(path)?,
+            style,
+            visible_to: visible_to.into_iter().map(Into::into).collect(),
+        });
+        Ok(self)
+    }
+
+    /// Whether the policy masks no field
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Hide the fields of `event` that a caller with `roles` may not see
+    pub fn apply(&self, event: &mut AuditEvent, roles: &[String]) {
+        for rule in &self.rules {
+            if roles.iter().any(|role| rule.visible_to.contains(role)) {
+                continue;
+            }
+            match rule.field.value_mut(event) {
+                Some(value) if !value.is_empty() => match rule.style {
+                    MaskStyle::Redact => *value = REDACTED.to_string(),
+                    MaskStyle::Null => value.clear(),
+                },
+                _ => {}
+            }
+        }
+    }
+}
+
 /// Secure query executor
 pub struct SecureQueryExecutor {
     /// ClickHouse client
     client: crate::clickhouse::ClickHouseClient,
     /// RLS manager
     rls_manager: RlsManager,
+    /// Field masking applied to the returned events
+    field_mask_policy: FieldMaskPolicy,
 }
 
 impl SecureQueryExecutor {
@@ -313,9 +426,37 @@ impl SecureQueryExecutor {
         Self {
             client,
             rls_manager,
+            field_mask_policy: FieldMaskPolicy::default(),
         }
     }
 
+    /// Mask fields of the returned events according to `policy`
+    pub fn with_field_mask_policy(mut self, policy: FieldMaskPolicy) -> Self {
+        self.field_mask_policy = policy;
+        self
+    }
+
+    /// Set the roles of the caller the results are masked for
+    pub fn set_caller_roles(&mut self, roles: Vec<String>) {
+        self.rls_manager.set_roles(roles);
+    }
+
+    /// Drop rows outside the tenant context, then mask the remaining ones
+    fn secure_results(&self, events: Vec<AuditEvent>) -> Vec<AuditEvent> {
+        let roles = self.rls_manager.get_roles();
+        events
+            .into_iter()
+            .filter(|event| {
+                let tenant_id = event.tenant_id.as_ref().map_or("", |t| t.value.as_str());
+                self.rls_manager.is_row_visible("audit_events", tenant_id)
+            })
+            .map(|mut event| {
+                self.field_mask_policy.apply(&mut event, roles);
+                event
+            })
+            .collect()
+    }
+
     /// Execute a SELECT query with RLS enforcement
     pub async fn query_with_rls(
         &self,
@@ -327,7 +468,7 @@ impl SecureQueryExecutor {
             .map_err(|e| anyhow::anyhow!("Failed to build RLS query: {}", e))?;
 
         // Execute the query
-        let result = self.client.query(&query).await?;
+        let result = self.secure_results(self.client.query(&query).await?);
 
         info!(
             "[RLS] Executed secure query, returned {} events",
@@ -353,7 +494,7 @@ impl SecureQueryExecutor {
         self.rls_manager.validate_query(sql, "audit_events")?;
 
         // Execute the query
-        let result = self.client.query_with_params(sql, &secure_params).await?;
+        let result = self.secure_results(self.client.query_with_params(sql, &secure_params).await?);
 
         info!(
             "[RLS] Executed parameterized query with RLS, returned {} events",
@@ -512,4 +653,79 @@ mod tests {
         let drop_sqls = manager.drop_all_policies_sql();
         assert!(!drop_sqls.is_empty());
     }
+
+    #[test]
+    fn test_is_row_visible_enforces_tenant_context() {
+        let mut manager = RlsManager::new();
+        assert!(!manager.is_row_visible("audit_events", "tenant-a"));
+
+        manager.set_tenant_id("tenant-a".to_string());
+        assert!(manager.is_row_visible("audit_events", "tenant-a"));
+        assert!(!manager.is_row_visible("audit_events", "tenant-b"));
+
+        // Tables without a policy are not restricted
+        assert!(manager.is_row_visible("other_table", "tenant-b"));
+    }
+
+    fn stored_event(tenant: &str, email: &str) -> AuditEvent {
+        AuditEvent {
+            tenant_id: Some(hodei_audit_proto::TenantId {
+                value: tenant.to_string(),
+            }),
+            user_identity: Some(hodei_audit_proto::UserIdentity {
+                user_id: "user-1".to_string(),
+                email: email.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_field_masking_depends_on_caller_roles() {
+        let client = crate::clickhouse::ClickHouseClient::new_with_defaults();
+        client.set_query_results(vec![
+            stored_event("tenant-a", "alice@example.com"),
+            stored_event("tenant-b", "bob@example.com"),
+        ]);
+        let mut rls = RlsManager::new();
+        rls.set_tenant_id("tenant-a".to_string());
+        let policy = FieldMaskPolicy::new()
+            .mask_field("user_identity.email", MaskStyle::Redact, ["admin"])
+            .unwrap()
+            .mask_field("user_identity.user_id", MaskStyle::Null, ["admin"])
+            .unwrap();
+        let mut executor = SecureQueryExecutor::new(client, rls).with_field_mask_policy(policy);
+        let sql = "SELECT * FROM audit_events WHERE tenant_id = {tenant_id:String}";
+
+        executor.set_caller_roles(vec!["auditor".to_string()]);
+        let masked = executor
+            .query_with_params(sql, &HashMap::new())
+            .await
+            .unwrap();
+        // RLS dropped the tenant-b row before masking
+        assert_eq!(masked.len(), 1);
+        let user = masked[0].user_identity.as_ref().unwrap();
+        assert_eq!(user.email, REDACTED);
+        assert_eq!(user.user_id, "");
+
+        executor.set_caller_roles(vec!["auditor".to_string(), "admin".to_string()]);
+        let visible = executor
+            .query_with_params(sql, &HashMap::new())
+            .await
+            .unwrap();
+        assert_eq!(visible.len(), 1);
+        let user = visible[0].user_identity.as_ref().unwrap();
+        assert_eq!(user.email, "alice@example.com");
+        assert_eq!(user.user_id, "user-1");
+    }
+
+    #[test]
+    fn test_field_mask_policy_rejects_unknown_field() {
+        assert!(
+            FieldMaskPolicy::new()
+                .mask_field("user_identity.password", MaskStyle::Redact, ["admin"])
+                .is_err()
+        );
+    }
 }
@@ -1,14 +1,25 @@
 //! S3/MinIO Storage Integration
 //!
 //! This module provides robust S3/MinIO integration for warm/cold storage tiers
-//! with Parquet format, compression, partitioning, and lifecycle policies.
+//! with Parquet, Avro or NDJSON objects, compression, partitioning, and
+//! lifecycle policies.
 
+use crate::batch_digest::{BatchDigestSigner, BatchIntegrity, batch_digest};
+use crate::encryption::{ALGORITHM_METADATA, EnvelopeEncryptor, TENANT_METADATA};
+use crate::object_format::{OBJECT_FORMAT_METADATA, ObjectFormat};
+use crate::outbound_tls::OutboundTlsConfig;
 use hodei_audit_proto::AuditEvent;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tracing::{error, info, warn};
 
+/// Object metadata key holding the batch digest of the events in the object
+pub const BATCH_DIGEST_METADATA: &str = "x-amz-meta-hodei-batch-digest";
+
+/// Object metadata key holding the signature of the batch digest
+pub const BATCH_SIGNATURE_METADATA: &str = "x-amz-meta-hodei-batch-signature";
+
 /// S3/MinIO client configuration
 #[derive(Debug, Clone)]
 pub struct S3Config {
@@ -38,6 +49,19 @@ pub struct S3Config {
     pub transition_to_ia_days: u32,
     /// Lifecycle expiration days
     pub expire_after_days: u32,
+    /// TLS policy used when `use_ssl` is set; `None` applies the default
+    /// policy
+    pub tls_config: Option<OutboundTlsConfig>,
+    /// Serialization format of new objects
+    pub object_format: ObjectFormat,
+}
+
+impl S3Config {
+    /// TLS policy for connections; `None` connects in plain text
+    pub fn tls_policy(&self) -> Option<OutboundTlsConfig> {
+        self.use_ssl
+            .then(|| self.tls_config.clone().unwrap_or_default())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -72,6 +96,8 @@ impl Default for S3Config {
             enable_lifecycle: true,
             transition_to_ia_days: 30,
             expire_after_days: 365,
+            tls_config: None,
+            object_format: ObjectFormat::default(),
         }
     }
 }
@@ -99,9 +125,9 @@ pub struct S3Metrics {
     pub estimated_cost_per_gb: f64,
 }
 
-/// Parquet writer statistics
+/// Batch object writer statistics
 #[derive(Debug, Clone)]
-pub struct ParquetStats {
+pub struct ObjectStats {
     /// Number of events written
     pub event_count: usize,
     /// File size in bytes
@@ -112,6 +138,10 @@ pub struct ParquetStats {
     pub write_latency_ms: f64,
     /// Object key
     pub object_key: String,
+    /// Format the object was written in
+    pub format: ObjectFormat,
+    /// Batch digest stored in the object metadata
+    pub batch_digest: String,
 }
 
 /// Lifecycle policy configuration
@@ -138,12 +168,25 @@ impl Default for LifecyclePolicy {
     }
 }
 
-/// S3/MinIO client with Parquet support
+/// S3/MinIO client writing objects in the configured format
 pub struct S3Client {
     /// Configuration
     config: S3Config,
     /// Performance metrics
     metrics: Arc<std::sync::RwLock<S3Metrics>>,
+    /// Simulated bucket contents
+    objects: Arc<std::sync::RwLock<BTreeMap<String, StoredObject>>>,
+    /// Per-tenant envelope encryption of object bodies
+    encryption: Option<Arc<EnvelopeEncryptor>>,
+    /// Signs the batch digest stored with each object
+    digest_signer: BatchDigestSigner,
+}
+
+/// Object body and user metadata in the simulated bucket
+#[derive(Debug, Clone)]
+struct StoredObject {
+    body: Vec<u8>,
+    metadata: HashMap<String, String>,
 }
 
 /// Partitioning strategy
@@ -174,6 +217,15 @@ impl PartitionStrategy {
             })
             .unwrap_or_else(|| chrono::Utc::now());
 
+        self.partition_path_at(timestamp, tenant_id)
+    }
+
+    /// Partition path of `tenant_id`'s events at `timestamp`
+    fn partition_path_at(
+        &self,
+        timestamp: chrono::DateTime<chrono::Utc>,
+        tenant_id: &str,
+    ) -> String {
         match self.granularity {
             PartitionGranularity::Hour => format!(
                 "year={}/month={}/day={}/hour={}/tenant_id={}",
@@ -205,8 +257,35 @@ impl PartitionStrategy {
         }
     }
 
-    /// Build object key for Parquet file
-    fn build_object_key(&self, event: &AuditEvent, tenant_id: &str, batch_id: &str) -> String {
+    /// Date portion of the partition path used to list a whole day
+    ///
+    /// Hourly partitions are listed per day; weekly and monthly partitions
+    /// list the period containing `date`.
+    fn listing_prefix(&self, date: chrono::NaiveDate) -> String {
+        match self.granularity {
+            PartitionGranularity::Hour | PartitionGranularity::Day => format!(
+                "year={}/month={}/day={}/",
+                date.format("%Y"),
+                date.format("%m"),
+                date.format("%d")
+            ),
+            PartitionGranularity::Week => {
+                format!("year={}/week={}/", date.format("%Y"), date.format("%U"))
+            }
+            PartitionGranularity::Month => {
+                format!("year={}/month={}/", date.format("%Y"), date.format("%m"))
+            }
+        }
+    }
+
+    /// Build object key for a batch object in `format`
+    fn build_object_key(
+        &self,
+        event: &AuditEvent,
+        tenant_id: &str,
+        batch_id: &str,
+        format: ObjectFormat,
+    ) -> String {
         let timestamp = event
             .event_time
             .as_ref()
@@ -224,7 +303,12 @@ impl PartitionStrategy {
             .unwrap_or_else(|| chrono::Utc::now());
 
         let partition_path = self.build_partition_path(event, tenant_id);
-        format!("{}/audit_events_{}.parquet", partition_path, batch_id)
+        format!(
+            "{}/audit_events_{}.{}",
+            partition_path,
+            batch_id,
+            format.extension()
+        )
     }
 }
 
@@ -234,11 +318,38 @@ impl S3Client {
         let metrics = Arc::new(std::sync::RwLock::new(S3Metrics::default()));
 
         info!(
-            "[S3] Initialized client: bucket={}, region={}, compression={:?}, batch_size={}",
-            config.bucket, config.region, config.compression, config.batch_size
+            "[S3] Initialized client: bucket={}, region={}, format={}, compression={:?}, batch_size={}, tls={:?}",
+            config.bucket,
+            config.region,
+            config.object_format,
+            config.compression,
+            config.batch_size,
+            config.tls_policy().map(|tls| tls.min_version)
         );
 
-        Self { config, metrics }
+        Self {
+            config,
+            metrics,
+            objects: Arc::new(std::sync::RwLock::new(BTreeMap::new())),
+            encryption: None,
+            digest_signer: BatchDigestSigner::generate(),
+        }
+    }
+
+    /// Sign batch digests with `signer`; without it a random key is used,
+    /// and objects can't be verified by another client
+    pub fn with_digest_signer(mut self, signer: BatchDigestSigner) -> Self {
+        self.digest_signer = signer;
+        self
+    }
+
+    /// Encrypt every object with a data key of its tenant
+    ///
+    /// The wrapped data key is stored in the object metadata and objects
+    /// are decrypted transparently when read.
+    pub fn with_encryption(mut self, encryptor: Arc<EnvelopeEncryptor>) -> Self {
+        self.encryption = Some(encryptor);
+        self
     }
 
     /// Create with default configuration
@@ -265,10 +376,13 @@ impl S3Client {
                 .unwrap_or("unknown")
         );
         let strategy = PartitionStrategy::new(self.config.partition_granularity.clone());
-        let object_key = strategy.build_object_key(event, tenant_id, &batch_id);
+        let object_key =
+            strategy.build_object_key(event, tenant_id, &batch_id, self.config.object_format);
 
         // Simulate upload
         self.simulate_upload(&object_key, 1024).await?;
+        self.store_object(&object_key, tenant_id, std::slice::from_ref(event))
+            .await?;
 
         let latency = start_time.elapsed()?.as_millis() as f64;
         self.update_upload_metrics(1024, latency);
@@ -277,11 +391,8 @@ impl S3Client {
         Ok(object_key)
     }
 
-    /// Upload events as Parquet file
-    pub async fn upload_parquet_batch(
-        &self,
-        events: &[AuditEvent],
-    ) -> Result<ParquetStats, anyhow::Error> {
+    /// Upload events as one object in the configured format
+    pub async fn upload_batch(&self, events: &[AuditEvent]) -> Result<ObjectStats, anyhow::Error> {
         if events.is_empty() {
             return Err(anyhow::anyhow!("Empty event batch"));
         }
@@ -305,26 +416,31 @@ impl S3Client {
 
         // Build partition path and object key
         let strategy = PartitionStrategy::new(self.config.partition_granularity.clone());
-        let object_key = strategy.build_object_key(&events[0], tenant_id, &batch_id);
+        let format = self.config.object_format;
+        let object_key = strategy.build_object_key(&events[0], tenant_id, &batch_id, format);
 
-        // Simulate Parquet writing and compression
-        let (compressed_size, compression_ratio) = self.simulate_parquet_write(events).await?;
+        // Simulate object writing and compression
+        let (compressed_size, compression_ratio) = self.simulate_object_write(events).await?;
+        let batch_digest = self.store_object(&object_key, tenant_id, events).await?;
 
         let latency = start_time.elapsed()?.as_millis() as f64;
 
         // Update metrics
         self.update_parquet_metrics(events.len(), compressed_size, compression_ratio, latency);
 
-        let stats = ParquetStats {
+        let stats = ObjectStats {
             event_count: events.len(),
             file_size_bytes: compressed_size,
             compression_ratio,
             write_latency_ms: latency,
             object_key,
+            format,
+            batch_digest,
         };
 
         info!(
-            "[S3] Parquet batch uploaded: {} events, {} bytes (ratio: {:.2}x), latency: {}ms",
+            "[S3] {} batch uploaded: {} events, {} bytes (ratio: {:.2}x), latency: {}ms",
+            stats.format,
             stats.event_count,
             stats.file_size_bytes,
             stats.compression_ratio,
@@ -335,34 +451,106 @@ impl S3Client {
     }
 
     /// Query events from S3 (simulated)
+    ///
+    /// Reads every object under `params["prefix"]` (the whole bucket
+    /// without it), each decoded in the format it was written in.
     pub async fn query_events(
         &self,
-        sql: &str,
-        _params: &HashMap<String, String>,
+        _sql: &str,
+        params: &HashMap<String, String>,
     ) -> Result<Vec<AuditEvent>, anyhow::Error> {
         let start_time = SystemTime::now();
 
         // Simulate query execution
         tokio::time::sleep(Duration::from_millis(200)).await; // S3 queries are slower
 
+        let prefix = params.get("prefix").map(String::as_str).unwrap_or("");
+        let mut events = Vec::new();
+        for key in self.list_objects(prefix).await? {
+            events.extend(self.read_object(&key).await?);
+        }
+
         let latency = start_time.elapsed()?.as_millis() as f64;
         self.update_query_metrics(latency);
 
-        info!("[S3] Query executed: latency {}ms", latency as u64);
-        Ok(vec![])
+        info!(
+            "[S3] Query executed: {} events, latency {}ms",
+            events.len(),
+            latency as u64
+        );
+        Ok(events)
     }
 
-    /// Get object at key
+    /// Get object at key, as stored (encrypted if encryption is enabled)
     pub async fn get_object(&self, key: &str) -> Result<Vec<u8>, anyhow::Error> {
         // Simulate object retrieval
         info!("[S3] Getting object: {}", key);
-        Ok(vec![])
+        Ok(self.stored_object(key)?.body)
+    }
+
+    /// Get the user metadata of the object at key
+    pub async fn get_object_metadata(
+        &self,
+        key: &str,
+    ) -> Result<HashMap<String, String>, anyhow::Error> {
+        Ok(self.stored_object(key)?.metadata)
+    }
+
+    /// Read the events stored in an object, decrypting it if needed
+    ///
+    /// Objects are decoded in the format recorded in their metadata;
+    /// objects without one are Parquet.
+    pub async fn read_object(&self, key: &str) -> Result<Vec<AuditEvent>, anyhow::Error> {
+        let object = self.stored_object(key)?;
+        let format = match object.metadata.get(OBJECT_FORMAT_METADATA) {
+            Some(format) => format.parse()?,
+            None => ObjectFormat::Parquet,
+        };
+        if !object.metadata.contains_key(ALGORITHM_METADATA) {
+            return format.decode(&object.body);
+        }
+
+        let encryptor = self
+            .encryption
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Object {} is encrypted but no KMS is set", key))?;
+        let tenant_id = object
+            .metadata
+            .get(TENANT_METADATA)
+            .ok_or_else(|| anyhow::anyhow!("Object {} has no tenant metadata", key))?;
+        let body = encryptor
+            .decrypt(tenant_id, &object.body, &object.metadata)
+            .await?;
+        format.decode(&body)
+    }
+
+    /// Recompute the batch digest of the object at key and compare it to
+    /// the one stored in its metadata, checking the digest's signature
+    ///
+    /// One read of the object tells whether any of its events changed,
+    /// before drilling into per-event verification.
+    pub async fn verify_batch_integrity(&self, key: &str) -> Result<BatchIntegrity, anyhow::Error> {
+        let mut metadata = self.get_object_metadata(key).await?;
+        let expected = metadata
+            .remove(BATCH_DIGEST_METADATA)
+            .ok_or_else(|| anyhow::anyhow!("Object {} has no batch digest", key))?;
+        let signature = metadata
+            .remove(BATCH_SIGNATURE_METADATA)
+            .unwrap_or_default();
+        let events = self.read_object(key).await?;
+        let integrity =
+            BatchIntegrity::check(key, &events, expected, &signature, &self.digest_signer);
+        if !integrity.is_valid() {
+            warn!("[S3] Batch digest mismatch for object {}", key);
+        }
+        Ok(integrity)
     }
 
     /// Delete object
     pub async fn delete_object(&self, key: &str) -> Result<(), anyhow::Error> {
         // Simulate object deletion
         info!("[S3] Deleting object: {}", key);
+        self.objects.write().unwrap().remove(key);
         Ok(())
     }
 
@@ -370,7 +558,59 @@ impl S3Client {
     pub async fn list_objects(&self, prefix: &str) -> Result<Vec<String>, anyhow::Error> {
         // Simulate object listing
         info!("[S3] Listing objects with prefix: {}", prefix);
-        Ok(vec![])
+        Ok(self
+            .objects
+            .read()
+            .unwrap()
+            .range(prefix.to_string()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, _)| key.clone())
+            .collect())
+    }
+
+    /// Listing prefixes covering every partition between two dates (inclusive)
+    pub fn partition_prefixes(
+        &self,
+        start: chrono::NaiveDate,
+        end: chrono::NaiveDate,
+    ) -> Vec<String> {
+        let strategy = PartitionStrategy::new(self.config.partition_granularity.clone());
+        let mut prefixes: Vec<String> = start
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .map(|date| strategy.listing_prefix(date))
+            .collect();
+        prefixes.dedup();
+        prefixes
+    }
+
+    /// Prefixes of `tenant_id`'s partitions intersecting `start..=end`
+    ///
+    /// Listing only these prefixes prunes every partition a time-ranged
+    /// query can't match.
+    pub fn partitions_for_range(
+        tenant_id: &str,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        granularity: PartitionGranularity,
+    ) -> Vec<String> {
+        let step = match granularity {
+            PartitionGranularity::Hour => chrono::Duration::hours(1),
+            _ => chrono::Duration::days(1),
+        };
+        let Ok(mut timestamp) = chrono::DurationRound::duration_trunc(start, step) else {
+            return Vec::new();
+        };
+        let strategy = PartitionStrategy::new(granularity);
+        let mut prefixes: Vec<String> = Vec::new();
+        while timestamp <= end {
+            let prefix = format!("{}/", strategy.partition_path_at(timestamp, tenant_id));
+            if prefixes.last() != Some(&prefix) {
+                prefixes.push(prefix);
+            }
+            timestamp += step;
+        }
+        prefixes
     }
 
     /// Health check
@@ -432,6 +672,59 @@ impl S3Client {
         Ok(())
     }
 
+    /// Store events as an object in the simulated bucket, in the configured
+    /// format and encrypted for their tenant when encryption is enabled
+    ///
+    /// The format and the batch digest of the events go into the object
+    /// metadata; the digest is returned.
+    async fn store_object(
+        &self,
+        key: &str,
+        tenant_id: &str,
+        events: &[AuditEvent],
+    ) -> Result<String, anyhow::Error> {
+        let format = self.config.object_format;
+        let body = format.encode(events)?;
+        let mut object = match &self.encryption {
+            Some(encryptor) => {
+                let encrypted = encryptor.encrypt(tenant_id, &body).await?;
+                StoredObject {
+                    body: encrypted.ciphertext,
+                    metadata: encrypted.metadata,
+                }
+            }
+            None => StoredObject {
+                body,
+                metadata: HashMap::new(),
+            },
+        };
+        let digest = batch_digest(events);
+        object
+            .metadata
+            .insert(OBJECT_FORMAT_METADATA.to_string(), format.to_string());
+        object
+            .metadata
+            .insert(BATCH_DIGEST_METADATA.to_string(), digest.clone());
+        object.metadata.insert(
+            BATCH_SIGNATURE_METADATA.to_string(),
+            self.digest_signer.sign(&digest),
+        );
+        self.objects
+            .write()
+            .unwrap()
+            .insert(key.to_string(), object);
+        Ok(digest)
+    }
+
+    fn stored_object(&self, key: &str) -> Result<StoredObject, anyhow::Error> {
+        self.objects
+            .read()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("NoSuchKey: {}", key))
+    }
+
     /// Simulate upload operation
     async fn simulate_upload(&self, _key: &str, _size_bytes: u64) -> Result<(), anyhow::Error> {
         // Simulate network latency
@@ -439,12 +732,12 @@ impl S3Client {
         Ok(())
     }
 
-    /// Simulate Parquet write with compression
-    async fn simulate_parquet_write(
+    /// Simulate object write with compression
+    async fn simulate_object_write(
         &self,
         events: &[AuditEvent],
     ) -> Result<(u64, f64), anyhow::Error> {
-        // Simulate Parquet file creation
+        // Simulate object creation
         let estimated_size = events.len() * 2048; // ~2KB per event
         let (compressed_size, compression_ratio) = match self.config.compression {
             CompressionType::None => (estimated_size, 1.0),
@@ -596,7 +889,7 @@ mod tests {
             .map(|i| create_test_event(&format!("test-{}", i)))
             .collect();
 
-        let result = client.upload_parquet_batch(&events).await;
+        let result = client.upload_batch(&events).await;
 
         assert!(result.is_ok());
         let stats = result.unwrap();
@@ -607,6 +900,167 @@ mod tests {
 
         let metrics = client.get_metrics();
         assert_eq!(metrics.parquet_files, 1);
+
+        let stored = client.read_object(&stats.object_key).await.unwrap();
+        assert_eq!(stored, events);
+        let prefix = stats.object_key.split("tenant_id=").next().unwrap();
+        assert_eq!(
+            client.list_objects(prefix).await.unwrap(),
+            vec![stats.object_key.clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_round_trips_through_each_format() {
+        use hodei_audit_proto::MetadataExt;
+
+        for format in [
+            ObjectFormat::Parquet,
+            ObjectFormat::NdJson,
+            ObjectFormat::Avro,
+        ] {
+            let client = S3Client::new(S3Config {
+                object_format: format,
+                ..S3Config::default()
+            });
+            let events: Vec<AuditEvent> = (0..5)
+                .map(|i| {
+                    let mut event = create_test_event(&format!("{}-{}", format, i));
+                    event.http_context = Some(hodei_audit_proto::HttpContext {
+                        method: "POST".to_string(),
+                        path: "/policy-stores".to_string(),
+                        user_agent: "sdk/1.0".to_string(),
+                        source_ip: "10.0.0.1".to_string(),
+                        status_code: 201,
+                        content_length: 512,
+                    });
+                    event.set_json("attempt", serde_json::json!(i));
+                    event
+                })
+                .collect();
+
+            let stats = client.upload_batch(&events).await.unwrap();
+            assert_eq!(stats.format, format);
+            assert!(
+                stats
+                    .object_key
+                    .ends_with(&format!(".{}", format.extension()))
+            );
+            assert_eq!(
+                client.get_object_metadata(&stats.object_key).await.unwrap()
+                    [OBJECT_FORMAT_METADATA],
+                format.as_str()
+            );
+
+            let read = client.query_events("", &HashMap::new()).await.unwrap();
+            assert_eq!(read, events, "{} round trip", format);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_digest_detects_modified_event() {
+        let client = S3Client::new_with_defaults();
+        let events: Vec<AuditEvent> = (0..5)
+            .map(|i| create_test_event(&format!("digest-{}", i)))
+            .collect();
+        let stats = client.upload_batch(&events).await.unwrap();
+        assert_eq!(stats.batch_digest, batch_digest(&events));
+
+        let integrity = client
+            .verify_batch_integrity(&stats.object_key)
+            .await
+            .unwrap();
+        assert!(integrity.is_valid());
+        assert_eq!(integrity.event_count, 5);
+
+        // Rewrite one event in place, keeping the object metadata
+        let mut tampered = events.clone();
+        tampered[2].action = "DeleteAuditTrail".to_string();
+        client
+            .objects
+            .write()
+            .unwrap()
+            .get_mut(&stats.object_key)
+            .unwrap()
+            .body = ObjectFormat::Parquet.encode(&tampered).unwrap();
+
+        let integrity = client
+            .verify_batch_integrity(&stats.object_key)
+            .await
+            .unwrap();
+        assert!(!integrity.is_valid());
+        assert_eq!(integrity.expected_digest, stats.batch_digest);
+
+        // Recomputing the digest in the metadata doesn't hide the change
+        client
+            .objects
+            .write()
+            .unwrap()
+            .get_mut(&stats.object_key)
+            .unwrap()
+            .metadata
+            .insert(BATCH_DIGEST_METADATA.to_string(), batch_digest(&tampered));
+        let integrity = client
+            .verify_batch_integrity(&stats.object_key)
+            .await
+            .unwrap();
+        assert_eq!(integrity.actual_digest, integrity.expected_digest);
+        assert!(!integrity.signature_valid);
+        assert!(!integrity.is_valid());
+    }
+
+    fn encrypted_client(kms: Arc<crate::encryption::InMemoryKms>) -> S3Client {
+        S3Client::new(S3Config::default()).with_encryption(Arc::new(EnvelopeEncryptor::new(kms)))
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_objects_round_trip() {
+        let client = encrypted_client(Arc::new(crate::encryption::InMemoryKms::new()));
+        let events: Vec<AuditEvent> = (0..3)
+            .map(|i| create_test_event(&format!("enc-{}", i)))
+            .collect();
+
+        let stats = client.upload_batch(&events).await.unwrap();
+
+        let body = client.get_object(&stats.object_key).await.unwrap();
+        assert_ne!(body, ObjectFormat::Parquet.encode(&events).unwrap());
+        let metadata = client.get_object_metadata(&stats.object_key).await.unwrap();
+        assert_eq!(metadata[TENANT_METADATA], "test-tenant");
+        assert!(metadata.contains_key(crate::encryption::WRAPPED_KEY_METADATA));
+
+        let stored = client.read_object(&stats.object_key).await.unwrap();
+        assert_eq!(stored, events);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_tenant_key_fails_to_decrypt() {
+        let kms = Arc::new(crate::encryption::InMemoryKms::new());
+        let client = encrypted_client(kms.clone());
+        let event = create_test_event("enc-1");
+        let key = client.upload_event(&event).await.unwrap();
+
+        // Give tenant-b a CMK of its own
+        let encryptor = EnvelopeEncryptor::new(kms.clone());
+        encryptor.encrypt("tenant-b", b"other").await.unwrap();
+
+        let body = client.get_object(&key).await.unwrap();
+        let metadata = client.get_object_metadata(&key).await.unwrap();
+        assert!(
+            encryptor
+                .decrypt("tenant-b", &body, &metadata)
+                .await
+                .is_err()
+        );
+        assert!(
+            encryptor
+                .decrypt("test-tenant", &body, &metadata)
+                .await
+                .is_ok()
+        );
+
+        // Revoking the tenant CMK makes its objects unreadable
+        assert!(kms.revoke("test-tenant"));
+        assert!(client.read_object(&key).await.is_err());
     }
 
     #[tokio::test]
@@ -678,12 +1132,56 @@ mod tests {
         assert!(path.contains("tenant_id=tenant-123"));
     }
 
+    #[test]
+    fn test_partitions_for_range_day() {
+        let start = chrono::DateTime::parse_from_rfc3339("2024-03-01T08:30:00Z")
+            .unwrap()
+            .to_utc();
+        let end = start + chrono::Duration::days(2);
+
+        let prefixes =
+            S3Client::partitions_for_range("tenant-123", start, end, PartitionGranularity::Day);
+
+        assert_eq!(
+            prefixes,
+            vec![
+                "year=2024/month=03/day=01/tenant_id=tenant-123/",
+                "year=2024/month=03/day=02/tenant_id=tenant-123/",
+                "year=2024/month=03/day=03/tenant_id=tenant-123/",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partitions_for_range_hour() {
+        let start = chrono::DateTime::parse_from_rfc3339("2024-03-01T10:15:00Z")
+            .unwrap()
+            .to_utc();
+        let end = start + chrono::Duration::minutes(90);
+
+        let prefixes =
+            S3Client::partitions_for_range("tenant-123", start, end, PartitionGranularity::Hour);
+
+        assert_eq!(
+            prefixes,
+            vec![
+                "year=2024/month=03/day=01/hour=10/tenant_id=tenant-123/",
+                "year=2024/month=03/day=01/hour=11/tenant_id=tenant-123/",
+            ]
+        );
+        assert!(
+            S3Client::partitions_for_range("tenant-123", end, start, PartitionGranularity::Hour)
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_object_key_building() {
         let strategy = PartitionStrategy::new(PartitionGranularity::Day);
         let event = create_test_event("test");
 
-        let key = strategy.build_object_key(&event, "tenant-123", "batch-001");
+        let key =
+            strategy.build_object_key(&event, "tenant-123", "batch-001", ObjectFormat::Parquet);
 
         assert!(key.contains("tenant_id=tenant-123"));
         assert!(key.contains("audit_events_batch-001.parquet"));
@@ -720,6 +1218,33 @@ mod tests {
         assert!(matches!(config_zstd.compression, CompressionType::Zstd));
     }
 
+    #[test]
+    fn test_tls_policy_follows_use_ssl() {
+        assert!(S3Config::default().tls_policy().is_none());
+
+        let config = S3Config {
+            use_ssl: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.tls_policy().unwrap().min_version,
+            crate::outbound_tls::TlsVersion::Tls12
+        );
+
+        let config = S3Config {
+            use_ssl: true,
+            tls_config: Some(
+                OutboundTlsConfig::default()
+                    .with_min_version(crate::outbound_tls::TlsVersion::Tls13),
+            ),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.tls_policy().unwrap().min_version,
+            crate::outbound_tls::TlsVersion::Tls13
+        );
+    }
+
     #[test]
     fn test_partition_granularity() {
         let config = S3Config {
@@ -0,0 +1,403 @@
+//! Event Schema Validation
+//!
+//! Producers register a JSON Schema for the `metadata` of the events of each
+//! `event_source` / `event_version`. At ingest time the metadata of every
+//! event with a registered schema is validated against it, and events that
+//! don't conform are either rejected or flagged, carrying the JSON pointer of
+//! each violation so the producer can fix it.
+
+use hodei_audit_proto::AuditEvent;
+use prost_types::value::Kind;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
+use tracing::warn;
+
+/// Version that matches any `event_version` of a source without its own schema
+pub const ANY_VERSION: &str = "*";
+
+/// Metadata key where flagged events record their violations
+pub const VIOLATIONS_METADATA_KEY: &str = "schema_violations";
+
+/// Schema registry errors
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    #[error("Invalid schema for {event_source}@{event_version}: {message}")]
+    InvalidSchema {
+        event_source: String,
+        event_version: String,
+        message: String,
+    },
+
+    #[error("Event does not conform to schema of {event_source}@{event_version}: {}", format_violations(.violations))]
+    Violations {
+        event_source: String,
+        event_version: String,
+        violations: Vec<SchemaViolation>,
+    },
+}
+
+/// A single schema violation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON pointer into the metadata of the offending value; for a missing
+    /// required property, the pointer of the property itself
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+fn format_violations(violations: &[SchemaViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// JSON Schemas of event metadata by `event_source` and `event_version`
+#[derive(Default)]
+pub struct SchemaRegistry {
+    schemas: RwLock<HashMap<(String, String), Arc<jsonschema::Validator>>>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) the schema of a source version
+    ///
+    /// Use [`ANY_VERSION`] to cover every version without a schema of its own.
+    pub fn register(
+        &self,
+        event_source: &str,
+        event_version: &str,
+        schema: &Value,
+    ) -> Result<(), SchemaError> {
+        let validator =
+            jsonschema::validator_for(schema).map_err(|e| SchemaError::InvalidSchema {
+                event_source: event_source.to_string(),
+                event_version: event_version.to_string(),
+                message: e.to_string(),
+            })?;
+        self.schemas.write().unwrap().insert(
+            (event_source.to_string(), event_version.to_string()),
+            Arc::new(validator),
+        );
+        Ok(())
+    }
+
+    /// Remove the schema of a source version
+    pub fn unregister(&self, event_source: &str, event_version: &str) -> bool {
+        self.schemas
+            .write()
+            .unwrap()
+            .remove(&(event_source.to_string(), event_version.to_string()))
+            .is_some()
+    }
+
+    /// Whether a schema applies to events of this source version
+    pub fn has_schema(&self, event_source: &str, event_version: &str) -> bool {
+        self.validator(event_source, event_version).is_some()
+    }
+
+    /// Validate the metadata of an event against its schema
+    ///
+    /// Events without a matching schema always pass. Missing metadata is
+    /// validated as an empty object.
+    pub fn validate(&self, event: &AuditEvent) -> Result<(), SchemaError> {
+        let Some(validator) = self.validator(&event.event_source, &event.event_version) else {
+            return Ok(());
+        };
+
+        let metadata = event
+            .metadata
+            .as_ref()
+            .map(struct_to_json)
+            .unwrap_or_else(|| Value::Object(Default::default()));
+        let violations: Vec<SchemaViolation> = validator
+            .iter_errors(&metadata)
+            .map(|error| {
+                let mut path = error.instance_path.to_string();
+                if let jsonschema::error::ValidationErrorKind::Required { property } = &error.kind {
+                    path.push('/');
+                    path.push_str(property.as_str().unwrap_or_default());
+                }
+                SchemaViolation {
+                    path,
+                    message: error.to_string(),
+                }
+            })
+            .collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(SchemaError::Violations {
+                event_source: event.event_source.clone(),
+                event_version: event.event_version.clone(),
+                violations,
+            })
+        }
+    }
+
+    fn validator(
+        &self,
+        event_source: &str,
+        event_version: &str,
+    ) -> Option<Arc<jsonschema::Validator>> {
+        let schemas = self.schemas.read().unwrap();
+        schemas
+            .get(&(event_source.to_string(), event_version.to_string()))
+            .or_else(|| schemas.get(&(event_source.to_string(), ANY_VERSION.to_string())))
+            .cloned()
+    }
+}
+
+impl std::fmt::Debug for SchemaRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut keys: Vec<String> = self
+            .schemas
+            .read()
+            .unwrap()
+            .keys()
+            .map(|(source, version)| format!("{}@{}", source, version))
+            .collect();
+        keys.sort();
+        f.debug_struct("SchemaRegistry")
+            .field("schemas", &keys)
+            .finish()
+    }
+}
+
+/// What to do with an event that doesn't conform to its schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemaValidationMode {
+    /// Refuse the event
+    #[default]
+    Reject,
+    /// Accept the event, recording the violations under
+    /// [`VIOLATIONS_METADATA_KEY`] in its metadata
+    Flag,
+}
+
+/// Ingest-time schema check
+#[derive(Debug, Clone)]
+pub struct SchemaValidator {
+    registry: Arc<SchemaRegistry>,
+    mode: SchemaValidationMode,
+}
+
+impl SchemaValidator {
+    pub fn new(registry: Arc<SchemaRegistry>, mode: SchemaValidationMode) -> Self {
+        Self { registry, mode }
+    }
+
+    pub fn mode(&self) -> SchemaValidationMode {
+        self.mode
+    }
+
+    /// Validate an event being ingested
+    ///
+    /// In `Reject` mode a non-conformant event is an error; in `Flag` mode
+    /// it is annotated and accepted.
+    pub fn check(&self, event: &mut AuditEvent) -> Result<(), SchemaError> {
+        let Err(error) = self.registry.validate(event) else {
+            return Ok(());
+        };
+        match (self.mode, error) {
+            (
+                SchemaValidationMode::Flag,
+                SchemaError::Violations {
+                    event_source,
+                    violations,
+                    ..
+                },
+            ) => {
+                warn!(
+                    "[Schema] Event {} from {} flagged: {}",
+                    event
+                        .event_id
+                        .as_ref()
+                        .map(|e| e.value.as_str())
+                        .unwrap_or(""),
+                    event_source,
+                    format_violations(&violations)
+                );
+                flag_violations(event, &violations);
+                Ok(())
+            }
+            (_, error) => Err(error),
+        }
+    }
+}
+
+fn flag_violations(event: &mut AuditEvent, violations: &[SchemaViolation]) {
+    let values = violations
+        .iter()
+        .map(|v| prost_types::Value {
+            kind: Some(Kind::StringValue(v.to_string())),
+        })
+        .collect();
+    event
+        .metadata
+        .get_or_insert_with(Default::default)
+        .fields
+        .insert(
+            VIOLATIONS_METADATA_KEY.to_string(),
+            prost_types::Value {
+                kind: Some(Kind::ListValue(prost_types::ListValue { values })),
+            },
+        );
+}
+
+/// Convert protobuf `Struct` metadata to JSON
+pub fn struct_to_json(metadata: &prost_types::Struct) -> Value {
+    Value::Object(
+        metadata
+            .fields
+            .iter()
+            .map(|(key, value)| (key.clone(), value_to_json(value)))
+            .collect(),
+    )
+}
+
+fn value_to_json(value: &prost_types::Value) -> Value {
+    match &value.kind {
+        None | Some(Kind::NullValue(_)) => Value::Null,
+        Some(Kind::NumberValue(n)) => serde_json::Number::from_f64(*n)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        Some(Kind::StringValue(s)) => Value::String(s.clone()),
+        Some(Kind::BoolValue(b)) => Value::Bool(*b),
+        Some(Kind::StructValue(s)) => struct_to_json(s),
+        Some(Kind::ListValue(list)) => {
+            Value::Array(list.values.iter().map(value_to_json).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn registry() -> SchemaRegistry {
+        let registry = SchemaRegistry::new();
+        registry
+            .register(
+                "auth.hodei.io",
+                "1.0",
+                &json!({
+                    "type": "object",
+                    "required": ["client_ip"],
+                    "properties": {
+                        "client_ip": { "type": "string" }
+                    }
+                }),
+            )
+            .unwrap();
+        registry
+    }
+
+    fn event(metadata: &[(&str, Kind)]) -> AuditEvent {
+        AuditEvent {
+            event_source: "auth.hodei.io".to_string(),
+            event_version: "1.0".to_string(),
+            metadata: Some(prost_types::Struct {
+                fields: metadata
+                    .iter()
+                    .map(|(key, kind)| {
+                        (
+                            key.to_string(),
+                            prost_types::Value {
+                                kind: Some(kind.clone()),
+                            },
+                        )
+                    })
+                    .collect(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_conformant_event_passes() {
+        let registry = registry();
+        let conformant = event(&[
+            ("client_ip", Kind::StringValue("10.0.0.1".to_string())),
+            ("attempts", Kind::NumberValue(2.0)),
+        ]);
+        assert!(registry.validate(&conformant).is_ok());
+
+        // Sources without a schema are not validated
+        let other = AuditEvent {
+            event_source: "billing.hodei.io".to_string(),
+            ..Default::default()
+        };
+        assert!(registry.validate(&other).is_ok());
+    }
+
+    #[test]
+    fn test_missing_field_is_reported_with_its_path() {
+        let registry = registry();
+
+        match registry.validate(&event(&[(
+            "user_agent",
+            Kind::StringValue("curl".to_string()),
+        )])) {
+            Err(SchemaError::Violations { violations, .. }) => {
+                assert_eq!(violations.len(), 1);
+                assert_eq!(violations[0].path, "/client_ip");
+            }
+            other => panic!("expected violations, got {:?}", other),
+        }
+
+        match registry.validate(&event(&[("client_ip", Kind::NumberValue(1.0))])) {
+            Err(SchemaError::Violations { violations, .. }) => {
+                assert_eq!(violations[0].path, "/client_ip");
+            }
+            other => panic!("expected violations, got {:?}", other),
+        }
+
+        assert!(matches!(
+            registry.register("bad", "1.0", &json!({ "type": 42 })),
+            Err(SchemaError::InvalidSchema { .. })
+        ));
+    }
+
+    #[test]
+    fn test_flag_mode_annotates_event() {
+        let registry = Arc::new(registry());
+        let mut missing = event(&[]);
+
+        let rejecting = SchemaValidator::new(registry.clone(), SchemaValidationMode::Reject);
+        assert!(rejecting.check(&mut missing.clone()).is_err());
+
+        let flagging = SchemaValidator::new(registry, SchemaValidationMode::Flag);
+        flagging.check(&mut missing).unwrap();
+        let flagged = &missing.metadata.as_ref().unwrap().fields[VIOLATIONS_METADATA_KEY];
+        match &flagged.kind {
+            Some(Kind::ListValue(list)) => {
+                assert_eq!(list.values.len(), 1);
+                assert!(matches!(
+                    &list.values[0].kind,
+                    Some(Kind::StringValue(v)) if v.starts_with("/client_ip:")
+                ));
+            }
+            other => panic!("expected a list of violations, got {:?}", other),
+        }
+    }
+}
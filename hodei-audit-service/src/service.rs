@@ -6,12 +6,18 @@
 //! - EventEnricher for event enrichment
 //! - QueryEngine for querying events
 //! - ClickHouse and S3 clients for storage
+//!
+//! With `retain_raw_events` each event is also stored as received, before
+//! enrichment, in the raw store of the tiered storage
+//! (`TieredStorage::with_raw_storage`). Both copies share the event_id: the
+//! raw one preserves the original record, the enriched one is what queries
+//! read. Tenant deletion reaches both.
 
 use crate::clickhouse::ClickHouseClient;
 use crate::enrichment::EventEnricher;
 use crate::query::{AuditQuery as EngineQuery, QueryEngine as Engine, QueryResult};
 use crate::s3_storage::S3Client;
-use crate::storage::TieredStorage;
+use crate::storage::{QueryFilter, TieredStorage};
 use anyhow::Result;
 use hodei_audit_proto::{AuditEvent, EventId, Hrn, TenantId};
 use hodei_audit_types::hrn::{HrnError, HrnMetadata, HrnResolver};
@@ -34,6 +40,8 @@ pub struct ServiceConfig {
     pub query_timeout_secs: u64,
     /// Enable metrics collection
     pub enable_metrics: bool,
+    /// Also store every event unaltered, as received
+    pub retain_raw_events: bool,
 }
 
 impl Default for ServiceConfig {
@@ -45,6 +53,7 @@ impl Default for ServiceConfig {
             batch_size: 1000,
             query_timeout_secs: 30,
             enable_metrics: true,
+            retain_raw_events: false,
         }
     }
 }
@@ -214,15 +223,37 @@ impl HodeiAuditService {
         Self::new(ServiceConfig::default()).await
     }
 
+    /// Store enriched events in `storage`
+    pub fn with_storage(mut self, storage: TieredStorage) -> Self {
+        self.storage = Arc::new(storage);
+        self
+    }
+
     /// Publish a single event
-    pub async fn publish_event(&self, event: AuditEvent) -> Result<EventId> {
+    ///
+    /// Events without an id get one before any copy is stored. With
+    /// `retain_raw_events`, publishing fails unless the storage has a raw
+    /// store.
+    pub async fn publish_event(&self, mut event: AuditEvent) -> Result<EventId> {
         let start_time = SystemTime::now();
-        let event_id = event.event_id.clone().unwrap_or_else(|| EventId {
-            value: uuid::Uuid::new_v4().to_string(),
-        });
+        let event_id = event
+            .event_id
+            .get_or_insert_with(|| EventId {
+                value: uuid::Uuid::new_v4().to_string(),
+            })
+            .clone();
 
         info!("[Service] Publishing event: {}", event_id.value);
 
+        // Step 0: Keep the event as received (if enabled)
+        if self.config.retain_raw_events
+            && let Err(e) = self.storage.store_raw_event(&event).await
+        {
+            error!("[Service] Failed to store raw event: {}", e);
+            self.increment_failed_events();
+            return Err(e);
+        }
+
         // Step 1: Enrich event (if enabled)
         let mut enriched_event = event;
         if self.config.enable_enrichment {
@@ -294,6 +325,25 @@ impl HodeiAuditService {
         Ok(published_ids)
     }
 
+    /// Stored (enriched) copy of an event
+    pub async fn get_event(&self, event_id: &str) -> Result<Option<AuditEvent>> {
+        self.storage.get_event_by_id(event_id).await
+    }
+
+    /// Event as received, before enrichment; `None` unless
+    /// `retain_raw_events` was set when it was published
+    pub async fn get_raw_event(&self, event_id: &str) -> Result<Option<AuditEvent>> {
+        let Some(raw_storage) = self.storage.raw_storage() else {
+            return Ok(None);
+        };
+        let filter = QueryFilter {
+            event_id: Some(event_id.to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        Ok(raw_storage.query_events(&filter).await?.into_iter().next())
+    }
+
     /// Query events
     pub async fn query_events(&self, query: EngineQuery) -> Result<QueryResult> {
         let start_time = SystemTime::now();
@@ -431,6 +481,7 @@ impl HodeiAuditService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::{InMemoryStorage, StorageBackend};
 
     fn create_test_event(id: &str) -> AuditEvent {
         AuditEvent {
@@ -478,6 +529,117 @@ mod tests {
         }
     }
 
+    async fn in_memory_service(config: ServiceConfig) -> HodeiAuditService {
+        let tier = || Arc::new(InMemoryStorage::new()) as Arc<dyn StorageBackend>;
+        HodeiAuditService::new(config).await.unwrap().with_storage(
+            TieredStorage::from_backends(
+                tier(),
+                tier(),
+                tier(),
+                Default::default(),
+                Default::default(),
+            )
+            .with_raw_storage(tier()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_raw_and_enriched_copies_share_event_id() {
+        let service = in_memory_service(ServiceConfig {
+            retain_raw_events: true,
+            ..Default::default()
+        })
+        .await;
+        let event = create_test_event("dual-1");
+        let ingested = prost::Message::encode_to_vec(&event);
+
+        service.publish_event(event).await.unwrap();
+
+        let raw = service.get_raw_event("dual-1").await.unwrap().unwrap();
+        assert_eq!(prost::Message::encode_to_vec(&raw), ingested);
+        assert!(!raw.enriched);
+        let enriched = service.get_event("dual-1").await.unwrap().unwrap();
+        assert!(enriched.enriched);
+        assert!(enriched.processed_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_raw_copy_is_not_kept_by_default() {
+        let service = in_memory_service(ServiceConfig::default()).await;
+
+        service
+            .publish_event(create_test_event("single-1"))
+            .await
+            .unwrap();
+
+        assert!(service.get_raw_event("single-1").await.unwrap().is_none());
+        assert!(service.get_event("single-1").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_raw_copy_requires_raw_store() {
+        let service = HodeiAuditService::new(ServiceConfig {
+            retain_raw_events: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        assert!(
+            service
+                .publish_event(create_test_event("no-raw-store"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_offboarding_deletes_raw_copies() {
+        use crate::compliance::ComplianceManager;
+        use crate::crypto::ports::signing::SigningService;
+        use crate::quotas::QuotaManager;
+
+        let service = in_memory_service(ServiceConfig {
+            retain_raw_events: true,
+            ..Default::default()
+        })
+        .await;
+        service
+            .publish_event(create_test_event("raw-offboard"))
+            .await
+            .unwrap();
+        assert!(
+            service
+                .get_raw_event("raw-offboard")
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        let signer = Arc::new(crate::crypto::Ed25519Signer::new());
+        let keypair = signer.generate_keypair().unwrap();
+        let mut compliance =
+            ComplianceManager::new().with_certificate_signer(signer, keypair.private_key);
+        let certificate = compliance
+            .offboard_tenant(
+                "test-tenant",
+                &service.storage,
+                &mut QuotaManager::new(),
+                "admin@example.com",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(certificate.events_deleted["raw"], 1);
+        assert!(
+            service
+                .get_raw_event("raw-offboard")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
     #[tokio::test]
     async fn test_service_initialization() {
         let service = HodeiAuditService::new_with_defaults().await;
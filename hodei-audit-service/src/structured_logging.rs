@@ -9,6 +9,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Log level
@@ -188,6 +189,8 @@ impl SensitiveDataDetector {
 pub struct StructuredLogger {
     service_name: String,
     sensitive_detector: SensitiveDataDetector,
+    /// Copies of written entries, shared by clones
+    recorded: Option<Arc<Mutex<Vec<LogEntry>>>>,
 }
 
 impl StructuredLogger {
@@ -196,9 +199,24 @@ impl StructuredLogger {
         Self {
             service_name: service_name.to_string(),
             sensitive_detector: SensitiveDataDetector::new(),
+            recorded: None,
         }
     }
 
+    /// Also keep every written entry in memory (for testing)
+    pub fn with_recording(mut self) -> Self {
+        self.recorded = Some(Arc::default());
+        self
+    }
+
+    /// Entries written so far by this logger and its clones, if recording
+    pub fn recorded_entries(&self) -> Vec<LogEntry> {
+        self.recorded
+            .as_ref()
+            .map(|recorded| recorded.lock().unwrap().clone())
+            .unwrap_or_default()
+    }
+
     /// Get current timestamp
     fn get_timestamp(&self) -> String {
         let now = SystemTime::now();
@@ -308,6 +326,10 @@ impl StructuredLogger {
         // - Or other centralized logging systems
         let json = serde_json::to_string(&entry).unwrap_or_default();
         println!("{}", json);
+
+        if let Some(recorded) = &self.recorded {
+            recorded.lock().unwrap().push(entry);
+        }
     }
 
     /// Export logs to JSON (for testing or manual review)
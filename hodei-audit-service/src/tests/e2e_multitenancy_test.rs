@@ -216,7 +216,7 @@ mod tests {
             chrono::Utc::now() - chrono::Duration::days(100),
             chrono::Utc::now(),
         );
-        compliance_manager.create_legal_hold(legal_hold);
+        compliance_manager.create_legal_hold(legal_hold).await;
         println!("✅ Legal hold created for Tenant A");
 
         // Step 9: Test compliance enforcement
@@ -7,7 +7,7 @@ use hodei_audit_proto::audit_control::{
     HealthCheckRequest, PublishBatchRequest, PublishEventRequest,
 };
 use hodei_audit_proto::audit_event::{AuditEvent, EventId, Hrn, TenantId};
-use hodei_audit_service::grpc::{GrpcConfig, run_grpc_server};
+use hodei_audit_service::grpc::{DEFAULT_MAX_EVENT_BYTES, GrpcConfig, run_grpc_server};
 use prost_types::Timestamp;
 use std::time::Duration;
 use tokio::time::sleep;
@@ -23,6 +23,11 @@ async fn test_grpc_services_integration() {
         audit_query_addr: "127.0.0.1:0".to_string(),
         audit_crypto_addr: "127.0.0.1:0".to_string(),
         vector_api_addr: "127.0.0.1:0".to_string(),
+        enable_reflection: false,
+        tls: None,
+        metrics: None,
+        max_event_bytes: DEFAULT_MAX_EVENT_BYTES,
+        ..Default::default()
     };
 
     // Iniciar servidor en background
@@ -401,7 +401,7 @@ mod tests {
             Utc::now(),
         );
 
-        manager.create_legal_hold(hold);
+        manager.create_legal_hold(hold).await;
 
         // Event within legal hold range should not be deletable
         let event_in_hold = Utc::now() - chrono::Duration::days(100);
@@ -17,6 +17,7 @@ mod vector_forwarder_tests {
             health_check_interval: std::time::Duration::from_secs(10),
             tls_config: None,
             use_compression: true,
+            ..Default::default()
         };
 
         let forwarder = VectorForwarder::new(config).await;
@@ -39,6 +40,7 @@ mod vector_forwarder_tests {
             health_check_interval: std::time::Duration::from_secs(5),
             tls_config: None,
             use_compression: false,
+            ..Default::default()
         };
 
         let forwarder = VectorForwarder::new(config).await;
@@ -74,6 +76,7 @@ mod vector_forwarder_tests {
             health_check_interval: std::time::Duration::from_secs(5),
             tls_config: None,
             use_compression: false,
+            ..Default::default()
         };
 
         let forwarder = VectorForwarder::new(config).await;
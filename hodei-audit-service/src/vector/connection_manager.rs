@@ -0,0 +1,470 @@
+//! Queued forwarding with automatic reconnection
+//!
+//! `QueuedVectorForwarder` buffers events in memory and drains them to
+//! Vector from a background connection-manager task. When the connection
+//! drops (a send fails with a retryable error, or a health check fails while
+//! idle) the task discards the channel and reconnects with bounded
+//! exponential backoff, then resumes draining the queue from the first
+//! undelivered event.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use hodei_audit_proto::AuditEvent;
+use tokio::sync::{Mutex, Notify, mpsc};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::vector::error::{VectorError, VectorResult};
+use crate::vector::vector_forwarder::{VectorForwarder, VectorForwarderConfig};
+
+/// Connection state shared with the manager task
+#[derive(Debug, Default)]
+struct ConnectionState {
+    connected: AtomicBool,
+    reconnects: AtomicU64,
+    sent_events: AtomicU64,
+}
+
+/// Forwarder draining an in-memory queue to Vector, reconnecting as needed
+#[derive(Debug)]
+pub struct QueuedVectorForwarder {
+    sender: mpsc::UnboundedSender<AuditEvent>,
+    /// Events received from the queue but not yet delivered
+    pending: Arc<Mutex<VecDeque<AuditEvent>>>,
+    state: Arc<ConnectionState>,
+    shutdown: Arc<Notify>,
+    handle: JoinHandle<()>,
+}
+
+impl QueuedVectorForwarder {
+    /// Start the connection-manager task
+    ///
+    /// The first connection is made in the background, so events can be
+    /// queued before Vector is reachable.
+    pub fn start(config: VectorForwarderConfig) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let pending = Arc::new(Mutex::new(VecDeque::new()));
+        let state = Arc::new(ConnectionState::default());
+        let shutdown = Arc::new(Notify::new());
+
+        let manager = ConnectionManager {
+            config,
+            receiver,
+            pending: pending.clone(),
+            state: state.clone(),
+            shutdown: shutdown.clone(),
+        };
+        let handle = tokio::spawn(manager.run());
+
+        Self {
+            sender,
+            pending,
+            state,
+            shutdown,
+            handle,
+        }
+    }
+
+    /// Queue an event for delivery
+    pub fn enqueue(&self, event: AuditEvent) -> VectorResult<()> {
+        self.sender
+            .send(event)
+            .map_err(|_| VectorError::Internal("Forwarder task has stopped".to_string()))
+    }
+
+    /// Whether the forwarder currently holds a connection to Vector
+    pub fn is_connected(&self) -> bool {
+        self.state.connected.load(Ordering::SeqCst)
+    }
+
+    /// Times the connection was re-established after being lost
+    pub fn reconnects(&self) -> u64 {
+        self.state.reconnects.load(Ordering::SeqCst)
+    }
+
+    /// Events delivered to Vector
+    pub fn sent_events(&self) -> u64 {
+        self.state.sent_events.load(Ordering::SeqCst)
+    }
+
+    /// Stop the manager task, returning the events that were not delivered
+    pub async fn shutdown(self) -> Vec<AuditEvent> {
+        self.shutdown.notify_one();
+        if let Err(e) = self.handle.await {
+            error!(error = %e, "Vector connection manager task failed");
+        }
+        self.pending.lock().await.drain(..).collect()
+    }
+}
+
+struct ConnectionManager {
+    config: VectorForwarderConfig,
+    receiver: mpsc::UnboundedReceiver<AuditEvent>,
+    pending: Arc<Mutex<VecDeque<AuditEvent>>>,
+    state: Arc<ConnectionState>,
+    shutdown: Arc<Notify>,
+}
+
+impl ConnectionManager {
+    async fn run(mut self) {
+        let mut backoff = self.config.reconnect_initial_backoff;
+        let mut ever_connected = false;
+
+        loop {
+            let mut forwarder = match self.connect().await {
+                Ok(forwarder) => forwarder,
+                Err(e) => {
+                    warn!(
+                        endpoint = self.config.endpoint,
+                        delay_ms = backoff.as_millis(),
+                        error = %e,
+                        "Failed to connect to Vector, retrying"
+                    );
+                    if !self.wait(backoff).await {
+                        break;
+                    }
+                    backoff = (backoff * 2).min(self.config.reconnect_max_backoff);
+                    continue;
+                }
+            };
+
+            backoff = self.config.reconnect_initial_backoff;
+            self.state.connected.store(true, Ordering::SeqCst);
+            if ever_connected {
+                self.state.reconnects.fetch_add(1, Ordering::SeqCst);
+                info!(endpoint = self.config.endpoint, "Reconnected to Vector");
+            }
+            ever_connected = true;
+
+            let keep_running = self.drain(&mut forwarder).await;
+            self.state.connected.store(false, Ordering::SeqCst);
+            if !keep_running {
+                break;
+            }
+            warn!(endpoint = self.config.endpoint, "Lost connection to Vector");
+        }
+
+        // Hand back what was still in the channel with the undelivered events
+        let mut pending = self.pending.lock().await;
+        while let Ok(event) = self.receiver.try_recv() {
+            pending.push_back(event);
+        }
+    }
+
+    async fn connect(&self) -> VectorResult<VectorForwarder> {
+        let channel = self.config.connect().await?;
+        VectorForwarder::new_with_client(self.config.clone(), Some(channel)).await
+    }
+
+    /// Send queued events until the connection is lost (`true`) or the
+    /// forwarder shuts down (`false`)
+    async fn drain(&mut self, forwarder: &mut VectorForwarder) -> bool {
+        loop {
+            let batch: Vec<AuditEvent> = {
+                let mut pending = self.pending.lock().await;
+                while let Ok(event) = self.receiver.try_recv() {
+                    pending.push_back(event);
+                }
+                pending
+                    .iter()
+                    .take(self.config.max_batch_size)
+                    .cloned()
+                    .collect()
+            };
+
+            if batch.is_empty() {
+                // Idle: wait for events, checking the connection meanwhile
+                tokio::select! {
+                    event = self.receiver.recv() => match event {
+                        Some(event) => self.pending.lock().await.push_back(event),
+                        None => return false,
+                    },
+                    _ = tokio::time::sleep(self.config.health_check_interval) => {
+                        if !forwarder.is_healthy().await {
+                            return true;
+                        }
+                    }
+                    _ = self.shutdown.notified() => return false,
+                }
+                continue;
+            }
+
+            let count = batch.len();
+            match forwarder.send_events(batch).await {
+                Ok(delivery) => {
+                    self.state
+                        .sent_events
+                        .fetch_add(delivery.accepted as u64, Ordering::SeqCst);
+                    if !delivery.rejected.is_empty() {
+                        error!(
+                            event_count = delivery.rejected.len(),
+                            error = delivery.message,
+                            "Vector kept rejecting events, dropping them"
+                        );
+                    }
+                }
+                // Keep the batch queued and reconnect
+                Err(e) if e.is_retryable() => return true,
+                Err(e) => {
+                    error!(
+                        event_count = count,
+                        error = %e,
+                        "Vector rejected batch, dropping it"
+                    );
+                }
+            }
+            self.pending.lock().await.drain(..count);
+        }
+    }
+
+    /// Sleep for `delay`; `false` if shutdown was requested meanwhile
+    async fn wait(&self, delay: Duration) -> bool {
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => true,
+            _ = self.shutdown.notified() => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hodei_audit_proto::vector_api_server::{VectorApi, VectorApiServer};
+    use hodei_audit_proto::{
+        EventBatchRequest, EventBatchResponse, EventId, EventStatus, HandshakeRequest,
+        HandshakeResponse, HealthCheckRequest, HealthCheckResponse, HealthStatus,
+    };
+    use tokio::sync::oneshot;
+    use tonic::Status;
+
+    /// Vector mock recording the ids of the events it receives
+    #[derive(Default, Clone)]
+    struct RecordingVector {
+        received: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[tonic::async_trait]
+    impl VectorApi for RecordingVector {
+        async fn send_event_batch(
+            &self,
+            request: tonic::Request<EventBatchRequest>,
+        ) -> Result<tonic::Response<EventBatchResponse>, Status> {
+            let events = request.into_inner().events;
+            let received_count = events.len() as u32;
+            self.received.lock().unwrap().extend(
+                events
+                    .into_iter()
+                    .map(|e| e.event_id.map(|id| id.value).unwrap_or_default()),
+            );
+            Ok(tonic::Response::new(EventBatchResponse {
+                success: true,
+                received_count,
+                ..Default::default()
+            }))
+        }
+
+        async fn health_check(
+            &self,
+            _request: tonic::Request<HealthCheckRequest>,
+        ) -> Result<tonic::Response<HealthCheckResponse>, Status> {
+            Ok(tonic::Response::new(HealthCheckResponse {
+                status: HealthStatus::StatusServing as i32,
+                ..Default::default()
+            }))
+        }
+
+        async fn handshake(
+            &self,
+            _request: tonic::Request<HandshakeRequest>,
+        ) -> Result<tonic::Response<HandshakeResponse>, Status> {
+            Ok(tonic::Response::new(HandshakeResponse::default()))
+        }
+    }
+
+    /// Vector mock rejecting each event the first time it sees it
+    #[derive(Default, Clone)]
+    struct RejectOnceVector {
+        received: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[tonic::async_trait]
+    impl VectorApi for RejectOnceVector {
+        async fn send_event_batch(
+            &self,
+            request: tonic::Request<EventBatchRequest>,
+        ) -> Result<tonic::Response<EventBatchResponse>, Status> {
+            let mut received = self.received.lock().unwrap();
+            let mut response = EventBatchResponse {
+                success: true,
+                ..Default::default()
+            };
+            for (index, event) in request.into_inner().events.into_iter().enumerate() {
+                let id = event.event_id.map(|id| id.value).unwrap_or_default();
+                let accepted = id != "event-2" || received.contains(&id);
+                if accepted {
+                    response.received_count += 1;
+                } else {
+                    response.rejected_count += 1;
+                }
+                response.event_statuses.push(EventStatus {
+                    index: index as u32,
+                    event_id: id.clone(),
+                    accepted,
+                    error: String::new(),
+                });
+                received.push(id);
+            }
+            Ok(tonic::Response::new(response))
+        }
+
+        async fn health_check(
+            &self,
+            _request: tonic::Request<HealthCheckRequest>,
+        ) -> Result<tonic::Response<HealthCheckResponse>, Status> {
+            Ok(tonic::Response::new(HealthCheckResponse {
+                status: HealthStatus::StatusServing as i32,
+                ..Default::default()
+            }))
+        }
+
+        async fn handshake(
+            &self,
+            _request: tonic::Request<HandshakeRequest>,
+        ) -> Result<tonic::Response<HandshakeResponse>, Status> {
+            Ok(tonic::Response::new(HandshakeResponse::default()))
+        }
+    }
+
+    /// Serve `vector` on `addr` until the returned sender fires
+    async fn serve(
+        vector: impl VectorApi,
+        addr: std::net::SocketAddr,
+    ) -> (oneshot::Sender<()>, JoinHandle<()>) {
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        let (stop, stopped) = oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            tonic::transport::Server::builder()
+                .add_service(VectorApiServer::new(vector))
+                .serve_with_incoming_shutdown(
+                    tokio_stream::wrappers::TcpListenerStream::new(listener),
+                    async {
+                        let _ = stopped.await;
+                    },
+                )
+                .await
+                .unwrap();
+        });
+        (stop, server)
+    }
+
+    fn event(id: &str) -> AuditEvent {
+        AuditEvent {
+            event_id: Some(EventId {
+                value: id.to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    async fn wait_until(mut condition: impl FnMut() -> bool) {
+        for _ in 0..200 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(25)).await;
+        }
+        panic!("condition not reached in time");
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_and_sends_queued_events_after_drop() {
+        let addr = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap()
+        };
+        let vector = RecordingVector::default();
+        let (stop, server) = serve(vector.clone(), addr).await;
+
+        let forwarder = QueuedVectorForwarder::start(VectorForwarderConfig {
+            endpoint: format!("http://{}", addr),
+            max_retries: 0,
+            connect_timeout: Duration::from_millis(500),
+            reconnect_initial_backoff: Duration::from_millis(20),
+            reconnect_max_backoff: Duration::from_millis(100),
+            use_compression: false,
+            ..Default::default()
+        });
+
+        forwarder.enqueue(event("event-1")).unwrap();
+        wait_until(|| forwarder.sent_events() == 1).await;
+        assert!(forwarder.is_connected());
+
+        // Drop the connection: Vector goes away while events keep coming
+        stop.send(()).unwrap();
+        server.await.unwrap();
+        forwarder.enqueue(event("event-2")).unwrap();
+        forwarder.enqueue(event("event-3")).unwrap();
+        wait_until(|| !forwarder.is_connected()).await;
+        assert_eq!(forwarder.sent_events(), 1);
+
+        let (_stop, _server) = serve(vector.clone(), addr).await;
+        wait_until(|| forwarder.sent_events() == 3).await;
+
+        assert!(forwarder.reconnects() >= 1);
+        assert_eq!(
+            *vector.received.lock().unwrap(),
+            vec!["event-1", "event-2", "event-3"]
+        );
+        assert!(forwarder.shutdown().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resends_only_the_rejected_events() {
+        let addr = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap()
+        };
+        let vector = RejectOnceVector::default();
+        let (_stop, _server) = serve(vector.clone(), addr).await;
+
+        let forwarder = QueuedVectorForwarder::start(VectorForwarderConfig {
+            endpoint: format!("http://{}", addr),
+            retry_delay: Duration::from_millis(10),
+            use_compression: false,
+            ..Default::default()
+        });
+        for id in ["event-1", "event-2", "event-3"] {
+            forwarder.enqueue(event(id)).unwrap();
+        }
+        wait_until(|| forwarder.sent_events() == 3).await;
+
+        let received = vector.received.lock().unwrap().clone();
+        assert_eq!(received.len(), 4);
+        assert_eq!(received[3], "event-2");
+        assert!(forwarder.shutdown().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_queues_events_until_vector_is_reachable() {
+        let addr = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap()
+        };
+        let forwarder = QueuedVectorForwarder::start(VectorForwarderConfig {
+            endpoint: format!("http://{}", addr),
+            connect_timeout: Duration::from_millis(200),
+            reconnect_initial_backoff: Duration::from_millis(20),
+            reconnect_max_backoff: Duration::from_millis(50),
+            ..Default::default()
+        });
+        forwarder.enqueue(event("event-1")).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!forwarder.is_connected());
+
+        let undelivered = forwarder.shutdown().await;
+        assert_eq!(undelivered.len(), 1);
+    }
+}
@@ -3,12 +3,14 @@
 //! This module provides the VectorForwarder client for sending audit events
 //! to Vector.dev for multi-sink distribution.
 
+pub mod connection_manager;
 pub mod error;
 #[cfg(feature = "vector-metrics")]
 pub mod metrics;
 pub mod sink_manager;
 pub mod vector_forwarder;
 
+pub use connection_manager::QueuedVectorForwarder;
 pub use error::{VectorError, VectorResult};
 
 #[cfg(feature = "vector-metrics")]
@@ -17,4 +19,4 @@ pub use metrics::{
 };
 
 pub use sink_manager::{VectorSinkConfig, VectorSinkManager, VectorSinkType, create_default_sinks};
-pub use vector_forwarder::{VectorForwarder, VectorForwarderConfig};
+pub use vector_forwarder::{BatchDelivery, VectorForwarder, VectorForwarderConfig};
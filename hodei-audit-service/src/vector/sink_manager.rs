@@ -2,6 +2,10 @@
 //!
 //! This module provides utilities for managing Vector sinks (ClickHouse, S3, Blackhole)
 //! and handling multi-sink fan-out.
+//!
+//! Tenants can be mapped to their own sinks (e.g. tenant A → their Splunk,
+//! tenant B → their S3); unmapped tenants go to the default sinks, or to
+//! every sink when no default is set.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -161,6 +165,10 @@ pub struct VectorSinkManager {
     sinks: HashMap<String, VectorSinkConfig>,
     /// Active sinks (enabled and healthy)
     active_sinks: Vec<String>,
+    /// Sinks receiving each mapped tenant's events
+    tenant_sinks: HashMap<String, Vec<String>>,
+    /// Sinks for unmapped tenants; empty means every sink
+    default_sinks: Vec<String>,
 }
 
 impl VectorSinkManager {
@@ -169,6 +177,8 @@ impl VectorSinkManager {
         Self {
             sinks: HashMap::new(),
             active_sinks: Vec::new(),
+            tenant_sinks: HashMap::new(),
+            default_sinks: Vec::new(),
         }
     }
 
@@ -183,6 +193,83 @@ impl VectorSinkManager {
         info!(sink = name, "Removing Vector sink");
         self.sinks.remove(name);
         self.active_sinks.retain(|s| s != name);
+        self.default_sinks.retain(|s| s != name);
+        for sinks in self.tenant_sinks.values_mut() {
+            sinks.retain(|s| s != name);
+        }
+    }
+
+    /// Send a tenant's events only to `sinks`
+    pub fn map_tenant(&mut self, tenant_id: &str, sinks: &[&str]) {
+        info!(tenant_id = tenant_id, sinks = ?sinks, "Mapping tenant to Vector sinks");
+        self.tenant_sinks.insert(
+            tenant_id.to_string(),
+            sinks.iter().map(|s| s.to_string()).collect(),
+        );
+    }
+
+    /// Send a tenant's events back to the default sinks
+    pub fn unmap_tenant(&mut self, tenant_id: &str) {
+        self.tenant_sinks.remove(tenant_id);
+    }
+
+    /// Sinks for tenants without a mapping
+    pub fn set_default_sinks(&mut self, sinks: &[&str]) {
+        self.default_sinks = sinks.iter().map(|s| s.to_string()).collect();
+    }
+
+    /// Enabled sinks that receive a tenant's events
+    pub fn sinks_for_tenant(&self, tenant_id: &str) -> Vec<&VectorSinkConfig> {
+        let names = match self.tenant_sinks.get(tenant_id) {
+            Some(names) => names,
+            None if self.default_sinks.is_empty() => {
+                let mut sinks: Vec<_> = self.sinks.values().filter(|s| s.enabled).collect();
+                sinks.sort_by(|a, b| a.name.cmp(&b.name));
+                return sinks;
+            }
+            None => &self.default_sinks,
+        };
+        names
+            .iter()
+            .filter_map(|name| self.sinks.get(name))
+            .filter(|sink| sink.enabled)
+            .collect()
+    }
+
+    /// VRL condition selecting the events a sink receives, `None` when no
+    /// tenant is mapped and every sink gets every event
+    fn tenant_condition(&self, name: &str) -> Option<String> {
+        if self.tenant_sinks.is_empty() {
+            return None;
+        }
+
+        let quoted = |tenants: Vec<&String>| {
+            let mut tenants: Vec<String> = tenants.iter().map(|t| format!("{:?}", t)).collect();
+            tenants.sort();
+            tenants.join(", ")
+        };
+        let mut clauses = Vec::new();
+        let mapped: Vec<&String> = self
+            .tenant_sinks
+            .iter()
+            .filter(|(_, sinks)| sinks.iter().any(|s| s == name))
+            .map(|(tenant, _)| tenant)
+            .collect();
+        if !mapped.is_empty() {
+            clauses.push(format!("includes([{}], .tenant_id.value)", quoted(mapped)));
+        }
+        if self.default_sinks.is_empty() || self.default_sinks.iter().any(|s| s == name) {
+            clauses.push(format!(
+                "!includes([{}], .tenant_id.value)",
+                quoted(self.tenant_sinks.keys().collect())
+            ));
+        }
+
+        Some(if clauses.is_empty() {
+            "false".to_string()
+        } else {
+            clauses.join(" || ")
+        })
     }
 
     /// Get a sink by name
@@ -263,9 +350,21 @@ impl VectorSinkManager {
                 continue;
             }
 
+            // Only this sink's tenants reach it
+            let input = match self.tenant_condition(name) {
+                Some(condition) => {
+                    config.push_str(&format!("# Route: {}\n[transforms.route_{}]\n", name, name));
+                    config.push_str("type = \"filter\"\n");
+                    config.push_str("inputs = [\"enrich\"]\n");
+                    config.push_str(&format!("condition = '{}'\n\n", condition));
+                    format!("route_{}", name)
+                }
+                None => "enrich".to_string(),
+            };
+
             config.push_str(&format!("# Sink: {}\n[transforms.to_{}]\n", name, name));
             config.push_str("type = \"remap\"\n");
-            config.push_str("inputs = [\"enrich\"]\n");
+            config.push_str(&format!("inputs = [\"{}\"]\n", input));
             config.push_str(&format!("{}_payload = {{\n", name.to_lowercase()));
             config.push_str("  \"event_id\" = .event_id.value,\n");
             config.push_str("  \"tenant_id\" = .tenant_id.value,\n");
@@ -506,4 +605,91 @@ mod tests {
         assert_eq!(format!("{}", BufferType::Memory), "memory");
         assert_eq!(format!("{}", BufferType::Disk), "disk");
     }
+
+    fn http_sink(name: &str, url: &str) -> VectorSinkConfig {
+        VectorSinkConfig {
+            name: name.to_string(),
+            sink_type: VectorSinkType::HTTP,
+            connection: SinkConnection {
+                clickhouse: None,
+                s3: None,
+                http: Some(HttpConnection {
+                    url: url.to_string(),
+                    method: "POST".to_string(),
+                    headers: HashMap::new(),
+                }),
+                kafka: None,
+            },
+            buffer: BufferConfig {
+                max_events: 1000,
+                buffer_type: BufferType::Memory,
+                when_full: WhenFull::Block,
+                max_file_size: None,
+            },
+            retry: RetryConfig {
+                max_attempts: 3,
+                initial_interval: 1,
+                max_interval: 10,
+                multiplier: 2.0,
+            },
+            enabled: true,
+        }
+    }
+
+    fn names(sinks: Vec<&VectorSinkConfig>) -> Vec<&str> {
+        sinks.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tenant_sink_mapping() {
+        let mut manager = create_default_sinks();
+        manager.add_sink(http_sink("splunk_a", "https://splunk.tenant-a.example"));
+        manager.map_tenant("tenant-a", &["splunk_a"]);
+        manager.map_tenant("tenant-b", &["s3_warm"]);
+        manager.set_default_sinks(&["clickhouse_hot"]);
+
+        assert_eq!(
+            names(manager.sinks_for_tenant("tenant-a")),
+            vec!["splunk_a"]
+        );
+        assert_eq!(names(manager.sinks_for_tenant("tenant-b")), vec!["s3_warm"]);
+        assert_eq!(
+            names(manager.sinks_for_tenant("tenant-c")),
+            vec!["clickhouse_hot"]
+        );
+
+        let config = manager.generate_vector_config();
+        assert!(config.contains(
+            "[transforms.route_splunk_a]\ntype = \"filter\"\ninputs = [\"enrich\"]\ncondition = 'includes([\"tenant-a\"], .tenant_id.value)'"
+        ));
+        assert!(
+            config.contains(
+                "condition = '!includes([\"tenant-a\", \"tenant-b\"], .tenant_id.value)'"
+            )
+        );
+        assert!(
+            config.contains(
+                "[transforms.to_splunk_a]\ntype = \"remap\"\ninputs = [\"route_splunk_a\"]"
+            )
+        );
+    }
+
+    #[test]
+    fn test_unmapped_tenants_use_every_sink_without_default() {
+        let mut manager = create_default_sinks();
+        assert_eq!(manager.sinks_for_tenant("tenant-a").len(), 3);
+        assert!(
+            !manager
+                .generate_vector_config()
+                .contains("type = \"filter\"")
+        );
+
+        manager.map_tenant("tenant-a", &["s3_warm"]);
+        manager.remove_sink("s3_warm");
+        assert!(manager.sinks_for_tenant("tenant-a").is_empty());
+        assert_eq!(
+            names(manager.sinks_for_tenant("tenant-c")),
+            vec!["blackhole_emergency", "clickhouse_hot"]
+        );
+    }
 }
@@ -4,17 +4,42 @@
 //! to Vector.dev for multi-sink distribution (ClickHouse, S3, etc.)
 
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 use hodei_audit_proto::{
     AuditEvent, EventBatchRequest, EventBatchResponse, HealthCheckRequest, HealthStatus,
     vector_api_client::VectorApiClient,
 };
-use tonic::transport::Channel;
+use hodei_audit_retry::RetryPolicy;
+use prost::Message;
+use prost::bytes::BufMut;
+use tonic::Status;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::transport::{Channel, Endpoint};
 use tracing::{error, info, warn};
 
+use crate::grpc::vector_api_server::{CURRENT_PROTOCOL_VERSION, events_to_retry};
+use crate::outbound_tls::OutboundTlsConfig;
 use crate::vector::error::{VectorError, VectorResult};
+use crate::zero_copy_batching::{BufferPool, ZeroCopyBatch};
+
+/// gRPC path of `VectorApi/SendEventBatch`
+const SEND_EVENT_BATCH_PATH: &str = "/hodei.audit.VectorApi/SendEventBatch";
+
+/// Outcome of delivering a batch to Vector
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchDelivery {
+    /// Batch ID Vector assigned to the last send
+    pub batch_id: String,
+    /// Events Vector accepted, across every send
+    pub accepted: usize,
+    /// Events Vector still rejected once the retries ran out
+    pub rejected: Vec<AuditEvent>,
+    /// Vector's message for the rejected events
+    pub message: String,
+}
 
 /// VectorForwarder - Client for sending events to Vector.dev
 ///
@@ -24,14 +49,24 @@ use crate::vector::error::{VectorError, VectorResult};
 /// - Health checks
 /// - Metrics and monitoring
 /// - Connection pooling
+///
+/// Every batch carries the configured `collector_id` and its own sequence
+/// number, so Vector can reject replays. Retries of a failed send reuse the
+/// batch's sequence; resending rejected events is a new batch. Sequences
+/// start at the creation time in microseconds, so they keep increasing
+/// across forwarders sharing a `collector_id`.
 #[derive(Debug, Clone)]
 pub struct VectorForwarder {
     /// gRPC client connection
     client: VectorApiClient<Channel>,
+    /// Underlying channel, used to send pre-encoded batches
+    channel: Channel,
     /// Configuration
     config: VectorForwarderConfig,
     /// Statistics
     stats: Arc<std::sync::atomic::AtomicU64>,
+    /// Sequence of the last batch built
+    sequence: Arc<AtomicU64>,
 }
 
 /// Configuration for VectorForwarder
@@ -51,10 +86,20 @@ pub struct VectorForwarderConfig {
     pub connect_timeout: Duration,
     /// Health check interval
     pub health_check_interval: Duration,
-    /// TLS configuration (placeholder)
-    pub tls_config: Option<()>,
+    /// TLS policy; `None` connects in plain text
+    pub tls_config: Option<OutboundTlsConfig>,
     /// Whether to use compression
     pub use_compression: bool,
+    /// Interval between HTTP/2 keepalive pings, to detect dropped connections
+    pub keepalive_interval: Duration,
+    /// How long to wait for a keepalive ack before closing the connection
+    pub keepalive_timeout: Duration,
+    /// First delay before reconnecting after the connection is lost
+    pub reconnect_initial_backoff: Duration,
+    /// Upper bound of the exponential reconnect backoff
+    pub reconnect_max_backoff: Duration,
+    /// Identifies this collector's batches for Vector's replay protection
+    pub collector_id: String,
 }
 
 impl Default for VectorForwarderConfig {
@@ -69,10 +114,54 @@ impl Default for VectorForwarderConfig {
             health_check_interval: Duration::from_secs(30),
             tls_config: None,
             use_compression: true,
+            keepalive_interval: Duration::from_secs(30),
+            keepalive_timeout: Duration::from_secs(10),
+            reconnect_initial_backoff: Duration::from_millis(100),
+            reconnect_max_backoff: Duration::from_secs(30),
+            collector_id: format!("vector-forwarder-{}", uuid::Uuid::new_v4()),
         }
     }
 }
 
+impl VectorForwarderConfig {
+    /// Endpoint for the configured Vector address, with timeouts and keepalive
+    ///
+    /// With `tls_config` set the scheme is `http`: the TLS handshake is done
+    /// by `OutboundTlsConfig::connect_channel`, not by tonic.
+    pub fn endpoint(&self) -> VectorResult<Endpoint> {
+        let uri = match (&self.tls_config, self.endpoint.strip_prefix("https://")) {
+            (Some(_), Some(authority)) => format!("http://{}", authority),
+            _ => self.endpoint.clone(),
+        };
+        Ok(Endpoint::from_shared(uri)
+            .map_err(|e| VectorError::InvalidArgument(format!("Invalid endpoint: {}", e)))?
+            .connect_timeout(self.connect_timeout)
+            .http2_keep_alive_interval(self.keepalive_interval)
+            .keep_alive_timeout(self.keepalive_timeout)
+            .keep_alive_while_idle(true))
+    }
+
+    /// Open a channel to Vector, over TLS if `tls_config` is set
+    pub async fn connect(&self) -> VectorResult<Channel> {
+        match &self.tls_config {
+            Some(tls) => tls
+                .connect_channel(self.endpoint()?)
+                .await
+                .map_err(|e| VectorError::ConnectionFailed(e.to_string())),
+            None => self
+                .endpoint()?
+                .connect()
+                .await
+                .map_err(|e| VectorError::ConnectionFailed(format!("Failed to connect: {}", e))),
+        }
+    }
+
+    /// Backoff between send attempts: `retry_delay` doubled on every retry
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::exponential(self.retry_delay, self.max_retries)
+    }
+}
+
 impl VectorForwarder {
     /// Create a new VectorForwarder with default configuration
     pub async fn new(config: VectorForwarderConfig) -> VectorResult<Self> {
@@ -91,25 +180,24 @@ impl VectorForwarder {
 
         let channel = match channel {
             Some(ch) => ch,
-            None => {
-                let channel = Channel::from_shared(config.endpoint.clone()).map_err(|e| {
-                    VectorError::InvalidArgument(format!("Invalid endpoint: {}", e))
-                })?;
-                channel.connect().await.map_err(|e| {
-                    VectorError::ConnectionFailed(format!("Failed to connect: {}", e))
-                })?
-            }
+            None => config.connect().await?,
         };
 
         // Create client (compression is set at request level, not client level)
-        let client = VectorApiClient::new(channel);
+        let client = VectorApiClient::new(channel.clone());
 
         let stats = Arc::new(std::sync::atomic::AtomicU64::new(0));
+        let sequence = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_micros() as u64)
+            .unwrap_or_default();
 
         let forwarder = Self {
             client,
+            channel,
             config,
             stats,
+            sequence: Arc::new(AtomicU64::new(sequence)),
         };
 
         Ok(forwarder)
@@ -117,103 +205,159 @@ impl VectorForwarder {
 
     /// Send a single event to Vector (convenience method)
     pub async fn send_event(&mut self, event: AuditEvent) -> VectorResult<String> {
-        self.send_events(vec![event]).await
+        let delivery = self.send_events(vec![event]).await?;
+        if !delivery.rejected.is_empty() {
+            return Err(VectorError::SendFailed(delivery.message));
+        }
+        Ok(delivery.batch_id)
     }
 
     /// Send multiple events to Vector as a batch
-    pub async fn send_events(&mut self, mut events: Vec<AuditEvent>) -> VectorResult<String> {
+    ///
+    /// Events Vector rejects are resent on their own, up to `max_retries`
+    /// times; those still rejected are returned in the delivery.
+    pub async fn send_events(&mut self, events: Vec<AuditEvent>) -> VectorResult<BatchDelivery> {
         if events.is_empty() {
             return Err(VectorError::InvalidArgument(
                 "Cannot send empty event batch".to_string(),
             ));
         }
 
-        // Create batch request
-        let request = EventBatchRequest { events };
+        let request = BatchPayload::Events(self.batch_request(events));
+        self.deliver(request).await
+    }
 
-        // Send with retry logic
-        let response = self.send_with_retry(request).await?;
+    /// Request for a new batch of `events`, with the next sequence
+    fn batch_request(&self, events: Vec<AuditEvent>) -> EventBatchRequest {
+        EventBatchRequest {
+            events,
+            protocol_version: CURRENT_PROTOCOL_VERSION,
+            collector_id: self.config.collector_id.clone(),
+            sequence: self.sequence.fetch_add(1, Ordering::Relaxed) + 1,
+        }
+    }
 
-        if !response.success {
-            return Err(VectorError::SendFailed(response.message));
+    /// Send a batch built by `ZeroCopyBatcher::add_event` without re-encoding it
+    ///
+    /// The batch payload already is an encoded `EventBatchRequest`, so its
+    /// bytes are written to the wire as is. The buffer is handed back to
+    /// `pool` once the batch is delivered, whether it succeeded or not;
+    /// rejected events are decoded from it and resent like in `send_events`.
+    pub async fn send_zero_copy_batch(
+        &mut self,
+        batch: ZeroCopyBatch,
+        pool: &BufferPool,
+    ) -> VectorResult<BatchDelivery> {
+        if batch.size == 0 {
+            pool.reclaim(batch).await;
+            return Err(VectorError::InvalidArgument(
+                "Cannot send empty event batch".to_string(),
+            ));
         }
 
-        info!(
-            batch_id = response.batch_id,
-            event_count = response.received_count,
-            "Successfully sent event batch to Vector"
-        );
+        // The batch fields are appended after the encoded events: protobuf
+        // merges concatenated messages, so the buffer needn't be copied
+        let batch_fields = self.batch_request(Vec::new()).encode_to_vec();
+        let request = BatchPayload::Encoded(EncodedBatch {
+            events: batch.data.clone(),
+            batch_fields,
+        });
+        let result = self.deliver(request).await;
+        pool.reclaim(batch).await;
+        result
+    }
 
-        Ok(response.batch_id)
+    /// Send `request`, then resend the events Vector rejected
+    async fn deliver(&mut self, mut request: BatchPayload) -> VectorResult<BatchDelivery> {
+        let mut delivery = BatchDelivery::default();
+        let mut attempt = 0;
+        loop {
+            let response = self.send_with_retry(&request).await?;
+            // Without per-event statuses a failed batch can't be split
+            if !response.success && response.event_statuses.is_empty() {
+                return Err(VectorError::SendFailed(response.message));
+            }
+            delivery.batch_id = response.batch_id.clone();
+            delivery.accepted += response.received_count as usize;
+            info!(
+                batch_id = response.batch_id,
+                event_count = response.received_count,
+                rejected_count = response.rejected_count,
+                "Sent event batch to Vector"
+            );
+
+            if response.event_statuses.iter().all(|status| status.accepted) {
+                return Ok(delivery);
+            }
+            let rejected = events_to_retry(request.into_events()?, &response);
+            if attempt >= self.config.max_retries {
+                warn!(
+                    rejected_count = rejected.len(),
+                    message = response.message,
+                    "Vector kept rejecting events, giving up on them"
+                );
+                delivery.rejected = rejected;
+                delivery.message = response.message;
+                return Ok(delivery);
+            }
+
+            warn!(
+                rejected_count = rejected.len(),
+                attempt = attempt + 1,
+                message = response.message,
+                "Resending events rejected by Vector"
+            );
+            tokio::time::sleep(self.config.retry_policy().delay_for_attempt(attempt)).await;
+            attempt += 1;
+            request = BatchPayload::Events(self.batch_request(rejected));
+        }
     }
 
     /// Send batch with exponential backoff retry
+    ///
+    /// Only retryable errors are retried; the error of the last attempt is
+    /// returned once the policy gives up.
     async fn send_with_retry(
         &mut self,
-        request: EventBatchRequest,
+        request: &BatchPayload,
     ) -> VectorResult<EventBatchResponse> {
-        let mut last_error = None;
-
-        for attempt in 0..=self.config.max_retries {
-            match self.send_batch_once(&request).await {
-                Ok(response) => {
-                    // Success - update stats
-                    self.stats
-                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                    return Ok(response);
-                }
-                Err(e) => {
-                    last_error = Some(e.clone());
-
-                    // Don't retry on certain errors
-                    if e.is_retryable() {
-                        if attempt < self.config.max_retries {
-                            let delay = self
-                                .config
-                                .retry_delay
-                                .checked_mul(2u32.pow(attempt))
-                                .unwrap_or(self.config.retry_delay);
-
-                            warn!(
-                                attempt = attempt + 1,
-                                max_attempts = self.config.max_retries + 1,
-                                delay_ms = delay.as_millis(),
-                                error = %e,
-                                "Failed to send batch, retrying..."
-                            );
-
-                            tokio::time::sleep(delay).await;
-                            continue;
-                        }
-                    }
-                    break;
-                }
-            }
-        }
-
-        // All retries exhausted
-        Err(last_error.unwrap_or_else(|| VectorError::Timeout("Max retries exhausted".to_string())))
+        let response = self
+            .config
+            .retry_policy()
+            .execute_with_retry_if(|| self.send_batch_once(request), VectorError::is_retryable)
+            .await
+            .map_err(|e| {
+                warn!(attempts = e.attempts(), "Giving up sending batch to Vector");
+                e.into_inner()
+            })?;
+
+        self.stats
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Ok(response)
     }
 
     /// Send batch once without retry
-    async fn send_batch_once(
-        &mut self,
-        request: &EventBatchRequest,
-    ) -> VectorResult<EventBatchResponse> {
+    async fn send_batch_once(&self, request: &BatchPayload) -> VectorResult<EventBatchResponse> {
         let start = Instant::now();
-        let event_count = request.events.len();
+        let payload_bytes = request.encoded_len();
 
-        // We need to clone the request for async operation
-        let request_clone = request.clone();
+        let result = match request {
+            BatchPayload::Events(request) => {
+                // We need to clone the request for async operation
+                let request_clone = request.clone();
 
-        // Create a mutable reference for the async operation
-        let mut client = self.client.clone();
+                // Create a mutable reference for the async operation
+                let mut client = self.client.clone();
+                client.send_event_batch(request_clone).await
+            }
+            BatchPayload::Encoded(payload) => self.send_encoded_once(payload.clone()).await,
+        };
 
-        match client.send_event_batch(request_clone).await {
+        match result {
             Ok(response) => {
                 let duration = start.elapsed();
                 info!(
-                    event_count = event_count,
+                    payload_bytes = payload_bytes,
                     duration_ms = duration.as_millis(),
                     "Batch sent successfully"
                 );
@@ -222,7 +366,7 @@ impl VectorForwarder {
             Err(status) => {
                 let duration = start.elapsed();
                 error!(
-                    event_count = event_count,
+                    payload_bytes = payload_bytes,
                     duration_ms = duration.as_millis(),
                     code = status.code() as u32,
                     error = status.message(),
@@ -233,6 +377,23 @@ impl VectorForwarder {
         }
     }
 
+    /// Send an already encoded `EventBatchRequest`
+    async fn send_encoded_once(
+        &self,
+        payload: EncodedBatch,
+    ) -> Result<tonic::Response<EventBatchResponse>, Status> {
+        let mut grpc = tonic::client::Grpc::new(self.channel.clone());
+        grpc.ready()
+            .await
+            .map_err(|e| Status::unavailable(format!("Service was not ready: {}", e)))?;
+        grpc.unary(
+            tonic::Request::new(payload),
+            http::uri::PathAndQuery::from_static(SEND_EVENT_BATCH_PATH),
+            EncodedBatchCodec,
+        )
+        .await
+    }
+
     /// Check Vector health
     pub async fn health_check(&mut self) -> VectorResult<HealthStatus> {
         let request = HealthCheckRequest {
@@ -277,6 +438,82 @@ impl VectorForwarder {
     }
 }
 
+/// Batch request sent by the forwarder
+enum BatchPayload {
+    /// Events still to be encoded by the prost codec
+    Events(EventBatchRequest),
+    /// Pre-encoded `EventBatchRequest` from a zero-copy batch
+    Encoded(EncodedBatch),
+}
+
+/// Pre-encoded events of a zero-copy batch plus the encoded batch fields
+/// (`protocol_version`, `collector_id`, `sequence`) written after them
+#[derive(Clone)]
+struct EncodedBatch {
+    events: Arc<Vec<u8>>,
+    batch_fields: Vec<u8>,
+}
+
+impl BatchPayload {
+    fn encoded_len(&self) -> usize {
+        match self {
+            BatchPayload::Events(request) => request.encoded_len(),
+            BatchPayload::Encoded(payload) => payload.events.len() + payload.batch_fields.len(),
+        }
+    }
+
+    /// Events of the batch, decoding a pre-encoded one
+    fn into_events(self) -> VectorResult<Vec<AuditEvent>> {
+        match self {
+            BatchPayload::Events(request) => Ok(request.events),
+            BatchPayload::Encoded(payload) => EventBatchRequest::decode(payload.events.as_slice())
+                .map(|request| request.events)
+                .map_err(|e| VectorError::Serialization(format!("Invalid encoded batch: {}", e))),
+        }
+    }
+}
+
+/// Codec writing pre-encoded request bytes and decoding a prost response
+#[derive(Debug, Clone, Copy, Default)]
+struct EncodedBatchCodec;
+
+impl Codec for EncodedBatchCodec {
+    type Encode = EncodedBatch;
+    type Decode = EventBatchResponse;
+    type Encoder = EncodedBatchCodec;
+    type Decoder = EncodedBatchCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        *self
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        *self
+    }
+}
+
+impl Encoder for EncodedBatchCodec {
+    type Item = EncodedBatch;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.put_slice(&item.events);
+        dst.put_slice(&item.batch_fields);
+        Ok(())
+    }
+}
+
+impl Decoder for EncodedBatchCodec {
+    type Item = EventBatchResponse;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        EventBatchResponse::decode(src)
+            .map(Some)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
 impl std::fmt::Display for VectorForwarder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -290,6 +527,281 @@ impl std::fmt::Display for VectorForwarder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::grpc::vector_api_server::VectorApiServiceImpl;
+    use crate::storage::{InMemoryStorage, QueryFilter, StorageBackend, StorageStats};
+    use crate::zero_copy_batching::{BatcherConfig, ZeroCopyBatcher};
+    use hodei_audit_proto::vector_api_server::{VectorApi, VectorApiServer};
+    use hodei_audit_proto::{
+        EventId, HandshakeRequest, HandshakeResponse, HealthCheckResponse, TenantId,
+    };
+
+    /// Vector mock que registra los lotes recibidos
+    #[derive(Default, Clone)]
+    struct RecordingVector {
+        received: Arc<std::sync::Mutex<Vec<EventBatchRequest>>>,
+    }
+
+    #[tonic::async_trait]
+    impl VectorApi for RecordingVector {
+        async fn send_event_batch(
+            &self,
+            request: tonic::Request<EventBatchRequest>,
+        ) -> Result<tonic::Response<EventBatchResponse>, Status> {
+            let request = request.into_inner();
+            let received_count = request.events.len() as u32;
+            self.received.lock().unwrap().push(request);
+            Ok(tonic::Response::new(EventBatchResponse {
+                success: true,
+                batch_id: "batch-1".to_string(),
+                received_count,
+                ..Default::default()
+            }))
+        }
+
+        async fn health_check(
+            &self,
+            _request: tonic::Request<HealthCheckRequest>,
+        ) -> Result<tonic::Response<HealthCheckResponse>, Status> {
+            Ok(tonic::Response::new(HealthCheckResponse::default()))
+        }
+
+        async fn handshake(
+            &self,
+            _request: tonic::Request<HandshakeRequest>,
+        ) -> Result<tonic::Response<HandshakeResponse>, Status> {
+            Ok(tonic::Response::new(HandshakeResponse::default()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_zero_copy_batch_sends_buffer_bytes_and_reclaims_buffer() {
+        let vector = RecordingVector::default();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(VectorApiServer::new(vector.clone()))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+
+        let mut forwarder = VectorForwarder::new(VectorForwarderConfig {
+            endpoint: format!("http://{}", addr),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let mut batcher = ZeroCopyBatcher::new(BatcherConfig {
+            max_batch_size: 1024,
+            flush_timeout: Duration::from_millis(100),
+        });
+        for i in 0..10 {
+            let event = AuditEvent {
+                event_id: Some(EventId {
+                    value: format!("event-{}", i),
+                }),
+                action: "CreatePolicyStore".to_string(),
+                ..Default::default()
+            };
+            batcher.add_event(&event).await.unwrap();
+        }
+        let batch = batcher.flush().await.unwrap();
+        let buffer_bytes = batch.as_slice().to_vec();
+
+        let delivery = forwarder
+            .send_zero_copy_batch(batch, batcher.pool())
+            .await
+            .unwrap();
+        assert_eq!(delivery.batch_id, "batch-1");
+        assert_eq!(delivery.accepted, 10);
+
+        // El servidor recibió los eventos del buffer del lote, con el
+        // colector y la secuencia añadidos tras ellos
+        let received = vector.received.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].events.len(), 10);
+        assert_eq!(
+            received[0].events,
+            EventBatchRequest::decode(buffer_bytes.as_slice())
+                .unwrap()
+                .events
+        );
+        assert_eq!(received[0].collector_id, forwarder.config().collector_id);
+        assert!(received[0].sequence > 0);
+
+        // El buffer volvió al pool y se reutiliza en el siguiente lote
+        let stats = batcher.get_pool_stats().await;
+        assert_eq!(stats.in_pool, 1);
+        assert_eq!(stats.total_created, 1);
+
+        batcher.add_event(&AuditEvent::default()).await.unwrap();
+        let stats = batcher.get_pool_stats().await;
+        assert_eq!(stats.in_pool, 0);
+        assert_eq!(stats.total_created, 1);
+
+        server.abort();
+    }
+
+    /// Raw storage whose first `failures` writes fail
+    #[derive(Default)]
+    struct FlakyStorage {
+        failures: std::sync::atomic::AtomicUsize,
+        inner: InMemoryStorage,
+    }
+
+    impl FlakyStorage {
+        fn check(&self) -> Result<(), anyhow::Error> {
+            let failing = self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if failing {
+                anyhow::bail!("connection refused");
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl StorageBackend for FlakyStorage {
+        async fn store_event(&self, event: &AuditEvent) -> Result<(), anyhow::Error> {
+            self.check()?;
+            self.inner.store_event(event).await
+        }
+
+        async fn store_batch(&self, events: &[AuditEvent]) -> Result<(), anyhow::Error> {
+            self.check()?;
+            self.inner.store_batch(events).await
+        }
+
+        async fn query_events(
+            &self,
+            filter: &QueryFilter,
+        ) -> Result<Vec<AuditEvent>, anyhow::Error> {
+            self.inner.query_events(filter).await
+        }
+
+        async fn count_events(&self, filter: &QueryFilter) -> Result<u64, anyhow::Error> {
+            self.inner.count_events(filter).await
+        }
+
+        async fn health_check(&self) -> Result<bool, anyhow::Error> {
+            Ok(true)
+        }
+
+        fn get_stats(&self) -> StorageStats {
+            self.inner.get_stats()
+        }
+    }
+
+    fn tenant_event(id: &str) -> AuditEvent {
+        AuditEvent {
+            event_id: Some(EventId {
+                value: id.to_string(),
+            }),
+            tenant_id: Some(TenantId {
+                value: "tenant-1".to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_is_retried_with_its_sequence() {
+        let raw = Arc::new(FlakyStorage {
+            failures: 1.into(),
+            ..Default::default()
+        });
+        let service = VectorApiServiceImpl::new()
+            .with_replay_protection()
+            .with_raw_storage(raw.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(VectorApiServer::new(service.clone()))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+
+        let mut forwarder = VectorForwarder::new(VectorForwarderConfig {
+            endpoint: format!("http://{}", addr),
+            retry_delay: Duration::from_millis(10),
+            use_compression: false,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let collector_id = forwarder.config().collector_id.clone();
+
+        // El primer intento falla al guardar la copia en bruto; el reintento
+        // con la misma secuencia se acepta
+        let delivery = forwarder
+            .send_events(vec![tenant_event("event-1")])
+            .await
+            .unwrap();
+        assert_eq!(delivery.accepted, 1);
+        assert_eq!(raw.inner.len(), 1);
+        let first = service.last_sequence(&collector_id).unwrap();
+
+        // El siguiente lote lleva una secuencia nueva
+        forwarder
+            .send_events(vec![tenant_event("event-2")])
+            .await
+            .unwrap();
+        assert_eq!(raw.inner.len(), 2);
+        assert_eq!(service.last_sequence(&collector_id), Some(first + 1));
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_sends_over_outbound_tls() {
+        let fixture = |name: &str| {
+            std::fs::read_to_string(format!(
+                "{}/tests/fixtures/tls/{}",
+                env!("CARGO_MANIFEST_DIR"),
+                name
+            ))
+            .unwrap()
+        };
+        let vector = RecordingVector::default();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            tonic::transport::Server::builder()
+                .tls_config(tonic::transport::ServerTlsConfig::new().identity(
+                    tonic::transport::Identity::from_pem(
+                        fixture("server.pem"),
+                        fixture("server.key"),
+                    ),
+                ))
+                .unwrap()
+                .add_service(VectorApiServer::new(vector.clone()))
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+
+        // Sin la CA de los fixtures el certificado del servidor no es de confianza
+        let untrusted = VectorForwarder::new(VectorForwarderConfig {
+            endpoint: format!("https://localhost:{}", addr.port()),
+            tls_config: Some(OutboundTlsConfig::default().with_ca_pem(fixture("untrusted-ca.pem"))),
+            ..Default::default()
+        })
+        .await;
+        assert!(matches!(untrusted, Err(VectorError::ConnectionFailed(_))));
+
+        let mut forwarder = VectorForwarder::new(VectorForwarderConfig {
+            endpoint: format!("https://localhost:{}", addr.port()),
+            tls_config: Some(OutboundTlsConfig::default().with_ca_pem(fixture("ca.pem"))),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        forwarder.send_event(AuditEvent::default()).await.unwrap();
+        assert_eq!(vector.received.lock().unwrap().len(), 1);
+
+        server.abort();
+    }
 
     #[tokio::test]
     async fn test_vector_forwarder_new() {
@@ -349,6 +861,7 @@ mod tests {
             health_check_interval: Duration::from_secs(60),
             tls_config: None,
             use_compression: false,
+            ..Default::default()
         };
 
         assert_eq!(config.endpoint, "http://test:9000");
@@ -0,0 +1,477 @@
+//! Webhook Notifications
+//!
+//! Sends an immediate HTTP notification for audit events that match a rule
+//! (e.g. failed authentications), instead of waiting for someone to look at
+//! a dashboard. Each notification is a JSON POST whose body is signed with
+//! HMAC-SHA256 so the receiver can verify where it came from. Deliveries are
+//! retried with exponential backoff, and every rule is rate limited so a
+//! burst of matching events does not flood the receiver.
+
+use crate::exporters::hrn_string;
+use chrono::DateTime;
+use hmac::{Hmac, Mac};
+use hodei_audit_proto::AuditEvent;
+use hodei_audit_types::{EventCategory, Outcome};
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Header carrying the body signature (`sha256=<hex>`)
+pub const SIGNATURE_HEADER: &str = "X-Hodei-Signature";
+/// Header carrying the name of the rule that matched
+pub const RULE_HEADER: &str = "X-Hodei-Rule";
+
+/// Webhook endpoint configuration
+#[derive(Clone)]
+pub struct WebhookConfig {
+    /// URL receiving the POST requests
+    pub url: String,
+    /// Shared secret for the HMAC signature
+    pub secret: Vec<u8>,
+    /// Retries after the first failed attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each further retry
+    pub initial_backoff: Duration,
+    /// Timeout of each request
+    pub timeout: Duration,
+}
+
+impl WebhookConfig {
+    /// Configuration with default retry and timeout settings
+    pub fn new(url: impl Into<String>, secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            url: url.into(),
+            secret: secret.into(),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl fmt::Debug for WebhookConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookConfig")
+            .field("url", &self.url)
+            .field("secret", &"<redacted>")
+            .field("max_retries", &self.max_retries)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+type EventPredicate = dyn Fn(&AuditEvent) -> bool + Send + Sync;
+
+/// Rule selecting the events that trigger a notification
+pub struct NotificationRule {
+    name: String,
+    predicate: Box<EventPredicate>,
+    max_notifications: usize,
+    window: Duration,
+    /// Send times within the current window
+    sent: Mutex<VecDeque<Instant>>,
+}
+
+impl NotificationRule {
+    /// Rule limited to 10 notifications per minute by default
+    pub fn new(
+        name: impl Into<String>,
+        predicate: impl Fn(&AuditEvent) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            predicate: Box::new(predicate),
+            max_notifications: 10,
+            window: Duration::from_secs(60),
+            sent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Allow at most `max_notifications` per sliding `window`
+    pub fn with_rate_limit(mut self, max_notifications: usize, window: Duration) -> Self {
+        self.max_notifications = max_notifications;
+        self.window = window;
+        self
+    }
+
+    /// Rule name, sent in the payload and the `X-Hodei-Rule` header
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the event matches this rule
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        (self.predicate)(event)
+    }
+
+    /// Reserve a slot in the rate limit window
+    fn try_acquire(&self) -> bool {
+        let now = Instant::now();
+        let mut sent = self.sent.lock().unwrap();
+        while sent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= self.window)
+        {
+            sent.pop_front();
+        }
+        if sent.len() < self.max_notifications {
+            sent.push_back(now);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl fmt::Debug for NotificationRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotificationRule")
+            .field("name", &self.name)
+            .field("max_notifications", &self.max_notifications)
+            .field("window", &self.window)
+            .finish()
+    }
+}
+
+/// Outcome of a notification for one matching rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// Accepted by the receiver after `attempts` requests
+    Sent { attempts: u32 },
+    /// Dropped by the rule's rate limit
+    RateLimited,
+    /// Every attempt failed
+    Failed(String),
+}
+
+/// Notification result for one rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delivery {
+    pub rule: String,
+    pub status: DeliveryStatus,
+}
+
+/// Posts signed notifications for events matching its rules
+#[derive(Debug)]
+pub struct WebhookNotifier {
+    config: WebhookConfig,
+    rules: Vec<NotificationRule>,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    /// Create a notifier with no rules
+    pub fn new(config: WebhookConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .unwrap_or_default();
+        Self {
+            config,
+            rules: Vec::new(),
+            client,
+        }
+    }
+
+    /// Add a notification rule
+    pub fn with_rule(mut self, rule: NotificationRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Notify every rule matching `event`
+    pub async fn notify(&self, event: &AuditEvent) -> Vec<Delivery> {
+        let mut deliveries = Vec::new();
+        for rule in self.rules.iter().filter(|r| r.matches(event)) {
+            let status = if rule.try_acquire() {
+                self.deliver(rule, event).await
+            } else {
+                debug!("[Webhook] rule={} rate limited", rule.name);
+                DeliveryStatus::RateLimited
+            };
+            deliveries.push(Delivery {
+                rule: rule.name.clone(),
+                status,
+            });
+        }
+        deliveries
+    }
+
+    /// Notify in the background, so ingestion never waits on the receiver
+    pub fn dispatch(self: &Arc<Self>, event: AuditEvent) {
+        if !self.rules.iter().any(|r| r.matches(&event)) {
+            return;
+        }
+        let notifier = Arc::clone(self);
+        tokio::spawn(async move {
+            for delivery in notifier.notify(&event).await {
+                if let DeliveryStatus::Failed(error) = delivery.status {
+                    warn!(
+                        "[Webhook] rule={} delivery failed: {}",
+                        delivery.rule, error
+                    );
+                }
+            }
+        });
+    }
+
+    /// POST the signed payload, retrying transient failures
+    async fn deliver(&self, rule: &NotificationRule, event: &AuditEvent) -> DeliveryStatus {
+        let body = payload(&rule.name, event).to_string();
+        let signature = format!("sha256={}", sign(&self.config.secret, body.as_bytes()));
+
+        let mut backoff = self.config.initial_backoff;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let result = self
+                .client
+                .post(&self.config.url)
+                .header("Content-Type", "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .header(RULE_HEADER, &rule.name)
+                .body(body.clone())
+                .send()
+                .await;
+
+            let (error, retryable) = match result {
+                Ok(response) if response.status().is_success() => {
+                    return DeliveryStatus::Sent { attempts };
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    (
+                        format!("receiver returned {}", status),
+                        status.is_server_error() || status.as_u16() == 429,
+                    )
+                }
+                Err(e) => (e.to_string(), true),
+            };
+
+            if !retryable || attempts > self.config.max_retries {
+                return DeliveryStatus::Failed(error);
+            }
+            debug!(
+                "[Webhook] rule={} attempt {} failed: {}, retrying in {:?}",
+                rule.name, attempts, error, backoff
+            );
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+}
+
+/// Hex HMAC-SHA256 of `body`
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// JSON body of a notification
+fn payload(rule: &str, event: &AuditEvent) -> serde_json::Value {
+    let event_time = event
+        .event_time
+        .as_ref()
+        .and_then(|t| DateTime::from_timestamp(t.seconds, t.nanos.max(0) as u32))
+        .map(|t| t.to_rfc3339());
+
+    json!({
+        "rule": rule,
+        "event": {
+            "event_id": event.event_id.as_ref().map(|id| &id.value),
+            "tenant_id": event.tenant_id.as_ref().map(|t| &t.value),
+            "action": event.action,
+            "category": EventCategory::from(event.event_category).as_str(),
+            "outcome": Outcome::from(event.outcome).as_str(),
+            "event_time": event_time,
+            "hrn": event.hrn.as_ref().map(hrn_string),
+            "user_id": event.user_identity.as_ref().map(|u| &u.user_id),
+            "error_code": event.error_code,
+            "error_message": event.error_message,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hodei_audit_proto::EventId;
+    use std::collections::HashMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[derive(Debug, Clone)]
+    struct Captured {
+        headers: HashMap<String, String>,
+        body: String,
+    }
+
+    /// Minimal HTTP server answering with `statuses` in order (then 200)
+    async fn mock_server(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<Captured>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks/audit", listener.local_addr().unwrap());
+        let captured = Arc::new(Mutex::new(Vec::new()));
+
+        let requests = captured.clone();
+        tokio::spawn(async move {
+            let mut statuses = statuses.into_iter();
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut raw = Vec::new();
+                let mut buf = [0u8; 4096];
+                let header_end = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    raw.extend_from_slice(&buf[..n]);
+                    if let Some(pos) = raw.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break pos + 4;
+                    }
+                };
+
+                let head = String::from_utf8_lossy(&raw[..header_end]).to_string();
+                let headers: HashMap<String, String> = head
+                    .lines()
+                    .skip(1)
+                    .filter_map(|line| line.split_once(": "))
+                    .map(|(k, v)| (k.to_ascii_lowercase(), v.to_string()))
+                    .collect();
+                let length: usize = headers
+                    .get("content-length")
+                    .map_or(0, |v| v.parse().unwrap());
+                while raw.len() < header_end + length {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    raw.extend_from_slice(&buf[..n]);
+                }
+                let body = String::from_utf8_lossy(&raw[header_end..]).to_string();
+                requests.lock().unwrap().push(Captured { headers, body });
+
+                let status = statuses.next().unwrap_or(200);
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (url, captured)
+    }
+
+    fn event(id: &str, action: &str, outcome: Outcome) -> AuditEvent {
+        AuditEvent {
+            event_id: Some(EventId {
+                value: id.to_string(),
+            }),
+            action: action.to_string(),
+            event_category: i32::from(EventCategory::Management),
+            outcome: i32::from(outcome),
+            ..Default::default()
+        }
+    }
+
+    fn auth_failures() -> NotificationRule {
+        NotificationRule::new("auth-failure", |event: &AuditEvent| {
+            event.action.starts_with("auth.") && Outcome::from(event.outcome) == Outcome::Failure
+        })
+    }
+
+    fn config(url: String) -> WebhookConfig {
+        WebhookConfig {
+            initial_backoff: Duration::from_millis(10),
+            ..WebhookConfig::new(url, "s3cret")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_matching_event_triggers_signed_post() {
+        let (url, captured) = mock_server(vec![]).await;
+        let notifier = WebhookNotifier::new(config(url)).with_rule(auth_failures());
+
+        let deliveries = notifier
+            .notify(&event("evt-1", "auth.login", Outcome::Failure))
+            .await;
+        assert_eq!(
+            deliveries,
+            vec![Delivery {
+                rule: "auth-failure".to_string(),
+                status: DeliveryStatus::Sent { attempts: 1 },
+            }]
+        );
+
+        let requests = captured.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        let request = &requests[0];
+        assert_eq!(
+            request.headers["x-hodei-signature"],
+            format!("sha256={}", sign(b"s3cret", request.body.as_bytes()))
+        );
+        assert_eq!(request.headers["x-hodei-rule"], "auth-failure");
+
+        let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(body["event"]["event_id"], "evt-1");
+        assert_eq!(body["event"]["outcome"], "failure");
+    }
+
+    #[tokio::test]
+    async fn test_non_matching_event_is_not_sent() {
+        let (url, captured) = mock_server(vec![]).await;
+        let notifier = WebhookNotifier::new(config(url)).with_rule(auth_failures());
+
+        let success = notifier
+            .notify(&event("evt-1", "auth.login", Outcome::Success))
+            .await;
+        let other = notifier
+            .notify(&event("evt-2", "policy.update", Outcome::Failure))
+            .await;
+
+        assert!(success.is_empty());
+        assert!(other.is_empty());
+        assert!(captured.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_caps_bursts_per_rule() {
+        let (url, captured) = mock_server(vec![]).await;
+        let notifier = WebhookNotifier::new(config(url))
+            .with_rule(auth_failures().with_rate_limit(2, Duration::from_secs(60)));
+
+        let mut statuses = Vec::new();
+        for i in 0..5 {
+            let event = event(&format!("evt-{}", i), "auth.login", Outcome::Failure);
+            statuses.extend(notifier.notify(&event).await.into_iter().map(|d| d.status));
+        }
+
+        assert_eq!(
+            statuses
+                .iter()
+                .filter(|s| matches!(s, DeliveryStatus::Sent { .. }))
+                .count(),
+            2
+        );
+        assert_eq!(
+            statuses
+                .iter()
+                .filter(|s| **s == DeliveryStatus::RateLimited)
+                .count(),
+            3
+        );
+        assert_eq!(captured.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_retried() {
+        let (url, captured) = mock_server(vec![503, 500]).await;
+        let notifier = WebhookNotifier::new(config(url)).with_rule(auth_failures());
+
+        let deliveries = notifier
+            .notify(&event("evt-1", "auth.login", Outcome::Failure))
+            .await;
+        assert_eq!(deliveries[0].status, DeliveryStatus::Sent { attempts: 3 });
+        assert_eq!(captured.lock().unwrap().len(), 3);
+    }
+}
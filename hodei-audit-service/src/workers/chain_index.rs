@@ -0,0 +1,364 @@
+//! Índice de posiciones en la cadena de digests
+//!
+//! El digest worker registra, para cada evento que encadena, su número de
+//! secuencia y el timestamp de su digest. Con ese índice se resuelven las
+//! consultas "as-of" ([`AsOf`]): solo se devuelven los eventos encadenados
+//! hasta la secuencia o el instante indicados, ordenados según la cadena, de
+//! modo que una instantánea de auditoría es reproducible aunque después se
+//! sigan ingiriendo eventos.
+
+use crate::crypto::ports::digest_chain::DigestChainError;
+use crate::storage::{AsOf, QueryFilter, StorageBackend, StorageStats};
+use async_trait::async_trait;
+use futures::StreamExt;
+use hodei_audit_proto::AuditEvent;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Posición de un evento en la cadena de digests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainPosition {
+    /// Secuencia con la que se encadenó el evento
+    pub sequence: u64,
+    /// Timestamp (segundos Unix) del digest del evento
+    pub chained_at: u64,
+}
+
+impl ChainPosition {
+    /// Indica si la posición queda dentro de la instantánea `as_of`
+    pub fn is_within(&self, as_of: AsOf) -> bool {
+        match as_of {
+            AsOf::Sequence(sequence) => self.sequence <= sequence,
+            AsOf::Timestamp(time) => unix_time(self.chained_at) <= time,
+        }
+    }
+}
+
+/// Port para el índice evento → posición en la cadena
+#[async_trait]
+pub trait ChainIndex: Send + Sync + 'static {
+    /// Registra la posición de un evento (idempotente)
+    async fn record(&self, event_id: &str, position: ChainPosition)
+    -> Result<(), DigestChainError>;
+
+    /// Posiciones de los eventos indicados que ya están encadenados
+    async fn positions(
+        &self,
+        event_ids: &[&str],
+    ) -> Result<HashMap<String, ChainPosition>, DigestChainError>;
+}
+
+/// Índice de cadena en memoria (desarrollo y testing)
+#[derive(Debug, Default)]
+pub struct InMemoryChainIndex {
+    positions: RwLock<HashMap<String, ChainPosition>>,
+}
+
+impl InMemoryChainIndex {
+    /// Crear nuevo índice vacío
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ChainIndex for InMemoryChainIndex {
+    async fn record(
+        &self,
+        event_id: &str,
+        position: ChainPosition,
+    ) -> Result<(), DigestChainError> {
+        self.positions
+            .write()
+            .unwrap()
+            .insert(event_id.to_string(), position);
+        Ok(())
+    }
+
+    async fn positions(
+        &self,
+        event_ids: &[&str],
+    ) -> Result<HashMap<String, ChainPosition>, DigestChainError> {
+        let positions = self.positions.read().unwrap();
+        Ok(event_ids
+            .iter()
+            .filter_map(|id| positions.get(*id).map(|p| (id.to_string(), *p)))
+            .collect())
+    }
+}
+
+/// Almacenamiento que resuelve las consultas con [`QueryFilter::as_of`]
+///
+/// Sin `as_of` delega sin más en el backend. Con `as_of`, descarta los
+/// eventos aún no encadenados o encadenados después de la instantánea y
+/// ordena el resultado por secuencia antes de aplicar `limit`.
+pub struct AsOfStorage {
+    inner: Arc<dyn StorageBackend>,
+    index: Arc<dyn ChainIndex>,
+}
+
+impl AsOfStorage {
+    pub fn new(inner: Arc<dyn StorageBackend>, index: Arc<dyn ChainIndex>) -> Self {
+        Self { inner, index }
+    }
+
+    /// Eventos de la instantánea `as_of` que cumplen `filter`
+    ///
+    /// El backend se recorre por páginas y solo se conservan los `limit`
+    /// eventos de menor secuencia. Con [`AsOf::Timestamp`] además se acota
+    /// la consulta por tiempo: el digest de un evento lleva su `event_time`
+    /// truncado a segundos, así que ninguno de la instantánea es posterior
+    /// al instante más un segundo.
+    async fn query_as_of(
+        &self,
+        filter: &QueryFilter,
+        as_of: AsOf,
+    ) -> Result<Vec<AuditEvent>, anyhow::Error> {
+        // El límite y la proyección se aplican tras filtrar por la cadena,
+        // que necesita el event_id
+        let mut bounded = QueryFilter {
+            as_of: None,
+            limit: None,
+            projection: Vec::new(),
+            ..filter.clone()
+        };
+        if let AsOf::Timestamp(time) = as_of {
+            let bound = time + Duration::from_secs(1);
+            bounded.end_time = Some(bounded.end_time.map_or(bound, |end| end.min(bound)));
+        }
+        let limit = filter.limit.unwrap_or(usize::MAX);
+
+        // Montículo de máximos: la cima es el evento que sobra primero
+        let mut snapshot: BinaryHeap<SnapshotEntry> = BinaryHeap::new();
+        let mut pages = self
+            .inner
+            .query_events_stream(&bounded)
+            .chunks(AS_OF_PAGE_SIZE);
+        while let Some(page) = pages.next().await {
+            let events = page.into_iter().collect::<Result<Vec<_>, _>>()?;
+            let ids: Vec<&str> = events
+                .iter()
+                .filter_map(|e| e.event_id.as_ref().map(|id| id.value.as_str()))
+                .collect();
+            let positions = self.index.positions(&ids).await?;
+
+            for event in events {
+                let Some(position) = event
+                    .event_id
+                    .as_ref()
+                    .and_then(|id| positions.get(&id.value))
+                    .filter(|position| position.is_within(as_of))
+                else {
+                    continue;
+                };
+                snapshot.push(SnapshotEntry(position.sequence, event));
+                if snapshot.len() > limit {
+                    snapshot.pop();
+                }
+            }
+        }
+
+        Ok(snapshot
+            .into_sorted_vec()
+            .into_iter()
+            .map(|SnapshotEntry(_, event)| filter.project(event))
+            .collect())
+    }
+}
+
+/// Eventos leídos por página al resolver una consulta as-of
+const AS_OF_PAGE_SIZE: usize = 256;
+
+/// Evento de una instantánea, ordenado por su secuencia en la cadena
+struct SnapshotEntry(u64, AuditEvent);
+
+impl PartialEq for SnapshotEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for SnapshotEntry {}
+
+impl PartialOrd for SnapshotEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SnapshotEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for AsOfStorage {
+    async fn store_event(&self, event: &AuditEvent) -> Result<(), anyhow::Error> {
+        self.inner.store_event(event).await
+    }
+
+    async fn store_batch(&self, events: &[AuditEvent]) -> Result<(), anyhow::Error> {
+        self.inner.store_batch(events).await
+    }
+
+    async fn query_events(&self, filter: &QueryFilter) -> Result<Vec<AuditEvent>, anyhow::Error> {
+        match filter.as_of {
+            Some(as_of) => self.query_as_of(filter, as_of).await,
+            None => self.inner.query_events(filter).await,
+        }
+    }
+
+    async fn count_events(&self, filter: &QueryFilter) -> Result<u64, anyhow::Error> {
+        match filter.as_of {
+            Some(as_of) => {
+                let unbounded = QueryFilter {
+                    limit: None,
+                    ..filter.clone()
+                };
+                Ok(self.query_as_of(&unbounded, as_of).await?.len() as u64)
+            }
+            None => self.inner.count_events(filter).await,
+        }
+    }
+
+    async fn health_check(&self) -> Result<bool, anyhow::Error> {
+        self.inner.health_check().await
+    }
+
+    fn get_stats(&self) -> StorageStats {
+        self.inner.get_stats()
+    }
+
+    async fn delete_events(&self, filter: &QueryFilter) -> Result<u64, anyhow::Error> {
+        self.inner.delete_events(filter).await
+    }
+
+    fn might_contain_event(&self, event_id: &str) -> bool {
+        self.inner.might_contain_event(event_id)
+    }
+}
+
+impl std::fmt::Debug for AsOfStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsOfStorage").finish_non_exhaustive()
+    }
+}
+
+/// Instante de un timestamp Unix en segundos
+pub(crate) fn unix_time(seconds: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{Ed25519Signer, InMemoryDigestChain, Sha256Hasher};
+    use crate::storage::InMemoryStorage;
+    use crate::workers::digest_worker::{DigestWorker, DigestWorkerConfig, SequencedEvent};
+    use hodei_audit_proto::{EventId, TenantId};
+
+    fn sequenced(sequence: u64) -> SequencedEvent {
+        SequencedEvent {
+            sequence,
+            event: AuditEvent {
+                event_id: Some(EventId {
+                    value: format!("evt-{}", sequence),
+                }),
+                tenant_id: Some(TenantId {
+                    value: "tenant1".to_string(),
+                }),
+                event_time: Some(prost_types::Timestamp {
+                    seconds: 1_700_000_000 + sequence as i64,
+                    nanos: 0,
+                }),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Encadena los eventos 1..=5 y los guarda en orden inverso
+    async fn chained_storage() -> AsOfStorage {
+        let index = Arc::new(InMemoryChainIndex::new());
+        let worker = DigestWorker::new(
+            Sha256Hasher::new(),
+            Ed25519Signer::new(),
+            InMemoryDigestChain::new(),
+            DigestWorkerConfig::default(),
+        )
+        .with_chain_index(index.clone());
+        worker
+            .run(futures::stream::iter((1..=5).map(sequenced)))
+            .await
+            .unwrap();
+
+        let backend = Arc::new(InMemoryStorage::new());
+        for sequence in (1..=5).rev() {
+            backend
+                .store_event(&sequenced(sequence).event)
+                .await
+                .unwrap();
+        }
+        // Ingerido pero aún no encadenado
+        backend.store_event(&sequenced(6).event).await.unwrap();
+
+        AsOfStorage::new(backend, index)
+    }
+
+    fn ids(events: &[AuditEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|e| e.event_id.as_ref().unwrap().value.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_as_of_sequence_returns_first_events_in_chain_order() {
+        let storage = chained_storage().await;
+
+        let snapshot = storage
+            .query_events(&QueryFilter {
+                as_of: Some(AsOf::Sequence(3)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(ids(&snapshot), vec!["evt-1", "evt-2", "evt-3"]);
+
+        let limited = storage
+            .query_events(&QueryFilter {
+                as_of: Some(AsOf::Sequence(3)),
+                limit: Some(2),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(ids(&limited), vec!["evt-1", "evt-2"]);
+
+        // Sin as-of también aparecen los eventos aún no encadenados
+        assert_eq!(
+            storage.count_events(&QueryFilter::default()).await.unwrap(),
+            6
+        );
+        let all_chained = QueryFilter {
+            as_of: Some(AsOf::Sequence(u64::MAX)),
+            ..Default::default()
+        };
+        assert_eq!(storage.count_events(&all_chained).await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_as_of_timestamp_uses_chain_timestamp() {
+        let storage = chained_storage().await;
+
+        let snapshot = storage
+            .query_events(&QueryFilter {
+                as_of: Some(AsOf::Timestamp(unix_time(1_700_000_002))),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(ids(&snapshot), vec!["evt-1", "evt-2"]);
+    }
+}
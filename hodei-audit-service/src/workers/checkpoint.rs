@@ -0,0 +1,453 @@
+//! Checkpoints de workers
+//!
+//! Persisten la última secuencia procesada por un worker (y la cabeza de la
+//! cadena de digests de cada tenant) para que un reinicio retome exactamente
+//! donde se quedó, sin huecos ni reprocesados.
+//!
+//! Además define los checkpoints firmados que se publican periódicamente en
+//! un sink externo (log, objeto S3, notario) para anclar la cadena en el
+//! tiempo. El sink replicado escribe cada checkpoint en varios destinos
+//! (p. ej. buckets en dos regiones) y comprueba que todas las réplicas
+//! coinciden, de modo que reescribir la historia en una sola región no pasa
+//! desapercibido.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+
+/// Errores del almacén de checkpoints
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("Error de E/S: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Checkpoint corrupto: {0}")]
+    Corrupted(String),
+
+    #[error("Error del sink: {0}")]
+    Sink(String),
+}
+
+/// Estado persistido de un worker
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerCheckpoint {
+    /// Última secuencia procesada (None si aún no se ha procesado nada)
+    pub last_sequence: Option<u64>,
+    /// Último digest encadenado por tenant
+    pub chain_heads: BTreeMap<String, String>,
+    /// Secuencias anteriores a `last_sequence` que no llegaron a tiempo; su
+    /// hueco está registrado en la cadena y se encadenan si llegan tarde
+    #[serde(default)]
+    pub open_gaps: BTreeSet<u64>,
+}
+
+/// Port para persistir checkpoints
+#[async_trait]
+pub trait CheckpointStore: Send + Sync + 'static {
+    /// Carga el último checkpoint guardado
+    async fn load(&self) -> Result<Option<WorkerCheckpoint>, CheckpointError>;
+
+    /// Guarda el checkpoint de forma duradera
+    async fn save(&self, checkpoint: &WorkerCheckpoint) -> Result<(), CheckpointError>;
+}
+
+/// Almacén de checkpoints en memoria (desarrollo y testing)
+#[derive(Debug, Default)]
+pub struct InMemoryCheckpointStore {
+    checkpoint: Mutex<Option<WorkerCheckpoint>>,
+}
+
+impl InMemoryCheckpointStore {
+    /// Crear nuevo almacén vacío
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for InMemoryCheckpointStore {
+    async fn load(&self) -> Result<Option<WorkerCheckpoint>, CheckpointError> {
+        Ok(self.checkpoint.lock().unwrap().clone())
+    }
+
+    async fn save(&self, checkpoint: &WorkerCheckpoint) -> Result<(), CheckpointError> {
+        *self.checkpoint.lock().unwrap() = Some(checkpoint.clone());
+        Ok(())
+    }
+}
+
+/// Almacén de checkpoints en fichero
+///
+/// Cada guardado escribe un fichero temporal, hace `fsync` y lo renombra
+/// sobre el definitivo, de modo que un fallo a mitad de escritura nunca deja
+/// un checkpoint a medias.
+#[derive(Debug, Clone)]
+pub struct FileCheckpointStore {
+    path: PathBuf,
+}
+
+impl FileCheckpointStore {
+    /// Crear almacén sobre la ruta indicada
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Ruta del fichero de checkpoint
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for FileCheckpointStore {
+    async fn load(&self) -> Result<Option<WorkerCheckpoint>, CheckpointError> {
+        match tokio::fs::read(&self.path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(|e| CheckpointError::Corrupted(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save(&self, checkpoint: &WorkerCheckpoint) -> Result<(), CheckpointError> {
+        let bytes = serde_json::to_vec(checkpoint)
+            .map_err(|e| CheckpointError::Corrupted(e.to_string()))?;
+
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let tmp_path = self.path.with_extension("tmp");
+        let mut file = tokio::fs::File::create(&tmp_path).await?;
+        file.write_all(&bytes).await?;
+        file.sync_all().await?;
+        drop(file);
+
+        tokio::fs::rename(&tmp_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+/// Checkpoint firmado de la cadena de un tenant
+///
+/// Prueba que la cadena tenía `chain_hash` como cabeza tras procesar
+/// `sequence` en el instante `timestamp`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedCheckpoint {
+    pub tenant_id: String,
+    pub digest_id: String,
+    pub chain_hash: String,
+    pub sequence: u64,
+    pub timestamp: u64,
+    pub signature: Vec<u8>,
+    pub public_key: Vec<u8>,
+}
+
+impl SignedCheckpoint {
+    /// Contenido canónico que cubre la firma
+    pub fn signing_payload(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}",
+            self.tenant_id, self.digest_id, self.chain_hash, self.sequence, self.timestamp
+        )
+    }
+}
+
+/// Destino de los checkpoints firmados
+#[async_trait]
+pub trait CheckpointSink: std::fmt::Debug + Send + Sync + 'static {
+    /// Publica un checkpoint firmado
+    async fn publish(&self, checkpoint: &SignedCheckpoint) -> Result<(), CheckpointError>;
+
+    /// Último checkpoint publicado para un tenant
+    ///
+    /// Por defecto el sink es de solo escritura y devuelve error.
+    async fn latest(&self, tenant_id: &str) -> Result<Option<SignedCheckpoint>, CheckpointError> {
+        let _ = tenant_id;
+        Err(CheckpointError::Sink(
+            "el sink no permite leer checkpoints".to_string(),
+        ))
+    }
+}
+
+/// Sink que escribe los checkpoints en el log
+#[derive(Debug, Default)]
+pub struct LogCheckpointSink;
+
+#[async_trait]
+impl CheckpointSink for LogCheckpointSink {
+    async fn publish(&self, checkpoint: &SignedCheckpoint) -> Result<(), CheckpointError> {
+        let record =
+            serde_json::to_string(checkpoint).map_err(|e| CheckpointError::Sink(e.to_string()))?;
+        tracing::info!(target: "audit_checkpoint", "{}", record);
+        Ok(())
+    }
+}
+
+/// Sink en memoria (desarrollo y testing)
+#[derive(Debug, Default)]
+pub struct InMemoryCheckpointSink {
+    published: Mutex<Vec<SignedCheckpoint>>,
+}
+
+impl InMemoryCheckpointSink {
+    /// Crear nuevo sink vacío
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checkpoints publicados hasta ahora
+    pub fn published(&self) -> Vec<SignedCheckpoint> {
+        self.published.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl CheckpointSink for InMemoryCheckpointSink {
+    async fn publish(&self, checkpoint: &SignedCheckpoint) -> Result<(), CheckpointError> {
+        self.published.lock().unwrap().push(checkpoint.clone());
+        Ok(())
+    }
+
+    async fn latest(&self, tenant_id: &str) -> Result<Option<SignedCheckpoint>, CheckpointError> {
+        Ok(self
+            .published
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|c| c.tenant_id == tenant_id)
+            .cloned())
+    }
+}
+
+/// Resultado de comparar el último checkpoint de un tenant en cada réplica
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplicaVerification {
+    pub tenant_id: String,
+    /// Hash de cadena del último checkpoint de cada réplica (None si no tiene)
+    pub chain_hashes: BTreeMap<String, Option<String>>,
+}
+
+impl ReplicaVerification {
+    /// Si todas las réplicas tienen el mismo último checkpoint
+    pub fn is_consistent(&self) -> bool {
+        self.divergent_replicas().is_empty()
+    }
+
+    /// Hash en el que coincide la mayoría de réplicas (en empate, el de la
+    /// primera réplica configurada)
+    pub fn agreed_hash(&self) -> Option<&str> {
+        let mut votes: Vec<(&Option<String>, usize)> = Vec::new();
+        for hash in self.chain_hashes.values() {
+            match votes.iter_mut().find(|(h, _)| *h == hash) {
+                Some((_, count)) => *count += 1,
+                None => votes.push((hash, 1)),
+            }
+        }
+        let max = votes.iter().map(|(_, count)| *count).max()?;
+        votes
+            .into_iter()
+            .find(|(_, count)| *count == max)
+            .and_then(|(hash, _)| hash.as_deref())
+    }
+
+    /// Réplicas cuyo último checkpoint no coincide con el acordado
+    pub fn divergent_replicas(&self) -> Vec<&str> {
+        let agreed = self.agreed_hash();
+        self.chain_hashes
+            .iter()
+            .filter(|(_, hash)| agreed.is_none() || hash.as_deref() != agreed)
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+/// Sink que replica cada checkpoint en varios destinos
+///
+/// La publicación se intenta en todas las réplicas y falla si alguna falla,
+/// para que el publicador reintente.
+#[derive(Debug, Default)]
+pub struct ReplicatedCheckpointSink {
+    replicas: Vec<(String, Arc<dyn CheckpointSink>)>,
+}
+
+impl ReplicatedCheckpointSink {
+    /// Crear sink sin réplicas
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Añadir una réplica (p. ej. un bucket en otra región)
+    pub fn with_replica(mut self, name: impl Into<String>, sink: Arc<dyn CheckpointSink>) -> Self {
+        self.replicas.push((name.into(), sink));
+        self
+    }
+
+    /// Nombres de las réplicas, en orden de configuración
+    pub fn replica_names(&self) -> Vec<&str> {
+        self.replicas
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Comprueba que todas las réplicas coinciden en el último checkpoint
+    /// del tenant
+    pub async fn verify_replicas(
+        &self,
+        tenant_id: &str,
+    ) -> Result<ReplicaVerification, CheckpointError> {
+        let mut chain_hashes = BTreeMap::new();
+        for (name, sink) in &self.replicas {
+            let latest = sink.latest(tenant_id).await?;
+            chain_hashes.insert(name.clone(), latest.map(|c| c.chain_hash));
+        }
+
+        let verification = ReplicaVerification {
+            tenant_id: tenant_id.to_string(),
+            chain_hashes,
+        };
+        if !verification.is_consistent() {
+            tracing::error!(
+                target: "audit_checkpoint",
+                "Réplicas de checkpoint divergentes para tenant {}: {:?}",
+                tenant_id,
+                verification.divergent_replicas()
+            );
+        }
+        Ok(verification)
+    }
+}
+
+#[async_trait]
+impl CheckpointSink for ReplicatedCheckpointSink {
+    async fn publish(&self, checkpoint: &SignedCheckpoint) -> Result<(), CheckpointError> {
+        let results = futures::future::join_all(
+            self.replicas
+                .iter()
+                .map(|(_, sink)| sink.publish(checkpoint)),
+        )
+        .await;
+
+        let failures: Vec<String> = self
+            .replicas
+            .iter()
+            .zip(results)
+            .filter_map(|((name, _), result)| result.err().map(|e| format!("{}: {}", name, e)))
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(CheckpointError::Sink(failures.join("; ")))
+        }
+    }
+
+    /// Último checkpoint de la primera réplica
+    async fn latest(&self, tenant_id: &str) -> Result<Option<SignedCheckpoint>, CheckpointError> {
+        match self.replicas.first() {
+            Some((_, sink)) => sink.latest(tenant_id).await,
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_file_checkpoint_roundtrip() {
+        let dir = tempdir().unwrap();
+        let store = FileCheckpointStore::new(dir.path().join("worker.checkpoint"));
+        assert_eq!(store.load().await.unwrap(), None);
+
+        let mut checkpoint = WorkerCheckpoint {
+            last_sequence: Some(42),
+            ..Default::default()
+        };
+        checkpoint
+            .chain_heads
+            .insert("tenant1".to_string(), "digest_1".to_string());
+        store.save(&checkpoint).await.unwrap();
+
+        // Una instancia nueva (reinicio) lee el mismo estado
+        let reopened = FileCheckpointStore::new(store.path());
+        assert_eq!(reopened.load().await.unwrap(), Some(checkpoint));
+    }
+
+    fn signed(tenant_id: &str, sequence: u64, chain_hash: &str) -> SignedCheckpoint {
+        SignedCheckpoint {
+            tenant_id: tenant_id.to_string(),
+            digest_id: format!("digest_{}", sequence),
+            chain_hash: chain_hash.to_string(),
+            sequence,
+            timestamp: 1_700_000_000 + sequence,
+            signature: vec![1; 64],
+            public_key: vec![2; 32],
+        }
+    }
+
+    fn replicated() -> (
+        Arc<InMemoryCheckpointSink>,
+        Arc<InMemoryCheckpointSink>,
+        ReplicatedCheckpointSink,
+    ) {
+        let primary = Arc::new(InMemoryCheckpointSink::new());
+        let secondary = Arc::new(InMemoryCheckpointSink::new());
+        let sink = ReplicatedCheckpointSink::new()
+            .with_replica("eu-west-1", primary.clone())
+            .with_replica("us-east-1", secondary.clone());
+        (primary, secondary, sink)
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_is_written_to_every_replica() {
+        let (primary, secondary, sink) = replicated();
+
+        sink.publish(&signed("tenant1", 1, "hash_1")).await.unwrap();
+        sink.publish(&signed("tenant1", 2, "hash_2")).await.unwrap();
+
+        assert_eq!(primary.published(), secondary.published());
+        assert_eq!(primary.published().len(), 2);
+
+        let verification = sink.verify_replicas("tenant1").await.unwrap();
+        assert!(verification.is_consistent());
+        assert_eq!(verification.agreed_hash(), Some("hash_2"));
+
+        // Una réplica de solo escritura no se puede verificar
+        let write_only =
+            ReplicatedCheckpointSink::new().with_replica("log", Arc::new(LogCheckpointSink));
+        assert!(write_only.verify_replicas("tenant1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_divergent_replica_is_detected() {
+        let (_, secondary, sink) = replicated();
+        sink.publish(&signed("tenant1", 1, "hash_1")).await.unwrap();
+
+        // Un atacante reescribe la historia en una sola región
+        secondary
+            .publish(&signed("tenant1", 1, "forged_hash"))
+            .await
+            .unwrap();
+
+        let verification = sink.verify_replicas("tenant1").await.unwrap();
+        assert!(!verification.is_consistent());
+        assert_eq!(
+            verification.chain_hashes["eu-west-1"].as_deref(),
+            Some("hash_1")
+        );
+        assert_eq!(
+            verification.chain_hashes["us-east-1"].as_deref(),
+            Some("forged_hash")
+        );
+        assert_eq!(verification.divergent_replicas(), vec!["us-east-1"]);
+    }
+}
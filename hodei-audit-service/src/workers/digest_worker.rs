@@ -2,11 +2,32 @@
 //!
 //! Este worker se ejecuta periódicamente para generar digests
 //! de los archivos de log, creando una cadena de tamper-evidence.
+//!
+//! También puede consumir una cola de eventos ingeridos (`run`), encadenando
+//! cada evento en orden y guardando un checkpoint tras cada uno para que un
+//! reinicio retome sin huecos ni duplicados. Una secuencia que no llega a
+//! tiempo no se descarta: su hueco queda registrado en la cadena
+//! [`SEQUENCE_GAP_CHAIN`] y el evento se encadena si llega más tarde.
+//!
+//! Si se configura, publica periódicamente checkpoints firmados de la cabeza
+//! de cada cadena para permitir su anclaje externo.
 
-use crate::crypto::ports::digest_chain::DigestChainService;
+use super::chain_index::{ChainIndex, ChainPosition};
+use super::checkpoint::{
+    CheckpointError, CheckpointSink, CheckpointStore, InMemoryCheckpointStore, SignedCheckpoint,
+    WorkerCheckpoint,
+};
+use crate::crypto::ports::digest_chain::{DigestChainError, DigestChainService, DigestInfo};
 use crate::crypto::ports::hashing::HashingService;
 use crate::crypto::ports::signing::SigningService;
+use futures::{Stream, StreamExt};
+use hodei_audit_proto::AuditEvent;
+use hodei_audit_types::canonical_bytes;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
 use std::path::PathBuf;
+use std::sync::Arc;
 use thiserror::Error;
 use tokio::time::{Duration, Instant};
 
@@ -24,6 +45,55 @@ pub enum DigestWorkerError {
 
     #[error("Error de E/S: {0}")]
     Io(#[from] std::io::Error),
+
+    /// La cadena no continúa desde el último digest conocido
+    #[error("Continuidad de cadena rota: {0}")]
+    ChainContinuity(String),
+
+    /// Fallo transitorio del almacén (cadena o checkpoint); se puede reintentar
+    #[error("Error de almacén: {0}")]
+    Store(String),
+
+    #[error("Error de checkpoint: {0}")]
+    Checkpoint(String),
+
+    /// La cola se cerró mientras faltaba un evento; los posteriores quedan
+    /// sin confirmar y se reciben de nuevo al reanudar
+    #[error("Hueco de secuencia: se esperaba {expected}, llegó {got}")]
+    SequenceGap { expected: u64, got: u64 },
+}
+
+impl DigestWorkerError {
+    /// Indica si el error es transitorio y la operación puede reintentarse
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Store(_) | Self::Io(_))
+    }
+}
+
+impl From<DigestChainError> for DigestWorkerError {
+    fn from(error: DigestChainError) -> Self {
+        match error {
+            DigestChainError::Validation(msg) => Self::ChainContinuity(msg),
+            other => Self::Store(other.to_string()),
+        }
+    }
+}
+
+impl From<CheckpointError> for DigestWorkerError {
+    fn from(error: CheckpointError) -> Self {
+        match error {
+            CheckpointError::Io(e) => Self::Store(e.to_string()),
+            CheckpointError::Sink(msg) => Self::Store(msg),
+            CheckpointError::Corrupted(msg) => Self::Checkpoint(msg),
+        }
+    }
+}
+
+/// Evento ingerido con su número de secuencia en la cola
+#[derive(Debug, Clone)]
+pub struct SequencedEvent {
+    pub sequence: u64,
+    pub event: AuditEvent,
 }
 
 /// Resultado de una ejecución del worker
@@ -34,6 +104,14 @@ pub struct DigestWorkerResult {
     pub start_time: u64,
     pub end_time: u64,
     pub duration_ms: u64,
+    /// Eventos añadidos a la cadena en esta ejecución
+    pub events_chained: usize,
+    /// Eventos ya encadenados antes del checkpoint y descartados
+    pub events_skipped: usize,
+    /// Huecos de secuencia registrados en la cadena en esta ejecución
+    pub gaps_recorded: usize,
+    /// Última secuencia confirmada en el checkpoint
+    pub last_sequence: Option<u64>,
 }
 
 /// Configuración del DigestWorker
@@ -45,6 +123,68 @@ pub struct DigestWorkerConfig {
     pub interval_hours: u64,
     /// Timeout para procesamiento (en segundos)
     pub timeout_secs: u64,
+    /// Publicación periódica de checkpoints firmados (desactivada si es None)
+    pub checkpoint_publication: Option<CheckpointPublicationConfig>,
+}
+
+impl Default for DigestWorkerConfig {
+    fn default() -> Self {
+        Self {
+            logs_dir: PathBuf::from("./logs"),
+            interval_hours: 1,
+            timeout_secs: 300,
+            checkpoint_publication: None,
+        }
+    }
+}
+
+/// Configuración de la publicación de checkpoints firmados
+#[derive(Clone)]
+pub struct CheckpointPublicationConfig {
+    /// Intervalo entre publicaciones
+    pub interval: Duration,
+    /// Destino de los checkpoints (log, S3, notario externo...)
+    pub sink: Arc<dyn CheckpointSink>,
+    /// Clave privada con la que se firman los checkpoints
+    pub signing_key: Vec<u8>,
+}
+
+impl fmt::Debug for CheckpointPublicationConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // La clave privada nunca se imprime
+        f.debug_struct("CheckpointPublicationConfig")
+            .field("interval", &self.interval)
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Eventos fuera de orden que se retienen como máximo por defecto
+pub const DEFAULT_REORDER_WINDOW: usize = 1024;
+
+/// Tiempo máximo de espera por defecto para rellenar un hueco
+pub const DEFAULT_REORDER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Primera secuencia de la cola por defecto, esperada sin checkpoint previo
+pub const DEFAULT_FIRST_SEQUENCE: u64 = 1;
+
+/// Cadena donde se registran los huecos de secuencia
+pub const SEQUENCE_GAP_CHAIN: &str = "_sequence_gaps";
+
+/// Estado de una ejecución de `run`
+#[derive(Default)]
+struct RunState {
+    checkpoint: WorkerCheckpoint,
+    /// Eventos adelantados a la espera de `expected`
+    pending: BTreeMap<u64, SequencedEvent>,
+    /// Siguiente secuencia a encadenar
+    expected: u64,
+    /// Límite para rellenar el hueco en `expected`, si lo hay
+    gap_deadline: Option<Instant>,
+    events_chained: usize,
+    events_skipped: usize,
+    gaps_recorded: usize,
+    last_digest_id: Option<String>,
 }
 
 /// Worker para generación de digests
@@ -58,6 +198,11 @@ where
     signing_service: SS,
     chain_service: DS,
     config: DigestWorkerConfig,
+    checkpoint_store: Arc<dyn CheckpointStore>,
+    reorder_window: usize,
+    reorder_timeout: Duration,
+    first_sequence: u64,
+    chain_index: Option<Arc<dyn ChainIndex>>,
 }
 
 impl<HS, SS, DS> DigestWorker<HS, SS, DS>
@@ -78,7 +223,375 @@ where
             signing_service,
             chain_service,
             config,
+            checkpoint_store: Arc::new(InMemoryCheckpointStore::new()),
+            reorder_window: DEFAULT_REORDER_WINDOW,
+            reorder_timeout: DEFAULT_REORDER_TIMEOUT,
+            first_sequence: DEFAULT_FIRST_SEQUENCE,
+            chain_index: None,
+        }
+    }
+
+    /// Usar un almacén de checkpoints (p. ej. duradero en fichero)
+    pub fn with_checkpoint_store(mut self, store: Arc<dyn CheckpointStore>) -> Self {
+        self.checkpoint_store = store;
+        self
+    }
+
+    /// Registrar en `index` la posición en la cadena de cada evento
+    /// encadenado, para poder resolver consultas "as-of"
+    pub fn with_chain_index(mut self, index: Arc<dyn ChainIndex>) -> Self {
+        self.chain_index = Some(index);
+        self
+    }
+
+    /// Configurar la ventana de reordenación
+    ///
+    /// Se retienen hasta `window` eventos adelantados mientras falta uno
+    /// anterior. Si la ventana se desborda o la secuencia que falta no llega
+    /// en `timeout` desde que se detectó su hueco, el hueco se registra en
+    /// la cadena y se continúa con la siguiente.
+    pub fn with_reorder_window(mut self, window: usize, timeout: Duration) -> Self {
+        self.reorder_window = window;
+        self.reorder_timeout = timeout;
+        self
+    }
+
+    /// Primera secuencia de la cola, esperada cuando no hay checkpoint
+    pub fn with_first_sequence(mut self, sequence: u64) -> Self {
+        self.first_sequence = sequence;
+        self
+    }
+
+    /// Consumir eventos de la cola hasta que se cierre
+    ///
+    /// La secuencia esperada parte del checkpoint (o de la primera secuencia
+    /// configurada si no lo hay), nunca del primer evento recibido. Los
+    /// eventos ya cubiertos por el checkpoint se descartan; el resto se
+    /// encadena estrictamente en orden de secuencia, reordenando dentro de
+    /// la ventana configurada, y el checkpoint se persiste tras cada evento.
+    /// Los eventos de un hueco registrado se encadenan cuando llegan.
+    pub async fn run<S>(&self, events: S) -> Result<DigestWorkerResult, DigestWorkerError>
+    where
+        S: Stream<Item = SequencedEvent>,
+    {
+        let start_time = Instant::now();
+        let start_timestamp = unix_now();
+
+        let checkpoint = self.checkpoint_store.load().await?.unwrap_or_default();
+        let mut state = RunState {
+            expected: checkpoint
+                .last_sequence
+                .map_or(self.first_sequence, |last| last + 1),
+            checkpoint,
+            ..Default::default()
+        };
+
+        let mut events = std::pin::pin!(events);
+        loop {
+            let next = match state.gap_deadline {
+                None => events.next().await,
+                Some(deadline) => match tokio::time::timeout_at(deadline, events.next()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        self.skip_gap(&mut state).await?;
+                        continue;
+                    }
+                },
+            };
+            let Some(sequenced) = next else {
+                break;
+            };
+
+            if state.checkpoint.open_gaps.contains(&sequenced.sequence) {
+                self.chain_late_event(&mut state, sequenced).await?;
+                continue;
+            }
+            if sequenced.sequence < state.expected
+                || state.pending.contains_key(&sequenced.sequence)
+            {
+                state.events_skipped += 1;
+                continue;
+            }
+
+            state.pending.insert(sequenced.sequence, sequenced);
+            self.drain_pending(&mut state).await?;
+            while state.pending.len() > self.reorder_window {
+                self.skip_gap(&mut state).await?;
+            }
+        }
+
+        if !state.pending.is_empty() {
+            return Err(sequence_gap(state.expected, &state.pending));
+        }
+
+        Ok(DigestWorkerResult {
+            digest_id: state
+                .last_digest_id
+                .unwrap_or_else(|| "no-events".to_string()),
+            files_processed: 0,
+            start_time: start_timestamp,
+            end_time: unix_now(),
+            duration_ms: start_time.elapsed().as_millis() as u64,
+            events_chained: state.events_chained,
+            events_skipped: state.events_skipped,
+            gaps_recorded: state.gaps_recorded,
+            last_sequence: state.checkpoint.last_sequence,
+        })
+    }
+
+    /// Encadenar los eventos retenidos que continúan la secuencia esperada
+    ///
+    /// Si queda un hueco, su plazo empieza a contar cuando se detecta, y no
+    /// se reinicia con cada evento que llega detrás.
+    async fn drain_pending(&self, state: &mut RunState) -> Result<(), DigestWorkerError> {
+        let mut advanced = false;
+        while let Some(sequenced) = state.pending.remove(&state.expected) {
+            let digest = self.chain_event(&mut state.checkpoint, &sequenced).await?;
+            self.index_event(&sequenced, &digest).await?;
+            state.checkpoint.last_sequence = Some(sequenced.sequence);
+            self.checkpoint_store.save(&state.checkpoint).await?;
+
+            state.events_chained += 1;
+            state.last_digest_id = Some(digest.id);
+            state.expected += 1;
+            advanced = true;
+        }
+        if state.pending.is_empty() {
+            state.gap_deadline = None;
+        } else if advanced || state.gap_deadline.is_none() {
+            state.gap_deadline = Some(Instant::now() + self.reorder_timeout);
+        }
+        Ok(())
+    }
+
+    /// Registrar en la cadena el hueco de la secuencia esperada y continuar
+    /// con los eventos retenidos detrás de él
+    async fn skip_gap(&self, state: &mut RunState) -> Result<(), DigestWorkerError> {
+        let sequence = state.expected;
+        tracing::warn!(
+            "Secuencia {} no recibida a tiempo; se registra el hueco en la cadena",
+            sequence
+        );
+        let marker = SequencedEvent {
+            sequence,
+            event: AuditEvent {
+                event_id: Some(hodei_audit_proto::EventId {
+                    value: format!("sequence-gap-{}", sequence),
+                }),
+                tenant_id: Some(hodei_audit_proto::TenantId {
+                    value: SEQUENCE_GAP_CHAIN.to_string(),
+                }),
+                action: "SequenceGap".to_string(),
+                ..Default::default()
+            },
+        };
+        let digest = self.chain_event(&mut state.checkpoint, &marker).await?;
+        state.checkpoint.open_gaps.insert(sequence);
+        state.checkpoint.last_sequence = Some(sequence);
+        self.checkpoint_store.save(&state.checkpoint).await?;
+
+        state.gaps_recorded += 1;
+        state.last_digest_id = Some(digest.id);
+        state.expected += 1;
+        state.gap_deadline = None;
+        self.drain_pending(state).await
+    }
+
+    /// Encadenar un evento que llega después de registrarse su hueco
+    async fn chain_late_event(
+        &self,
+        state: &mut RunState,
+        sequenced: SequencedEvent,
+    ) -> Result<(), DigestWorkerError> {
+        let digest = self.chain_event(&mut state.checkpoint, &sequenced).await?;
+        self.index_event(&sequenced, &digest).await?;
+        state.checkpoint.open_gaps.remove(&sequenced.sequence);
+        self.checkpoint_store.save(&state.checkpoint).await?;
+
+        state.events_chained += 1;
+        state.last_digest_id = Some(digest.id);
+        Ok(())
+    }
+
+    /// Publicar periódicamente checkpoints firmados hasta que `shutdown` termine
+    ///
+    /// Los fallos de publicación se registran y se reintentan en el siguiente
+    /// intervalo. Devuelve el número de checkpoints publicados.
+    pub async fn run_checkpoint_publisher<F>(&self, shutdown: F) -> usize
+    where
+        F: Future<Output = ()>,
+    {
+        let Some(publication) = self.config.checkpoint_publication.as_ref() else {
+            return 0;
+        };
+
+        let mut ticker = tokio::time::interval(publication.interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut shutdown = std::pin::pin!(shutdown);
+        let mut published = 0;
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = ticker.tick() => match self.publish_checkpoints().await {
+                    Ok(checkpoints) => published += checkpoints.len(),
+                    Err(e) => tracing::warn!("No se pudo publicar el checkpoint firmado: {}", e),
+                },
+            }
+        }
+
+        published
+    }
+
+    /// Firmar y publicar un checkpoint de la cabeza de cada cadena
+    ///
+    /// Solo cubre lo ya confirmado en el almacén de checkpoints. Un tenant
+    /// cuya cadena avanzó respecto al checkpoint se omite hasta la siguiente
+    /// publicación.
+    pub async fn publish_checkpoints(&self) -> Result<Vec<SignedCheckpoint>, DigestWorkerError> {
+        let Some(publication) = self.config.checkpoint_publication.as_ref() else {
+            return Ok(Vec::new());
+        };
+        let Some(checkpoint) = self.checkpoint_store.load().await? else {
+            return Ok(Vec::new());
+        };
+        let Some(sequence) = checkpoint.last_sequence else {
+            return Ok(Vec::new());
+        };
+
+        let public_key = self
+            .signing_service
+            .get_public_key(&publication.signing_key)
+            .map_err(|e| DigestWorkerError::Signing(e.to_string()))?;
+        let timestamp = unix_now();
+
+        let mut published = Vec::with_capacity(checkpoint.chain_heads.len());
+        for (tenant_id, head) in &checkpoint.chain_heads {
+            let Some(latest) = self
+                .chain_service
+                .get_latest_digest(tenant_id)
+                .await?
+                .filter(|d| d.id == *head)
+            else {
+                continue;
+            };
+
+            let mut signed = SignedCheckpoint {
+                tenant_id: tenant_id.clone(),
+                digest_id: latest.id,
+                chain_hash: latest.hash,
+                sequence,
+                timestamp,
+                signature: Vec::new(),
+                public_key: public_key.clone(),
+            };
+            signed.signature = self
+                .signing_service
+                .sign(&signed.signing_payload(), &publication.signing_key)
+                .map_err(|e| DigestWorkerError::Signing(e.to_string()))?;
+
+            publication.sink.publish(&signed).await?;
+            published.push(signed);
+        }
+
+        Ok(published)
+    }
+
+    /// Registrar la posición en la cadena de un evento recién encadenado
+    ///
+    /// Se hace antes de guardar el checkpoint, así que tras una caída el
+    /// evento se vuelve a registrar con la misma posición.
+    async fn index_event(
+        &self,
+        sequenced: &SequencedEvent,
+        digest: &DigestInfo,
+    ) -> Result<(), DigestWorkerError> {
+        let (Some(index), Some(event_id)) = (&self.chain_index, &sequenced.event.event_id) else {
+            return Ok(());
+        };
+        index
+            .record(
+                &event_id.value,
+                ChainPosition {
+                    sequence: sequenced.sequence,
+                    chained_at: digest.timestamp,
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Añadir un evento a la cadena de su tenant
+    async fn chain_event(
+        &self,
+        checkpoint: &mut WorkerCheckpoint,
+        sequenced: &SequencedEvent,
+    ) -> Result<DigestInfo, DigestWorkerError> {
+        let event = &sequenced.event;
+        let tenant_id = event
+            .tenant_id
+            .as_ref()
+            .map(|t| t.value.clone())
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| {
+                DigestWorkerError::Chain(format!(
+                    "evento con secuencia {} sin tenant_id",
+                    sequenced.sequence
+                ))
+            })?;
+
+        let hash = self
+            .hashing_service
+            .hash_data(&canonical_bytes(event))
+            .map_err(|e| DigestWorkerError::Hashing(e.to_string()))?;
+
+        let expected_head = checkpoint.chain_heads.get(&tenant_id).cloned();
+        let latest = self.chain_service.get_latest_digest(&tenant_id).await?;
+
+        // Recuperación tras caída: el digest se añadió pero el checkpoint no
+        // llegó a persistirse, así que no se vuelve a encadenar
+        if let Some(recovered) = latest
+            .as_ref()
+            .filter(|d| is_same_append(d, &hash, expected_head.as_deref()))
+        {
+            checkpoint
+                .chain_heads
+                .insert(tenant_id, recovered.id.clone());
+            return Ok(recovered.clone());
+        }
+
+        let latest_id = latest.map(|d| d.id);
+        if expected_head.is_some() && latest_id != expected_head {
+            return Err(DigestWorkerError::ChainContinuity(format!(
+                "tenant {}: se esperaba {:?} como último digest, la cadena tiene {:?}",
+                tenant_id, expected_head, latest_id
+            )));
         }
+
+        let label = event
+            .event_id
+            .as_ref()
+            .map(|id| id.value.clone())
+            .unwrap_or_else(|| format!("seq-{}", sequenced.sequence));
+        let timestamp = event
+            .event_time
+            .as_ref()
+            .map(|t| t.seconds.max(0) as u64)
+            .unwrap_or_default();
+
+        let digest = self
+            .chain_service
+            .generate_digest(
+                &tenant_id,
+                timestamp,
+                timestamp,
+                &[(label.as_str(), hash)],
+                latest_id.as_deref(),
+            )
+            .await?;
+
+        checkpoint.chain_heads.insert(tenant_id, digest.id.clone());
+        Ok(digest)
     }
 
     /// Ejecutar una vez el worker
@@ -105,6 +618,10 @@ where
                 start_time: start_timestamp,
                 end_time: end_timestamp,
                 duration_ms: start_time.elapsed().as_millis() as u64,
+                events_chained: 0,
+                events_skipped: 0,
+                gaps_recorded: 0,
+                last_sequence: None,
             });
         }
 
@@ -155,6 +672,10 @@ where
             start_time: start_timestamp,
             end_time: end_timestamp,
             duration_ms: start_time.elapsed().as_millis() as u64,
+            events_chained: 0,
+            events_skipped: 0,
+            gaps_recorded: 0,
+            last_sequence: None,
         })
     }
 
@@ -199,10 +720,35 @@ where
     }
 }
 
+/// Indica si `digest` es el resultado de encadenar ya el evento con `hash`
+/// sobre la cabeza esperada
+fn is_same_append(digest: &DigestInfo, hash: &str, expected_head: Option<&str>) -> bool {
+    digest.hash == hash
+        && (expected_head.is_none() || digest.previous_digest_id.as_deref() == expected_head)
+}
+
+/// Error para el hueco entre la secuencia esperada y el primer evento retenido
+fn sequence_gap(expected: u64, pending: &BTreeMap<u64, SequencedEvent>) -> DigestWorkerError {
+    let got = pending.keys().next().copied().unwrap_or_default();
+    DigestWorkerError::SequenceGap { expected, got }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::crypto::{Ed25519Signer, InMemoryDigestChain, Sha256Hasher};
+    use crate::workers::checkpoint::{FileCheckpointStore, InMemoryCheckpointSink};
+    use hodei_audit_proto::{EventId, TenantId};
+    use std::collections::BTreeSet;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tempfile::tempdir;
 
     #[tokio::test]
     async fn test_digest_worker_run_once_empty() {
@@ -214,6 +760,7 @@ mod tests {
             logs_dir: PathBuf::from("/tmp/logs"),
             interval_hours: 1,
             timeout_secs: 300,
+            ..Default::default()
         };
 
         let worker = DigestWorker::new(hashing, signing, chain, config);
@@ -222,4 +769,328 @@ mod tests {
         assert_eq!(result.files_processed, 0);
         assert_eq!(result.digest_id, "no-files");
     }
+
+    fn queue_worker(
+        chain: InMemoryDigestChain,
+        store: Arc<dyn CheckpointStore>,
+    ) -> DigestWorker<Sha256Hasher, Ed25519Signer, InMemoryDigestChain> {
+        let config = DigestWorkerConfig {
+            logs_dir: PathBuf::from("/tmp/logs"),
+            interval_hours: 1,
+            timeout_secs: 300,
+            ..Default::default()
+        };
+        DigestWorker::new(Sha256Hasher::new(), Ed25519Signer::new(), chain, config)
+            .with_checkpoint_store(store)
+    }
+
+    fn sequenced(sequence: u64) -> SequencedEvent {
+        SequencedEvent {
+            sequence,
+            event: AuditEvent {
+                event_id: Some(EventId {
+                    value: format!("evt-{}", sequence),
+                }),
+                tenant_id: Some(TenantId {
+                    value: "tenant1".to_string(),
+                }),
+                action: "write".to_string(),
+                event_time: Some(prost_types::Timestamp {
+                    seconds: 1_700_000_000 + sequence as i64,
+                    nanos: 0,
+                }),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn events(sequences: std::ops::RangeInclusive<u64>) -> impl Stream<Item = SequencedEvent> {
+        futures::stream::iter(sequences.map(sequenced))
+    }
+
+    /// Almacén cuyo siguiente guardado falla, simulando una caída
+    #[derive(Default)]
+    struct FailingStore {
+        inner: InMemoryCheckpointStore,
+        fail_next_save: AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl CheckpointStore for FailingStore {
+        async fn load(&self) -> Result<Option<WorkerCheckpoint>, CheckpointError> {
+            self.inner.load().await
+        }
+
+        async fn save(&self, checkpoint: &WorkerCheckpoint) -> Result<(), CheckpointError> {
+            if self.fail_next_save.swap(false, Ordering::SeqCst) {
+                return Err(std::io::Error::other("disk unavailable").into());
+            }
+            self.inner.save(checkpoint).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_resumes_from_checkpoint_after_restart() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("digest_worker.checkpoint");
+        let chain = InMemoryDigestChain::new();
+
+        let worker = queue_worker(chain.clone(), Arc::new(FileCheckpointStore::new(&path)));
+        let first = worker.run(events(1..=3)).await.unwrap();
+        assert_eq!(first.events_chained, 3);
+        assert_eq!(first.last_sequence, Some(3));
+        drop(worker);
+
+        // Reinicio: la cola vuelve a entregar desde la secuencia 2
+        let restarted = queue_worker(chain.clone(), Arc::new(FileCheckpointStore::new(&path)));
+        let second = restarted.run(events(2..=5)).await.unwrap();
+        assert_eq!(second.events_skipped, 2);
+        assert_eq!(second.events_chained, 2);
+        assert_eq!(second.last_sequence, Some(5));
+
+        let digests = chain.list_digests("tenant1", None, None).await.unwrap();
+        assert_eq!(digests.len(), 5);
+        assert!(chain.verify_chain("tenant1").await.unwrap());
+        assert_eq!(second.digest_id, digests[4].id);
+    }
+
+    #[tokio::test]
+    async fn test_run_does_not_rechain_when_checkpoint_was_lost() {
+        let chain = InMemoryDigestChain::new();
+        let store = Arc::new(FailingStore::default());
+        let worker = queue_worker(chain.clone(), store.clone());
+
+        worker.run(events(1..=2)).await.unwrap();
+
+        // El evento 3 se encadena pero su checkpoint no llega a guardarse
+        store.fail_next_save.store(true, Ordering::SeqCst);
+        let err = worker.run(events(3..=3)).await.unwrap_err();
+        assert!(matches!(err, DigestWorkerError::Store(_)));
+        assert!(err.is_transient());
+
+        let result = worker.run(events(3..=4)).await.unwrap();
+        assert_eq!(result.last_sequence, Some(4));
+
+        let digests = chain.list_digests("tenant1", None, None).await.unwrap();
+        assert_eq!(digests.len(), 4);
+        assert!(chain.verify_chain("tenant1").await.unwrap());
+    }
+
+    fn events_in(sequences: &[u64]) -> impl Stream<Item = SequencedEvent> + use<> {
+        futures::stream::iter(sequences.iter().copied().map(sequenced).collect::<Vec<_>>())
+    }
+
+    #[tokio::test]
+    async fn test_run_reorders_events_within_window() {
+        let chain = InMemoryDigestChain::new();
+        let worker = queue_worker(chain.clone(), Arc::new(InMemoryCheckpointStore::new()))
+            .with_reorder_window(4, Duration::from_secs(1));
+
+        let result = worker.run(events_in(&[1, 3, 2, 5, 4])).await.unwrap();
+        assert_eq!(result.events_chained, 5);
+        assert_eq!(result.last_sequence, Some(5));
+
+        // La posición en la cadena coincide con la secuencia de cada evento
+        let digests = chain.list_digests("tenant1", None, None).await.unwrap();
+        for (position, digest) in digests.iter().enumerate() {
+            let sequence = position as u64 + 1;
+            assert_eq!(
+                digest.id,
+                format!("digest_tenant1_{}_{}", 1_700_000_000 + sequence, position)
+            );
+        }
+        assert!(chain.verify_chain("tenant1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_fresh_start_expects_first_sequence_not_first_arrival() {
+        let chain = InMemoryDigestChain::new();
+        let worker = queue_worker(chain.clone(), Arc::new(InMemoryCheckpointStore::new()))
+            .with_reorder_window(4, Duration::from_secs(1));
+
+        // La secuencia 2 llega antes que la 1 y no debe tomarse como inicio
+        let result = worker.run(events_in(&[2, 1, 3])).await.unwrap();
+        assert_eq!(result.events_chained, 3);
+        assert_eq!(result.events_skipped, 0);
+        assert_eq!(result.gaps_recorded, 0);
+
+        let digests = chain.list_digests("tenant1", None, None).await.unwrap();
+        assert_eq!(digests.len(), 3);
+        assert_eq!(digests[0].id, "digest_tenant1_1700000001_0");
+    }
+
+    #[tokio::test]
+    async fn test_run_records_unfillable_sequence_gap_and_chains_late_event() {
+        let chain = InMemoryDigestChain::new();
+        let store = Arc::new(InMemoryCheckpointStore::new());
+        let worker = queue_worker(chain.clone(), store.clone())
+            .with_reorder_window(2, Duration::from_secs(1));
+
+        // La ventana se desborda sin que llegue la secuencia 3
+        let result = worker.run(events_in(&[1, 2, 4, 5, 6])).await.unwrap();
+        assert_eq!(result.events_chained, 5);
+        assert_eq!(result.gaps_recorded, 1);
+        assert_eq!(result.last_sequence, Some(6));
+
+        // El hueco queda en la cadena y pendiente en el checkpoint
+        let checkpoint = store.load().await.unwrap().unwrap();
+        assert_eq!(checkpoint.open_gaps, BTreeSet::from([3]));
+        let gaps = chain
+            .list_digests(SEQUENCE_GAP_CHAIN, None, None)
+            .await
+            .unwrap();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(
+            chain
+                .list_digests("tenant1", None, None)
+                .await
+                .unwrap()
+                .len(),
+            5
+        );
+
+        // La secuencia 3 llega tarde: se encadena en vez de descartarse
+        let late = worker.run(events_in(&[3])).await.unwrap();
+        assert_eq!(late.events_chained, 1);
+        assert_eq!(late.events_skipped, 0);
+        assert!(store.load().await.unwrap().unwrap().open_gaps.is_empty());
+        assert_eq!(
+            chain
+                .list_digests("tenant1", None, None)
+                .await
+                .unwrap()
+                .len(),
+            6
+        );
+        assert!(chain.verify_chain("tenant1").await.unwrap());
+        assert!(chain.verify_chain(SEQUENCE_GAP_CHAIN).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_reorder_timeout_applies_per_gap() {
+        let chain = InMemoryDigestChain::new();
+        let worker = queue_worker(chain, Arc::new(InMemoryCheckpointStore::new()))
+            .with_reorder_window(64, Duration::from_millis(100));
+
+        // La secuencia 2 nunca llega, pero detrás del hueco siguen llegando
+        // eventos más deprisa que el plazo
+        let (tx, rx) = tokio::sync::mpsc::channel(64);
+        tx.send(sequenced(1)).await.unwrap();
+        let producer = tokio::spawn(async move {
+            for sequence in 3..=12 {
+                tx.send(sequenced(sequence)).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(30)).await;
+            }
+        });
+
+        let result = worker
+            .run(tokio_stream::wrappers::ReceiverStream::new(rx))
+            .await
+            .unwrap();
+        producer.await.unwrap();
+        assert_eq!(result.gaps_recorded, 1);
+        assert_eq!(result.events_chained, 11);
+        assert_eq!(result.last_sequence, Some(12));
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_gap_left_open_when_queue_closes() {
+        let chain = InMemoryDigestChain::new();
+        let store = Arc::new(InMemoryCheckpointStore::new());
+        let worker =
+            queue_worker(chain, store.clone()).with_reorder_window(16, Duration::from_secs(60));
+
+        let err = worker.run(events_in(&[1, 3])).await.unwrap_err();
+        assert!(matches!(
+            err,
+            DigestWorkerError::SequenceGap {
+                expected: 2,
+                got: 3
+            }
+        ));
+        // La secuencia 3 no se confirma: se recibirá de nuevo al reanudar
+        let checkpoint = store.load().await.unwrap().unwrap();
+        assert_eq!(checkpoint.last_sequence, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_publishes_signed_checkpoints_on_interval() {
+        let signer = Ed25519Signer::new();
+        let keypair = signer.generate_keypair().unwrap();
+        let sink = Arc::new(InMemoryCheckpointSink::new());
+
+        let chain = InMemoryDigestChain::new();
+        let config = DigestWorkerConfig {
+            checkpoint_publication: Some(CheckpointPublicationConfig {
+                interval: Duration::from_millis(40),
+                sink: sink.clone(),
+                signing_key: keypair.private_key.clone(),
+            }),
+            ..Default::default()
+        };
+        let worker = DigestWorker::new(
+            Sha256Hasher::new(),
+            Ed25519Signer::new(),
+            chain.clone(),
+            config,
+        );
+        worker.run(events(1..=3)).await.unwrap();
+
+        let published = worker
+            .run_checkpoint_publisher(tokio::time::sleep(Duration::from_millis(100)))
+            .await;
+        assert!(published >= 2);
+
+        let head = chain.get_latest_digest("tenant1").await.unwrap().unwrap();
+        let checkpoints = sink.published();
+        assert_eq!(checkpoints.len(), published);
+        for checkpoint in &checkpoints {
+            assert_eq!(checkpoint.tenant_id, "tenant1");
+            assert_eq!(checkpoint.digest_id, head.id);
+            assert_eq!(checkpoint.chain_hash, head.hash);
+            assert_eq!(checkpoint.sequence, 3);
+            assert_eq!(checkpoint.public_key, keypair.public_key);
+            assert!(
+                signer
+                    .verify(
+                        &checkpoint.signing_payload(),
+                        &checkpoint.signature,
+                        &keypair.public_key
+                    )
+                    .unwrap()
+            );
+        }
+
+        // Una firma no vale para otra cabeza de cadena
+        let mut forged = checkpoints[0].clone();
+        forged.chain_hash = "forged".to_string();
+        assert!(
+            !signer
+                .verify(
+                    &forged.signing_payload(),
+                    &forged.signature,
+                    &keypair.public_key
+                )
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_detects_broken_chain_continuity() {
+        let chain = InMemoryDigestChain::new();
+        let worker = queue_worker(chain.clone(), Arc::new(InMemoryCheckpointStore::new()));
+        worker.run(events(1..=2)).await.unwrap();
+
+        // Alguien añade un digest fuera del worker
+        let foreign = vec![("external.parquet", "foreign".to_string())];
+        let head = chain.get_latest_digest("tenant1").await.unwrap().unwrap();
+        chain
+            .generate_digest("tenant1", 0, 1, &foreign, Some(&head.id))
+            .await
+            .unwrap();
+
+        let err = worker.run(events(3..=3)).await.unwrap_err();
+        assert!(matches!(err, DigestWorkerError::ChainContinuity(_)));
+        assert!(!err.is_transient());
+    }
 }
@@ -2,4 +2,6 @@
 //!
 //! Workers background para tareas de mantenimiento y procesamiento.
 
+pub mod chain_index;
+pub mod checkpoint;
 pub mod digest_worker;
@@ -5,23 +5,40 @@
 //! - Buffer pool for reusing allocations
 //! - Slice-based operations
 //! - Pin and borrowing optimization
+//! - Events encoded in place as an `EventBatchRequest` payload, so a flushed
+//!   batch can go to the wire without re-encoding
 
+use hodei_audit_proto::AuditEvent;
 use std::collections::VecDeque;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tracing::warn;
 
 /// Zero-copy buffer pool configuration
 #[derive(Debug, Clone)]
 pub struct BufferPoolConfig {
     /// Initial buffer size
     pub initial_size: usize,
-    /// Maximum buffer size
+    /// Maximum buffer size; hard cap for a single item spilled to the heap
     pub max_size: usize,
     /// Maximum number of buffers in pool
     pub max_buffers: usize,
-    /// Buffer growth factor
+    /// Buffer growth factor (also applied to pool capacity when adapting)
     pub growth_factor: f64,
+    /// Grow pool capacity under sustained misses and shrink it when idle
+    pub adaptive_sizing: bool,
+    /// Upper bound for pool capacity under adaptive sizing
+    pub adaptive_max_buffers: usize,
+    /// Miss rate over a window of requests that triggers growth
+    pub adaptive_miss_rate: f64,
+    /// Number of buffer requests evaluated per adaptive window
+    pub adaptive_window: u64,
+    /// Time without misses after which capacity shrinks toward `max_buffers`
+    pub idle_shrink_after: Duration,
+    /// Number of sub-pools; buffer counts above are split evenly across them
+    pub shards: usize,
 }
 
 impl Default for BufferPoolConfig {
@@ -31,6 +48,12 @@ impl Default for BufferPoolConfig {
             max_size: 1024 * 1024 * 16, // 16MB
             max_buffers: 100,
             growth_factor: 1.5,
+            adaptive_sizing: false,
+            adaptive_max_buffers: 1000,
+            adaptive_miss_rate: 0.5,
+            adaptive_window: 100,
+            idle_shrink_after: Duration::from_secs(60),
+            shards: 1,
         }
     }
 }
@@ -84,6 +107,21 @@ impl ZeroCopyBuffer {
         &self.metadata
     }
 
+    /// Encode an event into the buffer
+    ///
+    /// The event is written as field 1 (`events`) of an `EventBatchRequest`,
+    /// so the buffer contents are always a valid encoded batch request.
+    pub fn write_event(&mut self, event: &AuditEvent) -> Result<(), BufferError> {
+        let encoded_len = prost::encoding::message::encoded_len(1, event);
+        if self.data.len() + encoded_len > self.data.capacity() {
+            return Err(BufferError::InsufficientCapacity);
+        }
+        prost::encoding::message::encode(1, event, &mut self.data);
+        self.metadata.bytes_written += encoded_len as u64;
+        self.metadata.last_used = Instant::now();
+        Ok(())
+    }
+
     /// Write bytes to buffer
     pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), BufferError> {
         if self.data.len() + bytes.len() > self.data.capacity() {
@@ -107,6 +145,8 @@ struct BufferMetadata {
     last_used: Instant,
     /// Total bytes written
     bytes_written: u64,
+    /// Pool shard that owns the buffer
+    shard: usize,
 }
 
 impl BufferMetadata {
@@ -121,75 +161,182 @@ impl BufferMetadata {
             created_at: Instant::now(),
             last_used: Instant::now(),
             bytes_written: 0,
+            shard: 0,
         }
     }
 }
 
-/// Buffer pool for reusing buffers
-pub struct BufferPool {
+/// Buffer pool pressure counters and adaptive sizing state
+#[derive(Debug, Clone)]
+struct PoolPressure {
+    /// Requests served from the pool
+    hits: u64,
+    /// Requests that found the pool empty
+    misses: u64,
+    /// Misses allocated while the live buffers already filled the pool
+    fallback_allocations: u64,
+    /// Buffers dropped on return because the pool was full
+    discarded: u64,
+    /// Buffers created and not discarded yet
+    live_buffers: u64,
+    /// Current pool capacity
+    capacity: usize,
+    /// Requests in the current adaptive window
+    window_requests: u64,
+    /// Misses in the current adaptive window
+    window_misses: u64,
+    /// Last miss (or last shrink step)
+    last_miss: Instant,
+}
+
+/// Single sub-pool of a `BufferPool`
+struct PoolShard {
+    /// Index of the shard inside its pool
+    index: usize,
+    /// Configuration of this shard (capacity already split across shards)
     config: BufferPoolConfig,
-    pool: Arc<RwLock<VecDeque<Arc<RwLock<ZeroCopyBuffer>>>>>,
-    created_buffers: Arc<RwLock<u64>>,
+    pool: RwLock<VecDeque<Arc<RwLock<ZeroCopyBuffer>>>>,
+    created_buffers: RwLock<u64>,
+    pressure: RwLock<PoolPressure>,
 }
 
-impl BufferPool {
-    /// Create a new buffer pool
-    pub fn new(config: BufferPoolConfig) -> Self {
+impl PoolShard {
+    fn new(index: usize, config: BufferPoolConfig) -> Self {
         Self {
-            pool: Arc::new(RwLock::new(VecDeque::new())),
-            created_buffers: Arc::new(RwLock::new(0)),
+            index,
+            pool: RwLock::new(VecDeque::new()),
+            created_buffers: RwLock::new(0),
+            pressure: RwLock::new(PoolPressure {
+                hits: 0,
+                misses: 0,
+                fallback_allocations: 0,
+                discarded: 0,
+                live_buffers: 0,
+                capacity: config.max_buffers,
+                window_requests: 0,
+                window_misses: 0,
+                last_miss: Instant::now(),
+            }),
             config,
         }
     }
 
-    /// Get or create a buffer
-    pub async fn get_buffer(&self) -> Arc<RwLock<ZeroCopyBuffer>> {
+    async fn get_buffer(&self) -> Arc<RwLock<ZeroCopyBuffer>> {
         let mut pool = self.pool.write().await;
+        let mut pressure = self.pressure.write().await;
+        pressure.window_requests += 1;
 
         // Try to get a buffer from the pool
         if let Some(buffer) = pool.pop_front() {
+            pressure.hits += 1;
+            self.adapt_capacity(&mut pressure);
             return buffer;
         }
 
+        pressure.misses += 1;
+        pressure.window_misses += 1;
+        pressure.last_miss = Instant::now();
+        if pressure.live_buffers >= pressure.capacity as u64 {
+            pressure.fallback_allocations += 1;
+        }
+        pressure.live_buffers += 1;
+        self.adapt_capacity(&mut pressure);
+
         // Create a new buffer
-        let buffer = Arc::new(RwLock::new(ZeroCopyBuffer::new(self.config.initial_size)));
+        let mut buffer = ZeroCopyBuffer::new(self.config.initial_size);
+        buffer.metadata.shard = self.index;
         let mut created = self.created_buffers.write().await;
         *created += 1;
 
-        buffer
+        Arc::new(RwLock::new(buffer))
     }
 
-    /// Return a buffer to the pool
-    pub async fn return_buffer(&self, mut buffer: Arc<RwLock<ZeroCopyBuffer>>) {
-        // Reset buffer before returning
+    /// Grow capacity at the end of a window with a high miss rate
+    fn adapt_capacity(&self, pressure: &mut PoolPressure) {
+        if pressure.window_requests < self.config.adaptive_window.max(1) {
+            return;
+        }
+
+        let miss_rate = pressure.window_misses as f64 / pressure.window_requests as f64;
+        if self.config.adaptive_sizing && miss_rate >= self.config.adaptive_miss_rate {
+            let grown = (pressure.capacity as f64 * self.config.growth_factor).ceil() as usize;
+            pressure.capacity = grown.max(pressure.capacity + 1).min(
+                self.config
+                    .adaptive_max_buffers
+                    .max(self.config.max_buffers),
+            );
+        }
+        pressure.window_requests = 0;
+        pressure.window_misses = 0;
+    }
+
+    async fn shrink_if_idle(&self) -> usize {
+        let mut pool = self.pool.write().await;
+        let mut pressure = self.pressure.write().await;
+
+        if !self.config.adaptive_sizing
+            || pressure.capacity <= self.config.max_buffers
+            || pressure.last_miss.elapsed() < self.config.idle_shrink_after
         {
-            let mut buf = buffer.write().await;
-            buf.clear();
+            return pressure.capacity;
+        }
+
+        let shrunk = (pressure.capacity as f64 / self.config.growth_factor).floor() as usize;
+        pressure.capacity = shrunk
+            .min(pressure.capacity - 1)
+            .max(self.config.max_buffers);
+        pressure.last_miss = Instant::now();
+
+        while pool.len() > pressure.capacity {
+            pool.pop_back();
+            pressure.discarded += 1;
+            pressure.live_buffers = pressure.live_buffers.saturating_sub(1);
         }
 
+        pressure.capacity
+    }
+
+    /// Put a cleared buffer back, dropping it if the pool is full
+    async fn push_returned(&self, buffer: Arc<RwLock<ZeroCopyBuffer>>) -> bool {
         let mut pool = self.pool.write().await;
+        let mut pressure = self.pressure.write().await;
 
         // Check if pool has space
-        if pool.len() < self.config.max_buffers {
+        if pool.len() < pressure.capacity {
             pool.push_back(buffer);
+            true
+        } else {
+            // If pool is full, buffer is dropped
+            pressure.discarded += 1;
+            pressure.live_buffers = pressure.live_buffers.saturating_sub(1);
+            false
         }
-        // If pool is full, buffer is dropped
     }
 
-    /// Get pool statistics
-    pub async fn get_stats(&self) -> BufferPoolStats {
+    async fn discard(&self) {
+        let mut pressure = self.pressure.write().await;
+        pressure.discarded += 1;
+        pressure.live_buffers = pressure.live_buffers.saturating_sub(1);
+    }
+
+    async fn get_stats(&self) -> BufferPoolStats {
         let pool = self.pool.read().await;
         let created = *self.created_buffers.read().await;
+        let pressure = self.pressure.read().await;
 
         BufferPoolStats {
             total_created: created,
             in_pool: pool.len(),
-            capacity: self.config.max_buffers,
+            capacity: pressure.capacity,
+            baseline_capacity: self.config.max_buffers,
+            hits: pressure.hits,
+            misses: pressure.misses,
+            fallback_allocations: pressure.fallback_allocations,
+            discarded: pressure.discarded,
         }
     }
 
-    /// Clean up old buffers
-    pub async fn cleanup(&self, max_age: Duration) {
+    async fn cleanup(&self, max_age: Duration) {
         let mut pool = self.pool.write().await;
         let now = Instant::now();
 
@@ -204,8 +351,11 @@ impl BufferPool {
 
             if now.duration_since(meta.last_used) < max_age {
                 to_retain.push_back(buffer);
+            } else {
+                // Buffer is dropped
+                let mut pressure = self.pressure.write().await;
+                pressure.live_buffers = pressure.live_buffers.saturating_sub(1);
             }
-            // else: buffer is dropped
         }
 
         // Keep the buffers that should be retained
@@ -213,12 +363,179 @@ impl BufferPool {
     }
 }
 
+/// Shard used by the current thread
+///
+/// Threads are assigned shards round-robin the first time they touch any
+/// pool, so each worker thread keeps hitting the same sub-pool.
+fn local_shard(shards: usize) -> usize {
+    static NEXT_THREAD: AtomicUsize = AtomicUsize::new(0);
+    thread_local! {
+        static THREAD_SLOT: usize = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+    }
+    THREAD_SLOT.with(|slot| *slot % shards)
+}
+
+/// Buffer pool for reusing buffers
+///
+/// With `BufferPoolConfig::shards > 1` the pool is split into independent
+/// sub-pools, one per worker thread, so acquiring and releasing buffers does
+/// not contend on a single lock. Buffers always return to the shard that
+/// created them and stats are aggregated across shards.
+#[derive(Clone)]
+pub struct BufferPool {
+    config: BufferPoolConfig,
+    shards: Arc<[PoolShard]>,
+}
+
+impl BufferPool {
+    /// Create a new buffer pool
+    pub fn new(config: BufferPoolConfig) -> Self {
+        let shard_count = config.shards.max(1);
+        let shard_config = BufferPoolConfig {
+            max_buffers: config.max_buffers.div_ceil(shard_count),
+            adaptive_max_buffers: config.adaptive_max_buffers.div_ceil(shard_count),
+            ..config.clone()
+        };
+        let shards = (0..shard_count)
+            .map(|index| PoolShard::new(index, shard_config.clone()))
+            .collect();
+
+        Self { config, shards }
+    }
+
+    /// Number of shards
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Shard owning a buffer, falling back to the local shard for foreign buffers
+    fn owning_shard(&self, metadata: &BufferMetadata) -> &PoolShard {
+        self.shards
+            .get(metadata.shard)
+            .unwrap_or_else(|| self.local_shard())
+    }
+
+    fn local_shard(&self) -> &PoolShard {
+        &self.shards[local_shard(self.shards.len())]
+    }
+
+    /// Get or create a buffer
+    pub async fn get_buffer(&self) -> Arc<RwLock<ZeroCopyBuffer>> {
+        self.local_shard().get_buffer().await
+    }
+
+    /// Shrink capacity toward `max_buffers` after `idle_shrink_after` without misses
+    ///
+    /// Each call shrinks at most one step; call it periodically (e.g. next to
+    /// `cleanup`). Returns the resulting capacity.
+    pub async fn shrink_if_idle(&self) -> usize {
+        let mut capacity = 0;
+        for shard in self.shards.iter() {
+            capacity += shard.shrink_if_idle().await;
+        }
+        capacity
+    }
+
+    /// Return a buffer to the pool
+    pub async fn return_buffer(&self, buffer: Arc<RwLock<ZeroCopyBuffer>>) {
+        // Reset buffer before returning
+        let shard = {
+            let mut buf = buffer.write().await;
+            buf.clear();
+            self.owning_shard(&buf.metadata)
+        };
+
+        shard.push_returned(buffer).await;
+    }
+
+    /// Reclaim the buffer of a sent batch
+    ///
+    /// Returns `false` (and drops the buffer) if the batch payload is still
+    /// shared elsewhere or the pool is full.
+    pub async fn reclaim(&self, batch: ZeroCopyBatch) -> bool {
+        // One-off heap buffers from a spill never belong to the pool
+        if batch.data.capacity() > self.config.initial_size {
+            return false;
+        }
+
+        let shard = self.owning_shard(&batch.metadata);
+        let Ok(mut data) = Arc::try_unwrap(batch.data) else {
+            shard.discard().await;
+            return false;
+        };
+        data.clear();
+
+        let mut metadata = batch.metadata;
+        metadata.last_used = Instant::now();
+
+        shard
+            .push_returned(Arc::new(RwLock::new(ZeroCopyBuffer { data, metadata })))
+            .await
+    }
+
+    /// Get pool statistics, aggregated across shards
+    pub async fn get_stats(&self) -> BufferPoolStats {
+        let mut stats = BufferPoolStats::default();
+        for shard in self.shards.iter() {
+            let shard_stats = shard.get_stats().await;
+            stats.total_created += shard_stats.total_created;
+            stats.in_pool += shard_stats.in_pool;
+            stats.capacity += shard_stats.capacity;
+            stats.baseline_capacity += shard_stats.baseline_capacity;
+            stats.hits += shard_stats.hits;
+            stats.misses += shard_stats.misses;
+            stats.fallback_allocations += shard_stats.fallback_allocations;
+            stats.discarded += shard_stats.discarded;
+        }
+        stats
+    }
+
+    /// Get per-shard pool statistics
+    pub async fn get_shard_stats(&self) -> Vec<BufferPoolStats> {
+        let mut stats = Vec::with_capacity(self.shards.len());
+        for shard in self.shards.iter() {
+            stats.push(shard.get_stats().await);
+        }
+        stats
+    }
+
+    /// Clean up old buffers
+    pub async fn cleanup(&self, max_age: Duration) {
+        for shard in self.shards.iter() {
+            shard.cleanup(max_age).await;
+        }
+    }
+}
+
 /// Buffer pool statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct BufferPoolStats {
     pub total_created: u64,
     pub in_pool: usize,
+    /// Current capacity (grows above the baseline under adaptive sizing)
     pub capacity: usize,
+    /// Configured `max_buffers`
+    pub baseline_capacity: usize,
+    /// Requests served from the pool
+    pub hits: u64,
+    /// Requests that found the pool empty
+    pub misses: u64,
+    /// Allocations made beyond pool capacity
+    pub fallback_allocations: u64,
+    /// Buffers dropped instead of being pooled
+    pub discarded: u64,
+}
+
+impl BufferPoolStats {
+    /// Fraction of requests served from the pool
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
 }
 
 /// Zero-copy batcher
@@ -231,7 +548,11 @@ pub struct ZeroCopyBatcher {
 impl ZeroCopyBatcher {
     /// Create a new zero-copy batcher
     pub fn new(config: BatcherConfig) -> Self {
-        let pool_config = BufferPoolConfig::default();
+        Self::with_pool_config(config, BufferPoolConfig::default())
+    }
+
+    /// Create a new zero-copy batcher with a custom buffer pool
+    pub fn with_pool_config(config: BatcherConfig, pool_config: BufferPoolConfig) -> Self {
         Self {
             pool: BufferPool::new(pool_config),
             config,
@@ -239,6 +560,42 @@ impl ZeroCopyBatcher {
         }
     }
 
+    /// Make room for an item larger than a pooled buffer
+    ///
+    /// The batch so far moves into a one-off heap buffer sized to also hold
+    /// the item, and the pooled buffer goes back to the pool. Items above
+    /// `BufferPoolConfig::max_size` are rejected as `Oversized`.
+    async fn spill_to_heap(&mut self, item_len: usize) -> Result<(), BatcherError> {
+        let max = self.pool.config.max_size;
+        if item_len > max {
+            return Err(BufferError::Oversized {
+                size: item_len,
+                max,
+            }
+            .into());
+        }
+
+        let current = self.get_active_buffer().await?;
+        let mut spilled = {
+            let buf = current.read().await;
+            let mut spilled = ZeroCopyBuffer::new(buf.len() + item_len);
+            spilled.data.extend_from_slice(buf.as_slice());
+            spilled.metadata.bytes_written = buf.metadata.bytes_written;
+            spilled
+        };
+        spilled.metadata.last_used = Instant::now();
+
+        self.pool.return_buffer(current).await;
+        self.active_buffer = Some(Arc::new(RwLock::new(spilled)));
+
+        warn!(
+            item_len = item_len,
+            buffer_size = self.pool.config.initial_size,
+            "Item larger than pooled buffer, spilling batch to heap"
+        );
+        Ok(())
+    }
+
     /// Get active buffer
     async fn get_active_buffer(&mut self) -> Result<Arc<RwLock<ZeroCopyBuffer>>, BatcherError> {
         if let Some(ref buffer) = self.active_buffer {
@@ -252,6 +609,11 @@ impl ZeroCopyBatcher {
 
     /// Add data to batch
     pub async fn add_data(&mut self, data: &[u8]) -> Result<(), BatcherError> {
+        // Items that don't fit in any pooled buffer go to the heap
+        if data.len() > self.pool.config.initial_size {
+            self.spill_to_heap(data.len()).await?;
+        }
+
         // Get or create active buffer
         if self.active_buffer.is_none() {
             self.active_buffer = Some(self.pool.get_buffer().await);
@@ -279,19 +641,36 @@ impl ZeroCopyBatcher {
         Ok(())
     }
 
+    /// Add an event to the batch, encoding it directly into the pooled buffer
+    ///
+    /// Fails with `InsufficientCapacity` when the active buffer is full; the
+    /// caller should flush and retry. Events larger than a pooled buffer are
+    /// still batched through a heap fallback, up to `max_size`.
+    pub async fn add_event(&mut self, event: &AuditEvent) -> Result<(), BatcherError> {
+        let encoded_len = prost::encoding::message::encoded_len(1, event);
+        if encoded_len > self.pool.config.initial_size {
+            self.spill_to_heap(encoded_len).await?;
+        }
+
+        let buffer = self.get_active_buffer().await?;
+        let mut buf = buffer.write().await;
+        buf.write_event(event)?;
+        Ok(())
+    }
+
     /// Flush current batch
+    ///
+    /// The batch takes ownership of the pooled allocation without copying it;
+    /// hand the batch back with `BufferPool::reclaim` once it has been sent.
     pub async fn flush(&mut self) -> Result<ZeroCopyBatch, BatcherError> {
         if let Some(buffer) = self.active_buffer.take() {
-            // Read buffer data
-            let buf = buffer.read().await;
-            let data = Arc::new(buf.as_slice().to_vec());
-            let size = buf.len();
+            // Move the buffer data out of the pooled buffer
+            let mut buf = buffer.write().await;
+            let data = Arc::new(std::mem::take(&mut buf.data));
+            let size = data.len();
             let metadata = buf.metadata().clone();
             drop(buf);
 
-            // Return buffer to pool
-            self.pool.return_buffer(buffer).await;
-
             // Create batch
             let batch = ZeroCopyBatch {
                 data,
@@ -309,6 +688,11 @@ impl ZeroCopyBatcher {
     pub async fn get_pool_stats(&self) -> BufferPoolStats {
         self.pool.get_stats().await
     }
+
+    /// Get the buffer pool backing this batcher
+    pub fn pool(&self) -> &BufferPool {
+        &self.pool
+    }
 }
 
 /// Zero-copy batch result
@@ -324,6 +708,9 @@ pub struct ZeroCopyBatch {
 
 impl ZeroCopyBatch {
     /// Get batch data slice
+    ///
+    /// For batches built with `add_event` this is the encoded
+    /// `EventBatchRequest`, ready to be sent as is.
     pub fn as_slice(&self) -> &[u8] {
         &self.data
     }
@@ -345,6 +732,8 @@ pub enum BufferError {
     InsufficientCapacity,
     #[error("Buffer pool exhausted")]
     PoolExhausted,
+    #[error("Item of {size} bytes exceeds the {max} bytes hard cap")]
+    Oversized { size: usize, max: usize },
 }
 
 /// Batcher error types
@@ -401,6 +790,139 @@ mod tests {
         assert!(batch.size > 0);
     }
 
+    #[tokio::test]
+    async fn test_add_event_encodes_batch_request() {
+        use hodei_audit_proto::{EventBatchRequest, EventId};
+        use prost::Message;
+
+        let mut batcher = ZeroCopyBatcher::new(BatcherConfig {
+            max_batch_size: 1024,
+            flush_timeout: Duration::from_millis(100),
+        });
+        let events: Vec<AuditEvent> = (0..3)
+            .map(|i| AuditEvent {
+                event_id: Some(EventId {
+                    value: format!("event-{}", i),
+                }),
+                ..Default::default()
+            })
+            .collect();
+        for event in &events {
+            batcher.add_event(event).await.unwrap();
+        }
+
+        let batch = batcher.flush().await.unwrap();
+        let decoded = EventBatchRequest::decode(batch.as_slice()).unwrap();
+        assert_eq!(decoded.events, events);
+
+        assert!(batcher.pool().reclaim(batch).await);
+        assert_eq!(batcher.get_pool_stats().await.in_pool, 1);
+    }
+
+    fn small_pool_batcher(initial_size: usize, max_size: usize) -> ZeroCopyBatcher {
+        ZeroCopyBatcher::with_pool_config(
+            BatcherConfig {
+                max_batch_size: 1024,
+                flush_timeout: Duration::from_millis(100),
+            },
+            BufferPoolConfig {
+                initial_size,
+                max_size,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_oversized_event_spills_to_heap() {
+        use hodei_audit_proto::EventBatchRequest;
+        use prost::Message;
+
+        let mut batcher = small_pool_batcher(256, 64 * 1024);
+        let small = AuditEvent {
+            action: "CreatePolicyStore".to_string(),
+            ..Default::default()
+        };
+        let large = AuditEvent {
+            action: "x".repeat(4096),
+            ..Default::default()
+        };
+
+        batcher.add_event(&small).await.unwrap();
+        batcher.add_event(&large).await.unwrap();
+
+        let batch = batcher.flush().await.unwrap();
+        let decoded = EventBatchRequest::decode(batch.as_slice()).unwrap();
+        assert_eq!(decoded.events, vec![small, large]);
+
+        // The pooled buffer went back to the pool; the heap buffer does not
+        assert_eq!(batcher.get_pool_stats().await.in_pool, 1);
+        assert!(!batcher.pool().reclaim(batch).await);
+        assert_eq!(batcher.get_pool_stats().await.in_pool, 1);
+    }
+
+    #[tokio::test]
+    async fn test_data_over_hard_cap_is_oversized() {
+        let mut batcher = small_pool_batcher(256, 1024);
+
+        let err = batcher.add_data(&[0u8; 2048]).await.unwrap_err();
+        assert!(matches!(
+            err,
+            BatcherError::BufferError(BufferError::Oversized {
+                size: 2048,
+                max: 1024
+            })
+        ));
+
+        // Up to the hard cap the data is still batched
+        batcher.add_data(&[1u8; 1000]).await.unwrap();
+        assert_eq!(batcher.flush().await.unwrap().size, 1000);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_sharded_pool_keeps_buffers_in_owning_shard() {
+        let pool = BufferPool::new(BufferPoolConfig {
+            initial_size: 1024,
+            max_buffers: 64,
+            shards: 4,
+            ..Default::default()
+        });
+        assert_eq!(pool.shard_count(), 4);
+
+        let tasks: Vec<_> = (0..32)
+            .map(|_| {
+                let pool = pool.clone();
+                tokio::spawn(async move {
+                    for _ in 0..200 {
+                        let buffer = pool.get_buffer().await;
+                        buffer.write().await.write_bytes(b"event").unwrap();
+                        // Let the task migrate to another worker thread
+                        tokio::task::yield_now().await;
+                        pool.return_buffer(buffer).await;
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.hits + stats.misses, 32 * 200);
+        assert_eq!(stats.baseline_capacity, 64);
+        // Every buffer created is either back in the pool or was discarded
+        assert_eq!(stats.in_pool as u64 + stats.discarded, stats.total_created);
+
+        for (index, shard) in pool.shards.iter().enumerate() {
+            let buffers = shard.pool.read().await;
+            assert!(buffers.len() <= 16);
+            for buffer in buffers.iter() {
+                assert_eq!(buffer.read().await.metadata.shard, index);
+            }
+        }
+        assert_eq!(pool.get_shard_stats().await.len(), 4);
+    }
+
     #[tokio::test]
     async fn test_buffer_pool_stats() {
         let config = BufferPoolConfig {
@@ -415,4 +937,92 @@ mod tests {
         assert_eq!(stats.in_pool, 0);
         assert_eq!(stats.total_created, 1);
     }
+
+    #[tokio::test]
+    async fn test_draining_pool_counts_misses() {
+        let pool = BufferPool::new(BufferPoolConfig {
+            max_buffers: 2,
+            ..Default::default()
+        });
+
+        let first = pool.get_buffer().await;
+        pool.return_buffer(first).await;
+
+        // One buffer in the pool: a hit, then the pool is drained
+        let _a = pool.get_buffer().await;
+        let _b = pool.get_buffer().await;
+        let _c = pool.get_buffer().await;
+
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 3);
+        // Three live buffers with capacity for two
+        assert_eq!(stats.fallback_allocations, 1);
+        assert_eq!(stats.hit_rate(), 0.25);
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_growth_under_sustained_misses() {
+        let pool = BufferPool::new(BufferPoolConfig {
+            max_buffers: 4,
+            adaptive_sizing: true,
+            adaptive_max_buffers: 10,
+            adaptive_window: 4,
+            growth_factor: 2.0,
+            ..Default::default()
+        });
+
+        // Every request misses: buffers are held, never returned
+        let mut held = Vec::new();
+        for _ in 0..8 {
+            held.push(pool.get_buffer().await);
+        }
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.capacity, 10);
+        assert_eq!(stats.baseline_capacity, 4);
+
+        // The grown pool keeps every returned buffer
+        for buffer in held {
+            pool.return_buffer(buffer).await;
+        }
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.in_pool, 8);
+        assert_eq!(stats.discarded, 0);
+    }
+
+    #[tokio::test]
+    async fn test_idle_shrink_returns_toward_baseline() {
+        let pool = BufferPool::new(BufferPoolConfig {
+            max_buffers: 2,
+            adaptive_sizing: true,
+            adaptive_max_buffers: 8,
+            adaptive_window: 2,
+            growth_factor: 2.0,
+            idle_shrink_after: Duration::from_millis(20),
+            ..Default::default()
+        });
+
+        let mut held = Vec::new();
+        for _ in 0..6 {
+            held.push(pool.get_buffer().await);
+        }
+        for buffer in held {
+            pool.return_buffer(buffer).await;
+        }
+        assert_eq!(pool.get_stats().await.capacity, 8);
+
+        // Not idle long enough yet
+        assert_eq!(pool.shrink_if_idle().await, 8);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(pool.shrink_if_idle().await, 4);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(pool.shrink_if_idle().await, 2);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(pool.shrink_if_idle().await, 2);
+
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.in_pool, 2);
+        assert_eq!(stats.discarded, 4);
+    }
 }
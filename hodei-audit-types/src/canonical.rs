@@ -0,0 +1,248 @@
+//! Canonical event serialization
+//!
+//! The digest chain hashes audit events, so their byte representation must
+//! never depend on protobuf field order, map iteration order or the
+//! presence of fields added in later schema versions. `canonical_bytes`
+//! produces a versioned encoding: a version prefix followed by JSON with
+//! object keys sorted at every level and absent optional fields written
+//! explicitly as `null`.
+
+use hodei_audit_proto::metadata::value_to_json;
+use hodei_audit_proto::{AuditEvent, EventId, Hrn, HttpContext, TenantId, UserIdentity};
+use serde_json::{Value, json};
+
+/// Version of the canonical encoding
+pub const CANONICAL_VERSION: u32 = 1;
+
+/// Prefix identifying the encoding version inside the hashed bytes
+const CANONICAL_PREFIX: &[u8] = b"hodei-audit-event/v1\n";
+
+/// Deterministic, versioned encoding of an event for hashing
+///
+/// Two events with the same logical content always produce the same bytes.
+/// Fields added to `AuditEvent` after v1 are not part of this encoding;
+/// covering them requires a new version.
+pub fn canonical_bytes(event: &AuditEvent) -> Vec<u8> {
+    let mut out = CANONICAL_PREFIX.to_vec();
+    write_canonical(&canonical_value(event), &mut out);
+    out
+}
+
+fn canonical_value(event: &AuditEvent) -> Value {
+    // Exhaustive on purpose: a new proto field fails to compile here until
+    // someone decides whether it needs a new canonical version
+    let AuditEvent {
+        event_id,
+        tenant_id,
+        hrn,
+        user_identity,
+        http_context,
+        action,
+        event_category,
+        management_type,
+        access_type,
+        read_only,
+        outcome,
+        error_code,
+        error_message,
+        event_time,
+        processed_at,
+        latency_ms,
+        metadata,
+        correlation_id,
+        trace_id,
+        span_id,
+        event_source,
+        event_version,
+        management_event,
+        enriched,
+    } = event;
+
+    let timestamp = |ts: &Option<prost_types::Timestamp>| {
+        ts.as_ref().map_or(
+            Value::Null,
+            |t| json!({ "seconds": t.seconds, "nanos": t.nanos }),
+        )
+    };
+
+    json!({
+        "event_id": event_id.as_ref().map(|EventId { value }| value),
+        "tenant_id": tenant_id.as_ref().map(|TenantId { value }| value),
+        "hrn": hrn.as_ref().map(hrn_value),
+        "user_identity": user_identity.as_ref().map(user_identity_value),
+        "http_context": http_context.as_ref().map(http_context_value),
+        "action": action,
+        "event_category": event_category,
+        "management_type": management_type,
+        "access_type": access_type,
+        "read_only": read_only,
+        "outcome": outcome,
+        "error_code": error_code,
+        "error_message": error_message,
+        "event_time": timestamp(event_time),
+        "processed_at": timestamp(processed_at),
+        "latency_ms": latency_ms,
+        "metadata": metadata.as_ref().map(|m| {
+            Value::Object(
+                m.fields
+                    .iter()
+                    .map(|(k, v)| (k.clone(), value_to_json(v)))
+                    .collect(),
+            )
+        }),
+        "correlation_id": correlation_id,
+        "trace_id": trace_id,
+        "span_id": span_id,
+        "event_source": event_source,
+        "event_version": event_version,
+        "management_event": management_event,
+        "enriched": enriched,
+    })
+}
+
+fn hrn_value(hrn: &Hrn) -> Value {
+    let Hrn {
+        partition,
+        service,
+        tenant_id,
+        region,
+        resource_type,
+        resource_path,
+    } = hrn;
+    json!({
+        "partition": partition,
+        "service": service,
+        "tenant_id": tenant_id,
+        "region": region,
+        "resource_type": resource_type,
+        "resource_path": resource_path,
+    })
+}
+
+fn user_identity_value(identity: &UserIdentity) -> Value {
+    let UserIdentity {
+        user_id,
+        username,
+        email,
+        roles,
+        tenant_id,
+    } = identity;
+    json!({
+        "user_id": user_id,
+        "username": username,
+        "email": email,
+        "roles": roles,
+        "tenant_id": tenant_id,
+    })
+}
+
+fn http_context_value(context: &HttpContext) -> Value {
+    let HttpContext {
+        method,
+        path,
+        user_agent,
+        source_ip,
+        status_code,
+        content_length,
+    } = context;
+    json!({
+        "method": method,
+        "path": path,
+        "user_agent": user_agent,
+        "source_ip": source_ip,
+        "status_code": status_code,
+        "content_length": content_length,
+    })
+}
+
+/// Compact JSON with object keys sorted, independent of `serde_json`'s map
+/// ordering features
+fn write_canonical(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(b']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push(b'{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                // Serializing a string or scalar to a Vec cannot fail
+                serde_json::to_writer(&mut *out, key).expect("string serialization");
+                out.push(b':');
+                write_canonical(item, out);
+            }
+            out.push(b'}');
+        }
+        scalar => serde_json::to_writer(&mut *out, scalar).expect("scalar serialization"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hodei_audit_proto::MetadataExt;
+    use sha2::{Digest, Sha256};
+
+    fn sha256(bytes: &[u8]) -> Vec<u8> {
+        Sha256::digest(bytes).to_vec()
+    }
+
+    #[test]
+    fn test_field_order_does_not_change_canonical_bytes() {
+        let mut first = AuditEvent {
+            event_id: Some(EventId {
+                value: "evt-1".to_string(),
+            }),
+            action: "policy.update".to_string(),
+            ..Default::default()
+        };
+        first.tenant_id = Some(TenantId {
+            value: "tenant-a".to_string(),
+        });
+        first.set_json("ip", json!("10.0.0.1"));
+        first.set_json("context", json!({ "b": 2, "a": [1, 2] }));
+
+        let mut second = AuditEvent {
+            tenant_id: Some(TenantId {
+                value: "tenant-a".to_string(),
+            }),
+            ..Default::default()
+        };
+        second.set_json("context", json!({ "a": [1, 2], "b": 2 }));
+        second.set_json("ip", json!("10.0.0.1"));
+        second.action = "policy.update".to_string();
+        second.event_id = Some(EventId {
+            value: "evt-1".to_string(),
+        });
+
+        let bytes = canonical_bytes(&first);
+        assert_eq!(bytes, canonical_bytes(&second));
+        assert_eq!(sha256(&bytes), sha256(&canonical_bytes(&second)));
+        assert!(bytes.starts_with(CANONICAL_PREFIX));
+
+        second.action = "policy.delete".to_string();
+        assert_ne!(sha256(&bytes), sha256(&canonical_bytes(&second)));
+    }
+
+    #[test]
+    fn test_absent_fields_are_explicit_nulls() {
+        let bytes = canonical_bytes(&AuditEvent::default());
+        let body = std::str::from_utf8(&bytes[CANONICAL_PREFIX.len()..]).unwrap();
+
+        assert!(body.starts_with("{\"access_type\":0,\"action\":\"\""));
+        assert!(body.contains("\"event_time\":null"));
+        assert!(body.contains("\"hrn\":null"));
+        assert!(body.contains("\"metadata\":null"));
+    }
+}
@@ -0,0 +1,269 @@
+//! Event classification enums
+//!
+//! The proto carries `event_category` and `outcome` as raw `i32` fields.
+//! These enums convert them to and from their numeric, textual and serde
+//! forms. Values outside the known set map to `Unknown` instead of failing,
+//! so data written by newer producers can still be read.
+
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Event category (CloudTrail-inspired)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum EventCategory {
+    Unspecified,
+    Management,
+    Data,
+    Insight,
+    /// Numeric or textual value not known to this version
+    Unknown,
+}
+
+impl EventCategory {
+    /// Every known variant
+    pub const ALL: [EventCategory; 4] = [
+        Self::Unspecified,
+        Self::Management,
+        Self::Data,
+        Self::Insight,
+    ];
+
+    /// Lowercase name used in queries and reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "unspecified",
+            Self::Management => "management",
+            Self::Data => "data",
+            Self::Insight => "insight",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl From<i32> for EventCategory {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => Self::Unspecified,
+            1 => Self::Management,
+            2 => Self::Data,
+            3 => Self::Insight,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl From<EventCategory> for i32 {
+    /// `Unknown` has no wire value and is written as unspecified (0)
+    fn from(category: EventCategory) -> Self {
+        match category {
+            EventCategory::Unspecified | EventCategory::Unknown => 0,
+            EventCategory::Management => 1,
+            EventCategory::Data => 2,
+            EventCategory::Insight => 3,
+        }
+    }
+}
+
+impl From<hodei_audit_proto::EventCategory> for EventCategory {
+    fn from(category: hodei_audit_proto::EventCategory) -> Self {
+        Self::from(category as i32)
+    }
+}
+
+impl FromStr for EventCategory {
+    type Err = Infallible;
+
+    /// Accepts the lowercase name or the proto name (`CATEGORY_DATA`),
+    /// case-insensitively
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        let name = name.strip_prefix("category_").unwrap_or(&name);
+        Ok(Self::ALL
+            .into_iter()
+            .find(|c| c.as_str() == name)
+            .unwrap_or(Self::Unknown))
+    }
+}
+
+impl fmt::Display for EventCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for EventCategory {
+    fn from(value: String) -> Self {
+        let Ok(category) = value.parse::<EventCategory>();
+        category
+    }
+}
+
+impl From<EventCategory> for String {
+    fn from(category: EventCategory) -> Self {
+        category.as_str().to_string()
+    }
+}
+
+/// Event outcome
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Outcome {
+    Unspecified,
+    Success,
+    Failure,
+    Error,
+    Denied,
+    /// Numeric or textual value not known to this version
+    Unknown,
+}
+
+impl Outcome {
+    /// Every known variant
+    pub const ALL: [Outcome; 5] = [
+        Self::Unspecified,
+        Self::Success,
+        Self::Failure,
+        Self::Error,
+        Self::Denied,
+    ];
+
+    /// Lowercase name used in queries and reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "unspecified",
+            Self::Success => "success",
+            Self::Failure => "failure",
+            Self::Error => "error",
+            Self::Denied => "denied",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl From<i32> for Outcome {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => Self::Unspecified,
+            1 => Self::Success,
+            2 => Self::Failure,
+            3 => Self::Error,
+            4 => Self::Denied,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl From<Outcome> for i32 {
+    /// `Unknown` has no wire value and is written as unspecified (0)
+    fn from(outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Unspecified | Outcome::Unknown => 0,
+            Outcome::Success => 1,
+            Outcome::Failure => 2,
+            Outcome::Error => 3,
+            Outcome::Denied => 4,
+        }
+    }
+}
+
+impl From<hodei_audit_proto::Outcome> for Outcome {
+    fn from(outcome: hodei_audit_proto::Outcome) -> Self {
+        Self::from(outcome as i32)
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = Infallible;
+
+    /// Accepts the lowercase name or the proto name (`OUTCOME_SUCCESS`),
+    /// case-insensitively
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        let name = name.strip_prefix("outcome_").unwrap_or(&name);
+        Ok(Self::ALL
+            .into_iter()
+            .find(|o| o.as_str() == name)
+            .unwrap_or(Self::Unknown))
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for Outcome {
+    fn from(value: String) -> Self {
+        let Ok(outcome) = value.parse::<Outcome>();
+        outcome
+    }
+}
+
+impl From<Outcome> for String {
+    fn from(outcome: Outcome) -> Self {
+        outcome.as_str().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_category_round_trips() {
+        for category in EventCategory::ALL {
+            assert_eq!(EventCategory::from(i32::from(category)), category);
+            assert_eq!(category.to_string().parse(), Ok(category));
+            let json = serde_json::to_string(&category).unwrap();
+            assert_eq!(
+                serde_json::from_str::<EventCategory>(&json).unwrap(),
+                category
+            );
+        }
+
+        assert_eq!(
+            EventCategory::from(hodei_audit_proto::EventCategory::CategoryData),
+            EventCategory::Data
+        );
+        assert_eq!("CATEGORY_INSIGHT".parse(), Ok(EventCategory::Insight));
+    }
+
+    #[test]
+    fn test_outcome_round_trips() {
+        for outcome in Outcome::ALL {
+            assert_eq!(Outcome::from(i32::from(outcome)), outcome);
+            assert_eq!(outcome.to_string().parse(), Ok(outcome));
+            let json = serde_json::to_string(&outcome).unwrap();
+            assert_eq!(serde_json::from_str::<Outcome>(&json).unwrap(), outcome);
+        }
+
+        assert_eq!(
+            serde_json::to_string(&Outcome::Success).unwrap(),
+            "\"success\""
+        );
+        assert_eq!(
+            Outcome::from(hodei_audit_proto::Outcome::Denied),
+            Outcome::Denied
+        );
+        assert_eq!("Failure".parse(), Ok(Outcome::Failure));
+    }
+
+    #[test]
+    fn test_unknown_values_map_to_unknown() {
+        assert_eq!(Outcome::from(99), Outcome::Unknown);
+        assert_eq!(Outcome::from(-1), Outcome::Unknown);
+        assert_eq!("timeout".parse(), Ok(Outcome::Unknown));
+        assert_eq!(
+            serde_json::from_str::<Outcome>("\"partial\"").unwrap(),
+            Outcome::Unknown
+        );
+        assert_eq!(i32::from(Outcome::Unknown), 0);
+
+        assert_eq!(EventCategory::from(42), EventCategory::Unknown);
+        assert_eq!("billing".parse(), Ok(EventCategory::Unknown));
+    }
+}
@@ -2,6 +2,12 @@
 //!
 //! This crate contains common types used across the hodei-audit ecosystem
 
+pub mod canonical;
+pub mod classification;
 pub mod hrn;
+pub mod migration;
 
+pub use canonical::{CANONICAL_VERSION, canonical_bytes};
+pub use classification::{EventCategory, Outcome};
 pub use hrn::{Hrn, HrnError, HrnMetadata, HrnResolver};
+pub use migration::{CURRENT_EVENT_VERSION, MigrationError, migrate_event};
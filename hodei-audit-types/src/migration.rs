@@ -0,0 +1,198 @@
+//! Event schema migration
+//!
+//! Events keep the `event_version` of the schema they were written with.
+//! `migrate_event` up-converts an event from an older version by applying,
+//! in order, the transform of every version in between, so events of every
+//! schema generation can be processed in the current shape.
+//!
+//! Versions are the major number of `event_version` (`"1"`, `"1.0"` and
+//! `"v1"` are all version 1); events without one predate versioning and are
+//! treated as version 1.
+
+use crate::classification::EventCategory;
+use hodei_audit_proto::{AuditEvent, TenantId};
+use thiserror::Error;
+
+/// Schema version of events written by this release
+pub const CURRENT_EVENT_VERSION: u32 = 2;
+
+/// Migration errors
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MigrationError {
+    #[error("Unrecognized event_version: {0:?}")]
+    InvalidVersion(String),
+
+    #[error("Unknown event schema version {0}")]
+    UnknownVersion(u32),
+
+    #[error("Cannot downgrade an event from version {from} to {to}")]
+    Downgrade { from: u32, to: u32 },
+}
+
+/// Transform taking an event from version `from` to `from + 1`
+struct Migration {
+    from: u32,
+    apply: fn(&mut AuditEvent),
+}
+
+/// Every migration, oldest first, one per version step
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 1,
+    apply: v1_to_v2,
+}];
+
+/// Version 2 made `tenant_id`, `correlation_id` and `management_event`
+/// mandatory
+///
+/// v1 events carried the tenant only in the HRN or the user identity, had no
+/// correlation (each event is its own root) and derived `management_event`
+/// from the category.
+fn v1_to_v2(event: &mut AuditEvent) {
+    if event.tenant_id.as_ref().is_none_or(|t| t.value.is_empty()) {
+        let tenant = event
+            .hrn
+            .as_ref()
+            .map(|hrn| hrn.tenant_id.clone())
+            .filter(|t| !t.is_empty())
+            .or_else(|| {
+                event
+                    .user_identity
+                    .as_ref()
+                    .map(|user| user.tenant_id.clone())
+                    .filter(|t| !t.is_empty())
+            });
+        if let Some(value) = tenant {
+            event.tenant_id = Some(TenantId { value });
+        }
+    }
+    if event.correlation_id.is_empty()
+        && let Some(event_id) = &event.event_id
+    {
+        event.correlation_id = event_id.value.clone();
+    }
+    event.management_event = EventCategory::from(event.event_category) == EventCategory::Management;
+}
+
+/// `event_version` written for schema version `version`
+pub fn version_string(version: u32) -> String {
+    format!("{}.0", version)
+}
+
+/// Schema version of an event
+pub fn event_schema_version(event: &AuditEvent) -> Result<u32, MigrationError> {
+    let version = event.event_version.trim();
+    if version.is_empty() {
+        return Ok(1);
+    }
+    version
+        .strip_prefix('v')
+        .unwrap_or(version)
+        .split('.')
+        .next()
+        .and_then(|major| major.parse().ok())
+        .filter(|major| *major > 0)
+        .ok_or_else(|| MigrationError::InvalidVersion(event.event_version.clone()))
+}
+
+/// Up-convert an event to schema version `to_version`
+///
+/// Events already at `to_version` are returned unchanged.
+pub fn migrate_event(mut event: AuditEvent, to_version: u32) -> Result<AuditEvent, MigrationError> {
+    if to_version > CURRENT_EVENT_VERSION {
+        return Err(MigrationError::UnknownVersion(to_version));
+    }
+    let from = event_schema_version(&event)?;
+    if from > CURRENT_EVENT_VERSION {
+        return Err(MigrationError::UnknownVersion(from));
+    }
+    if from > to_version {
+        return Err(MigrationError::Downgrade {
+            from,
+            to: to_version,
+        });
+    }
+    if from == to_version {
+        return Ok(event);
+    }
+
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|m| m.from >= from && m.from < to_version)
+    {
+        (migration.apply)(&mut event);
+    }
+    event.event_version = version_string(to_version);
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hodei_audit_proto::{EventId, Hrn};
+
+    fn v1_event() -> AuditEvent {
+        AuditEvent {
+            event_id: Some(EventId {
+                value: "evt-1".to_string(),
+            }),
+            hrn: Some(Hrn {
+                tenant_id: "tenant-a".to_string(),
+                ..Default::default()
+            }),
+            event_category: i32::from(EventCategory::Management),
+            event_version: "1.0".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_v1_event_gets_current_defaults() {
+        let migrated = migrate_event(v1_event(), CURRENT_EVENT_VERSION).unwrap();
+
+        assert_eq!(migrated.correlation_id, "evt-1");
+        assert_eq!(migrated.tenant_id.as_ref().unwrap().value, "tenant-a");
+        assert!(migrated.management_event);
+        assert_eq!(migrated.event_version, "2.0");
+        assert_eq!(event_schema_version(&migrated), Ok(CURRENT_EVENT_VERSION));
+
+        // Deterministic, and a no-op once current
+        assert_eq!(
+            migrated,
+            migrate_event(v1_event(), CURRENT_EVENT_VERSION).unwrap()
+        );
+        assert_eq!(
+            migrate_event(migrated.clone(), CURRENT_EVENT_VERSION).unwrap(),
+            migrated
+        );
+    }
+
+    #[test]
+    fn test_migration_keeps_existing_values() {
+        let mut event = v1_event();
+        event.correlation_id = "req-9".to_string();
+        event.event_version = String::new();
+
+        let migrated = migrate_event(event, CURRENT_EVENT_VERSION).unwrap();
+        assert_eq!(migrated.correlation_id, "req-9");
+    }
+
+    #[test]
+    fn test_invalid_migrations_are_rejected() {
+        let mut event = v1_event();
+        event.event_version = "2".to_string();
+        assert_eq!(
+            migrate_event(event.clone(), 1),
+            Err(MigrationError::Downgrade { from: 2, to: 1 })
+        );
+        assert_eq!(
+            migrate_event(event.clone(), 3),
+            Err(MigrationError::UnknownVersion(3))
+        );
+
+        event.event_version = "beta".to_string();
+        assert_eq!(
+            migrate_event(event, CURRENT_EVENT_VERSION),
+            Err(MigrationError::InvalidVersion("beta".to_string()))
+        );
+    }
+}